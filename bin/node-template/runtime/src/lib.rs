@@ -391,6 +391,10 @@ impl_runtime_apis! {
 			Grandpa::grandpa_authorities()
 		}
 
+		fn grandpa_pending_change() -> Option<fg_primitives::ScheduledChange<NumberFor<Block>>> {
+			Grandpa::grandpa_pending_change()
+		}
+
 		fn submit_report_equivocation_extrinsic(
 			_equivocation_proof: fg_primitives::EquivocationProof<
 				<Block as BlockT>::Hash,
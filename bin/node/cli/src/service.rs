@@ -54,7 +54,13 @@ macro_rules! new_full_start {
 				Ok(sc_consensus::LongestChain::new(backend.clone()))
 			})?
 			.with_transaction_pool(|config, client, _fetcher, prometheus_registry| {
-				let pool_api = sc_transaction_pool::FullChainApi::new(client.clone());
+				// signed extrinsics provide `(AccountId, Index)` tags, Ethereum transactions
+				// provide `(H160, U256)` tags
+				let sender_tags = sc_transaction_pool::txpool::base_pool::SenderTags::none()
+					.with(32, 4)
+					.with(20, 32);
+				let pool_api = sc_transaction_pool::FullChainApi::new(client.clone())
+					.with_sender_tags(sender_tags);
 				Ok(sc_transaction_pool::BasicPool::new(
 					config,
 					std::sync::Arc::new(pool_api),
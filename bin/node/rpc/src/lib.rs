@@ -32,8 +32,9 @@
 
 use std::{sync::Arc, fmt};
 
+use codec::{Decode, Encode};
 use node_primitives::{Block, BlockNumber, AccountId, Index, Balance, Hash};
-use node_runtime::UncheckedExtrinsic;
+use node_runtime::{Runtime, UncheckedExtrinsic};
 use sp_api::ProvideRuntimeApi;
 use sp_transaction_pool::TransactionPool;
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
@@ -46,6 +47,7 @@ use sc_consensus_babe_rpc::BabeRpcHandler;
 use sc_finality_grandpa::{SharedVoterState, SharedAuthoritySet};
 use sc_finality_grandpa_rpc::GrandpaRpcHandler;
 use sc_rpc_api::DenyUnsafe;
+use sp_runtime::OpaqueExtrinsic;
 
 /// Light client extra dependencies.
 pub struct LightDeps<C, F, P> {
@@ -93,6 +95,19 @@ pub struct FullDeps<C, P, SC> {
 	pub grandpa: GrandpaDeps,
 }
 
+/// Wraps signed Ethereum transactions into unsigned `pallet_evm::Call::transact` extrinsics.
+pub struct TransactionConverter;
+
+impl pallet_evm_rpc::ConvertTransaction<OpaqueExtrinsic> for TransactionConverter {
+	fn convert_transaction(&self, transaction: pallet_evm::Transaction) -> OpaqueExtrinsic {
+		let extrinsic = UncheckedExtrinsic::new_unsigned(
+			pallet_evm::Call::<Runtime>::transact(transaction).into(),
+		);
+		OpaqueExtrinsic::decode(&mut &extrinsic.encode()[..])
+			.expect("Encoded extrinsic is a valid opaque extrinsic; qed")
+	}
+}
+
 /// Instantiate all Full RPC extensions.
 pub fn create_full<C, P, M, SC>(
 	deps: FullDeps<C, P, SC>,
@@ -101,17 +116,19 @@ pub fn create_full<C, P, M, SC>(
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error=BlockChainError> + 'static,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
-	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber, Hash>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance, UncheckedExtrinsic>,
+	C::Api: pallet_evm_rpc::EvmRuntimeApi<Block, BlockNumber>,
 	C::Api: BabeApi<Block>,
 	<C::Api as sp_api::ApiErrorExt>::Error: fmt::Debug,
-	P: TransactionPool + 'static,
+	P: TransactionPool<Block = Block> + 'static,
 	M: jsonrpc_core::Metadata + Default,
 	SC: SelectChain<Block> +'static,
 {
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 	use pallet_contracts_rpc::{Contracts, ContractsApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+	use pallet_evm_rpc::{Eth, EthApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
@@ -133,17 +150,20 @@ pub fn create_full<C, P, M, SC>(
 	} = grandpa;
 
 	io.extend_with(
-		SystemApi::to_delegate(FullSystem::new(client.clone(), pool))
+		SystemApi::to_delegate(FullSystem::new(client.clone(), pool.clone()))
 	);
 	// Making synchronous calls in light client freezes the browser currently,
 	// more context: https://github.com/paritytech/substrate/pull/3480
 	// These RPCs should use an asynchronous caller instead.
 	io.extend_with(
-		ContractsApi::to_delegate(Contracts::new(client.clone()))
+		ContractsApi::to_delegate(Contracts::new(client.clone(), deny_unsafe))
 	);
 	io.extend_with(
 		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
 	);
+	io.extend_with(
+		EthApi::to_delegate(Eth::new(client.clone(), pool, TransactionConverter, deny_unsafe))
+	);
 	io.extend_with(
 		sc_consensus_babe_rpc::BabeApi::to_delegate(
 			BabeRpcHandler::new(
@@ -18,10 +18,11 @@
 //! Some configurable implementations as associated type for the substrate runtime.
 
 use node_primitives::Balance;
+use sp_core::U256;
 use sp_runtime::traits::{Convert, Saturating};
 use sp_runtime::{FixedPointNumber, Fixed128, Perquintill};
-use frame_support::traits::{OnUnbalanced, Currency, Get};
-use crate::{Balances, System, Authorship, MaximumBlockWeight, NegativeImbalance};
+use frame_support::{traits::{OnUnbalanced, Currency, Get}, weights::WeightToFeePolynomial};
+use crate::{Balances, System, Authorship, MaximumBlockWeight, NegativeImbalance, Runtime};
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -46,6 +47,21 @@ impl Convert<u128, Balance> for CurrencyToVoteHandler {
 	fn convert(x: u128) -> Balance { x * Self::factor() }
 }
 
+/// The minimal gas price of EVM executions.
+///
+/// A unit of gas costs as much as the weight it is charged as, so EVM executions pay the same
+/// fee for their weight as other extrinsics do before fee adjustments.
+pub struct EvmFeeCalculator;
+
+impl pallet_evm::FeeCalculator for EvmFeeCalculator {
+	fn min_gas_price() -> U256 {
+		let fee = <Runtime as pallet_transaction_payment::Trait>::WeightToFee::calc(
+			&pallet_evm::WEIGHT_PER_GAS,
+		);
+		U256::from(fee.max(1))
+	}
+}
+
 /// Update the given multiplier based on the following formula
 ///
 ///   diff = (previous_block_weight - target_weight)/max_weight
@@ -24,17 +24,20 @@
 
 use sp_std::prelude::*;
 use frame_support::{
-	construct_runtime, parameter_types, debug,
+	construct_runtime, parameter_types, debug, RuntimeDebug,
 	weights::{
 		Weight, IdentityFee,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 	},
-	traits::{Currency, Imbalance, KeyOwnerProofSystem, OnUnbalanced, Randomness, LockIdentifier},
+	traits::{
+		Currency, Imbalance, KeyOwnerProofSystem, OnUnbalanced, Randomness, LockIdentifier,
+		InstanceFilter, OnRuntimeUpgrade,
+	},
 };
 use sp_core::{
 	crypto::KeyTypeId,
 	u32_trait::{_1, _2, _3, _4},
-	OpaqueMetadata,
+	OpaqueMetadata, H160, H256, U256,
 };
 pub use node_primitives::{AccountId, Signature};
 use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment};
@@ -57,10 +60,10 @@ use pallet_grandpa::fg_primitives;
 use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
-use pallet_contracts_rpc_runtime_api::ContractExecResult;
+use pallet_contracts_rpc_runtime_api::{ContractExecResult, ContractTraceResult};
 use pallet_session::{historical as pallet_session_historical};
 use sp_inherents::{InherentData, CheckInherentsResult};
-use codec::Encode;
+use codec::{Encode, Decode};
 use static_assertions::const_assert;
 
 #[cfg(any(feature = "std", test))]
@@ -74,7 +77,7 @@ pub use pallet_staking::StakerStatus;
 
 /// Implementations of some helper traits passed into runtime modules as associated types.
 pub mod impls;
-use impls::{CurrencyToVoteHandler, Author, TargetedFeeAdjustment};
+use impls::{CurrencyToVoteHandler, Author, TargetedFeeAdjustment, EvmFeeCalculator};
 
 /// Constant values used within the runtime.
 pub mod constants;
@@ -93,12 +96,19 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 251,
-	impl_version: 1,
+	spec_version: 259,
+	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
+/// The BABE epoch configuration at genesis.
+pub const BABE_GENESIS_EPOCH_CONFIG: sp_consensus_babe::BabeEpochConfiguration =
+	sp_consensus_babe::BabeEpochConfiguration {
+		c: PRIMARY_PROBABILITY,
+		allowed_slots: sp_consensus_babe::AllowedSlots::PrimaryAndSecondaryPlainSlots,
+	};
+
 /// Native version.
 #[cfg(any(feature = "std", test))]
 pub fn native_version() -> NativeVersion {
@@ -168,24 +178,108 @@ impl frame_system::Trait for Runtime {
 	type OnKilledAccount = ();
 }
 
+impl pallet_utility::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type IsCallable = ();
+}
+
 parameter_types! {
 	// One storage item; value is size 4+4+16+32 bytes = 56 bytes.
-	pub const MultisigDepositBase: Balance = 30 * CENTS;
+	pub const DepositBase: Balance = 30 * CENTS;
 	// Additional storage item size of 32 bytes.
-	pub const MultisigDepositFactor: Balance = 5 * CENTS;
+	pub const DepositFactor: Balance = 5 * CENTS;
 	pub const MaxSignatories: u16 = 100;
 }
 
-impl pallet_utility::Trait for Runtime {
+impl pallet_multisig::Trait for Runtime {
 	type Event = Event;
 	type Call = Call;
 	type Currency = Balances;
-	type MultisigDepositBase = MultisigDepositBase;
-	type MultisigDepositFactor = MultisigDepositFactor;
+	type DepositBase = DepositBase;
+	type DepositFactor = DepositFactor;
 	type MaxSignatories = MaxSignatories;
 	type IsCallable = ();
 }
 
+parameter_types! {
+	// One storage item; value is size 8 bytes for the deposit.
+	pub const ProxyDepositBase: Balance = 20 * CENTS;
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = 5 * CENTS;
+	pub const MaxProxies: u16 = 32;
+	// One storage item; value is size 8 bytes for the deposit.
+	pub const AnnouncementDepositBase: Balance = 20 * CENTS;
+	// Additional storage item size of 68 bytes.
+	pub const AnnouncementDepositFactor: Balance = 10 * CENTS;
+	pub const MaxPending: u32 = 32;
+}
+
+/// The type used to represent the kinds of proxying allowed.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug)]
+pub enum ProxyType {
+	Any,
+	NonTransfer,
+	Governance,
+	Staking,
+}
+impl Default for ProxyType { fn default() -> Self { Self::Any } }
+impl InstanceFilter<Call> for ProxyType {
+	fn filter(&self, c: &Call) -> bool {
+		match self {
+			ProxyType::Any => true,
+			// A whitelist: calls which dispatch other calls, such as `Utility::batch` or
+			// `Multisig::as_multi`, would otherwise let the proxy smuggle a transfer through.
+			ProxyType::NonTransfer => matches!(c,
+				Call::System(..) | Call::Babe(..) | Call::Timestamp(..)
+					| Call::Indices(pallet_indices::Call::claim(..))
+					| Call::Indices(pallet_indices::Call::free(..))
+					| Call::Authorship(..) | Call::Staking(..) | Call::ElectionProviderMultiPhase(..)
+					| Call::Session(..) | Call::Democracy(..) | Call::Council(..)
+					| Call::TechnicalCommittee(..) | Call::Elections(..)
+					| Call::TechnicalMembership(..) | Call::FinalityTracker(..) | Call::Grandpa(..)
+					| Call::Treasury(..) | Call::ImOnline(..) | Call::AuthorityDiscovery(..)
+					| Call::Identity(..) | Call::Society(..)
+					| Call::Vesting(pallet_vesting::Call::vest(..))
+					| Call::Vesting(pallet_vesting::Call::vest_other(..))
+					| Call::Proxy(pallet_proxy::Call::add_proxy(..))
+					| Call::Proxy(pallet_proxy::Call::remove_proxy(..))
+					| Call::Proxy(pallet_proxy::Call::remove_proxies(..))
+					| Call::Proxy(pallet_proxy::Call::anonymous(..))
+					| Call::Proxy(pallet_proxy::Call::kill_anonymous(..))
+			),
+			ProxyType::Governance => matches!(c,
+				Call::Democracy(..) | Call::Council(..) | Call::Society(..)
+					| Call::TechnicalCommittee(..) | Call::Elections(..) | Call::Treasury(..)
+			),
+			ProxyType::Staking => matches!(c, Call::Staking(..)),
+		}
+	}
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			(_, ProxyType::Any) => false,
+			(ProxyType::NonTransfer, _) => true,
+			_ => false,
+		}
+	}
+}
+
+impl pallet_proxy::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = MaxProxies;
+	type MaxPending = MaxPending;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+	type IsCallable = ();
+}
+
 parameter_types! {
 	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
 }
@@ -320,11 +414,19 @@ parameter_types! {
 	pub const BondingDuration: pallet_staking::EraIndex = 24 * 28;
 	pub const SlashDeferDuration: pallet_staking::EraIndex = 24 * 7; // 1/4 the bonding duration.
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
-	pub const ElectionLookahead: BlockNumber = EPOCH_DURATION_IN_BLOCKS / 4;
+	// the election is conducted by `ElectionProviderMultiPhase`, which has its own phases.
+	pub const ElectionLookahead: BlockNumber = 0;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
 	pub const MaxIterations: u32 = 5;
 }
 
+/// The balancing applied after each round of the offchain PhragMMS election.
+pub struct OffchainBalancing;
+impl sp_phragmen::BalancingConfig for OffchainBalancing {
+	const ITERATIONS: usize = 10;
+	const TOLERANCE: sp_phragmen::ExtendedBalance = 0;
+}
+
 impl pallet_staking::Trait for Runtime {
 	type Currency = Balances;
 	type UnixTime = Timestamp;
@@ -344,8 +446,38 @@ impl pallet_staking::Trait for Runtime {
 	type ElectionLookahead = ElectionLookahead;
 	type Call = Call;
 	type MaxIterations = MaxIterations;
+	type Solver = sp_phragmen::PhragMMS<OffchainBalancing>;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = StakingUnsignedPriority;
+	type ElectionProvider = ElectionProviderMultiPhase;
+}
+
+parameter_types! {
+	// phase durations. 1/4 of the last session for each.
+	pub const SignedPhase: BlockNumber = EPOCH_DURATION_IN_BLOCKS / 4;
+	pub const UnsignedPhase: BlockNumber = EPOCH_DURATION_IN_BLOCKS / 4;
+
+	// signed config
+	pub const SignedMaxSubmissions: u32 = 10;
+	pub const SignedRewardBase: Balance = 1 * DOLLARS;
+	pub const SignedDepositBase: Balance = 1 * DOLLARS;
+	pub const SignedDepositByte: Balance = 1 * CENTS;
+}
+
+impl pallet_election_provider_multi_phase::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type SignedPhase = SignedPhase;
+	type UnsignedPhase = UnsignedPhase;
+	type SignedMaxSubmissions = SignedMaxSubmissions;
+	type SignedRewardBase = SignedRewardBase;
+	type SignedDepositBase = SignedDepositBase;
+	type SignedDepositByte = SignedDepositByte;
+	type SlashHandler = Treasury;
+	type RewardHandler = (); // rewards are minted from the void
+	type UnsignedPriority = MultiPhaseUnsignedPriority;
+	type DataProvider = Staking;
+	type Solver = sp_phragmen::PhragMMS<OffchainBalancing>;
 }
 
 parameter_types! {
@@ -438,6 +570,7 @@ impl pallet_elections_phragmen::Trait for Runtime {
 	type DesiredMembers = DesiredMembers;
 	type DesiredRunnersUp = DesiredRunnersUp;
 	type TermDuration = TermDuration;
+	type Solver = sp_phragmen::SequentialPhragmen;
 }
 
 parameter_types! {
@@ -500,6 +633,10 @@ parameter_types! {
 	pub const RentByteFee: Balance = 1 * DOLLARS;
 	pub const RentDepositOffset: Balance = 1000 * DOLLARS;
 	pub const SurchargeReward: Balance = 150 * DOLLARS;
+	pub const ContractsStorageMode: pallet_contracts::StorageMode =
+		pallet_contracts::StorageMode::Rent;
+	pub const DepositPerByte: Balance = 1 * DOLLARS;
+	pub const DepositPerItem: Balance = 10 * DOLLARS;
 }
 
 impl pallet_contracts::Trait for Runtime {
@@ -518,6 +655,34 @@ impl pallet_contracts::Trait for Runtime {
 	type SurchargeReward = SurchargeReward;
 	type MaxDepth = pallet_contracts::DefaultMaxDepth;
 	type MaxValueSize = pallet_contracts::DefaultMaxValueSize;
+	type StorageMode = ContractsStorageMode;
+	type DepositCurrency = Balances;
+	type DepositPerByte = DepositPerByte;
+	type DepositPerItem = DepositPerItem;
+	type ChainExtension = ();
+}
+
+parameter_types! {
+	pub const EvmModuleId: ModuleId = ModuleId(*b"py/evmpa");
+	pub const ReceiptsRetention: BlockNumber = 7 * DAYS;
+	/// EVM executions may use half of the weight available to normal extrinsics, leaving the
+	/// rest to other extrinsics and to storing the receipts of the executions.
+	pub EvmBlockGasLimit: u32 = (AvailableBlockRatio::get() * MaximumBlockWeight::get()
+		/ 2 / pallet_evm::WEIGHT_PER_GAS) as u32;
+	/// Not used by any public Ethereum network.
+	pub const EvmChainId: u64 = 0x535542;
+}
+
+impl pallet_evm::Trait for Runtime {
+	type ModuleId = EvmModuleId;
+	type FeeCalculator = EvmFeeCalculator;
+	type ConvertAccountId = pallet_evm::HashTruncateConvertAccountId<BlakeTwo256>;
+	type Currency = Balances;
+	type Event = Event;
+	type Precompiles = pallet_evm::StandardPrecompiles;
+	type ReceiptsRetention = ReceiptsRetention;
+	type BlockGasLimit = EvmBlockGasLimit;
+	type ChainId = EvmChainId;
 }
 
 impl pallet_sudo::Trait for Runtime {
@@ -528,8 +693,9 @@ impl pallet_sudo::Trait for Runtime {
 parameter_types! {
 	pub const SessionDuration: BlockNumber = EPOCH_DURATION_IN_SLOTS as _;
 	pub const ImOnlineUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
-	/// We prioritize im-online heartbeats over phragmen solution submission.
+	/// We prioritize im-online heartbeats over staking and multi-phase election solution submission.
 	pub const StakingUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+	pub const MultiPhaseUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2 - 1;
 }
 
 
@@ -727,7 +893,7 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic
 	{
 		System: frame_system::{Module, Call, Config, Storage, Event<T>},
-		Utility: pallet_utility::{Module, Call, Storage, Event<T>},
+		Utility: pallet_utility::{Module, Call, Event},
 		Babe: pallet_babe::{Module, Call, Storage, Config, Inherent(Timestamp)},
 		Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
 		Authorship: pallet_authorship::{Module, Call, Storage, Inherent},
@@ -735,6 +901,7 @@ construct_runtime!(
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
 		Staking: pallet_staking::{Module, Call, Config<T>, Storage, Event<T>, ValidateUnsigned},
+		ElectionProviderMultiPhase: pallet_election_provider_multi_phase::{Module, Call, Storage, Event<T>, ValidateUnsigned},
 		Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
 		Democracy: pallet_democracy::{Module, Call, Storage, Config, Event<T>},
 		Council: pallet_collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
@@ -756,6 +923,9 @@ construct_runtime!(
 		Recovery: pallet_recovery::{Module, Call, Storage, Event<T>},
 		Vesting: pallet_vesting::{Module, Call, Storage, Event<T>, Config<T>},
 		Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
+		Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
+		Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
+		EVM: pallet_evm::{Module, Call, Storage, Event<T>, ValidateUnsigned},
 	}
 );
 
@@ -791,7 +961,23 @@ pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive = frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllModules>;
+pub type Executive = frame_executive::Executive<
+	Runtime,
+	Block,
+	frame_system::ChainContext<Runtime>,
+	Runtime,
+	AllModules,
+	BabeEpochConfigMigration,
+>;
+
+/// Stores the BABE epoch configuration of chains started before it was stored.
+pub struct BabeEpochConfigMigration;
+
+impl OnRuntimeUpgrade for BabeEpochConfigMigration {
+	fn on_runtime_upgrade() -> Weight {
+		Babe::migrate_epoch_config(BABE_GENESIS_EPOCH_CONFIG)
+	}
+}
 
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
@@ -856,6 +1042,10 @@ impl_runtime_apis! {
 			Grandpa::grandpa_authorities()
 		}
 
+		fn grandpa_pending_change() -> Option<fg_primitives::ScheduledChange<NumberFor<Block>>> {
+			Grandpa::grandpa_pending_change()
+		}
+
 		fn submit_report_equivocation_extrinsic(
 			equivocation_proof: fg_primitives::EquivocationProof<
 				<Block as BlockT>::Hash,
@@ -893,16 +1083,24 @@ impl_runtime_apis! {
 			sp_consensus_babe::BabeGenesisConfiguration {
 				slot_duration: Babe::slot_duration(),
 				epoch_length: EpochDuration::get(),
-				c: PRIMARY_PROBABILITY,
+				c: BABE_GENESIS_EPOCH_CONFIG.c,
 				genesis_authorities: Babe::authorities(),
 				randomness: Babe::randomness(),
-				allowed_slots: sp_consensus_babe::AllowedSlots::PrimaryAndSecondaryPlainSlots,
+				allowed_slots: BABE_GENESIS_EPOCH_CONFIG.allowed_slots,
 			}
 		}
 
 		fn current_epoch_start() -> sp_consensus_babe::SlotNumber {
 			Babe::current_epoch_start()
 		}
+
+		fn current_epoch() -> sp_consensus_babe::Epoch {
+			Babe::current_epoch()
+		}
+
+		fn next_epoch() -> sp_consensus_babe::Epoch {
+			Babe::next_epoch()
+		}
 	}
 
 	impl sp_authority_discovery::AuthorityDiscoveryApi<Block> for Runtime {
@@ -917,7 +1115,7 @@ impl_runtime_apis! {
 		}
 	}
 
-	impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber>
+	impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash>
 		for Runtime
 	{
 		fn call(
@@ -938,6 +1136,25 @@ impl_runtime_apis! {
 			}
 		}
 
+		fn trace_call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractTraceResult<AccountId, Balance, Hash> {
+			let (exec_result, gas_consumed, trace) =
+				Contracts::bare_call_traced(origin, dest.into(), value, gas_limit, input_data);
+			let exec_result = match exec_result {
+				Ok(v) => ContractExecResult::Success {
+					status: v.status,
+					data: v.data,
+				},
+				Err(_) => ContractExecResult::Error,
+			};
+			ContractTraceResult { exec_result, gas_consumed, trace }
+		}
+
 		fn get_storage(
 			address: AccountId,
 			key: [u8; 32],
@@ -952,6 +1169,74 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_evm_rpc_runtime_api::EvmApi<Block, BlockNumber> for Runtime {
+		fn chain_id() -> u64 {
+			EVM::chain_id()
+		}
+
+		fn gas_price() -> U256 {
+			use pallet_evm::FeeCalculator;
+			<Runtime as pallet_evm::Trait>::FeeCalculator::min_gas_price()
+		}
+
+		fn account_basic(address: H160) -> pallet_evm::Account {
+			EVM::accounts(address)
+		}
+
+		fn account_code_at(address: H160) -> Vec<u8> {
+			EVM::account_codes(address)
+		}
+
+		fn storage_at(address: H160, index: H256) -> H256 {
+			EVM::account_storages(address, index)
+		}
+
+		fn call(
+			from: H160,
+			to: H160,
+			input_data: Vec<u8>,
+			value: U256,
+			gas_limit: u32,
+			gas_price: Option<U256>,
+		) -> Result<pallet_evm::CallInfo, sp_runtime::DispatchError> {
+			use pallet_evm::FeeCalculator;
+			let gas_price = gas_price
+				.unwrap_or_else(<Runtime as pallet_evm::Trait>::FeeCalculator::min_gas_price);
+			EVM::execute_call(from, to, input_data, value, gas_limit, gas_price, None)
+				.map_err(Into::into)
+		}
+
+		fn create(
+			from: H160,
+			input_data: Vec<u8>,
+			value: U256,
+			gas_limit: u32,
+			gas_price: Option<U256>,
+		) -> Result<pallet_evm::CreateInfo, sp_runtime::DispatchError> {
+			use pallet_evm::FeeCalculator;
+			let gas_price = gas_price
+				.unwrap_or_else(<Runtime as pallet_evm::Trait>::FeeCalculator::min_gas_price);
+			EVM::execute_create(from, input_data, value, gas_limit, gas_price, None)
+				.map_err(Into::into)
+		}
+
+		fn transaction_status(hash: H256) -> Option<pallet_evm::TransactionStatus<BlockNumber>> {
+			EVM::transaction_status(hash)
+		}
+
+		fn block_receipts(number: BlockNumber) -> Vec<pallet_evm::Receipt> {
+			EVM::block_receipts(number)
+		}
+
+		fn block_logs_bloom(number: BlockNumber) -> pallet_evm::Bloom {
+			EVM::block_logs_bloom(number)
+		}
+
+		fn receipts_retention() -> BlockNumber {
+			EVM::receipts_retention()
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
 		Block,
 		Balance,
@@ -974,6 +1259,13 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> Result<Weight, sp_runtime::RuntimeString> {
+			Executive::try_runtime_upgrade().map_err(Into::into)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -1001,11 +1293,14 @@ impl_runtime_apis! {
 
 			add_benchmark!(params, batches, b"balances", Balances);
 			add_benchmark!(params, batches, b"collective", Council);
+			add_benchmark!(params, batches, b"contracts", Contracts);
 			add_benchmark!(params, batches, b"democracy", Democracy);
 			add_benchmark!(params, batches, b"elections", Elections);
 			add_benchmark!(params, batches, b"identity", Identity);
 			add_benchmark!(params, batches, b"im-online", ImOnline);
+			add_benchmark!(params, batches, b"multisig", Multisig);
 			add_benchmark!(params, batches, b"offences", OffencesBench::<Runtime>);
+			add_benchmark!(params, batches, b"proxy", Proxy);
 			add_benchmark!(params, batches, b"scheduler", Scheduler);
 			add_benchmark!(params, batches, b"session", SessionBench::<Runtime>);
 			add_benchmark!(params, batches, b"staking", Staking);
@@ -1034,4 +1329,40 @@ mod tests {
 
 		is_submit_signed_transaction::<Runtime>();
 	}
+
+	#[test]
+	fn non_transfer_proxy_cannot_batch_transfers() {
+		use frame_support::{assert_noop, assert_ok};
+		use sp_runtime::traits::Dispatchable;
+
+		let alice = AccountId::from([1u8; 32]);
+		let bob = AccountId::from([2u8; 32]);
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(alice.clone(), 10 * DOLLARS), (bob.clone(), 10 * DOLLARS)],
+		}.assimilate_storage(&mut t).unwrap();
+
+		sp_io::TestExternalities::new(t).execute_with(|| {
+			let proxy = |call: Call| {
+				Call::Proxy(pallet_proxy::Call::proxy(alice.clone(), None, Box::new(call)))
+					.dispatch(Origin::signed(bob.clone()))
+			};
+			assert_ok!(Call::Proxy(pallet_proxy::Call::add_proxy(bob.clone(), ProxyType::NonTransfer, 0))
+				.dispatch(Origin::signed(alice.clone())));
+
+			let transfer = Call::Balances(BalancesCall::transfer(bob.clone().into(), DOLLARS));
+			let wrapped = vec![
+				Call::Utility(pallet_utility::Call::batch(vec![transfer.clone()])),
+				Call::Utility(pallet_utility::Call::as_sub(0, Box::new(transfer.clone()))),
+				Call::Multisig(pallet_multisig::Call::as_multi_threshold_1(vec![], Box::new(transfer.clone()))),
+				Call::Proxy(pallet_proxy::Call::proxy(bob.clone(), None, Box::new(transfer.clone()))),
+				transfer,
+			];
+			for call in wrapped {
+				assert_noop!(proxy(call), pallet_proxy::Error::<Runtime>::Unproxyable);
+			}
+
+			assert_ok!(proxy(Call::System(SystemCall::remark(vec![]))));
+		});
+	}
 }
@@ -162,6 +162,16 @@ pub trait BlockImportOperation<Block: BlockT> {
 	/// Inject storage data into the database replacing any existing data.
 	fn reset_storage(&mut self, storage: Storage) -> sp_blockchain::Result<Block::Hash>;
 
+	/// Inject trie nodes of the state of a block downloaded from the network, keyed by their
+	/// prefixed database key.
+	///
+	/// The state may be split over several operations. The block itself is imported along with
+	/// the last chunk, and becomes the new base of the chain: it must be finalized, its ancestors
+	/// are not required to be known, and all the nodes of its state must have been injected.
+	fn insert_state_nodes(&mut self, _nodes: Vec<(Vec<u8>, Vec<u8>)>) -> sp_blockchain::Result<()> {
+		Err(sp_blockchain::Error::Backend("State import is not supported by this backend".into()))
+	}
+
 	/// Set storage changes.
 	fn update_storage(
 		&mut self,
@@ -447,6 +457,13 @@ pub trait Backend<Block: BlockT>: AuxStore + Send + Sync {
 	/// Returns state backend with post-state of given block.
 	fn state_at(&self, block: BlockId<Block>) -> sp_blockchain::Result<Self::State>;
 
+	/// Returns state backend with the state injected with
+	/// `BlockImportOperation::insert_state_nodes`, given its root, before the block it belongs
+	/// to is imported.
+	fn imported_state(&self, _root: &Block::Hash) -> sp_blockchain::Result<Self::State> {
+		Err(sp_blockchain::Error::Backend("State import is not supported by this backend".into()))
+	}
+
 	/// Attempts to revert the chain by `n` blocks. If `revert_finalized` is set
 	/// it will attempt to revert past any finalized block, this is unsafe and
 	/// can potentially leave the node in an inconsistent state.
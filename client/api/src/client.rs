@@ -92,6 +92,29 @@ pub trait BlockBackend<Block: BlockT> {
 	fn justification(&self, id: &BlockId<Block>) -> sp_blockchain::Result<Option<Justification>>;
 }
 
+/// Import of the full state of a block, obtained out of band.
+pub trait StateImporter<Block: BlockT> {
+	/// Import a chunk of the trie nodes of the state of a finalized block, keyed by their
+	/// prefixed database key, as returned by `ProofProvider::verify_range_proof`. Chunks are
+	/// written to the database right away.
+	///
+	/// Once all the chunks are imported, the block itself is imported through the block import
+	/// pipeline with `BlockImportParams::state_imported` set, so that consensus engines can
+	/// initialize their state from it. It becomes the new best and finalized block, regardless
+	/// of whether its ancestors are known.
+	fn import_state_chunk(&self, nodes: Vec<(Vec<u8>, Vec<u8>)>) -> sp_blockchain::Result<()>;
+
+	/// Call `method` of the runtime on the imported state of the block with the given header,
+	/// once all its chunks are imported but before the block itself is. Consensus engines use
+	/// this to check the state before the block becomes the new base of the chain.
+	fn call_imported_state(
+		&self,
+		header: &Block::Header,
+		method: &str,
+		call_data: &[u8],
+	) -> sp_blockchain::Result<Vec<u8>>;
+}
+
 /// Provide a list of potential uncle headers for a given block.
 pub trait ProvideUncles<Block: BlockT> {
 	/// Gets the uncles of the block with `target_hash` going back `max_generation` ancestors.
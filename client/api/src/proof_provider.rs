@@ -21,7 +21,7 @@ use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT},
 };
-use crate::{StorageProof, ChangesProof};
+use crate::{StorageProof, ChangesProof, CheckedRange};
 use sp_storage::{ChildInfo, StorageKey, PrefixedStorageKey};
 
 /// Interface for providing block proving utilities.
@@ -70,4 +70,26 @@ pub trait ProofProvider<Block: BlockT> {
 		storage_key: Option<&PrefixedStorageKey>,
 		key: &StorageKey,
 	) -> sp_blockchain::Result<ChangesProof<Block::Header>>;
+
+	/// Given a `BlockId`, iterate over all storage values starting at `start_key` exclusively,
+	/// building proofs until size limit is reached. Returns the proof and the number of
+	/// key-value pairs it proves. Iterates over `child_info` instead of the top trie if given.
+	fn read_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: Option<&[u8]>,
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, u32)>;
+
+	/// Verify a proof built by `read_proof_collection` against the given state root.
+	/// Returns the key-value pairs read from the proof, the trie nodes they were read from,
+	/// and whether the end of the trie was reached.
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_key: Option<&[u8]>,
+	) -> sp_blockchain::Result<CheckedRange>;
 }
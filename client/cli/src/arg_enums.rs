@@ -174,6 +174,27 @@ arg_enum! {
 	}
 }
 
+arg_enum! {
+	/// How the chain is synchronized with the network.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum SyncMode {
+		// Download and import all the blocks.
+		Full,
+		// Download a GRANDPA warp sync proof and the state at a recent finalized block first.
+		Warp,
+	}
+}
+
+impl Into<sc_network::config::SyncMode> for SyncMode {
+	fn into(self) -> sc_network::config::SyncMode {
+		match self {
+			SyncMode::Full => sc_network::config::SyncMode::Full,
+			SyncMode::Warp => sc_network::config::SyncMode::Warp,
+		}
+	}
+}
+
 /// Default value for the `--execution-syncing` parameter.
 pub const DEFAULT_EXECUTION_SYNCING: ExecutionStrategy = ExecutionStrategy::NativeElseWasm;
 /// Default value for the `--execution-import-block` parameter.
@@ -23,6 +23,7 @@ mod import_blocks_cmd;
 mod purge_chain_cmd;
 mod revert_cmd;
 mod run_cmd;
+mod try_runtime_cmd;
 
 pub use self::build_spec_cmd::BuildSpecCmd;
 pub use self::check_block_cmd::CheckBlockCmd;
@@ -32,6 +33,7 @@ pub use self::purge_chain_cmd::PurgeChainCmd;
 pub use self::revert_cmd::RevertCmd;
 pub use self::run_cmd::RunCmd;
 pub use self::export_state_cmd::ExportStateCmd;
+pub use self::try_runtime_cmd::TryRuntimeCmd;
 use std::fmt::Debug;
 use structopt::StructOpt;
 
@@ -62,6 +64,9 @@ pub enum Subcommand {
 
 	/// Export state as raw chain spec.
 	ExportState(ExportStateCmd),
+
+	/// Execute a runtime upgrade against a snapshot of the chain state.
+	TryRuntime(TryRuntimeCmd),
 }
 
 // TODO: move to config.rs?
@@ -407,6 +412,7 @@ macro_rules! substrate_cli_subcommands {
 }
 
 substrate_cli_subcommands!(
-	Subcommand => BuildSpec, ExportBlocks, ImportBlocks, CheckBlock, Revert, PurgeChain, ExportState
+	Subcommand =>
+		BuildSpec, ExportBlocks, ImportBlocks, CheckBlock, Revert, PurgeChain, ExportState, TryRuntime
 );
 
@@ -0,0 +1,215 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{ImportParams, SharedParams, BlockNumberOrHash},
+};
+use codec::Decode;
+use log::{info, debug};
+use sc_executor::NativeExecutor;
+use sc_service::{Configuration, ServiceBuilderCommand};
+use sp_core::{
+	hexdisplay::HexDisplay,
+	storage::{Storage, StorageChild, StorageKey, StorageData, ChildInfo},
+	traits::{CallInWasm, MissingHostFunctions},
+};
+use sp_runtime::traits::{Block as BlockT, NumberFor, HashFor};
+use sp_state_machine::TestExternalities;
+use std::{collections::HashMap, fmt::Debug, fs, io, path::{Path, PathBuf}, str::FromStr};
+use structopt::StructOpt;
+
+/// The runtime API method that executes the runtime upgrade and its checks.
+const TRY_RUNTIME_UPGRADE: &str = "TryRuntime_on_runtime_upgrade";
+
+/// The `try-runtime` command used to execute a runtime upgrade against a snapshot of the state
+/// of a chain.
+///
+/// The runtime that is upgraded to must be built with the `try-runtime` feature.
+#[derive(Debug, StructOpt, Clone)]
+pub struct TryRuntimeCmd {
+	/// Path to the Wasm blob of the runtime to upgrade to.
+	#[structopt(long, value_name = "PATH", parse(from_os_str))]
+	pub wasm: PathBuf,
+
+	/// Take the state snapshot from a raw chain spec, as written by `export-state`, instead of
+	/// the local database.
+	#[structopt(long, value_name = "PATH", parse(from_os_str))]
+	pub snapshot: Option<PathBuf>,
+
+	/// Block hash or number of the local database state to take the snapshot of.
+	///
+	/// Defaults to the best block. Ignored when `--snapshot` is given.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// The default number of 64KB pages to ever allocate for Wasm execution.
+	///
+	/// Don't alter this unless you know what you're doing.
+	#[structopt(long = "default-heap-pages", value_name = "COUNT")]
+	pub default_heap_pages: Option<u64>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl TryRuntimeCmd {
+	/// Run the `try-runtime` command
+	pub fn run<B, BC, BB>(
+		&self,
+		config: Configuration,
+		builder: B,
+	) -> error::Result<()>
+	where
+		B: FnOnce(Configuration) -> Result<BC, sc_service::error::Error>,
+		BC: ServiceBuilderCommand<Block = BB> + Unpin,
+		BB: BlockT + Debug,
+		<NumberFor<BB> as FromStr>::Err: std::fmt::Debug,
+		BB::Hash: FromStr,
+		<BB::Hash as FromStr>::Err: std::fmt::Debug,
+	{
+		let executor = NativeExecutor::<BC::NativeDispatch>::new(
+			config.wasm_method,
+			config.default_heap_pages,
+			1, // The runtime instances cache size.
+		);
+		let code = fs::read(&self.wasm)?;
+
+		let storage = match self.snapshot {
+			Some(ref path) => {
+				info!("Loading state snapshot from {}...", path.display());
+				read_snapshot(path)?
+			},
+			None => {
+				info!("Loading state snapshot from the local database...");
+				let block_id = self.at.clone().map(|b| b.parse()).transpose()?;
+				builder(config)?.export_raw_state(block_id)?
+			},
+		};
+		info!(
+			"Loaded {} top-level keys and {} child tries",
+			storage.top.len(),
+			storage.children_default.len(),
+		);
+
+		let mut ext = TestExternalities::<HashFor<BB>>::new_with_code(
+			&code,
+			with_prefixed_child_keys(storage),
+		);
+
+		info!("Executing runtime upgrade...");
+		let result = executor.call_in_wasm(
+			&code,
+			None,
+			TRY_RUNTIME_UPGRADE,
+			&[],
+			&mut ext.ext(),
+			MissingHostFunctions::Disallow,
+		).map_err(|e| format!("Runtime upgrade panicked: {}", e))?;
+		let weight = <Result<u64, String> as Decode>::decode(&mut &result[..])
+			.map_err(|e| format!("Failed to decode runtime upgrade result: {:?}", e))?
+			.map_err(|e| format!("Runtime upgrade checks failed: {}", e))?;
+
+		let changes = ext.overlayed_changes();
+		let mut changed_keys = 0;
+		for (key, _) in changes.changes(None) {
+			debug!("Changed top-level key 0x{}", HexDisplay::from(key));
+			changed_keys += 1;
+		}
+		for child_info in changes.child_infos() {
+			for (key, _) in changes.changes(Some(child_info)) {
+				debug!(
+					"Changed key 0x{} of child trie 0x{}",
+					HexDisplay::from(key),
+					HexDisplay::from(&child_info.storage_key()),
+				);
+				changed_keys += 1;
+			}
+		}
+
+		println!(
+			"Runtime upgrade consumed {} weight and changed {} storage keys.",
+			weight,
+			changed_keys,
+		);
+		Ok(())
+	}
+}
+
+/// Read the raw storage of a chain spec, as written by `export-state`.
+fn read_snapshot(path: &Path) -> error::Result<Storage> {
+	let file = fs::File::open(path)?;
+	let spec: serde_json::Value = serde_json::from_reader(io::BufReader::new(file))
+		.map_err(|e| format!("Error parsing state snapshot: {}", e))?;
+	let raw = &spec["genesis"]["raw"];
+	if raw.is_null() {
+		return Err("State snapshot is not a raw chain spec".into());
+	}
+
+	let top: HashMap<StorageKey, StorageData> = serde_json::from_value(raw["top"].clone())
+		.map_err(|e| format!("Error parsing top-level storage of state snapshot: {}", e))?;
+	let children: HashMap<StorageKey, HashMap<StorageKey, StorageData>> =
+		if raw["childrenDefault"].is_null() {
+			Default::default()
+		} else {
+			serde_json::from_value(raw["childrenDefault"].clone())
+				.map_err(|e| format!("Error parsing child storage of state snapshot: {}", e))?
+		};
+
+	Ok(Storage {
+		top: top.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
+		children_default: children.into_iter().map(|(storage_key, child_content)| {
+			let child_info = ChildInfo::new_default(storage_key.0.as_slice());
+			(
+				storage_key.0,
+				StorageChild {
+					data: child_content.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
+					child_info,
+				},
+			)
+		}).collect(),
+	})
+}
+
+/// Key the child tries of `storage` by their prefixed storage key, as `TestExternalities`
+/// expects.
+fn with_prefixed_child_keys(mut storage: Storage) -> Storage {
+	storage.children_default = storage.children_default
+		.into_iter()
+		.map(|(_, child)| (child.child_info.prefixed_storage_key().into_inner(), child))
+		.collect();
+	storage
+}
+
+impl CliConfiguration for TryRuntimeCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+
+	fn default_heap_pages(&self) -> error::Result<Option<u64>> {
+		Ok(self.default_heap_pages)
+	}
+}
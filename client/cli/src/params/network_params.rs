@@ -16,6 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::arg_enums::SyncMode;
 use crate::params::node_key_params::NodeKeyParams;
 use sc_network::{
 	config::{NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, TransportConfig},
@@ -107,6 +108,20 @@ pub struct NetworkParams {
 	/// This option will be removed in the future.
 	#[structopt(long)]
 	pub legacy_network_protocol: bool,
+
+	/// Blockchain syncing mode.
+	///
+	/// `Warp` downloads a chain of GRANDPA justifications to skip over the history of the chain,
+	/// up to a recently finalized block, then downloads the state at that block and starts from
+	/// it. It only has an effect when starting from genesis.
+	#[structopt(
+		long = "sync",
+		value_name = "SYNC_MODE",
+		possible_values = &SyncMode::variants(),
+		case_insensitive = true,
+		default_value = "Full",
+	)]
+	pub sync: SyncMode,
 }
 
 impl NetworkParams {
@@ -152,6 +167,7 @@ impl NetworkParams {
 			listen_addresses,
 			public_addresses,
 			notifications_protocols: Vec::new(),
+			request_response_protocols: Vec::new(),
 			node_key,
 			node_name: node_name.to_string(),
 			client_version: client_id.to_string(),
@@ -166,6 +182,7 @@ impl NetworkParams {
 			max_parallel_downloads: self.max_parallel_downloads,
 			allow_non_globals_in_dht: self.discover_local || is_dev,
 			use_new_block_requests_protocol: !self.legacy_network_protocol,
+			sync_mode: self.sync.into(),
 		}
 	}
 }
@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use sc_service::config::TransactionPoolOptions;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 /// Parameters used to create the pool configuration.
@@ -29,6 +30,20 @@ pub struct TransactionPoolParams {
 	/// Maximum number of kilobytes of all transactions stored in the pool.
 	#[structopt(long = "pool-kbytes", value_name = "COUNT", default_value = "20480")]
 	pub pool_kbytes: usize,
+
+	/// Maximum number of transactions of a single sender in the transaction pool.
+	#[structopt(long = "pool-sender-limit", value_name = "COUNT", default_value = "512")]
+	pub pool_sender_limit: usize,
+
+	/// Maximum number of kilobytes of all transactions of a single sender stored in the pool.
+	#[structopt(long = "pool-sender-kbytes", value_name = "COUNT", default_value = "2048")]
+	pub pool_sender_kbytes: usize,
+
+	/// Keep the transactions in the pool across restarts by journaling them to the given file.
+	///
+	/// The transactions are revalidated against the best block when the node is started again.
+	#[structopt(long = "pool-journal", value_name = "PATH", parse(from_os_str))]
+	pub pool_journal: Option<PathBuf>,
 }
 
 impl TransactionPoolParams {
@@ -45,6 +60,12 @@ impl TransactionPoolParams {
 		opts.future.count = self.pool_limit / factor;
 		opts.future.total_bytes = self.pool_kbytes * 1024 / factor;
 
+		// per sender
+		opts.per_sender.count = self.pool_sender_limit;
+		opts.per_sender.total_bytes = self.pool_sender_kbytes * 1024;
+
+		opts.journal_path = self.pool_journal.clone();
+
 		opts
 	}
 }
@@ -103,7 +103,7 @@ use sp_timestamp::{TimestampInherentData, InherentType as TimestampInherent};
 use sp_consensus::import_queue::{Verifier, BasicQueue, CacheKeyId};
 use sc_client_api::{
 	backend::AuxStore,
-	BlockchainEvents, ProvideUncles,
+	BlockchainEvents, ProvideUncles, StateImporter,
 };
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 
@@ -196,6 +196,19 @@ impl Epoch {
 	}
 }
 
+impl From<sp_consensus_babe::Epoch> for Epoch {
+	fn from(epoch: sp_consensus_babe::Epoch) -> Self {
+		Epoch {
+			epoch_index: epoch.epoch_index,
+			start_slot: epoch.start_slot,
+			duration: epoch.duration,
+			authorities: epoch.authorities,
+			randomness: epoch.randomness,
+			config: epoch.config,
+		}
+	}
+}
+
 #[derive(derive_more::Display, Debug)]
 enum Error<B: BlockT> {
 	#[display(fmt = "Multiple BABE pre-runtime digests, rejecting!")]
@@ -210,6 +223,8 @@ enum Error<B: BlockT> {
 	Extraction(sp_consensus::Error),
 	#[display(fmt = "Could not fetch epoch at {:?}", _0)]
 	FetchEpoch(B::Hash),
+	#[display(fmt = "No authorities are known for the epoch after the one of {:?}", _0)]
+	UnknownNextEpochAuthorities(B::Hash),
 	#[display(fmt = "Header {:?} rejected: too far in the future", _0)]
 	TooFarInFuture(B::Hash),
 	#[display(fmt = "Parent ({}) of {} unavailable. Cannot import", _0, _1)]
@@ -290,7 +305,7 @@ impl Config {
 				&b, |v| v == 1,
 			)?;
 			let has_api_v2 = a.has_api_with::<dyn BabeApi<B, Error = sp_blockchain::Error>, _>(
-				&b, |v| v == 2,
+				&b, |v| v >= 2,
 			)?;
 
 			if has_api_v1 {
@@ -959,7 +974,8 @@ impl<Block, Client, Inner> BlockImport<Block> for BabeBlockImport<Block, Client,
 	Inner: BlockImport<Block, Transaction = sp_api::TransactionFor<Client, Block>> + Send + Sync,
 	Inner::Error: Into<ConsensusError>,
 	Client: HeaderBackend<Block> + HeaderMetadata<Block, Error = sp_blockchain::Error>
-		+ AuxStore + ProvideRuntimeApi<Block> + ProvideCache<Block> + Send + Sync,
+		+ AuxStore + ProvideRuntimeApi<Block> + ProvideCache<Block> + StateImporter<Block>
+		+ Send + Sync,
 	Client::Api: BabeApi<Block> + ApiExt<Block>,
 {
 	type Error = ConsensusError;
@@ -981,6 +997,10 @@ impl<Block, Client, Inner> BlockImport<Block> for BabeBlockImport<Block, Client,
 			Err(e) => return Err(ConsensusError::ClientImport(e.to_string())),
 		}
 
+		if block.state_imported {
+			return self.import_state(block, new_cache);
+		}
+
 		let pre_digest = find_pre_digest::<Block>(&block.header)
 			.expect("valid babe headers must contain a predigest; \
 					 header has been already verified; qed");
@@ -1206,6 +1226,94 @@ impl<Block, Client, Inner> BlockImport<Block> for BabeBlockImport<Block, Client,
 	}
 }
 
+impl<Block, Client, Inner> BabeBlockImport<Block, Client, Inner> where
+	Block: BlockT,
+	Inner: BlockImport<Block, Transaction = sp_api::TransactionFor<Client, Block>> + Send + Sync,
+	Inner::Error: Into<ConsensusError>,
+	Client: HeaderBackend<Block> + HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ AuxStore + ProvideRuntimeApi<Block> + ProvideCache<Block> + StateImporter<Block>
+		+ Send + Sync,
+	Client::Api: BabeApi<Block> + ApiExt<Block>,
+{
+	/// Import a block whose state was downloaded rather than built by executing
+	/// it. None of its ancestors are known, so the epoch changes are reset to
+	/// the current and next epoch reported by the runtime at that block.
+	fn import_state(
+		&mut self,
+		mut block: BlockImportParams<Block, sp_api::TransactionFor<Client, Block>>,
+		new_cache: HashMap<CacheKeyId, Vec<u8>>,
+	) -> Result<ImportResult, ConsensusError> {
+		let hash = block.post_hash();
+		let number = *block.header.number();
+
+		// the block isn't in the database yet, so the runtime is called on its
+		// imported state directly.
+		let call = |method| self.client.call_imported_state(&block.header, method, &[])
+			.and_then(|encoded| sp_consensus_babe::Epoch::decode(&mut &encoded[..])
+				.map_err(|e| sp_blockchain::Error::CallResultDecode("failed to decode epoch", e)));
+		let (current, next) = match call("BabeApi_current_epoch")
+			.and_then(|current| call("BabeApi_next_epoch").map(|next| (current, next)))
+		{
+			Ok(epochs) => epochs,
+			Err(e) => {
+				debug!(target: "babe", "Failed to fetch epochs at {}: {:?}", hash, e);
+				return Err(ConsensusError::ClientImport(
+					babe_err(Error::<Block>::FetchEpoch(hash)).into()
+				));
+			},
+		};
+
+		// the runtime doesn't know the next authorities until the next epoch change after
+		// a migration of its storage, and blocks of the next epoch couldn't be verified.
+		if next.authorities.is_empty() {
+			return Err(ConsensusError::ClientImport(
+				babe_err(Error::<Block>::UnknownNextEpochAuthorities(hash)).into()
+			));
+		}
+
+		let epoch_index = current.epoch_index;
+		let mut epoch_changes = self.epoch_changes.lock();
+		let old_epoch_changes = (*epoch_changes).clone();
+		epoch_changes.reset(hash, number, current.into(), next.into());
+
+		// the imported block starts the chain, so it has no weight of its own.
+		aux_schema::write_epoch_changes::<Block, _, _>(
+			&*epoch_changes,
+			|insert| block.auxiliary.extend(
+				insert.iter().map(|(k, v)| (k.to_vec(), Some(v.to_vec())))
+			),
+		);
+		aux_schema::write_block_weight(
+			hash,
+			0,
+			|insert| block.auxiliary.extend(
+				insert.iter().map(|(k, v)| (k.to_vec(), Some(v.to_vec())))
+			),
+		);
+
+		let import_result = self.inner.import_block(block, new_cache);
+
+		// revert to the original epoch changes in case there's an error
+		// importing the block
+		match import_result {
+			Ok(ImportResult::Imported(aux)) => {
+				info!(
+					target: "babe",
+					"👶 Starting from epoch {} at imported state {}",
+					epoch_index,
+					hash,
+				);
+
+				Ok(ImportResult::Imported(aux))
+			},
+			import_result => {
+				*epoch_changes = old_epoch_changes;
+				import_result.map_err(Into::into)
+			},
+		}
+	}
+}
+
 /// Gets the best finalized block and its slot, and prunes the given epoch tree.
 fn prune_finalized<Block, Client>(
 	client: Arc<Client>,
@@ -796,3 +796,95 @@ fn verify_slots_are_strictly_increasing() {
 		&mut block_import,
 	);
 }
+
+#[test]
+fn importing_state_resets_epoch_changes() {
+	use sc_client_api::{ProofProvider, StateImporter};
+
+	let mut net = BabeTestNet::new(2);
+
+	let peer = net.peer(0);
+	let data = peer.data.as_ref().expect("babe link set up during initialization");
+	let client = peer.client().as_full().expect("Only full clients are used in tests").clone();
+	let mut block_import = data.block_import.lock().take().expect("import set up during init");
+
+	let mut proposer_factory = DummyFactory {
+		client: client.clone(),
+		config: data.link.config.clone(),
+		epoch_changes: data.link.epoch_changes.clone(),
+		mutator: Arc::new(|_, _| ()),
+	};
+
+	let mut parent = client.header(&BlockId::Number(0)).unwrap().unwrap();
+	for slot_number in 1..5 {
+		let hash = propose_and_import_block(
+			&parent,
+			Some(slot_number),
+			&mut proposer_factory,
+			&mut block_import,
+		);
+		parent = client.header(&BlockId::Hash(hash)).unwrap().unwrap();
+	}
+	let target = parent;
+	let at = BlockId::Hash(target.hash());
+	let current: Epoch = client.runtime_api().current_epoch(&at).unwrap().into();
+	let next: Epoch = client.runtime_api().next_epoch(&at).unwrap().into();
+
+	// the second peer downloads the state at the target and imports it
+	// without any of its ancestors.
+	let peer = net.peer(1);
+	let data = peer.data.as_ref().expect("babe link set up during initialization");
+	let synced_client = peer.client().as_full().expect("Only full clients are used in tests").clone();
+	let mut synced_block_import = data.block_import.lock().take().expect("import set up during init");
+
+	let (proof, _) = client.read_proof_collection(&at, None, None, usize::max_value()).unwrap();
+	let checked = synced_client.verify_range_proof(*target.state_root(), proof, None, None).unwrap();
+	assert!(checked.complete);
+	synced_client.import_state_chunk(checked.nodes).unwrap();
+
+	let mut import = BlockImportParams::new(BlockOrigin::NetworkInitialSync, target.clone());
+	import.finalized = true;
+	import.fork_choice = Some(ForkChoiceStrategy::Custom(true));
+	import.allow_missing_state = true;
+	import.state_imported = true;
+	assert!(matches!(
+		synced_block_import.import_block(import, Default::default()),
+		Ok(ImportResult::Imported(_)),
+	));
+	assert_eq!(synced_client.info().finalized_hash, target.hash());
+
+	// the epochs reported by the runtime are tracked from the imported block.
+	let epoch_changes = data.link.epoch_changes.clone();
+	let epoch_for_child = |slot_number| epoch_changes.lock().epoch_data_for_child_of(
+		descendent_query(&*synced_client),
+		&target.hash(),
+		*target.number(),
+		slot_number,
+		|_| panic!("no genesis epoch is needed after importing state"),
+	).unwrap().unwrap();
+	assert_eq!(epoch_for_child(current.start_slot), current);
+	assert_eq!(epoch_for_child(next.start_slot), next);
+	assert_eq!(
+		aux_schema::load_epoch_changes::<TestBlock, _>(&*synced_client, &data.link.config)
+			.unwrap()
+			.lock()
+			.encode(),
+		epoch_changes.lock().encode(),
+	);
+	assert_eq!(aux_schema::load_block_weight(&*synced_client, target.hash()).unwrap(), Some(0));
+
+	// and the node keeps importing blocks on top of the imported state.
+	let mut proposer_factory = DummyFactory {
+		client: synced_client.clone(),
+		config: data.link.config.clone(),
+		epoch_changes: data.link.epoch_changes.clone(),
+		mutator: Arc::new(|_, _| ()),
+	};
+	let hash = propose_and_import_block(
+		&target,
+		None,
+		&mut proposer_factory,
+		&mut synced_block_import,
+	);
+	assert_eq!(synced_client.info().best_hash, hash);
+}
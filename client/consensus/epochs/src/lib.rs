@@ -636,6 +636,29 @@ impl<Hash, Number, E: Epoch> EpochChanges<Hash, Number, E> where
 		}
 	}
 
+	/// Reset the epoch changes to a single entry at the given block, holding
+	/// the current and the next epoch as of that block.
+	///
+	/// Used when the chain starts from a block whose state was imported
+	/// without its ancestors, so the epoch changes can't be tracked from
+	/// genesis. Any previously tracked changes are discarded.
+	pub fn reset(&mut self, hash: Hash, number: Number, current: E, next: E) {
+		let persisted = PersistedEpoch::Genesis(current, next);
+		let header = PersistedEpochHeader::from(&persisted);
+
+		self.inner = ForkTree::new();
+		self.epochs = BTreeMap::new();
+
+		// the tree is empty, so the import can't fail on a missing ancestor.
+		let _ = self.inner.import(
+			hash,
+			number,
+			header,
+			&|_: &Hash, _: &Hash| -> Result<bool, std::convert::Infallible> { Ok(false) },
+		);
+		self.epochs.insert((hash, number), persisted);
+	}
+
 	/// Return the inner fork tree.
 	pub fn tree(&self) -> &ForkTree<Hash, Number, PersistedEpochHeader<E>> {
 		&self.inner
@@ -967,4 +990,59 @@ mod tests {
 			assert!(epoch_for_x_child_before_genesis.is_none());
 		}
 	}
+
+	#[test]
+	fn reset_starts_from_imported_block() {
+		//
+		// 0 - A - B(imported state) - C
+		//
+		let is_descendent_of = |base: &Hash, block: &Hash| -> Result<bool, TestError> {
+			match (base, *block) {
+				(b"0", _) => Ok(true),
+				(b"A", b) => Ok(b == *b"B" || b == *b"C"),
+				(b"B", b) => Ok(b == *b"C"),
+				_ => Ok(false),
+			}
+		};
+
+		let current = Epoch { start_slot: 100, duration: 100 };
+		let next = Epoch { start_slot: 200, duration: 100 };
+
+		let mut epoch_changes = EpochChanges::<_, _, Epoch>::new();
+		epoch_changes.import(
+			&is_descendent_of,
+			*b"A",
+			1,
+			*b"0",
+			ViableEpoch::<_, Epoch>::UnimportedGenesis(Epoch { start_slot: 1, duration: 100 })
+				.increment(()),
+		).unwrap();
+
+		epoch_changes.reset(*b"B", 50, current.clone(), next.clone());
+
+		assert_eq!(
+			epoch_changes.tree().roots().map(|(h, n, _)| (*h, *n)).collect::<Vec<_>>(),
+			vec![(*b"B", 50)],
+		);
+
+		let epoch_for_c = epoch_changes.epoch_data_for_child_of(
+			&is_descendent_of,
+			b"B",
+			50,
+			150,
+			|_| panic!("no genesis epoch after a reset"),
+		).unwrap().unwrap();
+
+		assert_eq!(epoch_for_c, current);
+
+		let epoch_for_c = epoch_changes.epoch_data_for_child_of(
+			&is_descendent_of,
+			b"B",
+			50,
+			250,
+			|_| panic!("no genesis epoch after a reset"),
+		).unwrap().unwrap();
+
+		assert_eq!(epoch_for_c, next);
+	}
 }
@@ -63,7 +63,7 @@ use sp_blockchain::{
 	well_known_cache_keys, HeaderBackend,
 };
 use codec::{Decode, Encode};
-use hash_db::Prefix;
+use hash_db::{Prefix, EMPTY_PREFIX};
 use sp_trie::{MemoryDB, PrefixedMemoryDB, prefixed_key};
 use sp_database::Transaction;
 use parking_lot::RwLock;
@@ -540,6 +540,7 @@ pub struct BlockImportOperation<Block: BlockT> {
 	finalized_blocks: Vec<(BlockId<Block>, Option<Justification>)>,
 	set_head: Option<BlockId<Block>>,
 	commit_state: bool,
+	imported_state: Option<Vec<(Vec<u8>, Vec<u8>)>>,
 }
 
 impl<Block: BlockT> BlockImportOperation<Block> {
@@ -631,6 +632,12 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		Ok(root)
 	}
 
+	fn insert_state_nodes(&mut self, nodes: Vec<(Vec<u8>, Vec<u8>)>) -> ClientResult<()> {
+		self.imported_state.get_or_insert_with(Vec::new).extend(nodes);
+		self.commit_state = true;
+		Ok(())
+	}
+
 	fn update_changes_trie(
 		&mut self,
 		update: ChangesTrieTransaction<HashFor<Block>, NumberFor<Block>>,
@@ -1039,6 +1046,27 @@ impl<Block: BlockT> Backend<Block> {
 			last_finalized_hash = block_hash;
 		}
 
+		// nodes of a state downloaded from the network are written as they come, since they
+		// are all part of the state of the block imported along with the last chunk.
+		let is_state_import = operation.imported_state.is_some();
+		let mut imported_state_root_found = false;
+		let state_root_key = operation.pending_block.as_ref().map(|pending_block| {
+			let state_root = pending_block.header.state_root();
+			if self.storage.prefix_keys {
+				prefixed_key::<HashFor<Block>>(state_root, EMPTY_PREFIX)
+			} else {
+				state_root.as_ref().to_vec()
+			}
+		});
+		for (mut key, value) in operation.imported_state.take().unwrap_or_default() {
+			if !self.storage.prefix_keys {
+				// Strip prefix
+				key.drain(0 .. key.len() - DB_HASH_LEN);
+			}
+			imported_state_root_found |= state_root_key.as_ref() == Some(&key);
+			transaction.set_from_vec(columns::STATE, &key, value);
+		}
+
 		let imported = if let Some(pending_block) = operation.pending_block {
 			let hash = pending_block.header.hash();
 			let parent_hash = *pending_block.header.parent_hash();
@@ -1047,7 +1075,35 @@ impl<Block: BlockT> Backend<Block> {
 			// blocks are keyed by number + hash.
 			let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
 
-			let (enacted, retracted) = if pending_block.leaf_state.is_best() {
+			// A block imported with its full state becomes the new base of the chain. Its parent
+			// is not known, so it is finalized without going through the usual checks.
+			if is_state_import {
+				if let Some(Some(_)) = operation.changes_trie_config_update {
+					return Err(sp_blockchain::Error::Backend(
+						"Changes tries are not supported when importing state".into()
+					));
+				}
+				if number.is_zero() || !pending_block.leaf_state.is_final() {
+					return Err(sp_blockchain::Error::Backend(
+						"Blocks imported with state must be finalized and not genesis".into()
+					));
+				}
+				let state_root_key = state_root_key.expect("pending block is some; qed");
+				if !imported_state_root_found && self.storage.db.get(columns::STATE, &state_root_key).is_none() {
+					return Err(sp_blockchain::Error::InvalidStateRoot);
+				}
+			}
+
+			let (enacted, retracted) = if is_state_import {
+				transaction.set_from_vec(columns::META, meta_keys::BEST_BLOCK, lookup_key.clone());
+				utils::insert_number_to_key_mapping(
+					&mut transaction,
+					columns::KEY_LOOKUP,
+					number,
+					hash,
+				)?;
+				(Default::default(), Default::default())
+			} else if pending_block.leaf_state.is_best() {
 				self.set_head_with_transaction(&mut transaction, parent_hash, (number, hash))?
 			} else {
 				(Default::default(), Default::default())
@@ -1133,12 +1189,21 @@ impl<Block: BlockT> Backend<Block> {
 				}
 				self.state_usage.tally_writes(ops, bytes);
 				let number_u64 = number.saturated_into::<u64>();
-				let commit = self.storage.state_db.insert_block(
-					&hash,
-					number_u64,
-					&pending_block.header.parent_hash(),
-					changeset,
-				).map_err(|e: sc_state_db::Error<io::Error>|
+				let commit = if is_state_import {
+					self.storage.state_db.import_base_block(
+						&hash,
+						number_u64,
+						&pending_block.header.parent_hash(),
+						changeset,
+					)
+				} else {
+					self.storage.state_db.insert_block(
+						&hash,
+						number_u64,
+						&pending_block.header.parent_hash(),
+						changeset,
+					)
+				}.map_err(|e: sc_state_db::Error<io::Error>|
 					sp_blockchain::Error::from(format!("State database error: {:?}", e))
 				)?;
 				apply_state_commit(&mut transaction, commit);
@@ -1154,24 +1219,35 @@ impl<Block: BlockT> Backend<Block> {
 			let is_best = pending_block.leaf_state.is_best();
 			let changes_trie_updates = operation.changes_trie_updates;
 			let changes_trie_config_update = operation.changes_trie_config_update;
-			changes_trie_cache_ops = Some(self.changes_tries_storage.commit(
-				&mut transaction,
-				changes_trie_updates,
-				cache::ComplexBlockId::new(
-					*header.parent_hash(),
-					if number.is_zero() { Zero::zero() } else { number - One::one() },
-				),
-				cache::ComplexBlockId::new(hash, number),
-				header,
-				finalized,
-				changes_trie_config_update,
-				changes_trie_cache_ops,
-			)?);
+			if !is_state_import {
+				changes_trie_cache_ops = Some(self.changes_tries_storage.commit(
+					&mut transaction,
+					changes_trie_updates,
+					cache::ComplexBlockId::new(
+						*header.parent_hash(),
+						if number.is_zero() { Zero::zero() } else { number - One::one() },
+					),
+					cache::ComplexBlockId::new(hash, number),
+					header,
+					finalized,
+					changes_trie_config_update,
+					changes_trie_cache_ops,
+				)?);
+			}
 			self.state_usage.merge_sm(operation.old_state.usage_info());
 			// release state reference so that it can be finalized
 			let cache = operation.old_state.into_cache_changes();
 
-			if finalized {
+			if is_state_import {
+				// the state has already been canonicalized by `import_base_block`.
+				let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
+				transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, lookup_key);
+				let new_displaced = self.blockchain.leaves.write().finalize_height(number);
+				match finalization_displaced_leaves {
+					None => finalization_displaced_leaves = Some(new_displaced),
+					Some(ref mut displaced) => displaced.merge(new_displaced),
+				}
+			} else if finalized {
 				// TODO: ensure best chain contains this block.
 				self.ensure_sequential_finalization(header, Some(last_finalized_hash))?;
 				self.note_finalized(
@@ -1384,6 +1460,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			finalized_blocks: Vec::new(),
 			set_head: None,
 			commit_state: false,
+			imported_state: None,
 		})
 	}
 
@@ -1636,6 +1713,25 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 		}
 	}
 
+	fn imported_state(&self, root: &Block::Hash) -> ClientResult<Self::State> {
+		// the nodes of an imported state are written as they come, so only the root is checked.
+		let root_node = sp_state_machine::Storage::get(self.storage.as_ref(), root, (&[], None))
+			.map_err(sp_blockchain::Error::Backend)?;
+		if root_node.is_none() {
+			return Err(sp_blockchain::Error::InvalidStateRoot);
+		}
+
+		let db_state = DbState::<Block>::new(self.storage.clone(), *root);
+		let state = RefTrackingState::new(db_state, self.storage.clone(), None);
+		let caching_state = CachingState::new(state, self.shared_cache.clone(), None);
+		Ok(SyncingCachingState::new(
+			caching_state,
+			self.state_usage.clone(),
+			self.blockchain.meta.clone(),
+			self.import_lock.clone(),
+		))
+	}
+
 	fn have_state_at(&self, hash: &Block::Hash, number: NumberFor<Block>) -> bool {
 		if self.is_archive {
 			match self.blockchain.header_metadata(hash.clone()) {
@@ -2283,6 +2379,62 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn import_state_as_new_base() {
+		let backend = Backend::<Block>::new_test(10, 10);
+		insert_header(&backend, 0, Default::default(), None, Default::default());
+
+		let mut nodes = PrefixedMemoryDB::<BlakeTwo256>::default();
+		let mut state_root = H256::default();
+		{
+			let mut trie = TrieDBMut::new(&mut nodes, &mut state_root);
+			trie.insert(&[1, 3, 5], &[2, 4, 6]).unwrap();
+			trie.insert(&[1, 2, 3], &[9, 9, 9]).unwrap();
+		}
+		let mut nodes: Vec<_> = nodes.drain().into_iter().map(|(key, (value, _))| (key, value)).collect();
+		let header = Header {
+			number: 10,
+			parent_hash: H256::from_low_u64_be(9),
+			state_root,
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let hash = header.hash();
+
+		// the block can't be imported before its state.
+		let mut op = backend.begin_operation().unwrap();
+		op.insert_state_nodes(Vec::new()).unwrap();
+		op.set_block_data(header.clone(), None, None, NewBlockState::Final).unwrap();
+		assert!(backend.commit_operation(op).is_err());
+
+		// the state is imported in chunks, the block along with the last one.
+		let last_chunk = nodes.split_off(nodes.len() / 2);
+		let mut op = backend.begin_operation().unwrap();
+		op.insert_state_nodes(nodes).unwrap();
+		backend.commit_operation(op).unwrap();
+		assert_eq!(backend.blockchain().info().best_number, 0);
+
+		let mut op = backend.begin_operation().unwrap();
+		op.insert_state_nodes(last_chunk).unwrap();
+		op.set_block_data(header, None, None, NewBlockState::Final).unwrap();
+		backend.commit_operation(op).unwrap();
+
+		let info = backend.blockchain().info();
+		assert_eq!(info.best_hash, hash);
+		assert_eq!(info.best_number, 10);
+		assert_eq!(info.finalized_hash, hash);
+		assert_eq!(info.finalized_number, 10);
+		assert_eq!(backend.blockchain().leaves().unwrap(), vec![hash]);
+
+		let state = backend.state_at(BlockId::Number(10)).unwrap();
+		assert_eq!(state.storage(&[1, 3, 5]).unwrap(), Some(vec![2, 4, 6]));
+		assert_eq!(state.storage(&[1, 2, 3]).unwrap(), Some(vec![9, 9, 9]));
+
+		// blocks can be imported on top of it
+		let block11 = insert_header(&backend, 11, hash, None, Default::default());
+		assert_eq!(backend.blockchain().info().best_hash, block11);
+	}
+
 	#[test]
 	fn test_finalize_non_sequential() {
 		let backend = Backend::<Block>::new_test(10, 10);
@@ -534,6 +534,62 @@ impl<H, N: Add<Output=N> + Clone> PendingChange<H, N> {
 	}
 }
 
+/// Tracks the historical standard authority set changes. For each authority set that was
+/// handed off through a standard change, we store the number of the last block it finalized,
+/// which always has a justification stored alongside it.
+///
+/// Nodes upgraded from a version which didn't track the changes only know about the handoffs
+/// of the authority sets starting with the one that was current at the time of the upgrade.
+#[derive(Debug, Clone, Encode, Decode, PartialEq)]
+pub(crate) struct AuthoritySetChanges<N> {
+	/// The id of the first authority set whose handoff is tracked.
+	first_set_id: u64,
+	/// The id of each handed off authority set, and the number of the last block it finalized.
+	changes: Vec<(u64, N)>,
+}
+
+impl<N: Ord + Clone> AuthoritySetChanges<N> {
+	/// Create empty authority set changes, tracking the handoffs from the genesis authority set.
+	pub(crate) fn empty() -> Self {
+		Self::tracked_from(0)
+	}
+
+	/// Create empty authority set changes, tracking the handoffs from the authority set with the
+	/// given id. The handoffs of the previous authority sets are unknown.
+	pub(crate) fn tracked_from(set_id: u64) -> Self {
+		AuthoritySetChanges {
+			first_set_id: set_id,
+			changes: Vec::new(),
+		}
+	}
+
+	/// Note that the authority set with the given id finalized its last block at
+	/// `block_number`.
+	pub(crate) fn append(&mut self, set_id: u64, block_number: N) {
+		self.changes.push((set_id, block_number));
+	}
+
+	/// Returns an iterator over the changes which were enacted strictly after the given block.
+	///
+	/// Returns `None` if the block may have been finalized by an authority set whose handoff
+	/// isn't tracked, in which case the changes after it can't be known.
+	pub(crate) fn iter_from(&self, block_number: N) -> Option<impl Iterator<Item = &(u64, N)>> {
+		if self.first_set_id != 0 {
+			match self.changes.first() {
+				Some((_, first_block)) if *first_block <= block_number => {},
+				_ => return None,
+			}
+		}
+
+		let idx = self.changes
+			.binary_search_by_key(&block_number, |(_, n)| n.clone())
+			.map(|i| i + 1)
+			.unwrap_or_else(|i| i);
+
+		Some(self.changes[idx..].iter())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1163,4 +1219,39 @@ mod tests {
 			Err(Error::InvalidAuthoritySet)
 		));
 	}
+
+	#[test]
+	fn authority_set_changes_iter_from() {
+		let mut authority_set_changes = AuthoritySetChanges::empty();
+		authority_set_changes.append(0, 41);
+		authority_set_changes.append(1, 81);
+		authority_set_changes.append(2, 121);
+
+		let iter_from = |n| authority_set_changes.iter_from(n).unwrap().cloned().collect::<Vec<_>>();
+
+		assert_eq!(iter_from(0), vec![(0, 41), (1, 81), (2, 121)]);
+		assert_eq!(iter_from(41), vec![(1, 81), (2, 121)]);
+		assert_eq!(iter_from(50), vec![(1, 81), (2, 121)]);
+		assert_eq!(iter_from(121), vec![]);
+		assert_eq!(iter_from(200), vec![]);
+	}
+
+	#[test]
+	fn authority_set_changes_iter_from_untracked_sets() {
+		let mut authority_set_changes = AuthoritySetChanges::tracked_from(5);
+		assert!(authority_set_changes.iter_from(0).is_none());
+		assert!(authority_set_changes.iter_from(200).is_none());
+
+		authority_set_changes.append(5, 81);
+		authority_set_changes.append(6, 121);
+
+		let iter_from = |n| authority_set_changes.iter_from(n).map(|changes| changes.cloned().collect::<Vec<_>>());
+
+		// blocks before the end of the first tracked set may have been finalized by untracked sets.
+		assert_eq!(iter_from(0), None);
+		assert_eq!(iter_from(80), None);
+		assert_eq!(iter_from(81), Some(vec![(6, 121)]));
+		assert_eq!(iter_from(100), Some(vec![(6, 121)]));
+		assert_eq!(iter_from(200), Some(vec![]));
+	}
 }
@@ -27,7 +27,9 @@ use sp_runtime::traits::{Block as BlockT, NumberFor};
 use log::{info, warn};
 use sp_finality_grandpa::{AuthorityList, SetId, RoundNumber};
 
-use crate::authorities::{AuthoritySet, SharedAuthoritySet, PendingChange, DelayKind};
+use crate::authorities::{
+	AuthoritySet, AuthoritySetChanges, SharedAuthoritySet, PendingChange, DelayKind,
+};
 use crate::consensus_changes::{SharedConsensusChanges, ConsensusChanges};
 use crate::environment::{
 	CompletedRound, CompletedRounds, CurrentRounds, HasVoted, SharedVoterSetState, VoterSetState,
@@ -39,6 +41,8 @@ const SET_STATE_KEY: &[u8] = b"grandpa_completed_round";
 const CONCLUDED_ROUNDS: &[u8] = b"grandpa_concluded_rounds";
 const AUTHORITY_SET_KEY: &[u8] = b"grandpa_voters";
 const CONSENSUS_CHANGES_KEY: &[u8] = b"grandpa_consensus_changes";
+const AUTHORITY_SET_CHANGES_KEY: &[u8] = b"grandpa_authority_set_changes";
+const WARP_SYNC_TARGET_KEY: &[u8] = b"grandpa_warp_sync_target";
 
 const CURRENT_VERSION: u32 = 2;
 
@@ -259,6 +263,26 @@ fn migrate_from_version1<Block: BlockT, B, G>(
 	Ok(None)
 }
 
+/// Start tracking the authority set changes from the current authority set, if they weren't
+/// tracked yet. This happens on nodes upgraded from a version that didn't track the changes, and
+/// the handoffs of the previous authority sets can't be proven by them.
+fn track_authority_set_changes<B: AuxStore, N: Encode + Decode + Ord + Clone>(
+	backend: &B,
+	set_id: u64,
+) -> ClientResult<()> {
+	if set_id == 0 || load_decode::<_, AuthoritySetChanges<N>>(backend, AUTHORITY_SET_CHANGES_KEY)?.is_some() {
+		return Ok(());
+	}
+
+	info!(target: "afg", "👴 Tracking GRANDPA authority set changes from set #{}. \
+		Warp sync proofs can't be generated for earlier blocks.", set_id);
+
+	backend.insert_aux(
+		&[(AUTHORITY_SET_CHANGES_KEY, AuthoritySetChanges::<N>::tracked_from(set_id).encode().as_slice())],
+		&[],
+	)
+}
+
 /// Load or initialize persistent data from backend.
 pub(crate) fn load_persistent<Block: BlockT, B, G>(
 	backend: &B,
@@ -280,6 +304,7 @@ pub(crate) fn load_persistent<Block: BlockT, B, G>(
 	match version {
 		None => {
 			if let Some((new_set, set_state)) = migrate_from_version0::<Block, _, _>(backend, &make_genesis_round)? {
+				track_authority_set_changes::<_, NumberFor<Block>>(backend, new_set.set_id)?;
 				return Ok(PersistentData {
 					authority_set: new_set.into(),
 					consensus_changes: Arc::new(consensus_changes.into()),
@@ -289,6 +314,7 @@ pub(crate) fn load_persistent<Block: BlockT, B, G>(
 		},
 		Some(1) => {
 			if let Some((new_set, set_state)) = migrate_from_version1::<Block, _, _>(backend, &make_genesis_round)? {
+				track_authority_set_changes::<_, NumberFor<Block>>(backend, new_set.set_id)?;
 				return Ok(PersistentData {
 					authority_set: new_set.into(),
 					consensus_changes: Arc::new(consensus_changes.into()),
@@ -319,6 +345,8 @@ pub(crate) fn load_persistent<Block: BlockT, B, G>(
 					}
 				};
 
+				track_authority_set_changes::<_, NumberFor<Block>>(backend, set.set_id)?;
+
 				return Ok(PersistentData {
 					authority_set: set.into(),
 					consensus_changes: Arc::new(consensus_changes.into()),
@@ -433,6 +461,64 @@ pub(crate) fn update_consensus_changes<H, N, F, R>(
 	write_aux(&[(CONSENSUS_CHANGES_KEY, set.encode().as_slice())])
 }
 
+/// Update the historical authority set changes.
+pub(crate) fn update_authority_set_changes<N, F, R>(
+	set_changes: &AuthoritySetChanges<N>,
+	write_aux: F
+) -> R where
+	N: Encode,
+	F: FnOnce(&[(&'static [u8], &[u8])]) -> R,
+{
+	write_aux(&[(AUTHORITY_SET_CHANGES_KEY, set_changes.encode().as_slice())])
+}
+
+/// Load the historical authority set changes, or empty changes if none were stored yet.
+///
+/// Changes are only missing while the genesis authority set is current, since
+/// [`load_persistent`] starts tracking them on nodes that handed it off before tracking them.
+pub(crate) fn load_authority_set_changes<B: AuxStore, N: Decode + Ord + Clone>(backend: &B)
+	-> ClientResult<AuthoritySetChanges<N>>
+{
+	load_decode(backend, AUTHORITY_SET_CHANGES_KEY)
+		.map(|changes| changes.unwrap_or_else(AuthoritySetChanges::empty))
+}
+
+/// Write the authority set proven by a warp sync to be current after the given block, to
+/// initialize the voter from once the state of the block is imported.
+pub(crate) fn write_warp_sync_target<B: AuxStore, H: Encode>(
+	backend: &B,
+	hash: &H,
+	set_id: SetId,
+	authorities: &AuthorityList,
+) -> ClientResult<()> {
+	backend.insert_aux(&[(WARP_SYNC_TARGET_KEY, (hash, set_id, authorities).encode().as_slice())], &[])
+}
+
+/// Load the authority set proven by a warp sync to be current after the given block, if any.
+pub(crate) fn load_warp_sync_target<B: AuxStore, H: Decode + PartialEq>(backend: &B, hash: &H)
+	-> ClientResult<Option<(SetId, AuthorityList)>>
+{
+	Ok(load_decode::<_, (H, SetId, AuthorityList)>(backend, WARP_SYNC_TARGET_KEY)?
+		.filter(|(target, _, _)| target == hash)
+		.map(|(_, set_id, authorities)| (set_id, authorities)))
+}
+
+/// Auxiliary data to write along with a block whose state was imported out of band: the
+/// authority set it starts from replaces the one proven by the warp sync, and the handoffs of
+/// earlier authority sets are unknown.
+pub(crate) fn imported_state_aux<Block: BlockT>(
+	set: &AuthoritySet<Block::Hash, NumberFor<Block>>,
+	new_set: &NewAuthoritySet<Block::Hash, NumberFor<Block>>,
+) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+	let mut aux = update_authority_set::<Block, _, _>(set, Some(new_set), |insert| {
+		insert.iter().map(|(k, v)| (k.to_vec(), Some(v.to_vec()))).collect::<Vec<_>>()
+	});
+	let set_changes = AuthoritySetChanges::<NumberFor<Block>>::tracked_from(set.set_id);
+	aux.push((AUTHORITY_SET_CHANGES_KEY.to_vec(), Some(set_changes.encode())));
+	aux.push((WARP_SYNC_TARGET_KEY.to_vec(), None));
+	aux
+}
+
 #[cfg(test)]
 pub(crate) fn load_authorities<B: AuxStore, H: Decode, N: Decode>(backend: &B)
 	-> Option<AuthoritySet<H, N>> {
@@ -625,6 +711,57 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn load_persistent_tracks_authority_set_changes_from_current_set() {
+		let client = substrate_test_runtime_client::new();
+
+		let authorities = vec![(AuthorityId::default(), 100)];
+		let set_id = 3;
+
+		{
+			let authority_set = AuthoritySet::<H256, u64>::new(
+				authorities.clone(),
+				set_id,
+				ForkTree::new(),
+				Vec::new(),
+			).unwrap();
+
+			client.insert_aux(
+				&[
+					(AUTHORITY_SET_KEY, authority_set.encode().as_slice()),
+					(VERSION_KEY, 2u32.encode().as_slice()),
+				],
+				&[],
+			).unwrap();
+		}
+
+		assert_eq!(load_authority_set_changes::<_, u64>(&client).unwrap(), AuthoritySetChanges::empty());
+
+		load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
+			&client,
+			H256::random(),
+			0,
+			|| unreachable!(),
+		).unwrap();
+
+		// the handoffs of the previous sets are unknown.
+		let mut set_changes = AuthoritySetChanges::tracked_from(set_id);
+		assert_eq!(load_authority_set_changes::<_, u64>(&client).unwrap(), set_changes);
+
+		// tracked changes are kept on restart.
+		set_changes.append(set_id, 42);
+		update_authority_set_changes(&set_changes, |insert| client.insert_aux(insert, &[])).unwrap();
+
+		load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
+			&client,
+			H256::random(),
+			0,
+			|| unreachable!(),
+		).unwrap();
+
+		assert_eq!(load_authority_set_changes::<_, u64>(&client).unwrap(), set_changes);
+	}
+
 	#[test]
 	fn write_read_concluded_rounds() {
 		let client = substrate_test_runtime_client::new();
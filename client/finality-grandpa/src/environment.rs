@@ -1236,6 +1236,26 @@ pub(crate) fn finalize_block<BE, Block, Client>(
 			}
 		}
 
+		if let Some((_, canon_number)) = status.new_set_block {
+			// the previous set has finalized its last block, which is stored with a
+			// justification. keep track of it so that we can prove the handoff to warp
+			// syncing nodes.
+			let mut set_changes = crate::aux_schema::load_authority_set_changes(&*client)?;
+			set_changes.append(authority_set.set_id - 1, canon_number);
+
+			let write_result = crate::aux_schema::update_authority_set_changes(
+				&set_changes,
+				|insert| apply_aux(import_op, insert, &[]),
+			);
+
+			if let Err(e) = write_result {
+				warn!(target: "afg", "Failed to write authority set changes to disk. Bailing.");
+				warn!(target: "afg", "Node is in a potentially inconsistent state.");
+
+				return Err(e.into());
+			}
+		}
+
 		Ok(new_authorities.map(VoterCommand::ChangeAuthorities))
 	});
 
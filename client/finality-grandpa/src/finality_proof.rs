@@ -49,13 +49,17 @@ use parity_scale_codec::{Encode, Decode};
 use finality_grandpa::BlockNumberOps;
 use sp_runtime::{
 	Justification, generic::BlockId,
-	traits::{NumberFor, Block as BlockT, Header as HeaderT, One},
+	traits::{NumberFor, Block as BlockT, Header as HeaderT, One, Zero},
 };
 use sp_core::storage::StorageKey;
 use sc_telemetry::{telemetry, CONSENSUS_INFO};
-use sp_finality_grandpa::{AuthorityId, AuthorityList, VersionedAuthorityList, GRANDPA_AUTHORITIES_KEY};
+use sc_network::config::WarpSyncProgress;
+use sp_finality_grandpa::{
+	AuthorityId, AuthorityList, SetId, VersionedAuthorityList, GRANDPA_AUTHORITIES_KEY,
+};
 
 use crate::justification::GrandpaJustification;
+use crate::warp_proof::{WarpSyncProof, check_warp_sync_proof};
 use crate::VoterSet;
 
 /// Maximum number of fragments that we want to return in a single prove_finality call.
@@ -197,6 +201,42 @@ impl<B, Block> sc_network::config::FinalityProofProvider<Block> for FinalityProo
 			),
 		}
 	}
+
+	fn prove_warp_sync(&self, begin: Block::Hash) -> Result<Option<Vec<u8>>, ClientError> {
+		let set_changes = crate::aux_schema::load_authority_set_changes(&*self.backend)?;
+		let proof = WarpSyncProof::<Block>::generate(
+			&*self.backend.blockchain(),
+			begin,
+			&set_changes,
+		)?;
+
+		if proof.proofs.is_empty() {
+			return Ok(None);
+		}
+
+		Ok(Some(proof.encode()))
+	}
+
+	fn verify_warp_sync_proof(
+		&self,
+		state: Option<&[u8]>,
+		proof: &[u8],
+	) -> Result<WarpSyncProgress<Block>, ClientError> {
+		let (set_id, authorities) = match state {
+			Some(state) => <(SetId, AuthorityList)>::decode(&mut &state[..])
+				.map_err(|_| ClientError::Backend("Invalid warp sync state".to_string()))?,
+			None => (0, self.authority_provider.authorities(&BlockId::Number(Zero::zero()))?),
+		};
+
+		let (progress, (set_id, authorities)) = check_warp_sync_proof::<Block>(set_id, authorities, proof)?;
+		if let WarpSyncProgress::Complete { header, .. } = &progress {
+			// the block import starts from this authority set once the state of the block is
+			// imported.
+			crate::aux_schema::write_warp_sync_target(&*self.backend, &header.hash(), set_id, &authorities)?;
+		}
+
+		Ok(progress)
+	}
 }
 
 /// The effects of block finality.
@@ -19,7 +19,7 @@
 use std::{sync::Arc, collections::HashMap};
 
 use log::{debug, trace};
-use parity_scale_codec::Encode;
+use parity_scale_codec::{Decode, Encode};
 use parking_lot::RwLockWriteGuard;
 
 use sp_blockchain::{BlockStatus, well_known_cache_keys};
@@ -44,6 +44,7 @@ use crate::authorities::{AuthoritySet, SharedAuthoritySet, DelayKind, PendingCha
 use crate::consensus_changes::SharedConsensusChanges;
 use crate::environment::finalize_block;
 use crate::justification::GrandpaJustification;
+use fork_tree::ForkTree;
 use std::marker::PhantomData;
 
 /// A block-import handler for GRANDPA.
@@ -182,7 +183,7 @@ impl<'a, Block: 'a + BlockT> Drop for PendingSetChanges<'a, Block> {
 	}
 }
 
-fn find_scheduled_change<B: BlockT>(header: &B::Header)
+pub(crate) fn find_scheduled_change<B: BlockT>(header: &B::Header)
 	-> Option<ScheduledChange<NumberFor<B>>>
 {
 	let id = OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID);
@@ -401,6 +402,75 @@ where
 	}
 }
 
+impl<BE, Block: BlockT, Client, SC> GrandpaBlockImport<BE, Block, Client, SC> where
+	NumberFor<Block>: finality_grandpa::BlockNumberOps,
+	BE: Backend<Block>,
+	Client: crate::ClientForGrandpa<Block, BE>,
+	for<'a> &'a Client:
+		BlockImport<Block, Error = ConsensusError, Transaction = TransactionFor<Client, Block>>,
+{
+	// import a block whose state was imported out of band. its ancestors are unknown, so the
+	// voter restarts from the authority set that the warp sync proved to be current after it.
+	// changes signaled before the block but not yet enacted would be lost, so the block is
+	// refused if the runtime has one pending.
+	fn import_state(
+		&mut self,
+		mut block: BlockImportParams<Block, TransactionFor<Client, Block>>,
+		new_cache: HashMap<well_known_cache_keys::Id, Vec<u8>>,
+	) -> Result<ImportResult, ConsensusError> {
+		let hash = block.post_hash();
+		let number = *block.header.number();
+
+		let (set_id, authorities) = crate::aux_schema::load_warp_sync_target(&*self.inner, &hash)
+			.map_err(|e| ConsensusError::ClientImport(e.to_string()))?
+			.ok_or_else(|| ConsensusError::ClientImport(
+				format!("No authority set proven by warp sync for block #{} ({})", number, hash),
+			))?;
+
+		let pending_change = self.inner
+			.call_imported_state(&block.header, "GrandpaApi_grandpa_pending_change", &[])
+			.and_then(|encoded| {
+				Option::<ScheduledChange<NumberFor<Block>>>::decode(&mut &encoded[..])
+					.map_err(|e| sp_blockchain::Error::CallResultDecode(
+						"failed to decode GRANDPA pending change",
+						e,
+					))
+			})
+			.map_err(|e| ConsensusError::ClientImport(e.to_string()))?;
+		if pending_change.is_some() {
+			return Err(ConsensusError::ClientImport(format!(
+				"Authority set change pending at imported state of block #{} ({})",
+				number,
+				hash,
+			)));
+		}
+
+		let authority_set = AuthoritySet::new(authorities.clone(), set_id, ForkTree::new(), Vec::new())
+			.ok_or(ConsensusError::InvalidAuthoritiesSet)?;
+		let new_set = NewAuthoritySet { canon_number: number, canon_hash: hash, set_id, authorities };
+
+		block.auxiliary.extend(crate::aux_schema::imported_state_aux::<Block>(&authority_set, &new_set));
+
+		let import_result = (&*self.inner).import_block(block, new_cache)
+			.map_err(|e| ConsensusError::ClientImport(e.to_string()))?;
+
+		if let ImportResult::Imported(_) = import_result {
+			debug!(
+				target: "afg",
+				"Starting from authority set #{} after importing the state of block #{} ({})",
+				set_id,
+				number,
+				hash,
+			);
+
+			*self.authority_set.inner().write() = authority_set;
+			let _ = self.send_voter_commands.unbounded_send(VoterCommand::ChangeAuthorities(new_set));
+		}
+
+		Ok(import_result)
+	}
+}
+
 impl<BE, Block: BlockT, Client, SC> BlockImport<Block>
 	for GrandpaBlockImport<BE, Block, Client, SC> where
 		NumberFor<Block>: finality_grandpa::BlockNumberOps,
@@ -429,6 +499,10 @@ impl<BE, Block: BlockT, Client, SC> BlockImport<Block>
 			Err(e) => return Err(ConsensusError::ClientImport(e.to_string())),
 		}
 
+		if block.state_imported {
+			return self.import_state(block, new_cache);
+		}
+
 		// on initial sync we will restrict logging under info to avoid spam.
 		let initial_sync = block.origin == BlockOrigin::NetworkInitialSync;
 
@@ -62,7 +62,7 @@ use log::{debug, info};
 use sc_client_api::{
 	backend::{AuxStore, Backend},
 	LockImportRun, BlockchainEvents, CallExecutor,
-	ExecutionStrategy, Finalizer, TransactionFor, ExecutorProvider,
+	ExecutionStrategy, Finalizer, TransactionFor, ExecutorProvider, StateImporter,
 };
 use parity_scale_codec::{Decode, Encode};
 use prometheus_endpoint::{PrometheusError, Registry};
@@ -116,6 +116,7 @@ mod light_import;
 mod observer;
 mod until_imported;
 mod voting_rule;
+mod warp_proof;
 
 pub use authorities::SharedAuthoritySet;
 pub use finality_proof::{FinalityProofProvider, StorageAndProofProvider};
@@ -327,6 +328,7 @@ pub trait ClientForGrandpa<Block, BE>:
 	LockImportRun<Block, BE> + Finalizer<Block, BE> + AuxStore
 	+ HeaderMetadata<Block, Error = sp_blockchain::Error> + HeaderBackend<Block>
 	+ BlockchainEvents<Block> + ProvideRuntimeApi<Block> + ExecutorProvider<Block>
+	+ StateImporter<Block>
 	+ BlockImport<Block, Transaction = TransactionFor<BE, Block>, Error = sp_consensus::Error>
 	where
 		BE: Backend<Block>,
@@ -340,6 +342,7 @@ impl<Block, BE, T> ClientForGrandpa<Block, BE> for T
 		T: LockImportRun<Block, BE> + Finalizer<Block, BE> + AuxStore
 			+ HeaderMetadata<Block, Error = sp_blockchain::Error> + HeaderBackend<Block>
 			+ BlockchainEvents<Block> + ProvideRuntimeApi<Block> + ExecutorProvider<Block>
+			+ StateImporter<Block>
 			+ BlockImport<Block, Transaction = TransactionFor<BE, Block>, Error = sp_consensus::Error>,
 {}
 
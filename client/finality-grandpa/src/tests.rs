@@ -217,6 +217,10 @@ sp_api::mock_impl_runtime_apis! {
 			self.inner.genesis_authorities.clone()
 		}
 
+		fn grandpa_pending_change(&self) -> Option<ScheduledChange<BlockNumber>> {
+			None
+		}
+
 		fn submit_report_equivocation_extrinsic(
 			_equivocation_proof: EquivocationProof<Hash, BlockNumber>,
 			_key_owner_proof: OpaqueKeyOwnershipProof,
@@ -1724,3 +1728,137 @@ fn imports_justification_for_regular_blocks_on_import() {
 		client.justification(&BlockId::Hash(block_hash)).unwrap().is_some(),
 	);
 }
+
+/// Downloads the state of the given block of the first peer into the second one, like a warp sync
+/// does.
+fn download_state(net: &mut GrandpaTestNet, header: &<Block as BlockT>::Header) {
+	use sc_client_api::{ProofProvider, StateImporter};
+
+	let at = BlockId::Hash(header.hash());
+	let (proof, _) = net.peer(0).client().as_full().expect("only full clients are used in test")
+		.read_proof_collection(&at, None, None, usize::max_value())
+		.unwrap();
+	let client = net.peer(1).client().as_full().expect("only full clients are used in test");
+	let checked = client.verify_range_proof(*header.state_root(), proof, None, None).unwrap();
+	assert!(checked.complete);
+	client.import_state_chunk(checked.nodes).unwrap();
+}
+
+/// The import of a block whose state was downloaded.
+fn state_import(
+	header: &<Block as BlockT>::Header,
+) -> BlockImportParams<Block, TransactionFor<substrate_test_runtime_client::Backend, Block>> {
+	let mut import = BlockImportParams::new(BlockOrigin::NetworkInitialSync, header.clone());
+	import.finalized = true;
+	import.fork_choice = Some(ForkChoiceStrategy::Custom(true));
+	import.allow_missing_state = true;
+	import.state_imported = true;
+	import
+}
+
+#[test]
+fn importing_state_starts_from_warp_sync_authority_set() {
+	use futures::{FutureExt, StreamExt};
+
+	let peers_a = &[Ed25519Keyring::Alice, Ed25519Keyring::Bob, Ed25519Keyring::Charlie];
+	let peers_b = &[Ed25519Keyring::Alice, Ed25519Keyring::Bob];
+	let api = TestApi::new(make_ids(peers_a));
+	let mut net = GrandpaTestNet::new(api.clone(), 2);
+
+	net.peer(0).push_blocks(3, false);
+	let header = net.peer(0).client().header(&BlockId::Number(3)).unwrap().unwrap();
+	let hash = header.hash();
+
+	// the second peer warp synced to block #3 and downloaded its state.
+	download_state(&mut net, &header);
+	let (client, backend) = match net.peer(1).client() {
+		PeersClient::Full(client, backend) => (client.clone(), backend.clone()),
+		PeersClient::Light(..) => unreachable!("only full clients are used in test"),
+	};
+	let (mut block_import, mut link) = block_import(
+		client.clone(),
+		&api,
+		LongestChain::new(backend.clone()),
+	).unwrap();
+
+	let import = || state_import(&header);
+
+	// the authority set must have been proven by the warp sync.
+	assert!(block_import.import_block(import(), HashMap::new()).is_err());
+	assert_eq!(client.info().finalized_number, 0);
+
+	aux_schema::write_warp_sync_target(&*client, &hash, 2, &make_ids(peers_b)).unwrap();
+	assert!(matches!(
+		block_import.import_block(import(), HashMap::new()),
+		Ok(ImportResult::Imported(_)),
+	));
+	assert_eq!(client.info().finalized_hash, hash);
+	assert_eq!(aux_schema::load_warp_sync_target(&*client, &hash).unwrap(), None);
+
+	// the voter restarts from the proven authority set, which is persisted.
+	let authority_set = link.persistent_data.authority_set.inner().read().clone();
+	assert_eq!(authority_set.current(), (2, &make_ids(peers_b)[..]));
+	assert_eq!(
+		aux_schema::load_authorities::<_, Hash, BlockNumber>(&*backend),
+		Some(authority_set),
+	);
+	match link.voter_commands_rx.next().now_or_never() {
+		Some(Some(VoterCommand::ChangeAuthorities(new))) => {
+			assert_eq!((new.canon_hash, new.canon_number, new.set_id), (hash, 3, 2));
+			assert_eq!(new.authorities, make_ids(peers_b));
+		},
+		_ => panic!("expected the voter to change authorities"),
+	}
+}
+
+#[test]
+fn importing_state_with_pending_authority_set_change_fails() {
+	use futures::{FutureExt, StreamExt};
+	use substrate_test_runtime_client::runtime::{Extrinsic, GRANDPA_PENDING_CHANGE_KEY};
+
+	let peers = &[Ed25519Keyring::Alice, Ed25519Keyring::Bob];
+	let api = TestApi::new(make_ids(peers));
+	let mut net = GrandpaTestNet::new(api.clone(), 2);
+
+	// a change is pending in the runtime at block #3, while the warp sync can only prove the
+	// authority set after it.
+	net.peer(0).push_blocks(2, false);
+	net.peer(0).generate_blocks(1, BlockOrigin::File, |mut builder| {
+		let change = ScheduledChange { next_authorities: make_ids(peers), delay: 0 };
+		builder.push(Extrinsic::StorageChange(
+			GRANDPA_PENDING_CHANGE_KEY.to_vec(),
+			Some(Some(change).encode()),
+		)).unwrap();
+		builder.build().unwrap().block
+	});
+	let header = net.peer(0).client().header(&BlockId::Number(3)).unwrap().unwrap();
+	let hash = header.hash();
+
+	download_state(&mut net, &header);
+	let (client, backend) = match net.peer(1).client() {
+		PeersClient::Full(client, backend) => (client.clone(), backend.clone()),
+		PeersClient::Light(..) => unreachable!("only full clients are used in test"),
+	};
+	let (mut block_import, mut link) = block_import(
+		client.clone(),
+		&api,
+		LongestChain::new(backend.clone()),
+	).unwrap();
+
+	aux_schema::write_warp_sync_target(&*client, &hash, 2, &make_ids(peers)).unwrap();
+	assert!(block_import.import_block(state_import(&header), HashMap::new()).is_err());
+
+	// the block is not imported, and the warp sync target is kept.
+	assert!(client.header(&BlockId::Hash(hash)).unwrap().is_none());
+	assert_eq!(client.info().finalized_number, 0);
+	assert!(aux_schema::load_warp_sync_target(&*client, &hash).unwrap().is_some());
+
+	// the voter keeps the authority set it started with.
+	let authority_set = link.persistent_data.authority_set.inner().read().clone();
+	assert_eq!(authority_set.current(), (0, &make_ids(peers)[..]));
+	assert_eq!(
+		aux_schema::load_authorities::<_, Hash, BlockNumber>(&*backend),
+		Some(authority_set),
+	);
+	assert!(link.voter_commands_rx.next().now_or_never().is_none());
+}
@@ -0,0 +1,526 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GRANDPA warp sync proof generation and check.
+//!
+//! A warp sync proof allows a node that only knows the genesis authority set to learn about a
+//! recently finalized block without importing the chain. It is made of one fragment for each
+//! standard authority set change, in order, where each fragment contains:
+//! 1) the last block finalized by an authority set, whose header signals the next authority set;
+//! 2) the justification of that block, signed by the authority set.
+//!
+//! The last fragment of a complete proof may instead justify a block finalized after the last
+//! authority set change, in which case its header doesn't signal any change.
+//!
+//! Only changes scheduled with a delay of zero can be proven, since the header of the last block
+//! of the set must contain the next authorities. Proofs are refused over delayed changes, and
+//! forced changes interrupt the proof.
+
+use log::trace;
+use parity_scale_codec::{Encode, Decode};
+use finality_grandpa::{BlockNumberOps, voter_set::VoterSet};
+use sc_network::config::WarpSyncProgress;
+use sp_blockchain::{
+	Backend as BlockchainBackend, Error as ClientError, Result as ClientResult,
+};
+use sp_finality_grandpa::{AuthorityList, SetId};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Zero},
+};
+
+use crate::authorities::AuthoritySetChanges;
+use crate::import::find_scheduled_change;
+use crate::justification::GrandpaJustification;
+
+/// Maximum size of a warp sync proof, in bytes. Warp sync proofs are sent over the network, so
+/// they must fit in a single response.
+const MAX_WARP_SYNC_PROOF_SIZE: usize = 8 * 1024 * 1024;
+
+/// Proof of the finality of a block, in a warp sync proof.
+#[derive(Encode, Decode)]
+pub(crate) struct WarpSyncFragment<Block: BlockT> {
+	/// The header of the justified block. This is the last block of an authority set, and its
+	/// header signals the next authority set, unless this is the last fragment of the proof.
+	pub header: Block::Header,
+	/// The justification of the header, signed by the authority set being handed off.
+	pub justification: GrandpaJustification<Block>,
+}
+
+/// Proof of the finality of a recent block, starting at a block known to the verifier.
+#[derive(Encode, Decode)]
+pub(crate) struct WarpSyncProof<Block: BlockT> {
+	/// The fragments of the proof, ordered by block number.
+	pub proofs: Vec<WarpSyncFragment<Block>>,
+	/// Whether the proof reaches the latest justified block of the prover. If `false`, the proof
+	/// was cut short because of its size, and more proofs should be requested starting at its
+	/// last block.
+	pub is_finished: bool,
+}
+
+impl<Block: BlockT> WarpSyncProof<Block> {
+	/// Generate a warp sync proof for all the authority set changes enacted after the
+	/// finalized block `begin`, followed by the most recent justification known, if any.
+	pub(crate) fn generate<B: BlockchainBackend<Block>>(
+		blockchain: &B,
+		begin: Block::Hash,
+		set_changes: &AuthoritySetChanges<NumberFor<Block>>,
+	) -> ClientResult<WarpSyncProof<Block>> {
+		let begin_number = blockchain.expect_block_number_from_id(&BlockId::Hash(begin))?;
+		let info = blockchain.info();
+		if begin_number > info.finalized_number {
+			return Err(ClientError::Backend(
+				format!("Cannot generate warp sync proof for non-finalized block: {}", begin),
+			));
+		}
+
+		// early-return if we sure that the block is NOT a part of canonical chain
+		let canonical_begin = blockchain.expect_block_hash_from_id(&BlockId::Number(begin_number))?;
+		if begin != canonical_begin {
+			return Err(ClientError::Backend(
+				format!("Cannot generate warp sync proof for non-canonical block: {}", begin),
+			));
+		}
+
+		let mut proofs = Vec::new();
+		let mut proofs_encoded_len = 0;
+		let mut proof_limit_reached = false;
+		let mut interrupted = false;
+		let mut last_proven_number = begin_number;
+
+		// refuse to skip authority set changes that we don't know about.
+		let set_changes = set_changes.iter_from(begin_number).ok_or_else(|| ClientError::Backend(
+			format!("Missing authority set changes to generate warp sync proof from block: {}", begin),
+		))?;
+
+		for (set_id, last_block) in set_changes {
+			let header = blockchain.expect_header(BlockId::Number(*last_block))?;
+
+			match find_scheduled_change::<Block>(&header) {
+				Some(change) if change.delay.is_zero() => {},
+				// the verifier can't check a delayed change, since the header that enacts it
+				// isn't part of the proof.
+				Some(_) => return Err(ClientError::Backend(
+					format!("Cannot prove delayed handoff of authority set #{}", set_id),
+				)),
+				// the next authority set can't be proven if it wasn't signalled in this header,
+				// e.g. after a forced change.
+				None => {
+					trace!(
+						target: "afg",
+						"Authority set #{} handoff at #{:?} can't be proven. Interrupting warp sync proof.",
+						set_id,
+						last_block,
+					);
+
+					interrupted = true;
+					break;
+				},
+			}
+
+			let justification = match blockchain.justification(BlockId::Number(*last_block))? {
+				Some(justification) => justification,
+				None => return Err(ClientError::Backend(
+					format!("Missing justification for the last block of authority set #{}", set_id),
+				)),
+			};
+			let justification = GrandpaJustification::<Block>::decode(&mut &justification[..])
+				.map_err(|_| ClientError::JustificationDecode)?;
+
+			let fragment = WarpSyncFragment { header, justification };
+			let fragment_size = fragment.encode().len();
+
+			// leave some room for the encoding of the vector length and `is_finished`.
+			if proofs_encoded_len + fragment_size >= MAX_WARP_SYNC_PROOF_SIZE - 64 {
+				proof_limit_reached = true;
+				break;
+			}
+
+			proofs_encoded_len += fragment_size;
+			proofs.push(fragment);
+			last_proven_number = *last_block;
+		}
+
+		// the verifier knows the current authority set, so add the most recent justification
+		// to let it jump past the last authority set change.
+		if !proof_limit_reached && !interrupted {
+			let mut current_number = info.finalized_number;
+			while current_number > last_proven_number {
+				if let Some(justification) = blockchain.justification(BlockId::Number(current_number))? {
+					let justification = GrandpaJustification::<Block>::decode(&mut &justification[..])
+						.map_err(|_| ClientError::JustificationDecode)?;
+					let header = blockchain.expect_header(BlockId::Number(current_number))?;
+
+					proofs.push(WarpSyncFragment { header, justification });
+					break;
+				}
+
+				current_number = current_number - One::one();
+			}
+		}
+
+		trace!(
+			target: "afg",
+			"Built warp sync proof starting at #{:?} with {} fragments.",
+			begin_number,
+			proofs.len(),
+		);
+
+		Ok(WarpSyncProof { proofs, is_finished: !proof_limit_reached })
+	}
+
+	/// Verify the proof, given the authority set that finalized the first block of the proof.
+	///
+	/// Returns the authority set after the last fragment of the proof.
+	pub(crate) fn verify(
+		&self,
+		set_id: SetId,
+		authorities: AuthorityList,
+	) -> ClientResult<(SetId, AuthorityList)>
+		where
+			NumberFor<Block>: BlockNumberOps,
+	{
+		let mut current_set_id = set_id;
+		let mut current_authorities = authorities;
+
+		for (fragment_index, fragment) in self.proofs.iter().enumerate() {
+			let voters = VoterSet::new(current_authorities.iter().cloned()).ok_or(
+				ClientError::Consensus(sp_consensus::Error::InvalidAuthoritiesSet),
+			)?;
+
+			let target = (fragment.justification.commit.target_hash, fragment.justification.commit.target_number);
+			if target != (fragment.header.hash(), *fragment.header.number()) {
+				return Err(ClientError::BadJustification(
+					"mismatch between header and justification in warp sync proof".into(),
+				));
+			}
+
+			fragment.justification.verify(current_set_id, &voters)?;
+
+			match find_scheduled_change::<Block>(&fragment.header) {
+				Some(change) if change.delay.is_zero() => {
+					current_authorities = change.next_authorities;
+					current_set_id += 1;
+				},
+				Some(_) => return Err(ClientError::BadJustification(
+					"delayed authority set change in warp sync proof".into(),
+				)),
+				None if fragment_index == self.proofs.len() - 1 && self.is_finished => {},
+				None => return Err(ClientError::BadJustification(
+					"header is missing authority set change digest in warp sync proof".into(),
+				)),
+			}
+		}
+
+		Ok((current_set_id, current_authorities))
+	}
+}
+
+/// Check a warp sync proof, starting with the given authority set.
+///
+/// Returns the progress of the warp sync, where the state of a partial warp sync is the encoded
+/// authority set to check the next proof with, along with the authority set after the last block
+/// of the proof.
+pub(crate) fn check_warp_sync_proof<Block: BlockT>(
+	set_id: SetId,
+	authorities: AuthorityList,
+	remote_proof: &[u8],
+) -> ClientResult<(WarpSyncProgress<Block>, (SetId, AuthorityList))>
+	where
+		NumberFor<Block>: BlockNumberOps,
+{
+	let proof = WarpSyncProof::<Block>::decode(&mut &remote_proof[..])
+		.map_err(|_| ClientError::BadJustification("failed to decode warp sync proof".into()))?;
+
+	let last_fragment = match proof.proofs.last() {
+		Some(fragment) => fragment,
+		None => return Err(ClientError::BadJustification("empty warp sync proof".into())),
+	};
+
+	let (set_id, authorities) = proof.verify(set_id, authorities)?;
+
+	let progress = if proof.is_finished {
+		WarpSyncProgress::Complete {
+			header: last_fragment.header.clone(),
+			justification: last_fragment.justification.encode(),
+		}
+	} else {
+		WarpSyncProgress::Partial {
+			last_hash: last_fragment.header.hash(),
+			state: (set_id, &authorities).encode(),
+		}
+	};
+
+	Ok((progress, (set_id, authorities)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+	use sc_client_api::{NewBlockState, in_mem::Blockchain as InMemoryBlockchain};
+	use sp_blockchain::HeaderBackend;
+	use sp_finality_grandpa::{ConsensusLog, ScheduledChange, GRANDPA_ENGINE_ID};
+	use sp_keyring::Ed25519Keyring;
+	use sp_runtime::{Digest, DigestItem};
+	use substrate_test_runtime_client::runtime::{Block, Header, H256};
+
+	fn authorities(keys: &[Ed25519Keyring]) -> AuthorityList {
+		keys.iter().map(|key| (key.public().into(), 1)).collect()
+	}
+
+	fn header(number: u64, parent_hash: H256, next_authorities: Option<AuthorityList>) -> Header {
+		delayed_header(number, parent_hash, next_authorities, 0)
+	}
+
+	fn delayed_header(
+		number: u64,
+		parent_hash: H256,
+		next_authorities: Option<AuthorityList>,
+		delay: u64,
+	) -> Header {
+		let mut digest = Digest::default();
+		if let Some(next_authorities) = next_authorities {
+			let change = ConsensusLog::<u64>::ScheduledChange(ScheduledChange { next_authorities, delay });
+			digest.push(DigestItem::Consensus(GRANDPA_ENGINE_ID, change.encode()));
+		}
+
+		Header::new(number, Default::default(), Default::default(), parent_hash, digest)
+	}
+
+	fn justification(
+		blockchain: &Arc<InMemoryBlockchain<Block>>,
+		header: &Header,
+		set_id: SetId,
+		keys: &[Ed25519Keyring],
+	) -> Vec<u8> {
+		let round = 1;
+		let precommit = finality_grandpa::Precommit {
+			target_hash: header.hash(),
+			target_number: *header.number(),
+		};
+		let msg = finality_grandpa::Message::Precommit(precommit.clone());
+		let encoded = sp_finality_grandpa::localized_payload(round, set_id, &msg);
+
+		let precommits = keys.iter().map(|key| finality_grandpa::SignedPrecommit {
+			precommit: precommit.clone(),
+			signature: key.sign(&encoded[..]).into(),
+			id: key.public().into(),
+		}).collect();
+
+		let commit = finality_grandpa::Commit {
+			target_hash: header.hash(),
+			target_number: *header.number(),
+			precommits,
+		};
+
+		GrandpaJustification::from_commit(blockchain, round, commit).unwrap().encode()
+	}
+
+	/// Builds a chain of 10 finalized blocks, where the authority set is handed off at blocks 3
+	/// (to Bob) and 6 (to Charlie), and block 8 is justified.
+	fn test_blockchain() -> (Arc<InMemoryBlockchain<Block>>, AuthoritySetChanges<u64>) {
+		let blockchain = Arc::new(InMemoryBlockchain::<Block>::new());
+		let mut set_changes = AuthoritySetChanges::empty();
+		let mut set_id = 0;
+		let mut keys = vec![Ed25519Keyring::Alice];
+		let mut parent_hash = Default::default();
+
+		for number in 0..=10 {
+			let next_keys = match number {
+				3 => Some(vec![Ed25519Keyring::Bob]),
+				6 => Some(vec![Ed25519Keyring::Charlie]),
+				_ => None,
+			};
+			let header = header(number, parent_hash, next_keys.as_ref().map(|keys| authorities(keys)));
+			let hash = header.hash();
+			let justification = if next_keys.is_some() || number == 8 {
+				Some(justification(&blockchain, &header, set_id, &keys))
+			} else {
+				None
+			};
+			blockchain.insert(hash, header, justification, None, NewBlockState::Final).unwrap();
+
+			if let Some(next_keys) = next_keys {
+				set_changes.append(set_id, number);
+				set_id += 1;
+				keys = next_keys;
+			}
+
+			parent_hash = hash;
+		}
+
+		(blockchain, set_changes)
+	}
+
+	fn hash_of(blockchain: &InMemoryBlockchain<Block>, number: u64) -> H256 {
+		blockchain.expect_block_hash_from_id(&BlockId::Number(number)).unwrap()
+	}
+
+	#[test]
+	fn warp_sync_proof_from_genesis_is_complete() {
+		let (blockchain, set_changes) = test_blockchain();
+
+		let proof = WarpSyncProof::<Block>::generate(
+			&*blockchain,
+			hash_of(&blockchain, 0),
+			&set_changes,
+		).unwrap();
+
+		assert!(proof.is_finished);
+		assert_eq!(
+			proof.proofs.iter().map(|fragment| *fragment.header.number()).collect::<Vec<_>>(),
+			vec![3, 6, 8],
+		);
+
+		match check_warp_sync_proof::<Block>(
+			0,
+			authorities(&[Ed25519Keyring::Alice]),
+			&proof.encode(),
+		).unwrap() {
+			(WarpSyncProgress::Complete { header, .. }, set) => {
+				assert_eq!(*header.number(), 8);
+				assert_eq!(set, (2, authorities(&[Ed25519Keyring::Charlie])));
+			},
+			progress => panic!("Unexpected warp sync progress: {:?}", progress),
+		}
+
+		assert_eq!(
+			proof.verify(0, authorities(&[Ed25519Keyring::Alice])).unwrap(),
+			(2, authorities(&[Ed25519Keyring::Charlie])),
+		);
+	}
+
+	#[test]
+	fn warp_sync_proof_starts_after_begin() {
+		let (blockchain, set_changes) = test_blockchain();
+
+		let proof = WarpSyncProof::<Block>::generate(
+			&*blockchain,
+			hash_of(&blockchain, 3),
+			&set_changes,
+		).unwrap();
+
+		assert_eq!(
+			proof.proofs.iter().map(|fragment| *fragment.header.number()).collect::<Vec<_>>(),
+			vec![6, 8],
+		);
+		assert!(proof.verify(1, authorities(&[Ed25519Keyring::Bob])).is_ok());
+	}
+
+	#[test]
+	fn warp_sync_proof_fails_with_wrong_authorities() {
+		let (blockchain, set_changes) = test_blockchain();
+
+		let proof = WarpSyncProof::<Block>::generate(
+			&*blockchain,
+			hash_of(&blockchain, 0),
+			&set_changes,
+		).unwrap();
+
+		assert!(proof.verify(0, authorities(&[Ed25519Keyring::Bob])).is_err());
+		assert!(proof.verify(1, authorities(&[Ed25519Keyring::Alice])).is_err());
+	}
+
+	#[test]
+	fn warp_sync_proof_fails_with_missing_change_digest() {
+		let (blockchain, set_changes) = test_blockchain();
+
+		let mut proof = WarpSyncProof::<Block>::generate(
+			&*blockchain,
+			hash_of(&blockchain, 0),
+			&set_changes,
+		).unwrap();
+
+		// an unfinished proof must end with an authority set change.
+		proof.is_finished = false;
+		assert!(proof.verify(0, authorities(&[Ed25519Keyring::Alice])).is_err());
+
+		proof.proofs.pop();
+		match check_warp_sync_proof::<Block>(
+			0,
+			authorities(&[Ed25519Keyring::Alice]),
+			&proof.encode(),
+		).unwrap() {
+			(WarpSyncProgress::Partial { last_hash, state }, _) => {
+				assert_eq!(last_hash, hash_of(&blockchain, 6));
+				assert_eq!(state, (2u64, authorities(&[Ed25519Keyring::Charlie])).encode());
+			},
+			progress => panic!("Unexpected warp sync progress: {:?}", progress),
+		}
+	}
+
+	#[test]
+	fn warp_sync_proof_fails_for_unfinalized_block() {
+		let (blockchain, set_changes) = test_blockchain();
+		let header = header(11, hash_of(&blockchain, 10), None);
+		blockchain.insert(header.hash(), header.clone(), None, None, NewBlockState::Best).unwrap();
+
+		assert!(WarpSyncProof::<Block>::generate(&*blockchain, header.hash(), &set_changes).is_err());
+	}
+
+	#[test]
+	fn warp_sync_proof_fails_with_untracked_set_changes() {
+		let (blockchain, _) = test_blockchain();
+
+		// the node only tracked the handoffs starting with the second authority set.
+		let mut set_changes = AuthoritySetChanges::tracked_from(1);
+		set_changes.append(1, 6);
+
+		assert!(WarpSyncProof::<Block>::generate(&*blockchain, hash_of(&blockchain, 0), &set_changes).is_err());
+		assert!(WarpSyncProof::<Block>::generate(&*blockchain, hash_of(&blockchain, 3), &set_changes).is_err());
+
+		let proof = WarpSyncProof::<Block>::generate(
+			&*blockchain,
+			hash_of(&blockchain, 6),
+			&set_changes,
+		).unwrap();
+
+		assert_eq!(
+			proof.proofs.iter().map(|fragment| *fragment.header.number()).collect::<Vec<_>>(),
+			vec![8],
+		);
+	}
+
+	#[test]
+	fn warp_sync_proof_fails_with_delayed_change() {
+		let blockchain = Arc::new(InMemoryBlockchain::<Block>::new());
+		let mut set_changes = AuthoritySetChanges::empty();
+		let mut parent_hash = Default::default();
+
+		for number in 0..=3 {
+			let next_authorities = if number == 3 {
+				Some(authorities(&[Ed25519Keyring::Bob]))
+			} else {
+				None
+			};
+			let header = delayed_header(number, parent_hash, next_authorities, 2);
+			let hash = header.hash();
+			let justification = if number == 3 {
+				Some(justification(&blockchain, &header, 0, &[Ed25519Keyring::Alice]))
+			} else {
+				None
+			};
+			blockchain.insert(hash, header, justification, None, NewBlockState::Final).unwrap();
+			parent_hash = hash;
+		}
+		set_changes.append(0, 3);
+
+		assert!(WarpSyncProof::<Block>::generate(&*blockchain, hash_of(&blockchain, 0), &set_changes).is_err());
+	}
+}
@@ -16,12 +16,14 @@
 
 use crate::{
 	config::{ProtocolId, Role}, block_requests, light_client_handler, finality_requests,
+	request_responses, state_requests, warp_requests,
 	debug_info, discovery::{DiscoveryBehaviour, DiscoveryConfig, DiscoveryOut},
 	protocol::{message::{self, Roles}, CustomMessageOutcome, Protocol},
 	Event, ObservedRole, DhtEvent, ExHashT,
 };
 
 use codec::Encode as _;
+use futures::channel::oneshot;
 use libp2p::NetworkBehaviour;
 use libp2p::core::{Multiaddr, PeerId, PublicKey};
 use libp2p::kad::record;
@@ -46,8 +48,14 @@ pub struct Behaviour<B: BlockT, H: ExHashT> {
 	block_requests: block_requests::BlockRequests<B>,
 	/// Finality proof request handling.
 	finality_proof_requests: finality_requests::FinalityProofRequests<B>,
+	/// Warp sync request handling.
+	warp_sync_requests: warp_requests::WarpSyncRequests<B>,
+	/// State request handling.
+	state_requests: state_requests::StateRequests<B>,
 	/// Light client request handling.
 	light_client_handler: light_client_handler::LightClientHandler<B>,
+	/// User-defined request-response protocols handling.
+	request_responses: request_responses::RequestResponses,
 
 	/// Queue of events to produce for the outside.
 	#[behaviour(ignore)]
@@ -108,7 +116,10 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 		local_public_key: PublicKey,
 		block_requests: block_requests::BlockRequests<B>,
 		finality_proof_requests: finality_requests::FinalityProofRequests<B>,
+		warp_sync_requests: warp_requests::WarpSyncRequests<B>,
+		state_requests: state_requests::StateRequests<B>,
 		light_client_handler: light_client_handler::LightClientHandler<B>,
+		request_responses: request_responses::RequestResponses,
 		disco_config: DiscoveryConfig,
 	) -> Self {
 		Behaviour {
@@ -117,7 +128,10 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 			discovery: disco_config.finish(),
 			block_requests,
 			finality_proof_requests,
+			warp_sync_requests,
+			state_requests,
 			light_client_handler,
+			request_responses,
 			events: VecDeque::new(),
 			role,
 		}
@@ -211,6 +225,19 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 	pub fn light_client_request(&mut self, r: light_client_handler::Request<B>) -> Result<(), light_client_handler::Error> {
 		self.light_client_handler.request(r)
 	}
+
+	/// Sends a request on one of the user-defined request-response protocols.
+	///
+	/// The response, or the reason of the failure, is sent on `pending_response`.
+	pub fn send_request(
+		&mut self,
+		target: &PeerId,
+		protocol: &[u8],
+		request: Vec<u8>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, request_responses::RequestFailure>>,
+	) {
+		self.request_responses.send_request(target, protocol, request, pending_response)
+	}
 }
 
 fn reported_roles_to_observed_role(local_role: &Role, remote: &PeerId, roles: Roles) -> ObservedRole {
@@ -272,6 +299,17 @@ Behaviour<B, H> {
 			CustomMessageOutcome::FinalityProofRequest { target, block_hash, request } => {
 				self.finality_proof_requests.send_request(&target, block_hash, request);
 			},
+			CustomMessageOutcome::WarpSyncRequest { target, begin } => {
+				self.warp_sync_requests.send_request(&target, begin);
+			},
+			CustomMessageOutcome::StateRequest { target, request } => {
+				self.state_requests.send_request(
+					&target,
+					request.block,
+					request.child_storage_key,
+					request.start,
+				);
+			},
 			CustomMessageOutcome::NotificationStreamOpened { remote, protocols, roles } => {
 				let role = reported_roles_to_observed_role(&self.role, &remote, roles);
 				for engine_id in protocols {
@@ -355,6 +393,44 @@ impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<finality_requests::Even
 	}
 }
 
+impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<warp_requests::Event> for Behaviour<B, H> {
+	fn inject_event(&mut self, event: warp_requests::Event) {
+		match event {
+			warp_requests::Event::Response { peer, proof } => {
+				let ev = self.substrate.on_warp_sync_response(peer, proof);
+				self.inject_event(ev);
+			}
+		}
+	}
+}
+
+impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<state_requests::Event> for Behaviour<B, H> {
+	fn inject_event(&mut self, event: state_requests::Event) {
+		match event {
+			state_requests::Event::Response { peer, proof } => {
+				let ev = self.substrate.on_state_response(peer, proof);
+				self.inject_event(ev);
+			}
+		}
+	}
+}
+
+impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<request_responses::Event> for Behaviour<B, H> {
+	fn inject_event(&mut self, event: request_responses::Event) {
+		match event {
+			request_responses::Event::AnsweredRequest { peer, protocol, build_time } => {
+				self.events.push_back(BehaviourOut::AnsweredRequest { peer, protocol, build_time });
+			}
+			request_responses::Event::RequestStarted { peer, protocol } => {
+				self.events.push_back(BehaviourOut::RequestStarted { peer, protocol });
+			}
+			request_responses::Event::RequestFinished { peer, protocol, request_duration } => {
+				self.events.push_back(BehaviourOut::RequestFinished { peer, protocol, request_duration });
+			}
+		}
+	}
+}
+
 impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<debug_info::DebugInfoEvent>
 	for Behaviour<B, H> {
 	fn inject_event(&mut self, event: debug_info::DebugInfoEvent) {
@@ -19,24 +19,64 @@
 //! Blockchain access trait
 
 use sp_blockchain::{Error, HeaderBackend, HeaderMetadata};
-use sc_client_api::{BlockBackend, ProofProvider};
-use sp_runtime::traits::{Block as BlockT, BlockIdTo};
+use sc_client_api::{BlockBackend, ProofProvider, StateImporter};
+use sp_runtime::{Justification, traits::{Block as BlockT, BlockIdTo}};
 
 /// Local client abstraction for the network.
 pub trait Client<Block: BlockT>: HeaderBackend<Block> + ProofProvider<Block> + BlockIdTo<Block, Error = Error>
-	+ BlockBackend<Block> + HeaderMetadata<Block, Error = Error> + Send + Sync
+	+ BlockBackend<Block> + HeaderMetadata<Block, Error = Error> + StateImporter<Block> + Send + Sync
 {}
 
 impl<Block: BlockT, T> Client<Block> for T
 	where
 		T: HeaderBackend<Block> + ProofProvider<Block> + BlockIdTo<Block, Error = Error>
-			+ BlockBackend<Block> + HeaderMetadata<Block, Error = Error> + Send + Sync
+			+ BlockBackend<Block> + HeaderMetadata<Block, Error = Error> + StateImporter<Block> + Send + Sync
 {}
 
 /// Finality proof provider.
 pub trait FinalityProofProvider<Block: BlockT>: Send + Sync {
 	/// Prove finality of the block.
 	fn prove_finality(&self, for_block: Block::Hash, request: &[u8]) -> Result<Option<Vec<u8>>, Error>;
+
+	/// Prove finality of the chain of authority set changes following the block `begin`, up to
+	/// a recently finalized block. Used to serve warp syncing nodes.
+	///
+	/// Returns `None` if there is nothing to prove, or if warp sync is not supported.
+	fn prove_warp_sync(&self, _begin: Block::Hash) -> Result<Option<Vec<u8>>, Error> {
+		Ok(None)
+	}
+
+	/// Verify a proof generated by a remote `prove_warp_sync`.
+	///
+	/// `state` must be `None` for the first proof, which is verified starting from genesis, and
+	/// afterwards the state returned in the previous [`WarpSyncProgress::Partial`].
+	fn verify_warp_sync_proof(
+		&self,
+		_state: Option<&[u8]>,
+		_proof: &[u8],
+	) -> Result<WarpSyncProgress<Block>, Error> {
+		Err(Error::Backend("Warp sync is not supported".into()))
+	}
+}
+
+/// Progress of a warp sync, as the result of verifying a warp sync proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarpSyncProgress<Block: BlockT> {
+	/// The proof is valid, but more proofs are needed. The next proof must be requested starting
+	/// at `last_hash`, and verified using `state`.
+	Partial {
+		/// Hash of the last block proven to be finalized.
+		last_hash: Block::Hash,
+		/// Opaque state to verify the next proof with.
+		state: Vec<u8>,
+	},
+	/// The proof is valid and complete.
+	Complete {
+		/// The most recent block proven to be finalized.
+		header: Block::Header,
+		/// The justification of `header`.
+		justification: Justification,
+	},
 }
 
 impl<Block: BlockT> FinalityProofProvider<Block> for () {
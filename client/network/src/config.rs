@@ -21,8 +21,9 @@
 //! The [`Params`] struct is the struct that must be passed in order to initialize the networking.
 //! See the documentation of [`Params`].
 
-pub use crate::chain::{Client, FinalityProofProvider};
+pub use crate::chain::{Client, FinalityProofProvider, WarpSyncProgress};
 pub use crate::on_demand_layer::{AlwaysBadChecker, OnDemand};
+pub use crate::request_responses::{IncomingRequest, ProtocolConfig as RequestResponseConfig};
 pub use libp2p::{identity, core::PublicKey, wasm_ext::ExtTransport, build_multiaddr};
 
 // Note: this re-export shouldn't be part of the public API of the crate and will be removed in
@@ -407,6 +408,8 @@ pub struct NetworkConfiguration {
 	/// List of notifications protocols that the node supports. Must also include a
 	/// `ConsensusEngineId` for backwards-compatibility.
 	pub notifications_protocols: Vec<(ConsensusEngineId, Cow<'static, [u8]>)>,
+	/// List of request-response protocols that the node supports.
+	pub request_response_protocols: Vec<RequestResponseConfig>,
 	/// Maximum allowed number of incoming connections.
 	pub in_peers: u32,
 	/// Number of outgoing connections we're trying to maintain.
@@ -428,6 +431,8 @@ pub struct NetworkConfiguration {
 	/// If true, uses the `/<chainid>/block-requests/<version>` experimental protocol rather than
 	/// the legacy substream. This option is meant to be hard-wired to `true` in the future.
 	pub use_new_block_requests_protocol: bool,
+	/// How the chain should be synchronized when the node starts.
+	pub sync_mode: SyncMode,
 }
 
 impl NetworkConfiguration {
@@ -445,6 +450,7 @@ impl NetworkConfiguration {
 			boot_nodes: Vec::new(),
 			node_key,
 			notifications_protocols: Vec::new(),
+			request_response_protocols: Vec::new(),
 			in_peers: 25,
 			out_peers: 75,
 			reserved_nodes: Vec::new(),
@@ -460,6 +466,7 @@ impl NetworkConfiguration {
 			max_parallel_downloads: 5,
 			allow_non_globals_in_dht: false,
 			use_new_block_requests_protocol: true,
+			sync_mode: SyncMode::Full,
 		}
 	}
 }
@@ -535,6 +542,23 @@ pub enum TransportConfig {
 	MemoryOnly,
 }
 
+/// The way the chain is synchronized with the network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncMode {
+	/// Download and import every block since the last known one. This is the default.
+	Full,
+	/// Download a GRANDPA warp sync proof to learn about the latest finalized block, skipping
+	/// over the intermediate blocks, then continue with a full sync from there. Full nodes
+	/// download the state at that block first, and import it as the new base of the chain.
+	Warp,
+}
+
+impl Default for SyncMode {
+	fn default() -> Self {
+		SyncMode::Full
+	}
+}
+
 /// The policy for connections to non-reserved peers.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum NonReservedPeerMode {
@@ -142,6 +142,8 @@
 //! bytes containing some data associated with this block announcement, e.g. a candidate message.
 //! - Notifications protocols that are registered using the `register_notifications_protocol`
 //! method. For example: `/paritytech/grandpa/1`. See below for more information.
+//! - Request-response protocols that are registered in the `NetworkConfiguration`, through the
+//! `request_response_protocols` field. See below for more information.
 //!
 //! ## The legacy Substrate substream
 //!
@@ -187,6 +189,11 @@
 //!
 //! Each request is performed in a new separate substream.
 //!
+//! The API of `sc-network` allows one to register user-defined request-response protocols in the
+//! network configuration. Requests are then sent with `NetworkService::request`, and incoming
+//! requests are delivered on the channel passed in the configuration of the protocol, together
+//! with a channel to send the response through. Requests and responses are opaque bytes.
+//!
 //! ## Notifications protocols
 //!
 //! A so-called notifications protocol is defined as follow:
@@ -251,10 +258,13 @@ mod finality_requests;
 mod light_client_handler;
 mod on_demand_layer;
 mod protocol;
+mod request_responses;
 mod schema;
 mod service;
+mod state_requests;
 mod transport;
 mod utils;
+mod warp_requests;
 
 pub mod config;
 pub mod error;
@@ -264,6 +274,7 @@ pub use service::{NetworkService, NetworkWorker};
 pub use protocol::PeerInfo;
 pub use protocol::event::{Event, DhtEvent, ObservedRole};
 pub use protocol::sync::SyncState;
+pub use request_responses::RequestFailure;
 pub use libp2p::{Multiaddr, PeerId};
 #[doc(inline)]
 pub use libp2p::multiaddr;
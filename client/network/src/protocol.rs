@@ -19,7 +19,10 @@
 use crate::{
 	ExHashT,
 	chain::{Client, FinalityProofProvider},
-	config::{BoxFinalityProofRequestBuilder, ProtocolId, TransactionPool, TransactionImportFuture, TransactionImport},
+	config::{
+		BoxFinalityProofRequestBuilder, ProtocolId, SyncMode, TransactionPool, TransactionImportFuture,
+		TransactionImport,
+	},
 	error,
 	utils::interval
 };
@@ -313,6 +316,8 @@ pub struct ProtocolConfig {
 	pub roles: Roles,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// How the chain should be synchronized.
+	pub sync_mode: SyncMode,
 }
 
 impl Default for ProtocolConfig {
@@ -320,6 +325,7 @@ impl Default for ProtocolConfig {
 		ProtocolConfig {
 			roles: Roles::FULL,
 			max_parallel_downloads: 5,
+			sync_mode: SyncMode::Full,
 		}
 	}
 }
@@ -385,6 +391,8 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 			finality_proof_request_builder,
 			block_announce_validator,
 			config.max_parallel_downloads,
+			config.sync_mode,
+			finality_proof_provider.clone(),
 		);
 
 		let important_peers = {
@@ -1832,6 +1840,41 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		}
 	}
 
+	/// Must be called after a [`CustomMessageOutcome::WarpSyncRequest`] has been emitted, to
+	/// notify of the response. An empty proof means that the remote has nothing to prove.
+	pub fn on_warp_sync_response(
+		&mut self,
+		who: PeerId,
+		proof: Vec<u8>,
+	) -> CustomMessageOutcome<B> {
+		trace!(target: "sync", "Warp sync response from {} ({} bytes)", who, proof.len());
+		if let Err(sync::BadPeer(id, repu)) = self.sync.on_warp_sync_response(who, proof) {
+			self.behaviour.disconnect_peer(&id);
+			self.peerset_handle.report_peer(id, repu);
+		}
+		CustomMessageOutcome::None
+	}
+
+	/// Must be called after a [`CustomMessageOutcome::StateRequest`] has been emitted, to
+	/// notify of the response. An empty proof means that the remote doesn't have the state.
+	pub fn on_state_response(
+		&mut self,
+		who: PeerId,
+		proof: Vec<u8>,
+	) -> CustomMessageOutcome<B> {
+		trace!(target: "sync", "State response from {} ({} bytes)", who, proof.len());
+		match self.sync.on_state_response(who, proof) {
+			Ok(sync::OnStateData::Import(origin, block)) =>
+				CustomMessageOutcome::BlockImport(origin, vec![block]),
+			Ok(sync::OnStateData::Continue) => CustomMessageOutcome::None,
+			Err(sync::BadPeer(id, repu)) => {
+				self.behaviour.disconnect_peer(&id);
+				self.peerset_handle.report_peer(id, repu);
+				CustomMessageOutcome::None
+			}
+		}
+	}
+
 	fn format_stats(&self) -> String {
 		let mut out = String::new();
 		for (id, stats) in &self.context_data.stats {
@@ -1917,6 +1960,18 @@ pub enum CustomMessageOutcome<B: BlockT> {
 	/// If the request times out, or the peer responds in an invalid way, the peer has to be
 	/// disconnect. This will inform the state machine that the request it has emitted is stale.
 	FinalityProofRequest { target: PeerId, block_hash: B::Hash, request: Vec<u8> },
+	/// A new warp sync request must be emitted, for a proof starting at the block `begin`.
+	/// Once you have the response, you must call `Protocol::on_warp_sync_response`.
+	/// It is the responsibility of the handler to ensure that a timeout exists.
+	/// If the request times out, or the peer responds in an invalid way, the peer has to be
+	/// disconnect. This will inform the state machine that the request it has emitted is stale.
+	WarpSyncRequest { target: PeerId, begin: B::Hash },
+	/// A new state request must be emitted.
+	/// Once you have the response, you must call `Protocol::on_state_response`.
+	/// It is the responsibility of the handler to ensure that a timeout exists.
+	/// If the request times out, or the peer responds in an invalid way, the peer has to be
+	/// disconnect. This will inform the state machine that the request it has emitted is stale.
+	StateRequest { target: PeerId, request: sync::StateRequest<B> },
 	/// Peer has a reported a new head of chain.
 	PeerNewBest(PeerId, NumberFor<B>),
 	None,
@@ -2090,6 +2145,20 @@ impl<B: BlockT, H: ExHashT> NetworkBehaviour for Protocol<B, H> {
 				)
 			}
 		}
+		if let Some((id, begin)) = self.sync.warp_sync_request() {
+			let event = CustomMessageOutcome::WarpSyncRequest {
+				target: id,
+				begin,
+			};
+			self.pending_messages.push_back(event);
+		}
+		if let Some((id, request)) = self.sync.state_request() {
+			let event = CustomMessageOutcome::StateRequest {
+				target: id,
+				request,
+			};
+			self.pending_messages.push_back(event);
+		}
 		if let Poll::Ready(Some((peer_id, result))) = self.pending_transactions.poll_next_unpin(cx) {
 			self.on_handle_extrinsic_import(peer_id, result);
 		}
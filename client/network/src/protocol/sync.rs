@@ -35,7 +35,8 @@ use sp_consensus::{BlockOrigin, BlockStatus,
 	import_queue::{IncomingBlock, BlockImportResult, BlockImportError}
 };
 use crate::{
-	config::BoxFinalityProofRequestBuilder,
+	chain::FinalityProofProvider,
+	config::{BoxFinalityProofRequestBuilder, SyncMode},
 	protocol::message::{self, generic::FinalityProofRequest, BlockAnnounce, BlockAttributes, BlockRequest, BlockResponse,
 	FinalityProofResponse, Roles},
 };
@@ -49,9 +50,15 @@ use sp_runtime::{
 	traits::{Block as BlockT, Header, NumberFor, Zero, One, CheckedSub, SaturatedConversion, Hash, HashFor}
 };
 use std::{fmt, ops::Range, collections::{HashMap, HashSet, VecDeque}, sync::Arc};
+use state::{OnStateResponse, StateSync};
+use warp::{OnWarpSyncResponse, WarpSync};
+
+pub use state::StateRequest;
 
 mod blocks;
 mod extra_requests;
+mod state;
+mod warp;
 
 /// Maximum blocks to request in a single packet.
 const MAX_BLOCKS_TO_REQUEST: usize = 128;
@@ -103,6 +110,12 @@ mod rep {
 	/// Reputation change for peers which send us a block with bad finality proof.
 	pub const BAD_FINALITY_PROOF: Rep = Rep::new(-(1 << 16), "Bad finality proof");
 
+	/// Reputation change for peers which send us a bad warp sync proof.
+	pub const BAD_WARP_PROOF: Rep = Rep::new(-(1 << 29), "Bad warp sync proof");
+
+	/// Reputation change for peers which send us a bad state proof.
+	pub const BAD_STATE_PROOF: Rep = Rep::new(-(1 << 29), "Bad state proof");
+
 	/// Reputation change when a peer sent us invlid ancestry result.
 	pub const UNKNOWN_ANCESTOR:Rep = Rep::new(-(1 << 16), "DB Error");
 }
@@ -191,6 +204,12 @@ pub struct ChainSync<B: BlockT> {
 	max_parallel_downloads: u32,
 	/// Total number of processed blocks (imported or failed).
 	processed_blocks: usize,
+	/// Warp sync in progress, if any. No blocks are requested until it is over.
+	warp_sync: Option<WarpSync<B>>,
+	/// Latest finalized header, and its justification, proven by the warp sync.
+	warp_sync_target: Option<(B::Header, Justification)>,
+	/// State sync in progress, if any. No blocks are requested until it is over.
+	state_sync: Option<StateSync<B>>,
 }
 
 /// All the data we have about a Peer that we are trying to sync with
@@ -249,7 +268,11 @@ pub enum PeerSyncState<B: BlockT> {
 	/// Downloading justification for given block hash.
 	DownloadingJustification(B::Hash),
 	/// Downloading finality proof for given block hash.
-	DownloadingFinalityProof(B::Hash)
+	DownloadingFinalityProof(B::Hash),
+	/// Downloading a warp sync proof.
+	DownloadingWarpProof,
+	/// Downloading a range of state.
+	DownloadingState,
 }
 
 impl<B: BlockT> PeerSyncState<B> {
@@ -305,6 +328,15 @@ pub enum OnBlockData<B: BlockT> {
 	Request(PeerId, BlockRequest<B>)
 }
 
+/// Result of [`ChainSync::on_state_response`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OnStateData<B: BlockT> {
+	/// The state download goes on.
+	Continue,
+	/// The whole state has been downloaded, the block it belongs to should be imported.
+	Import(BlockOrigin, IncomingBlock<B>),
+}
+
 /// Result of [`ChainSync::on_block_announce`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OnBlockAnnounce {
@@ -351,6 +383,8 @@ impl<B: BlockT> ChainSync<B> {
 		request_builder: Option<BoxFinalityProofRequestBuilder<B>>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		max_parallel_downloads: u32,
+		mode: SyncMode,
+		finality_proof_provider: Option<Arc<dyn FinalityProofProvider<B>>>,
 	) -> Self {
 		let mut required_block_attributes = BlockAttributes::HEADER | BlockAttributes::JUSTIFICATION;
 
@@ -358,6 +392,19 @@ impl<B: BlockT> ChainSync<B> {
 			required_block_attributes |= BlockAttributes::BODY
 		}
 
+		let warp_sync = match (mode, finality_proof_provider) {
+			(SyncMode::Full, _) => None,
+			(SyncMode::Warp, _) if !info.finalized_number.is_zero() => {
+				info!("⏩ Warp sync requested, but the chain is already synced. Continuing with full sync.");
+				None
+			},
+			(SyncMode::Warp, None) => {
+				warn!("Warp sync requires a finality proof provider. Continuing with full sync.");
+				None
+			},
+			(SyncMode::Warp, Some(provider)) => Some(WarpSync::new(provider, info.genesis_hash)),
+		};
+
 		ChainSync {
 			client,
 			peers: HashMap::new(),
@@ -376,6 +423,9 @@ impl<B: BlockT> ChainSync<B> {
 			block_announce_validator,
 			max_parallel_downloads,
 			processed_blocks: 0,
+			warp_sync,
+			warp_sync_target: None,
+			state_sync: None,
 		}
 	}
 
@@ -638,9 +688,163 @@ impl<B: BlockT> ChainSync<B> {
 		})
 	}
 
+	/// Get the next warp sync request, if any.
+	///
+	/// The request is sent to the available peer with the best block. Once the response has
+	/// arrived, call [`ChainSync::on_warp_sync_response`].
+	pub fn warp_sync_request(&mut self) -> Option<(PeerId, B::Hash)> {
+		let begin = self.warp_sync.as_ref()?.next_request()?;
+		let (who, peer) = self.peers.iter_mut()
+			.filter(|(_, peer)| peer.state.is_available())
+			.max_by_key(|(_, peer)| peer.best_number)?;
+
+		trace!(target: "sync", "New warp sync request for {} starting at {}", who, begin);
+		peer.state = PeerSyncState::DownloadingWarpProof;
+		let who = who.clone();
+		if let Some(warp_sync) = &mut self.warp_sync {
+			warp_sync.on_request_sent(who.clone());
+		}
+		Some((who, begin))
+	}
+
+	/// Handle a response to a warp sync request.
+	pub fn on_warp_sync_response(&mut self, who: PeerId, proof: Vec<u8>) -> Result<(), BadPeer> {
+		let warp_sync = match &mut self.warp_sync {
+			Some(warp_sync) if warp_sync.is_pending(&who) => warp_sync,
+			_ => {
+				debug!(target: "sync", "Unexpected warp sync response from {}", who);
+				return Ok(())
+			}
+		};
+
+		let peer_best_hash = match self.peers.get_mut(&who) {
+			Some(peer) => {
+				peer.state = PeerSyncState::Available;
+				peer.best_hash
+			},
+			None => {
+				debug!(target: "sync", "Warp sync response from unknown peer {}", who);
+				return Ok(())
+			}
+		};
+
+		match warp_sync.on_response(&who, &peer_best_hash, &proof) {
+			OnWarpSyncResponse::Continue => Ok(()),
+			OnWarpSyncResponse::Complete(target) => {
+				self.warp_sync = None;
+				// Full nodes continue by downloading the state at the target.
+				if let (Some((header, justification)), true) = (&target, self.role.is_full()) {
+					self.state_sync = Some(StateSync::new(
+						self.client.clone(),
+						header.clone(),
+						Some(justification.clone()),
+					));
+				}
+				self.warp_sync_target = target;
+				self.pending_requests.set_all();
+				Ok(())
+			},
+			OnWarpSyncResponse::BadProof => Err(BadPeer(who, rep::BAD_WARP_PROOF)),
+		}
+	}
+
+	/// Get the next state request, if any.
+	///
+	/// The request is sent to an available peer with a best block at least as high as the block
+	/// we download the state of. Once the response has arrived, call
+	/// [`ChainSync::on_state_response`].
+	pub fn state_request(&mut self) -> Option<(PeerId, StateRequest<B>)> {
+		let state_sync = self.state_sync.as_mut()?;
+		let request = state_sync.next_request()?;
+		let target_number = state_sync.target_number();
+		let (who, peer) = self.peers.iter_mut()
+			.filter(|(who, peer)| peer.state.is_available()
+				&& peer.best_number >= target_number
+				&& state_sync.is_available(who)
+			)
+			.max_by_key(|(_, peer)| peer.best_number)?;
+
+		trace!(target: "sync", "New state request for {}: {:?}", who, request);
+		peer.state = PeerSyncState::DownloadingState;
+		state_sync.on_request_sent(who.clone());
+		Some((who.clone(), request))
+	}
+
+	/// Handle a response to a state request.
+	///
+	/// Once the whole state is downloaded, the block it belongs to must be imported. Block sync
+	/// resumes from it once [`ChainSync::on_blocks_processed`] reports it imported.
+	pub fn on_state_response(&mut self, who: PeerId, proof: Vec<u8>) -> Result<OnStateData<B>, BadPeer> {
+		let state_sync = match &mut self.state_sync {
+			Some(state_sync) if state_sync.is_pending(&who) => state_sync,
+			_ => {
+				debug!(target: "sync", "Unexpected state response from {}", who);
+				return Ok(OnStateData::Continue)
+			}
+		};
+
+		if let Some(peer) = self.peers.get_mut(&who) {
+			peer.state = PeerSyncState::Available;
+		}
+
+		match state_sync.on_response(&who, &proof) {
+			OnStateResponse::Continue => Ok(OnStateData::Continue),
+			OnStateResponse::Import(header, justification) => {
+				let hash = header.hash();
+				self.queue_blocks.insert(hash);
+				Ok(OnStateData::Import(BlockOrigin::NetworkInitialSync, IncomingBlock {
+					hash,
+					header: Some(header),
+					body: None,
+					justification,
+					origin: None,
+					allow_missing_state: true,
+					import_existing: false,
+					state_imported: true,
+				}))
+			},
+			OnStateResponse::BadProof => Err(BadPeer(who, rep::BAD_STATE_PROOF)),
+			OnStateResponse::Failed => {
+				// continue with a full sync.
+				self.state_sync = None;
+				self.pending_requests.set_all();
+				Ok(OnStateData::Continue)
+			},
+		}
+	}
+
+	/// Called once the block the state was downloaded for has been processed by the import
+	/// queue.
+	fn on_state_imported(
+		&mut self,
+		hash: B::Hash,
+		result: Result<BlockImportResult<NumberFor<B>>, BlockImportError>,
+	) {
+		self.state_sync = None;
+		self.pending_requests.set_all();
+		let number = match result {
+			Ok(BlockImportResult::ImportedUnknown(number, ..)) |
+			Ok(BlockImportResult::ImportedKnown(number)) => number,
+			Err(e) => {
+				// continue with a full sync.
+				error!(target: "sync", "💔 Error importing state at {}: {:?}", hash, e);
+				return
+			},
+		};
+
+		self.best_queued_hash = hash;
+		self.best_queued_number = number;
+		self.best_imported_number = number;
+		for peer in self.peers.values_mut() {
+			if peer.best_number >= number {
+				peer.common_number = number;
+			}
+		}
+	}
+
 	/// Get an iterator over all block requests of all peers.
 	pub fn block_requests(&mut self) -> impl Iterator<Item = (&PeerId, BlockRequest<B>)> + '_ {
-		if self.pending_requests.is_empty() {
+		if self.pending_requests.is_empty() || self.warp_sync.is_some() || self.state_sync.is_some() {
 			return Either::Left(std::iter::empty())
 		}
 		if self.queue_blocks.len() > MAX_IMPORTING_BLOCKS {
@@ -747,6 +951,7 @@ impl<B: BlockT> ChainSync<B> {
 										origin: block_data.origin,
 										allow_missing_state: true,
 										import_existing: false,
+										state_imported: false,
 									}
 								}).collect()
 						}
@@ -766,6 +971,7 @@ impl<B: BlockT> ChainSync<B> {
 									origin: Some(who.clone()),
 									allow_missing_state: true,
 									import_existing: false,
+									state_imported: false,
 								}
 							}).collect()
 						}
@@ -837,7 +1043,9 @@ impl<B: BlockT> ChainSync<B> {
 
 						| PeerSyncState::Available
 						| PeerSyncState::DownloadingJustification(..)
-						| PeerSyncState::DownloadingFinalityProof(..) => Vec::new()
+						| PeerSyncState::DownloadingFinalityProof(..)
+						| PeerSyncState::DownloadingWarpProof
+						| PeerSyncState::DownloadingState => Vec::new()
 					}
 				} else {
 					// When request.is_none() this is a block announcement. Just accept blocks.
@@ -851,6 +1059,7 @@ impl<B: BlockT> ChainSync<B> {
 							origin: Some(who.clone()),
 							allow_missing_state: true,
 							import_existing: false,
+							state_imported: false,
 						}
 					}).collect()
 				}
@@ -1007,6 +1216,13 @@ impl<B: BlockT> ChainSync<B> {
 		self.processed_blocks += results.len();
 
 		for (result, hash) in results {
+			let state_imported = self.state_sync.as_ref()
+				.map_or(false, |state_sync| state_sync.is_complete() && state_sync.target_hash() == hash);
+			if state_imported {
+				self.on_state_imported(hash, result);
+				continue;
+			}
+
 			if has_error {
 				continue;
 			}
@@ -1265,6 +1481,12 @@ impl<B: BlockT> ChainSync<B> {
 
 	/// Call when a peer has disconnected.
 	pub fn peer_disconnected(&mut self, who: &PeerId) {
+		if let Some(warp_sync) = &mut self.warp_sync {
+			warp_sync.peer_disconnected(who);
+		}
+		if let Some(state_sync) = &mut self.state_sync {
+			state_sync.peer_disconnected(who);
+		}
 		self.blocks.clear_peer_download(who);
 		self.peers.remove(who);
 		self.extra_justifications.peer_disconnected(who);
@@ -1549,11 +1771,15 @@ mod test {
 	use super::message::FromBlock;
 	use substrate_test_runtime_client::{
 		runtime::Block,
-		DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+		DefaultTestClientBuilderExt, TestClient, TestClientBuilder, TestClientBuilderExt,
 	};
 	use sp_blockchain::HeaderBackend;
 	use sc_block_builder::BlockBuilderProvider;
-	use sp_consensus::block_validation::DefaultBlockAnnounceValidator;
+	use sp_consensus::{
+		BlockImportParams, Error as ConsensusError,
+		block_validation::DefaultBlockAnnounceValidator,
+		import_queue::{CacheKeyId, Verifier, import_single_block},
+	};
 
 	#[test]
 	fn processes_empty_response_on_justification_request_for_unknown_block() {
@@ -1573,6 +1799,8 @@ mod test {
 			None,
 			block_announce_validator,
 			1,
+			SyncMode::Full,
+			None,
 		);
 
 		let (a1_hash, a1_number) = {
@@ -1632,4 +1860,349 @@ mod test {
 			})
 		);
 	}
+
+	struct UnusedVerifier;
+
+	impl Verifier<Block> for UnusedVerifier {
+		fn verify(
+			&mut self,
+			_: BlockOrigin,
+			_: <Block as BlockT>::Header,
+			_: Option<Justification>,
+			_: Option<Vec<<Block as BlockT>::Extrinsic>>,
+		) -> Result<(BlockImportParams<Block, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String> {
+			panic!("blocks with imported state are not verified")
+		}
+	}
+
+	struct TestWarpProofProvider(<Block as BlockT>::Header);
+
+	impl FinalityProofProvider<Block> for TestWarpProofProvider {
+		fn prove_finality(&self, _: <Block as BlockT>::Hash, _: &[u8]) -> Result<Option<Vec<u8>>, ClientError> {
+			Ok(None)
+		}
+
+		fn verify_warp_sync_proof(
+			&self,
+			state: Option<&[u8]>,
+			proof: &[u8],
+		) -> Result<crate::chain::WarpSyncProgress<Block>, ClientError> {
+			match (state, proof) {
+				(None, [1]) => Ok(crate::chain::WarpSyncProgress::Partial {
+					last_hash: Default::default(),
+					state: vec![1],
+				}),
+				(Some([1]), [2]) => Ok(crate::chain::WarpSyncProgress::Complete {
+					header: self.0.clone(),
+					justification: vec![2],
+				}),
+				_ => Err(ClientError::BadJustification("invalid warp sync proof".into())),
+			}
+		}
+	}
+
+	#[test]
+	fn warp_sync_requests_proofs_before_blocks() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let info = client.info();
+		let block_announce_validator = Box::new(DefaultBlockAnnounceValidator::new(client.clone()));
+		let target = client.new_block(Default::default()).unwrap().build().unwrap().block.header;
+		let provider: Arc<dyn FinalityProofProvider<Block>> =
+			Arc::new(TestWarpProofProvider(target.clone()));
+		let peer_id = PeerId::random();
+
+		let mut sync = ChainSync::new(
+			Roles::FULL,
+			client.clone(),
+			&info,
+			None,
+			block_announce_validator,
+			1,
+			SyncMode::Warp,
+			Some(provider),
+		);
+
+		sync.new_peer(peer_id.clone(), target.hash(), *target.number()).unwrap();
+
+		// no blocks are requested while warp syncing.
+		assert!(sync.warp_sync.is_some());
+		assert_eq!(sync.block_requests().count(), 0);
+		assert_eq!(sync.warp_sync_request(), Some((peer_id.clone(), info.genesis_hash)));
+
+		// only one proof is requested at a time.
+		assert_eq!(sync.warp_sync_request(), None);
+
+		// a partial proof is followed by another request, starting at its last block.
+		assert_eq!(sync.on_warp_sync_response(peer_id.clone(), vec![1]), Ok(()));
+		assert_eq!(sync.warp_sync_request(), Some((peer_id.clone(), Default::default())));
+
+		// a complete proof ends the warp sync.
+		assert_eq!(sync.on_warp_sync_response(peer_id.clone(), vec![2]), Ok(()));
+		assert!(sync.warp_sync.is_none());
+		assert_eq!(sync.warp_sync_target.take(), Some((target, vec![2])));
+		assert_eq!(sync.warp_sync_request(), None);
+	}
+
+	#[test]
+	fn bad_warp_sync_proof_is_reported() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let info = client.info();
+		let block_announce_validator = Box::new(DefaultBlockAnnounceValidator::new(client.clone()));
+		let target = client.new_block(Default::default()).unwrap().build().unwrap().block.header;
+		let provider: Arc<dyn FinalityProofProvider<Block>> =
+			Arc::new(TestWarpProofProvider(target.clone()));
+		let peer_id = PeerId::random();
+
+		let mut sync = ChainSync::new(
+			Roles::FULL,
+			client.clone(),
+			&info,
+			None,
+			block_announce_validator,
+			1,
+			SyncMode::Warp,
+			Some(provider),
+		);
+
+		sync.new_peer(peer_id.clone(), target.hash(), *target.number()).unwrap();
+		assert!(sync.warp_sync_request().is_some());
+		assert_eq!(
+			sync.on_warp_sync_response(peer_id.clone(), vec![2]),
+			Err(BadPeer(peer_id.clone(), rep::BAD_WARP_PROOF)),
+		);
+
+		// there is no other peer to ask.
+		sync.peer_disconnected(&peer_id);
+		assert!(sync.warp_sync.is_some());
+		assert_eq!(sync.warp_sync_request(), None);
+	}
+
+	#[test]
+	fn empty_warp_sync_proof_is_only_accepted_at_best_block() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let info = client.info();
+		let block_announce_validator = Box::new(DefaultBlockAnnounceValidator::new(client.clone()));
+		let target = client.new_block(Default::default()).unwrap().build().unwrap().block.header;
+		let provider: Arc<dyn FinalityProofProvider<Block>> =
+			Arc::new(TestWarpProofProvider(target.clone()));
+		let peer_id1 = PeerId::random();
+		let peer_id2 = PeerId::random();
+
+		let mut sync = ChainSync::new(
+			Roles::FULL,
+			client.clone(),
+			&info,
+			None,
+			block_announce_validator,
+			1,
+			SyncMode::Warp,
+			Some(provider),
+		);
+
+		// the first peer knows blocks after genesis, so it must prove their finality.
+		sync.new_peer(peer_id1.clone(), target.hash(), *target.number()).unwrap();
+		assert_eq!(sync.warp_sync_request(), Some((peer_id1.clone(), info.genesis_hash)));
+		assert_eq!(
+			sync.on_warp_sync_response(peer_id1.clone(), Vec::new()),
+			Err(BadPeer(peer_id1.clone(), rep::BAD_WARP_PROOF)),
+		);
+		sync.peer_disconnected(&peer_id1);
+		assert!(sync.warp_sync.is_some());
+
+		// the second peer only knows the genesis block.
+		sync.new_peer(peer_id2.clone(), info.genesis_hash, 0).unwrap();
+		assert_eq!(sync.warp_sync_request(), Some((peer_id2.clone(), info.genesis_hash)));
+		assert_eq!(sync.on_warp_sync_response(peer_id2.clone(), Vec::new()), Ok(()));
+		assert!(sync.warp_sync.is_none());
+		assert!(sync.warp_sync_target.is_none());
+	}
+
+	/// Serves all the state requests of `sync` from `remote`, returning the block to import.
+	fn serve_state(
+		sync: &mut ChainSync<Block>,
+		remote: &TestClient,
+	) -> Option<(BlockOrigin, IncomingBlock<Block>)> {
+		use codec::Encode;
+		use sc_client_api::ProofProvider;
+		use sp_core::storage::{ChildInfo, ChildType, PrefixedStorageKey};
+
+		let mut import = None;
+		while let Some((who, request)) = sync.state_request() {
+			let child_info = request.child_storage_key.as_ref().map(|key| {
+				match ChildType::from_prefixed_key(PrefixedStorageKey::new_ref(key)) {
+					Some((ChildType::ParentKeyId, storage_key)) => ChildInfo::new_default(storage_key),
+					None => panic!("only default child tries are requested"),
+				}
+			});
+			let (proof, _) = remote.read_proof_collection(
+				&BlockId::Hash(request.block),
+				child_info.as_ref(),
+				request.start.as_ref().map(|k| &k[..]),
+				0,
+			).unwrap();
+			match sync.on_state_response(who, proof.encode()).unwrap() {
+				OnStateData::Continue => {},
+				OnStateData::Import(origin, block) => import = Some((origin, block)),
+			}
+		}
+		import
+	}
+
+	#[test]
+	fn state_sync_imports_warp_sync_target() {
+		use sc_client_api::StorageProvider;
+		use sp_core::storage::{StorageKey, well_known_keys};
+		use substrate_test_runtime_client::ClientBlockImportExt;
+
+		let mut remote = Arc::new(TestClientBuilder::new().build());
+		let block = remote.new_block(Default::default()).unwrap().build().unwrap().block;
+		let target = block.header.clone();
+		remote.import(BlockOrigin::Own, block).unwrap();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let info = client.info();
+		let block_announce_validator = Box::new(DefaultBlockAnnounceValidator::new(client.clone()));
+		let provider: Arc<dyn FinalityProofProvider<Block>> =
+			Arc::new(TestWarpProofProvider(target.clone()));
+		let peer_id = PeerId::random();
+		let behind_peer_id = PeerId::random();
+
+		let mut sync = ChainSync::new(
+			Roles::FULL,
+			client.clone(),
+			&info,
+			None,
+			block_announce_validator,
+			1,
+			SyncMode::Warp,
+			Some(provider),
+		);
+
+		sync.new_peer(peer_id.clone(), target.hash(), *target.number()).unwrap();
+		sync.new_peer(behind_peer_id.clone(), info.genesis_hash, 0).unwrap();
+		assert!(sync.warp_sync_request().is_some());
+		assert_eq!(sync.on_warp_sync_response(peer_id.clone(), vec![1]), Ok(()));
+		assert!(sync.warp_sync_request().is_some());
+		assert_eq!(sync.on_warp_sync_response(peer_id.clone(), vec![2]), Ok(()));
+
+		// the state is requested from the peer which has the target, before any block.
+		assert!(sync.state_sync.is_some());
+		assert_eq!(sync.block_requests().count(), 0);
+		let (who, request) = sync.state_request().unwrap();
+		assert_eq!(who, peer_id);
+		assert_eq!(request, StateRequest { block: target.hash(), child_storage_key: None, start: None });
+		assert_eq!(sync.state_request(), None);
+
+		// a peer which doesn't have the state is not asked again.
+		assert_eq!(sync.on_state_response(peer_id.clone(), Vec::new()), Ok(OnStateData::Continue));
+		assert_eq!(sync.state_request(), None);
+		sync.peer_disconnected(&peer_id);
+		sync.new_peer(peer_id.clone(), target.hash(), *target.number()).unwrap();
+
+		// serve the state in small chunks.
+		let import = serve_state(&mut sync, &remote);
+
+		// block sync only resumes once the block is imported.
+		let (origin, block) = import.unwrap();
+		assert_eq!(origin, BlockOrigin::NetworkInitialSync);
+		assert!(block.state_imported);
+		assert!(sync.state_sync.is_some());
+		assert_eq!(sync.block_requests().count(), 0);
+		let hash = block.hash;
+		let result = import_single_block(&mut &*client, origin, block, &mut UnusedVerifier);
+		assert!(matches!(result, Ok(BlockImportResult::ImportedUnknown(1, _, None))));
+		assert_eq!(sync.on_blocks_processed(1, 1, vec![(result, hash)]).count(), 0);
+
+		assert!(sync.state_sync.is_none());
+		assert_eq!(sync.best_queued_number, 1);
+		let info = client.info();
+		assert_eq!(info.best_hash, target.hash());
+		assert_eq!(info.finalized_hash, target.hash());
+		let code = StorageKey(well_known_keys::CODE.to_vec());
+		assert_eq!(
+			client.storage(&BlockId::Hash(target.hash()), &code).unwrap(),
+			remote.storage(&BlockId::Hash(target.hash()), &code).unwrap(),
+		);
+		let all_keys = StorageKey(Vec::new());
+		assert_eq!(
+			client.storage_pairs(&BlockId::Hash(target.hash()), &all_keys).unwrap(),
+			remote.storage_pairs(&BlockId::Hash(target.hash()), &all_keys).unwrap(),
+		);
+	}
+
+	#[test]
+	fn failed_state_import_falls_back_to_full_sync() {
+		use substrate_test_runtime_client::ClientBlockImportExt;
+
+		let mut remote = Arc::new(TestClientBuilder::new().build());
+		let block = remote.new_block(Default::default()).unwrap().build().unwrap().block;
+		let target = block.header.clone();
+		remote.import(BlockOrigin::Own, block).unwrap();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let info = client.info();
+		let block_announce_validator = Box::new(DefaultBlockAnnounceValidator::new(client.clone()));
+		let provider: Arc<dyn FinalityProofProvider<Block>> =
+			Arc::new(TestWarpProofProvider(target.clone()));
+		let peer_id = PeerId::random();
+
+		let mut sync = ChainSync::new(
+			Roles::FULL,
+			client.clone(),
+			&info,
+			None,
+			block_announce_validator,
+			1,
+			SyncMode::Warp,
+			Some(provider),
+		);
+
+		sync.new_peer(peer_id.clone(), target.hash(), *target.number()).unwrap();
+		assert!(sync.warp_sync_request().is_some());
+		assert_eq!(sync.on_warp_sync_response(peer_id.clone(), vec![1]), Ok(()));
+		assert!(sync.warp_sync_request().is_some());
+		assert_eq!(sync.on_warp_sync_response(peer_id.clone(), vec![2]), Ok(()));
+		let (_, block) = serve_state(&mut sync, &remote).unwrap();
+
+		let results = vec![(Err(BlockImportError::Other(ConsensusError::InvalidAuthoritiesSet)), block.hash)];
+		assert_eq!(sync.on_blocks_processed(1, 1, results).count(), 0);
+		assert!(sync.state_sync.is_none());
+		assert_eq!(sync.best_queued_number, 0);
+		assert!(sync.block_requests().count() > 0);
+	}
+
+	#[test]
+	fn bad_state_proof_is_reported() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let info = client.info();
+		let block_announce_validator = Box::new(DefaultBlockAnnounceValidator::new(client.clone()));
+		let target = client.new_block(Default::default()).unwrap().build().unwrap().block.header;
+		let provider: Arc<dyn FinalityProofProvider<Block>> =
+			Arc::new(TestWarpProofProvider(target.clone()));
+		let peer_id = PeerId::random();
+
+		let mut sync = ChainSync::new(
+			Roles::FULL,
+			client.clone(),
+			&info,
+			None,
+			block_announce_validator,
+			1,
+			SyncMode::Warp,
+			Some(provider),
+		);
+
+		sync.new_peer(peer_id.clone(), target.hash(), *target.number()).unwrap();
+		assert!(sync.warp_sync_request().is_some());
+		assert_eq!(sync.on_warp_sync_response(peer_id.clone(), vec![1]), Ok(()));
+		assert!(sync.warp_sync_request().is_some());
+		assert_eq!(sync.on_warp_sync_response(peer_id.clone(), vec![2]), Ok(()));
+
+		assert!(sync.state_request().is_some());
+		assert_eq!(
+			sync.on_state_response(peer_id.clone(), vec![1, 2, 3]),
+			Err(BadPeer(peer_id.clone(), rep::BAD_STATE_PROOF)),
+		);
+		assert!(sync.state_sync.is_some());
+	}
 }
@@ -0,0 +1,268 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! State sync support.
+//!
+//! The state of a finalized block is downloaded range after range, first the top-level trie
+//! and then every child trie it references. Each range is checked against the state root of the
+//! block header, and the trie nodes it was read from are written to the database right away.
+//! Once everything is downloaded, the block is imported as the new base of the chain.
+
+use crate::chain::Client;
+use codec::Decode;
+use libp2p::PeerId;
+use log::{debug, error, info};
+use sc_client_api::StorageProof;
+use sp_core::storage::{ChildInfo, ChildType, PrefixedStorageKey, well_known_keys};
+use sp_runtime::{Justification, traits::{Block as BlockT, Header}};
+use std::{collections::{HashMap, VecDeque}, sync::Arc, time::Duration};
+use wasm_timer::Instant;
+
+/// Time to wait before asking a peer which didn't have the state again.
+const STATE_RETRY_WAIT: Duration = Duration::from_secs(10);
+
+/// A state request to send to a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateRequest<B: BlockT> {
+	/// Block to download the state of.
+	pub block: B::Hash,
+	/// Prefixed storage key of the child trie to read, or `None` for the top-level trie.
+	pub child_storage_key: Option<Vec<u8>>,
+	/// Last key received, to start the range after.
+	pub start: Option<Vec<u8>>,
+}
+
+/// Result of [`StateSync::on_response`].
+#[derive(Debug)]
+pub enum OnStateResponse<B: BlockT> {
+	/// More state is needed. The next request can be made.
+	Continue,
+	/// The whole state has been downloaded and written to the database. The block must be
+	/// imported.
+	Import(B::Header, Option<Justification>),
+	/// The proof was invalid.
+	BadProof,
+	/// The state can't be imported.
+	Failed,
+}
+
+/// State sync state machine.
+pub struct StateSync<B: BlockT> {
+	/// How to check state proofs.
+	client: Arc<dyn Client<B>>,
+	/// Header of the block we download the state of.
+	target_header: B::Header,
+	/// Justification of the block we download the state of.
+	target_justification: Option<Justification>,
+	/// Prefixed storage key of the child trie being downloaded, or `None` for the top-level trie.
+	current_child: Option<Vec<u8>>,
+	/// Last key received from the trie being downloaded.
+	last_key: Option<Vec<u8>>,
+	/// Prefixed storage keys of the child tries left to download.
+	pending_children: VecDeque<Vec<u8>>,
+	/// Number of key-value pairs downloaded so far.
+	imported_keys: u64,
+	/// Peer we are currently downloading state from, if any.
+	pending: Option<PeerId>,
+	/// Peers which didn't have the state we need, and when they told us so.
+	unavailable: HashMap<PeerId, Instant>,
+	/// The whole state has been downloaded, and the block is being imported.
+	complete: bool,
+}
+
+impl<B: BlockT> StateSync<B> {
+	/// Create a new instance, downloading the state of the given finalized block.
+	pub fn new(
+		client: Arc<dyn Client<B>>,
+		target_header: B::Header,
+		target_justification: Option<Justification>,
+	) -> Self {
+		StateSync {
+			client,
+			target_header,
+			target_justification,
+			current_child: None,
+			last_key: None,
+			pending_children: VecDeque::new(),
+			imported_keys: 0,
+			pending: None,
+			unavailable: HashMap::new(),
+			complete: false,
+		}
+	}
+
+	/// Returns the next state request, unless a request is already pending or the whole state
+	/// has been downloaded.
+	pub fn next_request(&self) -> Option<StateRequest<B>> {
+		if self.pending.is_some() || self.complete {
+			return None
+		}
+		Some(StateRequest {
+			block: self.target_header.hash(),
+			child_storage_key: self.current_child.clone(),
+			start: self.last_key.clone(),
+		})
+	}
+
+	/// Returns `true` if the given peer can be asked for state. Peers which didn't have the state
+	/// are asked again after a while, in case they were still importing the block.
+	pub fn is_available(&self, who: &PeerId) -> bool {
+		self.unavailable.get(who).map_or(true, |since| since.elapsed() >= STATE_RETRY_WAIT)
+	}
+
+	/// Returns the hash of the block we download the state of.
+	pub fn target_hash(&self) -> B::Hash {
+		self.target_header.hash()
+	}
+
+	/// Returns `true` once the whole state has been downloaded.
+	pub fn is_complete(&self) -> bool {
+		self.complete
+	}
+
+	/// Returns the number of the block we download the state of.
+	pub fn target_number(&self) -> <B::Header as Header>::Number {
+		*self.target_header.number()
+	}
+
+	/// Note that a request has been sent to the given peer.
+	pub fn on_request_sent(&mut self, who: PeerId) {
+		self.pending = Some(who);
+	}
+
+	/// Returns `true` if we are waiting for state from the given peer.
+	pub fn is_pending(&self, who: &PeerId) -> bool {
+		self.pending.as_ref() == Some(who)
+	}
+
+	/// Handle a disconnected peer.
+	pub fn peer_disconnected(&mut self, who: &PeerId) {
+		if self.is_pending(who) {
+			self.pending = None;
+		}
+		self.unavailable.remove(who);
+	}
+
+	/// Handle a state proof from the given peer.
+	pub fn on_response(&mut self, who: &PeerId, proof: &[u8]) -> OnStateResponse<B> {
+		self.pending = None;
+
+		if proof.is_empty() {
+			debug!(target: "sync", "{} doesn't have the state at {}", who, self.target_header.hash());
+			self.unavailable.insert(who.clone(), Instant::now());
+			return OnStateResponse::Continue;
+		}
+
+		let proof = match StorageProof::decode(&mut &proof[..]) {
+			Ok(proof) => proof,
+			Err(e) => {
+				debug!(target: "sync", "Failed to decode state proof from {}: {:?}", who, e);
+				return OnStateResponse::BadProof;
+			},
+		};
+		let child_info = match &self.current_child {
+			Some(prefixed_key) => match ChildType::from_prefixed_key(PrefixedStorageKey::new_ref(prefixed_key)) {
+				Some((ChildType::ParentKeyId, storage_key)) => Some(ChildInfo::new_default(storage_key)),
+				None => None,
+			},
+			None => None,
+		};
+		let checked = match self.client.verify_range_proof(
+			*self.target_header.state_root(),
+			proof,
+			child_info.as_ref(),
+			self.last_key.as_ref().map(|k| &k[..]),
+		) {
+			Ok(result) => result,
+			Err(e) => {
+				debug!(target: "sync", "Bad state proof from {}: {:?}", who, e);
+				return OnStateResponse::BadProof;
+			},
+		};
+
+		self.unavailable.remove(who);
+		if let Some((key, _)) = checked.values.last() {
+			self.last_key = Some(key.clone());
+		}
+		self.imported_keys += checked.values.len() as u64;
+		if child_info.is_none() {
+			for (key, _) in checked.values {
+				if key == well_known_keys::CHANGES_TRIE_CONFIG {
+					error!(target: "sync", "💔 Changes tries are not supported when importing state");
+					return OnStateResponse::Failed;
+				}
+				if key.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+					self.pending_children.push_back(key);
+				}
+			}
+		}
+		if let Err(e) = self.client.import_state_chunk(checked.nodes) {
+			error!(target: "sync", "💔 Error importing state at {}: {:?}", self.target_header.hash(), e);
+			return OnStateResponse::Failed;
+		}
+		debug!(
+			target: "sync",
+			"Downloaded {} state entries at {} so far",
+			self.imported_keys,
+			self.target_header.hash(),
+		);
+
+		if !checked.complete {
+			return OnStateResponse::Continue;
+		}
+		self.last_key = None;
+		self.current_child = self.pending_children.pop_front();
+		if self.current_child.is_some() {
+			return OnStateResponse::Continue;
+		}
+
+		info!(
+			"📥 Downloaded state at #{} ({}), {} entries",
+			self.target_header.number(),
+			self.target_header.hash(),
+			self.imported_keys,
+		);
+		self.complete = true;
+		OnStateResponse::Import(self.target_header.clone(), self.target_justification.take())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use substrate_test_runtime_client::{
+		runtime::Block,
+		DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+	};
+
+	#[test]
+	fn unavailable_peers_are_asked_again_later() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let target = client.header(&sp_runtime::generic::BlockId::Number(0)).unwrap().unwrap();
+		let mut state_sync = StateSync::<Block>::new(client, target, None);
+		let peer_id = PeerId::random();
+
+		assert!(state_sync.next_request().is_some());
+		state_sync.on_request_sent(peer_id.clone());
+		assert!(matches!(state_sync.on_response(&peer_id, &[]), OnStateResponse::Continue));
+		assert!(!state_sync.is_available(&peer_id));
+
+		state_sync.unavailable.insert(peer_id.clone(), Instant::now() - STATE_RETRY_WAIT);
+		assert!(state_sync.is_available(&peer_id));
+	}
+}
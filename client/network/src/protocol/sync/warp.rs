@@ -0,0 +1,141 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Warp sync support.
+//!
+//! Warp proofs are requested one after the other, each one starting at the last block of the
+//! previous one, until a peer returns a complete proof. Proofs are checked by the
+//! `FinalityProofProvider`, which carries the opaque verification state between them.
+
+use crate::chain::{FinalityProofProvider, WarpSyncProgress};
+use libp2p::PeerId;
+use log::{debug, info};
+use sp_runtime::{Justification, traits::{Block as BlockT, Header}};
+use std::sync::Arc;
+
+/// Result of [`WarpSync::on_response`].
+#[derive(Debug)]
+pub enum OnWarpSyncResponse<B: BlockT> {
+	/// More proofs are needed. The next request can be made.
+	Continue,
+	/// Warp sync is over. Contains the latest finalized header proven by the remote, if any, and
+	/// its justification.
+	Complete(Option<(B::Header, Justification)>),
+	/// The proof was invalid.
+	BadProof,
+}
+
+/// Warp sync state machine.
+pub struct WarpSync<B: BlockT> {
+	/// How to check warp sync proofs.
+	provider: Arc<dyn FinalityProofProvider<B>>,
+	/// Hash of the block to start the next proof at.
+	begin: B::Hash,
+	/// Verification state returned after checking the previous proof, if any.
+	state: Option<Vec<u8>>,
+	/// Peer we are currently downloading a proof from, if any.
+	pending: Option<PeerId>,
+}
+
+impl<B: BlockT> WarpSync<B> {
+	/// Create a new instance, starting at the genesis block.
+	pub fn new(provider: Arc<dyn FinalityProofProvider<B>>, genesis_hash: B::Hash) -> Self {
+		WarpSync {
+			provider,
+			begin: genesis_hash,
+			state: None,
+			pending: None,
+		}
+	}
+
+	/// Returns the hash of the block to request the next proof from, unless a request is already
+	/// pending.
+	pub fn next_request(&self) -> Option<B::Hash> {
+		if self.pending.is_some() {
+			None
+		} else {
+			Some(self.begin)
+		}
+	}
+
+	/// Note that a request has been sent to the given peer.
+	pub fn on_request_sent(&mut self, who: PeerId) {
+		self.pending = Some(who);
+	}
+
+	/// Returns `true` if we are waiting for a proof from the given peer.
+	pub fn is_pending(&self, who: &PeerId) -> bool {
+		self.pending.as_ref() == Some(who)
+	}
+
+	/// Handle a disconnected peer.
+	pub fn peer_disconnected(&mut self, who: &PeerId) {
+		if self.is_pending(who) {
+			self.pending = None;
+		}
+	}
+
+	/// Handle a warp sync proof from the given peer, whose best block is `peer_best_hash`.
+	pub fn on_response(
+		&mut self,
+		who: &PeerId,
+		peer_best_hash: &B::Hash,
+		proof: &[u8],
+	) -> OnWarpSyncResponse<B> {
+		self.pending = None;
+
+		if proof.is_empty() {
+			// The peer claims it hasn't finalized anything after `begin`, which can only be
+			// true if `begin` is its best block, and thus already final.
+			if *peer_best_hash != self.begin {
+				debug!(
+					target: "sync",
+					"Empty warp sync proof from {} starting at {}, but its best block is {}",
+					who,
+					self.begin,
+					peer_best_hash,
+				);
+				return OnWarpSyncResponse::BadProof;
+			}
+
+			debug!(target: "sync", "Empty warp sync proof from {} starting at {}", who, self.begin);
+			return OnWarpSyncResponse::Complete(None);
+		}
+
+		match self.provider.verify_warp_sync_proof(self.state.as_ref().map(|s| &s[..]), proof) {
+			Ok(WarpSyncProgress::Partial { last_hash, state }) => {
+				debug!(target: "sync", "Partial warp sync proof from {}, continuing at {}", who, last_hash);
+				self.begin = last_hash;
+				self.state = Some(state);
+				OnWarpSyncResponse::Continue
+			},
+			Ok(WarpSyncProgress::Complete { header, justification }) => {
+				info!(
+					"⏩ Warp sync reached finalized block #{} ({})",
+					header.number(),
+					header.hash(),
+				);
+				OnWarpSyncResponse::Complete(Some((header, justification)))
+			},
+			Err(e) => {
+				debug!(target: "sync", "Bad warp sync proof from {}: {:?}", who, e);
+				OnWarpSyncResponse::BadProof
+			},
+		}
+	}
+}
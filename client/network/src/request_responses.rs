@@ -0,0 +1,584 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+//
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `NetworkBehaviour` implementation which handles user-defined request-response protocols.
+//!
+//! Protocols are registered through the network configuration (cf. [`ProtocolConfig`]). Requests
+//! and responses are opaque bytes, and every request is sent on a separate connection substream
+//! which gets closed after the response has been sent back.
+//!
+//! Incoming requests are delivered through the `inbound_queue` of the protocol, together with a
+//! channel to send the response through. Protocols without an `inbound_queue` are not advertised
+//! to remotes, and can only be used to send requests.
+//!
+//! As with the other request-response protocols, if a remote doesn't support the protocol or
+//! answers improperly, the connection to that remote is closed.
+
+use bytes::Bytes;
+use futures::{
+	channel::{mpsc, oneshot},
+	future::{self, BoxFuture, Either},
+	prelude::*,
+	stream::FuturesUnordered,
+};
+use futures_timer::Delay;
+use libp2p::{
+	core::{
+		ConnectedPoint,
+		Multiaddr,
+		PeerId,
+		connection::ConnectionId,
+		upgrade::{InboundUpgrade, OutboundUpgrade, ReadOneError, UpgradeInfo},
+		upgrade::{read_one, write_one}
+	},
+	swarm::{
+		NegotiatedSubstream,
+		NetworkBehaviour,
+		NetworkBehaviourAction,
+		NotifyHandler,
+		OneShotHandler,
+		OneShotHandlerConfig,
+		PollParameters,
+		SubstreamProtocol
+	}
+};
+use std::{
+	borrow::Cow,
+	collections::{HashMap, VecDeque},
+	io,
+	iter,
+	pin::Pin,
+	sync::Arc,
+	time::Duration,
+	task::{Context, Poll}
+};
+use wasm_timer::Instant;
+
+/// Configuration of a request-response protocol.
+#[derive(Debug, Clone)]
+pub struct ProtocolConfig {
+	/// Name of the protocol on the wire. Should be something like `/foo/bar/1`.
+	pub name: Cow<'static, [u8]>,
+
+	/// Maximum allowed size, in bytes, of a request.
+	///
+	/// Incoming requests larger than this are rejected, and the connection is closed.
+	pub max_request_size: usize,
+
+	/// Maximum allowed size, in bytes, of a response.
+	///
+	/// Responses larger than this are rejected, and the connection is closed.
+	pub max_response_size: usize,
+
+	/// Duration after which emitted requests are considered timed out, and duration we leave
+	/// ourselves to answer incoming requests.
+	pub request_timeout: Duration,
+
+	/// Channel on which incoming requests are delivered.
+	///
+	/// If `None`, then the protocol is not advertised to remotes and incoming requests are
+	/// refused. Requests are dropped if the channel is full.
+	pub inbound_queue: Option<mpsc::Sender<IncomingRequest>>,
+}
+
+/// A single request received by a peer on a request-response protocol.
+#[derive(Debug)]
+pub struct IncomingRequest {
+	/// Who sent the request.
+	pub peer: PeerId,
+
+	/// Request sent by the remote. Will always be smaller than
+	/// [`ProtocolConfig::max_request_size`].
+	pub payload: Vec<u8>,
+
+	/// Channel to send back the response to.
+	///
+	/// Dropping the sender closes the substream without answering, which the remote will treat
+	/// as a failure of the request. The response is also discarded if it is sent after
+	/// [`ProtocolConfig::request_timeout`] or is larger than
+	/// [`ProtocolConfig::max_response_size`].
+	pub pending_response: oneshot::Sender<Vec<u8>>,
+}
+
+/// Error in a request sent through [`crate::NetworkService::request`].
+#[derive(Debug, derive_more::Display)]
+pub enum RequestFailure {
+	/// The protocol hasn't been registered in the network configuration.
+	#[display(fmt = "Unknown protocol")]
+	UnknownProtocol,
+	/// We are not connected to the target node.
+	#[display(fmt = "Not connected to the target node")]
+	NotConnected,
+	/// The connection the request was sent on has been closed before a response arrived.
+	#[display(fmt = "Connection closed before a response arrived")]
+	ConnectionClosed,
+	/// The remote hasn't answered in time.
+	#[display(fmt = "Request timed out")]
+	Timeout,
+	/// The network worker has shut down before the request could be completed.
+	#[display(fmt = "Request cancelled")]
+	Cancelled,
+}
+
+impl std::error::Error for RequestFailure {}
+
+/// Event generated by the request-responses behaviour.
+#[derive(Debug)]
+pub enum Event {
+	/// A request came and we have successfully answered it.
+	AnsweredRequest {
+		/// Peer which has emitted the request.
+		peer: PeerId,
+		/// Name of the protocol the request was sent on.
+		protocol: Vec<u8>,
+		/// Time elapsed between when we received the request and when we sent back the response.
+		build_time: Duration,
+	},
+
+	/// A request has been sent to a remote.
+	RequestStarted {
+		/// Peer the request has been sent to.
+		peer: PeerId,
+		/// Name of the protocol the request was sent on.
+		protocol: Vec<u8>,
+	},
+
+	/// A previously-started request has finished, successfully or not.
+	RequestFinished {
+		/// Peer the request was sent to.
+		peer: PeerId,
+		/// Name of the protocol the request was sent on.
+		protocol: Vec<u8>,
+		/// Time elapsed between the start of the request and its end.
+		request_duration: Duration,
+	},
+}
+
+/// The user-defined request-response protocols handling behaviour.
+pub struct RequestResponses {
+	/// Registered protocols, indexed by name.
+	protocols: HashMap<Bytes, ProtocolConfig>,
+	/// Maximum request size of each protocol we accept incoming requests on. Shared with all the
+	/// handlers.
+	inbound_protocols: Arc<HashMap<Bytes, usize>>,
+	/// Open connections with each peer.
+	peers: HashMap<PeerId, Vec<ConnectionId>>,
+	/// Requests we have emitted and are waiting a response for, indexed by request id.
+	ongoing_requests: HashMap<u64, OngoingRequest>,
+	/// Id to assign to the next request.
+	next_request_id: u64,
+	/// Futures sending back the responses to incoming requests.
+	outgoing: FuturesUnordered<BoxFuture<'static, Option<(PeerId, Bytes, Duration)>>>,
+	/// Events to return as soon as possible from `poll`.
+	pending_events: VecDeque<NetworkBehaviourAction<OutboundProtocol, Event>>,
+}
+
+#[derive(Debug)]
+struct OngoingRequest {
+	peer: PeerId,
+	/// Connection the request has been sent on.
+	connection: ConnectionId,
+	protocol: Bytes,
+	/// `Instant` when the request has been emitted. Used for diagnostic purposes.
+	emitted: Instant,
+	timeout: Delay,
+	pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+}
+
+impl RequestResponses {
+	/// Initializes the behaviour with the given protocols.
+	pub fn new(protocols: impl IntoIterator<Item = ProtocolConfig>) -> Self {
+		let mut by_name = HashMap::new();
+		for protocol in protocols {
+			let name = Bytes::from(protocol.name.clone().into_owned());
+			if by_name.insert(name, protocol).is_some() {
+				log::warn!(
+					target: "sub-libp2p",
+					"Request-response protocol registered multiple times; keeping the last one"
+				);
+			}
+		}
+
+		let inbound_protocols = by_name.iter()
+			.filter(|(_, config)| config.inbound_queue.is_some())
+			.map(|(name, config)| (name.clone(), config.max_request_size))
+			.collect();
+
+		RequestResponses {
+			protocols: by_name,
+			inbound_protocols: Arc::new(inbound_protocols),
+			peers: HashMap::new(),
+			ongoing_requests: HashMap::new(),
+			next_request_id: 0,
+			outgoing: FuturesUnordered::new(),
+			pending_events: VecDeque::new(),
+		}
+	}
+
+	/// Sends a request to `target` on the given protocol.
+	///
+	/// The response, or the reason of the failure, is sent on `pending_response`. If the remote
+	/// doesn't support the protocol or answers improperly, the target will be disconnected.
+	pub fn send_request(
+		&mut self,
+		target: &PeerId,
+		protocol: &[u8],
+		request: Vec<u8>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	) {
+		let (protocol, config) = match self.protocols.get_key_value(protocol) {
+			Some(entry) => entry,
+			None => {
+				let _ = pending_response.send(Err(RequestFailure::UnknownProtocol));
+				return;
+			}
+		};
+
+		let connection = match self.peers.get(target).and_then(|connections| connections.first()) {
+			Some(connection) => *connection,
+			None => {
+				let _ = pending_response.send(Err(RequestFailure::NotConnected));
+				return;
+			}
+		};
+
+		let request_id = self.next_request_id;
+		self.next_request_id += 1;
+		self.ongoing_requests.insert(request_id, OngoingRequest {
+			peer: target.clone(),
+			connection,
+			protocol: protocol.clone(),
+			emitted: Instant::now(),
+			timeout: Delay::new(config.request_timeout),
+			pending_response,
+		});
+
+		log::trace!(
+			target: "sub-libp2p",
+			"Enqueueing {} request to {:?}",
+			String::from_utf8_lossy(protocol),
+			target
+		);
+		self.pending_events.push_back(NetworkBehaviourAction::NotifyHandler {
+			peer_id: target.clone(),
+			handler: NotifyHandler::One(connection),
+			event: OutboundProtocol {
+				request,
+				request_id,
+				max_response_size: config.max_response_size,
+				protocol: protocol.clone(),
+			},
+		});
+		self.pending_events.push_back(NetworkBehaviourAction::GenerateEvent(Event::RequestStarted {
+			peer: target.clone(),
+			protocol: protocol.to_vec(),
+		}));
+	}
+
+	/// Removes the given ongoing request, reports `result` to its emitter and generates the
+	/// corresponding `RequestFinished` event.
+	fn finish_request(&mut self, request_id: u64, result: Result<Vec<u8>, RequestFailure>) -> Option<Event> {
+		let request = self.ongoing_requests.remove(&request_id)?;
+		let _ = request.pending_response.send(result);
+		Some(Event::RequestFinished {
+			peer: request.peer,
+			protocol: request.protocol.to_vec(),
+			request_duration: request.emitted.elapsed(),
+		})
+	}
+}
+
+impl NetworkBehaviour for RequestResponses {
+	type ProtocolsHandler = OneShotHandler<InboundProtocol, OutboundProtocol, NodeEvent<NegotiatedSubstream>>;
+	type OutEvent = Event;
+
+	fn new_handler(&mut self) -> Self::ProtocolsHandler {
+		let p = InboundProtocol {
+			protocols: self.inbound_protocols.clone(),
+		};
+		let mut cfg = OneShotHandlerConfig::default();
+		if let Some(timeout) = self.protocols.values().map(|config| config.request_timeout).max() {
+			cfg.substream_timeout = timeout;
+		}
+		OneShotHandler::new(SubstreamProtocol::new(p), cfg)
+	}
+
+	fn addresses_of_peer(&mut self, _: &PeerId) -> Vec<Multiaddr> {
+		Vec::new()
+	}
+
+	fn inject_connected(&mut self, _peer: &PeerId) {
+	}
+
+	fn inject_disconnected(&mut self, _peer: &PeerId) {
+	}
+
+	fn inject_connection_established(&mut self, peer_id: &PeerId, id: &ConnectionId, _: &ConnectedPoint) {
+		self.peers.entry(peer_id.clone()).or_default().push(*id);
+	}
+
+	fn inject_connection_closed(&mut self, peer_id: &PeerId, id: &ConnectionId, _: &ConnectedPoint) {
+		if let Some(connections) = self.peers.get_mut(peer_id) {
+			connections.retain(|c| c != id);
+			if connections.is_empty() {
+				self.peers.remove(peer_id);
+			}
+		} else {
+			log::error!(
+				target: "sub-libp2p",
+				"State inconsistency: peer_id not found in list of connections"
+			);
+		}
+
+		let closed = self.ongoing_requests.iter()
+			.filter(|(_, request)| request.connection == *id)
+			.map(|(request_id, _)| *request_id)
+			.collect::<Vec<_>>();
+		for request_id in closed {
+			log::debug!(
+				target: "sub-libp2p",
+				"Connection {:?} with {} closed with ongoing request",
+				id,
+				peer_id
+			);
+			if let Some(ev) = self.finish_request(request_id, Err(RequestFailure::ConnectionClosed)) {
+				self.pending_events.push_back(NetworkBehaviourAction::GenerateEvent(ev));
+			}
+		}
+	}
+
+	fn inject_event(
+		&mut self,
+		peer: PeerId,
+		_connection: ConnectionId,
+		event: NodeEvent<NegotiatedSubstream>
+	) {
+		match event {
+			NodeEvent::Request(protocol, payload, mut stream, handling_start) => {
+				let config = match self.protocols.get_mut(&protocol) {
+					Some(config) => config,
+					None => return,
+				};
+				let queue = match &mut config.inbound_queue {
+					Some(queue) => queue,
+					None => return,
+				};
+
+				let (tx, rx) = oneshot::channel();
+				let request = IncomingRequest {
+					peer: peer.clone(),
+					payload,
+					pending_response: tx,
+				};
+				if let Err(err) = queue.try_send(request) {
+					log::debug!(
+						target: "sub-libp2p",
+						"Dropping {} request from {}: {}",
+						String::from_utf8_lossy(&protocol),
+						peer,
+						err
+					);
+					return;
+				}
+
+				let request_timeout = config.request_timeout;
+				let max_response_size = config.max_response_size;
+				self.outgoing.push(async move {
+					let response = match future::select(rx, Delay::new(request_timeout)).await {
+						Either::Left((Ok(response), _)) => response,
+						Either::Left((Err(_), _)) => {
+							log::debug!(
+								target: "sub-libp2p",
+								"Request from {} dropped without a response",
+								peer
+							);
+							return None;
+						}
+						Either::Right(_) => {
+							log::debug!(
+								target: "sub-libp2p",
+								"Request from {} not answered in time",
+								peer
+							);
+							return None;
+						}
+					};
+
+					if response.len() > max_response_size {
+						log::debug!(
+							target: "sub-libp2p",
+							"Response to {} is {} bytes long, more than the limit of {}",
+							peer,
+							response.len(),
+							max_response_size
+						);
+						return None;
+					}
+
+					if let Err(e) = write_one(&mut stream, response).await {
+						log::debug!(
+							target: "sub-libp2p",
+							"Error writing response to {}: {}",
+							peer,
+							e
+						);
+						return None;
+					}
+
+					Some((peer, protocol, handling_start.elapsed()))
+				}.boxed());
+			}
+			NodeEvent::Response(request_id, response) => {
+				match self.finish_request(request_id, Ok(response)) {
+					Some(ev) => self.pending_events.push_back(NetworkBehaviourAction::GenerateEvent(ev)),
+					// Requests that timed out are removed from `self.ongoing_requests`, so this
+					// can legitimately happen.
+					None => log::trace!(
+						target: "sub-libp2p",
+						"Response from {} discarded because it concerns an obsolete request",
+						peer
+					),
+				}
+			}
+		}
+	}
+
+	fn poll(&mut self, cx: &mut Context, _: &mut impl PollParameters)
+		-> Poll<NetworkBehaviourAction<OutboundProtocol, Event>>
+	{
+		if let Some(ev) = self.pending_events.pop_front() {
+			return Poll::Ready(ev);
+		}
+
+		// Check the request timeouts.
+		let mut timed_out = None;
+		for (request_id, request) in &mut self.ongoing_requests {
+			if let Poll::Ready(_) = Pin::new(&mut request.timeout).poll(cx) {
+				log::debug!(target: "sub-libp2p", "Request timeout for {}", request.peer);
+				timed_out = Some(*request_id);
+				break;
+			}
+		}
+		if let Some(ev) = timed_out.and_then(|id| self.finish_request(id, Err(RequestFailure::Timeout))) {
+			return Poll::Ready(NetworkBehaviourAction::GenerateEvent(ev));
+		}
+
+		while let Poll::Ready(Some(answered)) = self.outgoing.poll_next_unpin(cx) {
+			if let Some((peer, protocol, build_time)) = answered {
+				let ev = Event::AnsweredRequest {
+					peer,
+					protocol: protocol.to_vec(),
+					build_time,
+				};
+				return Poll::Ready(NetworkBehaviourAction::GenerateEvent(ev));
+			}
+		}
+
+		Poll::Pending
+	}
+}
+
+/// Output type of inbound and outbound substream upgrades.
+#[derive(Debug)]
+pub enum NodeEvent<T> {
+	/// Incoming request from remote on the given protocol, substream to use for the response,
+	/// and when we started handling this request.
+	Request(Bytes, Vec<u8>, T, Instant),
+	/// Incoming response from remote to the request with the given id.
+	Response(u64, Vec<u8>),
+}
+
+/// Substream upgrade protocol.
+///
+/// Reads an incoming request on any of the protocols we accept requests on. The request will
+/// then become visible via `inject_event`, which forwards it to the `inbound_queue` of the
+/// protocol.
+#[derive(Debug, Clone)]
+pub struct InboundProtocol {
+	/// The protocols to use during upgrade negotiation, and their max. request length in bytes.
+	protocols: Arc<HashMap<Bytes, usize>>,
+}
+
+impl UpgradeInfo for InboundProtocol {
+	type Info = Bytes;
+	type InfoIter = std::vec::IntoIter<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		self.protocols.keys().cloned().collect::<Vec<_>>().into_iter()
+	}
+}
+
+impl<T> InboundUpgrade<T> for InboundProtocol
+where
+	T: AsyncRead + AsyncWrite + Unpin + Send + 'static
+{
+	type Output = NodeEvent<T>;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_inbound(self, mut s: T, protocol: Self::Info) -> Self::Future {
+		// This `Instant` will be passed around until the processing of this request is done.
+		let handling_start = Instant::now();
+
+		async move {
+			let len = self.protocols.get(&protocol).copied()
+				.ok_or_else(|| ReadOneError::Io(io::ErrorKind::InvalidInput.into()))?;
+			let vec = read_one(&mut s, len).await?;
+			Ok(NodeEvent::Request(protocol, vec, s, handling_start))
+		}.boxed()
+	}
+}
+
+/// Substream upgrade protocol.
+///
+/// Sends a request to remote and awaits the response.
+#[derive(Debug, Clone)]
+pub struct OutboundProtocol {
+	/// The request to send.
+	request: Vec<u8>,
+	/// Id of the request. Passed back through the `NodeEvent` when the response comes back.
+	request_id: u64,
+	/// The max. response length in bytes.
+	max_response_size: usize,
+	/// The protocol to use for upgrade negotiation.
+	protocol: Bytes,
+}
+
+impl UpgradeInfo for OutboundProtocol {
+	type Info = Bytes;
+	type InfoIter = iter::Once<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		iter::once(self.protocol.clone())
+	}
+}
+
+impl<T> OutboundUpgrade<T> for OutboundProtocol
+where
+	T: AsyncRead + AsyncWrite + Unpin + Send + 'static
+{
+	type Output = NodeEvent<T>;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_outbound(self, mut s: T, _: Self::Info) -> Self::Future {
+		async move {
+			write_one(&mut s, &self.request).await?;
+			let vec = read_one(&mut s, self.max_response_size).await?;
+			Ok(NodeEvent::Response(self.request_id, vec))
+		}.boxed()
+	}
+}
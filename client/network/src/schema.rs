@@ -26,4 +26,10 @@ pub mod v1 {
 	pub mod light {
 		include!(concat!(env!("OUT_DIR"), "/api.v1.light.rs"));
 	}
+	pub mod state {
+		include!(concat!(env!("OUT_DIR"), "/api.v1.state.rs"));
+	}
+	pub mod warp {
+		include!(concat!(env!("OUT_DIR"), "/api.v1.warp.rs"));
+	}
 }
@@ -37,11 +37,12 @@ use crate::{
 		NetworkState, NotConnectedPeer as NetworkStateNotConnectedPeer, Peer as NetworkStatePeer,
 	},
 	on_demand_layer::AlwaysBadChecker,
-	light_client_handler, block_requests, finality_requests,
+	light_client_handler, block_requests, finality_requests, state_requests, warp_requests,
+	request_responses::{RequestFailure, RequestResponses},
 	protocol::{self, event::Event, LegacyConnectionKillError, sync::SyncState, PeerInfo, Protocol},
 	transport, ReputationChange,
 };
-use futures::prelude::*;
+use futures::{channel::oneshot, prelude::*};
 use libp2p::{PeerId, Multiaddr};
 use libp2p::core::{ConnectedPoint, Executor, connection::{ConnectionError, PendingConnectionError}, either::EitherError};
 use libp2p::kad::record;
@@ -211,6 +212,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			protocol::ProtocolConfig {
 				roles: From::from(&params.role),
 				max_parallel_downloads: params.network_config.max_parallel_downloads,
+				sync_mode: params.network_config.sync_mode,
 			},
 			local_peer_id.clone(),
 			params.chain.clone(),
@@ -241,6 +243,14 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				let config = finality_requests::Config::new(&params.protocol_id);
 				finality_requests::FinalityProofRequests::new(config, params.finality_proof_provider.clone())
 			};
+			let warp_sync_requests = {
+				let config = warp_requests::Config::new(&params.protocol_id);
+				warp_requests::WarpSyncRequests::new(config, params.finality_proof_provider.clone())
+			};
+			let state_requests = {
+				let config = state_requests::Config::new(&params.protocol_id);
+				state_requests::StateRequests::new(config, params.chain.clone())
+			};
 			let light_client_handler = {
 				let config = light_client_handler::Config::new(&params.protocol_id);
 				light_client_handler::LightClientHandler::new(
@@ -279,7 +289,10 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				local_public,
 				block_requests,
 				finality_proof_requests,
+				warp_sync_requests,
+				state_requests,
 				light_client_handler,
+				RequestResponses::new(params.network_config.request_response_protocols.clone()),
 				discovery_config
 			);
 
@@ -541,6 +554,33 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
 		});
 	}
 
+	/// Sends a single request to `target` on one of the request-response protocols registered in
+	/// the `NetworkConfiguration::request_response_protocols` list, and waits for the response.
+	///
+	/// We must already be connected to `target`, otherwise `RequestFailure::NotConnected` is
+	/// returned. If the remote doesn't support the protocol or answers improperly, the connection
+	/// is closed.
+	pub async fn request(
+		&self,
+		target: PeerId,
+		protocol: impl Into<Cow<'static, [u8]>>,
+		request: Vec<u8>,
+	) -> Result<Vec<u8>, RequestFailure> {
+		let (tx, rx) = oneshot::channel();
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::Request {
+			target,
+			protocol: protocol.into(),
+			request,
+			pending_response: tx,
+		});
+
+		match rx.await {
+			Ok(result) => result,
+			// The `NetworkWorker` has been shut down and dropped the request.
+			Err(oneshot::Canceled) => Err(RequestFailure::Cancelled),
+		}
+	}
+
 	/// Returns a stream containing the events that happen on the network.
 	///
 	/// If this method is called multiple times, the events are duplicated.
@@ -777,6 +817,12 @@ enum ServiceToWorkerMsg<B: BlockT, H: ExHashT> {
 		engine_id: ConsensusEngineId,
 		protocol_name: Cow<'static, [u8]>,
 	},
+	Request {
+		target: PeerId,
+		protocol: Cow<'static, [u8]>,
+		request: Vec<u8>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	},
 	DisconnectPeer(PeerId),
 }
 
@@ -1104,6 +1150,8 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 					this.network_service
 						.register_notifications_protocol(engine_id, protocol_name);
 				},
+				ServiceToWorkerMsg::Request { target, protocol, request, pending_response } =>
+					this.network_service.send_request(&target, &protocol, request, pending_response),
 				ServiceToWorkerMsg::DisconnectPeer(who) =>
 					this.network_service.user_protocol_mut().disconnect_peer(&who),
 			}
@@ -1192,12 +1240,12 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 							ConnectionError::IO(_) =>
 								metrics.connections_closed_total.with_label_values(&[dir, "transport-error"]).inc(),
 							ConnectionError::Handler(NodeHandlerWrapperError::Handler(EitherError::A(EitherError::A(
-								EitherError::A(EitherError::A(EitherError::B(
-								EitherError::A(PingFailure::Timeout)))))))) =>
+								EitherError::A(EitherError::A(EitherError::A(EitherError::A(EitherError::A(
+								EitherError::B(EitherError::A(PingFailure::Timeout))))))))))) =>
 								metrics.connections_closed_total.with_label_values(&[dir, "ping-timeout"]).inc(),
 							ConnectionError::Handler(NodeHandlerWrapperError::Handler(EitherError::A(EitherError::A(
-								EitherError::A(EitherError::A(EitherError::A(
-								EitherError::B(LegacyConnectionKillError)))))))) =>
+								EitherError::A(EitherError::A(EitherError::A(EitherError::A(EitherError::A(
+								EitherError::A(EitherError::B(LegacyConnectionKillError))))))))))) =>
 								metrics.connections_closed_total.with_label_values(&[dir, "force-closed"]).inc(),
 							ConnectionError::Handler(NodeHandlerWrapperError::Handler(_)) =>
 								metrics.connections_closed_total.with_label_values(&[dir, "protocol-error"]).inc(),
@@ -16,7 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{config, Event, NetworkService, NetworkWorker};
+use crate::{config, Event, NetworkService, NetworkWorker, PeerId};
 
 use futures::prelude::*;
 use sp_runtime::traits::{Block as BlockT, Header as _};
@@ -272,3 +272,100 @@ fn notifications_state_consistent() {
 		}
 	});
 }
+
+const REQUEST_PROTOCOL: &[u8] = b"/test/echo/1";
+
+fn request_response_config(inbound_queue: Option<futures::channel::mpsc::Sender<config::IncomingRequest>>)
+	-> config::RequestResponseConfig
+{
+	config::RequestResponseConfig {
+		name: From::from(REQUEST_PROTOCOL),
+		max_request_size: 1024,
+		max_response_size: 1024,
+		request_timeout: Duration::from_secs(10),
+		inbound_queue,
+	}
+}
+
+/// Builds two connected nodes which both support the `REQUEST_PROTOCOL` request-response
+/// protocol. Only the first node answers requests, by sending back the request reversed.
+/// Returns once the second node is connected to the first one.
+fn build_nodes_request_response() -> (Arc<TestNetworkService>, Arc<TestNetworkService>) {
+	let listen_addr = config::build_multiaddr![Memory(rand::random::<u64>())];
+	let (tx, mut rx) = futures::channel::mpsc::channel::<config::IncomingRequest>(16);
+
+	let (node1, _) = build_test_full_node(config::NetworkConfiguration {
+		notifications_protocols: vec![(ENGINE_ID, From::from(&b"/foo"[..]))],
+		request_response_protocols: vec![request_response_config(Some(tx))],
+		listen_addresses: vec![listen_addr.clone()],
+		transport: config::TransportConfig::MemoryOnly,
+		.. config::NetworkConfiguration::new_local()
+	});
+
+	let (node2, mut events_stream2) = build_test_full_node(config::NetworkConfiguration {
+		notifications_protocols: vec![(ENGINE_ID, From::from(&b"/foo"[..]))],
+		request_response_protocols: vec![request_response_config(None)],
+		reserved_nodes: vec![config::MultiaddrWithPeerId {
+			multiaddr: listen_addr,
+			peer_id: node1.local_peer_id().clone(),
+		}],
+		transport: config::TransportConfig::MemoryOnly,
+		.. config::NetworkConfiguration::new_local()
+	});
+
+	async_std::task::spawn(async move {
+		while let Some(request) = rx.next().await {
+			let mut response = request.payload;
+			response.reverse();
+			let _ = request.pending_response.send(response);
+		}
+	});
+
+	async_std::task::block_on(async {
+		loop {
+			match events_stream2.next().await.unwrap() {
+				Event::NotificationStreamOpened { .. } => break,
+				_ => {}
+			}
+		}
+	});
+
+	(node1, node2)
+}
+
+#[test]
+fn request_response_answered() {
+	let (node1, node2) = build_nodes_request_response();
+
+	async_std::task::block_on(async move {
+		let response = node2
+			.request(node1.local_peer_id().clone(), REQUEST_PROTOCOL, b"hello".to_vec())
+			.await
+			.unwrap();
+		assert_eq!(response, b"olleh".to_vec());
+
+		// Requests can be sent again on the same connection.
+		let response = node2
+			.request(node1.local_peer_id().clone(), REQUEST_PROTOCOL, b"world".to_vec())
+			.await
+			.unwrap();
+		assert_eq!(response, b"dlrow".to_vec());
+	});
+}
+
+#[test]
+fn request_response_fails_on_unknown_protocol_or_peer() {
+	let (node1, node2) = build_nodes_request_response();
+
+	async_std::task::block_on(async move {
+		match node2.request(node1.local_peer_id().clone(), &b"/test/unknown/1"[..], Vec::new()).await {
+			Err(crate::RequestFailure::UnknownProtocol) => {}
+			other => panic!("unexpected result: {:?}", other),
+		}
+
+		match node2.request(PeerId::random(), REQUEST_PROTOCOL, Vec::new()).await {
+			Err(crate::RequestFailure::NotConnected) => {}
+			other => panic!("unexpected result: {:?}", other),
+		}
+	});
+}
@@ -26,7 +26,7 @@ use std::{collections::HashMap, pin::Pin, sync::Arc, marker::PhantomData, task::
 
 use libp2p::build_multiaddr;
 use log::trace;
-use sc_network::config::FinalityProofProvider;
+use sc_network::config::{FinalityProofProvider, WarpSyncProgress};
 use sp_blockchain::{
 	HeaderBackend, Result as ClientResult,
 	well_known_cache_keys::{self, Id as CacheKeyId},
@@ -48,10 +48,10 @@ use sp_consensus::Error as ConsensusError;
 use sp_consensus::{BlockOrigin, ForkChoiceStrategy, BlockImportParams, BlockCheckParams, JustificationImport};
 use futures::prelude::*;
 use sc_network::{NetworkWorker, NetworkService, config::ProtocolId};
-use sc_network::config::{NetworkConfiguration, TransportConfig, BoxFinalityProofRequestBuilder};
+use sc_network::config::{NetworkConfiguration, TransportConfig, BoxFinalityProofRequestBuilder, SyncMode};
 use libp2p::PeerId;
 use parking_lot::Mutex;
-use sp_core::H256;
+use sp_core::{H256, Encode, Decode};
 use sc_network::config::ProtocolConfig;
 use sp_runtime::generic::{BlockId, OpaqueDigestItemId};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
@@ -519,6 +519,15 @@ impl<B: BlockT> VerifierAdapter<B> {
 	}
 }
 
+/// Configuration of a full peer.
+#[derive(Default)]
+pub struct FullPeerConfig {
+	/// Number of recent blocks to keep the state of. All the states are kept if `None`.
+	pub keep_blocks: Option<u32>,
+	/// How the peer syncs with the network.
+	pub sync_mode: SyncMode,
+}
+
 pub trait TestNetFactory: Sized {
 	type Verifier: 'static + Verifier<Block>;
 	type PeerData: Default;
@@ -579,12 +588,17 @@ pub trait TestNetFactory: Sized {
 	}
 
 	fn add_full_peer(&mut self) {
-		self.add_full_peer_with_states(None)
+		self.add_full_peer_with_config(Default::default())
 	}
 
-	/// Add a full peer.
+	/// Add a full peer, keeping the states of the given number of recent blocks.
 	fn add_full_peer_with_states(&mut self, keep_blocks: Option<u32>) {
-		let test_client_builder = match keep_blocks {
+		self.add_full_peer_with_config(FullPeerConfig { keep_blocks, ..Default::default() })
+	}
+
+	/// Add a full peer.
+	fn add_full_peer_with_config(&mut self, config: FullPeerConfig) {
+		let test_client_builder = match config.keep_blocks {
 			Some(keep_blocks) => TestClientBuilder::with_pruning_window(keep_blocks),
 			None => TestClientBuilder::with_default_backend(),
 		};
@@ -627,6 +641,7 @@ pub trait TestNetFactory: Sized {
 		network_config.transport = TransportConfig::MemoryOnly;
 		network_config.listen_addresses = vec![listen_addr.clone()];
 		network_config.allow_non_globals_in_dht = true;
+		network_config.sync_mode = config.sync_mode;
 
 		let network = NetworkWorker::new(sc_network::config::Params {
 			role: Role::Full,
@@ -925,3 +940,67 @@ impl TestNetFactory for JustificationTestNet {
 		)
 	}
 }
+
+/// Serves the last finalized block of the client as a warp sync proof, and trusts any
+/// such proof it is given. Does not prove anything.
+pub struct TrustedWarpProofProvider(PeersClient);
+
+impl FinalityProofProvider<Block> for TrustedWarpProofProvider {
+	fn prove_finality(&self, _: H256, _: &[u8]) -> ClientResult<Option<Vec<u8>>> {
+		Ok(None)
+	}
+
+	fn prove_warp_sync(&self, _begin: H256) -> ClientResult<Option<Vec<u8>>> {
+		let info = self.0.info();
+		if info.finalized_number == 0 {
+			return Ok(None);
+		}
+		Ok(self.0.header(&BlockId::Hash(info.finalized_hash))?.map(|header| header.encode()))
+	}
+
+	fn verify_warp_sync_proof(
+		&self,
+		_state: Option<&[u8]>,
+		proof: &[u8],
+	) -> ClientResult<WarpSyncProgress<Block>> {
+		let header = Decode::decode(&mut &proof[..])
+			.map_err(|e| sp_blockchain::Error::BadJustification(e.to_string()))?;
+		Ok(WarpSyncProgress::Complete { header, justification: Vec::new() })
+	}
+}
+
+pub struct WarpSyncTestNet(TestNet);
+
+impl TestNetFactory for WarpSyncTestNet {
+	type Verifier = PassThroughVerifier;
+	type PeerData = ();
+
+	fn from_config(config: &ProtocolConfig) -> Self {
+		WarpSyncTestNet(TestNet::from_config(config))
+	}
+
+	fn make_verifier(&self, client: PeersClient, config: &ProtocolConfig, peer_data: &()) -> Self::Verifier {
+		self.0.make_verifier(client, config, peer_data)
+	}
+
+	fn peer(&mut self, i: usize) -> &mut Peer<Self::PeerData> {
+		self.0.peer(i)
+	}
+
+	fn peers(&self) -> &Vec<Peer<Self::PeerData>> {
+		self.0.peers()
+	}
+
+	fn mut_peers<F: FnOnce(
+		&mut Vec<Peer<Self::PeerData>>,
+	)>(&mut self, closure: F) {
+		self.0.mut_peers(closure)
+	}
+
+	fn make_finality_proof_provider(
+		&self,
+		client: PeersClient,
+	) -> Option<Arc<dyn FinalityProofProvider<Block>>> {
+		Some(Arc::new(TrustedWarpProofProvider(client)))
+	}
+}
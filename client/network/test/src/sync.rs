@@ -710,3 +710,35 @@ fn can_sync_to_peers_with_wrong_common_block() {
 	assert!(net.peer(1).client().header(&BlockId::Hash(final_hash)).unwrap().is_some());
 }
 
+
+#[test]
+fn warp_syncs_state_and_imports_blocks_on_top() {
+	let _ = ::env_logger::try_init();
+	let mut net = WarpSyncTestNet::new(1);
+	net.peer(0).push_blocks(10, false);
+	let target = net.peer(0).client().info().best_hash;
+	net.peer(0).client().finalize_block(BlockId::Hash(target), None, true).unwrap();
+	let best = net.peer(0).push_blocks(10, false);
+
+	// the new peer starts from the state of the finalized block, and executes the
+	// blocks following it.
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Warp,
+		..Default::default()
+	});
+
+	block_on(futures::future::poll_fn::<(), _>(|cx| {
+		net.poll(cx);
+		if net.peer(1).client().info().best_hash == best {
+			Poll::Ready(())
+		} else {
+			Poll::Pending
+		}
+	}));
+
+	let info = net.peer(1).client().info();
+	assert_eq!((info.finalized_hash, info.finalized_number), (target, 10));
+	let ancestor = net.peer(0).client().header(&BlockId::Number(5)).unwrap().unwrap().hash();
+	assert!(!net.peer(1).has_block(&ancestor));
+	assert!(net.peer(1).client().as_full().unwrap().state_at(&BlockId::Hash(best)).is_ok());
+}
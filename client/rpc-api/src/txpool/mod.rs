@@ -0,0 +1,99 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate transaction pool API.
+
+use jsonrpc_core::Result as RpcResult;
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_transaction_pool::PoolEvent;
+
+pub use self::gen_client::Client as TxPoolClient;
+
+/// Transaction in the pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolTransaction<Hash> {
+	/// Hash of the transaction.
+	pub hash: Hash,
+	/// SCALE-encoded transaction.
+	pub extrinsic: Bytes,
+	/// Priority of the transaction.
+	pub priority: u64,
+	/// Tags required by the transaction.
+	pub requires: Vec<Bytes>,
+	/// Tags provided by the transaction.
+	pub provides: Vec<Bytes>,
+	/// Number of blocks after `validated_at` for which the transaction stays valid.
+	pub longevity: u64,
+	/// Number of the block the transaction was validated at.
+	pub validated_at: u64,
+	/// Sender of the transaction, if the pool is able to determine it.
+	pub sender: Option<Bytes>,
+	/// Whether the transaction is propagated to other peers.
+	pub propagate: bool,
+}
+
+/// Transactions in the queues of the pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolContent<Hash> {
+	/// Transactions in the ready queue, in the order they would be included in a block.
+	pub ready: Vec<PoolTransaction<Hash>>,
+	/// Transactions in the future queue.
+	pub future: Vec<PoolTransaction<Hash>>,
+}
+
+/// Substrate transaction pool API
+#[rpc]
+pub trait TxPoolApi<Hash> {
+	/// RPC metadata
+	type Metadata;
+
+	/// Returns all transactions in the pool.
+	///
+	/// This is an unsafe call, it is denied to be called externally.
+	#[rpc(name = "txpool_content")]
+	fn content(&self) -> RpcResult<PoolContent<Hash>>;
+
+	/// Returns the transactions of the given sender in the pool.
+	///
+	/// The sender is matched against the sender determined by the pool, for FRAME-based
+	/// runtimes this is the encoded account id.
+	///
+	/// This is an unsafe call, it is denied to be called externally.
+	#[rpc(name = "txpool_contentFrom")]
+	fn content_from(&self, sender: Bytes) -> RpcResult<PoolContent<Hash>>;
+
+	/// Subscribe to the events of all transactions entering and leaving the pool.
+	///
+	/// See [`PoolEvent`](sp_transaction_pool::PoolEvent) for details on the events. The
+	/// subscription is closed by the node if the subscriber falls too far behind.
+	#[pubsub(subscription = "txpool_event", subscribe, name = "txpool_subscribeEvents")]
+	fn subscribe_events(&self, metadata: Self::Metadata, subscriber: Subscriber<PoolEvent<Hash>>);
+
+	/// Unsubscribe from the pool events.
+	#[pubsub(subscription = "txpool_event", unsubscribe, name = "txpool_unsubscribeEvents")]
+	fn unsubscribe_events(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
+}
@@ -33,5 +33,6 @@ pub mod chain;
 pub mod offchain;
 pub mod state;
 pub mod system;
+pub mod txpool;
 #[cfg(test)]
 mod testing;
@@ -0,0 +1,126 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate transaction pool API.
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+use log::warn;
+
+use rpc::{Result as RpcResult, futures::{Sink, Future}};
+use futures::{StreamExt as _, compat::Compat};
+use sc_rpc_api::{DenyUnsafe, Subscriptions};
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use codec::Encode;
+use sp_core::Bytes;
+use sp_transaction_pool::{TransactionPool, InPoolTransaction, PoolEvent, TxHash};
+
+pub use sc_rpc_api::txpool::*;
+
+/// Transaction pool API
+pub struct TxPool<P> {
+	/// Transactions pool
+	pool: Arc<P>,
+	/// Subscriptions manager
+	subscriptions: Subscriptions,
+	/// Whether to deny unsafe calls
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<P> TxPool<P> {
+	/// Create new instance of transaction pool API.
+	pub fn new(pool: Arc<P>, subscriptions: Subscriptions, deny_unsafe: DenyUnsafe) -> Self {
+		TxPool {
+			pool,
+			subscriptions,
+			deny_unsafe,
+		}
+	}
+}
+
+impl<P: TransactionPool> TxPool<P> {
+	/// Returns the transactions in the pool, optionally only the ones of the given sender.
+	fn content_of(&self, sender: Option<&[u8]>) -> PoolContent<TxHash<P>> {
+		let to_rpc = |tx: Arc<P::InPoolTransaction>| {
+			let tx_sender = self.pool.transaction_sender(&tx);
+			if sender.map_or(false, |sender| tx_sender.as_deref() != Some(sender)) {
+				return None;
+			}
+
+			Some(PoolTransaction {
+				hash: tx.hash().clone(),
+				extrinsic: tx.data().encode().into(),
+				priority: *tx.priority(),
+				requires: tx.requires().iter().cloned().map(Into::into).collect(),
+				provides: tx.provides().iter().cloned().map(Into::into).collect(),
+				longevity: tx.longevity().saturating_sub(*tx.validated_at()),
+				validated_at: *tx.validated_at(),
+				sender: tx_sender.map(Into::into),
+				propagate: tx.is_propagable(),
+			})
+		};
+
+		PoolContent {
+			ready: self.pool.ready().filter_map(&to_rpc).collect(),
+			future: self.pool.futures().into_iter().filter_map(&to_rpc).collect(),
+		}
+	}
+}
+
+impl<P> TxPoolApi<TxHash<P>> for TxPool<P>
+	where
+		P: TransactionPool + Sync + Send + 'static,
+{
+	type Metadata = crate::metadata::Metadata;
+
+	fn content(&self) -> RpcResult<PoolContent<TxHash<P>>> {
+		self.deny_unsafe.check_if_safe()?;
+		Ok(self.content_of(None))
+	}
+
+	fn content_from(&self, sender: Bytes) -> RpcResult<PoolContent<TxHash<P>>> {
+		self.deny_unsafe.check_if_safe()?;
+		Ok(self.content_of(Some(&sender[..])))
+	}
+
+	fn subscribe_events(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<PoolEvent<TxHash<P>>>,
+	) {
+		let stream = self.pool.pool_event_stream()
+			.map(|event| Ok::<_, ()>(Ok(event)));
+
+		self.subscriptions.add(subscriber, move |sink| {
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(Compat::new(stream))
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_events(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+}
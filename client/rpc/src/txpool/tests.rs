@@ -0,0 +1,150 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use std::sync::Arc;
+use codec::Encode;
+use sp_core::{H256, blake2_256, hexdisplay::HexDisplay};
+use sp_runtime::generic::BlockId;
+use sp_transaction_pool::TransactionSource;
+use substrate_test_runtime_client::{
+	self, AccountKeyring, runtime::{Extrinsic, Transfer, Block}, Backend, Client,
+};
+use sc_transaction_pool::{BasicPool, FullChainApi};
+use futures::{executor, compat::Future01CompatExt};
+use rpc::futures::Stream as _;
+
+fn uxt(sender: AccountKeyring, nonce: u64) -> Extrinsic {
+	let tx = Transfer {
+		amount: Default::default(),
+		nonce,
+		from: sender.into(),
+		to: Default::default(),
+	};
+	tx.into_signed_tx()
+}
+
+type FullTransactionPool = BasicPool<
+	FullChainApi<Client<Backend>, Block>,
+	Block,
+>;
+
+fn api() -> TxPool<FullTransactionPool> {
+	api_with(DenyUnsafe::No)
+}
+
+fn api_with(deny_unsafe: DenyUnsafe) -> TxPool<FullTransactionPool> {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let pool = Arc::new(BasicPool::new(
+		Default::default(),
+		Arc::new(FullChainApi::new(client)),
+		None,
+	).0);
+	TxPool::new(pool, Subscriptions::new(Arc::new(crate::testing::TaskExecutor)), deny_unsafe)
+}
+
+fn submit(api: &TxPool<FullTransactionPool>, xt: Extrinsic) -> H256 {
+	executor::block_on(api.pool.submit_one(&BlockId::number(0), TransactionSource::External, xt))
+		.unwrap()
+}
+
+#[test]
+fn should_return_pool_content() {
+	// given
+	let api = api();
+	submit(&api, uxt(AccountKeyring::Alice, 0));
+	submit(&api, uxt(AccountKeyring::Bob, 0));
+	let future = submit(&api, uxt(AccountKeyring::Alice, 2));
+
+	// when
+	let content = api.content().unwrap();
+
+	// then
+	assert_eq!(content.ready.len(), 2);
+	assert_eq!(content.future.len(), 1);
+	assert_eq!(content.future[0].hash, future);
+	assert_eq!(
+		content.future[0].requires,
+		vec![Bytes((AccountKeyring::Alice.public(), 1u64).encode())],
+	);
+}
+
+#[test]
+fn should_return_content_from_sender() {
+	// given
+	let api = api();
+	let xt = uxt(AccountKeyring::Alice, 0);
+	let ready = submit(&api, xt.clone());
+	submit(&api, uxt(AccountKeyring::Bob, 0));
+	let future = submit(&api, uxt(AccountKeyring::Alice, 2));
+
+	// when
+	let content = api.content_from(AccountKeyring::Alice.to_raw_public_vec().into()).unwrap();
+
+	// then
+	assert_eq!(content.ready, vec![PoolTransaction {
+		hash: ready,
+		extrinsic: xt.encode().into(),
+		priority: 0,
+		requires: vec![],
+		provides: vec![Bytes((AccountKeyring::Alice.public(), 0u64).encode())],
+		longevity: 64,
+		validated_at: 0,
+		sender: Some(AccountKeyring::Alice.to_raw_public_vec().into()),
+		propagate: true,
+	}]);
+	assert_eq!(content.future.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![future]);
+}
+
+#[test]
+fn should_deny_unsafe_calls() {
+	// given
+	let api = api_with(DenyUnsafe::Yes);
+	submit(&api, uxt(AccountKeyring::Alice, 0));
+
+	// then
+	assert_eq!(api.content(), Err(rpc::Error::method_not_found()));
+	assert_eq!(
+		api.content_from(AccountKeyring::Alice.to_raw_public_vec().into()),
+		Err(rpc::Error::method_not_found()),
+	);
+}
+
+#[test]
+fn should_notify_about_pool_events() {
+	// given
+	let api = api();
+	let (subscriber, id_rx, data) = jsonrpc_pubsub::typed::Subscriber::new_test("test");
+
+	// when
+	api.subscribe_events(Default::default(), subscriber);
+	assert_eq!(executor::block_on(id_rx.compat()), Ok(Ok(1.into())));
+	let xt = uxt(AccountKeyring::Alice, 0);
+	submit(&api, xt.clone());
+
+	// then
+	let h = blake2_256(&xt.encode());
+	assert_eq!(
+		executor::block_on(data.into_future().compat()).unwrap().0,
+		Some(format!(
+			r#"{{"jsonrpc":"2.0","method":"test","params":{{"result":{{"ready":"0x{}"}},"subscription":1}}}}"#,
+			HexDisplay::from(&h),
+		))
+	);
+}
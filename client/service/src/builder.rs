@@ -1175,7 +1175,7 @@ ServiceBuilder<
 		// RPC
 		let (system_rpc_tx, system_rpc_rx) = tracing_unbounded("mpsc_system_rpc");
 		let gen_handler = |deny_unsafe: sc_rpc::DenyUnsafe| {
-			use sc_rpc::{chain, state, author, system, offchain};
+			use sc_rpc::{chain, state, author, system, offchain, txpool};
 
 			let system_info = sc_rpc::system::SystemInfo {
 				chain_name: chain_spec.name().into(),
@@ -1214,10 +1214,11 @@ ServiceBuilder<
 			let author = sc_rpc::author::Author::new(
 				client.clone(),
 				transaction_pool.clone(),
-				subscriptions,
+				subscriptions.clone(),
 				keystore.clone(),
 				deny_unsafe,
 			);
+			let txpool = txpool::TxPool::new(transaction_pool.clone(), subscriptions, deny_unsafe);
 			let system = system::System::new(system_info, system_rpc_tx.clone(), deny_unsafe);
 
 			let maybe_offchain_rpc = offchain_storage.clone()
@@ -1235,6 +1236,7 @@ ServiceBuilder<
 				chain::ChainApi::to_delegate(chain),
 				maybe_offchain_rpc,
 				author::AuthorApi::to_delegate(author),
+				txpool::TxPoolApi::to_delegate(txpool),
 				system::SystemApi::to_delegate(system),
 				rpc_extensions_builder.build(deny_unsafe),
 			))
@@ -175,6 +175,7 @@ fn import_block_to_queue<TBl, TImpQu>(
 			origin: None,
 			allow_missing_state: false,
 			import_existing: force,
+			state_imported: false,
 		}
 	]);
 }
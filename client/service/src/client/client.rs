@@ -44,6 +44,7 @@ use sp_state_machine::{
 	DBValue, Backend as StateBackend, ChangesTrieAnchorBlockId,
 	prove_read, prove_child_read, ChangesTrieRootsStorage, ChangesTrieStorage,
 	ChangesTrieConfigurationRange, key_changes, key_changes_proof,
+	prove_range_read_with_size, read_range_proof_check, CheckedRange,
 };
 use sc_executor::RuntimeVersion;
 use sp_consensus::{
@@ -73,7 +74,7 @@ use sc_client_api::{
 	client::{
 		ImportNotifications, FinalityNotification, FinalityNotifications, BlockImportNotification,
 		ClientInfo, BlockchainEvents, BlockBackend, ProvideUncles, BadBlocks, ForkBlocks,
-		BlockOf,
+		BlockOf, StateImporter,
 	},
 	execution_extensions::ExecutionExtensions,
 	notifications::{StorageNotifications, StorageEventStream},
@@ -682,6 +683,43 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		result
 	}
 
+	/// Import the block whose state has been imported with `StateImporter::import_state_chunk`.
+	///
+	/// The block becomes the new finalized base of the chain; its ancestors are not imported.
+	fn apply_imported_state(
+		&self,
+		operation: &mut ClientImportOperation<Block, B>,
+		import_block: BlockImportParams<Block, backend::TransactionFor<B, Block>>,
+	) -> sp_blockchain::Result<ImportResult> {
+		let BlockImportParams {
+			header,
+			justification,
+			post_digests,
+			body,
+			auxiliary,
+			intermediates,
+			..
+		} = import_block;
+
+		if !intermediates.is_empty() || !post_digests.is_empty() {
+			return Err(Error::IncompletePipeline)
+		}
+
+		let hash = header.hash();
+		if self.backend.blockchain().status(BlockId::Hash(hash))? == blockchain::BlockStatus::InChain {
+			return Ok(ImportResult::AlreadyInChain)
+		}
+
+		info!("📥 Importing state at #{} ({})", header.number(), hash);
+		*self.importing_block.write() = Some(hash);
+
+		operation.op.insert_state_nodes(Vec::new())?;
+		operation.op.set_block_data(header, body, justification, NewBlockState::Final)?;
+		operation.op.insert_aux(auxiliary)?;
+
+		Ok(ImportResult::imported(true))
+	}
+
 	fn execute_and_import_block(
 		&self,
 		operation: &mut ClientImportOperation<Block, B>,
@@ -1251,6 +1289,29 @@ impl<B, E, Block, RA> ProofProvider<Block> for Client<B, E, Block, RA> where
 			cht::size(),
 		)
 	}
+
+	fn read_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: Option<&[u8]>,
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, u32)> {
+		self.state_at(id)
+			.and_then(|state| prove_range_read_with_size(state, child_info, start_key, size_limit)
+				.map_err(Into::into))
+	}
+
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_key: Option<&[u8]>,
+	) -> sp_blockchain::Result<CheckedRange> {
+		read_range_proof_check::<HashFor<Block>>(root, proof, child_info, start_key)
+			.map_err(Into::into)
+	}
 }
 
 
@@ -1689,6 +1750,15 @@ impl<B, E, Block, RA> sp_consensus::BlockImport<Block> for &Client<B, E, Block,
 		let span = tracing::span!(tracing::Level::DEBUG, "import_block");
 		let _enter = span.enter();
 
+		if import_block.state_imported {
+			return self.lock_import_and_run(|operation| {
+				self.apply_imported_state(operation, import_block)
+			}).map_err(|e| {
+				warn!("State import error:\n{:?}", e);
+				ConsensusError::ClientImport(e.to_string()).into()
+			})
+		}
+
 		if let Some(res) = self.prepare_block_storage_changes(&mut import_block).map_err(|e| {
 			warn!("Block prepare storage changes error:\n{:?}", e);
 			ConsensusError::ClientImport(e.to_string())
@@ -1877,6 +1947,28 @@ where
 	}
 }
 
+impl<B, E, Block, RA> StateImporter<Block> for Client<B, E, Block, RA>
+	where
+		B: backend::Backend<Block>,
+		E: CallExecutor<Block>,
+		Block: BlockT,
+{
+	fn import_state_chunk(&self, nodes: Vec<(Vec<u8>, Vec<u8>)>) -> sp_blockchain::Result<()> {
+		self.lock_import_and_run(|operation| operation.op.insert_state_nodes(nodes))
+	}
+
+	fn call_imported_state(
+		&self,
+		header: &Block::Header,
+		method: &str,
+		call_data: &[u8],
+	) -> sp_blockchain::Result<Vec<u8>> {
+		let state = self.backend.imported_state(header.state_root())?;
+		self.executor.prove_at_state(state, &mut Default::default(), method, call_data)
+			.map(|(result, _)| result)
+	}
+}
+
 impl<B, E, Block, RA> BlockBackend<Block> for Client<B, E, Block, RA>
 	where
 		B: backend::Backend<Block>,
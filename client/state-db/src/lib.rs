@@ -297,6 +297,26 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		Ok(commit)
 	}
 
+	fn import_base_block<E: fmt::Debug>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		parent_hash: &BlockHash,
+		changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		if self.mode == PruningMode::ArchiveAll {
+			return self.insert_block(hash, number, parent_hash, changeset);
+		}
+		self.non_canonical.reset_last_canonicalized()?;
+		let mut commit = self.insert_block(hash, number, parent_hash, changeset)?;
+		let canonicalized = self.canonicalize_block(hash)?;
+		commit.data.inserted.extend(canonicalized.data.inserted);
+		commit.data.deleted.extend(canonicalized.data.deleted);
+		commit.meta.inserted.extend(canonicalized.meta.inserted);
+		commit.meta.deleted.extend(canonicalized.meta.deleted);
+		Ok(commit)
+	}
+
 	fn best_canonical(&self) -> Option<u64> {
 		return self.non_canonical.last_canonicalized_block_number()
 	}
@@ -471,6 +491,19 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 		self.db.write().canonicalize_block(hash)
 	}
 
+	/// Import a block which state is not built on top of the last canonicalized block, and
+	/// canonicalize it right away. This is used to start from a state downloaded from the
+	/// network rather than from genesis. There must be no non-canonical blocks when calling this.
+	pub fn import_base_block<E: fmt::Debug>(
+		&self,
+		hash: &BlockHash,
+		number: u64,
+		parent_hash: &BlockHash,
+		changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		self.db.write().import_base_block(hash, number, parent_hash, changeset)
+	}
+
 	/// Prevents pruning of specified block and its descendants.
 	pub fn pin(&self, hash: &BlockHash) -> Result<(), PinError> {
 		self.db.write().pin(hash)
@@ -645,6 +678,69 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[1, 21, 3, 921, 922, 93, 94])));
 	}
 
+	#[test]
+	fn import_base_block_works() {
+		let mut db = make_db(&[]);
+		let state_db: StateDb<H256, H256> = StateDb::new(
+			PruningMode::keep_blocks(2),
+			false,
+			&db,
+		).unwrap();
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(0),
+					0,
+					&H256::from_low_u64_be(0),
+					make_changeset(&[0], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(0)).unwrap());
+		state_db.apply_pending();
+
+		db.commit(
+			&state_db
+				.import_base_block::<io::Error>(
+					&H256::from_low_u64_be(10),
+					10,
+					&H256::from_low_u64_be(9),
+					make_changeset(&[10], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(10));
+		assert!(db.data_eq(&make_db(&[0, 10])));
+
+		// blocks can be imported on top of the base block
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(11),
+					11,
+					&H256::from_low_u64_be(10),
+					make_changeset(&[11], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+
+		// but not while there are non-canonical blocks
+		assert!(state_db
+			.import_base_block::<io::Error>(
+				&H256::from_low_u64_be(20),
+				20,
+				&H256::from_low_u64_be(19),
+				make_changeset(&[20], &[]),
+			)
+			.is_err());
+
+		let restored: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks(2), false, &db).unwrap();
+		assert_eq!(restored.best_canonical(), Some(10));
+	}
+
 	#[test]
 	fn detects_incompatible_mode() {
 		let mut db = make_db(&[]);
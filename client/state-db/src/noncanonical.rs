@@ -266,6 +266,18 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 		Ok(commit)
 	}
 
+	/// Forget about the last canonicalized block, so that the next inserted block is assumed to
+	/// have a canonicalized parent, whatever its number. Only possible when there are no
+	/// non-canonical blocks in the overlay.
+	pub fn reset_last_canonicalized<E: fmt::Debug>(&mut self) -> Result<(), Error<E>> {
+		if !self.levels.is_empty() || !self.pending_canonicalizations.is_empty() {
+			return Err(Error::InvalidBlockNumber);
+		}
+		trace!(target: "state-db", "Resetting last canonicalized block {:?}", self.last_canonicalized);
+		self.last_canonicalized = None;
+		Ok(())
+	}
+
 	fn discard_journals(
 		&self,
 		level_index: usize,
@@ -21,7 +21,8 @@
 //! For a more full-featured pool, have a look at the `pool` module.
 
 use std::{
-	collections::HashSet,
+	cmp,
+	collections::{BTreeMap, HashMap, HashSet},
 	fmt,
 	hash,
 	sync::Arc,
@@ -85,6 +86,17 @@ pub struct PruneStatus<Hash, Ex> {
 	pub pruned: Vec<Arc<Transaction<Hash, Ex>>>,
 }
 
+/// Reason of removing a transaction from the pool to keep it within its limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EvictionReason {
+	/// The ready queue exceeded its limits.
+	ReadyLimit,
+	/// The future queue exceeded its limits.
+	FutureLimit,
+	/// The sender of the transaction exceeded the per-sender limits.
+	SenderLimit,
+}
+
 /// Immutable transaction
 #[cfg_attr(test, derive(Clone))]
 #[derive(PartialEq, Eq, parity_util_mem::MallocSizeOf)]
@@ -99,6 +111,8 @@ pub struct Transaction<Hash, Extrinsic> {
 	pub priority: Priority,
 	/// At which block the transaction becomes invalid?
 	pub valid_till: Longevity,
+	/// At which block the transaction was validated.
+	pub validated_at: u64,
 	/// Tags required by the transaction.
 	pub requires: Vec<Tag>,
 	/// Tags that this transaction provides.
@@ -146,6 +160,10 @@ impl<Hash, Extrinsic> InPoolTransaction for Transaction<Hash, Extrinsic> {
 	fn is_propagable(&self) -> bool {
 		self.propagate
 	}
+
+	fn validated_at(&self) -> &u64 {
+		&self.validated_at
+	}
 }
 
 impl<Hash: Clone, Extrinsic: Clone> Transaction<Hash, Extrinsic> {
@@ -162,6 +180,7 @@ impl<Hash: Clone, Extrinsic: Clone> Transaction<Hash, Extrinsic> {
 			priority: self.priority.clone(),
 			source: self.source,
 			valid_till: self.valid_till.clone(),
+			validated_at: self.validated_at,
 			requires: self.requires.clone(),
 			provides: self.provides.clone(),
 			propagate: self.propagate,
@@ -228,6 +247,8 @@ pub struct BasePool<Hash: hash::Hash + Eq, Ex> {
 	/// transactions to future in case they were just stuck in verification.
 	recently_pruned: [HashSet<Tag>; RECENTLY_PRUNED_TAGS],
 	recently_pruned_index: usize,
+	/// Transactions in both queues by their sender, if known.
+	senders: SenderIndex<Hash>,
 }
 
 impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> Default for BasePool<Hash, Ex> {
@@ -245,6 +266,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			ready: Default::default(),
 			recently_pruned: Default::default(),
 			recently_pruned_index: 0,
+			senders: Default::default(),
 		}
 	}
 
@@ -303,6 +325,23 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.import_to_ready(tx)
 	}
 
+	/// Imports transaction sent by `sender` to the pool.
+	///
+	/// Like `import`, but the transaction is also subject to `enforce_sender_limits` of its
+	/// sender.
+	pub fn import_with_sender(
+		&mut self,
+		tx: Transaction<Hash, Ex>,
+		sender: Option<Vec<u8>>,
+	) -> error::Result<Imported<Hash, Ex>> {
+		let hash = tx.hash.clone();
+		let imported = self.import(tx)?;
+		if let Some(sender) = sender {
+			self.senders.insert(hash, sender);
+		}
+		Ok(imported)
+	}
+
 	/// Imports transaction to ready queue.
 	///
 	/// NOTE the transaction has to have all requirements satisfied.
@@ -354,12 +393,15 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		if removed.iter().any(|tx| tx.hash == hash) {
 			// We still need to remove all transactions that we promoted
 			// since they depend on each other and will never get to the best iterator.
-			self.ready.remove_subtree(&promoted);
+			let dropped = self.ready.remove_subtree(&promoted);
+			self.senders.remove(removed.iter().chain(&dropped));
 
 			debug!(target: "txpool", "[{:?}] Cycle detected, bailing.", hash);
 			return Err(error::Error::CycleDetected)
 		}
 
+		self.senders.remove(&removed);
+
 		Ok(Imported::Ready {
 			hash,
 			promoted,
@@ -374,7 +416,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	}
 
 	/// Returns an iterator over future transactions in the pool.
-	pub fn futures(&self) -> impl Iterator<Item=&Transaction<Hash, Ex>> {
+	pub fn futures(&self) -> impl Iterator<Item=&Arc<Transaction<Hash, Ex>>> {
 		self.future.all()
 	}
 
@@ -402,8 +444,15 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	///
 	/// Removes and returns worst transactions from the queues and all transactions that depend on them.
 	/// Technically the worst transaction should be evaluated by computing the entire pending set.
-	/// We use a simplified approach to remove the transaction that occupies the pool for the longest time.
-	pub fn enforce_limits(&mut self, ready: &Limit, future: &Limit) -> Vec<Arc<Transaction<Hash, Ex>>> {
+	/// We use a simplified approach to remove the transaction with the lowest priority per byte and,
+	/// among equally paying ones, the one that occupies the pool for the longest time. A transaction
+	/// which pays less per byte than everything in a full queue is dropped right away instead of
+	/// replacing a better paying one.
+	pub fn enforce_limits(
+		&mut self,
+		ready: &Limit,
+		future: &Limit,
+	) -> Vec<(EvictionReason, Arc<Transaction<Hash, Ex>>)> {
 		let mut removed = vec![];
 
 		while ready.is_exceeded(self.ready.len(), self.ready.bytes()) {
@@ -413,7 +462,9 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 					let transaction = &current.transaction;
 					match minimal {
 						None => Some(transaction.clone()),
-						Some(ref tx) if tx.insertion_id > transaction.insertion_id => {
+						Some(ref tx) if cmp_priority_per_byte(&transaction.transaction, &tx.transaction)
+							.then(transaction.insertion_id.cmp(&tx.insertion_id)) == cmp::Ordering::Less =>
+						{
 							Some(transaction.clone())
 						},
 						other => other,
@@ -421,7 +472,11 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 				});
 
 			if let Some(minimal) = minimal {
-				removed.append(&mut self.remove_subtree(&[minimal.transaction.hash.clone()]))
+				removed.extend(
+					self.remove_subtree(&[minimal.transaction.hash.clone()])
+						.into_iter()
+						.map(|tx| (EvictionReason::ReadyLimit, tx))
+				)
 			} else {
 				break;
 			}
@@ -433,7 +488,9 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 				.fold(|minimal, current| {
 					match minimal {
 						None => Some(current.clone()),
-						Some(ref tx) if tx.imported_at > current.imported_at => {
+						Some(ref tx) if cmp_priority_per_byte(&current.transaction, &tx.transaction)
+							.then(current.imported_at.cmp(&tx.imported_at)) == cmp::Ordering::Less =>
+						{
 							Some(current.clone())
 						},
 						other => other,
@@ -441,7 +498,11 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 				});
 
 			if let Some(minimal) = minimal {
-				removed.append(&mut self.remove_subtree(&[minimal.transaction.hash.clone()]))
+				removed.extend(
+					self.remove_subtree(&[minimal.transaction.hash.clone()])
+						.into_iter()
+						.map(|tx| (EvictionReason::FutureLimit, tx))
+				)
 			} else {
 				break;
 			}
@@ -450,6 +511,50 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		removed
 	}
 
+	/// Makes sure that none of the given `senders` has more transactions in the queues than
+	/// allowed by the `limit`.
+	///
+	/// Only transactions imported with `import_with_sender` count towards the limit of their
+	/// sender. The most recently imported transactions of a sender exceeding the limit are
+	/// removed first, future ones before ready ones, so that a sender can't push its own
+	/// transactions which are about to be included out of the pool.
+	/// Returns removed transactions and all transactions that depend on them.
+	pub fn enforce_sender_limits(
+		&mut self,
+		limit: &Limit,
+		senders: &HashSet<Vec<u8>>,
+	) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		let mut removed = vec![];
+		for sender in senders {
+			let (future, ready): (Vec<_>, Vec<_>) = self.senders.hashes(sender)
+				.partition(|hash| self.future.contains(hash));
+			// transactions of the sender in the order of removal
+			let candidates = self.future.by_hashes(&future).into_iter()
+				.chain(self.ready.by_hashes(&ready))
+				.flatten()
+				.collect::<Vec<_>>();
+
+			let mut count = candidates.len();
+			let mut bytes = candidates.iter().map(|tx| tx.bytes).sum::<usize>();
+			let hashes = candidates.iter().map(|tx| tx.hash.clone()).collect::<HashSet<_>>();
+			for tx in candidates {
+				if !limit.is_exceeded(count, bytes) {
+					break;
+				}
+
+				for tx in self.remove_subtree(&[tx.hash.clone()]) {
+					if hashes.contains(&tx.hash) {
+						count -= 1;
+						bytes -= tx.bytes;
+					}
+					removed.push(tx);
+				}
+			}
+		}
+
+		removed
+	}
+
 	/// Removes all transactions represented by the hashes and all other transactions
 	/// that depend on them.
 	///
@@ -461,12 +566,15 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	pub fn remove_subtree(&mut self, hashes: &[Hash]) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		let mut removed = self.ready.remove_subtree(hashes);
 		removed.extend(self.future.remove(hashes));
+		self.senders.remove(&removed);
 		removed
 	}
 
 	/// Removes and returns all transactions from the future queue.
 	pub fn clear_future(&mut self) -> Vec<Arc<Transaction<Hash, Ex>>> {
-		self.future.clear()
+		let removed = self.future.clear();
+		self.senders.remove(&removed);
+		removed
 	}
 
 	/// Prunes transactions that provide given list of tags.
@@ -490,6 +598,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			// store the tags for next submission
 			recently_pruned.insert(tag);
 		}
+		self.senders.remove(&pruned);
 
 		let mut promoted = vec![];
 		let mut failed = vec![];
@@ -522,6 +631,114 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	}
 }
 
+/// Compares the priority per byte of two transactions.
+fn cmp_priority_per_byte<Hash, Ex>(a: &Transaction<Hash, Ex>, b: &Transaction<Hash, Ex>) -> cmp::Ordering {
+	cmp_total_priority_per_byte((a.priority, a.bytes), (b.priority, b.bytes))
+}
+
+/// Compares the priority per byte of two groups of transactions, given as their total priority
+/// and total size.
+pub(crate) fn cmp_total_priority_per_byte(a: (Priority, usize), b: (Priority, usize)) -> cmp::Ordering {
+	let a_total = u128::from(a.0) * b.1 as u128;
+	let b_total = u128::from(b.0) * a.1 as u128;
+	a_total.cmp(&b_total)
+}
+
+/// Determines the sender of a transaction from the tags it provides.
+///
+/// Signed FRAME transactions provide an `(AccountId, Index)` tag, the sender is the prefix of
+/// that tag. Tags are only recognized by their length, so the layouts have to match the account
+/// ids and indices used by the runtime. Transactions which don't provide such a tag have no
+/// sender.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SenderTags {
+	/// The length of the sender and the length of the whole tag of every recognized layout.
+	layouts: Vec<(usize, usize)>,
+}
+
+impl SenderTags {
+	/// Recognizes no tags, so no transaction has a sender.
+	pub fn none() -> Self {
+		SenderTags { layouts: Vec::new() }
+	}
+
+	/// Also recognizes tags of a sender of `sender_len` bytes followed by an index of
+	/// `index_len` bytes.
+	pub fn with(mut self, sender_len: usize, index_len: usize) -> Self {
+		self.layouts.push((sender_len, sender_len + index_len));
+		self
+	}
+
+	/// Returns the sender of a transaction given its `provides` tags.
+	pub fn sender(&self, provides: &[Tag]) -> Option<Vec<u8>> {
+		provides.iter().find_map(|tag| self.layouts.iter()
+			.find(|(_, tag_len)| tag.len() == *tag_len)
+			.map(|(sender_len, _)| tag[..*sender_len].to_vec())
+		)
+	}
+}
+
+impl Default for SenderTags {
+	/// Recognizes 32-byte account ids with `u32` or `u64` indices.
+	fn default() -> Self {
+		SenderTags::none().with(32, 4).with(32, 8)
+	}
+}
+
+/// Transactions in the pool indexed by their sender.
+#[derive(Debug)]
+#[cfg_attr(not(target_os = "unknown"), derive(parity_util_mem::MallocSizeOf))]
+struct SenderIndex<Hash: hash::Hash + Eq> {
+	/// Transactions of every sender by the order they were imported in.
+	by_sender: HashMap<Vec<u8>, BTreeMap<u64, Hash>>,
+	/// Sender and import order of every transaction.
+	by_hash: HashMap<Hash, (Vec<u8>, u64)>,
+	/// Import order of the next transaction.
+	next_id: u64,
+}
+
+impl<Hash: hash::Hash + Eq> Default for SenderIndex<Hash> {
+	fn default() -> Self {
+		SenderIndex {
+			by_sender: Default::default(),
+			by_hash: Default::default(),
+			next_id: 0,
+		}
+	}
+}
+
+impl<Hash: hash::Hash + Eq + Clone> SenderIndex<Hash> {
+	fn insert(&mut self, hash: Hash, sender: Vec<u8>) {
+		let id = self.next_id;
+		self.next_id += 1;
+		self.by_sender.entry(sender.clone()).or_default().insert(id, hash.clone());
+		self.by_hash.insert(hash, (sender, id));
+	}
+
+	fn remove<'a, Ex: 'a>(
+		&mut self,
+		removed: impl IntoIterator<Item=&'a Arc<Transaction<Hash, Ex>>>,
+	) where Hash: 'a {
+		for tx in removed {
+			if let Some((sender, id)) = self.by_hash.remove(&tx.hash) {
+				if let Some(hashes) = self.by_sender.get_mut(&sender) {
+					hashes.remove(&id);
+					if hashes.is_empty() {
+						self.by_sender.remove(&sender);
+					}
+				}
+			}
+		}
+	}
+
+	/// Returns the transactions of `sender`, most recently imported first.
+	fn hashes(&self, sender: &[u8]) -> impl Iterator<Item=Hash> + '_ {
+		self.by_sender.get(sender)
+			.into_iter()
+			.flat_map(|hashes| hashes.values().rev().cloned())
+	}
+}
+
 /// Queue limits
 #[derive(Debug, Clone)]
 pub struct Limit {
@@ -560,6 +777,7 @@ mod tests {
 			hash: 1u64,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![1]],
 			propagate: true,
@@ -583,6 +801,7 @@ mod tests {
 			hash: 1,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![1]],
 			propagate: true,
@@ -594,6 +813,7 @@ mod tests {
 			hash: 1,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![1]],
 			propagate: true,
@@ -618,6 +838,7 @@ mod tests {
 			hash: 1,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![0]],
 			provides: vec![vec![1]],
 			propagate: true,
@@ -631,6 +852,7 @@ mod tests {
 			hash: 2,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![0]],
 			propagate: true,
@@ -654,6 +876,7 @@ mod tests {
 			hash: 1,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![0]],
 			provides: vec![vec![1]],
 			propagate: true,
@@ -665,6 +888,7 @@ mod tests {
 			hash: 3,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![2]],
 			provides: vec![],
 			propagate: true,
@@ -676,6 +900,7 @@ mod tests {
 			hash: 2,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![1]],
 			provides: vec![vec![3], vec![2]],
 			propagate: true,
@@ -687,6 +912,7 @@ mod tests {
 			hash: 4,
 			priority: 1_000u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![3], vec![4]],
 			provides: vec![],
 			propagate: true,
@@ -701,6 +927,7 @@ mod tests {
 			hash: 5,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![0], vec![4]],
 			propagate: true,
@@ -734,6 +961,7 @@ mod tests {
 			hash: 1,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![0]],
 			provides: vec![vec![1]],
 			propagate: true,
@@ -745,6 +973,7 @@ mod tests {
 			hash: 3,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![1]],
 			provides: vec![vec![2]],
 			propagate: true,
@@ -760,6 +989,7 @@ mod tests {
 			hash: 2,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![2]],
 			provides: vec![vec![0]],
 			propagate: true,
@@ -781,6 +1011,7 @@ mod tests {
 			hash: 4,
 			priority: 50u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![0]],
 			propagate: true,
@@ -810,6 +1041,7 @@ mod tests {
 			hash: 1,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![0]],
 			provides: vec![vec![1]],
 			propagate: true,
@@ -821,6 +1053,7 @@ mod tests {
 			hash: 3,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![1]],
 			provides: vec![vec![2]],
 			propagate: true,
@@ -836,6 +1069,7 @@ mod tests {
 			hash: 2,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![2]],
 			provides: vec![vec![0]],
 			propagate: true,
@@ -857,6 +1091,7 @@ mod tests {
 			hash: 4,
 			priority: 1u64, // lower priority than Tx(2)
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![0]],
 			propagate: true,
@@ -881,6 +1116,7 @@ mod tests {
 			hash: 5,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![0], vec![4]],
 			propagate: true,
@@ -892,6 +1128,7 @@ mod tests {
 			hash: 7,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![2], vec![7]],
 			propagate: true,
@@ -911,6 +1148,7 @@ mod tests {
 			hash: 5,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![0], vec![4]],
 			propagate: true,
@@ -922,6 +1160,7 @@ mod tests {
 			hash: 1,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![0]],
 			provides: vec![vec![1]],
 			propagate: true,
@@ -933,6 +1172,7 @@ mod tests {
 			hash: 3,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![2]],
 			provides: vec![],
 			propagate: true,
@@ -944,6 +1184,7 @@ mod tests {
 			hash: 2,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![1]],
 			provides: vec![vec![3], vec![2]],
 			propagate: true,
@@ -955,6 +1196,7 @@ mod tests {
 			hash: 4,
 			priority: 1_000u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![3], vec![4]],
 			provides: vec![],
 			propagate: true,
@@ -967,6 +1209,7 @@ mod tests {
 			hash: 6,
 			priority: 1_000u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![11]],
 			provides: vec![],
 			propagate: true,
@@ -994,6 +1237,7 @@ mod tests {
 			hash: 5,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![0]],
 			provides: vec![vec![100]],
 			propagate: true,
@@ -1006,6 +1250,7 @@ mod tests {
 			hash: 1,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![vec![1]],
 			propagate: true,
@@ -1017,6 +1262,7 @@ mod tests {
 			hash: 2,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![2]],
 			provides: vec![vec![3]],
 			propagate: true,
@@ -1028,6 +1274,7 @@ mod tests {
 			hash: 3,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![1]],
 			provides: vec![vec![2]],
 			propagate: true,
@@ -1039,6 +1286,7 @@ mod tests {
 			hash: 4,
 			priority: 1_000u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![3], vec![2]],
 			provides: vec![vec![4]],
 			propagate: true,
@@ -1075,6 +1323,7 @@ mod tests {
 				hash: 4,
 				priority: 1_000u64,
 				valid_till: 64u64,
+				validated_at: 0,
 				requires: vec![vec![3], vec![2]],
 				provides: vec![vec![4]],
 				propagate: true,
@@ -1094,6 +1343,7 @@ source: TransactionSource::External, requires: [03,02], provides: [04], data: [4
 				hash: 4,
 				priority: 1_000u64,
 				valid_till: 64u64,
+				validated_at: 0,
 				requires: vec![vec![3], vec![2]],
 				provides: vec![vec![4]],
 				propagate: true,
@@ -1106,6 +1356,7 @@ source: TransactionSource::External, requires: [03,02], provides: [04], data: [4
 				hash: 4,
 				priority: 1_000u64,
 				valid_till: 64u64,
+				validated_at: 0,
 				requires: vec![vec![3], vec![2]],
 				provides: vec![vec![4]],
 				propagate: false,
@@ -1128,6 +1379,7 @@ source: TransactionSource::External, requires: [03,02], provides: [04], data: [4
 			hash: 5,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![0]],
 			provides: vec![],
 			propagate: true,
@@ -1152,6 +1404,7 @@ source: TransactionSource::External, requires: [03,02], provides: [04], data: [4
 			hash: 5,
 			priority: 5u64,
 			valid_till: 64u64,
+			validated_at: 0,
 			requires: vec![vec![0]],
 			provides: vec![],
 			propagate: true,
@@ -1182,6 +1435,7 @@ source: TransactionSource::External, requires: [03,02], provides: [04], data: [4
 				hash: 5,
 				priority: 5u64,
 				valid_till: 64u64,
+				validated_at: 0,
 				requires: vec![vec![0]],
 				provides: vec![],
 				propagate: true,
@@ -1196,4 +1450,107 @@ source: TransactionSource::External, requires: [03,02], provides: [04], data: [4
 		assert_eq!(pool.reject_future_transactions, true);
 		assert_eq!(pool.future.len(), 1);
 	}
+
+	#[test]
+	fn should_evict_transaction_with_lowest_priority_per_byte() {
+		// given
+		let mut pool = pool();
+		let limit = Limit {
+			count: 2,
+			total_bytes: 100,
+		};
+		let transaction = |hash, priority, bytes| Transaction {
+			data: vec![hash as u8],
+			bytes,
+			hash,
+			priority,
+			valid_till: 64u64,
+			validated_at: 0,
+			requires: vec![],
+			provides: vec![vec![hash as u8]],
+			propagate: true,
+			source: Source::External,
+		};
+		pool.import(transaction(1, 10, 2)).unwrap();
+		pool.import(transaction(2, 10, 10)).unwrap();
+		pool.import(transaction(3, 2, 1)).unwrap();
+
+		// when
+		let removed = pool.enforce_limits(&limit, &limit);
+
+		// then
+		let removed = removed.into_iter().map(|(reason, tx)| (reason, tx.hash)).collect::<Vec<_>>();
+		assert_eq!(removed, vec![(EvictionReason::ReadyLimit, 2)]);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![1, 3]);
+	}
+
+	#[test]
+	fn should_enforce_sender_limits() {
+		// given
+		let mut pool = pool();
+		let limit = Limit {
+			count: 2,
+			total_bytes: 100,
+		};
+		let tag = |sender: u8, nonce: u32| {
+			let mut tag = vec![sender; 32];
+			tag.extend_from_slice(&nonce.to_le_bytes());
+			tag
+		};
+		let transaction = |hash, requires: Vec<Tag>, provides: Vec<Tag>| Transaction {
+			data: vec![hash as u8],
+			bytes: 1,
+			hash,
+			priority: 5u64,
+			valid_till: 64u64,
+			validated_at: 0,
+			requires,
+			provides,
+			propagate: true,
+			source: Source::External,
+		};
+		let mut import = |tx: Transaction<Hash, Vec<u8>>| {
+			let sender = SenderTags::default().sender(&tx.provides);
+			pool.import_with_sender(tx, sender).unwrap();
+		};
+		import(transaction(1, vec![], vec![tag(1, 0)]));
+		import(transaction(2, vec![tag(1, 0)], vec![tag(1, 1)]));
+		import(transaction(3, vec![tag(1, 1)], vec![tag(1, 2)]));
+		import(transaction(4, vec![tag(1, 4)], vec![tag(1, 5)]));
+		import(transaction(5, vec![], vec![tag(2, 0)]));
+		import(transaction(6, vec![], vec![vec![1]]));
+		import(transaction(7, vec![], vec![vec![2]]));
+		import(transaction(8, vec![], vec![vec![3]]));
+		let senders = vec![vec![1u8; 32], vec![2u8; 32]].into_iter().collect();
+
+		// when
+		let removed = pool.enforce_sender_limits(&limit, &senders);
+
+		// then
+		assert_eq!(removed.into_iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![4, 3]);
+		assert_eq!(pool.ready.len(), 6);
+		assert_eq!(pool.future.len(), 0);
+		assert_eq!(pool.senders.hashes(&[1u8; 32]).collect::<Vec<_>>(), vec![2, 1]);
+		assert_eq!(pool.senders.hashes(&[2u8; 32]).collect::<Vec<_>>(), vec![5]);
+
+		// the index follows the transactions out of the pool
+		pool.prune_tags(vec![tag(1, 0)]);
+		assert_eq!(pool.senders.hashes(&[1u8; 32]).collect::<Vec<_>>(), vec![2]);
+	}
+
+	#[test]
+	fn should_recognize_sender_tags() {
+		let tags = SenderTags::default().with(20, 32);
+		let tag = |sender_len: usize, index_len: usize| {
+			let mut tag = vec![1u8; sender_len];
+			tag.extend(vec![0u8; index_len]);
+			tag
+		};
+
+		assert_eq!(tags.sender(&[tag(32, 4)]), Some(vec![1u8; 32]));
+		assert_eq!(tags.sender(&[tag(32, 8)]), Some(vec![1u8; 32]));
+		assert_eq!(tags.sender(&[vec![2], tag(20, 32)]), Some(vec![1u8; 20]));
+		assert_eq!(tags.sender(&[tag(20, 4)]), None);
+		assert_eq!(SenderTags::none().sender(&[tag(32, 4)]), None);
+	}
 }
@@ -227,8 +227,8 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 	}
 
 	/// Returns iterator over all future transactions
-	pub fn all(&self) -> impl Iterator<Item=&Transaction<Hash, Ex>> {
-		self.waiting.values().map(|waiting| &*waiting.transaction)
+	pub fn all(&self) -> impl Iterator<Item=&Arc<Transaction<Hash, Ex>>> {
+		self.waiting.values().map(|waiting| &waiting.transaction)
 	}
 
 	/// Removes and returns all future transactions.
@@ -263,6 +263,7 @@ mod tests {
 				hash: 1,
 				priority: 1,
 				valid_till: 2,
+				validated_at: 0,
 				requires: vec![vec![1], vec![2]],
 				provides: vec![vec![3], vec![4]],
 				propagate: true,
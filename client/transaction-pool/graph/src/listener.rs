@@ -25,21 +25,30 @@ use serde::Serialize;
 use crate::{watcher, ChainApi, BlockHash};
 use log::{debug, trace, warn};
 use sp_runtime::traits;
+use sp_transaction_pool::{PoolEvent, PoolEventStream};
+use futures::channel::mpsc;
 
 /// Extrinsic pool default listener.
 pub struct Listener<H: hash::Hash + Eq + Debug, C: ChainApi> {
 	watchers: HashMap<H, watcher::Sender<H, BlockHash<C>>>,
 	finality_watchers: LinkedHashMap<BlockHash<C>, Vec<H>>,
+	event_sinks: Vec<mpsc::Sender<PoolEvent<H>>>,
 }
 
 /// Maximum number of blocks awaiting finality at any time.
 const MAX_FINALITY_WATCHERS: usize = 512;
 
+/// Maximum number of pool events buffered for a single event stream.
+///
+/// Streams that fall further behind are dropped instead of buffering without bound.
+pub const MAX_POOL_EVENTS_BUFFERED: usize = 1024;
+
 impl<H: hash::Hash + Eq + Debug, C: ChainApi> Default for Listener<H, C> {
 	fn default() -> Self {
 		Listener {
 			watchers: Default::default(),
 			finality_watchers: Default::default(),
+			event_sinks: Default::default(),
 		}
 	}
 }
@@ -58,6 +67,30 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 		}
 	}
 
+	fn notify(&mut self, event: PoolEvent<H>) {
+		self.event_sinks = std::mem::take(&mut self.event_sinks)
+			.into_iter()
+			.filter_map(|mut sink| match sink.try_send(event.clone()) {
+				Ok(()) => Some(sink),
+				Err(e) => {
+					if e.is_full() {
+						debug!(target: "txpool", "Dropping pool event stream that is lagging behind.");
+					}
+					None
+				},
+			})
+			.collect();
+	}
+
+	/// Creates a new event stream of all transactions entering and leaving the pool.
+	///
+	/// The stream is closed if it falls more than [`MAX_POOL_EVENTS_BUFFERED`] events behind.
+	pub fn pool_event_stream(&mut self) -> PoolEventStream<H> {
+		let (sink, stream) = mpsc::channel(MAX_POOL_EVENTS_BUFFERED);
+		self.event_sinks.push(sink);
+		stream
+	}
+
 	/// Creates a new watcher for given verified extrinsic.
 	///
 	/// The watcher can be used to subscribe to life-cycle events of that extrinsic.
@@ -76,8 +109,10 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 	pub fn ready(&mut self, tx: &H, old: Option<&H>) {
 		trace!(target: "txpool", "[{:?}] Ready (replaced with {:?})", tx, old);
 		self.fire(tx, |watcher| watcher.ready());
+		self.notify(PoolEvent::Ready(tx.clone()));
 		if let Some(old) = old {
 			self.fire(old, |watcher| watcher.usurped(tx.clone()));
+			self.notify(PoolEvent::Usurped(old.clone(), tx.clone()));
 		}
 	}
 
@@ -85,6 +120,7 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 	pub fn future(&mut self, tx: &H) {
 		trace!(target: "txpool", "[{:?}] Future", tx);
 		self.fire(tx, |watcher| watcher.future());
+		self.notify(PoolEvent::Future(tx.clone()));
 	}
 
 	/// Transaction was dropped from the pool because of the limit.
@@ -93,7 +129,11 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 		self.fire(tx, |watcher| match by {
 			Some(t) => watcher.usurped(t.clone()),
 			None => watcher.dropped(),
-		})
+		});
+		self.notify(match by {
+			Some(t) => PoolEvent::Usurped(tx.clone(), t.clone()),
+			None => PoolEvent::Dropped(tx.clone()),
+		});
 	}
 
 	/// Transaction was removed as invalid.
@@ -104,6 +144,13 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 			debug!(target: "txpool", "[{:?}] Extrinsic invalid", tx);
 		}
 		self.fire(tx, |watcher| watcher.invalid());
+		self.notify(PoolEvent::Invalid(tx.clone()));
+	}
+
+	/// Transaction was temporarily banned.
+	pub fn banned(&mut self, tx: &H) {
+		trace!(target: "txpool", "[{:?}] Banned", tx);
+		self.notify(PoolEvent::Banned(tx.clone()));
 	}
 
 	/// Transaction was pruned from the pool.
@@ -19,6 +19,7 @@
 use std::{
 	hash,
 	collections::HashMap,
+	path::PathBuf,
 	sync::Arc,
 };
 
@@ -93,6 +94,18 @@ pub trait ChainApi: Send + Sync {
 
 	/// Returns a block body given the block id.
 	fn block_body(&self, at: &BlockId<Self::Block>) -> Self::BodyFuture;
+
+	/// Returns the sender of a validated transaction.
+	///
+	/// The sender is only used to enforce the per-sender limits of the pool, transactions without
+	/// a sender aren't subject to them. By default it's derived from the `provides` tags of the
+	/// transaction, using the default `base_pool::SenderTags` layouts.
+	fn transaction_sender(
+		&self,
+		tx: &base::Transaction<ExHash<Self>, ExtrinsicFor<Self>>,
+	) -> Option<Vec<u8>> {
+		base::SenderTags::default().sender(&tx.provides)
+	}
 }
 
 /// Pool configuration options.
@@ -102,8 +115,14 @@ pub struct Options {
 	pub ready: base::Limit,
 	/// Future queue limits.
 	pub future: base::Limit,
+	/// Limits of transactions of a single sender in both queues.
+	pub per_sender: base::Limit,
 	/// Reject future transactions.
 	pub reject_future_transactions: bool,
+	/// Path of the journal that keeps the pool contents across restarts.
+	///
+	/// The pool contents are not persisted if `None`.
+	pub journal_path: Option<PathBuf>,
 }
 
 impl Default for Options {
@@ -117,7 +136,12 @@ impl Default for Options {
 				count: 512,
 				total_bytes: 1 * 1024 * 1024,
 			},
+			per_sender: base::Limit {
+				count: 512,
+				total_bytes: 2 * 1024 * 1024,
+			},
 			reject_future_transactions: false,
+			journal_path: None,
 		}
 	}
 }
@@ -637,6 +661,30 @@ mod tests {
 		assert_eq!(it.next(), None);
 	}
 
+	#[test]
+	fn should_drop_lagging_pool_event_streams() {
+		use crate::listener::{Listener, MAX_POOL_EVENTS_BUFFERED};
+		use sp_transaction_pool::PoolEvent;
+
+		// given
+		let mut listener = Listener::<u64, TestApi>::default();
+		let mut lagging = listener.pool_event_stream();
+		let mut polled = listener.pool_event_stream();
+		let events = MAX_POOL_EVENTS_BUFFERED as u64 + 1;
+
+		// when
+		for tx in 0..=events {
+			listener.ready(&tx, None);
+			assert_eq!(polled.try_next().unwrap(), Some(PoolEvent::Ready(tx)));
+		}
+
+		// then
+		let buffered = std::iter::from_fn(|| lagging.try_next().unwrap()).collect::<Vec<_>>();
+		assert_eq!(buffered, (0..events).map(PoolEvent::Ready).collect::<Vec<_>>());
+		listener.ready(&(events + 1), None);
+		assert_eq!(polled.try_next().unwrap(), Some(PoolEvent::Ready(events + 1)));
+	}
+
 	#[test]
 	fn should_clear_stale_transactions() {
 		// given
@@ -726,6 +774,39 @@ mod tests {
 		assert!(!pool.validated_pool.rotator().is_banned(&hash2));
 	}
 
+	#[test]
+	fn should_count_evicted_transactions() {
+		// given
+		let limit = Limit {
+			count: 1,
+			total_bytes: 1000,
+		};
+		let pool = Pool::new(Options {
+			ready: limit.clone(),
+			future: limit.clone(),
+			..Default::default()
+		}, TestApi::default().into());
+		let transfer = |from, nonce| uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(from)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce,
+		});
+
+		// when
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(1, 0))).unwrap();
+		block_on(pool.submit_one(&BlockId::Number(1), SOURCE, transfer(2, 1))).unwrap();
+		block_on(pool.submit_one(&BlockId::Number(1), SOURCE, transfer(1, 5))).unwrap();
+		block_on(pool.submit_one(&BlockId::Number(1), SOURCE, transfer(2, 6))).unwrap();
+
+		// then
+		let evictions = pool.validated_pool().take_evictions();
+		assert_eq!(evictions.get(&base::EvictionReason::ReadyLimit), Some(&1));
+		assert_eq!(evictions.get(&base::EvictionReason::FutureLimit), Some(&1));
+		assert_eq!(evictions.get(&base::EvictionReason::SenderLimit), None);
+		assert!(pool.validated_pool().take_evictions().is_empty());
+	}
+
 	#[test]
 	fn should_error_if_reject_immediately() {
 		// given
@@ -33,7 +33,7 @@ use sp_runtime::transaction_validity::{
 use sp_transaction_pool::error;
 
 use crate::future::WaitingTransaction;
-use crate::base_pool::Transaction;
+use crate::base_pool::{cmp_total_priority_per_byte, Transaction};
 
 /// An in-pool transaction reference.
 ///
@@ -291,9 +291,9 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 
 				// remove from unlocks
 				for tag in &tx.transaction.transaction.requires {
-					if let Some(hash) = self.provided_tags.get(tag) {
-						if let Some(tx) = ready.get_mut(hash) {
-							remove_item(&mut tx.unlocks, &hash);
+					if let Some(other) = self.provided_tags.get(tag) {
+						if let Some(tx_unlocking) = ready.get_mut(other) {
+							remove_item(&mut tx_unlocking.unlocks, &hash);
 						}
 					}
 				}
@@ -333,6 +333,11 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 
 			if let Some(tx) = res {
 				let unlocks = tx.unlocks;
+
+				// make sure the transaction is not returned by `get` anymore, even if it's
+				// imported again later on.
+				self.best.remove(&tx.transaction);
+
 				let tx = tx.transaction.transaction;
 
 				// prune previous transactions as well
@@ -399,9 +404,10 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 
 	/// Checks if the transaction is providing the same tags as other transactions.
 	///
-	/// In case that's true it determines if the priority of transactions that
-	/// we are about to replace is lower than the priority of the replacement transaction.
-	/// We remove/replace old transactions in case they have lower priority.
+	/// In case that's true it determines if the priority per byte of transactions that
+	/// we are about to replace is lower than the priority per byte of the replacement transaction.
+	/// We remove/replace old transactions in case they have lower priority per byte, so that
+	/// a large transaction can't replace denser ones by paying slightly more in total.
 	///
 	/// In case replacement is successful returns a list of removed transactions
 	/// and a list of hashes that are still in pool and gets unlocked by the new transaction.
@@ -423,19 +429,22 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 				return Ok((vec![], vec![]));
 			}
 
-			// now check if collective priority is lower than the replacement transaction.
-			let old_priority = {
+			// now check if collective priority per byte is lower than the replacement transaction.
+			let (old_priority, old_bytes) = {
 				let ready = self.ready.read();
 				replace_hashes
 					.iter()
 					.filter_map(|hash| ready.get(hash))
-					.fold(0u64, |total, tx|
-						total.saturating_add(tx.transaction.transaction.priority)
-					)
+					.fold((0u64, 0usize), |(priority, bytes), tx| (
+						priority.saturating_add(tx.transaction.transaction.priority),
+						bytes.saturating_add(tx.transaction.transaction.bytes),
+					))
 			};
 
-			// bail - the transaction has too low priority to replace the old ones
-			if old_priority >= tx.priority {
+			// bail - the transaction has too low priority per byte to replace the old ones
+			if cmp_total_priority_per_byte((old_priority, old_bytes), (tx.priority, tx.bytes))
+				!= cmp::Ordering::Less
+			{
 				return Err(error::Error::TooLowPriority { old: old_priority, new: tx.priority })
 			}
 
@@ -556,6 +565,7 @@ mod tests {
 			hash: id as u64,
 			priority: 1,
 			valid_till: 2,
+			validated_at: 0,
 			requires: vec![vec![1], vec![2]],
 			provides: vec![vec![3], vec![4]],
 			propagate: true,
@@ -599,6 +609,37 @@ mod tests {
 		assert_eq!(ready.get().count(), 1);
 	}
 
+	#[test]
+	fn should_replace_transaction_with_higher_priority_per_byte_only() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		let mut tx1 = tx(1);
+		tx1.requires.clear();
+		tx1.priority = 10;
+		tx1.bytes = 10;
+		let mut tx2 = tx(2);
+		tx2.requires.clear();
+		tx2.priority = 11;
+		tx2.bytes = 100;
+		let mut tx3 = tx(3);
+		tx3.requires.clear();
+		tx3.priority = 2;
+		tx3.bytes = 1;
+		import(&mut ready, tx1).unwrap();
+
+		// when
+		let err = import(&mut ready, tx2).unwrap_err();
+
+		// then
+		assert!(matches!(err, error::Error::TooLowPriority { old: 10, new: 11 }));
+		assert_eq!(ready.get().next().unwrap().hash, 1);
+
+		// a smaller transaction paying more per byte replaces it
+		import(&mut ready, tx3).unwrap();
+		assert_eq!(ready.get().count(), 1);
+		assert_eq!(ready.get().next().unwrap().hash, 3);
+	}
+
 	#[test]
 	fn should_replace_multiple_transactions_correctly() {
 		// given
@@ -657,6 +698,7 @@ mod tests {
 			hash: 5,
 			priority: 1,
 			valid_till: u64::max_value(),	// use the max_value() here for testing.
+			validated_at: 0,
 			requires: vec![tx1.provides[0].clone()],
 			provides: vec![],
 			propagate: true,
@@ -681,6 +723,48 @@ mod tests {
 		assert_eq!(it.next(), None);
 	}
 
+	#[test]
+	fn should_not_return_pruned_transaction_twice_when_imported_again() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		let mut tx1 = tx(1);
+		tx1.requires.clear();
+		tx1.provides = vec![vec![1]];
+		import(&mut ready, tx1.clone()).unwrap();
+
+		// when
+		assert_eq!(ready.prune_tags(vec![1]).len(), 1);
+		import(&mut ready, tx1).unwrap();
+
+		// then
+		assert_eq!(ready.get().map(|tx| tx.hash).collect::<Vec<_>>(), vec![1]);
+	}
+
+	#[test]
+	fn should_remove_unlocks_of_removed_subtree() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		let mut tx1 = tx(1);
+		tx1.requires.clear();
+		tx1.provides = vec![vec![1]];
+		let mut tx2 = tx(2);
+		tx2.requires = vec![vec![1]];
+		tx2.provides = vec![vec![2]];
+		import(&mut ready, tx1).unwrap();
+		import(&mut ready, tx2.clone()).unwrap();
+
+		// when
+		assert_eq!(ready.remove_subtree(&[2]).len(), 1);
+		tx2.requires.clear();
+		tx2.priority = 10;
+		import(&mut ready, tx2).unwrap();
+
+		// then
+		let mut hashes = ready.get().map(|tx| tx.hash).collect::<Vec<_>>();
+		hashes.sort();
+		assert_eq!(hashes, vec![1, 2]);
+	}
+
 	#[test]
 	fn can_report_heap_size() {
 		let mut ready = ReadyTransactions::default();
@@ -690,6 +774,7 @@ mod tests {
 			hash: 5,
 			priority: 1,
 			valid_till: u64::max_value(),	// use the max_value() here for testing.
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![],
 			propagate: true,
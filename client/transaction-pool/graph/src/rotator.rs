@@ -122,6 +122,7 @@ mod tests {
 			hash: hash.clone(),
 			priority: 5,
 			valid_till: 1,
+			validated_at: 0,
 			requires: vec![],
 			provides: vec![],
 			propagate: true,
@@ -189,6 +190,7 @@ mod tests {
 				hash,
 				priority: 5,
 				valid_till,
+				validated_at: 0,
 				requires: vec![],
 				provides: vec![],
 				propagate: true,
@@ -34,7 +34,7 @@ use sp_runtime::{
 	traits::{self, SaturatedConversion},
 	transaction_validity::{TransactionTag as Tag, ValidTransaction, TransactionSource},
 };
-use sp_transaction_pool::{error, PoolStatus};
+use sp_transaction_pool::{error, PoolStatus, PoolEventStream};
 use wasm_timer::Instant;
 use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 
@@ -76,6 +76,7 @@ impl<Hash, Ex, Error> ValidatedTransaction<Hash, Ex, Error> {
 			valid_till: at
 				.saturated_into::<u64>()
 				.saturating_add(validity.longevity),
+			validated_at: at,
 		})
 	}
 }
@@ -98,6 +99,7 @@ pub struct ValidatedPool<B: ChainApi> {
 	>>,
 	import_notification_sinks: Mutex<Vec<TracingUnboundedSender<ExHash<B>>>>,
 	rotator: PoolRotator<ExHash<B>>,
+	evictions: Mutex<HashMap<base::EvictionReason, usize>>,
 }
 
 #[cfg(not(target_os = "unknown"))]
@@ -123,12 +125,19 @@ impl<B: ChainApi> ValidatedPool<B> {
 			pool: RwLock::new(base_pool),
 			import_notification_sinks: Default::default(),
 			rotator: Default::default(),
+			evictions: Default::default(),
 		}
 	}
 
 	/// Bans given set of hashes.
 	pub fn ban(&self, now: &Instant, hashes: impl IntoIterator<Item=ExHash<B>>) {
-		self.rotator.ban(now, hashes)
+		let hashes = hashes.into_iter().collect::<Vec<_>>();
+		self.rotator.ban(now, hashes.iter().cloned());
+
+		let mut listener = self.listener.write();
+		for hash in &hashes {
+			listener.banned(hash);
+		}
 	}
 
 	/// Returns true if transaction with given hash is currently banned from the pool.
@@ -140,13 +149,21 @@ impl<B: ChainApi> ValidatedPool<B> {
 	pub fn submit<T>(&self, txs: T) -> Vec<Result<ExHash<B>, B::Error>> where
 		T: IntoIterator<Item=ValidatedTransactionFor<B>>
 	{
+		let mut senders = HashSet::new();
 		let results = txs.into_iter()
-			.map(|validated_tx| self.submit_one(validated_tx))
+			.map(|validated_tx| {
+				let sender = match validated_tx {
+					ValidatedTransaction::Valid(ref tx) => self.api.transaction_sender(tx),
+					_ => None,
+				};
+				senders.extend(sender.clone());
+				self.submit_one(validated_tx, sender)
+			})
 			.collect::<Vec<_>>();
 
 		// only enforce limits if there is at least one imported transaction
 		let removed = if results.iter().any(|res| res.is_ok()) {
-			self.enforce_limits()
+			self.enforce_limits(&senders)
 		} else {
 			Default::default()
 		};
@@ -157,11 +174,15 @@ impl<B: ChainApi> ValidatedPool<B> {
 		}).collect()
 	}
 
-	/// Submit single pre-validated transaction to the pool.
-	fn submit_one(&self, tx: ValidatedTransactionFor<B>) -> Result<ExHash<B>, B::Error> {
+	/// Submit single pre-validated transaction of the given sender to the pool.
+	fn submit_one(
+		&self,
+		tx: ValidatedTransactionFor<B>,
+		sender: Option<Vec<u8>>,
+	) -> Result<ExHash<B>, B::Error> {
 		match tx {
 			ValidatedTransaction::Valid(tx) => {
-				let imported = self.pool.write().import(tx)?;
+				let imported = self.pool.write().import_with_sender(tx, sender)?;
 
 				if let base::Imported::Ready { ref hash, .. } = imported {
 					self.import_notification_sinks.lock()
@@ -173,7 +194,7 @@ impl<B: ChainApi> ValidatedPool<B> {
 				Ok(imported.hash().clone())
 			},
 			ValidatedTransaction::Invalid(hash, err) => {
-				self.rotator.ban(&Instant::now(), std::iter::once(hash));
+				self.ban(&Instant::now(), std::iter::once(hash));
 				Err(err.into())
 			},
 			ValidatedTransaction::Unknown(hash, err) => {
@@ -183,11 +204,29 @@ impl<B: ChainApi> ValidatedPool<B> {
 		}
 	}
 
-	fn enforce_limits(&self) -> HashSet<ExHash<B>> {
-		let status = self.pool.read().status();
+	fn enforce_limits(&self, senders: &HashSet<Vec<u8>>) -> HashSet<ExHash<B>> {
 		let ready_limit = &self.options.ready;
 		let future_limit = &self.options.future;
+		let sender_limit = &self.options.per_sender;
 
+		let mut evicted = Vec::new();
+		if !senders.is_empty() {
+			let mut pool = self.pool.write();
+			evicted.extend(
+				pool.enforce_sender_limits(sender_limit, senders)
+					.into_iter()
+					.map(|tx| (base::EvictionReason::SenderLimit, tx))
+			);
+			if !evicted.is_empty() {
+				log::debug!(
+					target: "txpool",
+					"Enforcing sender limits ({}/{}kB per sender): {} dropped",
+					sender_limit.count, sender_limit.total_bytes / 1024, evicted.len(),
+				);
+			}
+		}
+
+		let status = self.pool.read().status();
 		log::debug!(target: "txpool", "Pool Status: {:?}", status);
 		if ready_limit.is_exceeded(status.ready, status.ready_bytes)
 			|| future_limit.is_exceeded(status.future, status.future_bytes)
@@ -199,29 +238,42 @@ impl<B: ChainApi> ValidatedPool<B> {
 				future_limit.count, future_limit.total_bytes / 1024,
 			);
 
-			// clean up the pool
-			let removed = {
-				let mut pool = self.pool.write();
-				let removed = pool.enforce_limits(ready_limit, future_limit)
-					.into_iter().map(|x| x.hash.clone()).collect::<HashSet<_>>();
-				// ban all removed transactions
-				self.rotator.ban(&Instant::now(), removed.iter().map(|x| x.clone()));
-				removed
-			};
+			let removed = self.pool.write().enforce_limits(ready_limit, future_limit);
 			if !removed.is_empty() {
 				log::debug!(target: "txpool", "Enforcing limits: {} dropped", removed.len());
 			}
+			evicted.extend(removed);
+		}
 
-			// run notifications
-			let mut listener = self.listener.write();
-			for h in &removed {
-				listener.dropped(h, None);
-			}
+		if evicted.is_empty() {
+			return Default::default();
+		}
 
+		// clean up the pool
+		let removed = {
+			let mut evictions = self.evictions.lock();
+			for (reason, _) in &evicted {
+				*evictions.entry(*reason).or_default() += 1;
+			}
+			let removed = evicted.into_iter().map(|(_, tx)| tx.hash.clone()).collect::<HashSet<_>>();
+			// ban all removed transactions
+			self.ban(&Instant::now(), removed.iter().map(|x| x.clone()));
 			removed
-		} else {
-			Default::default()
+		};
+
+		// run notifications
+		let mut listener = self.listener.write();
+		for h in &removed {
+			listener.dropped(h, None);
 		}
+
+		removed
+	}
+
+	/// Returns the number of transactions removed to keep the pool within its limits
+	/// since the last call, by reason.
+	pub fn take_evictions(&self) -> HashMap<base::EvictionReason, usize> {
+		std::mem::take(&mut *self.evictions.lock())
 	}
 
 	/// Import a single extrinsic and starts to watch their progress in the pool.
@@ -239,7 +291,7 @@ impl<B: ChainApi> ValidatedPool<B> {
 					.map(|_| watcher)
 			},
 			ValidatedTransaction::Invalid(hash, err) => {
-				self.rotator.ban(&Instant::now(), std::iter::once(hash));
+				self.ban(&Instant::now(), std::iter::once(hash));
 				Err(err.into())
 			},
 			ValidatedTransaction::Unknown(_, err) => Err(err.into()),
@@ -304,36 +356,39 @@ impl<B: ChainApi> ValidatedPool<B> {
 				let mut final_statuses = HashMap::new();
 				for (hash, tx_to_resubmit) in txs_to_resubmit {
 					match tx_to_resubmit {
-						ValidatedTransaction::Valid(tx) => match pool.import(tx) {
-							Ok(imported) => match imported {
-								base::Imported::Ready { promoted, failed, removed, .. } => {
-									final_statuses.insert(hash, Status::Ready);
-									for hash in promoted {
+						ValidatedTransaction::Valid(tx) => {
+							let sender = self.api.transaction_sender(&tx);
+							match pool.import_with_sender(tx, sender) {
+								Ok(imported) => match imported {
+									base::Imported::Ready { promoted, failed, removed, .. } => {
 										final_statuses.insert(hash, Status::Ready);
-									}
-									for hash in failed {
-										final_statuses.insert(hash, Status::Failed);
-									}
-									for tx in removed {
-										final_statuses.insert(tx.hash.clone(), Status::Dropped);
-									}
+										for hash in promoted {
+											final_statuses.insert(hash, Status::Ready);
+										}
+										for hash in failed {
+											final_statuses.insert(hash, Status::Failed);
+										}
+										for tx in removed {
+											final_statuses.insert(tx.hash.clone(), Status::Dropped);
+										}
+									},
+									base::Imported::Future { .. } => {
+										final_statuses.insert(hash, Status::Future);
+									},
 								},
-								base::Imported::Future { .. } => {
-									final_statuses.insert(hash, Status::Future);
+								Err(err) => {
+									// we do not want to fail if single transaction import has failed
+									// nor we do want to propagate this error, because it could tx unknown to caller
+									// => let's just notify listeners (and issue debug message)
+									log::warn!(
+										target: "txpool",
+										"[{:?}] Removing invalid transaction from update: {}",
+										hash,
+										err,
+									);
+									final_statuses.insert(hash, Status::Failed);
 								},
-							},
-							Err(err) => {
-								// we do not want to fail if single transaction import has failed
-								// nor we do want to propagate this error, because it could tx unknown to caller
-								// => let's just notify listeners (and issue debug message)
-								log::warn!(
-									target: "txpool",
-									"[{:?}] Removing invalid transaction from update: {}",
-									hash,
-									err,
-								);
-								final_statuses.insert(hash, Status::Failed);
-							},
+							}
 						},
 						ValidatedTransaction::Invalid(_, _) | ValidatedTransaction::Unknown(_, _) => {
 							final_statuses.insert(hash, Status::Failed);
@@ -513,6 +568,11 @@ impl<B: ChainApi> ValidatedPool<B> {
 		stream
 	}
 
+	/// Return an event stream of all transactions entering and leaving the pool.
+	pub fn pool_event_stream(&self) -> PoolEventStream<ExHash<B>> {
+		self.listener.write().pool_event_stream()
+	}
+
 	/// Invoked when extrinsics are broadcasted.
 	pub fn on_broadcasted(&self, propagated: HashMap<ExHash<B>, Vec<String>>) {
 		let mut listener = self.listener.write();
@@ -536,7 +596,7 @@ impl<B: ChainApi> ValidatedPool<B> {
 		log::debug!(target: "txpool", "Removing invalid transactions: {:?}", hashes);
 
 		// temporarily ban invalid transactions
-		self.rotator.ban(&Instant::now(), hashes.iter().cloned());
+		self.ban(&Instant::now(), hashes.iter().cloned());
 
 		let invalid = self.pool.write().remove_subtree(hashes);
 
@@ -555,6 +615,23 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.pool.read().ready()
 	}
 
+	/// Returns all transactions in the future queue.
+	pub fn futures(&self) -> Vec<TransactionFor<B>> {
+		self.pool.read().futures().cloned().collect()
+	}
+
+	/// Returns the source and the extrinsic of every transaction in the pool.
+	///
+	/// Ready transactions come first, in the order they would be included in a block, followed
+	/// by the future transactions.
+	pub fn extrinsics(&self) -> Vec<(TransactionSource, ExtrinsicFor<B>)> {
+		let pool = self.pool.read();
+		pool.ready()
+			.map(|tx| (tx.source, tx.data.clone()))
+			.chain(pool.futures().map(|tx| (tx.source, tx.data.clone())))
+			.collect()
+	}
+
 	/// Returns pool status.
 	pub fn status(&self) -> PoolStatus {
 		self.pool.read().status()
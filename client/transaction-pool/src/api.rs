@@ -35,6 +35,7 @@ use sp_runtime::{
 };
 use sp_transaction_pool::runtime_api::TaggedTransactionQueue;
 use sp_api::{ProvideRuntimeApi, ApiExt};
+use sc_transaction_graph::base_pool::SenderTags;
 
 use crate::error::{self, Error};
 
@@ -42,6 +43,7 @@ use crate::error::{self, Error};
 pub struct FullChainApi<Client, Block> {
 	client: Arc<Client>,
 	pool: ThreadPool,
+	sender_tags: SenderTags,
 	_marker: PhantomData<Block>,
 }
 
@@ -58,9 +60,20 @@ impl<Client, Block> FullChainApi<Client, Block> where
 				.name_prefix("txpool-verifier")
 				.create()
 				.expect("Failed to spawn verifier threads, that are critical for node operation."),
+			sender_tags: Default::default(),
 			_marker: Default::default()
 		}
 	}
+
+	/// Use the given tags to determine the senders of transactions.
+	///
+	/// The defaults only recognize 32-byte account ids, runtimes with other account ids or
+	/// indices (e.g. Ethereum transactions) have to configure their layouts here to be subject
+	/// to the per-sender limits of the pool.
+	pub fn with_sender_tags(mut self, sender_tags: SenderTags) -> Self {
+		self.sender_tags = sender_tags;
+		self
+	}
 }
 
 impl<Client, Block> sc_transaction_graph::ChainApi for FullChainApi<Client, Block> where
@@ -141,6 +154,13 @@ impl<Client, Block> sc_transaction_graph::ChainApi for FullChainApi<Client, Bloc
 			(<traits::HashFor::<Block> as traits::Hash>::hash(x), x.len())
 		})
 	}
+
+	fn transaction_sender(
+		&self,
+		tx: &sc_transaction_graph::Transaction<Self::Hash, sc_transaction_graph::ExtrinsicFor<Self>>,
+	) -> Option<Vec<u8>> {
+		self.sender_tags.sender(&tx.provides)
+	}
 }
 
 /// The transaction pool logic for light client.
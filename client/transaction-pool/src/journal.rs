@@ -0,0 +1,245 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! On-disk journal of the pool contents.
+//!
+//! The journal is a snapshot of all transactions in the pool which is rewritten periodically and
+//! when the pool is dropped. The transactions loaded on startup are resubmitted to the pool with
+//! the first new block, so they are revalidated against the best block.
+//!
+//! The journal is written by a dedicated thread, so that the file system is never accessed from
+//! the tasks maintaining the pool.
+
+use std::{
+	fs, io::{self, Write}, path::{Path, PathBuf}, sync::mpsc, thread, time::Duration,
+};
+
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use sp_runtime::transaction_validity::TransactionSource;
+use wasm_timer::Instant;
+
+/// Minimal time between two writes of the journal.
+const JOURNAL_WRITE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The version of the journal format.
+const JOURNAL_VERSION: u32 = 1;
+
+/// A transaction as it is stored in the journal.
+///
+/// The extrinsic is kept encoded, so that a single extrinsic which can't be decoded anymore,
+/// for instance after a runtime upgrade, doesn't invalidate the whole journal.
+#[derive(Encode, Decode)]
+struct JournalEntry {
+	source: TransactionSource,
+	extrinsic: Vec<u8>,
+}
+
+/// Keeps the transactions of the pool on disk.
+pub struct Journal<Ex> {
+	/// Transactions loaded from the journal which haven't been resubmitted to the pool yet.
+	///
+	/// They are written back to the journal along with the pool contents, so that they aren't
+	/// lost if the node is stopped before the first new block.
+	pending: Mutex<Option<Vec<(TransactionSource, Ex)>>>,
+	last_write: Mutex<Option<Instant>>,
+	/// Sends the contents to write to the writer thread, `None` if it couldn't be spawned.
+	writer: Mutex<Option<mpsc::Sender<Vec<JournalEntry>>>>,
+	/// The writer thread, which is joined on drop so that the last write isn't lost.
+	writer_thread: Option<thread::JoinHandle<()>>,
+}
+
+impl<Ex: Encode + Decode> Journal<Ex> {
+	/// Open the journal at `path` and load the transactions it contains.
+	///
+	/// A missing journal is treated as an empty one. A journal which can't be read is logged and
+	/// ignored, it is overwritten with the next write.
+	pub fn open(path: PathBuf) -> Self {
+		let pending = match read(&path) {
+			Ok(entries) => {
+				let total = entries.len();
+				let transactions = entries
+					.into_iter()
+					.filter_map(|entry| {
+						Ex::decode(&mut &entry.extrinsic[..])
+							.map(|xt| (entry.source, xt))
+							.ok()
+					})
+					.collect::<Vec<_>>();
+				if total > 0 {
+					log::info!(
+						target: "txpool",
+						"Loaded {} transactions from the journal at {}, {} could not be decoded",
+						transactions.len(),
+						path.display(),
+						total - transactions.len(),
+					);
+				}
+				transactions
+			},
+			Err(e) => {
+				log::warn!(
+					target: "txpool",
+					"Failed to read the transaction pool journal at {}: {}",
+					path.display(),
+					e,
+				);
+				Vec::new()
+			},
+		};
+
+		let (writer, writer_thread) = match spawn_writer(path.clone()) {
+			Ok((writer, writer_thread)) => (Some(writer), Some(writer_thread)),
+			Err(e) => {
+				log::warn!(
+					target: "txpool",
+					"Failed to spawn the writer of the transaction pool journal at {}: {}",
+					path.display(),
+					e,
+				);
+				(None, None)
+			},
+		};
+
+		Journal {
+			pending: Mutex::new(Some(pending)),
+			last_write: Mutex::new(None),
+			writer: Mutex::new(writer),
+			writer_thread,
+		}
+	}
+
+	/// Take the transactions loaded from the journal.
+	///
+	/// Returns `None` if they were taken already.
+	pub fn take_pending(&self) -> Option<Vec<(TransactionSource, Ex)>> {
+		self.pending.lock().take()
+	}
+
+	/// Returns true if the journal wasn't written within the last `JOURNAL_WRITE_INTERVAL`.
+	pub fn should_write(&self) -> bool {
+		self.last_write.lock()
+			.map_or(true, |last_write| last_write.elapsed() >= JOURNAL_WRITE_INTERVAL)
+	}
+
+	/// Replace the contents of the journal with the given transactions and the loaded ones
+	/// which haven't been resubmitted yet.
+	///
+	/// The journal is written in the background. If several writes are queued, only the most
+	/// recent contents are written.
+	pub fn write(&self, transactions: &[(TransactionSource, Ex)]) {
+		*self.last_write.lock() = Some(Instant::now());
+
+		let pending = self.pending.lock();
+		let entries = transactions
+			.iter()
+			.chain(pending.iter().flatten())
+			.map(|(source, xt)| JournalEntry { source: *source, extrinsic: xt.encode() })
+			.collect::<Vec<_>>();
+		drop(pending);
+
+		if let Some(writer) = self.writer.lock().as_ref() {
+			if writer.send(entries).is_err() {
+				log::warn!(target: "txpool", "The writer of the transaction pool journal has stopped");
+			}
+		}
+	}
+}
+
+impl<Ex> Drop for Journal<Ex> {
+	fn drop(&mut self) {
+		// Closing the channel stops the writer thread once the queued contents are written.
+		self.writer.lock().take();
+		if let Some(writer_thread) = self.writer_thread.take() {
+			if writer_thread.join().is_err() {
+				log::warn!(target: "txpool", "The writer of the transaction pool journal panicked");
+			}
+		}
+	}
+}
+
+/// Spawn the thread which writes the contents it receives to the journal at `path`.
+fn spawn_writer(
+	path: PathBuf,
+) -> io::Result<(mpsc::Sender<Vec<JournalEntry>>, thread::JoinHandle<()>)> {
+	let (sender, receiver) = mpsc::channel::<Vec<JournalEntry>>();
+	let writer_thread = thread::Builder::new()
+		.name("txpool-journal".into())
+		.spawn(move || {
+			while let Ok(mut entries) = receiver.recv() {
+				// Only the most recent contents of the pool are worth writing.
+				while let Ok(newer) = receiver.try_recv() {
+					entries = newer;
+				}
+
+				match write(&path, &entries) {
+					Ok(()) => log::debug!(
+						target: "txpool",
+						"Wrote {} transactions to the journal",
+						entries.len(),
+					),
+					Err(e) => log::warn!(
+						target: "txpool",
+						"Failed to write the transaction pool journal to {}: {}",
+						path.display(),
+						e,
+					),
+				}
+			}
+		})?;
+	Ok((sender, writer_thread))
+}
+
+fn read(path: &Path) -> io::Result<Vec<JournalEntry>> {
+	let data = match fs::read(path) {
+		Ok(data) => data,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(e) => return Err(e),
+	};
+	let invalid_data = |e: codec::Error| io::Error::new(io::ErrorKind::InvalidData, e.what());
+
+	let input = &mut &data[..];
+	let version = u32::decode(input).map_err(invalid_data)?;
+	if version != JOURNAL_VERSION {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("unsupported journal version {}", version),
+		));
+	}
+	Vec::<JournalEntry>::decode(input).map_err(invalid_data)
+}
+
+/// Write the journal to a temporary file first, so that a crash never leaves a partially
+/// written journal behind.
+///
+/// The temporary file is synced before it replaces the journal. Otherwise the file system may
+/// persist the rename before the data, leaving an empty or truncated journal after a power loss.
+fn write(path: &Path, entries: &[JournalEntry]) -> io::Result<()> {
+	let mut data = JOURNAL_VERSION.encode();
+	entries.encode_to(&mut data);
+
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent)?;
+	}
+	let tmp_path = path.with_extension("tmp");
+	let mut file = fs::File::create(&tmp_path)?;
+	file.write_all(&data)?;
+	file.sync_all()?;
+	drop(file);
+	fs::rename(&tmp_path, path)
+}
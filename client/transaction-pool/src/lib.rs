@@ -23,6 +23,7 @@
 #![warn(unused_extern_crates)]
 
 mod api;
+mod journal;
 mod revalidation;
 mod metrics;
 
@@ -40,17 +41,19 @@ use parking_lot::Mutex;
 
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block as BlockT, NumberFor, AtLeast32Bit, Extrinsic, Zero},
+	traits::{Block as BlockT, NumberFor, AtLeast32Bit, Extrinsic, Zero, One},
 };
 use sp_transaction_pool::{
 	TransactionPool, PoolStatus, ImportNotificationStream, TxHash, TransactionFor,
 	TransactionStatusStreamFor, MaintainedTransactionPool, PoolFuture, ChainEvent,
-	TransactionSource,
+	TransactionSource, PoolEventStream,
 };
 use wasm_timer::Instant;
 
+use sc_transaction_graph::base_pool::EvictionReason;
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use crate::metrics::MetricsLink as PrometheusMetrics;
+use crate::journal::Journal;
 
 type BoxedReadyIterator<Hash, Data> = Box<dyn Iterator<Item=Arc<sc_transaction_graph::base_pool::Transaction<Hash, Data>>> + Send>;
 
@@ -58,6 +61,8 @@ type ReadyIteratorFor<PoolApi> = BoxedReadyIterator<sc_transaction_graph::ExHash
 
 type PolledIterator<PoolApi> = Pin<Box<dyn Future<Output=ReadyIteratorFor<PoolApi>> + Send>>;
 
+type JournalFor<PoolApi> = Journal<sc_transaction_graph::ExtrinsicFor<PoolApi>>;
+
 /// Basic implementation of transaction pool that can be customized by providing PoolApi.
 pub struct BasicPool<PoolApi, Block>
 	where
@@ -70,6 +75,7 @@ pub struct BasicPool<PoolApi, Block>
 	revalidation_queue: Arc<revalidation::RevalidationQueue<PoolApi>>,
 	ready_poll: Arc<Mutex<ReadyPoll<ReadyIteratorFor<PoolApi>, Block>>>,
 	metrics: PrometheusMetrics,
+	journal: Option<Arc<JournalFor<PoolApi>>>,
 }
 
 struct ReadyPoll<T, Block: BlockT> {
@@ -176,6 +182,7 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 				revalidation_strategy: Arc::new(Mutex::new(RevalidationStrategy::Always)),
 				ready_poll: Default::default(),
 				metrics: Default::default(),
+				journal: None,
 			},
 			background_task,
 			notifier,
@@ -190,6 +197,7 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 		prometheus: Option<&PrometheusRegistry>,
 		revalidation_type: RevalidationType,
 	) -> (Self, Option<Pin<Box<dyn Future<Output=()> + Send>>>) {
+		let journal = options.journal_path.clone().map(|path| Arc::new(Journal::open(path)));
 		let pool = Arc::new(sc_transaction_graph::Pool::new(options, pool_api.clone()));
 		let (revalidation_queue, background_task) = match revalidation_type {
 			RevalidationType::Light => (revalidation::RevalidationQueue::new(pool_api.clone(), pool.clone()), None),
@@ -212,6 +220,7 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 				)),
 				ready_poll: Default::default(),
 				metrics: PrometheusMetrics::new(prometheus),
+				journal,
 			},
 			background_task,
 		)
@@ -223,6 +232,18 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 	}
 }
 
+impl<PoolApi, Block> Drop for BasicPool<PoolApi, Block>
+	where
+		Block: BlockT,
+		PoolApi: sc_transaction_graph::ChainApi<Block=Block, Hash=Block::Hash>,
+{
+	fn drop(&mut self) {
+		if let Some(journal) = self.journal.as_ref() {
+			journal.write(&self.pool.validated_pool().extrinsics());
+		}
+	}
+}
+
 impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 	where
 		Block: BlockT,
@@ -249,6 +270,7 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 			let tx_count = xts.len();
 			let res = pool.submit_at(&at, source, xts, false).await;
 			metrics.report(|metrics| metrics.validations_finished.inc_by(tx_count as u64));
+			report_evictions(&pool, &metrics);
 			res
 		}.boxed()
 	}
@@ -269,6 +291,7 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 			let res = pool.submit_one(&at, source, xt).await;
 
 			metrics.report(|metrics| metrics.validations_finished.inc());
+			report_evictions(&pool, &metrics);
 			res
 
 		}.boxed()
@@ -292,6 +315,7 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 				.await;
 
 			metrics.report(|metrics| metrics.validations_finished.inc());
+			report_evictions(&pool, &metrics);
 
 			result
 		}.boxed()
@@ -311,6 +335,14 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 		self.pool.validated_pool().import_notification_stream()
 	}
 
+	fn pool_event_stream(&self) -> PoolEventStream<TxHash<Self>> {
+		self.pool.validated_pool().pool_event_stream()
+	}
+
+	fn transaction_sender(&self, tx: &Self::InPoolTransaction) -> Option<Vec<u8>> {
+		self.api.transaction_sender(tx)
+	}
+
 	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
 		self.pool.hash_of(xt)
 	}
@@ -343,6 +375,10 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 	fn ready(&self) -> ReadyIteratorFor<PoolApi> {
 		Box::new(self.pool.validated_pool().ready())
 	}
+
+	fn futures(&self) -> Vec<Arc<Self::InPoolTransaction>> {
+		self.pool.validated_pool().futures()
+	}
 }
 
 #[cfg_attr(test, derive(Debug))]
@@ -436,7 +472,14 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 {
 	fn maintain(&self, event: ChainEvent<Self::Block>) -> Pin<Box<dyn Future<Output=()> + Send>> {
 		match event {
-			ChainEvent::NewBlock { id, retracted, .. } => {
+			ChainEvent::NewBlock { id, retracted, is_new_best, .. } => {
+				// The transactions of a block on a fork are still valid on the best chain, the
+				// pool is only maintained once the fork becomes the best chain.
+				if !is_new_best {
+					log::trace!(target: "txpool", "Skipping chain event - {:?} is not the new best block", id);
+					return Box::pin(ready(()));
+				}
+
 				let id = id.clone();
 				let pool = self.pool.clone();
 				let api = self.api.clone();
@@ -458,25 +501,34 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 				let retracted = retracted.clone();
 				let revalidation_queue = self.revalidation_queue.clone();
 				let ready_poll = self.ready_poll.clone();
+				let journal = self.journal.clone();
+				let metrics = self.metrics.clone();
 
 				async move {
-					// We don't query block if we won't prune anything
-					if !pool.validated_pool().status().is_empty() {
-						let hashes = api.block_body(&id).await
-							.unwrap_or_else(|e| {
-								log::warn!("Prune known transactions: error request {:?}!", e);
-								None
-							})
-							.unwrap_or_default()
-							.into_iter()
-							.map(|tx| pool.hash_of(&tx))
-							.collect::<Vec<_>>();
-
-						if let Err(e) = pool.prune_known(&id, &hashes) {
-							log::error!("Cannot prune known in the pool {:?}!", e);
+					// The blocks enacted along with the new best block were skipped when they were
+					// imported on a fork, so their transactions are pruned first.
+					let first_enacted = match retracted.first() {
+						Some(hash) => api.block_id_to_number(&BlockId::hash(hash.clone())).ok().flatten(),
+						None => None,
+					};
+					if let Some(mut number) = first_enacted {
+						while number < block_number {
+							prune_known_block(&pool, &api, &BlockId::Number(number)).await;
+							number += One::one();
 						}
 					}
 
+					prune_known_block(&pool, &api, &id).await;
+
+					// Cull the transactions which outlived their longevity and lift expired bans.
+					if let Err(e) = pool.validated_pool().clear_stale(&id) {
+						log::error!("Cannot clear stale transactions in the pool {:?}!", e);
+					}
+
+					if let Some(transactions) = journal.as_ref().and_then(|j| j.take_pending()) {
+						resubmit_journaled(&pool, &id, transactions).await;
+					}
+
 					let extra_pool = pool.clone();
 					// After #5200 lands, this arguably might be moved to the handler of "all blocks notification".
 					ready_poll.lock().trigger(block_number, move || Box::new(extra_pool.validated_pool().ready()));
@@ -520,6 +572,11 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 					}
 
 					revalidation_strategy.lock().clear();
+					report_evictions(&pool, &metrics);
+
+					if let Some(journal) = journal.as_ref().filter(|j| j.should_write()) {
+						journal.write(&pool.validated_pool().extrinsics());
+					}
 				}.boxed()
 			}
 			ChainEvent::Finalized { hash } => {
@@ -537,3 +594,87 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 		}
 	}
 }
+
+/// Prune the transactions included in the given block from the pool.
+async fn prune_known_block<PoolApi: sc_transaction_graph::ChainApi>(
+	pool: &sc_transaction_graph::Pool<PoolApi>,
+	api: &PoolApi,
+	id: &BlockId<PoolApi::Block>,
+) {
+	// We don't query block if we won't prune anything
+	if pool.validated_pool().status().is_empty() {
+		return;
+	}
+
+	let hashes = api.block_body(id).await
+		.unwrap_or_else(|e| {
+			log::warn!("Prune known transactions: error request {:?}!", e);
+			None
+		})
+		.unwrap_or_default()
+		.into_iter()
+		.map(|tx| pool.hash_of(&tx))
+		.collect::<Vec<_>>();
+
+	if let Err(e) = pool.prune_known(id, &hashes) {
+		log::error!("Cannot prune known in the pool {:?}!", e);
+	}
+}
+
+/// Report the transactions removed from the pool to keep it within its limits.
+fn report_evictions<PoolApi: sc_transaction_graph::ChainApi>(
+	pool: &sc_transaction_graph::Pool<PoolApi>,
+	metrics: &PrometheusMetrics,
+) {
+	let evictions = pool.validated_pool().take_evictions();
+	metrics.report(|metrics| {
+		for (reason, count) in evictions {
+			let reason = match reason {
+				EvictionReason::ReadyLimit => "ready_limit",
+				EvictionReason::FutureLimit => "future_limit",
+				EvictionReason::SenderLimit => "sender_limit",
+			};
+			metrics.evictions.with_label_values(&[reason]).inc_by(count as u64);
+		}
+	});
+}
+
+/// Resubmit the transactions loaded from the journal at the given block.
+///
+/// The transactions are revalidated, so stale, invalid and banned ones are dropped.
+async fn resubmit_journaled<PoolApi: sc_transaction_graph::ChainApi>(
+	pool: &sc_transaction_graph::Pool<PoolApi>,
+	at: &BlockId<PoolApi::Block>,
+	transactions: Vec<(TransactionSource, sc_transaction_graph::ExtrinsicFor<PoolApi>)>,
+) {
+	if transactions.is_empty() {
+		return;
+	}
+
+	let total = transactions.len();
+	// Only local transactions keep their source, everything else is resubmitted as external like
+	// the transactions of retracted blocks.
+	let (local, external): (Vec<_>, Vec<_>) = transactions
+		.into_iter()
+		.partition(|(source, _)| *source == TransactionSource::Local);
+
+	let mut imported = 0;
+	let batches = vec![(TransactionSource::Local, local), (TransactionSource::External, external)];
+	for (source, batch) in batches {
+		let xts = batch.into_iter().map(|(_, xt)| xt).collect::<Vec<_>>();
+		match pool.submit_at(at, source, xts, false).await {
+			Ok(results) => imported += results.iter().filter(|result| result.is_ok()).count(),
+			Err(e) => log::debug!(
+				target: "txpool",
+				"[{:?}] Error re-submitting journaled transactions: {:?}", at, e,
+			),
+		}
+	}
+
+	log::info!(
+		target: "txpool",
+		"Resubmitted {} of {} transactions from the journal",
+		imported,
+		total,
+	);
+}
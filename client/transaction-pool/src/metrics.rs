@@ -20,7 +20,7 @@
 
 use std::sync::Arc;
 
-use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+use prometheus_endpoint::{register, Counter, CounterVec, Opts, PrometheusError, Registry, U64};
 
 #[derive(Clone, Default)]
 pub struct MetricsLink(Arc<Option<Metrics>>);
@@ -48,6 +48,7 @@ pub struct Metrics {
 	pub validations_scheduled: Counter<U64>,
 	pub validations_finished: Counter<U64>,
 	pub validations_invalid: Counter<U64>,
+	pub evictions: CounterVec<U64>,
 }
 
 impl Metrics {
@@ -74,6 +75,16 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			evictions: register(
+				CounterVec::new(
+					Opts::new(
+						"sub_txpool_evictions",
+						"Total number of transactions that were removed from the pool to keep it within its limits",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
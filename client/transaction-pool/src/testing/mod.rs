@@ -19,3 +19,4 @@
 //! Tests for top-level transaction pool api
 
 mod pool;
+mod simulation;
@@ -248,6 +248,68 @@ fn should_resubmit_from_retracted_during_maintenance() {
 	assert_eq!(pool.status().ready, 1);
 }
 
+#[test]
+fn should_not_prune_transactions_of_blocks_which_are_not_best() {
+	let xt = uxt(Alice, 209);
+	let fork_hash = Hash::random();
+
+	let (pool, _guard, _notifier) = maintained_pool();
+
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, xt.clone())).expect("1. Imported");
+	assert_eq!(pool.status().ready, 1);
+
+	pool.api.push_fork_block(fork_hash, vec![xt.clone()]);
+
+	let event = ChainEvent::NewBlock {
+		id: BlockId::Hash(fork_hash),
+		is_new_best: false,
+		retracted: vec![],
+		header: header(1),
+	};
+
+	block_on(pool.maintain(event));
+	assert_eq!(pool.status().ready, 1);
+}
+
+#[test]
+fn should_prune_transactions_of_enacted_blocks() {
+	let xt1 = uxt(Alice, 209);
+	let xt2 = uxt(Alice, 210);
+	let retracted_hash = Hash::random();
+
+	let (pool, _guard, _notifier) = maintained_pool();
+
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, xt1.clone())).expect("1. Imported");
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, xt2.clone())).expect("2. Imported");
+	assert_eq!(pool.status().ready, 2);
+
+	// The fork containing both blocks was imported while another block was best.
+	pool.api.push_fork_block(retracted_hash, vec![]);
+	pool.api.push_block(1, vec![xt1.clone()]);
+	pool.api.push_block(2, vec![xt2.clone()]);
+
+	block_on(pool.maintain(block_event_with_retracted(2, vec![retracted_hash])));
+	assert_eq!(pool.status().ready, 0);
+}
+
+#[test]
+fn should_ban_stale_transactions_during_maintenance() {
+	let xt = uxt(Alice, 209);
+
+	let (pool, _guard, _notifier) = maintained_pool();
+	pool.api.set_valid_modifier(Box::new(|v: &mut ValidTransaction| v.longevity = 1));
+
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, xt.clone())).expect("1. Imported");
+	assert_eq!(pool.status().ready, 1);
+
+	pool.api.push_block(1, vec![]);
+	pool.api.push_block(2, vec![]);
+
+	block_on(pool.maintain(block_event(2)));
+	assert_eq!(pool.status().ready, 0);
+	assert!(block_on(pool.submit_one(&BlockId::number(2), SOURCE, xt)).is_err());
+}
+
 #[test]
 fn should_not_retain_invalid_hashes_from_retracted() {
 	let xt = uxt(Alice, 209);
@@ -705,3 +767,64 @@ fn should_not_accept_old_signatures() {
 		"Should be invalid transaction with bad proof",
 	);
 }
+
+fn journaled_pool(
+	api: Arc<TestApi>,
+	journal_path: std::path::PathBuf,
+) -> BasicPool<TestApi, Block> {
+	let options = txpool::Options { journal_path: Some(journal_path), ..Default::default() };
+	BasicPool::new(options, api, None).0
+}
+
+#[test]
+fn should_restore_transactions_from_journal() {
+	let dir = tempfile::tempdir().unwrap();
+	let journal_path = dir.path().join("txpool-journal");
+	// Signatures are randomized, so every extrinsic is created only once.
+	let alice = (209..212).map(|nonce| uxt(Alice, nonce)).collect::<Vec<_>>();
+	let bob = uxt(Bob, 0);
+
+	{
+		let pool = journaled_pool(Arc::new(TestApi::with_alice_nonce(209)), journal_path.clone());
+		for xt in &alice {
+			block_on(pool.submit_one(&BlockId::number(0), SOURCE, xt.clone())).unwrap();
+		}
+		block_on(pool.submit_one(&BlockId::number(0), TransactionSource::Local, bob.clone()))
+			.unwrap();
+		assert_eq!(pool.status().ready, 4);
+	}
+
+	// Alice's first transaction got included and her last one was banned in the meantime.
+	let api = Arc::new(TestApi::with_alice_nonce(210));
+	api.add_invalid(&alice[0]);
+	let pool = journaled_pool(api.clone(), journal_path);
+	let banned = pool.hash_of(&alice[2]);
+	pool.pool().validated_pool().ban(&Instant::now(), vec![banned]);
+	assert_eq!(pool.status().ready, 0);
+
+	block_on(pool.maintain(block_event(1)));
+
+	let mut ready = pool.ready().map(|tx| tx.data.clone()).collect::<Vec<_>>();
+	ready.sort_by_key(|xt| xt.transfer().nonce);
+	assert_eq!(ready, vec![bob, alice[1].clone()]);
+	assert_eq!(pool.status().future, 0);
+}
+
+#[test]
+fn should_keep_journal_until_transactions_are_resubmitted() {
+	let dir = tempfile::tempdir().unwrap();
+	let journal_path = dir.path().join("txpool-journal");
+	let api = Arc::new(TestApi::with_alice_nonce(209));
+
+	{
+		let pool = journaled_pool(api.clone(), journal_path.clone());
+		block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 209))).unwrap();
+	}
+
+	// The node is stopped again before it sees a new block.
+	drop(journaled_pool(api.clone(), journal_path.clone()));
+
+	let pool = journaled_pool(api, journal_path);
+	block_on(pool.maintain(block_event(1)));
+	assert_eq!(pool.status().ready, 1);
+}
@@ -0,0 +1,104 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Randomized scenarios of reorgs, revalidation and bans, replayable from their seed.
+
+use crate::*;
+use std::sync::Arc;
+use substrate_test_runtime_client::runtime::Block;
+use substrate_test_runtime_transaction_pool::simulation::{
+	self, Action, Config, Outcome, Scenario, SimulatedChain,
+};
+
+/// Creates a pool which revalidates transactions during maintenance rather than in a background
+/// task, so that a scenario plays out the same way on every run.
+fn deterministic_pool(chain: Arc<SimulatedChain>) -> BasicPool<SimulatedChain, Block> {
+	let pool = Arc::new(sc_transaction_graph::Pool::new(Default::default(), chain.clone()));
+	let revalidation_queue = revalidation::RevalidationQueue::new(chain.clone(), pool.clone());
+	BasicPool {
+		api: chain,
+		pool,
+		revalidation_queue: Arc::new(revalidation_queue),
+		revalidation_strategy: Arc::new(Mutex::new(RevalidationStrategy::Always)),
+		ready_poll: Default::default(),
+		metrics: Default::default(),
+		journal: None,
+	}
+}
+
+fn simulate(seed: u64, config: &Config) -> Outcome {
+	let scenario = Scenario::generate(seed, config);
+	let chain = Arc::new(SimulatedChain::new(config.longevity));
+	let pool = deterministic_pool(chain.clone());
+
+	simulation::run(&pool, &chain, &scenario).unwrap_or_else(|failure| panic!("{}", failure))
+}
+
+fn simulate_seeds(config: &Config) -> Outcome {
+	(0..50).map(|seed| simulate(seed, config)).fold(Outcome::default(), |total, outcome| Outcome {
+		reorgs: total.reorgs + outcome.reorgs,
+		banned_submissions: total.banned_submissions + outcome.banned_submissions,
+		culled: total.culled + outcome.culled,
+	})
+}
+
+#[test]
+fn scenarios_are_replayable() {
+	let config = Config::default();
+	let scenario = Scenario::generate(42, &config);
+
+	assert_eq!(scenario.actions, Scenario::generate(42, &config).actions);
+	assert_ne!(scenario.actions, Scenario::generate(43, &config).actions);
+	assert!(scenario.actions.iter().any(|action| matches!(action, Action::Import { .. })));
+	assert!(scenario.actions.iter().any(|action| matches!(action, Action::Submit { .. })));
+}
+
+#[test]
+fn should_keep_pool_consistent_on_the_best_chain() {
+	let config = Config { fork_percent: 0, ..Default::default() };
+	assert_eq!(simulate_seeds(&config).reorgs, 0);
+}
+
+#[test]
+fn should_keep_pool_consistent_across_reorgs() {
+	assert!(simulate_seeds(&Config::default()).reorgs > 0);
+}
+
+#[test]
+fn should_keep_pool_consistent_across_deep_reorgs() {
+	let config = Config {
+		accounts: 2,
+		fork_percent: 50,
+		max_fork_depth: 8,
+		..Default::default()
+	};
+	assert!(simulate_seeds(&config).reorgs > 0);
+}
+
+#[test]
+fn should_keep_banned_transactions_out_of_the_pool() {
+	let config = Config {
+		gap_percent: 30,
+		resubmit_percent: 30,
+		longevity: 2,
+		..Default::default()
+	};
+	let outcome = simulate_seeds(&config);
+	assert!(outcome.culled > 0);
+	assert!(outcome.banned_submissions > 0);
+}
@@ -0,0 +1,457 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Assets pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::{RawOrigin, Module as System, EventRecord};
+use frame_benchmarking::{benchmarks, account};
+use frame_support::dispatch::Dispatchable;
+use sp_runtime::traits::Bounded;
+
+use crate::Module as Assets;
+
+const SEED: u32 = 0;
+const MAX_ACCOUNTS: u32 = 1_000;
+const MAX_APPROVALS: u32 = 1_000;
+const MAX_USER_INDEX: u32 = 1_000;
+
+fn assert_last_event<T: Trait>(generic_event: <T as Trait>::Event) {
+	let events = System::<T>::events();
+	let system_event: <T as frame_system::Trait>::Event = generic_event.into();
+	// compare to the last event record
+	let EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let caller: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+// Creates the default asset, owned by and minted to `owner`, and returns the lookup of `owner`.
+fn create_default_minted_asset<T: Trait>(
+	owner: &T::AccountId,
+	amount: T::Balance,
+) -> Result<<T::Lookup as StaticLookup>::Source, &'static str> {
+	let owner_lookup = T::Lookup::unlookup(owner.clone());
+	Assets::<T>::create(
+		RawOrigin::Signed(owner.clone()).into(),
+		Default::default(),
+		owner_lookup.clone(),
+		1u32.into(),
+	)?;
+	Assets::<T>::mint(
+		RawOrigin::Signed(owner.clone()).into(),
+		Default::default(),
+		owner_lookup.clone(),
+		amount,
+	)?;
+	Ok(owner_lookup)
+}
+
+// Mints some of the default asset to `n` new accounts.
+fn add_accounts<T: Trait>(issuer: &T::AccountId, n: u32) -> Result<(), &'static str> {
+	for i in 0..n {
+		let target = account::<T::AccountId>("member", i, SEED);
+		let target_lookup = T::Lookup::unlookup(target);
+		Assets::<T>::mint(
+			RawOrigin::Signed(issuer.clone()).into(),
+			Default::default(),
+			target_lookup,
+			100u32.into(),
+		)?;
+	}
+	Ok(())
+}
+
+// Approves `n` new delegates to transfer some of the default asset of `owner`.
+fn add_approvals<T: Trait>(owner: &T::AccountId, n: u32) -> Result<(), &'static str> {
+	for i in 0..n {
+		let delegate = account::<T::AccountId>("approval", i, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate);
+		Assets::<T>::approve_transfer(
+			RawOrigin::Signed(owner.clone()).into(),
+			Default::default(),
+			delegate_lookup,
+			100u32.into(),
+		)?;
+	}
+	Ok(())
+}
+
+benchmarks! {
+	_ {
+		let u in 0 .. MAX_USER_INDEX => ();
+	}
+
+	create {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), caller_lookup, 1u32.into())
+	verify {
+		assert_last_event::<T>(RawEvent::Created(Default::default(), caller.clone(), caller).into());
+	}
+
+	force_create {
+		let u in ...;
+
+		let caller: T::AccountId = account("caller", u, SEED);
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::force_create(Default::default(), caller_lookup, 1u32.into());
+	}: { call.dispatch(origin)? }
+	verify {
+		assert_last_event::<T>(RawEvent::ForceCreated(Default::default(), caller).into());
+	}
+
+	destroy {
+		let a in 0 .. MAX_ACCOUNTS;
+		let p in 0 .. MAX_APPROVALS;
+
+		let caller = funded_account::<T>("caller", 0);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		add_accounts::<T>(&caller, a)?;
+		add_approvals::<T>(&caller, p)?;
+		let witness = Asset::<T>::get(T::AssetId::default())
+			.map(|d| DestroyWitness { accounts: d.accounts, approvals: d.approvals })
+			.ok_or("asset should exist")?;
+	}: _(RawOrigin::Signed(caller), Default::default(), witness)
+	verify {
+		assert_last_event::<T>(RawEvent::Destroyed(Default::default()).into());
+	}
+
+	force_destroy {
+		let a in 0 .. MAX_ACCOUNTS;
+		let p in 0 .. MAX_APPROVALS;
+
+		let caller = funded_account::<T>("caller", 0);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		add_accounts::<T>(&caller, a)?;
+		add_approvals::<T>(&caller, p)?;
+		let witness = Asset::<T>::get(T::AssetId::default())
+			.map(|d| DestroyWitness { accounts: d.accounts, approvals: d.approvals })
+			.ok_or("asset should exist")?;
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::force_destroy(Default::default(), witness);
+	}: { call.dispatch(origin)? }
+	verify {
+		assert_last_event::<T>(RawEvent::Destroyed(Default::default()).into());
+	}
+
+	mint {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		let caller_lookup = create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		let amount = T::Balance::from(100u32);
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), caller_lookup, amount)
+	verify {
+		assert_last_event::<T>(RawEvent::Issued(Default::default(), caller, amount).into());
+	}
+
+	burn {
+		let u in ...;
+
+		let amount = T::Balance::from(100u32);
+		let caller = funded_account::<T>("caller", u);
+		let caller_lookup = create_default_minted_asset::<T>(&caller, amount)?;
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), caller_lookup, amount)
+	verify {
+		assert_last_event::<T>(RawEvent::Burned(Default::default(), caller, amount).into());
+	}
+
+	// Worst case: the sender is reaped and the recipient is created.
+	transfer {
+		let u in ...;
+
+		let amount = T::Balance::from(100u32);
+		let caller = funded_account::<T>("caller", u);
+		create_default_minted_asset::<T>(&caller, amount)?;
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), target_lookup, amount)
+	verify {
+		assert_last_event::<T>(RawEvent::Transferred(Default::default(), caller, target, amount).into());
+	}
+
+	// Worst case: the source is reaped and the recipient is created.
+	force_transfer {
+		let u in ...;
+
+		let amount = T::Balance::from(100u32);
+		let caller = funded_account::<T>("caller", u);
+		let caller_lookup = create_default_minted_asset::<T>(&caller, amount)?;
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), caller_lookup, target_lookup, amount)
+	verify {
+		assert_last_event::<T>(
+			RawEvent::ForceTransferred(Default::default(), caller, target, amount).into()
+		);
+	}
+
+	freeze {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		let caller_lookup = create_default_minted_asset::<T>(&caller, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), caller_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::Frozen(Default::default(), caller).into());
+	}
+
+	thaw {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		let caller_lookup = create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		Assets::<T>::freeze(
+			RawOrigin::Signed(caller.clone()).into(),
+			Default::default(),
+			caller_lookup.clone(),
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), caller_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::Thawed(Default::default(), caller).into());
+	}
+
+	freeze_asset {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller), Default::default())
+	verify {
+		assert_last_event::<T>(RawEvent::AssetFrozen(Default::default()).into());
+	}
+
+	thaw_asset {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		Assets::<T>::freeze_asset(RawOrigin::Signed(caller.clone()).into(), Default::default())?;
+	}: _(RawOrigin::Signed(caller), Default::default())
+	verify {
+		assert_last_event::<T>(RawEvent::AssetThawed(Default::default()).into());
+	}
+
+	// Worst case: both the asset deposit and the metadata deposit are moved.
+	transfer_ownership {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		let limit = T::StringLimit::get() as usize;
+		Assets::<T>::set_metadata(
+			RawOrigin::Signed(caller.clone()).into(),
+			Default::default(),
+			vec![0u8; limit],
+			vec![0u8; limit],
+			12,
+		)?;
+		let target = funded_account::<T>("target", 0);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller), Default::default(), target_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::OwnerChanged(Default::default(), target).into());
+	}
+
+	set_team {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		let target0: T::AccountId = account("target", 0, SEED);
+		let target1: T::AccountId = account("target", 1, SEED);
+		let target2: T::AccountId = account("target", 2, SEED);
+	}: _(
+		RawOrigin::Signed(caller),
+		Default::default(),
+		T::Lookup::unlookup(target0.clone()),
+		T::Lookup::unlookup(target1.clone()),
+		T::Lookup::unlookup(target2.clone())
+	)
+	verify {
+		assert_last_event::<T>(RawEvent::TeamChanged(Default::default(), target0, target1, target2).into());
+	}
+
+	force_asset_status {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target);
+		let origin = T::ForceOrigin::successful_origin();
+		let call = Call::<T>::force_asset_status(
+			Default::default(),
+			target_lookup.clone(),
+			target_lookup.clone(),
+			target_lookup.clone(),
+			target_lookup,
+			100u32.into(),
+			true,
+		);
+	}: { call.dispatch(origin)? }
+	verify {
+		assert_last_event::<T>(RawEvent::AssetStatusChanged(Default::default()).into());
+	}
+
+	set_metadata {
+		let n in 0 .. T::StringLimit::get();
+		let s in 0 .. T::StringLimit::get();
+
+		let name = vec![0u8; n as usize];
+		let symbol = vec![0u8; s as usize];
+		let decimals = 12;
+
+		let caller = funded_account::<T>("caller", 0);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller), Default::default(), name.clone(), symbol.clone(), decimals)
+	verify {
+		assert_last_event::<T>(RawEvent::MetadataSet(Default::default(), name, symbol, decimals).into());
+	}
+
+	clear_metadata {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		let limit = T::StringLimit::get() as usize;
+		Assets::<T>::set_metadata(
+			RawOrigin::Signed(caller.clone()).into(),
+			Default::default(),
+			vec![0u8; limit],
+			vec![0u8; limit],
+			12,
+		)?;
+	}: _(RawOrigin::Signed(caller), Default::default())
+	verify {
+		assert_last_event::<T>(RawEvent::MetadataCleared(Default::default()).into());
+	}
+
+	approve_transfer {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let amount = T::Balance::from(100u32);
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), delegate_lookup, amount)
+	verify {
+		assert_last_event::<T>(
+			RawEvent::ApprovedTransfer(Default::default(), caller, delegate, amount).into()
+		);
+	}
+
+	// Worst case: the owner is reaped, the destination is created and the approval is removed.
+	transfer_approved {
+		let u in ...;
+
+		let amount = T::Balance::from(100u32);
+		let owner = funded_account::<T>("owner", u);
+		let owner_lookup = create_default_minted_asset::<T>(&owner, amount)?;
+
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		Assets::<T>::approve_transfer(
+			RawOrigin::Signed(owner.clone()).into(),
+			Default::default(),
+			delegate_lookup,
+			amount,
+		)?;
+
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let dest_lookup = T::Lookup::unlookup(dest.clone());
+	}: _(RawOrigin::Signed(delegate.clone()), Default::default(), owner_lookup, dest_lookup, amount)
+	verify {
+		assert!(Approvals::<T>::get((T::AssetId::default(), &owner, &delegate)).is_none());
+		assert_last_event::<T>(
+			RawEvent::TransferredApproved(Default::default(), owner, delegate, dest, amount).into()
+		);
+	}
+
+	cancel_approval {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+		create_default_minted_asset::<T>(&caller, 100u32.into())?;
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		Assets::<T>::approve_transfer(
+			RawOrigin::Signed(caller.clone()).into(),
+			Default::default(),
+			delegate_lookup.clone(),
+			100u32.into(),
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), delegate_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::ApprovalCancelled(Default::default(), caller, delegate).into());
+	}
+
+	issue {
+		let u in ...;
+
+		let caller = funded_account::<T>("caller", u);
+	}: _(RawOrigin::Signed(caller.clone()), 100u32.into())
+	verify {
+		assert_last_event::<T>(RawEvent::Issued(Default::default(), caller, 100u32.into()).into());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_create::<Test>());
+			assert_ok!(test_benchmark_force_create::<Test>());
+			assert_ok!(test_benchmark_destroy::<Test>());
+			assert_ok!(test_benchmark_force_destroy::<Test>());
+			assert_ok!(test_benchmark_mint::<Test>());
+			assert_ok!(test_benchmark_burn::<Test>());
+			assert_ok!(test_benchmark_transfer::<Test>());
+			assert_ok!(test_benchmark_force_transfer::<Test>());
+			assert_ok!(test_benchmark_freeze::<Test>());
+			assert_ok!(test_benchmark_thaw::<Test>());
+			assert_ok!(test_benchmark_freeze_asset::<Test>());
+			assert_ok!(test_benchmark_thaw_asset::<Test>());
+			assert_ok!(test_benchmark_transfer_ownership::<Test>());
+			assert_ok!(test_benchmark_set_team::<Test>());
+			assert_ok!(test_benchmark_force_asset_status::<Test>());
+			assert_ok!(test_benchmark_set_metadata::<Test>());
+			assert_ok!(test_benchmark_clear_metadata::<Test>());
+			assert_ok!(test_benchmark_approve_transfer::<Test>());
+			assert_ok!(test_benchmark_transfer_approved::<Test>());
+			assert_ok!(test_benchmark_cancel_approval::<Test>());
+			assert_ok!(test_benchmark_issue::<Test>());
+		});
+	}
+}
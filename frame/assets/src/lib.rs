@@ -22,10 +22,13 @@
 //! ## Overview
 //!
 //! The Assets module provides functionality for asset management of fungible asset classes
-//! with a fixed supply, including:
+//! with a managed supply, including:
 //!
-//! * Asset Issuance
-//! * Asset Transfer
+//! * Asset Creation
+//! * Asset Issuance (minting) and Burning
+//! * Asset Transfer, directly or through an approved delegate
+//! * Account and Asset Freezing
+//! * Asset Metadata
 //! * Asset Destruction
 //!
 //! To use it in your runtime, you need to implement the assets [`Trait`](./trait.Trait.html).
@@ -34,31 +37,72 @@
 //!
 //! ### Terminology
 //!
-//! * **Asset issuance:** The creation of a new asset, whose total supply will belong to the
-//!   account that issues the asset.
+//! * **Asset creation:** The creation of a new asset class, reserving a deposit from its owner.
+//! * **Asset issuance:** The minting of new units of an asset into some account, increasing the
+//!   total supply of the asset.
+//! * **Asset burning:** The removal of units of an asset from some account, decreasing the total
+//!   supply of the asset.
 //! * **Asset transfer:** The action of transferring assets from one account to another.
-//! * **Asset destruction:** The process of an account removing its entire holding of an asset.
+//! * **Approval:** A permission given by an account holder to a delegate, allowing it to transfer
+//!   up to some amount of the holder's assets.
+//! * **Freezing:** Preventing an account, or every account of an asset, from transferring their
+//!   assets.
+//! * **Minimum balance:** The smallest balance an account may hold of an asset. An account whose
+//!   balance would drop below this is reaped and its remaining balance is moved or burnt along
+//!   with it.
+//! * **Asset destruction:** The removal of an asset class, along with all of its accounts.
 //! * **Fungible asset:** An asset whose units are interchangeable.
 //! * **Non-fungible asset:** An asset for which each unit has unique characteristics.
 //!
+//! ### Roles
+//!
+//! Every asset class has four privileged accounts, which may all be the same account:
+//!
+//! * **Owner:** Can set the other roles, transfer the ownership, set the metadata and destroy the
+//!   asset. The owner pays the deposits of the asset and of its metadata.
+//! * **Issuer:** Can mint new units of the asset.
+//! * **Admin:** Can burn units from any account, force transfers and thaw accounts or the asset.
+//! * **Freezer:** Can freeze accounts or the whole asset.
+//!
 //! ### Goals
 //!
 //! The assets system in Substrate is designed to make the following possible:
 //!
-//! * Issue a unique asset to its creator's account.
-//! * Move assets between accounts.
-//! * Remove an account's balance of an asset when requested by that account's owner and update
-//!   the asset's total supply.
+//! * Create a unique asset class, managed by a team of privileged accounts.
+//! * Issue and burn units of an asset.
+//! * Move assets between accounts, or let a delegate move them on behalf of their holder.
+//! * Freeze accounts or assets whose units should not move.
+//! * Describe an asset with a name, a symbol and a number of decimals.
 //!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
 //!
-//! * `issue` - Issues the total supply of a new fungible asset to the account of the caller of the function.
-//! * `transfer` - Transfers an `amount` of units of fungible asset `id` from the balance of
-//! the function caller's account (`origin`) to a `target` account.
-//! * `destroy` - Destroys the entire holding of a fungible asset `id` associated with the account
-//! that called the function.
+//! * `create` - Creates a new asset class, taking the required deposit.
+//! * `force_create` - Creates a new asset class without taking any deposit.
+//! * `destroy` - Destroys an asset class and all of its accounts; called by the owner.
+//! * `force_destroy` - Destroys an asset class and all of its accounts; called by `ForceOrigin`.
+//! * `mint` - Increases the balance of an account and the total supply; called by the issuer.
+//! * `burn` - Decreases the balance of an account and the total supply; called by the admin.
+//! * `transfer` - Transfers an `amount` of units of asset `id` from the balance of the function
+//!   caller's account (`origin`) to a `target` account.
+//! * `force_transfer` - Transfers between arbitrary accounts; called by the admin.
+//! * `freeze` - Disallows transfers from an account; called by the freezer.
+//! * `thaw` - Allows transfers from an account again; called by the admin.
+//! * `freeze_asset` - Disallows transfers of an asset; called by the freezer.
+//! * `thaw_asset` - Allows transfers of an asset again; called by the admin.
+//! * `transfer_ownership` - Changes the owner of an asset, moving the deposits to the new owner.
+//! * `set_team` - Changes the issuer, admin and freezer of an asset; called by the owner.
+//! * `force_asset_status` - Changes the team, minimum balance and freezing of an asset; called
+//!   by `ForceOrigin`.
+//! * `set_metadata` - Sets the name, symbol and decimals of an asset, taking a deposit.
+//! * `clear_metadata` - Removes the metadata of an asset, returning its deposit.
+//! * `approve_transfer` - Allows a delegate to transfer some of the caller's balance.
+//! * `cancel_approval` - Removes an approval, returning its deposit.
+//! * `transfer_approved` - Transfers some balance of an account to another account; called by an
+//!   approved delegate of the former.
+//! * `issue` - Deprecated. Creates a new asset class with the next free identifier, owned by the
+//!   caller, and mints its whole supply to the caller.
 //!
 //! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
 //!
@@ -74,7 +118,6 @@
 //!
 //! The following example shows how to use the Assets module in your runtime by exposing public functions to:
 //!
-//! * Issue a new fungible asset for a token distribution event (airdrop).
 //! * Query the fungible asset holding balance of an account.
 //! * Query the total supply of a fungible asset that has been issued.
 //!
@@ -93,24 +136,13 @@
 //!
 //! decl_module! {
 //! 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-//! 		pub fn issue_token_airdrop(origin) -> dispatch::DispatchResult {
-//! 			let sender = ensure_signed(origin).map_err(|e| e.as_str())?;
-//!
-//! 			const ACCOUNT_ALICE: u64 = 1;
-//! 			const ACCOUNT_BOB: u64 = 2;
-//! 			const COUNT_AIRDROP_RECIPIENTS: u64 = 2;
-//! 			const TOKENS_FIXED_SUPPLY: u64 = 100;
-//!
-//! 			ensure!(!COUNT_AIRDROP_RECIPIENTS.is_zero(), "Divide by zero error.");
-//!
-//! 			let asset_id = Self::next_asset_id();
+//! 		pub fn ensure_whale(origin, #[compact] id: T::AssetId) -> dispatch::DispatchResult {
+//! 			let sender = ensure_signed(origin)?;
 //!
-//! 			<NextAssetId<T>>::mutate(|asset_id| *asset_id += 1);
-//! 			<Balances<T>>::insert((asset_id, &ACCOUNT_ALICE), TOKENS_FIXED_SUPPLY / COUNT_AIRDROP_RECIPIENTS);
-//! 			<Balances<T>>::insert((asset_id, &ACCOUNT_BOB), TOKENS_FIXED_SUPPLY / COUNT_AIRDROP_RECIPIENTS);
-//! 			<TotalSupply<T>>::insert(asset_id, TOKENS_FIXED_SUPPLY);
+//! 			let balance = <assets::Module<T>>::balance(id, sender);
+//! 			let supply = <assets::Module<T>>::total_supply(id);
+//! 			ensure!(balance >= supply / 10.into(), "Not a whale");
 //!
-//! 			Self::deposit_event(RawEvent::Issued(asset_id, sender, TOKENS_FIXED_SUPPLY));
 //! 			Ok(())
 //! 		}
 //! 	}
@@ -122,8 +154,7 @@
 //! Below are assumptions that must be held when using this module.  If any of
 //! them are violated, the behavior of this module is undefined.
 //!
-//! * The total count of assets should be less than
-//!   `Trait::AssetId::max_value()`.
+//! * The total supply of an asset should be less than `Trait::Balance::max_value()`.
 //!
 //! ## Related Modules
 //!
@@ -133,10 +164,26 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure};
-use sp_runtime::traits::{Member, AtLeast32Bit, Zero, StaticLookup};
+mod benchmarking;
+mod tests;
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use frame_support::{
+	Parameter, decl_module, decl_event, decl_storage, decl_error, ensure,
+	storage::{IterableStorageNMap, migration::StorageIterator},
+	traits::{Currency, ReservableCurrency, EnsureOrigin, Get, BalanceStatus},
+	dispatch::{DispatchError, DispatchResult, DispatchResultWithPostInfo},
+	weights::Weight,
+};
+use sp_runtime::{
+	RuntimeDebug,
+	traits::{Member, AtLeast32Bit, Zero, One, StaticLookup, Saturating, CheckedAdd, CheckedSub},
+};
+use sp_std::collections::btree_map::BTreeMap;
 use frame_system::{self as system, ensure_signed};
-use sp_runtime::traits::One;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 
 /// The module configuration trait.
 pub trait Trait: frame_system::Trait {
@@ -148,78 +195,1021 @@ pub trait Trait: frame_system::Trait {
 
 	/// The arithmetic type of asset identifier.
 	type AssetId: Parameter + AtLeast32Bit + Default + Copy;
+
+	/// The currency mechanism, used for the deposits.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The origin which may forcibly create or destroy an asset or otherwise alter privileged
+	/// attributes.
+	type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The amount of funds that must be reserved when creating a new asset class.
+	type AssetDeposit: Get<BalanceOf<Self>>;
+
+	/// The base amount of funds that must be reserved when setting the metadata of an asset.
+	type MetadataDepositBase: Get<BalanceOf<Self>>;
+
+	/// The additional funds that must be reserved for each byte of the name and the symbol of an
+	/// asset.
+	type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+
+	/// The amount of funds that must be reserved when creating a new approval.
+	type ApprovalDeposit: Get<BalanceOf<Self>>;
+
+	/// The maximum length of the name or the symbol of an asset.
+	type StringLimit: Get<u32>;
+}
+
+/// The details of an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct AssetDetails<Balance, AccountId, DepositBalance> {
+	/// Can change `owner`, `issuer`, `freezer` and `admin` accounts.
+	owner: AccountId,
+	/// Can mint tokens.
+	issuer: AccountId,
+	/// Can thaw tokens, force transfers and burn tokens from any account.
+	admin: AccountId,
+	/// Can freeze tokens.
+	freezer: AccountId,
+	/// The total supply across all accounts.
+	supply: Balance,
+	/// The balance deposited for this asset. This pays for the data stored here.
+	deposit: DepositBalance,
+	/// The minimum balance of an account. Accounts whose balance drops below it are reaped.
+	min_balance: Balance,
+	/// The total number of accounts.
+	accounts: u32,
+	/// The total number of approvals.
+	approvals: u32,
+	/// Whether the asset is frozen for non-admin transfers.
+	is_frozen: bool,
+}
+
+/// The balance of an account for some asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug)]
+pub struct AssetBalance<Balance> {
+	/// The balance.
+	balance: Balance,
+	/// Whether the account is frozen.
+	is_frozen: bool,
+}
+
+/// An approval given by an account holder to a delegate.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug)]
+pub struct Approval<Balance, DepositBalance> {
+	/// The amount of funds the delegate may still transfer from the holder's account.
+	amount: Balance,
+	/// The amount reserved on the holder's account to hold this item in storage.
+	deposit: DepositBalance,
+}
+
+/// The metadata of an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug)]
+pub struct AssetMetadata<DepositBalance> {
+	/// The balance deposited for this metadata.
+	///
+	/// This pays for the data stored in this struct.
+	deposit: DepositBalance,
+	/// The user friendly name of this asset. Limited in length by `StringLimit`.
+	name: Vec<u8>,
+	/// The ticker symbol for this asset. Limited in length by `StringLimit`.
+	symbol: Vec<u8>,
+	/// The number of decimals this asset uses to represent one unit.
+	decimals: u8,
+}
+
+/// The number of items to remove when destroying an asset class, checked against its actual
+/// number of items.
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug)]
+pub struct DestroyWitness {
+	/// The number of accounts holding the asset.
+	#[codec(compact)]
+	pub accounts: u32,
+	/// The number of approvals of the asset.
+	#[codec(compact)]
+	pub approvals: u32,
+}
+
+// A value placed in storage that represents the current version of the Assets storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// Assets have details and a team, and balances are kept per asset and account.
+	V1,
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		type Error = Error<T>;
 
+		/// The amount of funds that must be reserved when creating a new asset class.
+		const AssetDeposit: BalanceOf<T> = T::AssetDeposit::get();
+
+		/// The base amount of funds that must be reserved when setting the metadata of an asset.
+		const MetadataDepositBase: BalanceOf<T> = T::MetadataDepositBase::get();
+
+		/// The additional funds that must be reserved for each byte of the metadata of an asset.
+		const MetadataDepositPerByte: BalanceOf<T> = T::MetadataDepositPerByte::get();
+
+		/// The amount of funds that must be reserved when creating a new approval.
+		const ApprovalDeposit: BalanceOf<T> = T::ApprovalDeposit::get();
+
+		/// The maximum length of the name or the symbol of an asset.
+		const StringLimit: u32 = T::StringLimit::get();
+
 		fn deposit_event() = default;
-		/// Issue a new class of fungible assets. There are, and will only ever be, `total`
-		/// such assets and they'll all belong to the `origin` initially. It will have an
-		/// identifier `AssetId` instance: this will be specified in the `Issued` event.
+
+		fn on_runtime_upgrade() -> Weight {
+			if let None = StorageVersion::get() {
+				StorageVersion::put(Releases::V1);
+				Self::migrate_to_v1().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		/// Ensure no balance or total supply was left behind in the old storage items.
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				StorageIterator::<T::Balance>::new(b"Assets", b"Balances").next().is_none(),
+				"Asset balances left in the old storage",
+			);
+			ensure!(
+				StorageIterator::<T::Balance>::new(b"Assets", b"TotalSupply").next().is_none(),
+				"Asset total supplies left in the old storage",
+			);
+			Ok(())
+		}
+
+		/// Issue a new class of fungible assets from a public origin.
+		///
+		/// This new asset class has no assets initially and its owner is the origin.
+		///
+		/// The origin must be Signed and the sender must have sufficient funds free.
+		///
+		/// Funds of sender are reserved by `AssetDeposit`.
+		///
+		/// Parameters:
+		/// - `id`: The identifier of the new asset. This must not be currently in use to identify
+		/// an existing asset.
+		/// - `admin`: The admin of this class of assets. The admin is the initial address of each
+		/// member of the asset class's admin team.
+		/// - `min_balance`: The minimum balance of this new asset that any single account must
+		/// have. If an account's balance is reduced below this, then it collapses to zero.
+		///
+		/// Emits `Created` event when successful.
 		///
 		/// # <weight>
 		/// - `O(1)`
-		/// - 1 storage mutation (codec `O(1)`).
-		/// - 2 storage writes (condec `O(1)`).
+		/// - 2 storage reads, 2 storage writes.
+		/// - 1 currency reservation.
 		/// - 1 event.
 		/// # </weight>
-		#[weight = 0]
-		fn issue(origin, #[compact] total: T::Balance) {
+		#[weight = 45_000_000 + T::DbWeight::get().reads_writes(3, 3)]
+		fn create(origin,
+			#[compact] id: T::AssetId,
+			admin: <T::Lookup as StaticLookup>::Source,
+			#[compact] min_balance: T::Balance,
+		) {
+			let owner = ensure_signed(origin)?;
+			let admin = T::Lookup::lookup(admin)?;
+
+			ensure!(!Asset::<T>::contains_key(id), Error::<T>::InUse);
+			ensure!(!min_balance.is_zero(), Error::<T>::MinBalanceZero);
+
+			let deposit = T::AssetDeposit::get();
+			T::Currency::reserve(&owner, deposit)?;
+
+			Self::note_asset_id(id);
+			Asset::<T>::insert(id, AssetDetails {
+				owner: owner.clone(),
+				issuer: admin.clone(),
+				admin: admin.clone(),
+				freezer: admin.clone(),
+				supply: Zero::zero(),
+				deposit,
+				min_balance,
+				accounts: 0,
+				approvals: 0,
+				is_frozen: false,
+			});
+			Self::deposit_event(RawEvent::Created(id, owner, admin));
+		}
+
+		/// Issue a new class of fungible assets from a privileged origin.
+		///
+		/// This new asset class has no assets initially and no deposit is taken for it.
+		///
+		/// The origin must conform to `ForceOrigin`.
+		///
+		/// - `id`: The identifier of the new asset. This must not be currently in use to identify
+		/// an existing asset.
+		/// - `owner`: The owner of this class of assets. The owner has full superuser permissions
+		/// over this asset, but may later change and configure the permissions using
+		/// `transfer_ownership` and `set_team`.
+		/// - `min_balance`: The minimum balance of this new asset that any single account must
+		/// have. If an account's balance is reduced below this, then it collapses to zero.
+		///
+		/// Emits `ForceCreated` event when successful.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads, 2 storage writes.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 20_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn force_create(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			#[compact] min_balance: T::Balance,
+		) {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			ensure!(!Asset::<T>::contains_key(id), Error::<T>::InUse);
+			ensure!(!min_balance.is_zero(), Error::<T>::MinBalanceZero);
+
+			Self::note_asset_id(id);
+			Asset::<T>::insert(id, AssetDetails {
+				owner: owner.clone(),
+				issuer: owner.clone(),
+				admin: owner.clone(),
+				freezer: owner.clone(),
+				supply: Zero::zero(),
+				deposit: Zero::zero(),
+				min_balance,
+				accounts: 0,
+				approvals: 0,
+				is_frozen: false,
+			});
+			Self::deposit_event(RawEvent::ForceCreated(id, owner));
+		}
+
+		/// Destroy a class of fungible assets owned by the sender.
+		///
+		/// The origin must be Signed and the sender must be the owner of the asset `id`.
+		///
+		/// - `id`: The identifier of the asset to be destroyed. This must identify an existing
+		/// asset.
+		/// - `witness`: The number of accounts and approvals of the asset, which must be no less
+		/// than the actual ones.
+		///
+		/// All deposits of the asset, its metadata and its approvals are returned.
+		///
+		/// Emits `Destroyed` event when successful.
+		///
+		/// # <weight>
+		/// - `O(A + P)` where `A` is the number of accounts and `P` the number of approvals.
+		/// - 2 storage reads, `2 + P` storage deletions, 1 prefix deletion.
+		/// - `1 + P` currency unreservations.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = weight_for::destroy::<T>(witness.accounts, witness.approvals)]
+		fn destroy(origin,
+			#[compact] id: T::AssetId,
+			witness: DestroyWitness,
+		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
+			Self::do_destroy(id, witness, Some(origin))
+		}
 
-			let id = Self::next_asset_id();
-			<NextAssetId<T>>::mutate(|id| *id += One::one());
+		/// Destroy a class of fungible assets.
+		///
+		/// The origin must conform to `ForceOrigin`.
+		///
+		/// - `id`: The identifier of the asset to be destroyed. This must identify an existing
+		/// asset.
+		/// - `witness`: The number of accounts and approvals of the asset, which must be no less
+		/// than the actual ones.
+		///
+		/// All deposits of the asset, its metadata and its approvals are returned.
+		///
+		/// Emits `Destroyed` event when successful.
+		///
+		/// # <weight>
+		/// - `O(A + P)` where `A` is the number of accounts and `P` the number of approvals.
+		/// - 2 storage reads, `2 + P` storage deletions, 1 prefix deletion.
+		/// - `1 + P` currency unreservations.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = weight_for::destroy::<T>(witness.accounts, witness.approvals)]
+		fn force_destroy(origin,
+			#[compact] id: T::AssetId,
+			witness: DestroyWitness,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			Self::do_destroy(id, witness, None)
+		}
+
+		/// Mint assets of a particular class.
+		///
+		/// The origin must be Signed and the sender must be the Issuer of the asset `id`.
+		///
+		/// - `id`: The identifier of the asset to have some amount minted.
+		/// - `beneficiary`: The account to be credited with the minted assets.
+		/// - `amount`: The amount of the asset to be minted.
+		///
+		/// Emits `Issued` event when successful.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage mutations.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 40_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn mint(origin,
+			#[compact] id: T::AssetId,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let origin = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.issuer, Error::<T>::NoPermission);
 
-			<Balances<T>>::insert((id, &origin), total);
-			<TotalSupply<T>>::insert(id, total);
+				details.supply = details.supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
 
-			Self::deposit_event(RawEvent::Issued(id, origin, total));
+				Account::<T>::try_mutate(id, &beneficiary, |account| -> DispatchResult {
+					let new_balance = account.balance.saturating_add(amount);
+					ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
+					if account.balance.is_zero() {
+						details.accounts = details.accounts.saturating_add(1);
+					}
+					account.balance = new_balance;
+					Ok(())
+				})
+			})?;
+
+			Self::deposit_event(RawEvent::Issued(id, beneficiary, amount));
 		}
 
-		/// Move some assets from one holder to another.
+		/// Reduce the balance of `who` by as much as possible up to `amount` assets of `id`.
+		///
+		/// The origin must be Signed and the sender should be the Admin of the asset `id`.
+		///
+		/// Bails with `BalanceZero` if the `who` is already dead.
+		///
+		/// - `id`: The identifier of the asset to have some amount burned.
+		/// - `who`: The account to be debited from.
+		/// - `amount`: The maximum amount by which `who`'s balance should be reduced.
+		///
+		/// Emits `Burned` with the actual amount burned. If this takes the balance to below the
+		/// minimum for the asset, then the amount burned is increased to take it to zero.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage mutations.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 40_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn burn(origin,
+			#[compact] id: T::AssetId,
+			who: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let burned = Asset::<T>::try_mutate(id, |maybe_details| -> Result<T::Balance, DispatchError> {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.admin, Error::<T>::NoPermission);
+
+				let mut account = Account::<T>::get(id, &who);
+				ensure!(!account.balance.is_zero(), Error::<T>::BalanceZero);
+
+				let mut burned = amount.min(account.balance);
+				account.balance -= burned;
+				if account.balance < details.min_balance {
+					burned += account.balance;
+					details.accounts = details.accounts.saturating_sub(1);
+					Account::<T>::remove(id, &who);
+				} else {
+					Account::<T>::insert(id, &who, &account);
+				}
+
+				details.supply = details.supply.saturating_sub(burned);
+				Ok(burned)
+			})?;
+
+			Self::deposit_event(RawEvent::Burned(id, who, burned));
+		}
+
+		/// Move some assets from the sender account to another.
+		///
+		/// The origin must be Signed.
+		///
+		/// - `id`: The identifier of the asset to have some amount transferred.
+		/// - `target`: The account to be credited.
+		/// - `amount`: The amount by which the sender's balance of assets should be reduced and
+		/// `target`'s balance increased. The amount actually transferred may be slightly greater in
+		/// the case that the transfer would otherwise take the sender balance above zero but below
+		/// the minimum balance. Must be greater than zero.
+		///
+		/// Emits `Transferred` with the actual amount transferred.
 		///
 		/// # <weight>
 		/// - `O(1)`
 		/// - 1 static lookup
-		/// - 2 storage mutations (codec `O(1)`).
+		/// - 3 storage mutations.
 		/// - 1 event.
 		/// # </weight>
-		#[weight = 0]
+		#[weight = 60_000_000 + T::DbWeight::get().reads_writes(3, 3)]
 		fn transfer(origin,
 			#[compact] id: T::AssetId,
 			target: <T::Lookup as StaticLookup>::Source,
-			#[compact] amount: T::Balance
+			#[compact] amount: T::Balance,
 		) {
 			let origin = ensure_signed(origin)?;
-			let origin_account = (id, origin.clone());
-			let origin_balance = <Balances<T>>::get(&origin_account);
 			let target = T::Lookup::lookup(target)?;
-			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-			ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
 
-			Self::deposit_event(RawEvent::Transferred(id, origin, target.clone(), amount));
-			<Balances<T>>::insert(origin_account, origin_balance - amount);
-			<Balances<T>>::mutate((id, target), |balance| *balance += amount);
+			let transferred = Self::do_transfer(id, &origin, &target, amount, false, false)?;
+			Self::deposit_event(RawEvent::Transferred(id, origin, target, transferred));
 		}
 
-		/// Destroy any assets of `id` owned by `origin`.
+		/// Move some assets from one account to another.
+		///
+		/// The origin must be Signed and the sender should be the Admin of the asset `id`.
+		///
+		/// - `id`: The identifier of the asset to have some amount transferred.
+		/// - `source`: The account to be debited.
+		/// - `dest`: The account to be credited.
+		/// - `amount`: The amount by which the `source`'s balance of assets should be reduced and
+		/// `dest`'s balance increased. The amount actually transferred may be slightly greater in
+		/// the case that the transfer would otherwise take the `source` balance above zero but
+		/// below the minimum balance. Must be greater than zero.
+		///
+		/// Frozen accounts and assets are ignored.
+		///
+		/// Emits `ForceTransferred` with the actual amount transferred.
 		///
 		/// # <weight>
 		/// - `O(1)`
-		/// - 1 storage mutation (codec `O(1)`).
-		/// - 1 storage deletion (codec `O(1)`).
+		/// - 2 static lookups
+		/// - 3 storage mutations.
 		/// - 1 event.
 		/// # </weight>
-		#[weight = 0]
-		fn destroy(origin, #[compact] id: T::AssetId) {
+		#[weight = 60_000_000 + T::DbWeight::get().reads_writes(3, 3)]
+		fn force_transfer(origin,
+			#[compact] id: T::AssetId,
+			source: <T::Lookup as StaticLookup>::Source,
+			dest: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
 			let origin = ensure_signed(origin)?;
-			let balance = <Balances<T>>::take((id, &origin));
-			ensure!(!balance.is_zero(), Error::<T>::BalanceZero);
+			let source = T::Lookup::lookup(source)?;
+			let dest = T::Lookup::lookup(dest)?;
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.admin, Error::<T>::NoPermission);
 
-			<TotalSupply<T>>::mutate(id, |total_supply| *total_supply -= balance);
-			Self::deposit_event(RawEvent::Destroyed(id, origin, balance));
+			let transferred = Self::do_transfer(id, &source, &dest, amount, true, false)?;
+			Self::deposit_event(RawEvent::ForceTransferred(id, source, dest, transferred));
+		}
+
+		/// Disallow further unprivileged transfers from an account.
+		///
+		/// The origin must be Signed and the sender should be the Freezer of the asset `id`.
+		///
+		/// - `id`: The identifier of the asset to be frozen.
+		/// - `who`: The account to be frozen.
+		///
+		/// Emits `Frozen`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read, 1 storage mutation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 25_000_000 + T::DbWeight::get().reads_writes(2, 1)]
+		fn freeze(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.freezer, Error::<T>::NoPermission);
+			ensure!(Account::<T>::contains_key(id, &who), Error::<T>::BalanceZero);
+
+			Account::<T>::mutate(id, &who, |account| account.is_frozen = true);
+
+			Self::deposit_event(RawEvent::Frozen(id, who));
+		}
+
+		/// Allow unprivileged transfers from an account again.
+		///
+		/// The origin must be Signed and the sender should be the Admin of the asset `id`.
+		///
+		/// - `id`: The identifier of the asset to be thawed.
+		/// - `who`: The account to be unfrozen.
+		///
+		/// Emits `Thawed`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read, 1 storage mutation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 25_000_000 + T::DbWeight::get().reads_writes(2, 1)]
+		fn thaw(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.admin, Error::<T>::NoPermission);
+			ensure!(Account::<T>::contains_key(id, &who), Error::<T>::BalanceZero);
+
+			Account::<T>::mutate(id, &who, |account| account.is_frozen = false);
+
+			Self::deposit_event(RawEvent::Thawed(id, who));
+		}
+
+		/// Disallow further unprivileged transfers for the asset class.
+		///
+		/// The origin must be Signed and the sender should be the Freezer of the asset `id`.
+		///
+		/// - `id`: The identifier of the asset to be frozen.
+		///
+		/// Emits `AssetFrozen`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage mutation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 20_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn freeze_asset(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.freezer, Error::<T>::NoPermission);
+
+				details.is_frozen = true;
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::AssetFrozen(id));
+		}
+
+		/// Allow unprivileged transfers for the asset again.
+		///
+		/// The origin must be Signed and the sender should be the Admin of the asset `id`.
+		///
+		/// - `id`: The identifier of the asset to be thawed.
+		///
+		/// Emits `AssetThawed`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage mutation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 20_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn thaw_asset(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.admin, Error::<T>::NoPermission);
+
+				details.is_frozen = false;
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::AssetThawed(id));
+		}
+
+		/// Change the Owner of an asset.
+		///
+		/// The origin must be Signed and the sender should be the Owner of the asset `id`.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `owner`: The new Owner of this asset.
+		///
+		/// The deposits of the asset and of its metadata are moved to the new Owner.
+		///
+		/// Emits `OwnerChanged`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads, 1 storage mutation.
+		/// - 1 currency repatriation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 45_000_000 + T::DbWeight::get().reads_writes(4, 3)]
+		fn transfer_ownership(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+		) {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.owner, Error::<T>::NoPermission);
+				if details.owner == owner { return Ok(()) }
+
+				let metadata_deposit = Metadata::<T>::get(id).map_or(Zero::zero(), |m| m.deposit);
+				let deposit = details.deposit.saturating_add(metadata_deposit);
+
+				// Move the deposit to the new owner.
+				T::Currency::repatriate_reserved(&details.owner, &owner, deposit, BalanceStatus::Reserved)?;
+
+				details.owner = owner.clone();
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::OwnerChanged(id, owner));
+		}
+
+		/// Change the Issuer, Admin and Freezer of an asset.
+		///
+		/// The origin must be Signed and the sender should be the Owner of the asset `id`.
+		///
+		/// - `id`: The identifier of the asset to be frozen.
+		/// - `issuer`: The new Issuer of this asset.
+		/// - `admin`: The new Admin of this asset.
+		/// - `freezer`: The new Freezer of this asset.
+		///
+		/// Emits `TeamChanged`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage mutation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 25_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn set_team(origin,
+			#[compact] id: T::AssetId,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source,
+		) {
+			let origin = ensure_signed(origin)?;
+			let issuer = T::Lookup::lookup(issuer)?;
+			let admin = T::Lookup::lookup(admin)?;
+			let freezer = T::Lookup::lookup(freezer)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.owner, Error::<T>::NoPermission);
+
+				details.issuer = issuer.clone();
+				details.admin = admin.clone();
+				details.freezer = freezer.clone();
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::TeamChanged(id, issuer, admin, freezer));
+		}
+
+		/// Alter the attributes of a given asset.
+		///
+		/// The origin must conform to `ForceOrigin`.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `owner`: The new Owner of this asset.
+		/// - `issuer`: The new Issuer of this asset.
+		/// - `admin`: The new Admin of this asset.
+		/// - `freezer`: The new Freezer of this asset.
+		/// - `min_balance`: The minimum balance of this new asset that any single account must
+		/// have. If an account's balance is reduced below this, then it collapses to zero.
+		/// - `is_frozen`: Whether this asset class is frozen except for permissioned/admin
+		/// instructions.
+		///
+		/// Unlike `transfer_ownership`, the deposits of the asset stay with the previous Owner.
+		///
+		/// Emits `AssetStatusChanged` with the identity of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage mutation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 20_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn force_asset_status(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source,
+			#[compact] min_balance: T::Balance,
+			is_frozen: bool,
+		) {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(!min_balance.is_zero(), Error::<T>::MinBalanceZero);
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				details.owner = T::Lookup::lookup(owner)?;
+				details.issuer = T::Lookup::lookup(issuer)?;
+				details.admin = T::Lookup::lookup(admin)?;
+				details.freezer = T::Lookup::lookup(freezer)?;
+				details.min_balance = min_balance;
+				details.is_frozen = is_frozen;
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::AssetStatusChanged(id));
+		}
+
+		/// Set the metadata for an asset.
+		///
+		/// The origin must be Signed and the sender should be the Owner of the asset `id`.
+		///
+		/// Funds of sender are reserved according to the formula:
+		/// `MetadataDepositBase + MetadataDepositPerByte * (name.len + symbol.len)` taking into
+		/// account any already reserved funds.
+		///
+		/// - `id`: The identifier of the asset to update.
+		/// - `name`: The user friendly name of this asset. Limited in length by `StringLimit`.
+		/// - `symbol`: The exchange symbol for this asset. Limited in length by `StringLimit`.
+		/// - `decimals`: The number of decimals this asset uses to represent one unit.
+		///
+		/// Emits `MetadataSet`.
+		///
+		/// # <weight>
+		/// - `O(N + S)` where `N` and `S` are the lengths of the name and the symbol.
+		/// - 2 storage reads, 1 storage write.
+		/// - 1 currency reservation or unreservation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = weight_for::set_metadata::<T>(name.len() as u32, symbol.len() as u32)]
+		fn set_metadata(origin,
+			#[compact] id: T::AssetId,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) {
+			let origin = ensure_signed(origin)?;
+
+			ensure!(name.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
+			ensure!(symbol.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.owner, Error::<T>::NoPermission);
+
+			Metadata::<T>::try_mutate_exists(id, |metadata| -> DispatchResult {
+				let bytes_used = (name.len() + symbol.len()) as u32;
+				let old_deposit = metadata.take().map_or(Zero::zero(), |m| m.deposit);
+				let new_deposit = T::MetadataDepositPerByte::get()
+					.saturating_mul(bytes_used.into())
+					.saturating_add(T::MetadataDepositBase::get());
+
+				if new_deposit > old_deposit {
+					T::Currency::reserve(&origin, new_deposit - old_deposit)?;
+				} else {
+					T::Currency::unreserve(&origin, old_deposit - new_deposit);
+				}
+
+				*metadata = Some(AssetMetadata {
+					deposit: new_deposit,
+					name: name.clone(),
+					symbol: symbol.clone(),
+					decimals,
+				});
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::MetadataSet(id, name, symbol, decimals));
+		}
+
+		/// Clear the metadata for an asset.
+		///
+		/// The origin must be Signed and the sender should be the Owner of the asset `id`.
+		///
+		/// Any deposit is freed for the asset owner.
+		///
+		/// - `id`: The identifier of the asset to clear.
+		///
+		/// Emits `MetadataCleared`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read, 1 storage deletion.
+		/// - 1 currency unreservation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 40_000_000 + T::DbWeight::get().reads_writes(2, 1)]
+		fn clear_metadata(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.owner, Error::<T>::NoPermission);
+
+			let metadata = Metadata::<T>::take(id).ok_or(Error::<T>::Unknown)?;
+			T::Currency::unreserve(&details.owner, metadata.deposit);
+
+			Self::deposit_event(RawEvent::MetadataCleared(id));
+		}
+
+		/// Approve an amount of asset for transfer by a delegated third-party account.
+		///
+		/// The origin must be Signed.
+		///
+		/// Ensures that `ApprovalDeposit` worth of `Currency` is reserved from signing account
+		/// for the purpose of holding the approval. If some non-zero amount of assets is already
+		/// approved from signing account to `delegate`, then it is topped up or unreserved to
+		/// meet the right value.
+		///
+		/// NOTE: The signing account does not need to own `amount` of assets at the point of
+		/// making this call.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `delegate`: The account to delegate permission to transfer asset.
+		/// - `amount`: The amount of asset that may be transferred by `delegate`. If there is
+		/// already an approval in place, then this acts additively.
+		///
+		/// Emits `ApprovedTransfer` on success.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage mutations.
+		/// - 1 currency reservation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(3, 3)]
+		fn approve_transfer(origin,
+			#[compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+
+			let mut details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(!details.is_frozen, Error::<T>::Frozen);
+
+			Approvals::<T>::try_mutate((id, &owner, &delegate), |maybe_approved| -> DispatchResult {
+				let mut approved = match maybe_approved.take() {
+					// An approval already exists and is being updated.
+					Some(approved) => approved,
+					// A new approval is created.
+					None => {
+						details.approvals = details.approvals.saturating_add(1);
+						Default::default()
+					},
+				};
+
+				let deposit_required = T::ApprovalDeposit::get();
+				if approved.deposit < deposit_required {
+					T::Currency::reserve(&owner, deposit_required - approved.deposit)?;
+					approved.deposit = deposit_required;
+				}
+				approved.amount = approved.amount.saturating_add(amount);
+				*maybe_approved = Some(approved);
+				Ok(())
+			})?;
+			Asset::<T>::insert(id, details);
+
+			Self::deposit_event(RawEvent::ApprovedTransfer(id, owner, delegate, amount));
+		}
+
+		/// Cancel all of some asset approved for delegated transfer by a third-party account.
+		///
+		/// The origin must be Signed and there must be an approval in place between signer and
+		/// `delegate`.
+		///
+		/// Unreserves any deposit previously reserved by `approve_transfer` for the approval.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `delegate`: The account delegated permission to transfer asset.
+		///
+		/// Emits `ApprovalCancelled` on success.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage deletion, 1 storage mutation.
+		/// - 1 currency unreservation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 45_000_000 + T::DbWeight::get().reads_writes(3, 3)]
+		fn cancel_approval(origin,
+			#[compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+		) {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+
+			let approval = Approvals::<T>::take((id, &owner, &delegate))
+				.ok_or(Error::<T>::Unapproved)?;
+			T::Currency::unreserve(&owner, approval.deposit);
+			Asset::<T>::mutate(id, |maybe_details| if let Some(details) = maybe_details {
+				details.approvals = details.approvals.saturating_sub(1);
+			});
+
+			Self::deposit_event(RawEvent::ApprovalCancelled(id, owner, delegate));
+		}
+
+		/// Transfer some asset balance from a previously delegated account to some third-party
+		/// account.
+		///
+		/// The origin must be Signed and there must be an approval in place by the `owner` to the
+		/// signer.
+		///
+		/// If the entire amount approved for transfer is transferred, then any deposit previously
+		/// reserved by `approve_transfer` is unreserved.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `owner`: The account which previously approved for a transfer of at least `amount`
+		/// and from which the asset balance will be withdrawn.
+		/// - `destination`: The account to which the asset balance of `amount` will be
+		/// transferred.
+		/// - `amount`: The amount of assets to transfer. Exactly this amount is transferred: the
+		/// transfer fails if it would leave `owner` with a balance below the minimum balance.
+		///
+		/// Emits `TransferredApproved` on success.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 static lookups
+		/// - 4 storage mutations.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = 75_000_000 + T::DbWeight::get().reads_writes(5, 5)]
+		fn transfer_approved(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			destination: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let delegate = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let destination = T::Lookup::lookup(destination)?;
+
+			Approvals::<T>::try_mutate((id, &owner, &delegate), |maybe_approved| -> DispatchResult {
+				let mut approved = maybe_approved.take().ok_or(Error::<T>::Unapproved)?;
+				let remaining = approved.amount.checked_sub(&amount).ok_or(Error::<T>::Unapproved)?;
+
+				Self::do_transfer(id, &owner, &destination, amount, false, true)?;
+
+				if remaining.is_zero() {
+					T::Currency::unreserve(&owner, approved.deposit);
+					Asset::<T>::mutate(id, |maybe_details| if let Some(details) = maybe_details {
+						details.approvals = details.approvals.saturating_sub(1);
+					});
+				} else {
+					approved.amount = remaining;
+					*maybe_approved = Some(approved);
+				}
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::TransferredApproved(id, owner, delegate, destination, amount));
+		}
+
+		/// Issue a new class of fungible assets with an identifier chosen by the module.
+		///
+		/// Deprecated: this is kept for compatibility with the previous interface of the module.
+		/// Use `create` and `mint` instead.
+		///
+		/// The origin must be Signed and the sender must have sufficient funds free. The sender
+		/// becomes the owner and the whole team of the new asset, which has a minimum balance of
+		/// one unit, and all of its `total` units are minted into the sender's account.
+		///
+		/// Funds of sender are reserved by `AssetDeposit`.
+		///
+		/// Emits `Created` and `Issued` events when successful.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads, 3 storage writes.
+		/// - 1 currency reservation.
+		/// - 2 events.
+		/// # </weight>
+		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(3, 4)]
+		fn issue(origin, #[compact] total: T::Balance) {
+			let owner = ensure_signed(origin)?;
+
+			let id = Self::next_asset_id();
+			ensure!(!Asset::<T>::contains_key(id), Error::<T>::InUse);
+
+			let deposit = T::AssetDeposit::get();
+			T::Currency::reserve(&owner, deposit)?;
+
+			NextAssetId::<T>::put(id.saturating_add(One::one()));
+			let accounts = if total.is_zero() {
+				0
+			} else {
+				Account::<T>::insert(id, &owner, AssetBalance { balance: total, is_frozen: false });
+				1
+			};
+			Asset::<T>::insert(id, AssetDetails {
+				owner: owner.clone(),
+				issuer: owner.clone(),
+				admin: owner.clone(),
+				freezer: owner.clone(),
+				supply: total,
+				deposit,
+				min_balance: One::one(),
+				accounts,
+				approvals: 0,
+				is_frozen: false,
+			});
+			Self::deposit_event(RawEvent::Created(id, owner.clone(), owner.clone()));
+			Self::deposit_event(RawEvent::Issued(id, owner, total));
 		}
 	}
 }
@@ -230,36 +1220,136 @@ decl_event! {
 		<T as Trait>::Balance,
 		<T as Trait>::AssetId,
 	{
-		/// Some assets were issued.
+		/// Some asset class was created. [asset_id, creator, admin]
+		Created(AssetId, AccountId, AccountId),
+		/// Some assets were issued. [asset_id, beneficiary, amount]
 		Issued(AssetId, AccountId, Balance),
-		/// Some assets were transferred.
+		/// Some assets were transferred. [asset_id, from, to, amount]
 		Transferred(AssetId, AccountId, AccountId, Balance),
-		/// Some assets were destroyed.
-		Destroyed(AssetId, AccountId, Balance),
+		/// Some assets were destroyed. [asset_id, owner, amount]
+		Burned(AssetId, AccountId, Balance),
+		/// The management team changed. [asset_id, issuer, admin, freezer]
+		TeamChanged(AssetId, AccountId, AccountId, AccountId),
+		/// The owner changed. [asset_id, owner]
+		OwnerChanged(AssetId, AccountId),
+		/// Some assets were transferred by the admin. [asset_id, from, to, amount]
+		ForceTransferred(AssetId, AccountId, AccountId, Balance),
+		/// Some account `who` was frozen. [asset_id, who]
+		Frozen(AssetId, AccountId),
+		/// Some account `who` was thawed. [asset_id, who]
+		Thawed(AssetId, AccountId),
+		/// Some asset `asset_id` was frozen. [asset_id]
+		AssetFrozen(AssetId),
+		/// Some asset `asset_id` was thawed. [asset_id]
+		AssetThawed(AssetId),
+		/// An asset class was destroyed. [asset_id]
+		Destroyed(AssetId),
+		/// Some asset class was force-created. [asset_id, owner]
+		ForceCreated(AssetId, AccountId),
+		/// An asset class had its attributes changed by `ForceOrigin`. [asset_id]
+		AssetStatusChanged(AssetId),
+		/// New metadata has been set for an asset. [asset_id, name, symbol, decimals]
+		MetadataSet(AssetId, Vec<u8>, Vec<u8>, u8),
+		/// Metadata has been cleared for an asset. [asset_id]
+		MetadataCleared(AssetId),
+		/// (Additional) funds have been approved for transfer to a destination account.
+		/// [asset_id, source, delegate, amount]
+		ApprovedTransfer(AssetId, AccountId, AccountId, Balance),
+		/// An approval for account `delegate` was cancelled by `owner`.
+		/// [asset_id, owner, delegate]
+		ApprovalCancelled(AssetId, AccountId, AccountId),
+		/// An `amount` was transferred in its entirety from `owner` to `destination` by
+		/// the approved `delegate`.
+		/// [asset_id, owner, delegate, destination, amount]
+		TransferredApproved(AssetId, AccountId, AccountId, AccountId, Balance),
 	}
 }
 
 decl_error! {
 	pub enum Error for Module<T: Trait> {
-		/// Transfer amount should be non-zero
+		/// Transfer amount should be non-zero.
 		AmountZero,
-		/// Account balance must be greater than or equal to the transfer amount
+		/// Account balance must be greater than or equal to the transfer amount.
 		BalanceLow,
-		/// Balance should be non-zero
+		/// Balance should be non-zero.
 		BalanceZero,
+		/// The signing account has no permission to do the operation.
+		NoPermission,
+		/// The given asset ID is unknown.
+		Unknown,
+		/// The origin account is frozen.
+		Frozen,
+		/// The asset ID is already taken.
+		InUse,
+		/// The given witness data is wrong.
+		BadWitness,
+		/// Minimum balance should be non-zero.
+		MinBalanceZero,
+		/// A mint operation lead to an overflow.
+		Overflow,
+		/// Invalid metadata given.
+		BadMetadata,
+		/// No approval exists that would allow the transfer.
+		Unapproved,
+		/// The source account would not survive the transfer and it needs to stay alive.
+		WouldDie,
 	}
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Assets {
+		/// Details of an asset.
+		Asset: map hasher(blake2_128_concat) T::AssetId
+			=> Option<AssetDetails<T::Balance, T::AccountId, BalanceOf<T>>>;
+
 		/// The number of units of assets held by any given account.
-		Balances: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
-		/// The next asset identifier up for grabs.
+		Account: double_map hasher(blake2_128_concat) T::AssetId, hasher(blake2_128_concat) T::AccountId
+			=> AssetBalance<T::Balance>;
+
+		/// Approved balance transfers. First balance is the amount approved for transfer. Second
+		/// is the amount of `T::Currency` reserved for storing this.
+		Approvals: nmap
+			hasher(blake2_128_concat) T::AssetId,
+			hasher(blake2_128_concat) T::AccountId,
+			hasher(blake2_128_concat) T::AccountId
+			=> Option<Approval<T::Balance, BalanceOf<T>>>;
+
+		/// Metadata of an asset.
+		Metadata: map hasher(blake2_128_concat) T::AssetId => Option<AssetMetadata<BalanceOf<T>>>;
+
+		/// The identifier of the next asset class issued by `issue`. `create` and `force_create`
+		/// move it past the identifiers they take, so no asset class uses it or any identifier above.
 		NextAssetId get(fn next_asset_id): T::AssetId;
-		/// The total unit supply of an asset.
+
+		/// Storage version of the pallet.
 		///
-		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
-		TotalSupply: map hasher(twox_64_concat) T::AssetId => T::Balance;
+		/// New networks start with last version.
+		StorageVersion build(|_| Some(Releases::V1)): Option<Releases>;
+	}
+}
+
+/// Functions for calculating the weight of dispatchables.
+mod weight_for {
+	use frame_support::{traits::Get, weights::Weight};
+	use super::Trait;
+
+	/// Weight calculation for `destroy` and `force_destroy`.
+	pub(crate) fn destroy<T: Trait>(accounts: u32, approvals: u32) -> Weight {
+		let db = T::DbWeight::get();
+		db.reads_writes(2, 2) // `Asset` and `Metadata`
+			.saturating_add(db.writes(accounts.into())) // `Account` prefix deletion
+			.saturating_add(db.reads_writes(approvals.into(), approvals.into())) // `Approvals`
+			.saturating_add(40_000_000) // constant
+			.saturating_add(2_000_000u64.saturating_mul(accounts.into())) // A
+			.saturating_add(30_000_000u64.saturating_mul(approvals.into())) // P
+	}
+
+	/// Weight calculation for `set_metadata`.
+	pub(crate) fn set_metadata<T: Trait>(name: u32, symbol: u32) -> Weight {
+		T::DbWeight::get().reads_writes(2, 1)
+			.saturating_add(50_000_000) // constant
+			.saturating_add(5_000u64.saturating_mul(name.into())) // N
+			.saturating_add(5_000u64.saturating_mul(symbol.into())) // S
 	}
 }
 
@@ -269,161 +1359,157 @@ impl<T: Trait> Module<T> {
 
 	/// Get the asset `id` balance of `who`.
 	pub fn balance(id: T::AssetId, who: T::AccountId) -> T::Balance {
-		<Balances<T>>::get((id, who))
+		Account::<T>::get(id, who).balance
 	}
 
 	/// Get the total supply of an asset `id`.
 	pub fn total_supply(id: T::AssetId) -> T::Balance {
-		<TotalSupply<T>>::get(id)
+		Asset::<T>::get(id).map(|x| x.supply).unwrap_or_else(Zero::zero)
 	}
-}
 
-#[cfg(test)]
-mod tests {
-	use super::*;
+	/// Move `amount` of asset `id` from `source` to `dest`.
+	///
+	/// If `force` is set, the freezing of the asset and of `source` is ignored. If `exact` is
+	/// set, exactly `amount` is moved and the transfer fails rather than leaving `source` with a
+	/// balance below the minimum balance; otherwise that remaining balance is moved as well.
+	///
+	/// Returns the amount actually transferred.
+	fn do_transfer(
+		id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		force: bool,
+		exact: bool,
+	) -> Result<T::Balance, DispatchError> {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
 
-	use frame_support::{impl_outer_origin, assert_ok, assert_noop, parameter_types, weights::Weight};
-	use sp_core::H256;
-	// The testing primitives are very useful for avoiding having to work with signatures
-	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
-	use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+		let mut source_account = Account::<T>::get(id, source);
+		ensure!(force || !source_account.is_frozen, Error::<T>::Frozen);
+		source_account.balance = source_account.balance.checked_sub(&amount)
+			.ok_or(Error::<T>::BalanceLow)?;
 
-	impl_outer_origin! {
-		pub enum Origin for Test  where system = frame_system {}
-	}
+		Asset::<T>::try_mutate(id, |maybe_details| -> Result<T::Balance, DispatchError> {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			ensure!(force || !details.is_frozen, Error::<T>::Frozen);
 
-	// For testing the pallet, we construct most of a mock runtime. This means
-	// first constructing a configuration type (`Test`) which `impl`s each of the
-	// configuration traits of pallets we want to use.
-	#[derive(Clone, Eq, PartialEq)]
-	pub struct Test;
-	parameter_types! {
-		pub const BlockHashCount: u64 = 250;
-		pub const MaximumBlockWeight: Weight = 1024;
-		pub const MaximumBlockLength: u32 = 2 * 1024;
-		pub const AvailableBlockRatio: Perbill = Perbill::one();
-	}
-	impl frame_system::Trait for Test {
-		type Origin = Origin;
-		type Index = u64;
-		type Call = ();
-		type BlockNumber = u64;
-		type Hash = H256;
-		type Hashing = BlakeTwo256;
-		type AccountId = u64;
-		type Lookup = IdentityLookup<Self::AccountId>;
-		type Header = Header;
-		type Event = ();
-		type BlockHashCount = BlockHashCount;
-		type MaximumBlockWeight = MaximumBlockWeight;
-		type DbWeight = ();
-		type BlockExecutionWeight = ();
-		type ExtrinsicBaseWeight = ();
-		type MaximumExtrinsicWeight = MaximumBlockWeight;
-		type AvailableBlockRatio = AvailableBlockRatio;
-		type MaximumBlockLength = MaximumBlockLength;
-		type Version = ();
-		type ModuleToIndex = ();
-		type AccountData = ();
-		type OnNewAccount = ();
-		type OnKilledAccount = ();
-	}
-	impl Trait for Test {
-		type Event = ();
-		type Balance = u64;
-		type AssetId = u32;
-	}
-	type Assets = Module<Test>;
+			if source == dest {
+				return Ok(amount)
+			}
 
-	// This function basically just builds a genesis storage key/value store according to
-	// our desired mockup.
-	fn new_test_ext() -> sp_io::TestExternalities {
-		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
-	}
+			let mut amount = amount;
+			if source_account.balance < details.min_balance {
+				ensure!(!exact || source_account.balance.is_zero(), Error::<T>::WouldDie);
+				amount += source_account.balance;
+				source_account.balance = Zero::zero();
+			}
 
-	#[test]
-	fn issuing_asset_units_to_issuer_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-		});
-	}
+			Account::<T>::try_mutate(id, dest, |account| -> DispatchResult {
+				let new_balance = account.balance.saturating_add(amount);
+				ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
+				if account.balance.is_zero() {
+					details.accounts = details.accounts.saturating_add(1);
+				}
+				account.balance = new_balance;
+				Ok(())
+			})?;
 
-	#[test]
-	fn querying_total_supply_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 50);
-			assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 31));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 19);
-			assert_eq!(Assets::balance(0, 3), 31);
-			assert_ok!(Assets::destroy(Origin::signed(3), 0));
-			assert_eq!(Assets::total_supply(0), 69);
-		});
-	}
+			if source_account.balance.is_zero() {
+				details.accounts = details.accounts.saturating_sub(1);
+				Account::<T>::remove(id, source);
+			} else {
+				Account::<T>::insert(id, source, &source_account);
+			}
 
-	#[test]
-	fn transferring_amount_above_available_balance_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 50);
-		});
+			Ok(amount)
+		})
 	}
 
-	#[test]
-	fn transferring_amount_more_than_available_balance_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 50);
-			assert_ok!(Assets::destroy(Origin::signed(1), 0));
-			assert_eq!(Assets::balance(0, 1), 0);
-			assert_noop!(Assets::transfer(Origin::signed(1), 0, 1, 50), Error::<Test>::BalanceLow);
-		});
-	}
+	/// Destroy the asset `id`, returning all of its deposits.
+	///
+	/// If `maybe_check_owner` is given, it must be the owner of the asset.
+	fn do_destroy(
+		id: T::AssetId,
+		witness: DestroyWitness,
+		maybe_check_owner: Option<T::AccountId>,
+	) -> DispatchResultWithPostInfo {
+		let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+		if let Some(check_owner) = maybe_check_owner {
+			ensure!(details.owner == check_owner, Error::<T>::NoPermission);
+		}
+		ensure!(details.accounts <= witness.accounts, Error::<T>::BadWitness);
+		ensure!(details.approvals <= witness.approvals, Error::<T>::BadWitness);
 
-	#[test]
-	fn transferring_less_than_one_unit_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 0), Error::<Test>::AmountZero);
-		});
-	}
+		Account::<T>::remove_prefix(&id);
+		for ((owner, _), approval) in Approvals::<T>::drain_prefix((id,)) {
+			T::Currency::unreserve(&owner, approval.deposit);
+		}
+		if let Some(metadata) = Metadata::<T>::take(&id) {
+			T::Currency::unreserve(&details.owner, metadata.deposit);
+		}
+		T::Currency::unreserve(&details.owner, details.deposit);
+		Asset::<T>::remove(&id);
 
-	#[test]
-	fn transferring_more_units_than_total_supply_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 101), Error::<Test>::BalanceLow);
-		});
+		Self::deposit_event(RawEvent::Destroyed(id));
+
+		Ok(Some(weight_for::destroy::<T>(details.accounts, details.approvals)).into())
 	}
 
-	#[test]
-	fn destroying_asset_balance_with_positive_balance_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::destroy(Origin::signed(1), 0));
+	/// Keep `NextAssetId` past the identifier `id` of a newly created asset.
+	fn note_asset_id(id: T::AssetId) {
+		NextAssetId::<T>::mutate(|next| if id >= *next {
+			*next = id.saturating_add(One::one());
 		});
 	}
 
-	#[test]
-	fn destroying_asset_balance_with_zero_balance_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 2), 0);
-			assert_noop!(Assets::destroy(Origin::signed(2), 0), Error::<Test>::BalanceZero);
-		});
+	/// Move the balances and total supplies of assets issued before assets had a team, a deposit
+	/// and a minimum balance into `Account` and `Asset`.
+	///
+	/// These assets had no privileged accounts, so every role of a migrated asset is given to the
+	/// default account until `ForceOrigin` assigns a team with `force_asset_status`. No deposit
+	/// is held for them and their minimum balance is one unit, which keeps every non-zero balance
+	/// alive. `NextAssetId` is unchanged.
+	///
+	/// NOTE: The migration is unbounded: every old balance and total supply is moved within the
+	/// upgrade block, and the returned weight grows with their number. It is only suitable for
+	/// chains whose old `Balances` fit into a single block, others have to move them beforehand.
+	fn migrate_to_v1() -> Weight {
+		let mut reads_writes: Weight = 0;
+
+		// Key is `blake2_128(id ++ who) ++ id ++ who`.
+		let mut accounts = BTreeMap::<T::AssetId, u32>::new();
+		for (key, balance) in StorageIterator::<T::Balance>::new(b"Assets", b"Balances").drain() {
+			reads_writes += 1;
+			if balance.is_zero() {
+				continue
+			}
+			if let Some(Ok((id, who))) = key.get(16..)
+				.map(|mut k| <(T::AssetId, T::AccountId)>::decode(&mut k))
+			{
+				Account::<T>::insert(id, who, AssetBalance { balance, is_frozen: false });
+				*accounts.entry(id).or_default() += 1;
+			}
+		}
+
+		// Key is `twox_64(id) ++ id`.
+		for (key, supply) in StorageIterator::<T::Balance>::new(b"Assets", b"TotalSupply").drain() {
+			reads_writes += 1;
+			if let Some(Ok(id)) = key.get(8..).map(|mut k| T::AssetId::decode(&mut k)) {
+				Asset::<T>::insert(id, AssetDetails {
+					owner: Default::default(),
+					issuer: Default::default(),
+					admin: Default::default(),
+					freezer: Default::default(),
+					supply,
+					deposit: Zero::zero(),
+					min_balance: One::one(),
+					accounts: accounts.get(&id).copied().unwrap_or(0),
+					approvals: 0,
+					is_frozen: false,
+				});
+			}
+		}
+
+		T::DbWeight::get().reads_writes(reads_writes, reads_writes.saturating_mul(2))
 	}
 }
@@ -0,0 +1,730 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Tests for Assets Pallet
+
+#![cfg(test)]
+
+use super::*;
+
+use frame_support::{
+	assert_ok, assert_noop, impl_outer_origin, impl_outer_event, parameter_types,
+	weights::Weight, traits::{Currency, OnRuntimeUpgrade},
+	storage::migration::{put_storage_value, StorageIterator},
+};
+use sp_core::H256;
+// The testing primitives are very useful for avoiding having to work with signatures
+// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
+use sp_runtime::{Perbill, DispatchError, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+use crate as assets;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		pallet_balances<T>,
+		assets<T>,
+	}
+}
+
+// For testing the pallet, we construct most of a mock runtime. This means
+// first constructing a configuration type (`Test`) which `impl`s each of the
+// configuration traits of pallets we want to use.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type MaximumBlockLength = MaximumBlockLength;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+parameter_types! {
+	pub const AssetDeposit: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+}
+impl Trait for Test {
+	type Event = TestEvent;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+}
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Assets = Module<Test>;
+
+use pallet_balances::Error as BalancesError;
+
+// This function basically just builds a genesis storage key/value store according to
+// our desired mockup.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn last_event() -> TestEvent {
+	System::events().pop().map(|e| e.event).expect("Event expected")
+}
+
+fn accounts(id: u32) -> u32 {
+	Asset::<Test>::get(id).map_or(0, |d| d.accounts)
+}
+
+fn approvals(id: u32) -> u32 {
+	Asset::<Test>::get(id).map_or(0, |d| d.approvals)
+}
+
+#[test]
+fn basic_minting_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_eq!(Assets::balance(0, 2), 100);
+		assert_eq!(Assets::total_supply(0), 200);
+		assert_eq!(accounts(0), 2);
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::Issued(0, 2, 100)));
+	});
+}
+
+#[test]
+fn creating_asset_should_take_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Assets::create(Origin::signed(1), 0, 1, 1),
+			BalancesError::<Test, _>::InsufficientBalance,
+		);
+		Balances::make_free_balance_be(&1, 100);
+		assert_noop!(Assets::create(Origin::signed(1), 0, 1, 0), Error::<Test>::MinBalanceZero);
+		assert_ok!(Assets::create(Origin::signed(1), 0, 2, 1));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::Created(0, 1, 2)));
+		assert_noop!(Assets::create(Origin::signed(1), 0, 1, 1), Error::<Test>::InUse);
+
+		// The admin is also the issuer.
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 100), Error::<Test>::NoPermission);
+		assert_ok!(Assets::mint(Origin::signed(2), 0, 1, 100));
+	});
+}
+
+#[test]
+fn lifecycle_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&10, 100);
+		assert_ok!(Assets::create(Origin::signed(1), 0, 1, 1));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert!(Asset::<Test>::contains_key(0));
+
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0], vec![0], 12));
+		assert_eq!(Balances::reserved_balance(&1), 4);
+		assert!(Metadata::<Test>::contains_key(0));
+
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 10, 100));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 20, 100));
+		assert_eq!(accounts(0), 2);
+
+		assert_ok!(Assets::approve_transfer(Origin::signed(10), 0, 30, 50));
+		assert_eq!(Balances::reserved_balance(&10), 1);
+		assert_eq!(approvals(0), 1);
+
+		let witness = DestroyWitness { accounts: 2, approvals: 1 };
+		assert_ok!(Assets::destroy(Origin::signed(1), 0, witness));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::Destroyed(0)));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::reserved_balance(&10), 0);
+
+		assert!(!Asset::<Test>::contains_key(0));
+		assert!(!Metadata::<Test>::contains_key(0));
+		assert!(!Account::<Test>::contains_key(0, 10));
+		assert!(!Account::<Test>::contains_key(0, 20));
+		assert!(!Approvals::<Test>::contains_key((0, 10, 30)));
+
+		// The asset can be created and destroyed again.
+		assert_ok!(Assets::create(Origin::signed(1), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 10, 100));
+		let witness = DestroyWitness { accounts: 1, approvals: 0 };
+		assert_ok!(Assets::force_destroy(Origin::ROOT, 0, witness));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert!(!Asset::<Test>::contains_key(0));
+		assert!(!Account::<Test>::contains_key(0, 10));
+	});
+}
+
+#[test]
+fn destroy_with_bad_witness_should_not_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::create(Origin::signed(1), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 10, 100));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 20, 100));
+
+		let witness = DestroyWitness { accounts: 1, approvals: 0 };
+		assert_noop!(Assets::destroy(Origin::signed(1), 0, witness), Error::<Test>::BadWitness);
+		assert_noop!(Assets::force_destroy(Origin::ROOT, 0, witness), Error::<Test>::BadWitness);
+	});
+}
+
+#[test]
+fn destroy_should_refund_unused_weight() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::create(Origin::signed(1), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 10, 100));
+
+		let witness = DestroyWitness { accounts: 10, approvals: 10 };
+		let post_info = Assets::destroy(Origin::signed(1), 0, witness).unwrap();
+		assert_eq!(post_info.actual_weight, Some(weight_for::destroy::<Test>(1, 0)));
+	});
+}
+
+#[test]
+fn approval_lifecycle_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(
+			Assets::approve_transfer(Origin::signed(1), 0, 2, 50),
+			BalancesError::<Test, _>::InsufficientBalance,
+		);
+
+		Balances::make_free_balance_be(&1, 1);
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_eq!(approvals(0), 1);
+
+		// Further approvals add to the approved amount without any further deposit.
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 10));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_eq!(approvals(0), 1);
+
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 40));
+		assert_eq!(
+			last_event(),
+			TestEvent::assets(RawEvent::TransferredApproved(0, 1, 2, 3, 40)),
+		);
+		assert_eq!(Assets::balance(0, 1), 60);
+		assert_eq!(Assets::balance(0, 3), 40);
+		assert_eq!(Approvals::<Test>::get((0, 1, 2)).map(|a| a.amount), Some(20));
+
+		// The remaining approved amount can be used up, releasing the deposit.
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 20));
+		assert_eq!(Assets::balance(0, 3), 60);
+		assert!(!Approvals::<Test>::contains_key((0, 1, 2)));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(approvals(0), 0);
+	});
+}
+
+#[test]
+fn transfer_approved_above_approval_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		Balances::make_free_balance_be(&1, 1);
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 51),
+			Error::<Test>::Unapproved,
+		);
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(3), 0, 1, 3, 10),
+			Error::<Test>::Unapproved,
+		);
+	});
+}
+
+#[test]
+fn transfer_approved_should_not_reap_owner() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		Balances::make_free_balance_be(&1, 1);
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 100));
+
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 95),
+			Error::<Test>::WouldDie,
+		);
+
+		// Transferring the whole balance is fine.
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 100));
+		assert_eq!(Assets::balance(0, 1), 0);
+		assert_eq!(Assets::balance(0, 3), 100);
+		assert_eq!(accounts(0), 1);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+	});
+}
+
+#[test]
+fn cancel_approval_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		Balances::make_free_balance_be(&1, 1);
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+
+		assert_noop!(Assets::cancel_approval(Origin::signed(1), 0, 3), Error::<Test>::Unapproved);
+		assert_noop!(Assets::cancel_approval(Origin::signed(2), 0, 1), Error::<Test>::Unapproved);
+		assert_ok!(Assets::cancel_approval(Origin::signed(1), 0, 2));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::ApprovalCancelled(0, 1, 2)));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(approvals(0), 0);
+
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 10),
+			Error::<Test>::Unapproved,
+		);
+	});
+}
+
+#[test]
+fn min_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 10));
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 9), Error::<Test>::BalanceLow);
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(accounts(0), 1);
+
+		// Cannot create a new account with a balance that is below minimum...
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 9), Error::<Test>::BalanceLow);
+
+		// When deducting from an account to below minimum, it should be reaped.
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 91));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::Transferred(0, 1, 2, 100)));
+		assert!(!Account::<Test>::contains_key(0, 1));
+		assert_eq!(Assets::balance(0, 2), 100);
+		assert_eq!(accounts(0), 1);
+
+		assert_ok!(Assets::force_transfer(Origin::signed(1), 0, 2, 1, 91));
+		assert!(!Account::<Test>::contains_key(0, 2));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_eq!(accounts(0), 1);
+
+		assert_ok!(Assets::burn(Origin::signed(1), 0, 1, 91));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::Burned(0, 1, 100)));
+		assert!(!Account::<Test>::contains_key(0, 1));
+		assert_eq!(accounts(0), 0);
+		assert_eq!(Assets::total_supply(0), 0);
+	});
+}
+
+#[test]
+fn querying_total_supply_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Assets::balance(0, 1), 50);
+		assert_eq!(Assets::balance(0, 2), 50);
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 31));
+		assert_eq!(Assets::balance(0, 1), 50);
+		assert_eq!(Assets::balance(0, 2), 19);
+		assert_eq!(Assets::balance(0, 3), 31);
+		assert_ok!(Assets::burn(Origin::signed(1), 0, 3, u64::max_value()));
+		assert_eq!(Assets::total_supply(0), 69);
+	});
+}
+
+#[test]
+fn transferring_amount_below_available_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Assets::balance(0, 1), 50);
+		assert_eq!(Assets::balance(0, 2), 50);
+	});
+}
+
+#[test]
+fn transferring_frozen_user_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(Assets::freeze(Origin::signed(1), 0, 2), Error::<Test>::BalanceZero);
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::Frozen(0, 1)));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+
+		// The admin can still move the funds.
+		assert_ok!(Assets::force_transfer(Origin::signed(1), 0, 1, 2, 10));
+		assert_eq!(Assets::balance(0, 2), 10);
+
+		assert_ok!(Assets::thaw(Origin::signed(1), 0, 1));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Assets::balance(0, 2), 60);
+	});
+}
+
+#[test]
+fn transferring_frozen_asset_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::freeze_asset(Origin::signed(1), 0));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::AssetFrozen(0)));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+		Balances::make_free_balance_be(&1, 1);
+		assert_noop!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+
+		assert_ok!(Assets::thaw_asset(Origin::signed(1), 0));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Assets::balance(0, 2), 50);
+	});
+}
+
+#[test]
+fn origin_guards_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(Assets::transfer_ownership(Origin::signed(2), 0, 2), Error::<Test>::NoPermission);
+		assert_noop!(Assets::set_team(Origin::signed(2), 0, 2, 2, 2), Error::<Test>::NoPermission);
+		assert_noop!(Assets::freeze(Origin::signed(2), 0, 1), Error::<Test>::NoPermission);
+		assert_noop!(Assets::thaw(Origin::signed(2), 0, 2), Error::<Test>::NoPermission);
+		assert_noop!(Assets::freeze_asset(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_noop!(Assets::thaw_asset(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_noop!(Assets::mint(Origin::signed(2), 0, 2, 100), Error::<Test>::NoPermission);
+		assert_noop!(Assets::burn(Origin::signed(2), 0, 1, 100), Error::<Test>::NoPermission);
+		assert_noop!(Assets::force_transfer(Origin::signed(2), 0, 1, 2, 100), Error::<Test>::NoPermission);
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(2), 0, vec![0; 10], vec![0; 10], 12),
+			Error::<Test>::NoPermission,
+		);
+		let witness = DestroyWitness { accounts: 1, approvals: 0 };
+		assert_noop!(Assets::destroy(Origin::signed(2), 0, witness), Error::<Test>::NoPermission);
+		assert_noop!(Assets::force_create(Origin::signed(1), 1, 1, 1), DispatchError::BadOrigin);
+		assert_noop!(Assets::force_destroy(Origin::signed(1), 0, witness), DispatchError::BadOrigin);
+		assert_noop!(
+			Assets::force_asset_status(Origin::signed(1), 0, 1, 1, 1, 1, 1, false),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn transfer_owner_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&2, 100);
+		assert_ok!(Assets::create(Origin::signed(1), 0, 1, 1));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0; 10], vec![0; 10], 12));
+		assert_eq!(Balances::reserved_balance(&1), 22);
+
+		assert_ok!(Assets::transfer_ownership(Origin::signed(1), 0, 2));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::OwnerChanged(0, 2)));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::reserved_balance(&2), 22);
+		assert_noop!(Assets::transfer_ownership(Origin::signed(1), 0, 1), Error::<Test>::NoPermission);
+
+		assert_ok!(Assets::transfer_ownership(Origin::signed(2), 0, 1));
+		assert_eq!(Balances::reserved_balance(&1), 22);
+		assert_eq!(Balances::reserved_balance(&2), 0);
+	});
+}
+
+#[test]
+fn set_team_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::set_team(Origin::signed(1), 0, 2, 3, 4));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::TeamChanged(0, 2, 3, 4)));
+
+		assert_ok!(Assets::mint(Origin::signed(2), 0, 2, 100));
+		assert_ok!(Assets::freeze(Origin::signed(4), 0, 2));
+		assert_ok!(Assets::thaw(Origin::signed(3), 0, 2));
+		assert_ok!(Assets::force_transfer(Origin::signed(3), 0, 2, 3, 100));
+		assert_ok!(Assets::burn(Origin::signed(3), 0, 3, 100));
+	});
+}
+
+#[test]
+fn force_asset_status_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(
+			Assets::force_asset_status(Origin::ROOT, 1, 2, 3, 4, 5, 1, false),
+			Error::<Test>::Unknown,
+		);
+		assert_noop!(
+			Assets::force_asset_status(Origin::ROOT, 0, 2, 3, 4, 5, 0, false),
+			Error::<Test>::MinBalanceZero,
+		);
+
+		assert_ok!(Assets::force_asset_status(Origin::ROOT, 0, 2, 3, 4, 5, 10, true));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::AssetStatusChanged(0)));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 100), Error::<Test>::NoPermission);
+		assert_noop!(Assets::thaw_asset(Origin::signed(1), 0), Error::<Test>::NoPermission);
+
+		// the new team takes over
+		assert_ok!(Assets::thaw_asset(Origin::signed(4), 0));
+		assert_ok!(Assets::mint(Origin::signed(3), 0, 3, 100));
+		assert_ok!(Assets::freeze(Origin::signed(5), 0, 3));
+		assert_ok!(Assets::set_team(Origin::signed(2), 0, 1, 1, 1));
+		// and the new minimum balance applies
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 5), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn transferring_amount_more_than_available_balance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Assets::balance(0, 1), 50);
+		assert_eq!(Assets::balance(0, 2), 50);
+		assert_ok!(Assets::burn(Origin::signed(1), 0, 1, u64::max_value()));
+		assert_eq!(Assets::balance(0, 1), 0);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 1, 50), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn transferring_less_than_one_unit_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 0), Error::<Test>::AmountZero);
+	});
+}
+
+#[test]
+fn transferring_more_units_than_total_supply_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 101), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn burning_asset_balance_with_positive_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_ok!(Assets::burn(Origin::signed(1), 0, 1, u64::max_value()));
+		assert_eq!(Assets::balance(0, 1), 0);
+	});
+}
+
+#[test]
+fn burning_asset_balance_with_zero_balance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Assets::balance(0, 2), 0);
+		assert_noop!(Assets::burn(Origin::signed(1), 0, 2, u64::max_value()), Error::<Test>::BalanceZero);
+	});
+}
+
+#[test]
+fn set_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		// Cannot add metadata to unknown asset
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 10], 12),
+			Error::<Test>::Unknown,
+		);
+		assert_ok!(Assets::force_create(Origin::ROOT, 0, 1, 1));
+		// Cannot add metadata to unowned asset
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(2), 0, vec![0u8; 10], vec![0u8; 10], 12),
+			Error::<Test>::NoPermission,
+		);
+
+		// Cannot add oversized metadata
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 100], vec![0u8; 10], 12),
+			Error::<Test>::BadMetadata,
+		);
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 100], 12),
+			Error::<Test>::BadMetadata,
+		);
+
+		// Successfully add metadata and take deposit
+		Balances::make_free_balance_be(&1, 30);
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 10], 12));
+		assert_eq!(Balances::free_balance(&1), 9);
+
+		// Update deposit
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 5], 12));
+		assert_eq!(Balances::free_balance(&1), 14);
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 15], 12));
+		assert_eq!(Balances::free_balance(&1), 4);
+
+		// Cannot over-reserve
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 20], vec![0u8; 20], 12),
+			BalancesError::<Test, _>::InsufficientBalance,
+		);
+
+		// Clear Metadata
+		assert!(Metadata::<Test>::contains_key(0));
+		assert_noop!(Assets::clear_metadata(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_noop!(Assets::clear_metadata(Origin::signed(1), 1), Error::<Test>::Unknown);
+		assert_ok!(Assets::clear_metadata(Origin::signed(1), 0));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::MetadataCleared(0)));
+		assert!(!Metadata::<Test>::contains_key(0));
+		assert_eq!(Balances::free_balance(&1), 30);
+		assert_noop!(Assets::clear_metadata(Origin::signed(1), 0), Error::<Test>::Unknown);
+	});
+}
+
+#[test]
+fn issue_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_eq!(last_event(), TestEvent::assets(RawEvent::Issued(0, 1, 100)));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_eq!(Assets::total_supply(0), 100);
+		assert_eq!(accounts(0), 1);
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_eq!(Assets::next_asset_id(), 1);
+
+		// The issuer holds every role of the asset.
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 50));
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 2));
+		assert_ok!(Assets::thaw(Origin::signed(1), 0, 2));
+		assert_ok!(Assets::destroy(Origin::signed(1), 0, DestroyWitness { accounts: 2, approvals: 0 }));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+	});
+}
+
+#[test]
+fn issue_skips_created_ids() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Assets::create(Origin::signed(1), 0, 1, 1));
+		assert_ok!(Assets::force_create(Origin::ROOT, 5, 1, 1));
+		assert_ok!(Assets::create(Origin::signed(1), 2, 1, 1));
+		assert_eq!(Assets::next_asset_id(), 6);
+
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_eq!(Assets::balance(6, 1), 100);
+		assert_eq!(Assets::next_asset_id(), 7);
+	});
+}
+
+#[test]
+fn migration_to_v1_works() {
+	new_test_ext().execute_with(|| {
+		let put_balance = |id: u32, who: u64, balance: u64| {
+			let key = (id, who).encode();
+			let hashed = [&sp_io::hashing::blake2_128(&key)[..], &key[..]].concat();
+			put_storage_value(b"Assets", b"Balances", &hashed, balance);
+		};
+		let put_supply = |id: u32, supply: u64| {
+			let key = id.encode();
+			let hashed = [&sp_io::hashing::twox_64(&key)[..], &key[..]].concat();
+			put_storage_value(b"Assets", b"TotalSupply", &hashed, supply);
+		};
+		put_balance(0, 1, 60);
+		put_balance(0, 2, 40);
+		put_balance(0, 3, 0);
+		put_supply(0, 100);
+		put_balance(1, 2, 10);
+		put_supply(1, 10);
+		put_storage_value(b"Assets", b"NextAssetId", &[], 2u32);
+
+		Assets::on_runtime_upgrade();
+
+		assert_eq!(Assets::balance(0, 1), 60);
+		assert_eq!(Assets::balance(0, 2), 40);
+		assert!(!Account::<Test>::contains_key(0, 3));
+		assert_eq!(Assets::total_supply(0), 100);
+		assert_eq!(accounts(0), 2);
+		assert_eq!(Assets::balance(1, 2), 10);
+		assert_eq!(Assets::total_supply(1), 10);
+		assert_eq!(accounts(1), 1);
+		assert_eq!(Assets::next_asset_id(), 2);
+		assert!(StorageIterator::<u64>::new(b"Assets", b"Balances").next().is_none());
+		assert!(StorageIterator::<u64>::new(b"Assets", b"TotalSupply").next().is_none());
+
+		// Migrated assets keep working and the migration only runs once.
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 3, 10));
+		assert_eq!(accounts(0), 3);
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 10), Error::<Test>::NoPermission);
+		assert_ok!(Assets::force_asset_status(Origin::ROOT, 0, 1, 1, 1, 1, 1, false));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 10));
+		assert_eq!(Assets::total_supply(0), 110);
+		put_balance(0, 4, 5);
+		Assets::on_runtime_upgrade();
+		assert!(!Account::<Test>::contains_key(0, 4));
+	});
+}
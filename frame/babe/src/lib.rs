@@ -40,7 +40,7 @@ use sp_application_crypto::Public;
 use codec::{Encode, Decode};
 use sp_inherents::{InherentIdentifier, InherentData, ProvideInherent, MakeFatalError};
 use sp_consensus_babe::{
-	BABE_ENGINE_ID, ConsensusLog, BabeAuthorityWeight, SlotNumber,
+	BABE_ENGINE_ID, ConsensusLog, BabeAuthorityWeight, BabeEpochConfiguration, Epoch, SlotNumber,
 	inherents::{INHERENT_IDENTIFIER, BabeInherentData},
 	digests::{NextEpochDescriptor, NextConfigDescriptor, PreDigest},
 };
@@ -136,12 +136,23 @@ decl_storage! {
 		// variable to its underlying value.
 		pub Randomness get(fn randomness): schnorrkel::Randomness;
 
-		/// Next epoch configuration, if changed.
-		NextEpochConfig: Option<NextConfigDescriptor>;
+		/// Pending epoch configuration change that will be applied when the next epoch is enacted.
+		PendingEpochConfigChange: Option<NextConfigDescriptor>;
 
 		/// Next epoch randomness.
 		NextRandomness: schnorrkel::Randomness;
 
+		/// Next epoch authorities.
+		NextAuthorities get(fn next_authorities): Vec<(AuthorityId, BabeAuthorityWeight)>;
+
+		/// The configuration for the current epoch. Should never be `None` as it is initialized
+		/// in genesis.
+		EpochConfig: Option<BabeEpochConfiguration>;
+
+		/// The configuration for the next epoch, `None` if the config will not change (you can
+		/// fallback to `EpochConfig` instead in that case).
+		NextEpochConfig: Option<BabeEpochConfiguration>;
+
 		/// Randomness under construction.
 		///
 		/// We make a tradeoff between storage accesses and list length.
@@ -169,7 +180,11 @@ decl_storage! {
 	}
 	add_extra_genesis {
 		config(authorities): Vec<(AuthorityId, BabeAuthorityWeight)>;
-		build(|config| Module::<T>::initialize_authorities(&config.authorities))
+		config(epoch_config): Option<BabeEpochConfiguration>;
+		build(|config| {
+			Module::<T>::initialize_authorities(&config.authorities);
+			EpochConfig::put(config.epoch_config.clone().expect("epoch_config must not be None"));
+		})
 	}
 }
 
@@ -375,7 +390,7 @@ impl<T: Trait> Module<T> {
 	pub fn plan_config_change(
 		config: NextConfigDescriptor,
 	) {
-		NextEpochConfig::put(config);
+		PendingEpochConfigChange::put(config);
 	}
 
 	/// DANGEROUS: Enact an epoch change. Should be done on every block where `should_epoch_change` has returned `true`,
@@ -398,6 +413,7 @@ impl<T: Trait> Module<T> {
 
 		EpochIndex::put(epoch_index);
 		Authorities::put(authorities);
+		NextAuthorities::put(&next_authorities);
 
 		// Update epoch randomness.
 		let next_epoch_index = epoch_index
@@ -419,8 +435,15 @@ impl<T: Trait> Module<T> {
 		};
 		Self::deposit_consensus(ConsensusLog::NextEpochData(next_epoch));
 
-		if let Some(next_config) = NextEpochConfig::take() {
-			Self::deposit_consensus(ConsensusLog::NextConfigData(next_config));
+		if let Some(next_config) = NextEpochConfig::get() {
+			EpochConfig::put(next_config);
+		}
+
+		if let Some(pending_epoch_config_change) = PendingEpochConfigChange::take() {
+			let next_config: BabeEpochConfiguration = pending_epoch_config_change.clone().into();
+			NextEpochConfig::put(next_config);
+
+			Self::deposit_consensus(ConsensusLog::NextConfigData(pending_epoch_config_change));
 		}
 	}
 
@@ -428,7 +451,71 @@ impl<T: Trait> Module<T> {
 	// give correct results after `do_initialize` of the first block
 	// in the chain (as its result is based off of `GenesisSlot`).
 	pub fn current_epoch_start() -> SlotNumber {
-		(EpochIndex::get() * T::EpochDuration::get()) + GenesisSlot::get()
+		Self::epoch_start(EpochIndex::get())
+	}
+
+	/// Produces information about the current epoch.
+	pub fn current_epoch() -> Epoch {
+		Epoch {
+			epoch_index: EpochIndex::get(),
+			start_slot: Self::current_epoch_start(),
+			duration: T::EpochDuration::get(),
+			authorities: Self::authorities(),
+			randomness: Self::randomness(),
+			config: EpochConfig::get().expect("EpochConfig is initialized in genesis; we never `take` or `kill` it; qed"),
+		}
+	}
+
+	/// Produces information about the next epoch (which was already previously
+	/// announced).
+	pub fn next_epoch() -> Epoch {
+		let next_epoch_index = EpochIndex::get()
+			.checked_add(1)
+			.expect("epoch indices will never reach 2^64 before the death of the universe; qed");
+
+		Epoch {
+			epoch_index: next_epoch_index,
+			start_slot: Self::epoch_start(next_epoch_index),
+			duration: T::EpochDuration::get(),
+			authorities: NextAuthorities::get(),
+			randomness: NextRandomness::get(),
+			config: NextEpochConfig::get().unwrap_or_else(|| {
+				EpochConfig::get().expect("EpochConfig is initialized in genesis; we never `take` or `kill` it; qed")
+			}),
+		}
+	}
+
+	fn epoch_start(epoch_index: u64) -> SlotNumber {
+		(epoch_index * T::EpochDuration::get()) + GenesisSlot::get()
+	}
+
+	/// Migrate the storage of chains started before the epoch configuration was stored, given
+	/// the configuration of the current epoch, i.e. the genesis configuration unless it was
+	/// changed since.
+	///
+	/// The next epoch authorities aren't known until the next epoch change, so
+	/// [`Module::next_epoch`] reports no authorities until then and nodes refuse to start from
+	/// the state of blocks in between.
+	///
+	/// Does nothing if the epoch configuration is already stored.
+	pub fn migrate_epoch_config(epoch_config: BabeEpochConfiguration) -> Weight {
+		if EpochConfig::exists() {
+			return T::DbWeight::get().reads(1);
+		}
+
+		// the pending change used to be stored under the key of `NextEpochConfig`.
+		let key = NextEpochConfig::hashed_key();
+		let pending_change = frame_support::storage::unhashed::take::<NextConfigDescriptor>(&key);
+		let mut writes = 1;
+		if let Some(pending_change) = pending_change {
+			PendingEpochConfigChange::put(pending_change);
+			writes += 1;
+		}
+
+		EpochConfig::put(epoch_config);
+		writes += 1;
+
+		T::DbWeight::get().reads_writes(2, writes)
 	}
 
 	fn deposit_consensus<U: Encode>(new: U) {
@@ -559,6 +646,7 @@ impl<T: Trait> Module<T> {
 		if !authorities.is_empty() {
 			assert!(Authorities::get().is_empty(), "Authorities are already initialized!");
 			Authorities::put(authorities);
+			NextAuthorities::put(authorities);
 		}
 	}
 }
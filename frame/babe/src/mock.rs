@@ -32,7 +32,7 @@ use frame_support::{
 };
 use sp_io;
 use sp_core::{H256, U256, crypto::Pair};
-use sp_consensus_babe::AuthorityPair;
+use sp_consensus_babe::{AllowedSlots, AuthorityPair, BabeEpochConfiguration};
 use sp_consensus_vrf::schnorrkel::{VRFOutput, VRFProof};
 
 impl_outer_origin!{
@@ -112,6 +112,12 @@ impl Trait for Test {
 	type EpochChangeTrigger = crate::ExternalTrigger;
 }
 
+/// The epoch configuration of the test chain at genesis.
+pub const GENESIS_EPOCH_CONFIG: BabeEpochConfiguration = BabeEpochConfiguration {
+	c: (1, 4),
+	allowed_slots: AllowedSlots::PrimaryAndSecondaryPlainSlots,
+};
+
 pub fn new_test_ext(authorities_len: usize) -> (Vec<AuthorityPair>, sp_io::TestExternalities) {
 	let pairs = (0..authorities_len).map(|i| {
 		AuthorityPair::from_seed(&U256::from(i).into())
@@ -120,6 +126,7 @@ pub fn new_test_ext(authorities_len: usize) -> (Vec<AuthorityPair>, sp_io::TestE
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	GenesisConfig {
 		authorities: pairs.iter().map(|a| (a.public(), 1)).collect(),
+		epoch_config: Some(GENESIS_EPOCH_CONFIG),
 	}.assimilate_storage::<Test>(&mut t).unwrap();
 	(pairs, t.into())
 }
@@ -21,8 +21,8 @@ use super::*;
 use mock::*;
 use frame_support::traits::OnFinalize;
 use pallet_session::ShouldEndSession;
-use sp_core::crypto::IsWrappedBy;
-use sp_consensus_babe::AllowedSlots;
+use sp_core::crypto::{IsWrappedBy, Pair};
+use sp_consensus_babe::{AllowedSlots, BabeEpochConfiguration};
 use sp_consensus_vrf::schnorrkel::{VRFOutput, VRFProof};
 
 const EMPTY_RANDOMNESS: [u8; 32] = [
@@ -180,6 +180,80 @@ fn can_enact_next_config() {
 		);
 		let consensus_digest = DigestItem::Consensus(BABE_ENGINE_ID, consensus_log.encode());
 
-		assert_eq!(header.digest.logs[2], consensus_digest.clone())
+		assert_eq!(header.digest.logs[2], consensus_digest.clone());
+
+		// the change is announced for the next epoch.
+		assert_eq!(Babe::current_epoch().config, GENESIS_EPOCH_CONFIG);
+		assert_eq!(
+			Babe::next_epoch().config,
+			BabeEpochConfiguration { c: (1, 4), allowed_slots: AllowedSlots::PrimarySlots },
+		);
+	});
+}
+
+#[test]
+fn current_and_next_epoch_are_reported() {
+	let (pairs, mut ext) = new_test_ext(3);
+	ext.execute_with(|| {
+		let authorities = pairs.iter().map(|a| (a.public(), 1)).collect::<Vec<_>>();
+		go_to_block(1, 6);
+
+		let current_epoch = Babe::current_epoch();
+		assert_eq!(current_epoch.epoch_index, 0);
+		assert_eq!(current_epoch.start_slot, 6);
+		assert_eq!(current_epoch.duration, 3);
+		assert_eq!(current_epoch.authorities, authorities);
+		assert_eq!(current_epoch.config, GENESIS_EPOCH_CONFIG);
+
+		let next_epoch = Babe::next_epoch();
+		assert_eq!(next_epoch.epoch_index, 1);
+		assert_eq!(next_epoch.start_slot, 9);
+		assert_eq!(next_epoch.authorities, authorities);
+		assert_eq!(next_epoch.config, GENESIS_EPOCH_CONFIG);
+
+		Babe::enact_epoch_change(authorities[..1].to_vec(), authorities[1..].to_vec());
+
+		let current_epoch = Babe::current_epoch();
+		assert_eq!(current_epoch.epoch_index, 1);
+		assert_eq!(current_epoch.start_slot, 9);
+		assert_eq!(current_epoch.authorities, authorities[..1].to_vec());
+		assert_eq!(current_epoch.randomness, Babe::randomness());
+
+		let next_epoch = Babe::next_epoch();
+		assert_eq!(next_epoch.epoch_index, 2);
+		assert_eq!(next_epoch.start_slot, 12);
+		assert_eq!(next_epoch.authorities, authorities[1..].to_vec());
+		assert_eq!(next_epoch.randomness, NextRandomness::get());
+	});
+}
+
+#[test]
+fn migrate_epoch_config_keeps_pending_change() {
+	new_test_ext(0).1.execute_with(|| {
+		let pending_change = NextConfigDescriptor::V1 {
+			c: (1, 2),
+			allowed_slots: AllowedSlots::PrimarySlots,
+		};
+
+		// storage of a chain started before the epoch configuration was stored.
+		EpochConfig::kill();
+		frame_support::storage::unhashed::put(&NextEpochConfig::hashed_key(), &pending_change);
+
+		Babe::migrate_epoch_config(GENESIS_EPOCH_CONFIG);
+
+		assert_eq!(EpochConfig::get(), Some(GENESIS_EPOCH_CONFIG));
+		assert_eq!(NextEpochConfig::get(), None);
+		assert_eq!(PendingEpochConfigChange::get(), Some(pending_change.clone()));
+		assert_eq!(Babe::current_epoch().config, GENESIS_EPOCH_CONFIG);
+
+		// migrating again doesn't read the new `NextEpochConfig` as a pending change.
+		let next_config = BabeEpochConfiguration {
+			c: (1, 2),
+			allowed_slots: AllowedSlots::PrimarySlots,
+		};
+		NextEpochConfig::put(next_config.clone());
+		Babe::migrate_epoch_config(GENESIS_EPOCH_CONFIG);
+		assert_eq!(NextEpochConfig::get(), Some(next_config));
+		assert_eq!(PendingEpochConfigChange::get(), Some(pending_change));
 	});
 }
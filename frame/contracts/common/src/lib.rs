@@ -45,3 +45,54 @@ pub enum RentProjection<BlockNumber> {
 	/// E.g. because the contract accumulated enough funds to offset the rent storage costs.
 	NoEviction,
 }
+
+/// The kind of a frame recorded in a [`CallTrace`].
+#[derive(Eq, PartialEq, Clone, Copy, codec::Encode, codec::Decode, sp_runtime::RuntimeDebug)]
+pub enum CallKind {
+	/// A call to an existing contract or a plain account.
+	Call,
+	/// The instantiation of a new contract.
+	Instantiate,
+	/// The execution of another contract's code in the storage context of the caller.
+	DelegateCall,
+}
+
+/// An event deposited by a contract through `ext_deposit_event`.
+#[derive(Eq, PartialEq, Clone, codec::Encode, codec::Decode, sp_runtime::RuntimeDebug)]
+pub struct ContractEvent<Hash> {
+	/// The topics the event was deposited with.
+	pub topics: Vec<Hash>,
+	/// The data supplied by the contract.
+	pub data: Vec<u8>,
+}
+
+/// The trace of a single frame of a contract execution.
+///
+/// A traced execution yields a list of these in the order in which the frames were entered.
+/// The call tree can be reconstructed from the `depth` of each frame: the parent of a frame is
+/// the closest preceding frame with a smaller depth.
+#[derive(Eq, PartialEq, Clone, codec::Encode, codec::Decode, sp_runtime::RuntimeDebug)]
+pub struct CallTrace<AccountId, Balance, Hash> {
+	/// Whether this frame is a call or an instantiation.
+	pub kind: CallKind,
+	/// The nesting level of this frame. The frame entered by the origin has a depth of 1.
+	pub depth: u32,
+	/// The account that was called or instantiated.
+	pub dest: AccountId,
+	/// The value transferred to `dest`.
+	pub value: Balance,
+	/// The gas consumed by this frame, including the gas consumed by its nested frames.
+	pub gas_used: u64,
+	/// Whether the frame succeeded.
+	///
+	/// The storage changes and events of a failed frame and of all its nested frames are
+	/// reverted.
+	pub success: bool,
+	/// The storage keys of `dest` read by this frame, in the order of their first access.
+	pub storage_reads: Vec<[u8; 32]>,
+	/// The storage keys of `dest` written or cleared by this frame, in the order of their
+	/// first access.
+	pub storage_writes: Vec<[u8; 32]>,
+	/// The events deposited by this frame.
+	pub events: Vec<ContractEvent<Hash>>,
+}
@@ -0,0 +1,57 @@
+;; Call the chain extension and return its output to the caller.
+;;
+;; The input is the id of the function to call in the first four bytes followed by the
+;; input of that function. The output is the value returned by the chain extension followed
+;; by the contents of the scratch buffer.
+(module
+	(import "env" "ext_call_chain_extension"
+		(func $ext_call_chain_extension (param i32 i32 i32) (result i32))
+	)
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "ext_return" (func $ext_return (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(local $input_len i32)
+		(local $output_len i32)
+
+		;; Copy the input into the contract's memory.
+		(set_local $input_len (call $ext_scratch_size))
+		(call $ext_scratch_read
+			(i32.const 0)		;; Pointer in memory to the place where to copy.
+			(i32.const 0)		;; Offset from the start of the scratch buffer.
+			(get_local $input_len)	;; Count of bytes to copy.
+		)
+
+		;; Call the chain extension and store the returned value at offset 1024.
+		(i32.store
+			(i32.const 1024)
+			(call $ext_call_chain_extension
+				(i32.load (i32.const 0))	;; The function id.
+				(i32.const 4)			;; Pointer to the input of the function.
+				(i32.sub			;; Length of the input of the function.
+					(get_local $input_len)
+					(i32.const 4)
+				)
+			)
+		)
+
+		;; Append the contents of the scratch buffer and return everything.
+		(set_local $output_len (call $ext_scratch_size))
+		(call $ext_scratch_read
+			(i32.const 1028)
+			(i32.const 0)
+			(get_local $output_len)
+		)
+		(call $ext_return
+			(i32.const 1024)
+			(i32.add
+				(get_local $output_len)
+				(i32.const 4)
+			)
+		)
+	)
+)
@@ -0,0 +1,85 @@
+(module
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "ext_scratch_write" (func $ext_scratch_write (param i32 i32)))
+
+	(import "env" "ext_ecdsa_recover" (func $ext_ecdsa_recover (param i32 i32 i32) (result i32)))
+	(import "env" "ext_sr25519_verify"
+		(func $ext_sr25519_verify (param i32 i32 i32 i32) (result i32))
+	)
+	(import "env" "ext_ed25519_verify"
+		(func $ext_ed25519_verify (param i32 i32 i32 i32) (result i32))
+	)
+
+	(import "env" "memory" (memory 1 1))
+
+	;; Not in use by the tests besides instantiating the contract.
+	(func (export "deploy"))
+
+	;; Called by the tests.
+	;;
+	;; The first byte of the input selects the function under test, the rest of the input
+	;; contains its arguments:
+	;;
+	;; | value | Function           | Arguments                                           |
+	;; |-------|--------------------|-----------------------------------------------------|
+	;; |     0 | ext_ecdsa_recover  | signature (65 bytes), message hash (32 bytes)       |
+	;; |     1 | ext_sr25519_verify | signature (64 bytes), public key (32 bytes), message |
+	;; |     2 | ext_ed25519_verify | signature (64 bytes), public key (32 bytes), message |
+	;;
+	;; The value returned by the function is put into the scratch buffer as a little endian
+	;; `u32`. For `ext_ecdsa_recover` it is followed by the 33 bytes of the output buffer.
+	(func (export "call") (result i32)
+		(local $input_len i32)
+		(local $chosen_fn i32)
+
+		;; Copy the input into the contract's memory.
+		(local.set $input_len (call $ext_scratch_size))
+		(call $ext_scratch_read (i32.const 0) (i32.const 0) (local.get $input_len))
+		(local.set $chosen_fn (i32.load8_u (i32.const 0)))
+
+		(if (i32.eqz (local.get $chosen_fn))
+			(then
+				(i32.store
+					(i32.const 1024)
+					(call $ext_ecdsa_recover
+						(i32.const 1)		;; Pointer to the signature.
+						(i32.const 66)		;; Pointer to the message hash.
+						(i32.const 1028)	;; Pointer to the output buffer.
+					)
+				)
+				(call $ext_scratch_write (i32.const 1024) (i32.const 37))
+				(return (i32.const 0))
+			)
+		)
+
+		(if (i32.gt_u (local.get $chosen_fn) (i32.const 2))
+			;; We check that the chosen function identifier is within bounds: [0,2]
+			(then (unreachable))
+		)
+
+		(i32.store
+			(i32.const 1024)
+			(if (result i32) (i32.eq (local.get $chosen_fn) (i32.const 1))
+				(then
+					(call $ext_sr25519_verify
+						(i32.const 1)		;; Pointer to the signature.
+						(i32.const 65)		;; Pointer to the public key.
+						(i32.const 97)		;; Pointer to the message.
+						(i32.sub (local.get $input_len) (i32.const 97))
+					)
+				)
+				(else
+					(call $ext_ed25519_verify
+						(i32.const 1)		;; Pointer to the signature.
+						(i32.const 65)		;; Pointer to the public key.
+						(i32.const 97)		;; Pointer to the message.
+						(i32.sub (local.get $input_len) (i32.const 97))
+					)
+				)
+			)
+		)
+		(call $ext_scratch_write (i32.const 1024) (i32.const 4))
+		(i32.const 0)
+	)
+)
@@ -24,7 +24,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Codec, Decode, Encode};
-use pallet_contracts_primitives::{GetStorageResult, RentProjectionResult};
+use pallet_contracts_primitives::{CallTrace, GetStorageResult, RentProjectionResult};
 use sp_runtime::RuntimeDebug;
 use sp_std::vec::Vec;
 
@@ -46,12 +46,25 @@ pub enum ContractExecResult {
 	Error,
 }
 
+/// The result of a traced execution of a contract call.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ContractTraceResult<AccountId, Balance, Hash> {
+	/// The result of the call.
+	pub exec_result: ContractExecResult,
+	/// The gas consumed by the call.
+	pub gas_consumed: u64,
+	/// The traces of all frames entered by the call, in the order they were entered.
+	pub trace: Vec<CallTrace<AccountId, Balance, Hash>>,
+}
+
 sp_api::decl_runtime_apis! {
 	/// The API to interact with contracts without using executive.
-	pub trait ContractsApi<AccountId, Balance, BlockNumber> where
+	#[api_version(2)]
+	pub trait ContractsApi<AccountId, Balance, BlockNumber, Hash> where
 		AccountId: Codec,
 		Balance: Codec,
 		BlockNumber: Codec,
+		Hash: Codec,
 	{
 		/// Perform a call from a specified account to a given contract.
 		///
@@ -64,6 +77,18 @@ sp_api::decl_runtime_apis! {
 			input_data: Vec<u8>,
 		) -> ContractExecResult;
 
+		/// Perform a call from a specified account to a given contract and trace its execution.
+		///
+		/// Works like `call` but additionally returns the gas consumed and, for every frame that
+		/// was entered, the storage keys accessed, the events deposited and the gas used.
+		fn trace_call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractTraceResult<AccountId, Balance, Hash>;
+
 		/// Query a given storage key in a given contract.
 		///
 		/// Returns `Ok(Some(Vec<u8>))` if the storage value exists under the given key in the
@@ -22,12 +22,13 @@ use std::sync::Arc;
 use codec::Codec;
 use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
-use pallet_contracts_primitives::RentProjection;
+use pallet_contracts_primitives::{CallKind, CallTrace, ContractEvent, RentProjection};
+use sc_rpc_api::DenyUnsafe;
 use serde::{Deserialize, Serialize};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::{Bytes, H256};
-use sp_rpc::number;
+use sp_rpc::{gas::search_gas_limit, number};
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, Header as HeaderT},
@@ -35,7 +36,8 @@ use sp_runtime::{
 
 pub use self::gen_client::Client as ContractsClient;
 pub use pallet_contracts_rpc_runtime_api::{
-	self as runtime_api, ContractExecResult, ContractsApi as ContractsRuntimeApi,
+	self as runtime_api, ContractExecResult, ContractTraceResult,
+	ContractsApi as ContractsRuntimeApi,
 };
 
 const RUNTIME_ERROR: i64 = 1;
@@ -112,6 +114,105 @@ impl From<ContractExecResult> for RpcContractExecResult {
 	}
 }
 
+/// An RPC serializable kind of a traced frame.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcCallKind {
+	/// A call to an existing contract or a plain account.
+	Call,
+	/// The instantiation of a new contract.
+	Instantiate,
+	/// The execution of another contract's code in the storage context of the caller.
+	DelegateCall,
+}
+
+impl From<CallKind> for RpcCallKind {
+	fn from(kind: CallKind) -> Self {
+		match kind {
+			CallKind::Call => RpcCallKind::Call,
+			CallKind::Instantiate => RpcCallKind::Instantiate,
+			CallKind::DelegateCall => RpcCallKind::DelegateCall,
+		}
+	}
+}
+
+/// An RPC serializable event deposited by a contract.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcContractEvent<Hash> {
+	/// The topics the event was deposited with.
+	topics: Vec<Hash>,
+	/// The data supplied by the contract.
+	data: Bytes,
+}
+
+impl<Hash> From<ContractEvent<Hash>> for RpcContractEvent<Hash> {
+	fn from(event: ContractEvent<Hash>) -> Self {
+		RpcContractEvent {
+			topics: event.topics,
+			data: event.data.into(),
+		}
+	}
+}
+
+/// An RPC serializable trace of a single frame of a contract execution.
+///
+/// See `pallet_contracts_primitives::CallTrace` for the meaning of the fields.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct RpcCallTrace<AccountId, Balance, Hash> {
+	kind: RpcCallKind,
+	depth: u32,
+	dest: AccountId,
+	value: Balance,
+	gas_used: u64,
+	success: bool,
+	storage_reads: Vec<H256>,
+	storage_writes: Vec<H256>,
+	events: Vec<RpcContractEvent<Hash>>,
+}
+
+impl<AccountId, Balance, Hash> From<CallTrace<AccountId, Balance, Hash>>
+	for RpcCallTrace<AccountId, Balance, Hash>
+{
+	fn from(trace: CallTrace<AccountId, Balance, Hash>) -> Self {
+		RpcCallTrace {
+			kind: trace.kind.into(),
+			depth: trace.depth,
+			dest: trace.dest,
+			value: trace.value,
+			gas_used: trace.gas_used,
+			success: trace.success,
+			storage_reads: trace.storage_reads.into_iter().map(Into::into).collect(),
+			storage_writes: trace.storage_writes.into_iter().map(Into::into).collect(),
+			events: trace.events.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// An RPC serializable result of a contract call dry-run.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct RpcDryRunResult<AccountId, Balance, Hash> {
+	/// The result of the call when executed with the requested gas limit.
+	result: RpcContractExecResult,
+	/// The gas consumed by the call when executed with the requested gas limit.
+	///
+	/// `None` if the runtime doesn't support tracing calls.
+	gas_consumed: Option<u64>,
+	/// The smallest gas limit with which the call yields the same result.
+	///
+	/// This can be more than `gas_consumed` if a nested call requires a gas limit that exceeds
+	/// the gas it actually consumes. `None` if the call failed.
+	gas_required: Option<u64>,
+	/// The traces of all frames entered by the call, in the order they were entered.
+	///
+	/// Empty if the runtime doesn't support tracing calls.
+	trace: Vec<RpcCallTrace<AccountId, Balance, Hash>>,
+}
+
 /// Contracts RPC methods.
 #[rpc]
 pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
@@ -128,6 +229,23 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 		at: Option<BlockHash>,
 	) -> Result<RpcContractExecResult>;
 
+	/// Dry-runs a call to a contract and traces its execution.
+	///
+	/// Like `contracts_call` this doesn't change any state. Additionally to the result this
+	/// returns the gas consumed, the smallest gas limit the call requires and for every
+	/// entered frame the storage keys accessed, the events deposited and the gas used.
+	///
+	/// The required gas limit is searched for by repeating the call with smaller limits, which
+	/// makes this method considerably more expensive than `contracts_call`. It is therefore
+	/// considered unsafe. The number of repetitions is bounded, so the returned limit can be
+	/// slightly above the smallest one.
+	#[rpc(name = "contracts_dryRun")]
+	fn dry_run(
+		&self,
+		call_request: CallRequest<AccountId, Balance>,
+		at: Option<BlockHash>,
+	) -> Result<RpcDryRunResult<AccountId, Balance, BlockHash>>;
+
 	/// Returns the value under a specified storage `key` in a contract given by `address` param,
 	/// or `None` if it is not set.
 	#[rpc(name = "contracts_getStorage")]
@@ -155,14 +273,16 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 /// An implementation of contract specific RPC methods.
 pub struct Contracts<C, B> {
 	client: Arc<C>,
+	deny_unsafe: DenyUnsafe,
 	_marker: std::marker::PhantomData<B>,
 }
 
 impl<C, B> Contracts<C, B> {
 	/// Create new `Contracts` with the given reference to the client.
-	pub fn new(client: Arc<C>) -> Self {
+	pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
 		Contracts {
 			client,
+			deny_unsafe,
 			_marker: Default::default(),
 		}
 	}
@@ -182,9 +302,10 @@ where
 		AccountId,
 		Balance,
 		<<Block as BlockT>::Header as HeaderT>::Number,
+		<Block as BlockT>::Hash,
 	>,
-	AccountId: Codec,
-	Balance: Codec,
+	AccountId: Codec + Clone,
+	Balance: Codec + Clone,
 {
 	fn call(
 		&self,
@@ -203,23 +324,7 @@ where
 			gas_limit,
 			input_data,
 		} = call_request;
-		let gas_limit = gas_limit.to_number().map_err(|e| Error {
-			code: ErrorCode::InvalidParams,
-			message: e,
-			data: None,
-		})?;
-
-		let max_gas_limit = 5 * GAS_PER_SECOND;
-		if gas_limit > max_gas_limit {
-			return Err(Error {
-				code: ErrorCode::InvalidParams,
-				message: format!(
-					"Requested gas limit is greater than maximum allowed: {} > {}",
-					gas_limit, max_gas_limit
-				),
-				data: None,
-			});
-		}
+		let gas_limit = checked_gas_limit(gas_limit)?;
 
 		let exec_result = api
 			.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
@@ -228,6 +333,76 @@ where
 		Ok(exec_result.into())
 	}
 
+	fn dry_run(
+		&self,
+		call_request: CallRequest<AccountId, Balance>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcDryRunResult<AccountId, Balance, <Block as BlockT>::Hash>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let CallRequest {
+			origin,
+			dest,
+			value,
+			gas_limit,
+			input_data,
+		} = call_request;
+		let gas_limit = checked_gas_limit(gas_limit)?;
+		let input_data = input_data.to_vec();
+
+		let api = self.client.runtime_api();
+		let supports_tracing = api
+			.has_api_with::<dyn ContractsRuntimeApi<
+				Block,
+				AccountId,
+				Balance,
+				<<Block as BlockT>::Header as HeaderT>::Number,
+				<Block as BlockT>::Hash,
+				Error = (),
+			>, _>(&at, |version| version >= 2)
+			.map_err(|e| runtime_error_into_rpc_err(e))?;
+		let (exec_result, gas_consumed, trace) = if supports_tracing {
+			let ContractTraceResult { exec_result, gas_consumed, trace } = api
+				.trace_call(&at, origin.clone(), dest.clone(), value.clone(), gas_limit, input_data.clone())
+				.map_err(|e| runtime_error_into_rpc_err(e))?;
+			(exec_result, Some(gas_consumed), trace)
+		} else {
+			// Runtimes implementing the first version of the api can't trace calls. The gas
+			// required is then searched for starting from zero.
+			let exec_result = api
+				.call(&at, origin.clone(), dest.clone(), value.clone(), gas_limit, input_data.clone())
+				.map_err(|e| runtime_error_into_rpc_err(e))?;
+			(exec_result, None, Vec::new())
+		};
+
+		let gas_required = match exec_result {
+			ContractExecResult::Success { .. } => {
+				let yields_same_result = |limit| {
+					// A fresh runtime api instance is used for every attempt so that none of them
+					// observes the changes of a previous one.
+					self.client
+						.runtime_api()
+						.call(&at, origin.clone(), dest.clone(), value.clone(), limit, input_data.clone())
+						.map(|result| result == exec_result)
+						.map_err(|e| runtime_error_into_rpc_err(e))
+				};
+				Some(search_gas_limit(gas_consumed.unwrap_or(0), gas_limit, yields_same_result)?)
+			}
+			ContractExecResult::Error => None,
+		};
+
+		Ok(RpcDryRunResult {
+			result: exec_result.into(),
+			gas_consumed,
+			gas_required,
+			trace: trace.into_iter().map(Into::into).collect(),
+		})
+	}
+
 	fn get_storage(
 		&self,
 		address: AccountId,
@@ -270,6 +445,29 @@ where
 	}
 }
 
+/// Converts the requested gas limit into a number and checks that it doesn't exceed the maximum.
+fn checked_gas_limit(gas_limit: number::NumberOrHex<u64>) -> Result<u64> {
+	let gas_limit = gas_limit.to_number().map_err(|e| Error {
+		code: ErrorCode::InvalidParams,
+		message: e,
+		data: None,
+	})?;
+
+	let max_gas_limit = 5 * GAS_PER_SECOND;
+	if gas_limit > max_gas_limit {
+		return Err(Error {
+			code: ErrorCode::InvalidParams,
+			message: format!(
+				"Requested gas limit is greater than maximum allowed: {} > {}",
+				gas_limit, max_gas_limit
+			),
+			data: None,
+		});
+	}
+
+	Ok(gas_limit)
+}
+
 /// Converts a runtime trap into an RPC error.
 fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
 	Error {
@@ -294,4 +492,12 @@ mod tests {
 		test(r#"{"success":{"status":5,"data":"0x1234"}}"#);
 		test(r#"{"error":null}"#);
 	}
+
+	#[test]
+	fn should_serialize_dry_run_result_properly() {
+		let expected = r#"{"result":{"success":{"status":0,"data":"0x"}},"gasConsumed":100,"gasRequired":120,"trace":[{"kind":"instantiate","depth":1,"dest":5,"value":10,"gasUsed":80,"success":true,"storageReads":[],"storageWrites":["0x0101010101010101010101010101010101010101010101010101010101010101"],"events":[{"topics":[7],"data":"0x2a"}]}]}"#;
+		let res: RpcDryRunResult<u64, u64, u64> = serde_json::from_str(expected).unwrap();
+		let actual = serde_json::to_string(&res).unwrap();
+		assert_eq!(actual, expected);
+	}
 }
@@ -51,6 +51,11 @@ pub struct ChangeEntry<T: Trait> {
 }
 
 impl<T: Trait> ChangeEntry<T> {
+	/// Whether committing this entry changes the storage of a contract that remains alive.
+	fn changes_storage(&self) -> bool {
+		!self.storage.is_empty() && (!self.reset || self.code_hash.is_some())
+	}
+
 	fn balance(&self) -> Option<BalanceOf<T>> {
 		self.balance.or_else(|| {
 			if self.reset {
@@ -102,6 +107,19 @@ impl<T: Trait> Default for ChangeEntry<T> {
 
 pub type ChangeSet<T> = BTreeMap<<T as frame_system::Trait>::AccountId, ChangeEntry<T>>;
 
+/// How committing a change set alters the storage used by a contract.
+#[derive(Default)]
+pub struct StorageDelta {
+	/// The number of bytes added by new or grown values.
+	pub bytes_added: u32,
+	/// The number of bytes freed by removed or shrunk values.
+	pub bytes_removed: u32,
+	/// The number of key-value pairs added.
+	pub items_added: u32,
+	/// The number of key-value pairs removed.
+	pub items_removed: u32,
+}
+
 pub trait AccountDb<T: Trait> {
 	/// Account is used when overlayed otherwise trie_id must be provided.
 	/// This is for performance reason.
@@ -188,6 +206,7 @@ impl<T: Trait> AccountDb<T> for DirectAccountDb {
 							trie_id: <T as Trait>::TrieIdGenerator::trie_id(&address),
 							deduct_block: <frame_system::Module<T>>::block_number(),
 							rent_allowance: <BalanceOf<T>>::max_value(),
+							storage_deposit: <BalanceOf<T>>::zero(),
 							last_write: None,
 						}
 					}
@@ -200,6 +219,7 @@ impl<T: Trait> AccountDb<T> for DirectAccountDb {
 						trie_id: <T as Trait>::TrieIdGenerator::trie_id(&address),
 						deduct_block: <frame_system::Module<T>>::block_number(),
 						rent_allowance: <BalanceOf<T>>::max_value(),
+						storage_deposit: <BalanceOf<T>>::zero(),
 						last_write: None,
 					},
 					// There is no existing at the address nor a new one to be instantiated.
@@ -322,6 +342,69 @@ impl<'a, T: Trait> OverlayAccountDb<'a, T> {
 		self.local.into_inner()
 	}
 
+	/// Returns the contracts whose existing storage is wiped when this overlay is committed.
+	///
+	/// These are the contracts that are removed as well as the ones that are instantiated anew.
+	pub fn reset_contracts(&self) -> Vec<T::AccountId> {
+		self.local
+			.borrow()
+			.iter()
+			.filter(|(_, changed)| changed.reset)
+			.map(|(address, _)| address.clone())
+			.collect()
+	}
+
+	/// Returns the number of changed storage keys whose previous value `storage_deltas` reads.
+	pub fn changed_keys(&self) -> u32 {
+		self.local
+			.borrow()
+			.values()
+			.filter(|changed| changed.changes_storage())
+			.map(|changed| changed.storage.len() as u32)
+			.fold(0, u32::saturating_add)
+	}
+
+	/// Computes how the storage used by every contract changes when this overlay is committed.
+	///
+	/// Contracts that are removed by this overlay are omitted.
+	pub fn storage_deltas(&self) -> Vec<(T::AccountId, StorageDelta)> {
+		self.local
+			.borrow()
+			.iter()
+			.filter(|(_, changed)| changed.changes_storage())
+			.map(|(address, changed)| {
+				// A reset contract starts out with empty storage.
+				let trie_id = if changed.reset {
+					None
+				} else {
+					<ContractInfoOf<T>>::get(address)
+						.and_then(|i| i.get_alive())
+						.map(|i| i.trie_id)
+				};
+
+				let mut delta = StorageDelta::default();
+				for (key, opt_new_value) in changed.storage.iter() {
+					let opt_prev_value = self.underlying.get_storage(address, trie_id.as_ref(), key);
+					let prev_len = opt_prev_value.as_ref().map(|v| v.len() as u32).unwrap_or(0);
+					let new_len = opt_new_value.as_ref().map(|v| v.len() as u32).unwrap_or(0);
+
+					match (opt_prev_value.is_some(), opt_new_value.is_some()) {
+						(true, false) => delta.items_removed += 1,
+						(false, true) => delta.items_added += 1,
+						_ => {}
+					}
+					if new_len > prev_len {
+						delta.bytes_added += new_len - prev_len;
+					} else {
+						delta.bytes_removed += prev_len - new_len;
+					}
+				}
+
+				(address.clone(), delta)
+			})
+			.collect()
+	}
+
 	pub fn set_storage(
 		&mut self,
 		account: &T::AccountId,
@@ -367,6 +450,17 @@ impl<'a, T: Trait> OverlayAccountDb<'a, T> {
 		);
 	}
 
+	/// Replace the code of an existing contract, keeping its storage and balance.
+	///
+	/// Assume contract exists
+	pub fn set_code_hash(&mut self, account: &T::AccountId, code_hash: CodeHash<T>) {
+		self.local
+			.borrow_mut()
+			.entry(account.clone())
+			.or_insert(Default::default())
+			.code_hash = Some(code_hash);
+	}
+
 	/// Assume contract exists
 	pub fn set_rent_allowance(&mut self, account: &T::AccountId, rent_allowance: BalanceOf<T>) {
 		self.local
@@ -0,0 +1,274 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate. If not, see <http://www.gnu.org/licenses/>.
+
+//! Functions to procedurally construct contract code used for benchmarking.
+//!
+//! In order to be able to benchmark events that are triggered by contract execution
+//! (host function calls, individual instructions), we need to generate contracts that
+//! perform those events. Because the `wat` parser is only available in `std` we build
+//! the modules with the `parity_wasm` builder instead.
+
+use crate::{Trait, CodeHash};
+use parity_wasm::elements::{BlockType, Instruction, Instructions, FuncBody, ValueType};
+use sp_runtime::traits::Hash;
+use sp_std::{prelude::*, convert::TryFrom};
+
+/// Convert into a `WasmModule` in order to create a compiled wasm module.
+///
+/// This exists to have a more declarative way to describe a wasm module than to use
+/// parity-wasm directly. It is tailored to fit the structure of contracts that are
+/// needed for benchmarking.
+#[derive(Default)]
+pub struct ModuleDefinition {
+	/// Imported memory attached to the module. No memory is imported if `None`.
+	pub memory: Option<ImportedMemory>,
+	/// Initializers for the imported memory.
+	pub data_segments: Vec<DataSegment>,
+	/// Creates the supplied amount of i64 mutable globals initialized with zero.
+	pub num_globals: u32,
+	/// List of functions that the module should import. They start with index 0.
+	pub imported_functions: Vec<ImportedFunction>,
+	/// Function body of the exported `deploy` function. Body is empty if `None`.
+	/// Its index is `imported_functions.len()`.
+	pub deploy_body: Option<FuncBody>,
+	/// Function body of the exported `call` function. Body is empty if `None`.
+	/// Its index is `imported_functions.len() + 1`.
+	pub call_body: Option<FuncBody>,
+}
+
+/// A memory segment that is placed into the imported memory on instantiation.
+pub struct DataSegment {
+	pub offset: u32,
+	pub value: Vec<u8>,
+}
+
+/// The memory that is imported by a module.
+pub struct ImportedMemory {
+	pub min_pages: u32,
+	pub max_pages: u32,
+}
+
+impl ImportedMemory {
+	/// A memory of `max_memory_pages` as permitted by the current schedule.
+	pub fn max<T: Trait>() -> Self {
+		let pages = crate::Module::<T>::current_schedule().max_memory_pages;
+		Self { min_pages: pages, max_pages: pages }
+	}
+}
+
+/// A host function that is imported by a module.
+pub struct ImportedFunction {
+	pub name: &'static str,
+	pub params: Vec<ValueType>,
+	pub return_type: Option<ValueType>,
+}
+
+/// A wasm module ready to be put on chain with `put_code`.
+pub struct WasmModule<T: Trait> {
+	pub code: Vec<u8>,
+	pub hash: CodeHash<T>,
+}
+
+impl<T: Trait> From<ModuleDefinition> for WasmModule<T> {
+	fn from(def: ModuleDefinition) -> Self {
+		// Internal functions start at that offset.
+		let func_offset = u32::try_from(def.imported_functions.len())
+			.expect("The number of imported functions of a benchmark fits into u32; qed");
+
+		// Every contract must export "deploy" and "call" functions.
+		let mut contract = parity_wasm::builder::module()
+			// deploy function (first internal function)
+			.function()
+				.signature().with_return_type(None).build()
+				.with_body(def.deploy_body.unwrap_or_else(FuncBody::empty))
+				.build()
+			// call function (second internal function)
+			.function()
+				.signature().with_return_type(None).build()
+				.with_body(def.call_body.unwrap_or_else(FuncBody::empty))
+				.build()
+			.export().field("deploy").internal().func(func_offset).build()
+			.export().field("call").internal().func(func_offset + 1).build();
+
+		// Grant access to linear memory.
+		if let Some(memory) = &def.memory {
+			contract = contract.import()
+				.module("env").field("memory")
+				.external().memory(memory.min_pages, Some(memory.max_pages))
+				.build();
+		}
+
+		// Import host functions. They start with idx 0.
+		for func in def.imported_functions {
+			let sig = parity_wasm::builder::signature()
+				.with_params(func.params)
+				.with_return_type(func.return_type)
+				.build_sig();
+			let sig = contract.push_signature(sig);
+			contract = contract.import()
+				.module("env")
+				.field(func.name)
+				.with_external(parity_wasm::elements::External::Function(sig))
+				.build();
+		}
+
+		// Initialize memory.
+		for data in def.data_segments {
+			contract = contract.data()
+				.offset(Instruction::I32Const(data.offset as i32))
+				.value(data.value)
+				.build()
+		}
+
+		// Add global variables.
+		for _ in 0 .. def.num_globals {
+			contract = contract.global()
+				.value_type().i64()
+				.mutable()
+				.init_expr(Instruction::I64Const(0))
+				.build();
+		}
+
+		let code = contract.build().to_bytes()
+			.expect("The modules built for benchmarking are valid wasm; qed");
+		let hash = T::Hashing::hash(&code);
+		Self {
+			code,
+			hash,
+		}
+	}
+}
+
+impl<T: Trait> WasmModule<T> {
+	/// Creates a wasm module with an empty `call` and `deploy` function and nothing else.
+	pub fn dummy() -> Self {
+		ModuleDefinition::default().into()
+	}
+
+	/// Creates a wasm module of roughly `target_bytes` size.
+	///
+	/// The module consists of a `call` function which pushes and drops constants
+	/// `target_bytes / 3` times. Every pair of these instructions takes three bytes, not
+	/// counting the loops they are split into by [`body::repeated`].
+	pub fn sized(target_bytes: u32) -> Self {
+		ModuleDefinition {
+			call_body: Some(body::repeated(
+				target_bytes / 3,
+				&[Instruction::I32Const(0), Instruction::Drop],
+			)),
+			.. Default::default()
+		}
+		.into()
+	}
+
+	/// Creates a wasm module that calls the imported function `name` `repeat` times.
+	///
+	/// The function must not take any arguments nor return a value. This is the case for all
+	/// host functions that only put a value into the scratch buffer.
+	pub fn getter(name: &'static str, repeat: u32) -> Self {
+		ModuleDefinition {
+			imported_functions: vec![ImportedFunction {
+				name,
+				params: Vec::new(),
+				return_type: None,
+			}],
+			call_body: Some(body::repeated(repeat, &[Instruction::Call(0)])),
+			.. Default::default()
+		}
+		.into()
+	}
+
+	/// Creates a wasm module that executes `instructions` `repeat` times.
+	///
+	/// The module imports the maximum amount of memory and declares a single `i64` global
+	/// and a single `i64` local (both at index 0) which can be used by `instructions`.
+	pub fn instructions(instructions: &[Instruction], repeat: u32) -> Self {
+		ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			num_globals: 1,
+			call_body: Some(body::repeated_with_locals(
+				vec![parity_wasm::elements::Local::new(1, ValueType::I64)],
+				repeat,
+				instructions,
+			)),
+			.. Default::default()
+		}
+		.into()
+	}
+}
+
+/// Mechanisms to create a function body that can be used inside a `ModuleDefinition`.
+pub mod body {
+	use super::*;
+
+	/// How many repetitions of the instructions passed to [`repeated`] share a loop.
+	const REPETITIONS_PER_LOOP: usize = 1_000;
+
+	/// Creates a body that executes `instructions` once.
+	pub fn plain(mut instructions: Vec<Instruction>) -> FuncBody {
+		instructions.push(Instruction::End);
+		FuncBody::new(Vec::new(), Instructions::new(instructions))
+	}
+
+	/// Creates a body that calls the imported function `func` once for every set of `args`.
+	///
+	/// The arguments are pushed right before each call. The result of the function is
+	/// dropped if `drop_result` is set.
+	pub fn calls(
+		func: u32,
+		args: impl IntoIterator<Item = Vec<Instruction>>,
+		drop_result: bool,
+	) -> FuncBody {
+		let mut instructions = Vec::new();
+		for args in args {
+			instructions.extend(args);
+			instructions.push(Instruction::Call(func));
+			if drop_result {
+				instructions.push(Instruction::Drop);
+			}
+		}
+		plain(instructions)
+	}
+
+	/// Creates a body that executes `instructions` `repetitions` times in a row.
+	pub fn repeated(repetitions: u32, instructions: &[Instruction]) -> FuncBody {
+		repeated_with_locals(Vec::new(), repetitions, instructions)
+	}
+
+	/// Same as [`repeated`] but also declares the given `locals`.
+	///
+	/// Every `REPETITIONS_PER_LOOP` repetitions are wrapped into a loop that is executed
+	/// once. A loop is metered separately from the code around it, which keeps the cost
+	/// of every metered block within what the gas instrumentation can account for.
+	pub fn repeated_with_locals(
+		locals: Vec<parity_wasm::elements::Local>,
+		repetitions: u32,
+		instructions: &[Instruction],
+	) -> FuncBody {
+		let repetitions = usize::try_from(repetitions).unwrap_or(usize::max_value());
+		let mut body = Vec::with_capacity(instructions.len() * repetitions);
+		let mut done = 0;
+		while done < repetitions {
+			let count = (repetitions - done).min(REPETITIONS_PER_LOOP);
+			body.push(Instruction::Loop(BlockType::NoResult));
+			body.extend(instructions.iter().cycle().take(instructions.len() * count).cloned());
+			body.push(Instruction::End);
+			done += count;
+		}
+		body.push(Instruction::End);
+		FuncBody::new(locals, Instructions::new(body))
+	}
+}
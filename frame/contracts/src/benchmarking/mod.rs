@@ -0,0 +1,1177 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate. If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks for the contracts pallet.
+//!
+//! Apart from the dispatchables this measures every host function and every instruction class
+//! that a contract can use. The results are recorded in `weights.rs` from which the defaults of
+//! [`Schedule`] are derived.
+//!
+//! A host function benchmark calls the function `API_BENCHMARK_BATCH_SIZE` times per batch
+//! and an instruction benchmark executes its instructions `INSTR_BENCHMARK_BATCH_SIZE` times
+//! per batch. The cost of a single call or instruction is therefore the slope of the benchmark
+//! divided by the batch size. The instruction benchmarks need to push operands and drop
+//! results, so the costs of those helper instructions have to be subtracted from the result.
+//!
+//! The following host functions are not benchmarked:
+//!
+//! - `ext_gas` is the instrumentation itself and is accounted for by the instruction weights.
+//! - `ext_dispatch_call` and `ext_call_chain_extension` depend on the runtime they are used in.
+//!   Both read their input through the sandbox, which is charged per byte, and the work they
+//!   hand over to the runtime is charged separately: the weight of the dispatched call, or
+//!   whatever the chain extension charges with `Environment::charge_weight`. What remains is
+//!   entering the host function, which is charged like `ext_call` and `ext_caller`.
+//! - `ext_restore_to` requires a tombstone which can only be created by evicting a contract.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+mod code;
+
+use self::code::{body, DataSegment, ImportedFunction, ImportedMemory, ModuleDefinition, WasmModule};
+use super::*;
+use crate::account_db::StorageDelta;
+use crate::rent::subsistence_threshold;
+use crate::weights::{API_BENCHMARK_BATCH_SIZE, INSTR_BENCHMARK_BATCH_SIZE};
+use frame_benchmarking::{benchmarks, account};
+use frame_system::RawOrigin;
+use parity_wasm::elements::{BlockType, Instruction, ValueType};
+use sp_runtime::traits::Bounded;
+use hex_literal::hex;
+
+const SEED: u32 = 0;
+
+/// How many batches we do per host function benchmark.
+const API_BENCHMARK_BATCHES: u32 = 20;
+
+/// How many batches we do per instruction benchmark.
+const INSTR_BENCHMARK_BATCHES: u32 = 50;
+
+/// The largest code size in KiB that is benchmarked.
+const CODE_MAX_KB: u32 = 256;
+
+/// The largest amount of data in KiB that is passed to or processed by a benchmark.
+const DATA_MAX_KB: u32 = 64;
+
+/// The largest number of contracts or payers whose storage deposits are settled at once.
+const DEPOSIT_MAX: u32 = 100;
+
+/// The amount of balance that is given to every account that calls or instantiates contracts.
+fn caller_funding<T: Trait>() -> BalanceOf<T> {
+	BalanceOf::<T>::max_value() / 2u32.into()
+}
+
+/// Creates the account `name` and gives it `caller_funding`.
+fn funded_account<T: Trait>(name: &'static str) -> T::AccountId {
+	let caller: T::AccountId = account(name, 0, SEED);
+	T::Currency::make_free_balance_be(&caller, caller_funding::<T>());
+	caller
+}
+
+/// The call input of a host function that only takes `i32` arguments.
+fn i32_args(args: &[u32]) -> Vec<Instruction> {
+	args.iter().map(|arg| Instruction::I32Const(*arg as i32)).collect()
+}
+
+/// An imported host function that only takes `i32` arguments.
+fn import(name: &'static str, args: usize, has_result: bool) -> ImportedFunction {
+	ImportedFunction {
+		name,
+		params: vec![ValueType::I32; args],
+		return_type: if has_result { Some(ValueType::I32) } else { None },
+	}
+}
+
+/// A contract that was put on chain and instantiated for the benchmarks.
+struct Contract<T: Trait> {
+	caller: T::AccountId,
+	account_id: T::AccountId,
+	addr: <T::Lookup as StaticLookup>::Source,
+	endowment: BalanceOf<T>,
+}
+
+impl<T: Trait> Contract<T> {
+	/// Puts `module` on chain and instantiates it with `data` as input.
+	///
+	/// The account named `caller` is funded and used as instantiator.
+	fn new(caller: &'static str, module: WasmModule<T>, data: Vec<u8>)
+		-> Result<Contract<T>, &'static str>
+	{
+		let caller = funded_account::<T>(caller);
+		let endowment = caller_funding::<T>() / 3u32.into();
+		let account_id = T::DetermineContractAddress::contract_address_for(
+			&module.hash,
+			&data,
+			&caller,
+		);
+		let origin = RawOrigin::Signed(caller.clone());
+
+		Module::<T>::put_code(origin.clone().into(), module.code)?;
+		Module::<T>::instantiate(origin.into(), endowment, Weight::max_value(), module.hash, data)?;
+
+		Ok(Contract {
+			caller,
+			addr: T::Lookup::unlookup(account_id.clone()),
+			account_id,
+			endowment,
+		})
+	}
+
+	/// Instantiates a contract whose `call` function executes `body`.
+	///
+	/// The contract imports `imported_functions`, the maximum amount of memory and
+	/// places `data_segments` into it.
+	fn with_body(
+		imported_functions: Vec<ImportedFunction>,
+		data_segments: Vec<DataSegment>,
+		body: parity_wasm::elements::FuncBody,
+	) -> Result<Contract<T>, &'static str> {
+		let module = ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			data_segments,
+			imported_functions,
+			call_body: Some(body),
+			.. Default::default()
+		};
+		Self::new("caller", module.into(), vec![])
+	}
+
+	/// The origin to call this contract with.
+	fn origin(&self) -> RawOrigin<T::AccountId> {
+		RawOrigin::Signed(self.caller.clone())
+	}
+}
+
+/// Distinct storage keys which are laid out sequentially starting at offset 0.
+fn storage_keys(count: u32) -> (Vec<u8>, Vec<u32>) {
+	let keys = (0 .. count)
+		.flat_map(|n| blake2_256(&n.encode()).to_vec())
+		.collect::<Vec<_>>();
+	let key_ptrs = (0 .. count).map(|n| n * 32).collect();
+	(keys, key_ptrs)
+}
+
+benchmarks! {
+	_ { }
+
+	// The cost of replacing the stored schedule. The version `v` doesn't influence the cost,
+	// it's only there because every benchmark needs a component.
+	update_schedule {
+		let v in 1 .. 1_000;
+		let schedule = Schedule {
+			version: v,
+			.. Default::default()
+		};
+	}: _(RawOrigin::Root, schedule)
+	verify {
+		assert_eq!(Module::<T>::current_schedule().version, v);
+	}
+
+	// The cost of putting `n` KiB of code on chain. This includes the validation and
+	// instrumentation of the code.
+	put_code {
+		let n in 0 .. CODE_MAX_KB;
+		let caller = funded_account::<T>("caller");
+		let WasmModule { code, hash } = WasmModule::<T>::sized(n * 1024);
+		let origin = RawOrigin::Signed(caller);
+	}: _(origin, code)
+	verify {
+		assert!(<PristineCode<T>>::contains_key(&hash));
+	}
+
+	// The cost of instantiating a contract with `n` KiB of input data.
+	instantiate {
+		let n in 0 .. DATA_MAX_KB;
+		let data = vec![42u8; (n * 1024) as usize];
+		let endowment = caller_funding::<T>() / 3u32.into();
+		let caller = funded_account::<T>("caller");
+		let WasmModule { code, hash } = WasmModule::<T>::dummy();
+		let origin = RawOrigin::Signed(caller.clone());
+		let addr = T::DetermineContractAddress::contract_address_for(&hash, &data, &caller);
+		Module::<T>::put_code(origin.clone().into(), code)?;
+	}: _(origin, endowment, Weight::max_value(), hash, data)
+	verify {
+		assert_eq!(T::Currency::free_balance(&addr), endowment);
+		assert!(<ContractInfoOf<T>>::get(&addr).and_then(|info| info.get_alive()).is_some());
+	}
+
+	// The cost of calling a contract that does nothing with `n` KiB of input data.
+	call {
+		let n in 0 .. DATA_MAX_KB;
+		let data = vec![42u8; (n * 1024) as usize];
+		let instance = Contract::<T>::new("caller", WasmModule::dummy(), vec![])?;
+		let value = T::Currency::minimum_balance() * 100u32.into();
+		let origin = instance.origin();
+	}: _(origin, instance.addr, value, Weight::max_value(), data)
+	verify {
+		assert_eq!(T::Currency::free_balance(&instance.account_id), instance.endowment + value);
+	}
+
+	// The cost of a claim for a contract of `c` KiB of code which is due to pay rent but can
+	// afford it.
+	claim_surcharge {
+		let c in 0 .. CODE_MAX_KB;
+		let instance = Contract::<T>::new("caller", WasmModule::sized(c * 1024), vec![])?;
+		let origin = instance.origin();
+		frame_system::Module::<T>::set_block_number(
+			T::SignedClaimHandicap::get() + 1_000u32.into()
+		);
+	}: _(origin, instance.account_id.clone(), None)
+	verify {
+		let info = <ContractInfoOf<T>>::get(&instance.account_id).and_then(|info| info.get_alive());
+		assert!(info.is_some());
+	}
+
+	// The cost of settling the storage deposits of an execution that added storage to `c`
+	// contracts and freed storage of another contract for which `p` payers are refunded.
+	// This measures `storage_deposit_settle_cost` and `storage_deposit_refund_cost`.
+	settle {
+		let c in 0 .. DEPOSIT_MAX;
+		let p in 0 .. DEPOSIT_MAX;
+		let origin = funded_account::<T>("origin");
+		let charged = (0 .. c)
+			.map(|i| Contract::<T>::new("instantiator", WasmModule::dummy(), i.encode())
+				.map(|contract| contract.account_id))
+			.collect::<Result<Vec<_>, _>>()?;
+		let refunded = Contract::<T>::new("instantiator", WasmModule::dummy(), vec![])?;
+		let held = deposit::deposit_for::<T>(1024, 1);
+		for i in 0 .. p {
+			let payer: T::AccountId = account("payer", i, SEED);
+			T::DepositCurrency::make_free_balance_be(&payer, caller_funding::<T>());
+			T::DepositCurrency::reserve(&payer, held)?;
+			<StorageDepositOf<T>>::insert(&refunded.account_id, &payer, held);
+		}
+		let mut deltas = charged.into_iter()
+			.map(|contract| {
+				let delta = StorageDelta { bytes_added: 1024, items_added: 1, .. Default::default() };
+				(contract, delta)
+			})
+			.collect::<Vec<_>>();
+		deltas.push((
+			refunded.account_id.clone(),
+			StorageDelta { bytes_removed: 1024 * p, items_removed: p, .. Default::default() },
+		));
+		let cfg = Config::<T>::preload();
+		let mut gas_meter = GasMeter::<T>::new(Weight::max_value());
+		let mut deferred = Vec::new();
+	}: {
+		deposit::settle::<T>(&origin, Vec::new(), deltas, &cfg, &mut gas_meter, &mut deferred)?;
+	}
+	verify {
+		assert_eq!(<StorageDepositOf<T>>::iter_prefix(&refunded.account_id).count(), 0);
+	}
+
+	ext_caller {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller", WasmModule::getter("ext_caller", r * API_BENCHMARK_BATCH_SIZE), vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_address {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller", WasmModule::getter("ext_address", r * API_BENCHMARK_BATCH_SIZE), vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_gas_price {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller", WasmModule::getter("ext_gas_price", r * API_BENCHMARK_BATCH_SIZE), vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_gas_left {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller", WasmModule::getter("ext_gas_left", r * API_BENCHMARK_BATCH_SIZE), vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_balance {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller", WasmModule::getter("ext_balance", r * API_BENCHMARK_BATCH_SIZE), vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_value_transferred {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller",
+			WasmModule::getter("ext_value_transferred", r * API_BENCHMARK_BATCH_SIZE),
+			vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_minimum_balance {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller",
+			WasmModule::getter("ext_minimum_balance", r * API_BENCHMARK_BATCH_SIZE),
+			vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_tombstone_deposit {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller",
+			WasmModule::getter("ext_tombstone_deposit", r * API_BENCHMARK_BATCH_SIZE),
+			vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_rent_allowance {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller",
+			WasmModule::getter("ext_rent_allowance", r * API_BENCHMARK_BATCH_SIZE),
+			vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_block_number {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller", WasmModule::getter("ext_block_number", r * API_BENCHMARK_BATCH_SIZE), vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_now {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(
+			"caller", WasmModule::getter("ext_now", r * API_BENCHMARK_BATCH_SIZE), vec![],
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_random {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let subject_len = Module::<T>::current_schedule().max_subject_len;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_random", 2, false)],
+			vec![],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, subject_len])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call writes a value of the maximum size to a distinct key.
+	ext_set_storage {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let (keys, key_ptrs) = storage_keys(r * API_BENCHMARK_BATCH_SIZE);
+		let value_ptr = keys.len() as u32;
+		let value_len = T::MaxValueSize::get();
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_set_storage", 3, false)],
+			vec![DataSegment { offset: 0, value: keys }],
+			body::calls(0, key_ptrs.into_iter().map(|ptr| i32_args(&[ptr, value_ptr, value_len])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// The keys are written when the contract is instantiated so that every call clears a
+	// distinct existing key.
+	ext_clear_storage {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let (keys, key_ptrs) = storage_keys(r * API_BENCHMARK_BATCH_SIZE);
+		let module = ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			data_segments: vec![DataSegment { offset: 0, value: keys }],
+			imported_functions: vec![
+				import("ext_set_storage", 3, false),
+				import("ext_clear_storage", 1, false),
+			],
+			deploy_body: Some(body::calls(
+				0, key_ptrs.iter().map(|ptr| i32_args(&[*ptr, *ptr, 32])), false,
+			)),
+			call_body: Some(body::calls(1, key_ptrs.iter().map(|ptr| i32_args(&[*ptr])), false)),
+			.. Default::default()
+		};
+		let instance = Contract::<T>::new("caller", module.into(), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// The keys are written when the contract is instantiated so that every call reads a
+	// distinct existing key.
+	ext_get_storage {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let (keys, key_ptrs) = storage_keys(r * API_BENCHMARK_BATCH_SIZE);
+		let module = ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			data_segments: vec![DataSegment { offset: 0, value: keys }],
+			imported_functions: vec![
+				import("ext_set_storage", 3, false),
+				import("ext_get_storage", 1, true),
+			],
+			deploy_body: Some(body::calls(
+				0, key_ptrs.iter().map(|ptr| i32_args(&[*ptr, *ptr, 32])), false,
+			)),
+			call_body: Some(body::calls(1, key_ptrs.iter().map(|ptr| i32_args(&[*ptr])), true)),
+			.. Default::default()
+		};
+		let instance = Contract::<T>::new("caller", module.into(), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call creates a new account by transferring the minimum balance to it.
+	ext_transfer {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let value = T::Currency::minimum_balance().encode();
+		let receivers = (0 .. r * API_BENCHMARK_BATCH_SIZE)
+			.map(|i| account::<T::AccountId>("receiver", i, SEED))
+			.collect::<Vec<_>>();
+		let account_len = T::AccountId::default().encode().len() as u32;
+		let account_bytes = receivers.iter().flat_map(|x| x.encode()).collect::<Vec<_>>();
+		let value_len = value.len() as u32;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_transfer", 4, true)],
+			vec![
+				DataSegment { offset: 0, value },
+				DataSegment { offset: value_len, value: account_bytes },
+			],
+			body::calls(
+				0,
+				(0 .. r * API_BENCHMARK_BATCH_SIZE)
+					.map(|i| i32_args(&[value_len + i * account_len, account_len, 0, value_len])),
+				true,
+			),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	verify {
+		for receiver in &receivers {
+			assert_eq!(T::Currency::free_balance(receiver), T::Currency::minimum_balance());
+		}
+	}
+
+	// Every call is made to the same contract which does nothing.
+	ext_call {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let callee = Contract::<T>::new("callee", WasmModule::dummy(), vec![])?;
+		let callee_bytes = callee.account_id.encode();
+		let callee_len = callee_bytes.len() as u32;
+		let value = BalanceOf::<T>::zero().encode();
+		let value_len = value.len() as u32;
+		let mut call_args = i32_args(&[0, callee_len]);
+		// Devote all the remaining gas to the nested call.
+		call_args.push(Instruction::I64Const(0));
+		call_args.extend(i32_args(&[callee_len, value_len, 0, 0]));
+		let mut imported = import("ext_call", 6, true);
+		imported.params.insert(2, ValueType::I64);
+		let instance = Contract::<T>::with_body(
+			vec![imported],
+			vec![
+				DataSegment { offset: 0, value: callee_bytes },
+				DataSegment { offset: callee_len, value },
+			],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| call_args.clone()), true),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call executes the same code which does nothing.
+	ext_delegate_call {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let WasmModule { code, hash } = WasmModule::<T>::dummy();
+		Module::<T>::put_code(RawOrigin::Signed(funded_account::<T>("deployer")).into(), code)?;
+		let hash_bytes = hash.encode();
+		let hash_len = hash_bytes.len() as u32;
+		let mut call_args = i32_args(&[0, hash_len]);
+		// Devote all the remaining gas to the nested call.
+		call_args.push(Instruction::I64Const(0));
+		call_args.extend(i32_args(&[0, 0]));
+		let mut imported = import("ext_delegate_call", 4, true);
+		imported.params.insert(2, ValueType::I64);
+		let instance = Contract::<T>::with_body(
+			vec![imported],
+			vec![DataSegment { offset: 0, value: hash_bytes }],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| call_args.clone()), true),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call instantiates a new contract from the same code. The input data is distinct
+	// for every call so that each call creates a different contract.
+	ext_instantiate {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let WasmModule { code, hash } = WasmModule::<T>::dummy();
+		Module::<T>::put_code(RawOrigin::Signed(funded_account::<T>("deployer")).into(), code)?;
+		let hash_bytes = hash.encode();
+		let hash_len = hash_bytes.len() as u32;
+		let value = subsistence_threshold::<T>().encode();
+		let value_len = value.len() as u32;
+		let inputs = (0 .. r * API_BENCHMARK_BATCH_SIZE)
+			.flat_map(|i| i.encode())
+			.collect::<Vec<_>>();
+		let inputs_ptr = hash_len + value_len;
+		let mut imported = import("ext_instantiate", 6, true);
+		imported.params.insert(2, ValueType::I64);
+		let instance = Contract::<T>::with_body(
+			vec![imported],
+			vec![
+				DataSegment { offset: 0, value: hash_bytes },
+				DataSegment { offset: hash_len, value },
+				DataSegment { offset: inputs_ptr, value: inputs },
+			],
+			body::calls(
+				0,
+				(0 .. r * API_BENCHMARK_BATCH_SIZE).map(|i| {
+					let mut args = i32_args(&[0, hash_len]);
+					args.push(Instruction::I64Const(0));
+					args.extend(i32_args(&[hash_len, value_len, inputs_ptr + i * 4, 4]));
+					args
+				}),
+				true,
+			),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// A contract can only be terminated once.
+	ext_terminate {
+		let r in 0 .. 1;
+		let beneficiary = account::<T::AccountId>("beneficiary", 0, SEED);
+		let beneficiary_bytes = beneficiary.encode();
+		let beneficiary_len = beneficiary_bytes.len() as u32;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_terminate", 2, false)],
+			vec![DataSegment { offset: 0, value: beneficiary_bytes }],
+			body::calls(0, (0 .. r).map(|_| i32_args(&[0, beneficiary_len])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	verify {
+		if r > 0 {
+			assert!(<ContractInfoOf<T>>::get(&instance.account_id).is_none());
+		}
+	}
+
+	// A contract can only return once.
+	ext_return {
+		let r in 0 .. 1;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_return", 2, false)],
+			vec![],
+			body::calls(0, (0 .. r).map(|_| i32_args(&[0, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_scratch_size {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_scratch_size", 0, true)],
+			vec![],
+			body::repeated(r * API_BENCHMARK_BATCH_SIZE, &[Instruction::Call(0), Instruction::Drop]),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_scratch_read {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_scratch_read", 3, false)],
+			vec![],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 0, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call copies the `n` KiB of call input from the scratch buffer into the memory.
+	// This measures `sandbox_data_write_cost`.
+	ext_scratch_read_per_kb {
+		let n in 0 .. DATA_MAX_KB;
+		let len = n * 1024;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_scratch_read", 3, false)],
+			vec![],
+			body::calls(0, (0 .. API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 0, len])), false),
+		)?;
+		let origin = instance.origin();
+		let data = vec![42u8; len as usize];
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), data)
+
+	ext_scratch_write {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_scratch_write", 2, false)],
+			vec![],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call copies `n` KiB from the memory into the scratch buffer.
+	// This measures `sandbox_data_read_cost`.
+	ext_scratch_write_per_kb {
+		let n in 0 .. DATA_MAX_KB;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_scratch_write", 2, false)],
+			vec![],
+			body::calls(0, (0 .. API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, n * 1024])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_deposit_event {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_deposit_event", 4, false)],
+			vec![],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 0, 0, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call deposits an event with `t` topics and `n` KiB of data.
+	// This measures `event_per_topic_cost` and `event_data_per_byte_cost`.
+	ext_deposit_event_per_topic_and_kb {
+		let t in 0 .. Module::<T>::current_schedule().max_event_topics;
+		let n in 0 .. 16;
+		let topics = (0 .. t).map(|i| T::Hashing::hash_of(&i)).collect::<Vec<_>>().encode();
+		let topics_len = topics.len() as u32;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_deposit_event", 4, false)],
+			vec![DataSegment { offset: 0, value: topics }],
+			body::calls(
+				0,
+				(0 .. API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, topics_len, 0, n * 1024])),
+				false,
+			),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_set_rent_allowance {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let value = caller_funding::<T>().encode();
+		let value_len = value.len() as u32;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_set_rent_allowance", 2, false)],
+			vec![DataSegment { offset: 0, value }],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, value_len])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call sets the code hash to the same existing code.
+	ext_set_code_hash {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let WasmModule { code, hash } = WasmModule::<T>::dummy();
+		Module::<T>::put_code(RawOrigin::Signed(funded_account::<T>("deployer")).into(), code)?;
+		let hash_bytes = hash.encode();
+		let hash_len = hash_bytes.len() as u32;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_set_code_hash", 2, false)],
+			vec![DataSegment { offset: 0, value: hash_bytes }],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, hash_len])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call reads the same existing storage item of 1 KiB.
+	ext_get_runtime_storage {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let key = blake2_256(b"ext_get_runtime_storage").to_vec();
+		let key_len = key.len() as u32;
+		frame_support::storage::unhashed::put_raw(&key, &[42u8; 1024]);
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_get_runtime_storage", 2, true)],
+			vec![DataSegment { offset: 0, value: key }],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, key_len])), true),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// `ext_println` can only be imported with `Schedule::enable_println`. Every call prints the
+	// same short message.
+	ext_println {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		CurrentSchedule::put(Schedule {
+			enable_println: true,
+			.. Default::default()
+		});
+		let message = b"ext_println".to_vec();
+		let message_len = message.len() as u32;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_println", 2, false)],
+			vec![DataSegment { offset: 0, value: message }],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, message_len])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// The hash functions are called with an empty input which measures their base costs.
+	ext_hash_sha2_256 {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_hash_sha2_256", 3, false)],
+			vec![],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 0, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_hash_sha2_256_per_kb {
+		let n in 0 .. DATA_MAX_KB;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_hash_sha2_256", 3, false)],
+			vec![],
+			body::calls(0, (0 .. API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, n * 1024, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_hash_keccak_256 {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_hash_keccak_256", 3, false)],
+			vec![],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 0, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_hash_keccak_256_per_kb {
+		let n in 0 .. DATA_MAX_KB;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_hash_keccak_256", 3, false)],
+			vec![],
+			body::calls(0, (0 .. API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, n * 1024, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_hash_blake2_256 {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_hash_blake2_256", 3, false)],
+			vec![],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 0, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_hash_blake2_256_per_kb {
+		let n in 0 .. DATA_MAX_KB;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_hash_blake2_256", 3, false)],
+			vec![],
+			body::calls(0, (0 .. API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, n * 1024, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_hash_blake2_128 {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_hash_blake2_128", 3, false)],
+			vec![],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 0, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	ext_hash_blake2_128_per_kb {
+		let n in 0 .. DATA_MAX_KB;
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_hash_blake2_128", 3, false)],
+			vec![],
+			body::calls(0, (0 .. API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, n * 1024, 0])), false),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call recovers the public key of a valid signature.
+	ext_ecdsa_recover {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let signature = hex!("3dde91174bd9359027be59a428b8146513df80a2a3c7eda2194f64de04a69ab97b753169e94db6ffd50921a2668a48b94ca11e3d32c1ff19cfe88890aa7e8f3c00").to_vec();
+		let message_hash = blake2_256(b"").to_vec();
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_ecdsa_recover", 3, true)],
+			vec![
+				DataSegment { offset: 0, value: signature },
+				DataSegment { offset: 65, value: message_hash },
+			],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 65, 97])), true),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call verifies the signature of an empty message. The marker bit of the signature
+	// is set which makes the verification fail only after the full verification was carried out.
+	ext_sr25519_verify {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let mut signature = hex!("5a9755f069939f45d96aaf125cf5ce7ba1db998686f87f2fb3cbdea922078741a73891ba265f70c31436e18a9acd14d189d73c12317ab6c313285cd938453202").to_vec();
+		signature[63] |= 0x80;
+		let public_key = hex!("b4bfa1f7a5166695eb75299fd1c4c03ea212871c342f2c5dfea0902b2c246918").to_vec();
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_sr25519_verify", 4, true)],
+			vec![
+				DataSegment { offset: 0, value: signature },
+				DataSegment { offset: 64, value: public_key },
+			],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 64, 0, 0])), true),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call verifies the same signature against a message of `n` KiB. The whole message
+	// is hashed before the signature is checked.
+	ext_sr25519_verify_per_kb {
+		let n in 0 .. DATA_MAX_KB;
+		let mut signature = hex!("5a9755f069939f45d96aaf125cf5ce7ba1db998686f87f2fb3cbdea922078741a73891ba265f70c31436e18a9acd14d189d73c12317ab6c313285cd938453202").to_vec();
+		signature[63] |= 0x80;
+		let public_key = hex!("b4bfa1f7a5166695eb75299fd1c4c03ea212871c342f2c5dfea0902b2c246918").to_vec();
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_sr25519_verify", 4, true)],
+			vec![
+				DataSegment { offset: 0, value: signature },
+				DataSegment { offset: 64, value: public_key },
+			],
+			body::calls(
+				0,
+				(0 .. API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 64, 96, n * 1024])),
+				true,
+			),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call verifies a valid signature of an empty message.
+	ext_ed25519_verify {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let signature = hex!("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b").to_vec();
+		let public_key = hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").to_vec();
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_ed25519_verify", 4, true)],
+			vec![
+				DataSegment { offset: 0, value: signature },
+				DataSegment { offset: 64, value: public_key },
+			],
+			body::calls(0, (0 .. r * API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 64, 0, 0])), true),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Every call verifies the same signature against a message of `n` KiB. The signature
+	// doesn't match but the whole message is hashed before this is detected.
+	ext_ed25519_verify_per_kb {
+		let n in 0 .. DATA_MAX_KB;
+		let signature = hex!("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b").to_vec();
+		let public_key = hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").to_vec();
+		let instance = Contract::<T>::with_body(
+			vec![import("ext_ed25519_verify", 4, true)],
+			vec![
+				DataSegment { offset: 0, value: signature },
+				DataSegment { offset: 64, value: public_key },
+			],
+			body::calls(
+				0,
+				(0 .. API_BENCHMARK_BATCH_SIZE).map(|_| i32_args(&[0, 64, 96, n * 1024])),
+				true,
+			),
+		)?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_const = w - w_control_flow (drop)
+	instr_const {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[Instruction::I64Const(42), Instruction::Drop],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_local = w / 2
+	instr_local {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[Instruction::GetLocal(0), Instruction::SetLocal(0)],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Globals are metered like locals: w_local = max(w_local, w / 2)
+	instr_global {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[Instruction::GetGlobal(0), Instruction::SetGlobal(0)],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_load = w - w_const - w_control_flow (drop)
+	instr_load {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[Instruction::I32Const(0), Instruction::I64Load(3, 0), Instruction::Drop],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_store = w - 2 * w_const
+	instr_store {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[Instruction::I32Const(0), Instruction::I64Const(42), Instruction::I64Store(3, 0)],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_add = w - 2 * w_const - w_control_flow (drop)
+	instr_add {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[
+				Instruction::I64Const(42),
+				Instruction::I64Const(7),
+				Instruction::I64Add,
+				Instruction::Drop,
+			],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_mul = w - 2 * w_const - w_control_flow (drop)
+	instr_mul {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[
+				Instruction::I64Const(42),
+				Instruction::I64Const(7),
+				Instruction::I64Mul,
+				Instruction::Drop,
+			],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_div = w - 2 * w_const - w_control_flow (drop)
+	instr_div {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[
+				Instruction::I64Const(42),
+				Instruction::I64Const(7),
+				Instruction::I64DivS,
+				Instruction::Drop,
+			],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_bit = w - 2 * w_const - w_control_flow (drop)
+	instr_bit {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[
+				Instruction::I64Const(42),
+				Instruction::I64Const(7),
+				Instruction::I64Shl,
+				Instruction::Drop,
+			],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_integer_comparison = w - 2 * w_const - w_control_flow (drop)
+	instr_integer_comparison {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[
+				Instruction::I64Const(42),
+				Instruction::I64Const(7),
+				Instruction::I64LtS,
+				Instruction::Drop,
+			],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_conversion = w - w_const - w_control_flow (drop)
+	instr_conversion {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[Instruction::I64Const(42), Instruction::I32WrapI64, Instruction::Drop],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_control_flow = w / 2
+	instr_control_flow {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[Instruction::Block(BlockType::NoResult), Instruction::End],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// w_current_memory = w - w_control_flow (drop)
+	instr_current_memory {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[Instruction::CurrentMemory(0), Instruction::Drop],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	instr_nop {
+		let r in 0 .. INSTR_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new("caller", WasmModule::instructions(
+			&[Instruction::Nop],
+			r * INSTR_BENCHMARK_BATCH_SIZE,
+		), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// Grows the memory by one page `r` times. This measures `grow_mem_cost`.
+	// grow_mem_cost = w - w_const - w_control_flow (drop)
+	instr_grow_memory {
+		let r in 0 .. Module::<T>::current_schedule().max_memory_pages;
+		let module = ModuleDefinition {
+			memory: Some(ImportedMemory {
+				min_pages: 0,
+				max_pages: Module::<T>::current_schedule().max_memory_pages,
+			}),
+			call_body: Some(body::repeated(r, &[
+				Instruction::I32Const(1),
+				Instruction::GrowMemory(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		};
+		let instance = Contract::<T>::new("caller", module.into(), vec![])?;
+		let origin = instance.origin();
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn dispatchables() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_update_schedule::<Test>());
+			assert_ok!(test_benchmark_put_code::<Test>());
+			assert_ok!(test_benchmark_instantiate::<Test>());
+			assert_ok!(test_benchmark_call::<Test>());
+			assert_ok!(test_benchmark_claim_surcharge::<Test>());
+			assert_ok!(test_benchmark_settle::<Test>());
+		});
+	}
+
+	#[test]
+	fn host_functions() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_ext_caller::<Test>());
+			assert_ok!(test_benchmark_ext_address::<Test>());
+			assert_ok!(test_benchmark_ext_gas_price::<Test>());
+			assert_ok!(test_benchmark_ext_gas_left::<Test>());
+			assert_ok!(test_benchmark_ext_balance::<Test>());
+			assert_ok!(test_benchmark_ext_value_transferred::<Test>());
+			assert_ok!(test_benchmark_ext_minimum_balance::<Test>());
+			assert_ok!(test_benchmark_ext_tombstone_deposit::<Test>());
+			assert_ok!(test_benchmark_ext_rent_allowance::<Test>());
+			assert_ok!(test_benchmark_ext_block_number::<Test>());
+			assert_ok!(test_benchmark_ext_now::<Test>());
+			assert_ok!(test_benchmark_ext_random::<Test>());
+			assert_ok!(test_benchmark_ext_set_storage::<Test>());
+			assert_ok!(test_benchmark_ext_clear_storage::<Test>());
+			assert_ok!(test_benchmark_ext_get_storage::<Test>());
+			assert_ok!(test_benchmark_ext_transfer::<Test>());
+			assert_ok!(test_benchmark_ext_call::<Test>());
+			assert_ok!(test_benchmark_ext_delegate_call::<Test>());
+			assert_ok!(test_benchmark_ext_instantiate::<Test>());
+			assert_ok!(test_benchmark_ext_terminate::<Test>());
+			assert_ok!(test_benchmark_ext_return::<Test>());
+			assert_ok!(test_benchmark_ext_scratch_size::<Test>());
+			assert_ok!(test_benchmark_ext_scratch_read::<Test>());
+			assert_ok!(test_benchmark_ext_scratch_read_per_kb::<Test>());
+			assert_ok!(test_benchmark_ext_scratch_write::<Test>());
+			assert_ok!(test_benchmark_ext_scratch_write_per_kb::<Test>());
+			assert_ok!(test_benchmark_ext_deposit_event::<Test>());
+			assert_ok!(test_benchmark_ext_deposit_event_per_topic_and_kb::<Test>());
+			assert_ok!(test_benchmark_ext_set_rent_allowance::<Test>());
+			assert_ok!(test_benchmark_ext_set_code_hash::<Test>());
+			assert_ok!(test_benchmark_ext_get_runtime_storage::<Test>());
+			assert_ok!(test_benchmark_ext_println::<Test>());
+			assert_ok!(test_benchmark_ext_hash_sha2_256::<Test>());
+			assert_ok!(test_benchmark_ext_hash_sha2_256_per_kb::<Test>());
+			assert_ok!(test_benchmark_ext_hash_keccak_256::<Test>());
+			assert_ok!(test_benchmark_ext_hash_keccak_256_per_kb::<Test>());
+			assert_ok!(test_benchmark_ext_hash_blake2_256::<Test>());
+			assert_ok!(test_benchmark_ext_hash_blake2_256_per_kb::<Test>());
+			assert_ok!(test_benchmark_ext_hash_blake2_128::<Test>());
+			assert_ok!(test_benchmark_ext_hash_blake2_128_per_kb::<Test>());
+			assert_ok!(test_benchmark_ext_ecdsa_recover::<Test>());
+			assert_ok!(test_benchmark_ext_sr25519_verify::<Test>());
+			assert_ok!(test_benchmark_ext_sr25519_verify_per_kb::<Test>());
+			assert_ok!(test_benchmark_ext_ed25519_verify::<Test>());
+			assert_ok!(test_benchmark_ext_ed25519_verify_per_kb::<Test>());
+		});
+	}
+
+	#[test]
+	fn instructions() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_instr_const::<Test>());
+			assert_ok!(test_benchmark_instr_local::<Test>());
+			assert_ok!(test_benchmark_instr_global::<Test>());
+			assert_ok!(test_benchmark_instr_load::<Test>());
+			assert_ok!(test_benchmark_instr_store::<Test>());
+			assert_ok!(test_benchmark_instr_add::<Test>());
+			assert_ok!(test_benchmark_instr_mul::<Test>());
+			assert_ok!(test_benchmark_instr_div::<Test>());
+			assert_ok!(test_benchmark_instr_bit::<Test>());
+			assert_ok!(test_benchmark_instr_integer_comparison::<Test>());
+			assert_ok!(test_benchmark_instr_conversion::<Test>());
+			assert_ok!(test_benchmark_instr_control_flow::<Test>());
+			assert_ok!(test_benchmark_instr_current_memory::<Test>());
+			assert_ok!(test_benchmark_instr_nop::<Test>());
+			assert_ok!(test_benchmark_instr_grow_memory::<Test>());
+		});
+	}
+}
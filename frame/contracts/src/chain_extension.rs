@@ -0,0 +1,193 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate. If not, see <http://www.gnu.org/licenses/>.
+
+//! A mechanism for runtime authors to augment the functionality of contracts.
+//!
+//! The runtime is able to call into any contract and retrieve the result using
+//! [`bare_call`](crate::Module::bare_call). This already allows customization of runtime
+//! behaviour by user generated code (contracts). However, often it is more straightforward
+//! to allow the reverse behaviour: The contract calls into the runtime. We call the latter
+//! one a "chain extension" because it allows the chain to extend the set of functions that are
+//! callable by a contract.
+//!
+//! In order to create a chain extension the runtime author implements the [`ChainExtension`]
+//! trait and declares it in this pallet's [configuration Trait](crate::Trait). All types
+//! required for this endeavour are defined or re-exported in this module. There is an
+//! implementation on `()` which can be used to signal that no chain extension is available.
+//!
+//! # Usage
+//!
+//! A contract calls the extension through the `ext_call_chain_extension` host function,
+//! passing an implementation defined `func_id` and a pointer to an input buffer. The extension
+//! decides how to interpret the input, how much gas to charge and what to put into the scratch
+//! buffer as output. The `u32` returned by [`ChainExtension::call`] is handed back to the
+//! contract as-is.
+//!
+//! # Security
+//!
+//! The chain author alone is responsible for the security of the chain extension.
+//! This includes avoiding the exposure of exploitable functions and charging the
+//! appropriate amount of weight. In order to do so benchmarks must be written and the
+//! [`charge_weight`](Environment::charge_weight) function must be called **before**
+//! carrying out any action that causes the consumption of the chargeable weight.
+//! It cannot be overstated how delicate of a process the creation of a chain extension
+//! is. Check whether using [`bare_call`](crate::Module::bare_call) suffices for the
+//! use case at hand.
+
+use crate::{Trait, wasm::Runtime};
+use codec::{Decode, Encode};
+use sp_runtime::DispatchError;
+use sp_std::prelude::*;
+
+pub use crate::exec::Ext;
+pub use crate::gas::Gas;
+
+/// Result that returns a [`DispatchError`] on error.
+pub type Result<T> = sp_std::result::Result<T, DispatchError>;
+
+/// A trait used to extend the set of contract callable functions.
+///
+/// In order to create a custom chain extension this trait must be implemented and supplied
+/// to the pallet contracts configuration trait as the associated type of the same name.
+/// Consult the [module documentation](self) for a general explanation of chain extensions.
+pub trait ChainExtension<T: Trait> {
+	/// Call the chain extension logic.
+	///
+	/// This is the only function that needs to be implemented in order to write a
+	/// chain extensions. It is called whenever a contract calls the `ext_call_chain_extension`
+	/// imported wasm function.
+	///
+	/// # Parameters
+	/// - `func_id`: The first argument to `ext_call_chain_extension`. Usually used to
+	///   determine which function to realize.
+	/// - `env`: Access to the remaining arguments and the execution environment.
+	///
+	/// # Return
+	///
+	/// In case of `Err` the contract execution is immediately suspended and the passed error
+	/// is returned to the caller. Otherwise the value of [`RetVal`] determines the exit
+	/// behaviour.
+	fn call<E: Ext<T = T>>(func_id: u32, env: Environment<E>) -> Result<RetVal>;
+
+	/// Determines whether chain extensions are enabled for this chain.
+	///
+	/// The default implementation returns `true`. Therefore it is not necessary to overwrite
+	/// this function when implementing a chain extension. In case of `false` a contract
+	/// calling `ext_call_chain_extension` traps.
+	fn enabled() -> bool {
+		true
+	}
+}
+
+/// Implementation that indicates that no chain extension is available.
+impl<T: Trait> ChainExtension<T> for () {
+	fn call<E: Ext<T = T>>(_func_id: u32, _env: Environment<E>) -> Result<RetVal> {
+		// Never called since [`Self::enabled()`] is set to `false`. Because we want to
+		// avoid panics at all costs we supply a sensible error value here instead
+		// of an `unimplemented!`.
+		Err(DispatchError::Other("chain extension is not enabled"))
+	}
+
+	fn enabled() -> bool {
+		false
+	}
+}
+
+/// Determines the exit behaviour and return value of a chain extension.
+pub enum RetVal {
+	/// The chain extensions returns the supplied value to its calling contract.
+	Converging(u32),
+	/// The control does **not** return to the calling contract.
+	///
+	/// Use this to stop the execution of the contract when the chain extension returns.
+	/// The semantic is the same as for calling `ext_return`: The passed data is returned
+	/// to the caller of the contract as a successful result.
+	Diverging(Vec<u8>),
+}
+
+/// Grants the chain extension access to its parameters and execution environment.
+///
+/// It uses the scratch buffer for output in the same way as the other contract callable
+/// functions do. Reading the input from the contract memory charges the usual amount of gas
+/// for doing so.
+pub struct Environment<'a, 'b, E: Ext> {
+	runtime: &'a mut Runtime<'b, E>,
+	input_ptr: u32,
+	input_len: u32,
+}
+
+impl<'a, 'b, E: Ext> Environment<'a, 'b, E> {
+	/// Creates a new environment for consumption by a chain extension.
+	pub(crate) fn new(runtime: &'a mut Runtime<'b, E>, input_ptr: u32, input_len: u32) -> Self {
+		Environment {
+			runtime,
+			input_ptr,
+			input_len,
+		}
+	}
+
+	/// Charge the passed `amount` of weight from the overall gas limit of the contract.
+	///
+	/// Returns an error if the contract runs out of gas. The error should be propagated
+	/// by the chain extension as execution can't continue in that case.
+	pub fn charge_weight(&mut self, amount: Gas) -> Result<()> {
+		self.runtime.charge_chain_extension(amount)
+			.map_err(|_| DispatchError::Other("out of gas"))
+	}
+
+	/// Grants access to the execution environment of the current contract call.
+	///
+	/// Consult the functions on the returned type before re-implementing those functions.
+	pub fn ext(&mut self) -> &mut E {
+		self.runtime.ext()
+	}
+
+	/// The length of the input as passed in as `input_len`.
+	pub fn in_len(&self) -> u32 {
+		self.input_len
+	}
+
+	/// Reads the input passed by the contract.
+	///
+	/// Charges the gas for reading `input_len` bytes from the contract memory.
+	pub fn read(&mut self) -> Result<Vec<u8>> {
+		self.runtime.read_sandbox_memory(self.input_ptr, self.input_len)
+			.map_err(|_| DispatchError::Other("failed to read the chain extension input"))
+	}
+
+	/// Reads and decodes the input passed by the contract as a type `T`.
+	///
+	/// Charges the gas for reading `input_len` bytes from the contract memory. Returns an error
+	/// if the input can't be decoded as `T`.
+	pub fn read_as<T: Decode>(&mut self) -> Result<T> {
+		let input = self.read()?;
+		T::decode(&mut &input[..])
+			.map_err(|_| DispatchError::Other("failed to decode the chain extension input"))
+	}
+
+	/// Puts the given buffer into the scratch buffer, overwriting its current contents.
+	///
+	/// The contract retrieves the output with `ext_scratch_size` and `ext_scratch_read` after
+	/// the chain extension returned.
+	pub fn write(&mut self, data: Vec<u8>) {
+		self.runtime.set_scratch_buf(data);
+	}
+
+	/// Encodes the given value into the scratch buffer, overwriting its current contents.
+	pub fn write_as<T: Encode>(&mut self, value: &T) {
+		self.write(value.encode());
+	}
+}
@@ -0,0 +1,256 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate. If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage deposits as an alternative to the storage rent.
+//!
+//! When [`StorageMode::Deposit`] is configured, the origin of a call or instantiation pays a
+//! deposit for every byte and every key-value pair that the execution adds to the storage of a
+//! contract. The deposit is reserved on the payer through [`Trait::DepositCurrency`] and recorded
+//! both per payer in [`StorageDepositOf`] and in total in the
+//! [`storage_deposit`](crate::RawAliveContractInfo::storage_deposit) of the contract. Whenever an
+//! execution frees storage, the corresponding part of the deposit is unreserved: first the share
+//! of the origin of that execution, then the shares of the other payers. Removing a contract
+//! unreserves all deposits held for it. Contracts never pay rent and are never evicted in this
+//! mode. Computing and settling the deposits is charged to the gas meter of the execution, see
+//! [`DepositToken`].
+
+use crate::{
+	AliveContractInfo, BalanceOf, CodeHash, Config, ContractInfo, ContractInfoOf, RawEvent,
+	StorageDepositOf, TombstoneContractInfo, Trait, TrieId,
+	account_db::StorageDelta,
+	exec::DeferredAction,
+	gas::{Gas, GasMeter, Token},
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::DispatchError, IterableStorageDoubleMap, IterableStorageMap, StorageDoubleMap,
+	StorageMap,
+	traits::{Get, ReservableCurrency},
+};
+use sp_runtime::{RuntimeDebug, traits::{Saturating, Zero}};
+use sp_std::prelude::*;
+
+/// Determines how contracts pay for the storage they occupy.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum StorageMode {
+	/// Contracts pay rent for their storage from their own balance and are evicted to a
+	/// tombstone once they can't afford it anymore.
+	Rent,
+	/// Callers pay a deposit for the storage they add and get it refunded once the storage is
+	/// freed. Contracts are never evicted.
+	Deposit,
+}
+
+/// The work of computing and settling the storage deposits of an execution.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Copy, Clone)]
+pub enum DepositToken {
+	/// Read the previous values of the given number of changed storage keys.
+	ReadChangedKeys(u32),
+	/// Settled the deposits held for the given number of contracts.
+	Settle(u32),
+	/// Read and refunded the deposit a single payer holds for a contract.
+	RefundPayer,
+}
+
+impl<T: Trait> Token<T> for DepositToken {
+	type Metadata = Config<T>;
+
+	#[inline]
+	fn calculate_amount(&self, metadata: &Config<T>) -> Gas {
+		match *self {
+			DepositToken::ReadChangedKeys(keys) =>
+				metadata.schedule.storage_deposit_read_cost.saturating_mul(keys.into()),
+			DepositToken::Settle(contracts) =>
+				metadata.schedule.storage_deposit_settle_cost.saturating_mul(contracts.into()),
+			DepositToken::RefundPayer => metadata.schedule.storage_deposit_refund_cost,
+		}
+	}
+}
+
+/// The error returned when the gas runs out while computing or settling the deposits.
+pub const OUT_OF_GAS: &str = "not enough gas to settle the storage deposits";
+
+/// Returns the deposit required for `bytes` bytes stored in `items` key-value pairs.
+pub fn deposit_for<T: Trait>(bytes: u32, items: u32) -> BalanceOf<T> {
+	T::DepositPerByte::get()
+		.saturating_mul(bytes.into())
+		.saturating_add(T::DepositPerItem::get().saturating_mul(items.into()))
+}
+
+/// Charges `origin` for the storage added and refunds the payers for the storage freed by an
+/// execution whose changes were just committed.
+///
+/// `reset` are the contracts whose previous storage was wiped and `deltas` describe how the
+/// storage of every other touched contract changed. The events are pushed to `deferred`.
+/// The number of payers touched isn't known in advance, so each of them is charged to
+/// `gas_meter` once it is read. Returns an error if `origin` can't afford the deposit or the
+/// gas runs out, in which case the caller must roll back the storage transaction the changes
+/// were committed in.
+pub fn settle<T: Trait>(
+	origin: &T::AccountId,
+	reset: Vec<T::AccountId>,
+	deltas: Vec<(T::AccountId, StorageDelta)>,
+	cfg: &Config<T>,
+	gas_meter: &mut GasMeter<T>,
+	deferred: &mut Vec<DeferredAction<T>>,
+) -> Result<(), DispatchError> {
+	for contract in reset {
+		for (payer, amount) in <StorageDepositOf<T>>::drain_prefix(&contract) {
+			charge_refund(cfg, gas_meter)?;
+			T::DepositCurrency::unreserve(&payer, amount);
+			deferred.push(refunded_event(payer, contract.clone(), amount));
+		}
+	}
+
+	for (contract, delta) in deltas {
+		let StorageDelta { bytes_added, bytes_removed, items_added, items_removed } = delta;
+		let charge = deposit_for::<T>(bytes_added, items_added);
+		let refund = deposit_for::<T>(bytes_removed, items_removed);
+
+		if charge > refund {
+			let amount = charge - refund;
+			T::DepositCurrency::reserve(origin, amount)
+				.map_err(|_| "not enough balance to pay the storage deposit")?;
+			<StorageDepositOf<T>>::mutate(&contract, origin, |held| {
+				*held = held.saturating_add(amount)
+			});
+			adjust_total::<T>(&contract, |total| total.saturating_add(amount));
+			deferred.push(DeferredAction::DepositEvent {
+				event: RawEvent::StorageDepositCharged(origin.clone(), contract, amount),
+				topics: Vec::new(),
+			});
+		} else {
+			// Storage that was added before the deposit was introduced isn't covered by a
+			// deposit. Never refund more than the payers deposited. Only as many payers are
+			// read as are needed to cover the refund.
+			let mut remaining = refund - charge;
+			let mut refunds = Vec::new();
+			let payers = sp_std::iter::once(origin.clone())
+				.map(|payer| {
+					let held = <StorageDepositOf<T>>::get(&contract, &payer);
+					(payer, held)
+				})
+				.chain(
+					<StorageDepositOf<T>>::iter_prefix(&contract)
+						.filter(|(payer, _)| payer != origin)
+				);
+			for (payer, held) in payers {
+				if remaining.is_zero() {
+					break;
+				}
+				charge_refund(cfg, gas_meter)?;
+				let amount = remaining.min(held);
+				if !amount.is_zero() {
+					refunds.push((payer, held, amount));
+					remaining -= amount;
+				}
+			}
+
+			for (payer, held, amount) in refunds {
+				if amount == held {
+					<StorageDepositOf<T>>::remove(&contract, &payer);
+				} else {
+					<StorageDepositOf<T>>::insert(&contract, &payer, held - amount);
+				}
+				adjust_total::<T>(&contract, |total| total.saturating_sub(amount));
+				T::DepositCurrency::unreserve(&payer, amount);
+				deferred.push(refunded_event(payer, contract.clone(), amount));
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn charge_refund<T: Trait>(cfg: &Config<T>, gas_meter: &mut GasMeter<T>) -> Result<(), DispatchError> {
+	if gas_meter.charge(cfg, DepositToken::RefundPayer).is_out_of_gas() {
+		Err(OUT_OF_GAS.into())
+	} else {
+		Ok(())
+	}
+}
+
+fn adjust_total<T: Trait>(
+	contract: &T::AccountId,
+	f: impl FnOnce(BalanceOf<T>) -> BalanceOf<T>,
+) {
+	<ContractInfoOf<T>>::mutate(contract, |info| {
+		if let Some(ContractInfo::Alive(info)) = info {
+			info.storage_deposit = f(info.storage_deposit);
+		}
+	});
+}
+
+fn refunded_event<T: Trait>(
+	payer: T::AccountId,
+	contract: T::AccountId,
+	amount: BalanceOf<T>,
+) -> DeferredAction<T> {
+	DeferredAction::DepositEvent {
+		event: RawEvent::StorageDepositRefunded(payer, contract, amount),
+		topics: Vec::new(),
+	}
+}
+
+/// The layout of an alive contract before the storage deposit was introduced.
+#[derive(Encode, Decode)]
+pub(crate) struct OldRawAliveContractInfo<CodeHash, Balance, BlockNumber> {
+	pub trie_id: TrieId,
+	pub storage_size: u32,
+	pub empty_pair_count: u32,
+	pub total_pair_count: u32,
+	pub code_hash: CodeHash,
+	pub rent_allowance: Balance,
+	pub deduct_block: BlockNumber,
+	pub last_write: Option<BlockNumber>,
+}
+
+pub(crate) type OldAliveContractInfo<T> =
+	OldRawAliveContractInfo<CodeHash<T>, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+
+/// The layout of a contract before the storage deposit was introduced.
+#[derive(Encode, Decode)]
+pub(crate) enum OldContractInfo<T: Trait> {
+	Alive(OldAliveContractInfo<T>),
+	Tombstone(TombstoneContractInfo<T>),
+}
+
+/// Adds the storage deposit to all existing contracts.
+///
+/// Every contract starts out without a deposit, regardless of the [`StorageMode`]. Nobody paid
+/// for the storage that already exists, so it is not refunded when it is freed later on.
+pub(crate) fn migrate<T: Trait>() {
+	<ContractInfoOf<T>>::translate::<OldContractInfo<T>, _>(|_, old| {
+		let alive = match old {
+			OldContractInfo::Alive(alive) => alive,
+			OldContractInfo::Tombstone(tombstone) =>
+				return Some(ContractInfo::Tombstone(tombstone)),
+		};
+
+		Some(ContractInfo::Alive(AliveContractInfo::<T> {
+			trie_id: alive.trie_id,
+			storage_size: alive.storage_size,
+			empty_pair_count: alive.empty_pair_count,
+			total_pair_count: alive.total_pair_count,
+			code_hash: alive.code_hash,
+			rent_allowance: alive.rent_allowance,
+			storage_deposit: Zero::zero(),
+			deduct_block: alive.deduct_block,
+			last_write: alive.last_write,
+		}))
+	});
+}
@@ -20,6 +20,7 @@ use crate::account_db::{AccountDb, DirectAccountDb, OverlayAccountDb};
 use crate::gas::{Gas, GasMeter, Token};
 use crate::rent;
 
+use pallet_contracts_primitives::{CallKind, CallTrace, ContractEvent};
 use sp_std::prelude::*;
 use sp_runtime::traits::{Bounded, CheckedAdd, CheckedSub, Zero};
 use frame_support::{
@@ -102,7 +103,7 @@ pub trait Ext {
 	///
 	/// Returns `None` if the `key` wasn't previously set by `set_storage` or
 	/// was deleted.
-	fn get_storage(&self, key: &StorageKey) -> Option<Vec<u8>>;
+	fn get_storage(&mut self, key: &StorageKey) -> Option<Vec<u8>>;
 
 	/// Sets the storage entry by the given key to the specified value. If `value` is `None` then
 	/// the storage entry is deleted. Returns an Err if the value size is too large.
@@ -144,6 +145,23 @@ pub trait Ext {
 		input_data: Vec<u8>,
 	) -> ExecResult;
 
+	/// Execute the code specified by `code_hash` in the context of the current contract.
+	///
+	/// The code operates on the storage and balance of the current contract. `caller` and
+	/// `value_transferred` are the same as in the current call.
+	fn delegate_call(
+		&mut self,
+		code_hash: &CodeHash<Self::T>,
+		gas_meter: &mut GasMeter<Self::T>,
+		input_data: Vec<u8>,
+	) -> ExecResult;
+
+	/// Replace the code of the current contract with the code specified by `code_hash`.
+	///
+	/// The new code is used starting with the next call to the contract. Returns an error if
+	/// there is no code stored under `code_hash`.
+	fn set_code_hash(&mut self, code_hash: CodeHash<Self::T>) -> Result<(), DispatchError>;
+
 	/// Notes a call dispatch.
 	fn note_dispatch_call(&mut self, call: CallOf<Self::T>);
 
@@ -219,6 +237,8 @@ pub trait Loader<T: Trait> {
 	/// Load the main portion of the code specified by the `code_hash`. This executable
 	/// is called for each call to a contract.
 	fn load_main(&self, code_hash: &CodeHash<T>) -> Result<Self::Executable, &'static str>;
+	/// Check whether code with the given `code_hash` exists without loading it.
+	fn code_exists(&self, code_hash: &CodeHash<T>) -> bool;
 }
 
 /// A trait that represent a virtual machine.
@@ -291,6 +311,36 @@ pub enum DeferredAction<T: Trait> {
 	},
 }
 
+/// The trace of a contract execution as returned by [`crate::Module::bare_call_traced`].
+pub type CallTraceOf<T> = CallTrace<AccountIdOf<T>, BalanceOf<T>, TopicOf<T>>;
+
+/// Records what a single frame does while tracing is enabled.
+pub struct FrameRecorder<T: Trait> {
+	storage_reads: Vec<StorageKey>,
+	storage_writes: Vec<StorageKey>,
+	events: Vec<ContractEvent<TopicOf<T>>>,
+	/// The traces of all frames nested into this one in the order they were entered.
+	calls: Vec<CallTraceOf<T>>,
+}
+
+impl<T: Trait> Default for FrameRecorder<T> {
+	fn default() -> Self {
+		FrameRecorder {
+			storage_reads: Vec::new(),
+			storage_writes: Vec::new(),
+			events: Vec::new(),
+			calls: Vec::new(),
+		}
+	}
+}
+
+impl<T: Trait> FrameRecorder<T> {
+	/// Consumes the recorder of the top level context and returns the traces of all frames.
+	pub fn into_calls(self) -> Vec<CallTraceOf<T>> {
+		self.calls
+	}
+}
+
 pub struct ExecutionContext<'a, T: Trait + 'a, V, L> {
 	pub caller: Option<&'a ExecutionContext<'a, T, V, L>>,
 	pub self_account: T::AccountId,
@@ -303,6 +353,8 @@ pub struct ExecutionContext<'a, T: Trait + 'a, V, L> {
 	pub loader: &'a L,
 	pub timestamp: MomentOf<T>,
 	pub block_number: T::BlockNumber,
+	/// Records the execution of this frame if tracing is enabled.
+	pub recorder: Option<FrameRecorder<T>>,
 }
 
 impl<'a, T, E, V, L> ExecutionContext<'a, T, V, L>
@@ -328,6 +380,19 @@ where
 			loader: &loader,
 			timestamp: T::Time::now(),
 			block_number: <frame_system::Module<T>>::block_number(),
+			recorder: None,
+		}
+	}
+
+	/// Create the top level execution context which records a trace of all frames.
+	///
+	/// The trace can be retrieved from the `recorder` once the execution has finished.
+	pub fn top_level_traced(origin: T::AccountId, cfg: &'a Config<T>, vm: &'a V, loader: &'a L)
+		-> Self
+	{
+		ExecutionContext {
+			recorder: Some(Default::default()),
+			.. Self::top_level(origin, cfg, vm, loader)
 		}
 	}
 
@@ -346,6 +411,7 @@ where
 			loader: self.loader,
 			timestamp: self.timestamp.clone(),
 			block_number: self.block_number.clone(),
+			recorder: self.recorder.as_ref().map(|_| Default::default()),
 		}
 	}
 
@@ -406,8 +472,9 @@ where
 
 		let caller = self.self_account.clone();
 		let dest_trie_id = contract_info.and_then(|i| i.as_alive().map(|i| i.trie_id.clone()));
+		let frame = Frame { kind: CallKind::Call, dest: dest.clone(), trie_id: dest_trie_id, value };
 
-		self.with_nested_context(dest.clone(), dest_trie_id, |nested| {
+		self.with_nested_context(frame, gas_meter, |nested, gas_meter| {
 			if value > BalanceOf::<T>::zero() {
 				try_or_exec_error!(
 					transfer(
@@ -445,6 +512,56 @@ where
 		})
 	}
 
+	/// Execute the code specified by `code_hash` in the context of the current contract.
+	///
+	/// The code operates on the storage and balance of the current contract and sees the same
+	/// `caller` and `value` as the current frame. No funds are transferred.
+	pub fn delegate_call(
+		&mut self,
+		caller: T::AccountId,
+		value: BalanceOf<T>,
+		code_hash: &CodeHash<T>,
+		gas_meter: &mut GasMeter<T>,
+		input_data: Vec<u8>,
+	) -> ExecResult {
+		if self.depth == self.config.max_depth as usize {
+			return Err(ExecError {
+				reason: "reached maximum depth, cannot make a call".into(),
+				buffer: input_data,
+			});
+		}
+
+		if gas_meter
+			.charge(self.config, ExecFeeToken::Call)
+			.is_out_of_gas()
+		{
+			return Err(ExecError {
+				reason: "not enough gas to pay base call fee".into(),
+				buffer: input_data,
+			});
+		}
+
+		let frame = Frame {
+			kind: CallKind::DelegateCall,
+			dest: self.self_account.clone(),
+			trie_id: self.self_trie_id.clone(),
+			value: Zero::zero(),
+		};
+
+		self.with_nested_context(frame, gas_meter, |nested, gas_meter| {
+			let executable = try_or_exec_error!(
+				nested.loader.load_main(code_hash),
+				input_data
+			);
+			nested.vm.execute(
+				&executable,
+				nested.new_call_context(caller, value),
+				input_data,
+				gas_meter,
+			)
+		})
+	}
+
 	pub fn instantiate(
 		&mut self,
 		endowment: BalanceOf<T>,
@@ -477,9 +594,14 @@ where
 		);
 
 		// TrieId has not been generated yet and storage is empty since contract is new.
-		let dest_trie_id = None;
+		let frame = Frame {
+			kind: CallKind::Instantiate,
+			dest: dest.clone(),
+			trie_id: None,
+			value: endowment,
+		};
 
-		let output = self.with_nested_context(dest.clone(), dest_trie_id, |nested| {
+		let output = self.with_nested_context(frame, gas_meter, |nested, gas_meter| {
 			try_or_exec_error!(
 				nested.overlay.instantiate_contract(&dest, code_hash.clone()),
 				input_data
@@ -573,22 +695,40 @@ where
 		}
 	}
 
-	fn with_nested_context<F>(&mut self, dest: T::AccountId, trie_id: Option<TrieId>, func: F)
+	fn with_nested_context<F>(&mut self, frame: Frame<T>, gas_meter: &mut GasMeter<T>, func: F)
 		-> ExecResult
-		where F: FnOnce(&mut ExecutionContext<T, V, L>) -> ExecResult
+		where F: FnOnce(&mut ExecutionContext<T, V, L>, &mut GasMeter<T>) -> ExecResult
 	{
-		let (output, change_set, deferred) = {
-			let mut nested = self.nested(dest, trie_id);
-			let output = func(&mut nested)?;
-			(output, nested.overlay.into_change_set(), nested.deferred)
+		let gas_before = gas_meter.gas_left();
+		let Frame { kind, dest, trie_id, value } = frame;
+		let (output, change_set, deferred, recorder) = {
+			let mut nested = self.nested(dest.clone(), trie_id);
+			let output = func(&mut nested, gas_meter);
+			(output, nested.overlay.into_change_set(), nested.deferred, nested.recorder)
 		};
+		let success = output.as_ref().map(|output| output.is_success()).unwrap_or(false);
+
+		if let (Some(parent), Some(recorder)) = (self.recorder.as_mut(), recorder) {
+			parent.calls.push(CallTrace {
+				kind,
+				depth: self.depth as u32 + 1,
+				dest,
+				value,
+				gas_used: gas_before.saturating_sub(gas_meter.gas_left()),
+				success,
+				storage_reads: recorder.storage_reads,
+				storage_writes: recorder.storage_writes,
+				events: recorder.events,
+			});
+			parent.calls.extend(recorder.calls);
+		}
 
-		if output.is_success() {
+		if success {
 			self.overlay.commit(change_set);
 			self.deferred.extend(deferred);
 		}
 
-		Ok(output)
+		output
 	}
 
 	/// Returns whether a contract, identified by address, is currently live in the execution
@@ -599,6 +739,21 @@ where
 	}
 }
 
+/// Describes the frame a nested execution context is created for.
+struct Frame<T: Trait> {
+	kind: CallKind,
+	dest: T::AccountId,
+	trie_id: Option<TrieId>,
+	value: BalanceOf<T>,
+}
+
+/// Appends `key` to `keys` unless it was already recorded.
+fn record_key(keys: &mut Vec<StorageKey>, key: &StorageKey) {
+	if !keys.contains(key) {
+		keys.push(*key);
+	}
+}
+
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 #[derive(Copy, Clone)]
 pub enum TransferFeeKind {
@@ -734,7 +889,10 @@ where
 {
 	type T = T;
 
-	fn get_storage(&self, key: &StorageKey) -> Option<Vec<u8>> {
+	fn get_storage(&mut self, key: &StorageKey) -> Option<Vec<u8>> {
+		if let Some(recorder) = self.ctx.recorder.as_mut() {
+			record_key(&mut recorder.storage_reads, key);
+		}
 		self.ctx.overlay.get_storage(&self.ctx.self_account, self.ctx.self_trie_id.as_ref(), key)
 	}
 
@@ -745,6 +903,9 @@ where
 			}
 		}
 
+		if let Some(recorder) = self.ctx.recorder.as_mut() {
+			record_key(&mut recorder.storage_writes, &key);
+		}
 		self.ctx
 			.overlay
 			.set_storage(&self.ctx.self_account, key, value);
@@ -788,6 +949,36 @@ where
 		self.ctx.call(to.clone(), value, gas_meter, input_data)
 	}
 
+	fn delegate_call(
+		&mut self,
+		code_hash: &CodeHash<T>,
+		gas_meter: &mut GasMeter<T>,
+		input_data: Vec<u8>,
+	) -> ExecResult {
+		self.ctx.delegate_call(
+			self.caller.clone(),
+			self.value_transferred,
+			code_hash,
+			gas_meter,
+			input_data,
+		)
+	}
+
+	fn set_code_hash(&mut self, code_hash: CodeHash<T>) -> Result<(), DispatchError> {
+		if !self.ctx.loader.code_exists(&code_hash) {
+			return Err("code is not found".into());
+		}
+		let address = self.ctx.self_account.clone();
+		let old_code_hash = self.ctx.overlay.get_code_hash(&address)
+			.ok_or("the current contract has no code")?;
+		self.ctx.overlay.set_code_hash(&address, code_hash);
+		self.ctx.deferred.push(DeferredAction::DepositEvent {
+			event: RawEvent::ContractCodeUpdated(address, code_hash, old_code_hash),
+			topics: Vec::new(),
+		});
+		Ok(())
+	}
+
 	fn note_dispatch_call(&mut self, call: CallOf<Self::T>) {
 		self.ctx.deferred.push(DeferredAction::DispatchRuntimeCall {
 			origin: self.ctx.self_account.clone(),
@@ -844,6 +1035,9 @@ where
 	}
 
 	fn deposit_event(&mut self, topics: Vec<T::Hash>, data: Vec<u8>) {
+		if let Some(recorder) = self.ctx.recorder.as_mut() {
+			recorder.events.push(ContractEvent { topics: topics.clone(), data: data.clone() });
+		}
 		self.ctx.deferred.push(DeferredAction::DepositEvent {
 			topics,
 			event: RawEvent::ContractExecution(self.ctx.self_account.clone(), data),
@@ -898,6 +1092,7 @@ mod tests {
 		exec::{ExecReturnValue, ExecError, STATUS_SUCCESS}, CodeHash, Config,
 		gas::Gas,
 	};
+	use pallet_contracts_primitives::{CallKind, ContractEvent};
 	use std::{cell::RefCell, rc::Rc, collections::HashMap, marker::PhantomData};
 	use assert_matches::assert_matches;
 	use sp_runtime::DispatchError;
@@ -986,6 +1181,9 @@ mod tests {
 				.cloned()
 				.ok_or_else(|| "code not found")
 		}
+		fn code_exists(&self, code_hash: &CodeHash<Test>) -> bool {
+			self.map.contains_key(code_hash)
+		}
 	}
 
 	impl<'a> Vm<Test> for MockVm<'a> {
@@ -1780,4 +1978,122 @@ mod tests {
 			assert_matches!(result, Ok(_));
 		});
 	}
+
+	#[test]
+	fn delegate_call_runs_in_context_of_caller() {
+		let vm = MockVm::new();
+		let mut loader = MockLoader::empty();
+		let library_ch = loader.insert(|ctx| {
+			assert_eq!(*ctx.ext.address(), BOB);
+			assert_eq!(*ctx.ext.caller(), ALICE);
+			assert_eq!(ctx.ext.value_transferred(), 10);
+			assert_eq!(ctx.ext.balance(), 110);
+			ctx.ext.set_storage([1; 32], Some(vec![42])).unwrap();
+			exec_success()
+		});
+		let proxy_ch = loader.insert(move |ctx| {
+			assert_matches!(
+				ctx.ext.delegate_call(&library_ch, ctx.gas_meter, vec![]),
+				Ok(ExecReturnValue { status: STATUS_SUCCESS, .. })
+			);
+			assert_eq!(ctx.ext.get_storage(&[1; 32]), Some(vec![42]));
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let cfg = Config::preload();
+			let mut ctx = ExecutionContext::top_level(ALICE, &cfg, &vm, &loader);
+			ctx.overlay.set_balance(&ALICE, 100);
+			ctx.overlay.set_balance(&BOB, 100);
+			ctx.overlay.instantiate_contract(&BOB, proxy_ch).unwrap();
+
+			assert_matches!(
+				ctx.call(BOB, 10, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+			assert_eq!(ctx.overlay.get_storage(&BOB, None, &[1; 32]), Some(vec![42]));
+			assert_eq!(ctx.overlay.get_balance(&BOB), 110);
+			assert_eq!(ctx.overlay.get_code_hash(&BOB), Some(proxy_ch));
+		});
+	}
+
+	#[test]
+	fn set_code_hash_replaces_code() {
+		let vm = MockVm::new();
+		let mut loader = MockLoader::empty();
+		let new_ch = loader.insert(|_| {
+			Ok(ExecReturnValue { status: STATUS_SUCCESS, data: vec![2] })
+		});
+		let old_ch = loader.insert(move |ctx| {
+			let missing_ch = <Test as frame_system::Trait>::Hash::from_low_u64_be(1000);
+			assert!(ctx.ext.set_code_hash(missing_ch).is_err());
+			assert_matches!(ctx.ext.set_code_hash(new_ch), Ok(()));
+			Ok(ExecReturnValue { status: STATUS_SUCCESS, data: vec![1] })
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let cfg = Config::preload();
+			let mut ctx = ExecutionContext::top_level(ALICE, &cfg, &vm, &loader);
+			ctx.overlay.instantiate_contract(&BOB, old_ch).unwrap();
+
+			// The current execution finishes with the old code.
+			let output = ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]).unwrap();
+			assert_eq!(output.data, vec![1]);
+			assert_eq!(ctx.overlay.get_code_hash(&BOB), Some(new_ch));
+			assert_eq!(&ctx.events(), &[
+				DeferredAction::DepositEvent {
+					event: RawEvent::ContractCodeUpdated(BOB, new_ch, old_ch),
+					topics: Vec::new(),
+				},
+			]);
+
+			let output = ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]).unwrap();
+			assert_eq!(output.data, vec![2]);
+		});
+	}
+
+	#[test]
+	fn traced_call_records_frames() {
+		let vm = MockVm::new();
+		let mut loader = MockLoader::empty();
+		let failing_ch = loader.insert(|_| Ok(ExecReturnValue { status: 1, data: Vec::new() }));
+		let caller_ch = loader.insert(|ctx| {
+			assert_eq!(ctx.ext.get_storage(&[1; 32]), None);
+			ctx.ext.set_storage([2; 32], Some(vec![42])).unwrap();
+			ctx.ext.deposit_event(Vec::new(), vec![7]);
+			let output = ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![]).unwrap();
+			assert!(!output.is_success());
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let cfg = Config::preload();
+			let mut ctx = ExecutionContext::top_level_traced(ALICE, &cfg, &vm, &loader);
+			ctx.overlay.set_balance(&ALICE, 1000);
+			ctx.overlay.instantiate_contract(&BOB, caller_ch).unwrap();
+			ctx.overlay.instantiate_contract(&CHARLIE, failing_ch).unwrap();
+
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+
+			let trace = ctx.recorder.take().unwrap().into_calls();
+			assert_eq!(trace.len(), 2);
+
+			assert_eq!(trace[0].kind, CallKind::Call);
+			assert_eq!(trace[0].depth, 1);
+			assert_eq!(trace[0].dest, BOB);
+			assert!(trace[0].success);
+			assert_eq!(trace[0].storage_reads, vec![[1; 32]]);
+			assert_eq!(trace[0].storage_writes, vec![[2; 32]]);
+			assert_eq!(trace[0].events, vec![ContractEvent { topics: Vec::new(), data: vec![7] }]);
+
+			assert_eq!(trace[1].depth, 2);
+			assert_eq!(trace[1].dest, CHARLIE);
+			assert!(!trace[1].success);
+			assert!(trace[1].storage_writes.is_empty());
+			assert!(trace[0].gas_used > trace[1].gas_used);
+		});
+	}
 }
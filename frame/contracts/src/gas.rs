@@ -188,12 +188,17 @@ impl<T: Trait> GasMeter<T> {
 		self.gas_left
 	}
 
-	/// Turn this GasMeter into a DispatchResult that contains the actually used gas.
-	pub fn into_dispatch_result<R, E>(self, result: Result<R, E>) -> DispatchResultWithPostInfo where
+	/// Turn this GasMeter into a DispatchResult that contains the actually used gas on top of
+	/// the `base_weight` of the dispatchable.
+	pub fn into_dispatch_result<R, E>(
+		self,
+		result: Result<R, E>,
+		base_weight: Gas,
+	) -> DispatchResultWithPostInfo where
 		E: Into<DispatchError>,
 	{
 		let post_info = PostDispatchInfo {
-			actual_weight: Some(self.gas_spent()),
+			actual_weight: Some(base_weight.saturating_add(self.gas_spent())),
 		};
 
 		result
@@ -77,6 +77,7 @@
 //!
 //! * [Balances](../pallet_balances/index.html)
 
+#![recursion_limit="256"]
 #![cfg_attr(not(feature = "std"), no_std)]
 
 #[macro_use]
@@ -86,16 +87,23 @@ mod account_db;
 mod exec;
 mod wasm;
 mod rent;
+mod deposit;
+mod benchmarking;
+mod weights;
+
+pub mod chain_extension;
 
 #[cfg(test)]
 mod tests;
 
 use crate::exec::ExecutionContext;
 use crate::account_db::{AccountDb, DirectAccountDb};
+use crate::deposit::DepositToken;
 use crate::wasm::{WasmLoader, WasmVm};
 
 pub use crate::gas::{Gas, GasMeter};
-pub use crate::exec::{ExecResult, ExecReturnValue, ExecError, StatusCode};
+pub use crate::exec::{ExecResult, ExecReturnValue, ExecError, StatusCode, CallTraceOf};
+pub use crate::deposit::StorageMode;
 
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
@@ -114,9 +122,10 @@ use frame_support::dispatch::{
 };
 use frame_support::{
 	Parameter, decl_module, decl_event, decl_storage, decl_error,
-	parameter_types, IsSubType, storage::child::{self, ChildInfo},
+	parameter_types, IsSubType,
+	storage::{child::{self, ChildInfo}, with_transaction, TransactionOutcome},
 };
-use frame_support::traits::{OnUnbalanced, Currency, Get, Time, Randomness};
+use frame_support::traits::{OnUnbalanced, Currency, ReservableCurrency, Get, Time, Randomness};
 use frame_support::weights::{FunctionOf, DispatchClass, Weight, GetDispatchInfo, Pays};
 use frame_system::{self as system, ensure_signed, RawOrigin, ensure_root};
 use pallet_contracts_primitives::{RentProjection, ContractAccessError};
@@ -216,6 +225,10 @@ pub struct RawAliveContractInfo<CodeHash, Balance, BlockNumber> {
 	pub code_hash: CodeHash,
 	/// Pay rent at most up to this value.
 	pub rent_allowance: Balance,
+	/// The sum of the deposits that payers have reserved for the storage of this contract.
+	///
+	/// Always zero unless [`StorageMode::Deposit`] is used.
+	pub storage_deposit: Balance,
 	/// Last block rent has been payed.
 	pub deduct_block: BlockNumber,
 	/// Last block child storage has been written.
@@ -373,6 +386,31 @@ pub trait Trait: frame_system::Trait + pallet_transaction_payment::Trait {
 
 	/// The maximum size of a storage value in bytes.
 	type MaxValueSize: Get<u32>;
+
+	/// Whether contracts pay rent for their storage or callers pay deposits for it.
+	///
+	/// When switching an existing chain to [`StorageMode::Deposit`] the deposits for the
+	/// storage that already exists are not collected.
+	type StorageMode: Get<StorageMode>;
+
+	/// The currency in which storage deposits are reserved.
+	///
+	/// Must be the same currency as the one of `pallet_transaction_payment`. Only used with
+	/// [`StorageMode::Deposit`].
+	type DepositCurrency: ReservableCurrency<Self::AccountId, Balance = BalanceOf<Self>>;
+
+	/// The deposit a caller pays for every byte it adds to the storage of a contract.
+	///
+	/// Only used with [`StorageMode::Deposit`].
+	type DepositPerByte: Get<BalanceOf<Self>>;
+
+	/// The deposit a caller pays for every key-value pair it adds to the storage of a contract.
+	///
+	/// Only used with [`StorageMode::Deposit`].
+	type DepositPerItem: Get<BalanceOf<Self>>;
+
+	/// Type that allows the runtime authors to add new host functions for a contract to call.
+	type ChainExtension: chain_extension::ChainExtension<Self>;
 }
 
 /// Simple contract address determiner.
@@ -461,12 +499,36 @@ decl_module! {
 		/// The maximum size of a storage value in bytes. A reasonable default is 16 KiB.
 		const MaxValueSize: u32 = T::MaxValueSize::get();
 
+		/// Whether contracts pay rent for their storage or callers pay deposits for it.
+		const StorageMode: StorageMode = T::StorageMode::get();
+
+		/// The deposit a caller pays for every byte it adds to the storage of a contract.
+		const DepositPerByte: BalanceOf<T> = T::DepositPerByte::get();
+
+		/// The deposit a caller pays for every key-value pair it adds to the storage of a contract.
+		const DepositPerItem: BalanceOf<T> = T::DepositPerItem::get();
+
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			let version = StorageVersion::get();
+			let mut weight = T::DbWeight::get().reads(1);
+			if version.is_none() {
+				deposit::migrate::<T>();
+				weight = T::MaximumBlockWeight::get();
+			}
+			if version < Some(Releases::V2) {
+				migrate_schedule();
+				StorageVersion::put(Releases::V2);
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 2));
+			}
+			weight
+		}
+
 		/// Updates the schedule for metering contracts.
 		///
 		/// The schedule must have a greater version than the stored schedule.
-		#[weight = 0]
+		#[weight = weights::update_schedule(0).saturating_add(T::DbWeight::get().reads_writes(1, 1))]
 		pub fn update_schedule(origin, schedule: Schedule) -> DispatchResult {
 			ensure_root(origin)?;
 			if <Module<T>>::current_schedule().version >= schedule.version {
@@ -482,7 +544,8 @@ decl_module! {
 		/// Stores the given binary Wasm code into the chain's storage and returns its `codehash`.
 		/// You can instantiate contracts only with stored code.
 		#[weight = FunctionOf(
-			|args: (&Vec<u8>,)| Module::<T>::calc_code_put_costs(args.0),
+			|args: (&Vec<u8>,)| Module::<T>::calc_code_put_costs(args.0)
+				.saturating_add(T::DbWeight::get().reads_writes(1, 2)),
 			DispatchClass::Normal,
 			Pays::Yes
 		)]
@@ -507,7 +570,8 @@ decl_module! {
 		/// * If no account exists and the call value is not less than `existential_deposit`,
 		/// a regular account will be created and any value will be transferred.
 		#[weight = FunctionOf(
-			|args: (&<T::Lookup as StaticLookup>::Source, &BalanceOf<T>, &Weight, &Vec<u8>)| *args.2,
+			|args: (&<T::Lookup as StaticLookup>::Source, &BalanceOf<T>, &Weight, &Vec<u8>)|
+				Module::<T>::call_base_weight(args.3).saturating_add(*args.2),
 			DispatchClass::Normal,
 			Pays::Yes
 		)]
@@ -520,12 +584,13 @@ decl_module! {
 		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
 			let dest = T::Lookup::lookup(dest)?;
+			let base_weight = Self::call_base_weight(&data);
 			let mut gas_meter = GasMeter::new(gas_limit);
 
-			let result = Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
+			let result = Self::execute_wasm(origin, &mut gas_meter, None, |ctx, gas_meter| {
 				ctx.call(dest, value, gas_meter, data)
 			});
-			gas_meter.into_dispatch_result(result.map_err(|e| e.reason))
+			gas_meter.into_dispatch_result(result.map_err(|e| e.reason), base_weight)
 		}
 
 		/// Instantiates a new contract from the `codehash` generated by `put_code`, optionally transferring some balance.
@@ -539,7 +604,8 @@ decl_module! {
 		///   upon any call received by this account.
 		/// - The contract is initialized.
 		#[weight = FunctionOf(
-			|args: (&BalanceOf<T>, &Weight, &CodeHash<T>, &Vec<u8>)| *args.1,
+			|args: (&BalanceOf<T>, &Weight, &CodeHash<T>, &Vec<u8>)|
+				Module::<T>::instantiate_base_weight(args.3).saturating_add(*args.1),
 			DispatchClass::Normal,
 			Pays::Yes
 		)]
@@ -551,13 +617,14 @@ decl_module! {
 			data: Vec<u8>
 		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
+			let base_weight = Self::instantiate_base_weight(&data);
 			let mut gas_meter = GasMeter::new(gas_limit);
 
-			let result = Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
+			let result = Self::execute_wasm(origin, &mut gas_meter, None, |ctx, gas_meter| {
 				ctx.instantiate(endowment, gas_meter, &code_hash, data)
 					.map(|(_address, output)| output)
 			});
-			gas_meter.into_dispatch_result(result.map_err(|e| e.reason))
+			gas_meter.into_dispatch_result(result.map_err(|e| e.reason), base_weight)
 		}
 
 		/// Allows block producers to claim a small reward for evicting a contract. If a block producer
@@ -565,7 +632,11 @@ decl_module! {
 		///
 		/// If contract is not evicted as a result of this call, no actions are taken and
 		/// the sender is not eligible for the reward.
-		#[weight = 0]
+		///
+		/// The code of the contract isn't known in advance, so the claim is charged as if it was
+		/// as large as a block.
+		#[weight = weights::claim_surcharge(T::MaximumBlockLength::get() / 1024)
+			.saturating_add(T::DbWeight::get().reads_writes(3, 3))]
 		fn claim_surcharge(origin, dest: T::AccountId, aux_sender: Option<T::AccountId>) {
 			let origin = origin.into();
 			let (signed, rewarded) = match (origin, aux_sender) {
@@ -609,11 +680,31 @@ impl<T: Trait> Module<T> {
 		input_data: Vec<u8>,
 	) -> ExecResult {
 		let mut gas_meter = GasMeter::new(gas_limit);
-		Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
+		Self::execute_wasm(origin, &mut gas_meter, None, |ctx, gas_meter| {
 			ctx.call(dest, value, gas_meter, input_data)
 		})
 	}
 
+	/// Perform a call to a specified contract and record a trace of its execution.
+	///
+	/// This function behaves like `Self::bare_call` but additionally returns the gas consumed
+	/// by the call and the trace of every frame that was entered, in the order they were
+	/// entered. It is intended for RPCs that dry-run a call.
+	pub fn bare_call_traced(
+		origin: T::AccountId,
+		dest: T::AccountId,
+		value: BalanceOf<T>,
+		gas_limit: Gas,
+		input_data: Vec<u8>,
+	) -> (ExecResult, Gas, Vec<CallTraceOf<T>>) {
+		let mut gas_meter = GasMeter::new(gas_limit);
+		let mut trace = Vec::new();
+		let result = Self::execute_wasm(origin, &mut gas_meter, Some(&mut trace), |ctx, gas_meter| {
+			ctx.call(dest, value, gas_meter, input_data)
+		});
+		(result, gas_limit.saturating_sub(gas_meter.gas_left()), trace)
+	}
+
 	/// Query storage of a specified contract under a specified key.
 	pub fn get_storage(
 		address: T::AccountId,
@@ -642,24 +733,98 @@ impl<T: Trait> Module<T> {
 
 impl<T: Trait> Module<T> {
 	fn calc_code_put_costs(code: &Vec<u8>) -> Gas {
-		<Module<T>>::current_schedule().put_code_per_byte_cost.saturating_mul(code.len() as Gas)
+		<Module<T>>::current_schedule().put_code_per_byte_cost
+			.saturating_mul(code.len() as Gas)
+			.saturating_add(weights::put_code(0))
 	}
 
+	/// The size of the input `data` of a dispatchable in KiB, rounded up.
+	fn data_kb(data: &[u8]) -> u32 {
+		(data.len() as u32).saturating_add(1023) / 1024
+	}
+
+	/// The weight of `call` with the input `data`, without the gas limit.
+	///
+	/// Reads the schedule, the contract, its code, the timestamp and both accounts, and writes
+	/// the contract and both accounts.
+	fn call_base_weight(data: &[u8]) -> Weight {
+		weights::call(Self::data_kb(data))
+			.saturating_add(T::DbWeight::get().reads_writes(6, 3))
+	}
+
+	/// The weight of `instantiate` with the input `data`, without the gas limit.
+	///
+	/// Reads the schedule, the code, the contract to check it doesn't exist yet, the timestamp
+	/// and both accounts, and writes the contract and both accounts.
+	fn instantiate_base_weight(data: &[u8]) -> Weight {
+		weights::instantiate(Self::data_kb(data))
+			.saturating_add(T::DbWeight::get().reads_writes(6, 3))
+	}
+
+	/// Executes `func` in a new top level execution context.
+	///
+	/// If `trace` is supplied it is filled with the traces of all frames entered by `func`.
 	fn execute_wasm(
 		origin: T::AccountId,
 		gas_meter: &mut GasMeter<T>,
+		trace: Option<&mut Vec<CallTraceOf<T>>>,
 		func: impl FnOnce(&mut ExecutionContext<T, WasmVm, WasmLoader>, &mut GasMeter<T>) -> ExecResult
 	) -> ExecResult {
 		let cfg = Config::preload();
 		let vm = WasmVm::new(&cfg.schedule);
 		let loader = WasmLoader::new(&cfg.schedule);
-		let mut ctx = ExecutionContext::top_level(origin.clone(), &cfg, &vm, &loader);
+		let mut ctx = if trace.is_some() {
+			ExecutionContext::top_level_traced(origin.clone(), &cfg, &vm, &loader)
+		} else {
+			ExecutionContext::top_level(origin.clone(), &cfg, &vm, &loader)
+		};
 
-		let result = func(&mut ctx, gas_meter);
+		let mut result = func(&mut ctx, gas_meter);
+
+		if let (Some(trace), Some(recorder)) = (trace, ctx.recorder.take()) {
+			*trace = recorder.into_calls();
+		}
 
 		if result.as_ref().map(|output| output.is_success()).unwrap_or(false) {
-			// Commit all changes that made it thus far into the persistent storage.
-			DirectAccountDb.commit(ctx.overlay.into_change_set());
+			// The deposits can only be settled against the storage before the commit. Both the
+			// reads of the previous values and the settlement are paid for by the execution.
+			let settlement = if T::StorageMode::get() == StorageMode::Deposit {
+				let read = DepositToken::ReadChangedKeys(ctx.overlay.changed_keys());
+				if gas_meter.charge(&cfg, read).is_out_of_gas() {
+					None
+				} else {
+					let reset = ctx.overlay.reset_contracts();
+					let deltas = ctx.overlay.storage_deltas();
+					let contracts = reset.len().saturating_add(deltas.len()) as u32;
+					if gas_meter.charge(&cfg, DepositToken::Settle(contracts)).is_out_of_gas() {
+						None
+					} else {
+						Some((reset, deltas))
+					}
+				}
+			} else {
+				Some((Vec::new(), Vec::new()))
+			};
+			let settled = match settlement {
+				Some((reset, deltas)) => {
+					let change_set = ctx.overlay.into_change_set();
+					let deferred = &mut ctx.deferred;
+					with_transaction(|| {
+						// Commit all changes that made it thus far into the persistent storage.
+						DirectAccountDb.commit(change_set);
+						match deposit::settle::<T>(&origin, reset, deltas, &cfg, gas_meter, deferred) {
+							Ok(()) => TransactionOutcome::Commit(Ok(())),
+							Err(reason) => TransactionOutcome::Rollback(Err(reason)),
+						}
+					})
+				}
+				None => Err(deposit::OUT_OF_GAS.into()),
+			};
+			if let Err(reason) = settled {
+				// Nothing of the execution is committed, so nothing of it must become visible.
+				ctx.deferred.clear();
+				result = Err(ExecError { reason, buffer: Vec::new() });
+			}
 		}
 
 		// Execute deferred actions.
@@ -782,6 +947,7 @@ impl<T: Trait> Module<T> {
 			total_pair_count: origin_contract.total_pair_count,
 			code_hash,
 			rent_allowance,
+			storage_deposit: origin_contract.storage_deposit,
 			deduct_block: current_block,
 			last_write,
 		}));
@@ -838,9 +1004,62 @@ decl_event! {
 
 		/// An event deposited upon execution of a contract from the account.
 		ContractExecution(AccountId, Vec<u8>),
+
+		/// A storage deposit was reserved for the storage of a contract.
+		///
+		/// # Params
+		///
+		/// - `payer`: `AccountId`: The account the deposit was reserved on.
+		/// - `contract`: `AccountId`: The contract whose storage grew.
+		/// - `amount`: `Balance`: The amount that was reserved.
+		StorageDepositCharged(AccountId, AccountId, Balance),
+
+		/// A storage deposit for the storage of a contract was unreserved.
+		///
+		/// # Params
+		///
+		/// - `payee`: `AccountId`: The account the deposit was unreserved on.
+		/// - `contract`: `AccountId`: The contract whose storage shrank.
+		/// - `amount`: `Balance`: The amount that was refunded.
+		StorageDepositRefunded(AccountId, AccountId, Balance),
+
+		/// A contract replaced its code through `ext_set_code_hash`.
+		///
+		/// # Params
+		///
+		/// - `contract`: `AccountId`: The contract whose code was replaced.
+		/// - `new_code_hash`: `Hash`: The code hash the contract uses from now on.
+		/// - `old_code_hash`: `Hash`: The code hash the contract used before.
+		ContractCodeUpdated(AccountId, Hash, Hash),
 	}
 }
 
+// A value placed in storage that represents the current version of the Contracts storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+enum Releases {
+	/// Contracts carry a storage deposit.
+	V1,
+	/// The stored `Schedule` has the layout with hashing, signature and per-instruction costs.
+	V2,
+}
+
+/// Replaces the stored schedule with the default one.
+///
+/// A schedule stored with an older layout can't be decoded anymore. Only its version, which is
+/// the first field of every layout, is read and carried over incremented. This way
+/// `update_schedule` keeps rejecting the schedules that were already superseded.
+fn migrate_schedule() {
+	let version = frame_support::storage::unhashed::get_raw(&CurrentSchedule::hashed_key())
+		.and_then(|raw| u32::decode(&mut &raw[..]).ok())
+		.unwrap_or_default();
+	CurrentSchedule::put(Schedule {
+		version: version.saturating_add(1),
+		.. Default::default()
+	});
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait> as Contracts {
 		/// Current cost schedule for contracts.
@@ -855,6 +1074,14 @@ decl_storage! {
 		///
 		/// TWOX-NOTE: SAFE since `AccountId` is a secure hash.
 		pub ContractInfoOf: map hasher(twox_64_concat) T::AccountId => Option<ContractInfo<T>>;
+		/// The storage deposit reserved on a payer for the storage of a contract.
+		///
+		/// TWOX-NOTE: SAFE since `AccountId` is a secure hash.
+		pub StorageDepositOf:
+			double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) T::AccountId
+			=> BalanceOf<T>;
+		/// The version of the storage layout. `None` for chains that predate storage deposits.
+		StorageVersion build(|_| Some(Releases::V2)): Option<Releases>;
 	}
 }
 
@@ -896,8 +1123,16 @@ pub struct Schedule {
 	pub grow_mem_cost: Gas,
 
 	/// Gas cost of a regular operation.
+	///
+	/// Charged for every instruction that isn't covered by `instruction_weights`.
 	pub regular_op_cost: Gas,
 
+	/// Gas costs of the wasm instruction classes.
+	pub instruction_weights: InstructionWeights,
+
+	/// Base gas costs of the host functions that a contract can call.
+	pub host_fn_weights: HostFnWeights,
+
 	/// Gas cost per one byte returned.
 	pub return_data_per_byte_cost: Gas,
 
@@ -931,6 +1166,57 @@ pub struct Schedule {
 	/// Cost for instantiating a new contract.
 	pub instantiate_cost: Gas,
 
+	/// Gas cost of reading the previous value of a changed storage key to compute the storage
+	/// deposit. Only charged in [`StorageMode::Deposit`].
+	pub storage_deposit_read_cost: Gas,
+
+	/// Gas cost of settling the storage deposit of a single contract touched by an execution.
+	/// Only charged in [`StorageMode::Deposit`].
+	pub storage_deposit_settle_cost: Gas,
+
+	/// Gas cost of reading and refunding the storage deposit a single payer holds for a
+	/// contract whose storage was freed. Only charged in [`StorageMode::Deposit`].
+	pub storage_deposit_refund_cost: Gas,
+
+	/// Base gas cost of computing a SHA2 256-bit hash.
+	pub hash_sha2_256_cost: Gas,
+
+	/// Gas cost per input byte of computing a SHA2 256-bit hash.
+	pub hash_sha2_256_per_byte_cost: Gas,
+
+	/// Base gas cost of computing a KECCAK 256-bit hash.
+	pub hash_keccak_256_cost: Gas,
+
+	/// Gas cost per input byte of computing a KECCAK 256-bit hash.
+	pub hash_keccak_256_per_byte_cost: Gas,
+
+	/// Base gas cost of computing a BLAKE2 256-bit hash.
+	pub hash_blake2_256_cost: Gas,
+
+	/// Gas cost per input byte of computing a BLAKE2 256-bit hash.
+	pub hash_blake2_256_per_byte_cost: Gas,
+
+	/// Base gas cost of computing a BLAKE2 128-bit hash.
+	pub hash_blake2_128_cost: Gas,
+
+	/// Gas cost per input byte of computing a BLAKE2 128-bit hash.
+	pub hash_blake2_128_per_byte_cost: Gas,
+
+	/// Gas cost of recovering the public key from a SECP256k1 ECDSA signature.
+	pub ecdsa_recover_cost: Gas,
+
+	/// Base gas cost of verifying an sr25519 signature.
+	pub sr25519_verify_cost: Gas,
+
+	/// Gas cost per message byte of verifying an sr25519 signature.
+	pub sr25519_verify_per_byte_cost: Gas,
+
+	/// Base gas cost of verifying an ed25519 signature.
+	pub ed25519_verify_cost: Gas,
+
+	/// Gas cost per message byte of verifying an ed25519 signature.
+	pub ed25519_verify_per_byte_cost: Gas,
+
 	/// The maximum number of topics supported by an event.
 	pub max_event_topics: u32,
 
@@ -954,29 +1240,314 @@ pub struct Schedule {
 	pub max_subject_len: u32,
 }
 
-// 500 (2 instructions per nano second on 2GHZ) * 1000x slowdown through wasmi
-// This is a wild guess and should be viewed as a rough estimation.
-// Proper benchmarks are needed before this value and its derivatives can be used in production.
-const WASM_INSTRUCTION_COST: Gas = 500_000;
+/// Gas costs of the wasm instruction classes as distinguished by the gas metering
+/// instrumentation.
+///
+/// Every instruction of a class is charged with the same cost. Floating point instructions
+/// are not listed because they are rejected when the code is prepared.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+pub struct InstructionWeights {
+	/// `i32.const` and `i64.const`.
+	pub constant: Gas,
+
+	/// Accesses to locals and globals (`get_local`, `set_local`, `tee_local`, `get_global`,
+	/// `set_global`).
+	pub local: Gas,
+
+	/// Loads from the linear memory.
+	pub load: Gas,
+
+	/// Stores to the linear memory.
+	pub store: Gas,
+
+	/// Integer additions and subtractions.
+	pub add: Gas,
+
+	/// Integer multiplications.
+	pub mul: Gas,
+
+	/// Integer divisions and remainders.
+	pub div: Gas,
+
+	/// Bitwise operations, shifts and rotations.
+	pub bit: Gas,
+
+	/// Integer comparisons.
+	pub integer_comparison: Gas,
+
+	/// Conversions between integer types.
+	pub conversion: Gas,
+
+	/// Blocks, branches, calls, `drop` and `select`.
+	pub control_flow: Gas,
+
+	/// `current_memory`.
+	pub current_memory: Gas,
+
+	/// `nop`.
+	pub nop: Gas,
+}
+
+/// Base gas costs of the host functions.
+///
+/// These are charged when a host function is entered, on top of the costs for reading from and
+/// writing to the sandbox memory. Host functions which already have a dedicated base cost in
+/// [`Schedule`] (calls, instantiations, dispatches, events, hashing and signature checks) are
+/// not listed here. `ext_gas` is not listed because it only charges the gas it is passed.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+pub struct HostFnWeights {
+	/// Base cost of `ext_caller`.
+	pub ext_caller: Gas,
+
+	/// Base cost of `ext_address`.
+	pub ext_address: Gas,
+
+	/// Base cost of `ext_gas_price`.
+	pub ext_gas_price: Gas,
+
+	/// Base cost of `ext_gas_left`.
+	pub ext_gas_left: Gas,
+
+	/// Base cost of `ext_balance`.
+	pub ext_balance: Gas,
+
+	/// Base cost of `ext_value_transferred`.
+	pub ext_value_transferred: Gas,
+
+	/// Base cost of `ext_minimum_balance`.
+	pub ext_minimum_balance: Gas,
+
+	/// Base cost of `ext_tombstone_deposit`.
+	pub ext_tombstone_deposit: Gas,
+
+	/// Base cost of `ext_rent_allowance`.
+	pub ext_rent_allowance: Gas,
+
+	/// Base cost of `ext_block_number`.
+	pub ext_block_number: Gas,
+
+	/// Base cost of `ext_now`.
+	pub ext_now: Gas,
+
+	/// Base cost of `ext_random`.
+	pub ext_random: Gas,
+
+	/// Base cost of `ext_set_storage`.
+	pub ext_set_storage: Gas,
+
+	/// Base cost of `ext_clear_storage`.
+	pub ext_clear_storage: Gas,
+
+	/// Base cost of `ext_get_storage`.
+	pub ext_get_storage: Gas,
+
+	/// Base cost of `ext_transfer`.
+	pub ext_transfer: Gas,
+
+	/// Base cost of `ext_terminate`.
+	pub ext_terminate: Gas,
+
+	/// Base cost of `ext_restore_to`.
+	pub ext_restore_to: Gas,
+
+	/// Base cost of `ext_return`.
+	pub ext_return: Gas,
+
+	/// Base cost of `ext_scratch_size`.
+	pub ext_scratch_size: Gas,
+
+	/// Base cost of `ext_scratch_read`.
+	pub ext_scratch_read: Gas,
+
+	/// Base cost of `ext_scratch_write`.
+	pub ext_scratch_write: Gas,
+
+	/// Base cost of `ext_set_rent_allowance`.
+	pub ext_set_rent_allowance: Gas,
+
+	/// Base cost of `ext_set_code_hash`.
+	pub ext_set_code_hash: Gas,
+
+	/// Base cost of `ext_get_runtime_storage`.
+	pub ext_get_runtime_storage: Gas,
+
+	/// Base cost of `ext_println`.
+	pub ext_println: Gas,
+
+	/// Base cost of `ext_call_chain_extension`.
+	pub ext_call_chain_extension: Gas,
+}
+
+/// The weight of a single unit when every step of a benchmark component processes `units` units.
+fn per_unit(weight: impl Fn(u32) -> Weight, units: u32) -> Gas {
+	weight(1).saturating_sub(weight(0)) / Gas::from(units)
+}
+
+/// The weight of a single call of a host function benchmarked in batches.
+fn per_call(weight: impl Fn(u32) -> Weight) -> Gas {
+	per_unit(weight, weights::API_BENCHMARK_BATCH_SIZE)
+}
+
+/// The weight per byte of a host function benchmarked in batches with KiB of data.
+fn per_byte(weight: impl Fn(u32) -> Weight) -> Gas {
+	per_unit(weight, weights::API_BENCHMARK_BATCH_SIZE * 1024)
+}
+
+/// The weight of a single instruction benchmarked in batches.
+fn per_instr(weight: impl Fn(u32) -> Weight) -> Gas {
+	per_unit(weight, weights::INSTR_BENCHMARK_BATCH_SIZE)
+}
+
+/// The weight of a transfer which creates the receiving account.
+///
+/// This is `ext_transfer` without entering the host function which is charged like
+/// `ext_caller`.
+fn transfer_weight() -> Gas {
+	per_call(weights::ext_transfer).saturating_sub(per_call(weights::ext_caller))
+}
+
+// The instruction weights are derived from the `instr_*` benchmarks with the formulas noted
+// there. Results below the weight of `nop` are noise of the subtracted helper instructions and
+// are raised to it.
+impl Default for InstructionWeights {
+	fn default() -> InstructionWeights {
+		let control_flow = per_instr(weights::instr_control_flow) / 2;
+		let constant = per_instr(weights::instr_const).saturating_sub(control_flow);
+		let binary = |weight: fn(u32) -> Weight| per_instr(weight)
+			.saturating_sub(2 * constant)
+			.saturating_sub(control_flow);
+		let nop = per_instr(weights::instr_nop);
+		InstructionWeights {
+			constant: constant.max(nop),
+			local: (per_instr(weights::instr_local).max(per_instr(weights::instr_global)) / 2)
+				.max(nop),
+			load: per_instr(weights::instr_load)
+				.saturating_sub(constant)
+				.saturating_sub(control_flow)
+				.max(nop),
+			store: per_instr(weights::instr_store).saturating_sub(2 * constant).max(nop),
+			add: binary(weights::instr_add).max(nop),
+			mul: binary(weights::instr_mul).max(nop),
+			div: binary(weights::instr_div).max(nop),
+			bit: binary(weights::instr_bit).max(nop),
+			integer_comparison: binary(weights::instr_integer_comparison).max(nop),
+			conversion: per_instr(weights::instr_conversion)
+				.saturating_sub(constant)
+				.saturating_sub(control_flow)
+				.max(nop),
+			control_flow: control_flow.max(nop),
+			current_memory: per_instr(weights::instr_current_memory)
+				.saturating_sub(control_flow)
+				.max(nop),
+			nop,
+		}
+	}
+}
+
+// The host function weights are the results of the `ext_*` benchmarks divided by the batch
+// size. The transfer of `ext_transfer` and `ext_terminate` is charged separately with
+// `Schedule::transfer_cost`. `ext_restore_to` is charged like `ext_terminate`, since it isn't
+// benchmarked. `ext_call_chain_extension` is charged like `ext_caller`: the chain extension
+// charges its own work, the host function itself only has to be entered.
+impl Default for HostFnWeights {
+	fn default() -> HostFnWeights {
+		let ext_terminate = per_unit(weights::ext_terminate, 1).saturating_sub(transfer_weight());
+		HostFnWeights {
+			ext_caller: per_call(weights::ext_caller),
+			ext_address: per_call(weights::ext_address),
+			ext_gas_price: per_call(weights::ext_gas_price),
+			ext_gas_left: per_call(weights::ext_gas_left),
+			ext_balance: per_call(weights::ext_balance),
+			ext_value_transferred: per_call(weights::ext_value_transferred),
+			ext_minimum_balance: per_call(weights::ext_minimum_balance),
+			ext_tombstone_deposit: per_call(weights::ext_tombstone_deposit),
+			ext_rent_allowance: per_call(weights::ext_rent_allowance),
+			ext_block_number: per_call(weights::ext_block_number),
+			ext_now: per_call(weights::ext_now),
+			ext_random: per_call(weights::ext_random),
+			ext_set_storage: per_call(weights::ext_set_storage),
+			ext_clear_storage: per_call(weights::ext_clear_storage),
+			ext_get_storage: per_call(weights::ext_get_storage),
+			ext_transfer: per_call(weights::ext_caller),
+			ext_terminate,
+			ext_restore_to: ext_terminate,
+			ext_return: per_unit(weights::ext_return, 1),
+			ext_scratch_size: per_call(weights::ext_scratch_size),
+			ext_scratch_read: per_call(weights::ext_scratch_read),
+			ext_scratch_write: per_call(weights::ext_scratch_write),
+			ext_set_rent_allowance: per_call(weights::ext_set_rent_allowance),
+			ext_set_code_hash: per_call(weights::ext_set_code_hash),
+			ext_get_runtime_storage: per_call(weights::ext_get_runtime_storage),
+			ext_println: per_call(weights::ext_println),
+			ext_call_chain_extension: per_call(weights::ext_caller),
+		}
+	}
+}
 
+// Every cost is derived from the benchmark that measures it. The data of `ext_scratch_read` is
+// written to the sandbox memory and the data of `ext_scratch_write` is read from it. The
+// endowment transferred by `ext_instantiate` is charged separately with `instantiate_cost`.
+// `ext_dispatch_call` isn't benchmarked and is charged like a call on top of the weight of the
+// dispatched call, which covers the dispatch itself. Tracking the storage deposit of a changed
+// key is charged like a storage read.
 impl Default for Schedule {
 	fn default() -> Schedule {
+		let instruction_weights = InstructionWeights::default();
+		let regular_op_cost = [
+			instruction_weights.constant,
+			instruction_weights.local,
+			instruction_weights.load,
+			instruction_weights.store,
+			instruction_weights.add,
+			instruction_weights.mul,
+			instruction_weights.div,
+			instruction_weights.bit,
+			instruction_weights.integer_comparison,
+			instruction_weights.conversion,
+			instruction_weights.control_flow,
+			instruction_weights.current_memory,
+		].iter().copied().fold(instruction_weights.nop, Gas::max);
+		let sandbox_data_read_cost = per_byte(weights::ext_scratch_write_per_kb);
+		let call_base_cost = per_call(weights::ext_call);
 		Schedule {
 			version: 0,
-			put_code_per_byte_cost: WASM_INSTRUCTION_COST,
-			grow_mem_cost: WASM_INSTRUCTION_COST,
-			regular_op_cost: WASM_INSTRUCTION_COST,
-			return_data_per_byte_cost: WASM_INSTRUCTION_COST,
-			event_data_per_byte_cost: WASM_INSTRUCTION_COST,
-			event_per_topic_cost: WASM_INSTRUCTION_COST,
-			event_base_cost: WASM_INSTRUCTION_COST,
-			call_base_cost: 135 * WASM_INSTRUCTION_COST,
-			dispatch_base_cost: 135 * WASM_INSTRUCTION_COST,
-			instantiate_base_cost: 175 * WASM_INSTRUCTION_COST,
-			sandbox_data_read_cost: WASM_INSTRUCTION_COST,
-			sandbox_data_write_cost: WASM_INSTRUCTION_COST,
-			transfer_cost: 100 * WASM_INSTRUCTION_COST,
-			instantiate_cost: 200 * WASM_INSTRUCTION_COST,
+			put_code_per_byte_cost: per_unit(weights::put_code, 1024),
+			// Every `grow_memory` is paired with a constant and a `drop` like in `instr_const`.
+			grow_mem_cost: per_unit(weights::instr_grow_memory, 1)
+				.saturating_sub(per_instr(weights::instr_const)),
+			regular_op_cost,
+			instruction_weights,
+			host_fn_weights: Default::default(),
+			return_data_per_byte_cost: sandbox_data_read_cost,
+			event_data_per_byte_cost: per_byte(|n| weights::ext_deposit_event_per_topic_and_kb(0, n)),
+			event_per_topic_cost: per_call(|t| weights::ext_deposit_event_per_topic_and_kb(t, 0)),
+			event_base_cost: per_call(weights::ext_deposit_event),
+			call_base_cost,
+			dispatch_base_cost: call_base_cost,
+			instantiate_base_cost: per_call(weights::ext_instantiate)
+				.saturating_sub(transfer_weight()),
+			sandbox_data_read_cost,
+			sandbox_data_write_cost: per_byte(weights::ext_scratch_read_per_kb),
+			transfer_cost: transfer_weight(),
+			instantiate_cost: transfer_weight(),
+			storage_deposit_read_cost: per_call(weights::ext_get_storage),
+			storage_deposit_settle_cost: per_unit(|c| weights::settle(c, 0), 1),
+			storage_deposit_refund_cost: per_unit(|p| weights::settle(0, p), 1),
+			hash_sha2_256_cost: per_call(weights::ext_hash_sha2_256),
+			hash_sha2_256_per_byte_cost: per_byte(weights::ext_hash_sha2_256_per_kb),
+			hash_keccak_256_cost: per_call(weights::ext_hash_keccak_256),
+			hash_keccak_256_per_byte_cost: per_byte(weights::ext_hash_keccak_256_per_kb),
+			hash_blake2_256_cost: per_call(weights::ext_hash_blake2_256),
+			hash_blake2_256_per_byte_cost: per_byte(weights::ext_hash_blake2_256_per_kb),
+			hash_blake2_128_cost: per_call(weights::ext_hash_blake2_128),
+			hash_blake2_128_per_byte_cost: per_byte(weights::ext_hash_blake2_128_per_kb),
+			ecdsa_recover_cost: per_call(weights::ext_ecdsa_recover),
+			sr25519_verify_cost: per_call(weights::ext_sr25519_verify),
+			sr25519_verify_per_byte_cost: per_byte(weights::ext_sr25519_verify_per_kb),
+			ed25519_verify_cost: per_call(weights::ext_ed25519_verify),
+			ed25519_verify_per_byte_cost: per_byte(weights::ext_ed25519_verify_per_kb),
 			max_event_topics: 4,
 			max_stack_height: 64 * 1024,
 			max_memory_pages: 16,
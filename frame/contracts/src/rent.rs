@@ -17,7 +17,7 @@
 //! A module responsible for computing the right amount of weight and charging it.
 
 use crate::{
-	AliveContractInfo, BalanceOf, ContractInfo, ContractInfoOf, Module, RawEvent,
+	AliveContractInfo, BalanceOf, ContractInfo, ContractInfoOf, Module, RawEvent, StorageMode,
 	TombstoneContractInfo, Trait,
 };
 use frame_support::storage::child;
@@ -109,7 +109,7 @@ fn compute_fee_per_block<T: Trait>(
 /// tombstone deposit, required for leaving a tombstone.
 ///
 /// Rent mechanism cannot make the balance lower than subsistence threshold.
-fn subsistence_threshold<T: Trait>() -> BalanceOf<T> {
+pub(crate) fn subsistence_threshold<T: Trait>() -> BalanceOf<T> {
 	T::Currency::minimum_balance() + T::TombstoneDeposit::get()
 }
 
@@ -145,6 +145,11 @@ fn consider_case<T: Trait>(
 	handicap: T::BlockNumber,
 	contract: &AliveContractInfo<T>,
 ) -> Verdict<T> {
+	if T::StorageMode::get() == StorageMode::Deposit {
+		// The storage is paid for by deposits.
+		return Verdict::Exempt;
+	}
+
 	// How much block has passed since the last deduction for the contract.
 	let blocks_passed = {
 		// Calculate an effective block number, i.e. after adjusting for handicap.
@@ -366,6 +371,10 @@ pub fn compute_rent_projection<T: Trait>(
 		Some(ContractInfo::Alive(contract)) => contract,
 	};
 
+	if T::StorageMode::get() == StorageMode::Deposit {
+		return Ok(RentProjection::NoEviction);
+	}
+
 	// Compute how much would the fee per block be with the *updated* balance.
 	let balance = T::Currency::free_balance(account);
 	let fee_per_block = compute_fee_per_block::<T>(&balance, &alive_contract_info);
@@ -15,23 +15,30 @@
 // along with Substrate. If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	BalanceOf, ContractAddressFor, ContractInfo, ContractInfoOf, GenesisConfig, Module,
-	RawAliveContractInfo, RawEvent, Trait, TrieId, Schedule, TrieIdGenerator,
+	BalanceOf, ContractAddressFor, ContractInfo, ContractInfoOf, Error, GenesisConfig, Module,
+	RawAliveContractInfo, RawEvent, Trait, TrieId, Schedule, TrieIdGenerator, StorageMode,
+	StorageDepositOf, StorageVersion,
+	Releases,
 	account_db::{AccountDb, DirectAccountDb, OverlayAccountDb},
+	deposit::{OldContractInfo, OldRawAliveContractInfo},
+	chain_extension::{ChainExtension, Environment, Ext, RetVal, Result as ExtensionResult},
+	exec::ExecError,
 	gas::Gas,
 };
 use assert_matches::assert_matches;
+use pallet_contracts_primitives::RentProjection;
 use hex_literal::*;
 use codec::Encode;
 use sp_runtime::{
-	Perbill,
+	DispatchError, Perbill,
 	traits::{BlakeTwo256, Hash, IdentityLookup, Convert},
 	testing::{Header, H256},
 };
 use frame_support::{
-	assert_ok, assert_err_ignore_postinfo, impl_outer_dispatch, impl_outer_event,
-	impl_outer_origin, parameter_types, StorageMap, StorageValue,
-	traits::{Currency, Get},
+	assert_ok, assert_noop, assert_err_ignore_postinfo, impl_outer_dispatch, impl_outer_event,
+	impl_outer_origin, parameter_types, StorageMap, StorageDoubleMap, StorageValue, Hashable,
+	storage::{self, migration, TransactionOutcome},
+	traits::{Currency, Get, OnRuntimeUpgrade, ReservableCurrency},
 	weights::{Weight, PostDispatchInfo, IdentityFee},
 };
 use std::cell::RefCell;
@@ -66,6 +73,7 @@ impl_outer_dispatch! {
 
 thread_local! {
 	static EXISTENTIAL_DEPOSIT: RefCell<u64> = RefCell::new(0);
+	static STORAGE_MODE: RefCell<StorageMode> = RefCell::new(StorageMode::Rent);
 }
 
 pub struct ExistentialDeposit;
@@ -73,6 +81,11 @@ impl Get<u64> for ExistentialDeposit {
 	fn get() -> u64 { EXISTENTIAL_DEPOSIT.with(|v| *v.borrow()) }
 }
 
+pub struct TestStorageMode;
+impl Get<StorageMode> for TestStorageMode {
+	fn get() -> StorageMode { STORAGE_MODE.with(|v| *v.borrow()) }
+}
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Test;
 parameter_types! {
@@ -130,6 +143,8 @@ parameter_types! {
 	pub const SurchargeReward: u64 = 150;
 	pub const MaxDepth: u32 = 100;
 	pub const MaxValueSize: u32 = 16_384;
+	pub const DepositPerByte: u64 = 2;
+	pub const DepositPerItem: u64 = 10;
 }
 
 parameter_types! {
@@ -166,6 +181,11 @@ impl Trait for Test {
 	type SurchargeReward = SurchargeReward;
 	type MaxDepth = MaxDepth;
 	type MaxValueSize = MaxValueSize;
+	type StorageMode = TestStorageMode;
+	type DepositCurrency = Balances;
+	type DepositPerByte = DepositPerByte;
+	type DepositPerItem = DepositPerItem;
+	type ChainExtension = TestExtension;
 }
 
 type Balances = pallet_balances::Module<Test>;
@@ -181,6 +201,28 @@ impl ContractAddressFor<H256, u64> for DummyContractAddressFor {
 	}
 }
 
+/// The weight charged by [`TestExtension`] for querying a balance.
+const BALANCE_QUERY_WEIGHT: Gas = 1_000_000_000;
+
+/// A chain extension exposing the free balance of any account to contracts.
+pub struct TestExtension;
+impl ChainExtension<Test> for TestExtension {
+	fn call<E: Ext<T = Test>>(func_id: u32, mut env: Environment<E>) -> ExtensionResult<RetVal> {
+		match func_id {
+			// Write the free balance of the given account to the scratch buffer.
+			0 => {
+				env.charge_weight(BALANCE_QUERY_WEIGHT)?;
+				let who: u64 = env.read_as()?;
+				env.write_as(&Balances::free_balance(&who));
+				Ok(RetVal::Converging(0))
+			},
+			// Stop the execution of the contract and return the input to its caller.
+			1 => Ok(RetVal::Diverging(env.read()?)),
+			_ => Err(DispatchError::Other("unknown chain extension function")),
+		}
+	}
+}
+
 pub struct DummyTrieIdGenerator;
 impl TrieIdGenerator<u64> for DummyTrieIdGenerator {
 	fn trie_id(account_id: &u64) -> TrieId {
@@ -205,11 +247,13 @@ const GAS_LIMIT: Gas = 10_000_000_000;
 
 pub struct ExtBuilder {
 	existential_deposit: u64,
+	storage_mode: StorageMode,
 }
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
 			existential_deposit: 1,
+			storage_mode: StorageMode::Rent,
 		}
 	}
 }
@@ -218,8 +262,13 @@ impl ExtBuilder {
 		self.existential_deposit = existential_deposit;
 		self
 	}
+	pub fn storage_mode(mut self, storage_mode: StorageMode) -> Self {
+		self.storage_mode = storage_mode;
+		self
+	}
 	pub fn set_associated_consts(&self) {
 		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+		STORAGE_MODE.with(|v| *v.borrow_mut() = self.storage_mode);
 	}
 	pub fn build(self) -> sp_io::TestExternalities {
 		self.set_associated_consts();
@@ -270,7 +319,9 @@ fn returns_base_call_cost() {
 			Contracts::call(Origin::signed(ALICE), BOB, 0, GAS_LIMIT, Vec::new()),
 			Ok(
 				PostDispatchInfo {
-					actual_weight: Some(67500000),
+					actual_weight: Some(
+						crate::weights::call(0) + Contracts::current_schedule().call_base_cost
+					),
 				}
 			)
 		);
@@ -296,6 +347,7 @@ fn account_removal_does_not_remove_storage() {
 				deduct_block: System::block_number(),
 				code_hash: H256::repeat_byte(1),
 				rent_allowance: 40,
+				storage_deposit: 0,
 				last_write: None,
 			}));
 
@@ -313,6 +365,7 @@ fn account_removal_does_not_remove_storage() {
 				deduct_block: System::block_number(),
 				code_hash: H256::repeat_byte(2),
 				rent_allowance: 40,
+				storage_deposit: 0,
 				last_write: None,
 			}));
 
@@ -1239,6 +1292,392 @@ fn default_rent_allowance_on_instantiate() {
 		});
 }
 
+#[test]
+fn storage_deposit_charged_and_refunded() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_mode(StorageMode::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+
+			// The constructor stores 4 bytes in a single item.
+			let deposit = 4 * 2 + 10;
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				<Test as pallet_balances::Trait>::Balance::from(1_000u32).encode() // rent allowance
+			));
+			let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap().get_alive().unwrap();
+			assert_eq!(bob_contract.storage_deposit, deposit);
+			assert_eq!(StorageDepositOf::<Test>::get(BOB, ALICE), deposit);
+			assert_eq!(Balances::free_balance(BOB), 30_000);
+			assert_eq!(Balances::free_balance(ALICE), 1_000_000 - 30_000 - deposit);
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+			assert!(System::events().iter().any(|record| record.event ==
+				MetaEvent::contracts(RawEvent::StorageDepositCharged(ALICE, BOB, deposit))
+			));
+
+			// Adding another item is paid for by the caller.
+			let _ = Balances::deposit_creating(&CHARLIE, 1_000);
+			assert_ok!(Contracts::call(
+				Origin::signed(CHARLIE),
+				BOB,
+				0,
+				GAS_LIMIT,
+				call::set_storage_4_byte()
+			));
+			let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap().get_alive().unwrap();
+			assert_eq!(bob_contract.storage_deposit, 2 * deposit);
+			assert_eq!(StorageDepositOf::<Test>::get(BOB, CHARLIE), deposit);
+			assert_eq!(Balances::free_balance(BOB), 30_000);
+			assert_eq!(Balances::free_balance(CHARLIE), 1_000 - deposit);
+			assert_eq!(Balances::reserved_balance(CHARLIE), deposit);
+
+			// Removing it unreserves the deposit of the caller.
+			assert_ok!(Contracts::call(
+				Origin::signed(CHARLIE),
+				BOB,
+				0,
+				GAS_LIMIT,
+				call::remove_storage_4_byte()
+			));
+			let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap().get_alive().unwrap();
+			assert_eq!(bob_contract.storage_deposit, deposit);
+			assert!(!StorageDepositOf::<Test>::contains_key(BOB, CHARLIE));
+			assert_eq!(Balances::free_balance(CHARLIE), 1_000);
+			assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+			assert!(System::events().iter().any(|record| record.event ==
+				MetaEvent::contracts(RawEvent::StorageDepositRefunded(CHARLIE, BOB, deposit))
+			));
+		});
+}
+
+#[test]
+fn storage_deposit_refunded_to_payer() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_mode(StorageMode::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				<Test as pallet_balances::Trait>::Balance::from(1_000u32).encode() // rent allowance
+			));
+			let deposit = 4 * 2 + 10;
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				BOB,
+				0,
+				GAS_LIMIT,
+				call::set_storage_4_byte()
+			));
+			assert_eq!(Balances::reserved_balance(ALICE), 2 * deposit);
+
+			// CHARLIE never paid a deposit, so freeing storage doesn't refund anything to CHARLIE.
+			let _ = Balances::deposit_creating(&CHARLIE, 1_000);
+			assert_ok!(Contracts::call(
+				Origin::signed(CHARLIE),
+				BOB,
+				0,
+				GAS_LIMIT,
+				call::remove_storage_4_byte()
+			));
+			let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap().get_alive().unwrap();
+			assert_eq!(bob_contract.storage_deposit, deposit);
+			assert_eq!(StorageDepositOf::<Test>::get(BOB, ALICE), deposit);
+			assert!(!StorageDepositOf::<Test>::contains_key(BOB, CHARLIE));
+			assert_eq!(Balances::free_balance(CHARLIE), 1_000);
+			assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+			assert_eq!(Balances::free_balance(ALICE), 1_000_000 - 30_000 - deposit);
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+			assert!(System::events().iter().any(|record| record.event ==
+				MetaEvent::contracts(RawEvent::StorageDepositRefunded(ALICE, BOB, deposit))
+			));
+		});
+}
+
+#[test]
+fn storage_deposit_requires_balance() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_mode(StorageMode::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				<Test as pallet_balances::Trait>::Balance::from(1_000u32).encode() // rent allowance
+			));
+
+			// CHARLIE has less free balance than the deposit requires.
+			let _ = Balances::deposit_creating(&CHARLIE, 60);
+			assert_ok!(Balances::reserve(&CHARLIE, 50));
+			assert_err_ignore_postinfo!(
+				Contracts::call(
+					Origin::signed(CHARLIE),
+					BOB,
+					0,
+					GAS_LIMIT,
+					call::set_storage_4_byte(),
+				),
+				"not enough balance to pay the storage deposit"
+			);
+			let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap().get_alive().unwrap();
+			assert_eq!(bob_contract.storage_size, 4);
+			assert_eq!(bob_contract.storage_deposit, 18);
+			assert!(!StorageDepositOf::<Test>::contains_key(BOB, CHARLIE));
+			assert_eq!(Balances::free_balance(CHARLIE), 10);
+			assert_eq!(Balances::reserved_balance(CHARLIE), 50);
+		});
+}
+
+#[test]
+fn storage_deposit_settlement_costs_gas() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_mode(StorageMode::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				<Test as pallet_balances::Trait>::Balance::from(1_000u32).encode() // rent allowance
+			));
+
+			let _ = Balances::deposit_creating(&CHARLIE, 1_000);
+			let gas_consumed = storage::with_transaction(|| {
+				let (result, gas_consumed, _) = Contracts::bare_call_traced(
+					CHARLIE,
+					BOB,
+					0,
+					GAS_LIMIT,
+					call::set_storage_4_byte(),
+				);
+				assert!(result.unwrap().is_success());
+				TransactionOutcome::Rollback(gas_consumed)
+			});
+			let schedule = Contracts::current_schedule();
+			assert!(
+				gas_consumed >
+					schedule.storage_deposit_read_cost + schedule.storage_deposit_settle_cost
+			);
+
+			// The execution itself is affordable, settling its deposit isn't.
+			assert_err_ignore_postinfo!(
+				Contracts::call(
+					Origin::signed(CHARLIE),
+					BOB,
+					0,
+					gas_consumed - schedule.storage_deposit_settle_cost,
+					call::set_storage_4_byte(),
+				),
+				"not enough gas to settle the storage deposits"
+			);
+			let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap().get_alive().unwrap();
+			assert_eq!(bob_contract.storage_size, 4);
+			assert!(!StorageDepositOf::<Test>::contains_key(BOB, CHARLIE));
+			assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+
+			assert_ok!(Contracts::call(
+				Origin::signed(CHARLIE),
+				BOB,
+				0,
+				gas_consumed,
+				call::set_storage_4_byte()
+			));
+			assert_eq!(StorageDepositOf::<Test>::get(BOB, CHARLIE), 4 * 2 + 10);
+		});
+}
+
+#[test]
+fn storage_deposit_refund_costs_gas_per_payer() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_mode(StorageMode::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				<Test as pallet_balances::Trait>::Balance::from(1_000u32).encode() // rent allowance
+			));
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				BOB,
+				0,
+				GAS_LIMIT,
+				call::set_storage_4_byte()
+			));
+
+			// CHARLIE holds no deposit, so both CHARLIE and ALICE are read to refund ALICE.
+			let _ = Balances::deposit_creating(&CHARLIE, 1_000);
+			let gas_consumed = storage::with_transaction(|| {
+				let (result, gas_consumed, _) = Contracts::bare_call_traced(
+					CHARLIE,
+					BOB,
+					0,
+					GAS_LIMIT,
+					call::remove_storage_4_byte(),
+				);
+				assert!(result.unwrap().is_success());
+				TransactionOutcome::Rollback(gas_consumed)
+			});
+			let schedule = Contracts::current_schedule();
+			assert!(gas_consumed > 2 * schedule.storage_deposit_refund_cost);
+
+			assert_err_ignore_postinfo!(
+				Contracts::call(
+					Origin::signed(CHARLIE),
+					BOB,
+					0,
+					gas_consumed - schedule.storage_deposit_refund_cost,
+					call::remove_storage_4_byte(),
+				),
+				"not enough gas to settle the storage deposits"
+			);
+			let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap().get_alive().unwrap();
+			assert_eq!(bob_contract.storage_deposit, 2 * (4 * 2 + 10));
+			assert_eq!(Balances::reserved_balance(ALICE), 2 * (4 * 2 + 10));
+
+			assert_ok!(Contracts::call(
+				Origin::signed(CHARLIE),
+				BOB,
+				0,
+				gas_consumed,
+				call::remove_storage_4_byte()
+			));
+			assert_eq!(Balances::reserved_balance(ALICE), 4 * 2 + 10);
+		});
+}
+
+#[test]
+fn no_eviction_in_deposit_mode() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_mode(StorageMode::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				100,
+				GAS_LIMIT,
+				code_hash.into(),
+				<Test as pallet_balances::Trait>::Balance::from(1_000u32).encode() // rent allowance
+			));
+
+			// This would evict the contract if it had to pay rent.
+			initialize_block(10);
+			assert_ok!(Contracts::call(Origin::signed(ALICE), BOB, 0, GAS_LIMIT, call::null()));
+			assert_eq!(Contracts::rent_projection(BOB), Ok(RentProjection::NoEviction));
+
+			let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap().get_alive().unwrap();
+			assert_eq!(bob_contract.rent_allowance, 1_000);
+			assert_eq!(Balances::free_balance(BOB), 100);
+		});
+}
+
+#[test]
+fn storage_deposit_migration() {
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_mode(StorageMode::Deposit)
+		.build()
+		.execute_with(|| {
+			let put_old_contract = |account: u64, storage_size| migration::put_storage_value(
+				b"Contracts",
+				b"ContractInfoOf",
+				&account.twox_64_concat(),
+				OldContractInfo::<Test>::Alive(OldRawAliveContractInfo {
+					trie_id: <Test as Trait>::TrieIdGenerator::trie_id(&account),
+					storage_size,
+					empty_pair_count: 0,
+					total_pair_count: 1,
+					code_hash: H256::repeat_byte(1),
+					rent_allowance: 1_000,
+					deduct_block: 1,
+					last_write: None,
+				}),
+			);
+			put_old_contract(BOB, 40);
+			put_old_contract(CHARLIE, 4);
+			StorageVersion::kill();
+
+			Contracts::on_runtime_upgrade();
+
+			assert_eq!(StorageVersion::get(), Some(Releases::V2));
+			// Nobody paid for the existing storage.
+			let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap().get_alive().unwrap();
+			assert_eq!(bob_contract.storage_deposit, 0);
+			assert_eq!(bob_contract.storage_size, 40);
+			assert_eq!(bob_contract.rent_allowance, 1_000);
+			let charlie_contract = ContractInfoOf::<Test>::get(CHARLIE)
+				.unwrap()
+				.get_alive()
+				.unwrap();
+			assert_eq!(charlie_contract.storage_deposit, 0);
+		});
+}
+
+#[test]
+fn schedule_migration() {
+	ExtBuilder::default().build().execute_with(|| {
+		// A schedule with an older layout: the version followed by fewer costs.
+		storage::unhashed::put_raw(
+			&crate::CurrentSchedule::hashed_key(),
+			&(5u32, 1_000u64, 2_000u64, 3u32).encode(),
+		);
+		StorageVersion::put(Releases::V1);
+
+		Contracts::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get(), Some(Releases::V2));
+		assert_eq!(
+			Contracts::current_schedule(),
+			Schedule { version: 6, .. Default::default() },
+		);
+		assert_noop!(
+			Contracts::update_schedule(Origin::ROOT, Schedule { version: 6, .. Default::default() }),
+			Error::<Test>::InvalidScheduleVersion,
+		);
+	});
+}
+
 #[test]
 fn restorations_dirty_storage_and_different_storage() {
 	restoration(true, true);
@@ -1843,3 +2282,113 @@ fn crypto_hashes() {
 			}
 		})
 }
+
+#[test]
+fn crypto_signatures() {
+	use sp_core::{ecdsa, ed25519, sr25519, Pair};
+
+	let (wasm, code_hash) = compile_module::<Test>("crypto_signatures").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				vec![],
+			));
+			let call = |params: Vec<u8>| {
+				let result = <Module<Test>>::bare_call(ALICE, BOB, 0, GAS_LIMIT, params).unwrap();
+				assert_eq!(result.status, 0);
+				result.data
+			};
+			let message = b"_DEAD_BEEF";
+
+			// Recover the public key of an ECDSA signature.
+			let pair = ecdsa::Pair::from_seed(&[1; 32]);
+			let signature = pair.sign(message);
+			let params = (0u8, &signature, sp_io::hashing::blake2_256(message)).encode();
+			assert_eq!(call(params), (0u32, pair.public()).encode());
+
+			// Another message hash recovers some other public key.
+			let params = (0u8, &signature, [0u8; 32]).encode();
+			let result = call(params);
+			assert_eq!(result[..4], 0u32.encode()[..]);
+			assert_ne!(result[4..], pair.public().encode()[..]);
+
+			// The recovery fails for a malformed signature, leaving the output buffer untouched.
+			let malformed = ecdsa::Signature::from_raw([0; 65]);
+			let params = (0u8, &malformed, sp_io::hashing::blake2_256(message)).encode();
+			assert_eq!(call(params), (1u32, [0u8; 33]).encode());
+
+			// Verify sr25519 and ed25519 signatures.
+			let sr25519_pair = sr25519::Pair::from_seed(&[2; 32]);
+			let ed25519_pair = ed25519::Pair::from_seed(&[3; 32]);
+			let test_cases = vec![
+				(1u8, sr25519_pair.sign(message).encode(), sr25519_pair.public().encode(), 0u32),
+				(1, sr25519_pair.sign(b"other").encode(), sr25519_pair.public().encode(), 1),
+				(2, ed25519_pair.sign(message).encode(), ed25519_pair.public().encode(), 0),
+				(2, ed25519_pair.sign(b"other").encode(), ed25519_pair.public().encode(), 1),
+			];
+			for (chosen_fn, signature, public_key, expected) in test_cases {
+				let mut params = vec![chosen_fn];
+				params.extend_from_slice(&signature);
+				params.extend_from_slice(&public_key);
+				params.extend_from_slice(message);
+				assert_eq!(call(params), expected.encode());
+			}
+		})
+}
+
+#[test]
+fn chain_extension_works() {
+	let (wasm, code_hash) = compile_module::<Test>("chain_extension").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let _ = Balances::deposit_creating(&CHARLIE, 1_234);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				vec![],
+			));
+
+			// The extension reads the balance of CHARLIE and puts it into the scratch buffer.
+			let input = (0u32, CHARLIE).encode();
+			let result = <Module<Test>>::bare_call(ALICE, BOB, 0, GAS_LIMIT, input.clone()).unwrap();
+			assert_eq!(result.status, 0);
+			assert_eq!(result.data, (0u32, 1_234u64).encode());
+
+			// The weight charged by the extension is taken from the gas limit.
+			let result = <Module<Test>>::bare_call(ALICE, BOB, 0, BALANCE_QUERY_WEIGHT, input);
+			assert_matches!(
+				result,
+				Err(ExecError { reason: DispatchError::Other("ran out of gas during contract execution"), .. })
+			);
+
+			// A diverging extension returns its data to the caller of the contract.
+			let mut input = 1u32.encode();
+			input.extend_from_slice(b"DEAD_BEEF");
+			let result = <Module<Test>>::bare_call(ALICE, BOB, 0, GAS_LIMIT, input).unwrap();
+			assert_eq!(result.status, 0);
+			assert_eq!(result.data, b"DEAD_BEEF".to_vec());
+
+			// An error of the extension is returned to the caller of the contract.
+			let result = <Module<Test>>::bare_call(ALICE, BOB, 0, GAS_LIMIT, 2u32.encode());
+			assert_matches!(
+				result,
+				Err(ExecError { reason: DispatchError::Other("unknown chain extension function"), .. })
+			);
+		});
+}
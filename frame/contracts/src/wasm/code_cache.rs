@@ -49,6 +49,13 @@ pub fn save<T: Trait>(
 	Ok(code_hash)
 }
 
+/// Returns whether code with the given code hash is stored.
+///
+/// Unlike `load` this neither decodes nor re-instruments the code.
+pub fn exists<T: Trait>(code_hash: &CodeHash<T>) -> bool {
+	<CodeStorage<T>>::contains_key(code_hash)
+}
+
 /// Load code with the given code hash.
 ///
 /// If the module was instrumented with a lower version of schedule than
@@ -32,10 +32,11 @@ mod code_cache;
 mod prepare;
 mod runtime;
 
-use self::runtime::{to_execution_result, Runtime};
-use self::code_cache::load as load_code;
+use self::runtime::to_execution_result;
+use self::code_cache::{exists as code_exists, load as load_code};
 
 pub use self::code_cache::save as save_code;
+pub(crate) use self::runtime::Runtime;
 
 /// A prepared wasm module ready for execution.
 #[derive(Clone, Encode, Decode)]
@@ -90,6 +91,9 @@ impl<'a, T: Trait> crate::exec::Loader<T> for WasmLoader<'a> {
 			prefab_module,
 		})
 	}
+	fn code_exists(&self, code_hash: &CodeHash<T>) -> bool {
+		code_exists::<T>(code_hash)
+	}
 }
 
 /// Implementation of `Vm` that takes `WasmExecutable` and executes it.
@@ -198,6 +202,12 @@ mod tests {
 		gas_left: u64,
 	}
 
+	#[derive(Debug, PartialEq, Eq)]
+	struct DelegateCallEntry {
+		code_hash: H256,
+		data: Vec<u8>,
+	}
+
 	#[derive(Default)]
 	pub struct MockExt {
 		storage: HashMap<StorageKey, Vec<u8>>,
@@ -205,6 +215,8 @@ mod tests {
 		instantiates: Vec<InstantiateEntry>,
 		terminations: Vec<TerminationEntry>,
 		transfers: Vec<TransferEntry>,
+		delegate_calls: Vec<DelegateCallEntry>,
+		code_hashes: Vec<H256>,
 		dispatches: Vec<DispatchEntry>,
 		restores: Vec<RestoreEntry>,
 		// (topics, data)
@@ -226,7 +238,7 @@ mod tests {
 	impl Ext for MockExt {
 		type T = Test;
 
-		fn get_storage(&self, key: &StorageKey) -> Option<Vec<u8>> {
+		fn get_storage(&mut self, key: &StorageKey) -> Option<Vec<u8>> {
 			self.storage.get(key).cloned()
 		}
 		fn set_storage(&mut self, key: StorageKey, value: Option<Vec<u8>>)
@@ -290,6 +302,22 @@ mod tests {
 			// TODO: Add tests for different call outcomes.
 			Ok(ExecReturnValue { status: STATUS_SUCCESS, data: Vec::new() })
 		}
+		fn delegate_call(
+			&mut self,
+			code_hash: &CodeHash<Test>,
+			_gas_meter: &mut GasMeter<Test>,
+			data: Vec<u8>,
+		) -> ExecResult {
+			self.delegate_calls.push(DelegateCallEntry {
+				code_hash: code_hash.clone(),
+				data,
+			});
+			Ok(ExecReturnValue { status: STATUS_SUCCESS, data: vec![5, 6] })
+		}
+		fn set_code_hash(&mut self, code_hash: CodeHash<Test>) -> Result<(), DispatchError> {
+			self.code_hashes.push(code_hash);
+			Ok(())
+		}
 		fn terminate(
 			&mut self,
 			beneficiary: &u64,
@@ -383,7 +411,7 @@ mod tests {
 	impl Ext for &mut MockExt {
 		type T = <MockExt as Ext>::T;
 
-		fn get_storage(&self, key: &[u8; 32]) -> Option<Vec<u8>> {
+		fn get_storage(&mut self, key: &[u8; 32]) -> Option<Vec<u8>> {
 			(**self).get_storage(key)
 		}
 		fn set_storage(&mut self, key: [u8; 32], value: Option<Vec<u8>>)
@@ -424,6 +452,17 @@ mod tests {
 		) -> ExecResult {
 			(**self).call(to, value, gas_meter, input_data)
 		}
+		fn delegate_call(
+			&mut self,
+			code_hash: &CodeHash<Test>,
+			gas_meter: &mut GasMeter<Test>,
+			input_data: Vec<u8>,
+		) -> ExecResult {
+			(**self).delegate_call(code_hash, gas_meter, input_data)
+		}
+		fn set_code_hash(&mut self, code_hash: CodeHash<Test>) -> Result<(), DispatchError> {
+			(**self).set_code_hash(code_hash)
+		}
 		fn note_dispatch_call(&mut self, call: Call) {
 			(**self).note_dispatch_call(call)
 		}
@@ -561,7 +600,7 @@ mod tests {
 				to: 7,
 				value: 153,
 				data: Vec::new(),
-				gas_left: 9989000000,
+				gas_left: 9999110676,
 			}]
 		);
 	}
@@ -621,11 +660,121 @@ mod tests {
 				to: 9,
 				value: 6,
 				data: vec![1, 2, 3, 4],
-				gas_left: 9985500000,
+				gas_left: 9999525940,
+			}]
+		);
+	}
+
+	const CODE_DELEGATE_CALL: &str = r#"
+(module
+	;; ext_delegate_call(
+	;;    code_hash_ptr: u32,
+	;;    code_hash_len: u32,
+	;;    gas: u64,
+	;;    input_data_ptr: u32,
+	;;    input_data_len: u32
+	;;) -> u32
+	(import "env" "ext_delegate_call" (func $ext_delegate_call (param i32 i32 i64 i32 i32) (result i32)))
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		(call $assert
+			(i32.eqz
+				(call $ext_delegate_call
+					(i32.const 8)  ;; Pointer to `code_hash`
+					(i32.const 32) ;; Length of `code_hash`
+					(i64.const 0)  ;; How much gas to devote for the execution. 0 = all.
+					(i32.const 4)  ;; Pointer to input data buffer address
+					(i32.const 4)  ;; Length of input data buffer
+				)
+			)
+		)
+		;; The output of the delegated code is placed into the scratch buffer.
+		(call $assert
+			(i32.eq
+				(call $ext_scratch_size)
+				(i32.const 2)
+			)
+		)
+	)
+	(func (export "deploy"))
+
+	;; Input data to pass to the delegated code.
+	(data (i32.const 4) "\01\02\03\04")
+	;; Hash of code.
+	(data (i32.const 8)
+		"\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11"
+		"\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11"
+	)
+)
+"#;
+
+	#[test]
+	fn contract_delegate_call() {
+		let mut mock_ext = MockExt::default();
+		let _ = execute(
+			CODE_DELEGATE_CALL,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(
+			&mock_ext.delegate_calls,
+			&[DelegateCallEntry {
+				code_hash: [0x11; 32].into(),
+				data: vec![1, 2, 3, 4],
 			}]
 		);
 	}
 
+	const CODE_SET_CODE_HASH: &str = r#"
+(module
+	;; ext_set_code_hash(
+	;;     code_hash_ptr: u32,
+	;;     code_hash_len: u32,
+	;; )
+	(import "env" "ext_set_code_hash" (func $ext_set_code_hash (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+	(func (export "call")
+		(call $ext_set_code_hash
+			(i32.const 4)  ;; Pointer to `code_hash`
+			(i32.const 32) ;; Length of `code_hash`
+		)
+	)
+	(func (export "deploy"))
+
+	;; Hash of code.
+	(data (i32.const 4)
+		"\22\22\22\22\22\22\22\22\22\22\22\22\22\22\22\22"
+		"\22\22\22\22\22\22\22\22\22\22\22\22\22\22\22\22"
+	)
+)
+"#;
+
+	#[test]
+	fn contract_set_code_hash() {
+		let mut mock_ext = MockExt::default();
+		execute(
+			CODE_SET_CODE_HASH,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(&mock_ext.code_hashes, &[H256::from([0x22; 32])]);
+	}
+
 	const CODE_INSTANTIATE: &str = r#"
 (module
 	;; ext_instantiate(
@@ -683,7 +832,7 @@ mod tests {
 				code_hash: [0x11; 32].into(),
 				endowment: 3,
 				data: vec![1, 2, 3, 4],
-				gas_left: 9973500000,
+				gas_left: 9999525244,
 			}]
 		);
 	}
@@ -724,7 +873,7 @@ mod tests {
 			&mock_ext.terminations,
 			&[TerminationEntry {
 				beneficiary: 0x09,
-				gas_left: 9994500000,
+				gas_left: 9941107988,
 			}]
 		);
 	}
@@ -1609,7 +1758,7 @@ mod tests {
 			vec![0x00, 0x01, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xe5, 0x14, 0x00])
 		]);
 
-		assert_eq!(gas_meter.gas_left(), 9967000000);
+		assert_eq!(gas_meter.gas_left(), 9991848714);
 	}
 
 	const CODE_DEPOSIT_EVENT_MAX_TOPICS: &str = r#"
@@ -20,7 +20,7 @@
 
 use crate::wasm::env_def::ImportSatisfyCheck;
 use crate::wasm::PrefabWasmModule;
-use crate::Schedule;
+use crate::{Gas, Schedule};
 
 use parity_wasm::elements::{self, Internal, External, MemoryType, Type, ValueType};
 use pwasm_utils;
@@ -138,10 +138,26 @@ impl<'a> ContractModule<'a> {
 	}
 
 	fn inject_gas_metering(self) -> Result<Self, &'static str> {
+		let weights = &self.schedule.instruction_weights;
+		let fixed = |cost: Gas| rules::Metering::Fixed(cost.saturated_into());
 		let gas_rules =
 			rules::Set::new(
 				self.schedule.regular_op_cost.clone().saturated_into(),
-				Default::default(),
+				vec![
+					(rules::InstructionType::Const, fixed(weights.constant)),
+					(rules::InstructionType::Local, fixed(weights.local)),
+					(rules::InstructionType::Load, fixed(weights.load)),
+					(rules::InstructionType::Store, fixed(weights.store)),
+					(rules::InstructionType::Add, fixed(weights.add)),
+					(rules::InstructionType::Mul, fixed(weights.mul)),
+					(rules::InstructionType::Div, fixed(weights.div)),
+					(rules::InstructionType::Bit, fixed(weights.bit)),
+					(rules::InstructionType::IntegerComparsion, fixed(weights.integer_comparison)),
+					(rules::InstructionType::Conversion, fixed(weights.conversion)),
+					(rules::InstructionType::ControlFlow, fixed(weights.control_flow)),
+					(rules::InstructionType::CurrentMemory, fixed(weights.current_memory)),
+					(rules::InstructionType::Nop, fixed(weights.nop)),
+				].into_iter().collect(),
 			)
 			.with_grow_cost(self.schedule.grow_mem_cost.clone().saturated_into())
 			.with_forbidden_floats();
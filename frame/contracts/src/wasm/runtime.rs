@@ -16,7 +16,8 @@
 
 //! Environment definition of the wasm smart-contract runtime.
 
-use crate::{Schedule, Trait, CodeHash, BalanceOf};
+use crate::{Schedule, HostFnWeights, Trait, CodeHash, BalanceOf};
+use crate::chain_extension::{ChainExtension, Environment, RetVal};
 use crate::exec::{
 	Ext, ExecResult, ExecError, ExecReturnValue, StorageKey, TopicOf, STATUS_SUCCESS,
 };
@@ -25,13 +26,14 @@ use sp_sandbox;
 use frame_system;
 use sp_std::{prelude::*, mem, convert::TryInto};
 use codec::{Decode, Encode};
-use sp_runtime::traits::{Bounded, SaturatedConversion};
+use sp_runtime::{DispatchError, traits::{Bounded, SaturatedConversion}};
 use sp_io::hashing::{
 	keccak_256,
 	blake2_256,
 	blake2_128,
 	sha2_256,
 };
+use sp_core::{ed25519, sr25519};
 use frame_support::weights::GetDispatchInfo;
 
 /// The value returned from ext_call and ext_instantiate contract external functions if the call or
@@ -51,6 +53,8 @@ enum SpecialTrap {
 	/// Signals that a trap was generated in response to a succesful call to the
 	/// `ext_terminate` host function.
 	Termination,
+	/// Signals that a trap was generated because the chain extension returned an error.
+	ChainExtension(DispatchError),
 }
 
 /// Can only be used for one call.
@@ -80,6 +84,37 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 			special_trap: None,
 		}
 	}
+
+	/// Grants access to the externalities of the current call.
+	pub(crate) fn ext(&mut self) -> &mut E {
+		self.ext
+	}
+
+	/// Charge the gas meter with the weight consumed by a chain extension.
+	///
+	/// Returns `Err(HostError)` if there is not enough gas.
+	pub(crate) fn charge_chain_extension(&mut self, amount: Gas) -> Result<(), sp_sandbox::HostError> {
+		charge_gas(
+			self.gas_meter,
+			self.schedule,
+			&mut self.special_trap,
+			RuntimeToken::ChainExtension(amount),
+		)
+	}
+
+	/// Read designated chunk from the sandbox memory, consuming an appropriate amount of gas.
+	pub(crate) fn read_sandbox_memory(
+		&mut self,
+		ptr: u32,
+		len: u32,
+	) -> Result<Vec<u8>, sp_sandbox::HostError> {
+		read_sandbox_memory(self, ptr, len)
+	}
+
+	/// Replace the contents of the scratch buffer with the given data.
+	pub(crate) fn set_scratch_buf(&mut self, data: Vec<u8>) {
+		self.scratch_buf = data;
+	}
 }
 
 pub(crate) fn to_execution_result<E: Ext>(
@@ -106,6 +141,12 @@ pub(crate) fn to_execution_result<E: Ext>(
 				buffer: runtime.scratch_buf,
 			})
 		},
+		Some(SpecialTrap::ChainExtension(reason)) => {
+			return Err(ExecError {
+				reason,
+				buffer: runtime.scratch_buf,
+			})
+		},
 		None => (),
 	}
 
@@ -159,6 +200,24 @@ pub enum RuntimeToken {
 	/// (topic_count, data_bytes): A buffer of the given size is posted as an event indexed with the
 	/// given number of topics.
 	DepositEvent(u32, u32),
+	/// Weight charged by a chain extension.
+	ChainExtension(Gas),
+	/// The base cost of a host function as given by the schedule.
+	HostFn(Gas),
+	/// Computed a SHA2 256-bit hash over an input of the given number of bytes.
+	HashSha256(u32),
+	/// Computed a KECCAK 256-bit hash over an input of the given number of bytes.
+	HashKeccak256(u32),
+	/// Computed a BLAKE2 256-bit hash over an input of the given number of bytes.
+	HashBlake256(u32),
+	/// Computed a BLAKE2 128-bit hash over an input of the given number of bytes.
+	HashBlake128(u32),
+	/// Recovered the public key from a SECP256k1 ECDSA signature.
+	EcdsaRecovery,
+	/// Verified an sr25519 signature over a message of the given number of bytes.
+	Sr25519Verify(u32),
+	/// Verified an ed25519 signature over a message of the given number of bytes.
+	Ed25519Verify(u32),
 }
 
 impl<T: Trait> Token<T> for RuntimeToken {
@@ -197,6 +256,33 @@ impl<T: Trait> Token<T> for RuntimeToken {
 					)
 			},
 			DispatchWithWeight(gas) => gas.checked_add(metadata.dispatch_base_cost),
+			ChainExtension(amount) => Some(amount),
+			HostFn(amount) => Some(amount),
+			HashSha256(byte_count) => metadata
+				.hash_sha2_256_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.hash_sha2_256_cost)),
+			HashKeccak256(byte_count) => metadata
+				.hash_keccak_256_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.hash_keccak_256_cost)),
+			HashBlake256(byte_count) => metadata
+				.hash_blake2_256_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.hash_blake2_256_cost)),
+			HashBlake128(byte_count) => metadata
+				.hash_blake2_128_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.hash_blake2_128_cost)),
+			EcdsaRecovery => Some(metadata.ecdsa_recover_cost),
+			Sr25519Verify(byte_count) => metadata
+				.sr25519_verify_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.sr25519_verify_cost)),
+			Ed25519Verify(byte_count) => metadata
+				.ed25519_verify_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.ed25519_verify_cost)),
 		};
 
 		value.unwrap_or_else(|| Bounded::max_value())
@@ -221,6 +307,24 @@ fn charge_gas<T: Trait, Tok: Token<T>>(
 	}
 }
 
+/// Charge the gas meter with the base cost of a host function.
+///
+/// `weight` selects the cost of the calling host function from the schedule.
+///
+/// Returns `Err(HostError)` if there is not enough gas.
+fn charge_host_fn<E: Ext>(
+	ctx: &mut Runtime<E>,
+	weight: impl FnOnce(&HostFnWeights) -> Gas,
+) -> Result<(), sp_sandbox::HostError> {
+	let amount = weight(&ctx.schedule.host_fn_weights);
+	charge_gas(
+		ctx.gas_meter,
+		ctx.schedule,
+		&mut ctx.special_trap,
+		RuntimeToken::HostFn(amount),
+	)
+}
+
 /// Read designated chunk from the sandbox memory, consuming an appropriate amount of
 /// gas.
 ///
@@ -380,6 +484,7 @@ define_env!(Env, <E: Ext>,
 	// - If value length exceeds the configured maximum value length of a storage entry.
 	// - Upon trying to set an empty storage entry (value length is 0).
 	ext_set_storage(ctx, key_ptr: u32, value_ptr: u32, value_len: u32) => {
+		charge_host_fn(ctx, |weights| weights.ext_set_storage)?;
 		if value_len > ctx.ext.max_value_size() {
 			// Bail out if value length exceeds the set maximum value size.
 			return Err(sp_sandbox::HostError);
@@ -397,6 +502,7 @@ define_env!(Env, <E: Ext>,
 	//
 	// - `key_ptr`: pointer into the linear memory where the location to clear the value is placed.
 	ext_clear_storage(ctx, key_ptr: u32) => {
+		charge_host_fn(ctx, |weights| weights.ext_clear_storage)?;
 		let mut key: StorageKey = [0; 32];
 		read_sandbox_memory_into_buf(ctx, key_ptr, &mut key)?;
 		ctx.ext.set_storage(key, None).map_err(|_| sp_sandbox::HostError)?;
@@ -410,6 +516,7 @@ define_env!(Env, <E: Ext>,
 	// - key_ptr: pointer into the linear memory where the key
 	//   of the requested value is placed.
 	ext_get_storage(ctx, key_ptr: u32) -> u32 => {
+		charge_host_fn(ctx, |weights| weights.ext_get_storage)?;
 		let mut key: StorageKey = [0; 32];
 		read_sandbox_memory_into_buf(ctx, key_ptr, &mut key)?;
 		if let Some(value) = ctx.ext.get_storage(&key) {
@@ -440,6 +547,7 @@ define_env!(Env, <E: Ext>,
 		value_ptr: u32,
 		value_len: u32
 	) -> u32 => {
+		charge_host_fn(ctx, |weights| weights.ext_transfer)?;
 		let callee: <<E as Ext>::T as frame_system::Trait>::AccountId =
 			read_sandbox_memory_as(ctx, account_ptr, account_len)?;
 		let value: BalanceOf<<E as Ext>::T> =
@@ -490,43 +598,38 @@ define_env!(Env, <E: Ext>,
 		let value: BalanceOf<<E as Ext>::T> =
 			read_sandbox_memory_as(ctx, value_ptr, value_len)?;
 
-		// Read input data into the scratch buffer, then take ownership of it.
-		read_sandbox_memory_into_scratch(ctx, input_data_ptr, input_data_len)?;
-		let input_data = mem::replace(&mut ctx.scratch_buf, Vec::new());
+		nested_call(ctx, gas, input_data_ptr, input_data_len, |ext, nested_meter, input_data| {
+			ext.call(&callee, value, nested_meter, input_data)
+		})
+	},
 
-		let nested_gas_limit = if gas == 0 {
-			ctx.gas_meter.gas_left()
-		} else {
-			gas.saturated_into()
-		};
-		let ext = &mut ctx.ext;
-		let call_outcome = ctx.gas_meter.with_nested(nested_gas_limit, |nested_meter| {
-			match nested_meter {
-				Some(nested_meter) => {
-					ext.call(
-						&callee,
-						value,
-						nested_meter,
-						input_data,
-					)
-					.map_err(|err| err.buffer)
-				}
-				// there is not enough gas to allocate for the nested call.
-				None => Err(input_data),
-			}
-		});
+	// Execute the code specified by a code hash in the context of the calling contract.
+	//
+	// The code operates on the storage and the balance of the calling contract. The values
+	// returned by `ext_caller` and `ext_value_transferred` are the same as for the calling
+	// contract and no value is transferred. The return value and the scratch buffer are set in
+	// the same way as by `ext_call`, including the reverting of state changes on failure.
+	//
+	// - code_hash_ptr: a pointer to the buffer that contains the code hash of the code to execute.
+	//   Should be decodable as a `T::Hash`. Traps otherwise.
+	// - code_hash_len: length of the code hash buffer.
+	// - gas: how much gas to devote to the execution.
+	// - input_data_ptr: a pointer to a buffer to be used as input data to the executed code.
+	// - input_data_len: length of the input data buffer.
+	ext_delegate_call(
+		ctx,
+		code_hash_ptr: u32,
+		code_hash_len: u32,
+		gas: u64,
+		input_data_ptr: u32,
+		input_data_len: u32
+	) -> u32 => {
+		let code_hash: CodeHash<<E as Ext>::T> =
+			read_sandbox_memory_as(ctx, code_hash_ptr, code_hash_len)?;
 
-		match call_outcome {
-			Ok(output) => {
-				ctx.scratch_buf = output.data;
-				Ok(output.status.into())
-			},
-			Err(buffer) => {
-				ctx.scratch_buf = buffer;
-				ctx.scratch_buf.clear();
-				Ok(TRAP_RETURN_CODE)
-			},
-		}
+		nested_call(ctx, gas, input_data_ptr, input_data_len, |ext, nested_meter, input_data| {
+			ext.delegate_call(&code_hash, nested_meter, input_data)
+		})
 	},
 
 	// Instantiate a contract with the specified code hash.
@@ -638,6 +741,7 @@ define_env!(Env, <E: Ext>,
 		beneficiary_ptr: u32,
 		beneficiary_len: u32
 	) => {
+		charge_host_fn(ctx, |weights| weights.ext_terminate)?;
 		let beneficiary: <<E as Ext>::T as frame_system::Trait>::AccountId =
 			read_sandbox_memory_as(ctx, beneficiary_ptr, beneficiary_len)?;
 
@@ -652,6 +756,7 @@ define_env!(Env, <E: Ext>,
 	//
 	// This is the only way to return a data buffer to the caller.
 	ext_return(ctx, data_ptr: u32, data_len: u32) => {
+		charge_host_fn(ctx, |weights| weights.ext_return)?;
 		charge_gas(
 			ctx.gas_meter,
 			ctx.schedule,
@@ -676,6 +781,7 @@ define_env!(Env, <E: Ext>,
 	// extrinsic will be returned. Otherwise, if this call is initiated by another contract then the
 	// address of the contract will be returned.
 	ext_caller(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_caller)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.caller().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -683,6 +789,7 @@ define_env!(Env, <E: Ext>,
 
 	// Stores the address of the current contract into the scratch buffer.
 	ext_address(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_address)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.address().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -692,6 +799,7 @@ define_env!(Env, <E: Ext>,
 	//
 	// The data is encoded as T::Balance. The current contents of the scratch buffer are overwritten.
 	ext_gas_price(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_gas_price)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.get_weight_price().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -701,6 +809,7 @@ define_env!(Env, <E: Ext>,
 	//
 	// The data is encoded as Gas. The current contents of the scratch buffer are overwritten.
 	ext_gas_left(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_gas_left)?;
 		ctx.scratch_buf.clear();
 		ctx.gas_meter.gas_left().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -710,6 +819,7 @@ define_env!(Env, <E: Ext>,
 	//
 	// The data is encoded as T::Balance. The current contents of the scratch buffer are overwritten.
 	ext_balance(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_balance)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.balance().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -719,6 +829,7 @@ define_env!(Env, <E: Ext>,
 	//
 	// The data is encoded as T::Balance. The current contents of the scratch buffer are overwritten.
 	ext_value_transferred(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_value_transferred)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.value_transferred().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -730,6 +841,7 @@ define_env!(Env, <E: Ext>,
 	// The data is encoded as T::Hash. The current contents of the scratch buffer are
 	// overwritten.
 	ext_random(ctx, subject_ptr: u32, subject_len: u32) => {
+		charge_host_fn(ctx, |weights| weights.ext_random)?;
 		// The length of a subject can't exceed `max_subject_len`.
 		if subject_len > ctx.schedule.max_subject_len {
 			return Err(sp_sandbox::HostError);
@@ -743,6 +855,7 @@ define_env!(Env, <E: Ext>,
 
 	// Load the latest block timestamp into the scratch buffer
 	ext_now(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_now)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.now().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -753,6 +866,7 @@ define_env!(Env, <E: Ext>,
 	// The data is encoded as T::Balance. The current contents of the scratch buffer are
 	// overwritten.
 	ext_minimum_balance(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_minimum_balance)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.minimum_balance().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -770,6 +884,7 @@ define_env!(Env, <E: Ext>,
 	// below the sum of existential deposit and the tombstone deposit. The sum
 	// is commonly referred as subsistence threshold in code.
 	ext_tombstone_deposit(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_tombstone_deposit)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.tombstone_deposit().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -830,6 +945,7 @@ define_env!(Env, <E: Ext>,
 		delta_ptr: u32,
 		delta_count: u32
 	) => {
+		charge_host_fn(ctx, |weights| weights.ext_restore_to)?;
 		let dest: <<E as Ext>::T as frame_system::Trait>::AccountId =
 			read_sandbox_memory_as(ctx, dest_ptr, dest_len)?;
 		let code_hash: CodeHash<<E as Ext>::T> =
@@ -871,6 +987,7 @@ define_env!(Env, <E: Ext>,
 	//
 	// For more details on the scratch buffer see `ext_scratch_read`.
 	ext_scratch_size(ctx) -> u32 => {
+		charge_host_fn(ctx, |weights| weights.ext_scratch_size)?;
 		Ok(ctx.scratch_buf.len() as u32)
 	},
 
@@ -881,6 +998,7 @@ define_env!(Env, <E: Ext>,
 	// execution, the scratch buffer is filled with the input data. Whenever a contract calls
 	// function that uses the scratch buffer the contents of the scratch buffer are overwritten.
 	ext_scratch_read(ctx, dest_ptr: u32, offset: u32, len: u32) => {
+		charge_host_fn(ctx, |weights| weights.ext_scratch_read)?;
 		let offset = offset as usize;
 		if offset > ctx.scratch_buf.len() {
 			// Offset can't be larger than scratch buffer length.
@@ -912,6 +1030,7 @@ define_env!(Env, <E: Ext>,
 	//
 	// This should be used before exiting a call or instantiation in order to set the return data.
 	ext_scratch_write(ctx, src_ptr: u32, len: u32) => {
+		charge_host_fn(ctx, |weights| weights.ext_scratch_write)?;
 		read_sandbox_memory_into_scratch(ctx, src_ptr, len)
 	},
 
@@ -958,6 +1077,7 @@ define_env!(Env, <E: Ext>,
 	//   Should be decodable as a `T::Balance`. Traps otherwise.
 	// - value_len: length of the value buffer.
 	ext_set_rent_allowance(ctx, value_ptr: u32, value_len: u32) => {
+		charge_host_fn(ctx, |weights| weights.ext_set_rent_allowance)?;
 		let value: BalanceOf<<E as Ext>::T> =
 			read_sandbox_memory_as(ctx, value_ptr, value_len)?;
 		ctx.ext.set_rent_allowance(value);
@@ -965,10 +1085,29 @@ define_env!(Env, <E: Ext>,
 		Ok(())
 	},
 
+	// Replace the code of the calling contract with the code specified by a code hash.
+	//
+	// The storage and the balance of the contract are kept. The new code is executed starting
+	// with the next call to the contract, the current execution continues with the old code.
+	// Traps if no code is stored under the given hash.
+	//
+	// - code_hash_ptr: a pointer to the buffer that contains the new code hash.
+	//   Should be decodable as a `T::Hash`. Traps otherwise.
+	// - code_hash_len: length of the code hash buffer.
+	ext_set_code_hash(ctx, code_hash_ptr: u32, code_hash_len: u32) => {
+		charge_host_fn(ctx, |weights| weights.ext_set_code_hash)?;
+		let code_hash: CodeHash<<E as Ext>::T> =
+			read_sandbox_memory_as(ctx, code_hash_ptr, code_hash_len)?;
+		ctx.ext.set_code_hash(code_hash).map_err(|_| sp_sandbox::HostError)?;
+
+		Ok(())
+	},
+
 	// Stores the rent allowance into the scratch buffer.
 	//
 	// The data is encoded as T::Balance. The current contents of the scratch buffer are overwritten.
 	ext_rent_allowance(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_rent_allowance)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.rent_allowance().encode_to(&mut ctx.scratch_buf);
 
@@ -979,6 +1118,7 @@ define_env!(Env, <E: Ext>,
 	// Only available on `--dev` chains.
 	// This function may be removed at any time, superseded by a more general contract debugging feature.
 	ext_println(ctx, str_ptr: u32, str_len: u32) => {
+		charge_host_fn(ctx, |weights| weights.ext_println)?;
 		let data = read_sandbox_memory(ctx, str_ptr, str_len)?;
 		if let Ok(utf8) = core::str::from_utf8(&data) {
 			sp_runtime::print(utf8);
@@ -988,6 +1128,7 @@ define_env!(Env, <E: Ext>,
 
 	// Stores the current block number of the current contract into the scratch buffer.
 	ext_block_number(ctx) => {
+		charge_host_fn(ctx, |weights| weights.ext_block_number)?;
 		ctx.scratch_buf.clear();
 		ctx.ext.block_number().encode_to(&mut ctx.scratch_buf);
 		Ok(())
@@ -1000,6 +1141,7 @@ define_env!(Env, <E: Ext>,
 	// - key_ptr: the pointer into the linear memory where the requested value is placed.
 	// - key_len: the length of the key in bytes.
 	ext_get_runtime_storage(ctx, key_ptr: u32, key_len: u32) -> u32 => {
+		charge_host_fn(ctx, |weights| weights.ext_get_runtime_storage)?;
 		// Steal the scratch buffer so that we hopefully save an allocation for the `key_buf`.
 		read_sandbox_memory_into_scratch(ctx, key_ptr, key_len)?;
 		let key_buf = mem::replace(&mut ctx.scratch_buf, Vec::new());
@@ -1040,6 +1182,12 @@ define_env!(Env, <E: Ext>,
 	//                 data is placed. The function will write the result
 	//                 directly into this buffer.
 	ext_hash_sha2_256(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		charge_gas(
+			ctx.gas_meter,
+			ctx.schedule,
+			&mut ctx.special_trap,
+			RuntimeToken::HashSha256(input_len)
+		)?;
 		compute_hash_on_intermediate_buffer(ctx, sha2_256, input_ptr, input_len, output_ptr)
 	},
 
@@ -1064,6 +1212,12 @@ define_env!(Env, <E: Ext>,
 	//                 data is placed. The function will write the result
 	//                 directly into this buffer.
 	ext_hash_keccak_256(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		charge_gas(
+			ctx.gas_meter,
+			ctx.schedule,
+			&mut ctx.special_trap,
+			RuntimeToken::HashKeccak256(input_len)
+		)?;
 		compute_hash_on_intermediate_buffer(ctx, keccak_256, input_ptr, input_len, output_ptr)
 	},
 
@@ -1088,6 +1242,12 @@ define_env!(Env, <E: Ext>,
 	//                 data is placed. The function will write the result
 	//                 directly into this buffer.
 	ext_hash_blake2_256(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		charge_gas(
+			ctx.gas_meter,
+			ctx.schedule,
+			&mut ctx.special_trap,
+			RuntimeToken::HashBlake256(input_len)
+		)?;
 		compute_hash_on_intermediate_buffer(ctx, blake2_256, input_ptr, input_len, output_ptr)
 	},
 
@@ -1112,10 +1272,226 @@ define_env!(Env, <E: Ext>,
 	//                 data is placed. The function will write the result
 	//                 directly into this buffer.
 	ext_hash_blake2_128(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		charge_gas(
+			ctx.gas_meter,
+			ctx.schedule,
+			&mut ctx.special_trap,
+			RuntimeToken::HashBlake128(input_len)
+		)?;
 		compute_hash_on_intermediate_buffer(ctx, blake2_128, input_ptr, input_len, output_ptr)
 	},
+
+	// Recovers the public key from the given SECP256k1 ECDSA signature and message hash.
+	//
+	// Returns 0 and writes the 33 bytes of the compressed public key directly into the given
+	// output buffer if the recovery succeeds. Otherwise returns 1 and leaves the output buffer
+	// untouched.
+	//
+	// # Parameters
+	//
+	// - `signature_ptr`: the pointer into the linear memory where the 65 bytes of the signature
+	//                    are placed. The signature is expected in RSV format, with V being
+	//                    either `0/1` or `27/28`.
+	// - `message_hash_ptr`: the pointer into the linear memory where the 32 bytes of the
+	//                       message hash are placed.
+	// - `output_ptr`: the pointer into the linear memory where the output
+	//                 data is placed. The function will write the result
+	//                 directly into this buffer.
+	ext_ecdsa_recover(ctx, signature_ptr: u32, message_hash_ptr: u32, output_ptr: u32) -> u32 => {
+		charge_gas(
+			ctx.gas_meter,
+			ctx.schedule,
+			&mut ctx.special_trap,
+			RuntimeToken::EcdsaRecovery
+		)?;
+
+		let mut signature: [u8; 65] = [0; 65];
+		read_sandbox_memory_into_buf(ctx, signature_ptr, &mut signature)?;
+		let mut message_hash: [u8; 32] = [0; 32];
+		read_sandbox_memory_into_buf(ctx, message_hash_ptr, &mut message_hash)?;
+
+		match sp_io::crypto::secp256k1_ecdsa_recover_compressed(&signature, &message_hash) {
+			Ok(public_key) => {
+				write_sandbox_memory(
+					ctx.schedule,
+					&mut ctx.special_trap,
+					ctx.gas_meter,
+					&ctx.memory,
+					output_ptr,
+					&public_key,
+				)?;
+				Ok(0)
+			},
+			Err(_) => Ok(1),
+		}
+	},
+
+	// Verifies an sr25519 signature of the given message.
+	//
+	// Returns 0 if the signature is valid and 1 otherwise.
+	//
+	// # Parameters
+	//
+	// - `signature_ptr`: the pointer into the linear memory where the 64 bytes of the signature
+	//                    are placed.
+	// - `public_key_ptr`: the pointer into the linear memory where the 32 bytes of the public
+	//                     key are placed.
+	// - `message_ptr`: the pointer into the linear memory where the message is placed.
+	// - `message_len`: the length of the message in bytes.
+	ext_sr25519_verify(
+		ctx,
+		signature_ptr: u32,
+		public_key_ptr: u32,
+		message_ptr: u32,
+		message_len: u32
+	) -> u32 => {
+		charge_gas(
+			ctx.gas_meter,
+			ctx.schedule,
+			&mut ctx.special_trap,
+			RuntimeToken::Sr25519Verify(message_len)
+		)?;
+
+		let mut signature = sr25519::Signature::from_raw([0; 64]);
+		read_sandbox_memory_into_buf(ctx, signature_ptr, signature.as_mut())?;
+		let mut public_key = sr25519::Public::from_raw([0; 32]);
+		read_sandbox_memory_into_buf(ctx, public_key_ptr, public_key.as_mut())?;
+		let message = read_sandbox_memory(ctx, message_ptr, message_len)?;
+
+		// The result must be known right away, so the verification can't be batched.
+		if sp_io::crypto::sr25519_verify_unbatched(&signature, &message, &public_key) {
+			Ok(0)
+		} else {
+			Ok(1)
+		}
+	},
+
+	// Verifies an ed25519 signature of the given message.
+	//
+	// Returns 0 if the signature is valid and 1 otherwise.
+	//
+	// # Parameters
+	//
+	// - `signature_ptr`: the pointer into the linear memory where the 64 bytes of the signature
+	//                    are placed.
+	// - `public_key_ptr`: the pointer into the linear memory where the 32 bytes of the public
+	//                     key are placed.
+	// - `message_ptr`: the pointer into the linear memory where the message is placed.
+	// - `message_len`: the length of the message in bytes.
+	ext_ed25519_verify(
+		ctx,
+		signature_ptr: u32,
+		public_key_ptr: u32,
+		message_ptr: u32,
+		message_len: u32
+	) -> u32 => {
+		charge_gas(
+			ctx.gas_meter,
+			ctx.schedule,
+			&mut ctx.special_trap,
+			RuntimeToken::Ed25519Verify(message_len)
+		)?;
+
+		let mut signature = ed25519::Signature::from_raw([0; 64]);
+		read_sandbox_memory_into_buf(ctx, signature_ptr, signature.as_mut())?;
+		let mut public_key = ed25519::Public::from_raw([0; 32]);
+		read_sandbox_memory_into_buf(ctx, public_key_ptr, public_key.as_mut())?;
+		let message = read_sandbox_memory(ctx, message_ptr, message_len)?;
+
+		// The result must be known right away, so the verification can't be batched.
+		if sp_io::crypto::ed25519_verify_unbatched(&signature, &message, &public_key) {
+			Ok(0)
+		} else {
+			Ok(1)
+		}
+	},
+
+	// Call into the chain extension provided by the chain if any.
+	//
+	// Handling of the input buffer is up to the specific chain extension and so is the
+	// return value. Any caller of this function must therefore coordinate with the chain
+	// that it targets.
+	//
+	// The chain extension usually puts its output into the scratch buffer. Its former
+	// contents are left untouched otherwise.
+	//
+	// # Parameters
+	//
+	// - `func_id`: the id of the function within the chain extension to call.
+	// - `input_ptr`: the pointer into the linear memory where the input data is placed.
+	// - `input_len`: the length of the input data in bytes.
+	//
+	// # Note
+	//
+	// If no chain extension exists the contract will trap.
+	ext_call_chain_extension(ctx, func_id: u32, input_ptr: u32, input_len: u32) -> u32 => {
+		charge_host_fn(ctx, |weights| weights.ext_call_chain_extension)?;
+		if !<<E::T as Trait>::ChainExtension as ChainExtension<E::T>>::enabled() {
+			return Err(sp_sandbox::HostError);
+		}
+		let env = Environment::new(ctx, input_ptr, input_len);
+		match <<E::T as Trait>::ChainExtension as ChainExtension<E::T>>::call(func_id, env) {
+			Ok(RetVal::Converging(val)) => Ok(val),
+			Ok(RetVal::Diverging(data)) => {
+				ctx.special_trap = Some(SpecialTrap::Return(data));
+				Err(sp_sandbox::HostError)
+			},
+			Err(err) => {
+				// Running out of gas takes precedence over the error reported by the extension.
+				if ctx.special_trap.is_none() {
+					ctx.special_trap = Some(SpecialTrap::ChainExtension(err));
+				}
+				Err(sp_sandbox::HostError)
+			},
+		}
+	},
 );
 
+/// Executes `call` with the input data read from the sandbox memory and a nested gas meter.
+///
+/// The nested gas meter is limited to `gas` or to all the gas left if `gas` is 0. This is the
+/// shared implementation of `ext_call` and `ext_delegate_call`: on completion the scratch buffer
+/// is filled with the output data and the status code is returned. If the execution traps or
+/// there is not enough gas for the nested meter, the scratch buffer is cleared and
+/// `TRAP_RETURN_CODE` is returned.
+fn nested_call<E: Ext>(
+	ctx: &mut Runtime<E>,
+	gas: u64,
+	input_data_ptr: u32,
+	input_data_len: u32,
+	call: impl FnOnce(&mut E, &mut GasMeter<E::T>, Vec<u8>) -> ExecResult,
+) -> Result<u32, sp_sandbox::HostError> {
+	// Read input data into the scratch buffer, then take ownership of it.
+	read_sandbox_memory_into_scratch(ctx, input_data_ptr, input_data_len)?;
+	let input_data = mem::replace(&mut ctx.scratch_buf, Vec::new());
+
+	let nested_gas_limit = if gas == 0 {
+		ctx.gas_meter.gas_left()
+	} else {
+		gas.saturated_into()
+	};
+	let ext = &mut *ctx.ext;
+	let call_outcome = ctx.gas_meter.with_nested(nested_gas_limit, |nested_meter| {
+		match nested_meter {
+			Some(nested_meter) => call(ext, nested_meter, input_data).map_err(|err| err.buffer),
+			// there is not enough gas to allocate for the nested call.
+			None => Err(input_data),
+		}
+	});
+
+	match call_outcome {
+		Ok(output) => {
+			ctx.scratch_buf = output.data;
+			Ok(output.status.into())
+		},
+		Err(buffer) => {
+			ctx.scratch_buf = buffer;
+			ctx.scratch_buf.clear();
+			Ok(TRAP_RETURN_CODE)
+		},
+	}
+}
+
 /// Computes the given hash function on the scratch buffer.
 ///
 /// Reads from the sandboxed input buffer into an intermediate buffer.
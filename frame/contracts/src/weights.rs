@@ -0,0 +1,368 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate. If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights of the contracts pallet.
+//!
+//! Every function is the model fitted by the median slopes analysis to the results of the
+//! benchmark with the same name, run with 20 steps and 10 repeats. A host function or
+//! instruction benchmark measures whole batches, see [`API_BENCHMARK_BATCH_SIZE`] and
+//! [`INSTR_BENCHMARK_BATCH_SIZE`].
+//!
+//! The defaults of [`Schedule`](crate::Schedule) are derived from these functions. Regenerate
+//! this file whenever the benchmarks or the code they measure change.
+
+// Not every benchmark is used to derive a weight.
+#![allow(dead_code)]
+
+use frame_support::weights::Weight;
+
+/// How many host function calls are executed in a single batch.
+pub const API_BENCHMARK_BATCH_SIZE: u32 = 100;
+
+/// How many instructions are executed in a single batch.
+pub const INSTR_BENCHMARK_BATCH_SIZE: u32 = 1_000;
+
+pub fn update_schedule(v: u32) -> Weight {
+	(29_080_000 as Weight)
+		.saturating_add((0 as Weight).saturating_mul(v as Weight))
+}
+
+pub fn put_code(n: u32) -> Weight {
+	(0 as Weight)
+		.saturating_add((81_130_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn instantiate(n: u32) -> Weight {
+	(134_700_000 as Weight)
+		.saturating_add((2_961_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn call(n: u32) -> Weight {
+	(118_300_000 as Weight)
+		.saturating_add((0 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn claim_surcharge(c: u32) -> Weight {
+	(11_380_000 as Weight)
+		.saturating_add((34_000 as Weight).saturating_mul(c as Weight))
+}
+
+pub fn settle(c: u32, p: u32) -> Weight {
+	(2_118_000 as Weight)
+		.saturating_add((58_370_000 as Weight).saturating_mul(c as Weight))
+		.saturating_add((44_690_000 as Weight).saturating_mul(p as Weight))
+}
+
+pub fn ext_caller(r: u32) -> Weight {
+	(108_400_000 as Weight)
+		.saturating_add((55_470_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_address(r: u32) -> Weight {
+	(97_340_000 as Weight)
+		.saturating_add((54_690_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_gas_price(r: u32) -> Weight {
+	(97_410_000 as Weight)
+		.saturating_add((202_400_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_gas_left(r: u32) -> Weight {
+	(89_610_000 as Weight)
+		.saturating_add((55_120_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_balance(r: u32) -> Weight {
+	(96_690_000 as Weight)
+		.saturating_add((394_700_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_value_transferred(r: u32) -> Weight {
+	(100_700_000 as Weight)
+		.saturating_add((55_310_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_minimum_balance(r: u32) -> Weight {
+	(92_830_000 as Weight)
+		.saturating_add((54_960_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_tombstone_deposit(r: u32) -> Weight {
+	(89_600_000 as Weight)
+		.saturating_add((55_020_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_rent_allowance(r: u32) -> Weight {
+	(139_500_000 as Weight)
+		.saturating_add((338_100_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_block_number(r: u32) -> Weight {
+	(125_900_000 as Weight)
+		.saturating_add((47_090_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_now(r: u32) -> Weight {
+	(125_700_000 as Weight)
+		.saturating_add((52_510_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_random(r: u32) -> Weight {
+	(90_830_000 as Weight)
+		.saturating_add((478_300_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_set_storage(r: u32) -> Weight {
+	(0 as Weight)
+		.saturating_add((1_243_000_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_clear_storage(r: u32) -> Weight {
+	(75_760_000 as Weight)
+		.saturating_add((445_500_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_get_storage(r: u32) -> Weight {
+	(262_500_000 as Weight)
+		.saturating_add((188_500_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_transfer(r: u32) -> Weight {
+	(1_583_000_000 as Weight)
+		.saturating_add((2_010_000_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_call(r: u32) -> Weight {
+	(679_300_000 as Weight)
+		.saturating_add((6_188_000_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_delegate_call(r: u32) -> Weight {
+	(5_272_000_000 as Weight)
+		.saturating_add((4_772_000_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_instantiate(r: u32) -> Weight {
+	(0 as Weight)
+		.saturating_add((9_244_000_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_terminate(r: u32) -> Weight {
+	(94_460_000 as Weight)
+		.saturating_add((78_270_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_return(r: u32) -> Weight {
+	(81_950_000 as Weight)
+		.saturating_add((8_714_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_scratch_size(r: u32) -> Weight {
+	(88_870_000 as Weight)
+		.saturating_add((58_080_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_scratch_read(r: u32) -> Weight {
+	(107_200_000 as Weight)
+		.saturating_add((120_600_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_scratch_read_per_kb(n: u32) -> Weight {
+	(195_400_000 as Weight)
+		.saturating_add((4_333_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn ext_scratch_write(r: u32) -> Weight {
+	(94_050_000 as Weight)
+		.saturating_add((111_400_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_scratch_write_per_kb(n: u32) -> Weight {
+	(203_400_000 as Weight)
+		.saturating_add((3_008_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn ext_deposit_event(r: u32) -> Weight {
+	(75_640_000 as Weight)
+		.saturating_add((623_700_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_deposit_event_per_topic_and_kb(t: u32, n: u32) -> Weight {
+	(736_600_000 as Weight)
+		.saturating_add((164_800_000 as Weight).saturating_mul(t as Weight))
+		.saturating_add((39_380_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn ext_set_rent_allowance(r: u32) -> Weight {
+	(121_800_000 as Weight)
+		.saturating_add((121_600_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_set_code_hash(r: u32) -> Weight {
+	(91_710_000 as Weight)
+		.saturating_add((969_600_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_get_runtime_storage(r: u32) -> Weight {
+	(98_710_000 as Weight)
+		.saturating_add((263_800_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_println(r: u32) -> Weight {
+	(95_230_000 as Weight)
+		.saturating_add((71_460_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_hash_sha2_256(r: u32) -> Weight {
+	(128_400_000 as Weight)
+		.saturating_add((207_800_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_hash_sha2_256_per_kb(n: u32) -> Weight {
+	(304_500_000 as Weight)
+		.saturating_add((724_900_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn ext_hash_keccak_256(r: u32) -> Weight {
+	(91_570_000 as Weight)
+		.saturating_add((264_400_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_hash_keccak_256_per_kb(n: u32) -> Weight {
+	(431_600_000 as Weight)
+		.saturating_add((646_400_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn ext_hash_blake2_256(r: u32) -> Weight {
+	(119_400_000 as Weight)
+		.saturating_add((179_400_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_hash_blake2_256_per_kb(n: u32) -> Weight {
+	(288_900_000 as Weight)
+		.saturating_add((213_100_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn ext_hash_blake2_128(r: u32) -> Weight {
+	(112_900_000 as Weight)
+		.saturating_add((184_300_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_hash_blake2_128_per_kb(n: u32) -> Weight {
+	(282_500_000 as Weight)
+		.saturating_add((208_100_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn ext_ecdsa_recover(r: u32) -> Weight {
+	(9_988_000_000 as Weight)
+		.saturating_add((26_490_000_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_sr25519_verify(r: u32) -> Weight {
+	(5_504_000_000 as Weight)
+		.saturating_add((10_330_000_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_sr25519_verify_per_kb(n: u32) -> Weight {
+	(10_950_000_000 as Weight)
+		.saturating_add((656_100_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn ext_ed25519_verify(r: u32) -> Weight {
+	(103_100_000 as Weight)
+		.saturating_add((10_250_000_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn ext_ed25519_verify_per_kb(n: u32) -> Weight {
+	(10_870_000_000 as Weight)
+		.saturating_add((477_800_000 as Weight).saturating_mul(n as Weight))
+}
+
+pub fn instr_const(r: u32) -> Weight {
+	(634_600_000 as Weight)
+		.saturating_add((98_690_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_local(r: u32) -> Weight {
+	(94_480_000 as Weight)
+		.saturating_add((137_100_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_global(r: u32) -> Weight {
+	(99_750_000 as Weight)
+		.saturating_add((128_500_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_load(r: u32) -> Weight {
+	(110_300_000 as Weight)
+		.saturating_add((222_500_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_store(r: u32) -> Weight {
+	(148_300_000 as Weight)
+		.saturating_add((237_200_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_add(r: u32) -> Weight {
+	(447_900_000 as Weight)
+		.saturating_add((227_500_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_mul(r: u32) -> Weight {
+	(549_100_000 as Weight)
+		.saturating_add((251_100_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_div(r: u32) -> Weight {
+	(407_600_000 as Weight)
+		.saturating_add((246_600_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_bit(r: u32) -> Weight {
+	(0 as Weight)
+		.saturating_add((268_100_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_integer_comparison(r: u32) -> Weight {
+	(0 as Weight)
+		.saturating_add((283_100_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_conversion(r: u32) -> Weight {
+	(114_500_000 as Weight)
+		.saturating_add((206_200_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_control_flow(r: u32) -> Weight {
+	(130_600_000 as Weight)
+		.saturating_add((148_400_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_current_memory(r: u32) -> Weight {
+	(185_100_000 as Weight)
+		.saturating_add((142_000_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_nop(r: u32) -> Weight {
+	(124_100_000 as Weight)
+		.saturating_add((46_440_000 as Weight).saturating_mul(r as Weight))
+}
+
+pub fn instr_grow_memory(r: u32) -> Weight {
+	(0 as Weight)
+		.saturating_add((367_200_000 as Weight).saturating_mul(r as Weight))
+}
@@ -0,0 +1,860 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Multi phase, offchain election provider pallet.
+//!
+//! This pallet implements [`ElectionProvider`]: it computes the outcome of an NPoS election in
+//! multiple phases, mostly off-chain, and hands the result to whoever calls
+//! [`ElectionProvider::elect`]. The data of the election is obtained from
+//! [`Trait::DataProvider`], an implementor of [`ElectionDataProvider`].
+//!
+//! ## Phases
+//!
+//! At each block, [`ElectionDataProvider::next_election_prediction`] is used to estimate the time
+//! remaining to the next call to [`ElectionProvider::elect`]. Based on this, a phase is chosen:
+//!
+//! ```ignore
+//!                                                                    elect()
+//!                 +   <--T::SignedPhase-->  +  <--T::UnsignedPhase-->   +
+//!   +-------------------------------------------------------------------+
+//!    Phase::Off   +       Phase::Signed     +      Phase::Unsigned      +
+//! ```
+//!
+//! Note that the unsigned phase starts [`Trait::UnsignedPhase`] blocks before the predicted
+//! election, but only ends once [`ElectionProvider::elect`] is actually called. At the beginning
+//! of the first phase, a snapshot of the election data is stored on-chain. All solutions are
+//! expressed as indices into this snapshot.
+//!
+//! ### Signed Phase
+//!
+//! In the signed phase, any account can submit a solution (of type [`RawSolution`]) via
+//! [`Call::submit`]. A deposit is reserved, based on the size of the solution. A maximum of
+//! [`Trait::SignedMaxSubmissions`] solutions are queued, sorted by their claimed score. If the
+//! queue is full, a new solution is only accepted if it is better than the weakest queued one,
+//! which is then discarded and gets its deposit back.
+//!
+//! Solutions are not checked upon submission. Once the signed phase ends, they are examined from
+//! the best to the worst claimed score. Each solution undergoes the expensive
+//! [`Module::feasibility_check`], which ensures that the claimed score is correct, among other
+//! things. The first solution that passes the check is accepted and its submitter is rewarded with
+//! [`Trait::SignedRewardBase`]. Every solution that failed before it is slashed of its deposit.
+//! The remaining solutions are discarded without being checked, and their deposit is returned.
+//!
+//! ### Unsigned Phase
+//!
+//! The unsigned phase always follows the signed phase. In this phase, validator nodes which run an
+//! offchain worker compute a solution with [`Trait::Solver`] and submit it as an unsigned
+//! transaction via [`Call::submit_unsigned`]. Such a transaction is only accepted into the pool if
+//! it comes from the local node, thus it can only be included by the block author. Each unsigned
+//! solution is checked right away and is only accepted if it is better than the one that is
+//! already queued, according to [`is_score_better`].
+//!
+//! ### Fallback
+//!
+//! If no solution is queued by the time [`ElectionProvider::elect`] is called, the election is
+//! computed on-chain by [`onchain::OnChainSequentialPhragmen`], over the live data of
+//! [`Trait::DataProvider`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub mod signed;
+pub mod unsigned;
+
+use codec::{Encode, Decode, HasCompact};
+use frame_support::{
+	decl_module, decl_event, decl_storage, decl_error, ensure,
+	dispatch::DispatchResult,
+	traits::{Currency, ReservableCurrency, OnUnbalanced, Get},
+	weights::{Weight, constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS}},
+};
+use frame_system::{self as system, ensure_signed, ensure_none, offchain::SendTransactionTypes};
+use sp_election_providers::{ElectionDataProvider, ElectionProvider, onchain};
+use sp_phragmen::{
+	assignment_ratio_to_staked, build_support_map, evaluate_support, generate_compact_solution_type,
+	is_score_better, Assignment, NposSolver, PhragmenScore, Supports, VoteWeight,
+};
+use sp_runtime::{
+	PerU16, Perbill, RuntimeDebug, DispatchError,
+	traits::{Zero, SaturatedConversion},
+	transaction_validity::{
+		TransactionValidityError, TransactionValidity, ValidTransaction, InvalidTransaction,
+		TransactionSource, TransactionPriority,
+	},
+};
+use sp_std::{prelude::*, collections::{btree_map::BTreeMap, btree_set::BTreeSet}, convert::TryInto};
+
+pub use sp_election_providers::ElectionCompute;
+
+pub(crate) const LOG_TARGET: &'static str = "election-provider";
+
+// syntactic sugar for logging.
+#[macro_export]
+macro_rules! log {
+	($level:tt, $patter:expr $(, $values:expr)* $(,)?) => {
+		frame_support::debug::$level!(
+			target: crate::LOG_TARGET,
+			$patter $(, $values)*
+		)
+	};
+}
+
+/// Data type used to index voters in the compact type.
+pub type VoterIndex = u32;
+
+/// Data type used to index targets in the compact type.
+pub type TargetIndex = u16;
+
+/// Accuracy used for the solutions of the signed and unsigned phases. This better be small.
+pub type SolutionAccuracy = PerU16;
+
+/// Accuracy used for the on-chain fallback.
+pub type OnChainAccuracy = Perbill;
+
+// Note: Maximum number of votes per voter is set here -- 16.
+generate_compact_solution_type!(pub GenericCompactAssignments, 16);
+
+/// The compact type for election solutions.
+pub type CompactAssignments =
+	GenericCompactAssignments<VoterIndex, TargetIndex, SolutionAccuracy>;
+
+/// The balance type of this module.
+pub type BalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type PositiveImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::PositiveImbalance;
+type NegativeImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::NegativeImbalance;
+
+/// Current phase of the pallet.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+pub enum Phase<Bn> {
+	/// Nothing, the election is not happening.
+	Off,
+	/// Signed phase is open.
+	Signed,
+	/// Unsigned phase. First element is whether it is open or not, second the starting block
+	/// number.
+	Unsigned((bool, Bn)),
+}
+
+impl<Bn> Default for Phase<Bn> {
+	fn default() -> Self {
+		Phase::Off
+	}
+}
+
+impl<Bn: PartialEq + Eq> Phase<Bn> {
+	/// Whether the phase is signed or not.
+	pub fn is_signed(&self) -> bool {
+		matches!(self, Phase::Signed)
+	}
+
+	/// Whether the phase is unsigned or not.
+	pub fn is_unsigned(&self) -> bool {
+		matches!(self, Phase::Unsigned(_))
+	}
+
+	/// Whether the phase is unsigned and open or not, with specific start.
+	pub fn is_unsigned_open_at(&self, at: Bn) -> bool {
+		matches!(self, Phase::Unsigned((true, real)) if *real == at)
+	}
+
+	/// Whether the phase is unsigned and open or not.
+	pub fn is_unsigned_open(&self) -> bool {
+		matches!(self, Phase::Unsigned((true, _)))
+	}
+
+	/// Whether the phase is off or not.
+	pub fn is_off(&self) -> bool {
+		matches!(self, Phase::Off)
+	}
+}
+
+/// A raw, unchecked solution.
+///
+/// This is what will get submitted to the chain, in both the signed and the unsigned phase.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, Default)]
+pub struct RawSolution {
+	/// The winners, as indices into the targets of the snapshot.
+	pub winners: Vec<TargetIndex>,
+	/// Compact election edges.
+	pub compact: CompactAssignments,
+	/// The _claimed_ score of the solution.
+	pub score: PhragmenScore,
+	/// The round at which this solution should be submitted.
+	pub round: u32,
+}
+
+/// A raw, unchecked signed submission.
+///
+/// This is just a wrapper around [`RawSolution`] and some additional info.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct SignedSubmission<AccountId, Balance: HasCompact> {
+	/// Who submitted this solution.
+	pub who: AccountId,
+	/// The deposit reserved for storing this solution.
+	pub deposit: Balance,
+	/// The reward that should be given to this solution, if chosen as the best one.
+	pub reward: Balance,
+	/// The raw solution itself.
+	pub solution: RawSolution,
+}
+
+/// A checked solution, ready to be enacted.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, Default)]
+pub struct ReadySolution<AccountId> {
+	/// The final supports of the solution. This is target-major vector, storing each winner and
+	/// all the voters that are backing it.
+	pub supports: Supports<AccountId>,
+	/// The score of the solution.
+	pub score: PhragmenScore,
+	/// How this solution was computed.
+	pub compute: ElectionCompute,
+}
+
+/// The snapshot of the election data of a round.
+///
+/// This is stored at the beginning of the signed phase and cleared upon calling `elect`.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, Default)]
+pub struct RoundSnapshot<AccountId> {
+	/// All of the voters, with their stake and votes.
+	pub voters: Vec<(AccountId, VoteWeight, Vec<AccountId>)>,
+	/// All of the targets.
+	pub targets: Vec<AccountId>,
+}
+
+/// Some information about the size of the snapshot of the current round.
+///
+/// This is submitted along with an unsigned solution as witness data, such that its weight can be
+/// determined before dispatch. In short, these should be the same size as the size of the values
+/// stored in [`Snapshot`].
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, Default)]
+pub struct SolutionSize {
+	/// Number of voters in the snapshot of the current round.
+	#[codec(compact)]
+	pub voters: u32,
+	/// Number of targets in the snapshot of the current round.
+	#[codec(compact)]
+	pub targets: u32,
+}
+
+/// Reasons for which a solution can fail the [`Module::feasibility_check`].
+#[derive(Eq, PartialEq, RuntimeDebug)]
+pub enum FeasibilityError {
+	/// Wrong number of winners presented.
+	WrongWinnerCount,
+	/// The snapshot is not available.
+	///
+	/// This must be an internal error of the chain.
+	SnapshotUnavailable,
+	/// The solution was submitted for another round.
+	InvalidRound,
+	/// One of the winners is not a valid target, or is duplicated.
+	InvalidWinner,
+	/// A voter is not in the snapshot, or is duplicated.
+	InvalidVoter,
+	/// A vote is not among the votes of the voter in the snapshot, or points to a non-winner.
+	InvalidVote,
+	/// The claimed score does not match the computed one.
+	InvalidScore,
+	/// Error from phragmen crate. This usually relates to compact operation.
+	PhragmenError(sp_phragmen::Error),
+}
+
+impl From<sp_phragmen::Error> for FeasibilityError {
+	fn from(e: sp_phragmen::Error) -> Self {
+		FeasibilityError::PhragmenError(e)
+	}
+}
+
+/// Errors of [`ElectionProvider::elect`].
+#[derive(Eq, PartialEq, RuntimeDebug)]
+pub enum ElectionError {
+	/// No solution was queued, and the on-chain fallback failed as well.
+	OnChainFallback(onchain::Error),
+}
+
+impl From<onchain::Error> for ElectionError {
+	fn from(e: onchain::Error) -> Self {
+		ElectionError::OnChainFallback(e)
+	}
+}
+
+/// The configuration of the on-chain fallback of this pallet.
+pub struct OnChainConfig<T: Trait>(sp_std::marker::PhantomData<T>);
+
+impl<T: Trait> onchain::Config for OnChainConfig<T> {
+	type AccountId = T::AccountId;
+	type BlockNumber = T::BlockNumber;
+	type Accuracy = OnChainAccuracy;
+	type DataProvider = T::DataProvider;
+}
+
+pub mod weight {
+	use super::*;
+
+	/// Weight of [`Call::submit`].
+	///
+	/// The solution is not checked upon submission, thus this is constant.
+	///
+	/// State reads:
+	/// 	- CurrentPhase, Round, DesiredTargets, SignedSubmissions
+	/// 	- Deposit reservation.
+	/// State writes:
+	/// 	- SignedSubmissions
+	/// 	- Deposit reservation, and return of the deposit of an outgoing submission.
+	pub fn weight_for_submit<T: Trait>() -> Weight {
+		(50 * WEIGHT_PER_MICROS).saturating_add(T::DbWeight::get().reads_writes(6, 4))
+	}
+
+	/// Weight of [`Call::submit_unsigned`].
+	///
+	/// All weight notes are pertaining to the case of a better solution, in which we execute
+	/// the longest code path.
+	/// Weight: 0 + (0.63 μs * t) + (0.36 μs * v) + (96 μs * a ) + (8 μs * w ) with:
+	/// * t targets in the snapshot,
+	/// * v voters in the snapshot,
+	/// * a assignment in the submitted solution
+	/// * w winners in the submitted solution
+	///
+	/// State reads:
+	/// 	- Initial checks:
+	/// 		- CurrentPhase, Round, DesiredTargets, QueuedSolution, SnapshotMetadata
+	/// 		- Snapshot
+	/// State writes:
+	/// 	- QueuedSolution
+	pub fn weight_for_submit_unsigned<T: Trait>(
+		solution: &RawSolution,
+		witness: &SolutionSize,
+	) -> Weight {
+		(630 * WEIGHT_PER_NANOS).saturating_mul(witness.targets as Weight)
+			.saturating_add((360 * WEIGHT_PER_NANOS).saturating_mul(witness.voters as Weight))
+			.saturating_add((96 * WEIGHT_PER_MICROS).saturating_mul(solution.compact.len() as Weight))
+			.saturating_add((8 * WEIGHT_PER_MICROS).saturating_mul(solution.winners.len() as Weight))
+			.saturating_add(T::DbWeight::get().reads_writes(6, 1))
+	}
+
+	/// Weight of accepting a checked signed solution at the end of the signed phase, on top of
+	/// its feasibility check.
+	///
+	/// State writes:
+	/// 	- QueuedSolution
+	/// 	- Return of the deposit and payment of the reward.
+	pub fn weight_for_accept_solution<T: Trait>() -> Weight {
+		(20 * WEIGHT_PER_MICROS).saturating_add(T::DbWeight::get().reads_writes(2, 3))
+	}
+
+	/// Weight of rejecting an invalid signed solution at the end of the signed phase, on top of
+	/// its feasibility check.
+	///
+	/// State writes:
+	/// 	- Slash of the deposit.
+	pub fn weight_for_reject_solution<T: Trait>() -> Weight {
+		(15 * WEIGHT_PER_MICROS).saturating_add(T::DbWeight::get().reads_writes(1, 1))
+	}
+}
+
+pub trait Trait: frame_system::Trait + SendTransactionTypes<Call<Self>> {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The currency used for the deposits and rewards of signed submissions.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// Duration of the signed phase.
+	type SignedPhase: Get<Self::BlockNumber>;
+
+	/// Duration of the unsigned phase.
+	type UnsignedPhase: Get<Self::BlockNumber>;
+
+	/// Maximum number of signed submissions that can be queued.
+	type SignedMaxSubmissions: Get<u32>;
+
+	/// Base reward of a signed solution that is accepted.
+	type SignedRewardBase: Get<BalanceOf<Self>>;
+
+	/// Base deposit of a signed solution.
+	type SignedDepositBase: Get<BalanceOf<Self>>;
+
+	/// Deposit of a signed solution, per byte of its encoded size.
+	type SignedDepositByte: Get<BalanceOf<Self>>;
+
+	/// Handler for the slashed deposits of invalid signed solutions.
+	type SlashHandler: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// Handler for the rewards of accepted signed solutions.
+	type RewardHandler: OnUnbalanced<PositiveImbalanceOf<Self>>;
+
+	/// The base priority of the unsigned transaction submitted in the unsigned phase.
+	type UnsignedPriority: Get<TransactionPriority>;
+
+	/// Something that will provide the election data.
+	type DataProvider: ElectionDataProvider<Self::AccountId, Self::BlockNumber>;
+
+	/// The election algorithm used by the offchain worker in the unsigned phase.
+	type Solver: NposSolver;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ElectionProviderMultiPhase {
+		/// Internal counter for the number of rounds.
+		///
+		/// This is useful for de-duplication of transactions submitted to the pool, and general
+		/// diagnostics of the module. It is incremented once per every call to `elect`.
+		pub Round get(fn round): u32 = 1;
+
+		/// Current phase.
+		pub CurrentPhase get(fn current_phase): Phase<T::BlockNumber>;
+
+		/// Sorted (worse -> best) list of unchecked, signed solutions.
+		pub SignedSubmissions get(fn signed_submissions):
+			Vec<SignedSubmission<T::AccountId, BalanceOf<T>>>;
+
+		/// Current best solution, signed or unsigned.
+		pub QueuedSolution get(fn queued_solution): Option<ReadySolution<T::AccountId>>;
+
+		/// Snapshot of the election data of the current round.
+		///
+		/// This is created at the beginning of the signed phase and cleared upon calling `elect`.
+		pub Snapshot get(fn snapshot): Option<RoundSnapshot<T::AccountId>>;
+
+		/// The size of [`Snapshot`]. Only exists when [`Snapshot`] is present.
+		pub SnapshotMetadata get(fn snapshot_metadata): Option<SolutionSize>;
+
+		/// Desired number of targets to elect for this round.
+		///
+		/// Only exists when [`Snapshot`] is present.
+		pub DesiredTargets get(fn desired_targets): Option<u32>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where <T as frame_system::Trait>::AccountId {
+		/// A solution was stored with the given compute.
+		///
+		/// If the solution is signed, this means that it hasn't yet been processed. If the
+		/// solution is unsigned, this means that it has also been processed.
+		SolutionStored(ElectionCompute),
+		/// The election has been finalized, with `Some` of the given computation, or else if the
+		/// election failed, `None`.
+		ElectionFinalized(Option<ElectionCompute>),
+		/// An account has been rewarded for their signed submission being finalized.
+		Rewarded(AccountId),
+		/// An account has been slashed for submitting an invalid signed submission.
+		Slashed(AccountId),
+		/// The signed phase of the given round has started.
+		SignedPhaseStarted(u32),
+		/// The unsigned phase of the given round has started.
+		UnsignedPhaseStarted(u32),
+	}
+);
+
+decl_error! {
+	/// Error for the multi-phase election provider module.
+	pub enum Error for Module<T: Trait> {
+		/// Submission was too early, or too late.
+		PreDispatchEarlySubmission,
+		/// Submission was made for another round.
+		PreDispatchWrongRound,
+		/// Wrong number of winners presented.
+		PreDispatchWrongWinnerCount,
+		/// Submission was too weak, score-wise.
+		PreDispatchWeakSubmission,
+		/// The witness data of the solution does not match the snapshot.
+		PreDispatchWrongWitness,
+		/// The queue was full, and the solution was not better than any of the existing ones.
+		SignedQueueFull,
+		/// The origin failed to pay the deposit.
+		SignedCannotPayDeposit,
+		/// The solution failed the feasibility check.
+		InvalidSolution,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Duration of the signed phase.
+		const SignedPhase: T::BlockNumber = T::SignedPhase::get();
+
+		/// Duration of the unsigned phase.
+		const UnsignedPhase: T::BlockNumber = T::UnsignedPhase::get();
+
+		/// Maximum number of signed submissions that can be queued.
+		const SignedMaxSubmissions: u32 = T::SignedMaxSubmissions::get();
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let next_election = T::DataProvider::next_election_prediction(now).max(now);
+			let remaining = next_election - now;
+
+			let unsigned_deadline = T::UnsignedPhase::get();
+			let signed_deadline = T::SignedPhase::get() + unsigned_deadline;
+
+			match Self::current_phase() {
+				Phase::Off if remaining <= signed_deadline && remaining > unsigned_deadline => {
+					Self::on_initialize_open_signed()
+				},
+				phase @ Phase::Signed | phase @ Phase::Off
+					if remaining <= unsigned_deadline && !remaining.is_zero() =>
+				{
+					let weight = if phase.is_signed() {
+						// the snapshot exists already, check the signed solutions.
+						Self::finalize_signed_phase().1
+					} else {
+						// the signed phase was skipped, thus the snapshot must be created now.
+						Self::create_snapshot()
+					};
+					weight.saturating_add(Self::on_initialize_open_unsigned(now))
+				},
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+
+		fn offchain_worker(now: T::BlockNumber) {
+			// We only mine a solution in the first block of the unsigned phase.
+			if Self::current_phase().is_unsigned_open_at(now) {
+				match unsigned::set_check_offchain_execution_status::<T>(now) {
+					Ok(_) => match Self::mine_and_submit() {
+						Ok(_) => log!(info, "💸 successfully submitted a solution in round {}.", Self::round()),
+						Err(e) => log!(warn, "💸 failed to submit a solution: {:?}", e),
+					},
+					Err(why) => log!(debug, "💸 offchain worker denied: {}", why),
+				}
+			}
+		}
+
+		/// Submit a solution for the signed phase.
+		///
+		/// The dispatch origin fo this call must be __signed__.
+		///
+		/// The solution is not checked, but potentially queued based on its claimed score. It is
+		/// processed at the end of the signed phase. A deposit is reserved and recorded for the
+		/// solution. Based on the outcome, the solution is rewarded, slashed, or gets its deposit
+		/// back.
+		///
+		/// # <weight>
+		/// See `crate::weight` module.
+		/// # </weight>
+		#[weight = weight::weight_for_submit::<T>()]
+		pub fn submit(origin, solution: RawSolution) {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Self::current_phase().is_signed(), Error::<T>::PreDispatchEarlySubmission);
+			ensure!(solution.round == Self::round(), Error::<T>::PreDispatchWrongRound);
+			ensure!(
+				Self::desired_targets().map_or(false, |d| solution.winners.len() as u32 == d),
+				Error::<T>::PreDispatchWrongWinnerCount,
+			);
+
+			let mut queue = Self::signed_submissions();
+			let (index, outgoing) = Self::insert_submission(&who, &mut queue, solution)
+				.ok_or(Error::<T>::SignedQueueFull)?;
+
+			// collect the deposit. Thereafter, the function cannot fail.
+			T::Currency::reserve(&who, queue[index].deposit)
+				.map_err(|_| Error::<T>::SignedCannotPayDeposit)?;
+
+			// the weakest submission was kicked out of the queue; return its deposit.
+			if let Some(SignedSubmission { who, deposit, .. }) = outgoing {
+				let _remaining = T::Currency::unreserve(&who, deposit);
+				debug_assert!(_remaining.is_zero());
+			}
+
+			<SignedSubmissions<T>>::put(queue);
+			Self::deposit_event(RawEvent::SolutionStored(ElectionCompute::Signed));
+		}
+
+		/// Submit a solution for the unsigned phase.
+		///
+		/// The dispatch origin fo this call must be __none__.
+		///
+		/// This submission is checked on the fly. Moreover, it is only validated when submitted to
+		/// the pool from the local node. Effectively, this means that only active validators can
+		/// submit this transaction when authoring a block.
+		///
+		/// # <weight>
+		/// See `crate::weight` module.
+		/// # </weight>
+		#[weight = weight::weight_for_submit_unsigned::<T>(solution, witness)]
+		pub fn submit_unsigned(origin, solution: RawSolution, witness: SolutionSize) {
+			ensure_none(origin)?;
+
+			Self::unsigned_pre_dispatch_checks(&solution)?;
+			ensure!(
+				Self::snapshot_metadata().map_or(false, |size| size == witness),
+				Error::<T>::PreDispatchWrongWitness,
+			);
+
+			let ready = Self::feasibility_check(solution, ElectionCompute::Unsigned)
+				.map_err(|e| {
+					log!(warn, "💸 unsigned solution failed the feasibility check: {:?}", e);
+					Error::<T>::InvalidSolution
+				})?;
+
+			<QueuedSolution<T>>::put(ready);
+			Self::deposit_event(RawEvent::SolutionStored(ElectionCompute::Unsigned));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Open the signed phase, and create the snapshot.
+	fn on_initialize_open_signed() -> Weight {
+		let weight = Self::create_snapshot();
+		<CurrentPhase<T>>::put(Phase::Signed);
+		Self::deposit_event(RawEvent::SignedPhaseStarted(Self::round()));
+		log!(info, "💸 signed phase of round {} started.", Self::round());
+		weight.saturating_add(T::DbWeight::get().reads_writes(2, 1))
+	}
+
+	/// Open the unsigned phase. The snapshot must already exist.
+	fn on_initialize_open_unsigned(now: T::BlockNumber) -> Weight {
+		<CurrentPhase<T>>::put(Phase::Unsigned((true, now)));
+		Self::deposit_event(RawEvent::UnsignedPhaseStarted(Self::round()));
+		log!(info, "💸 unsigned phase of round {} started.", Self::round());
+		T::DbWeight::get().reads_writes(2, 1)
+	}
+
+	/// Store the snapshot of the election data, as provided by [`Trait::DataProvider`].
+	///
+	/// Note that targets beyond the range of [`TargetIndex`] can never be part of a submitted
+	/// solution.
+	fn create_snapshot() -> Weight {
+		let targets = T::DataProvider::targets();
+		let voters = T::DataProvider::voters();
+		let desired_targets = T::DataProvider::desired_targets().min(targets.len() as u32);
+
+		<SnapshotMetadata>::put(SolutionSize {
+			voters: voters.len() as u32,
+			targets: targets.len() as u32,
+		});
+		<DesiredTargets>::put(desired_targets);
+		<Snapshot<T>>::put(RoundSnapshot { voters, targets });
+
+		T::DbWeight::get().writes(3)
+	}
+
+	/// Checks that are common to the validation and the dispatch of an unsigned solution.
+	///
+	/// These are cheap checks that do not need to read the snapshot.
+	pub fn unsigned_pre_dispatch_checks(solution: &RawSolution) -> DispatchResult {
+		ensure!(Self::current_phase().is_unsigned_open(), Error::<T>::PreDispatchEarlySubmission);
+		ensure!(solution.round == Self::round(), Error::<T>::PreDispatchWrongRound);
+		ensure!(
+			Self::desired_targets().map_or(false, |d| solution.winners.len() as u32 == d),
+			Error::<T>::PreDispatchWrongWinnerCount,
+		);
+		ensure!(
+			Self::queued_solution().map_or(true, |q| is_score_better(q.score, solution.score)),
+			Error::<T>::PreDispatchWeakSubmission,
+		);
+		Ok(())
+	}
+
+	/// Check the feasibility of a solution against the snapshot of the current round.
+	///
+	/// This is an expensive operation: the solution is fully decoded, every vote is checked to
+	/// exist in the snapshot, and its score is recomputed. If the solution is feasible, it is
+	/// returned as a [`ReadySolution`], along with the given `compute`.
+	pub fn feasibility_check(
+		solution: RawSolution,
+		compute: ElectionCompute,
+	) -> Result<ReadySolution<T::AccountId>, FeasibilityError> {
+		let RawSolution { winners, compact, score, round } = solution;
+
+		ensure!(Self::round() == round, FeasibilityError::InvalidRound);
+
+		let desired_targets =
+			Self::desired_targets().ok_or(FeasibilityError::SnapshotUnavailable)?;
+		ensure!(winners.len() as u32 == desired_targets, FeasibilityError::WrongWinnerCount);
+
+		let RoundSnapshot { voters, targets } =
+			Self::snapshot().ok_or(FeasibilityError::SnapshotUnavailable)?;
+
+		// winners must be unique and valid.
+		let unique_winners = winners.iter().collect::<BTreeSet<_>>();
+		ensure!(unique_winners.len() == winners.len(), FeasibilityError::InvalidWinner);
+		let winners = winners
+			.into_iter()
+			.map(|i| targets.get(i as usize).cloned().ok_or(FeasibilityError::InvalidWinner))
+			.collect::<Result<Vec<T::AccountId>, FeasibilityError>>()?;
+
+		// decode the assignments. This fails if any index is out of range.
+		let voter_at = |i: VoterIndex| -> Option<T::AccountId> {
+			voters.get(i as usize).map(|(who, _, _)| who.clone())
+		};
+		let target_at = |i: TargetIndex| -> Option<T::AccountId> {
+			targets.get(i as usize).cloned()
+		};
+		let assignments = compact.into_assignment(voter_at, target_at)?;
+
+		// each voter must appear once, and only vote for targets it voted for in the snapshot.
+		let voters_cache = voters
+			.iter()
+			.map(|(who, stake, votes)| (who.clone(), (*stake, votes)))
+			.collect::<BTreeMap<T::AccountId, (VoteWeight, &Vec<T::AccountId>)>>();
+		let mut seen_voters = BTreeSet::<&T::AccountId>::new();
+		for Assignment { who, distribution } in assignments.iter() {
+			ensure!(seen_voters.insert(who), FeasibilityError::InvalidVoter);
+			let (_, votes) = voters_cache.get(who).ok_or(FeasibilityError::InvalidVoter)?;
+			ensure!(
+				distribution.iter().all(|(target, _)| votes.contains(target)),
+				FeasibilityError::InvalidVote,
+			);
+		}
+
+		// convert into staked assignments, with the stakes of the snapshot.
+		let stake_of = |who: &T::AccountId| -> VoteWeight {
+			voters_cache.get(who).map(|(stake, _)| *stake).unwrap_or_default()
+		};
+		let staked = assignment_ratio_to_staked(assignments, stake_of);
+
+		// every edge must point to a winner.
+		let (mut supports, num_errors) = build_support_map(&winners, &staked);
+		ensure!(num_errors == 0, FeasibilityError::InvalidVote);
+
+		// finally, the claimed score must be correct.
+		let known_score = evaluate_support(&supports);
+		ensure!(known_score == score, FeasibilityError::InvalidScore);
+
+		// keep the order of the winners of the solution.
+		let supports = winners
+			.into_iter()
+			.filter_map(|w| supports.remove(&w).map(|s| (w, s)))
+			.collect();
+
+		Ok(ReadySolution { supports, score, compute })
+	}
+
+	/// Enact the queued solution, or fall back to the on-chain election if there is none.
+	fn do_elect() -> Result<(Supports<T::AccountId>, ElectionCompute), ElectionError> {
+		<QueuedSolution<T>>::take()
+			.map_or_else(
+				|| {
+					<onchain::OnChainSequentialPhragmen<OnChainConfig<T>> as ElectionProvider<
+						T::AccountId,
+						T::BlockNumber,
+					>>::elect()
+						.map_err(ElectionError::from)
+				},
+				|ReadySolution { supports, compute, .. }| Ok((supports, compute)),
+			)
+			.map(|(supports, compute)| {
+				Self::deposit_event(RawEvent::ElectionFinalized(Some(compute)));
+				log!(info, "💸 finalized round {} with compute {:?}.", Self::round(), compute);
+				(supports, compute)
+			})
+			.map_err(|e| {
+				Self::deposit_event(RawEvent::ElectionFinalized(None));
+				log!(warn, "💸 failed to finalize round {}: {:?}.", Self::round(), e);
+				e
+			})
+	}
+
+	/// Clean up all of the data of the current round, and start the next one.
+	fn post_elect() {
+		Round::mutate(|r| *r += 1);
+		<CurrentPhase<T>>::put(Phase::Off);
+
+		<Snapshot<T>>::kill();
+		<SnapshotMetadata>::kill();
+		<DesiredTargets>::kill();
+
+		// if `elect` was called early, some signed submissions might have never been checked.
+		for SignedSubmission { who, deposit, .. } in <SignedSubmissions<T>>::take() {
+			let _remaining = T::Currency::unreserve(&who, deposit);
+			debug_assert!(_remaining.is_zero());
+		}
+	}
+}
+
+impl<T: Trait> ElectionProvider<T::AccountId, T::BlockNumber> for Module<T> {
+	type Error = ElectionError;
+	type DataProvider = T::DataProvider;
+
+	fn elect() -> Result<(Supports<T::AccountId>, ElectionCompute), Self::Error> {
+		let outcome = Self::do_elect();
+		Self::post_elect();
+		outcome
+	}
+}
+
+#[allow(deprecated)]
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+	fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+		if let Call::submit_unsigned(solution, _) = call {
+			// discard solution not coming from the local OCW.
+			match source {
+				TransactionSource::Local | TransactionSource::InBlock => { /* allowed */ }
+				_ => {
+					log!(debug, "rejecting unsigned transaction because it is not local/in-block.");
+					return InvalidTransaction::Call.into();
+				}
+			}
+
+			if let Err(error) = Self::unsigned_pre_dispatch_checks(solution) {
+				let error_number = match error {
+					DispatchError::Module { error, ..} => error,
+					_ => 0,
+				};
+				log!(
+					debug,
+					"validate unsigned pre dispatch checks failed due to module error #{:?}.",
+					error,
+				);
+				return InvalidTransaction::Custom(error_number).into();
+			}
+
+			ValidTransaction::with_tag_prefix("OffchainElection")
+				// The higher the score[0], the better a solution is.
+				.priority(
+					T::UnsignedPriority::get().saturating_add(solution.score[0].saturated_into())
+				)
+				// A single solution can exist in the pool per round. Each validator runs the OCW
+				// at most once per round.
+				.and_provides(solution.round)
+				// The transaction is only useful for the duration of the unsigned phase.
+				.longevity(
+					TryInto::<u64>::try_into(T::UnsignedPhase::get())
+						.unwrap_or(unsigned::DEFAULT_LONGEVITY)
+				)
+				// We don't propagate this. This can never be validated at a remote node.
+				.propagate(false)
+				.build()
+		} else {
+			InvalidTransaction::Call.into()
+		}
+	}
+
+	fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+		if let Call::submit_unsigned(solution, _) = call {
+			Self::unsigned_pre_dispatch_checks(solution)
+				.map_err(|_| InvalidTransaction::Stale.into())
+		} else {
+			Err(InvalidTransaction::Call.into())
+		}
+	}
+}
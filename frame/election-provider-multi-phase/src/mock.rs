@@ -0,0 +1,308 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test utilities
+
+use std::{cell::RefCell, sync::Arc};
+use parking_lot::RwLock;
+use sp_core::{
+	H256,
+	offchain::{
+		testing::{PoolState, TestOffchainExt, TestTransactionPoolExt},
+		OffchainExt, TransactionPoolExt,
+	},
+};
+use sp_runtime::{
+	Perbill,
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use frame_support::{
+	impl_outer_origin, impl_outer_dispatch, impl_outer_event, parameter_types,
+	traits::{Get, OnInitialize},
+	weights::{Weight, constants::RocksDbWeight},
+};
+use sp_election_providers::ElectionDataProvider;
+use sp_phragmen::VoteWeight;
+use crate::*;
+
+pub(crate) type AccountId = u64;
+pub(crate) type BlockNumber = u64;
+pub(crate) type Balance = u128;
+
+/// The length of an era, in blocks. An election is predicted at the end of each era.
+pub(crate) const ERA_LENGTH: BlockNumber = 30;
+
+thread_local! {
+	static TARGETS: RefCell<Vec<AccountId>> = RefCell::new(vec![10, 20, 30, 40]);
+	static VOTERS: RefCell<Vec<(AccountId, VoteWeight, Vec<AccountId>)>> = RefCell::new(vec![
+		(1, 10, vec![10, 20]),
+		(2, 10, vec![30, 40]),
+		(3, 10, vec![40]),
+		(4, 10, vec![10, 20, 30, 40]),
+		// self votes.
+		(10, 10, vec![10]),
+		(20, 20, vec![20]),
+		(30, 30, vec![30]),
+		(40, 40, vec![40]),
+	]);
+	static DESIRED_TARGETS: RefCell<u32> = RefCell::new(2);
+	static SIGNED_PHASE: RefCell<BlockNumber> = RefCell::new(5);
+	static UNSIGNED_PHASE: RefCell<BlockNumber> = RefCell::new(5);
+	static SIGNED_MAX_SUBMISSIONS: RefCell<u32> = RefCell::new(5);
+}
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		multi_phase::MultiPhase,
+	}
+}
+
+mod multi_phase {
+	// Re-export needed for `impl_outer_event!`.
+	pub use super::super::*;
+}
+
+use frame_system as system;
+use pallet_balances as balances;
+
+impl_outer_event! {
+	pub enum MetaEvent for Test {
+		system<T>,
+		balances<T>,
+		multi_phase<T>,
+	}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 2 * frame_support::weights::constants::WEIGHT_PER_SECOND;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = MetaEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = RocksDbWeight;
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type MaximumBlockLength = MaximumBlockLength;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+impl pallet_balances::Trait for Test {
+	type Balance = Balance;
+	type Event = MetaEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
+pub struct SignedPhase;
+impl Get<BlockNumber> for SignedPhase {
+	fn get() -> BlockNumber {
+		SIGNED_PHASE.with(|v| *v.borrow())
+	}
+}
+
+pub struct UnsignedPhase;
+impl Get<BlockNumber> for UnsignedPhase {
+	fn get() -> BlockNumber {
+		UNSIGNED_PHASE.with(|v| *v.borrow())
+	}
+}
+
+pub struct SignedMaxSubmissions;
+impl Get<u32> for SignedMaxSubmissions {
+	fn get() -> u32 {
+		SIGNED_MAX_SUBMISSIONS.with(|v| *v.borrow())
+	}
+}
+
+parameter_types! {
+	pub const SignedRewardBase: Balance = 7;
+	pub const SignedDepositBase: Balance = 5;
+	pub const SignedDepositByte: Balance = 0;
+	pub const UnsignedPriority: u64 = 1 << 20;
+}
+
+/// A data provider which predicts an election at the end of every era.
+pub struct StakingMock;
+impl ElectionDataProvider<AccountId, BlockNumber> for StakingMock {
+	fn targets() -> Vec<AccountId> {
+		TARGETS.with(|v| v.borrow().clone())
+	}
+
+	fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
+		VOTERS.with(|v| v.borrow().clone())
+	}
+
+	fn desired_targets() -> u32 {
+		DESIRED_TARGETS.with(|v| *v.borrow())
+	}
+
+	fn next_election_prediction(now: BlockNumber) -> BlockNumber {
+		(now / ERA_LENGTH + 1) * ERA_LENGTH
+	}
+}
+
+impl Trait for Test {
+	type Event = MetaEvent;
+	type Currency = Balances;
+	type SignedPhase = SignedPhase;
+	type UnsignedPhase = UnsignedPhase;
+	type SignedMaxSubmissions = SignedMaxSubmissions;
+	type SignedRewardBase = SignedRewardBase;
+	type SignedDepositBase = SignedDepositBase;
+	type SignedDepositByte = SignedDepositByte;
+	type SlashHandler = ();
+	type RewardHandler = ();
+	type UnsignedPriority = UnsignedPriority;
+	type DataProvider = StakingMock;
+	type Solver = sp_phragmen::SequentialPhragmen;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+pub type Extrinsic = TestXt<Call, ()>;
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type MultiPhase = Module<Test>;
+
+pub struct ExtBuilder {
+	signed_phase: BlockNumber,
+	unsigned_phase: BlockNumber,
+	signed_max_submissions: u32,
+	desired_targets: u32,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			signed_phase: 5,
+			unsigned_phase: 5,
+			signed_max_submissions: 5,
+			desired_targets: 2,
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn phases(mut self, signed: BlockNumber, unsigned: BlockNumber) -> Self {
+		self.signed_phase = signed;
+		self.unsigned_phase = unsigned;
+		self
+	}
+	pub fn signed_max_submissions(mut self, max: u32) -> Self {
+		self.signed_max_submissions = max;
+		self
+	}
+	pub fn set_associated_constants(&self) {
+		SIGNED_PHASE.with(|v| *v.borrow_mut() = self.signed_phase);
+		UNSIGNED_PHASE.with(|v| *v.borrow_mut() = self.unsigned_phase);
+		SIGNED_MAX_SUBMISSIONS.with(|v| *v.borrow_mut() = self.signed_max_submissions);
+		DESIRED_TARGETS.with(|v| *v.borrow_mut() = self.desired_targets);
+	}
+	pub fn build(self) -> sp_io::TestExternalities {
+		self.set_associated_constants();
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		let _ = pallet_balances::GenesisConfig::<Test> {
+			balances: vec![
+				// bunch of accounts that can submit signed solutions.
+				(99, 100),
+				(999, 100),
+				(9999, 100),
+			],
+		}.assimilate_storage(&mut storage);
+
+		let mut ext = sp_io::TestExternalities::from(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+	pub fn build_and_execute(self, test: impl FnOnce() -> ()) {
+		self.build().execute_with(test)
+	}
+}
+
+/// Convert an externalities to one that can handle offchain worker tests.
+pub fn offchainify(ext: &mut sp_io::TestExternalities) -> Arc<RwLock<PoolState>> {
+	let (offchain, _offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+
+	ext.register_extension(OffchainExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	pool_state
+}
+
+/// Progress to the given block, and call `on_initialize` of this pallet at each block.
+pub fn roll_to(n: BlockNumber) {
+	let now = System::block_number();
+	for i in now + 1..=n {
+		System::set_block_number(i);
+		MultiPhase::on_initialize(i);
+	}
+}
+
+/// All of the events of this pallet that were deposited so far.
+pub fn multi_phase_events() -> Vec<RawEvent<AccountId>> {
+	System::events()
+		.into_iter()
+		.filter_map(|r| if let MetaEvent::multi_phase(inner) = r.event { Some(inner) } else { None })
+		.collect()
+}
+
+/// Mine a solution over the current snapshot, without the witness.
+pub fn raw_solution() -> RawSolution {
+	MultiPhase::mine_solution().unwrap().0
+}
@@ -0,0 +1,180 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The signed phase implementation.
+
+use crate::{
+	BalanceOf, ElectionCompute, Module, QueuedSolution, RawEvent, RawSolution, ReadySolution,
+	SignedSubmission, SignedSubmissions, Trait, weight,
+};
+use codec::Encode;
+use frame_support::{
+	StorageValue,
+	traits::{Currency, Get, OnUnbalanced, ReservableCurrency},
+	weights::Weight,
+};
+use sp_phragmen::is_score_better;
+use sp_runtime::traits::{Saturating, SaturatedConversion, Zero};
+use sp_std::prelude::*;
+
+impl<T: Trait> Module<T> {
+	/// Finish the signed phase. Process the signed submissions from best to worse until a valid
+	/// one is found, rewarding the best one and slashing the invalid ones along the way.
+	///
+	/// Returns true if we have a good solution in the signed phase, along with the consumed
+	/// weight. At most [`Trait::SignedMaxSubmissions`] submissions are queued, so this checks the
+	/// feasibility of at most that many solutions, and the weight of each check is accounted for.
+	///
+	/// This drains the [`SignedSubmissions`], potentially storing the best valid one in
+	/// [`QueuedSolution`].
+	pub fn finalize_signed_phase() -> (bool, Weight) {
+		let mut all_submissions = <SignedSubmissions<T>>::take();
+		let mut found_solution = false;
+		let mut weight = T::DbWeight::get().reads_writes(1, 1);
+
+		// the size of the snapshot is the same for all of the submissions.
+		let witness = Self::snapshot_metadata().unwrap_or_default();
+
+		while let Some(best) = all_submissions.pop() {
+			let SignedSubmission { solution, who, deposit, reward } = best;
+			weight = weight.saturating_add(weight::weight_for_submit_unsigned::<T>(&solution, &witness));
+
+			match Self::feasibility_check(solution, ElectionCompute::Signed) {
+				Ok(ready_solution) => {
+					Self::finalize_signed_phase_accept_solution(ready_solution, &who, deposit, reward);
+					weight = weight.saturating_add(weight::weight_for_accept_solution::<T>());
+					found_solution = true;
+					break;
+				}
+				Err(why) => {
+					crate::log!(warn, "💸 signed solution of {:?} is invalid: {:?}", who, why);
+					Self::finalize_signed_phase_reject_solution(&who, deposit);
+					weight = weight.saturating_add(weight::weight_for_reject_solution::<T>());
+				}
+			}
+		}
+
+		// any remaining solution is discarded without being checked, and gets its deposit back.
+		let discarded = all_submissions.len() as Weight;
+		for SignedSubmission { who, deposit, .. } in all_submissions {
+			let _remaining = T::Currency::unreserve(&who, deposit);
+			debug_assert!(_remaining.is_zero());
+		}
+		weight = weight
+			.saturating_add(T::DbWeight::get().reads_writes(discarded, discarded))
+			.saturating_add(T::DbWeight::get().writes(3));
+
+		(found_solution, weight)
+	}
+
+	/// Helper function for the case where a solution is accepted in the signed phase.
+	///
+	/// Extracted to facilitate with weight calculation.
+	///
+	/// Infallible.
+	pub fn finalize_signed_phase_accept_solution(
+		ready_solution: ReadySolution<T::AccountId>,
+		who: &T::AccountId,
+		deposit: BalanceOf<T>,
+		reward: BalanceOf<T>,
+	) {
+		// write this ready solution.
+		<QueuedSolution<T>>::put(ready_solution);
+
+		// unreserve deposit.
+		let _remaining = T::Currency::unreserve(who, deposit);
+		debug_assert!(_remaining.is_zero());
+
+		// reward.
+		let positive_imbalance = T::Currency::deposit_creating(who, reward);
+		T::RewardHandler::on_unbalanced(positive_imbalance);
+
+		Self::deposit_event(RawEvent::Rewarded(who.clone()));
+	}
+
+	/// Helper function for the case where a solution is rejected in the signed phase.
+	///
+	/// Extracted to facilitate with weight calculation.
+	///
+	/// Infallible.
+	pub fn finalize_signed_phase_reject_solution(who: &T::AccountId, deposit: BalanceOf<T>) {
+		let (negative_imbalance, _remaining) = T::Currency::slash_reserved(who, deposit);
+		debug_assert!(_remaining.is_zero());
+		T::SlashHandler::on_unbalanced(negative_imbalance);
+
+		Self::deposit_event(RawEvent::Slashed(who.clone()));
+	}
+
+	/// Insert a solution into the queue, which is sorted from worse to best.
+	///
+	/// If the queue is full, the weakest solution is removed to make room, unless the new one is
+	/// not better than any of the queued ones. Note that a solution that has the same score as a
+	/// queued one is considered to be weaker than it.
+	///
+	/// Returns the index at which the new solution was inserted, along with the submission that
+	/// had to be removed, if any. Returns `None` if the solution was not inserted. The deposit of
+	/// neither the new nor the removed submission is (un)reserved.
+	pub fn insert_submission(
+		who: &T::AccountId,
+		queue: &mut Vec<SignedSubmission<T::AccountId, BalanceOf<T>>>,
+		solution: RawSolution,
+	) -> Option<(usize, Option<SignedSubmission<T::AccountId, BalanceOf<T>>>)> {
+		// from the best one downwards, find the first one that the new solution is better than.
+		let index = queue
+			.iter()
+			.enumerate()
+			.rev()
+			.find_map(|(i, s)| {
+				if is_score_better(s.solution.score, solution.score) {
+					Some(i + 1)
+				} else {
+					None
+				}
+			})
+			.unwrap_or(0);
+
+		let is_full = queue.len() >= T::SignedMaxSubmissions::get() as usize;
+		if is_full && index == 0 {
+			// weaker than all of the queued solutions.
+			return None;
+		}
+
+		let deposit = Self::deposit_for(&solution);
+		let reward = Self::reward_for(&solution);
+		queue.insert(index, SignedSubmission { who: who.clone(), deposit, reward, solution });
+
+		if is_full {
+			// remove the weakest one, which shifts the new one down by one.
+			Some((index - 1, Some(queue.remove(0))))
+		} else {
+			Some((index, None))
+		}
+	}
+
+	/// The deposit required to submit `solution`.
+	pub fn deposit_for(solution: &RawSolution) -> BalanceOf<T> {
+		let encoded_len: BalanceOf<T> = solution.encode().len().saturated_into();
+		T::SignedDepositBase::get().saturating_add(
+			T::SignedDepositByte::get().saturating_mul(encoded_len)
+		)
+	}
+
+	/// The reward for `solution`, if it is accepted.
+	pub fn reward_for(_solution: &RawSolution) -> BalanceOf<T> {
+		T::SignedRewardBase::get()
+	}
+}
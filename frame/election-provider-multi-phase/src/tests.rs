@@ -0,0 +1,512 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the module.
+
+use super::*;
+use mock::*;
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::OffchainWorker, unsigned::ValidateUnsigned};
+use sp_election_providers::ElectionProvider;
+
+fn with_score(mut solution: RawSolution, delta: i128) -> RawSolution {
+	solution.score[0] = (solution.score[0] as i128 + delta) as u128;
+	solution
+}
+
+mod phase {
+	use super::*;
+
+	#[test]
+	fn phase_rotation_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// signed phase opens at 30 - 10, unsigned at 30 - 5.
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+			assert_eq!(MultiPhase::round(), 1);
+
+			roll_to(19);
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+			assert!(MultiPhase::snapshot().is_none());
+
+			roll_to(20);
+			assert_eq!(MultiPhase::current_phase(), Phase::Signed);
+			assert!(MultiPhase::snapshot().is_some());
+			assert_eq!(
+				MultiPhase::snapshot_metadata(),
+				Some(SolutionSize { voters: 8, targets: 4 }),
+			);
+			assert_eq!(MultiPhase::desired_targets(), Some(2));
+
+			roll_to(24);
+			assert_eq!(MultiPhase::current_phase(), Phase::Signed);
+
+			roll_to(25);
+			assert_eq!(MultiPhase::current_phase(), Phase::Unsigned((true, 25)));
+			assert!(MultiPhase::snapshot().is_some());
+
+			roll_to(29);
+			assert_eq!(MultiPhase::current_phase(), Phase::Unsigned((true, 25)));
+
+			assert_eq!(
+				multi_phase_events(),
+				vec![RawEvent::SignedPhaseStarted(1), RawEvent::UnsignedPhaseStarted(1)],
+			);
+
+			assert_ok!(MultiPhase::elect());
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+			assert_eq!(MultiPhase::round(), 2);
+			assert!(MultiPhase::snapshot().is_none());
+			assert!(MultiPhase::snapshot_metadata().is_none());
+			assert!(MultiPhase::desired_targets().is_none());
+
+			// the next round follows the same schedule.
+			roll_to(49);
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+			roll_to(50);
+			assert_eq!(MultiPhase::current_phase(), Phase::Signed);
+		})
+	}
+
+	#[test]
+	fn signed_phase_can_be_skipped() {
+		ExtBuilder::default().phases(0, 5).build_and_execute(|| {
+			roll_to(24);
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+			assert!(MultiPhase::snapshot().is_none());
+
+			roll_to(25);
+			assert_eq!(MultiPhase::current_phase(), Phase::Unsigned((true, 25)));
+			assert!(MultiPhase::snapshot().is_some());
+		})
+	}
+
+	#[test]
+	fn onchain_fallback_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(25);
+			assert!(MultiPhase::queued_solution().is_none());
+
+			let (supports, compute) = MultiPhase::elect().unwrap();
+			assert_eq!(compute, ElectionCompute::OnChain);
+			assert_eq!(
+				supports.iter().map(|(who, _)| *who).collect::<Vec<_>>(),
+				vec![40, 30],
+			);
+			assert_eq!(
+				multi_phase_events().last(),
+				Some(&RawEvent::ElectionFinalized(Some(ElectionCompute::OnChain))),
+			);
+		})
+	}
+}
+
+mod signed_phase {
+	use super::*;
+
+	#[test]
+	fn cannot_submit_signed_outside_of_signed_phase() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let solution = raw_solution();
+
+			roll_to(25);
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(99), solution),
+				Error::<Test>::PreDispatchEarlySubmission,
+			);
+		})
+	}
+
+	#[test]
+	fn submit_signed_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let solution = raw_solution();
+			let deposit = MultiPhase::deposit_for(&solution);
+			assert_eq!(deposit, 5);
+
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution));
+			assert_eq!(Balances::reserved_balance(&99), 5);
+			assert_eq!(Balances::free_balance(&99), 95);
+			assert_eq!(MultiPhase::signed_submissions().len(), 1);
+			assert_eq!(
+				multi_phase_events().last(),
+				Some(&RawEvent::SolutionStored(ElectionCompute::Signed)),
+			);
+		})
+	}
+
+	#[test]
+	fn submit_signed_checks_round_and_winner_count() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let solution = raw_solution();
+
+			let mut wrong_round = solution.clone();
+			wrong_round.round += 1;
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(99), wrong_round),
+				Error::<Test>::PreDispatchWrongRound,
+			);
+
+			let mut wrong_winners = solution;
+			wrong_winners.winners.pop();
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(99), wrong_winners),
+				Error::<Test>::PreDispatchWrongWinnerCount,
+			);
+		})
+	}
+
+	#[test]
+	fn submit_signed_requires_deposit() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(123), raw_solution()),
+				Error::<Test>::SignedCannotPayDeposit,
+			);
+		})
+	}
+
+	#[test]
+	fn signed_queue_is_sorted_and_bounded() {
+		ExtBuilder::default().signed_max_submissions(2).build_and_execute(|| {
+			roll_to(20);
+			let solution = raw_solution();
+
+			assert_ok!(MultiPhase::submit(Origin::signed(99), with_score(solution.clone(), 5)));
+			assert_ok!(MultiPhase::submit(Origin::signed(999), with_score(solution.clone(), 10)));
+			assert_eq!(
+				MultiPhase::signed_submissions().iter().map(|s| s.who).collect::<Vec<_>>(),
+				vec![99, 999],
+			);
+
+			// weaker than, or as good as, all of the queued ones.
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(9999), with_score(solution.clone(), 2)),
+				Error::<Test>::SignedQueueFull,
+			);
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(9999), with_score(solution.clone(), 5)),
+				Error::<Test>::SignedQueueFull,
+			);
+
+			// better than the weakest one, which is kicked out and gets its deposit back.
+			assert_ok!(MultiPhase::submit(Origin::signed(9999), with_score(solution, 7)));
+			assert_eq!(
+				MultiPhase::signed_submissions().iter().map(|s| s.who).collect::<Vec<_>>(),
+				vec![9999, 999],
+			);
+			assert_eq!(Balances::reserved_balance(&99), 0);
+			assert_eq!(Balances::free_balance(&99), 100);
+			assert_eq!(Balances::reserved_balance(&9999), 5);
+		})
+	}
+
+	#[test]
+	fn finalize_signed_phase_rewards_and_slashes() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let solution = raw_solution();
+
+			// a valid solution.
+			assert_ok!(MultiPhase::submit(Origin::signed(999), solution.clone()));
+			// an invalid solution which claims to be better.
+			assert_ok!(MultiPhase::submit(Origin::signed(9999), with_score(solution.clone(), 1)));
+			// a weaker solution, which is never checked.
+			assert_ok!(MultiPhase::submit(Origin::signed(99), with_score(solution.clone(), -1)));
+
+			roll_to(25);
+			assert_eq!(MultiPhase::current_phase(), Phase::Unsigned((true, 25)));
+			assert!(MultiPhase::signed_submissions().is_empty());
+
+			let queued = MultiPhase::queued_solution().unwrap();
+			assert_eq!(queued.compute, ElectionCompute::Signed);
+			assert_eq!(queued.score, solution.score);
+
+			// slashed.
+			assert_eq!(Balances::reserved_balance(&9999), 0);
+			assert_eq!(Balances::free_balance(&9999), 95);
+			// rewarded.
+			assert_eq!(Balances::reserved_balance(&999), 0);
+			assert_eq!(Balances::free_balance(&999), 107);
+			// deposit returned.
+			assert_eq!(Balances::reserved_balance(&99), 0);
+			assert_eq!(Balances::free_balance(&99), 100);
+
+			assert!(multi_phase_events().contains(&RawEvent::Slashed(9999)));
+			assert!(multi_phase_events().contains(&RawEvent::Rewarded(999)));
+
+			assert_eq!(
+				multi_phase_events().last(),
+				Some(&RawEvent::UnsignedPhaseStarted(1)),
+			);
+			assert_eq!(MultiPhase::elect().unwrap().1, ElectionCompute::Signed);
+			assert_eq!(
+				multi_phase_events().last(),
+				Some(&RawEvent::ElectionFinalized(Some(ElectionCompute::Signed))),
+			);
+		})
+	}
+
+	#[test]
+	fn elect_returns_deposit_of_unchecked_submissions() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			assert_ok!(MultiPhase::submit(Origin::signed(99), raw_solution()));
+			assert_eq!(Balances::reserved_balance(&99), 5);
+
+			// the election happens earlier than predicted.
+			assert!(MultiPhase::elect().is_ok());
+			assert!(MultiPhase::signed_submissions().is_empty());
+			assert_eq!(Balances::reserved_balance(&99), 0);
+			assert_eq!(Balances::free_balance(&99), 100);
+		})
+	}
+}
+
+mod feasibility_check {
+	use super::*;
+
+	#[test]
+	fn mined_solution_is_feasible() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let solution = raw_solution();
+			let ready = MultiPhase::feasibility_check(solution.clone(), ElectionCompute::Signed)
+				.unwrap();
+			assert_eq!(ready.score, solution.score);
+			assert_eq!(ready.supports.len(), 2);
+		})
+	}
+
+	#[test]
+	fn wrong_round_is_rejected() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let mut solution = raw_solution();
+			solution.round += 1;
+			assert_eq!(
+				MultiPhase::feasibility_check(solution, ElectionCompute::Signed),
+				Err(FeasibilityError::InvalidRound),
+			);
+		})
+	}
+
+	#[test]
+	fn wrong_winners_are_rejected() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let solution = raw_solution();
+
+			let mut out_of_range = solution.clone();
+			out_of_range.winners[0] = 4;
+			assert_eq!(
+				MultiPhase::feasibility_check(out_of_range, ElectionCompute::Signed),
+				Err(FeasibilityError::InvalidWinner),
+			);
+
+			let mut duplicate = solution.clone();
+			duplicate.winners[0] = duplicate.winners[1];
+			assert_eq!(
+				MultiPhase::feasibility_check(duplicate, ElectionCompute::Signed),
+				Err(FeasibilityError::InvalidWinner),
+			);
+
+			let mut too_few = solution;
+			too_few.winners.pop();
+			assert_eq!(
+				MultiPhase::feasibility_check(too_few, ElectionCompute::Signed),
+				Err(FeasibilityError::WrongWinnerCount),
+			);
+		})
+	}
+
+	#[test]
+	fn wrong_score_is_rejected() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let solution = with_score(raw_solution(), 1);
+			assert_eq!(
+				MultiPhase::feasibility_check(solution, ElectionCompute::Signed),
+				Err(FeasibilityError::InvalidScore),
+			);
+		})
+	}
+
+	#[test]
+	fn votes_must_exist_in_snapshot() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let mut solution = raw_solution();
+
+			// voter 3 (index 2) only voted for target 40 (index 3). Make it vote for 10 instead.
+			let vote = solution.compact.votes1.iter_mut().find(|(v, _)| *v == 2).unwrap();
+			vote.1 = 0;
+			assert_eq!(
+				MultiPhase::feasibility_check(solution, ElectionCompute::Signed),
+				Err(FeasibilityError::InvalidVote),
+			);
+		})
+	}
+
+	#[test]
+	fn snapshot_is_required() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let solution = raw_solution();
+			assert!(MultiPhase::elect().is_ok());
+
+			let mut solution = solution;
+			solution.round = MultiPhase::round();
+			assert_eq!(
+				MultiPhase::feasibility_check(solution, ElectionCompute::Signed),
+				Err(FeasibilityError::SnapshotUnavailable),
+			);
+		})
+	}
+}
+
+mod unsigned_phase {
+	use super::*;
+
+	#[test]
+	fn submit_unsigned_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(25);
+			let (solution, witness) = MultiPhase::mine_solution().unwrap();
+
+			assert_ok!(MultiPhase::submit_unsigned(Origin::NONE, solution.clone(), witness));
+			let queued = MultiPhase::queued_solution().unwrap();
+			assert_eq!(queued.compute, ElectionCompute::Unsigned);
+			assert_eq!(queued.score, solution.score);
+			assert_eq!(
+				multi_phase_events().last(),
+				Some(&RawEvent::SolutionStored(ElectionCompute::Unsigned)),
+			);
+
+			// the same solution is not an improvement anymore.
+			assert_noop!(
+				MultiPhase::submit_unsigned(Origin::NONE, solution, witness),
+				Error::<Test>::PreDispatchWeakSubmission,
+			);
+		})
+	}
+
+	#[test]
+	fn submit_unsigned_checks_phase_and_witness() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(20);
+			let (solution, witness) = MultiPhase::mine_solution().unwrap();
+			assert_noop!(
+				MultiPhase::submit_unsigned(Origin::NONE, solution.clone(), witness),
+				Error::<Test>::PreDispatchEarlySubmission,
+			);
+
+			roll_to(25);
+			let wrong_witness = SolutionSize { voters: witness.voters - 1, ..witness };
+			assert_noop!(
+				MultiPhase::submit_unsigned(Origin::NONE, solution.clone(), wrong_witness),
+				Error::<Test>::PreDispatchWrongWitness,
+			);
+
+			assert_noop!(
+				MultiPhase::submit_unsigned(Origin::NONE, with_score(solution, 1), witness),
+				Error::<Test>::InvalidSolution,
+			);
+		})
+	}
+
+	#[test]
+	fn validate_unsigned_only_accepts_local_and_better_solutions() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(25);
+			let (solution, witness) = MultiPhase::mine_solution().unwrap();
+			let call = crate::Call::submit_unsigned(solution.clone(), witness);
+
+			assert_eq!(
+				<MultiPhase as ValidateUnsigned>::validate_unsigned(
+					TransactionSource::External,
+					&call,
+				),
+				TransactionValidity::Err(InvalidTransaction::Call.into()),
+			);
+
+			let valid = <MultiPhase as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::Local,
+				&call,
+			).unwrap();
+			assert_eq!(
+				valid.priority,
+				UnsignedPriority::get() + solution.score[0] as TransactionPriority,
+			);
+			assert_eq!(valid.longevity, 5);
+			assert!(!valid.propagate);
+
+			assert_ok!(MultiPhase::submit_unsigned(Origin::NONE, solution, witness));
+			assert!(<MultiPhase as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::Local,
+				&call,
+			).is_err());
+			assert!(<MultiPhase as ValidateUnsigned>::pre_dispatch(&call).is_err());
+		})
+	}
+
+	#[test]
+	fn offchain_worker_submits_solution() {
+		let mut ext = ExtBuilder::default().build();
+		let pool_state = offchainify(&mut ext);
+		ext.execute_with(|| {
+			roll_to(24);
+			MultiPhase::offchain_worker(24);
+			assert!(pool_state.read().transactions.is_empty());
+
+			roll_to(25);
+			MultiPhase::offchain_worker(25);
+			assert_eq!(pool_state.read().transactions.len(), 1);
+
+			let encoded = pool_state.read().transactions[0].clone();
+			let extrinsic: Extrinsic = Decode::decode(&mut &*encoded).unwrap();
+			let call = match extrinsic.call {
+				mock::Call::MultiPhase(inner) => inner,
+			};
+			assert!(<MultiPhase as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::Local,
+				&call,
+			).is_ok());
+
+			// the offchain worker does not run twice in the same phase.
+			MultiPhase::offchain_worker(25);
+			assert_eq!(pool_state.read().transactions.len(), 1);
+		})
+	}
+
+	#[test]
+	fn miner_does_not_submit_weaker_solution() {
+		let mut ext = ExtBuilder::default().build();
+		let pool_state = offchainify(&mut ext);
+		ext.execute_with(|| {
+			roll_to(25);
+			let (solution, witness) = MultiPhase::mine_solution().unwrap();
+			assert_ok!(MultiPhase::submit_unsigned(Origin::NONE, solution, witness));
+
+			assert_eq!(MultiPhase::mine_and_submit(), Err(unsigned::MinerError::PreDispatchChecksFailed));
+			assert!(pool_state.read().transactions.is_empty());
+		})
+	}
+}
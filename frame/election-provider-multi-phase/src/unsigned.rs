@@ -0,0 +1,201 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The unsigned phase implementation, i.e. the offchain miner.
+
+use crate::{
+	Call, CompactAssignments, Module, RawSolution, RoundSnapshot, SolutionAccuracy, SolutionSize,
+	TargetIndex, Trait, VoterIndex,
+};
+use frame_system::offchain::SubmitTransaction;
+use sp_phragmen::{
+	assignment_ratio_to_staked, assignment_staked_to_ratio, build_support_map, evaluate_support,
+	reduce, to_without_backing, Assignment, ExtendedBalance, NposSolver, PhragmenResult,
+	VoteWeight,
+};
+use sp_runtime::{offchain::storage::StorageValueRef, RuntimeDebug};
+use sp_std::{prelude::*, collections::btree_map::BTreeMap, convert::TryInto};
+
+/// Storage key used to store the persistent offchain worker status.
+pub(crate) const OFFCHAIN_HEAD_DB: &[u8] = b"parity/multi-phase-unsigned-election/";
+/// The repeat threshold of the offchain worker. This means we won't run the offchain worker twice
+/// within a window of 5 blocks.
+pub(crate) const OFFCHAIN_REPEAT: u32 = 5;
+/// Default number of blocks for which the unsigned transaction should stay in the pool
+pub(crate) const DEFAULT_LONGEVITY: u64 = 25;
+
+/// Error types related to the offchain miner.
+#[derive(RuntimeDebug, Eq, PartialEq)]
+pub enum MinerError {
+	/// The solver returned `None`. This means less targets than the minimum needed were present.
+	ElectionFailed,
+	/// Submission to the transaction pool failed.
+	PoolSubmissionFailed,
+	/// The snapshot data is not available.
+	SnapshotUnavailable,
+	/// Error from phragmen crate. This usually relates to compact operation.
+	PhragmenError(sp_phragmen::Error),
+	/// One of the computed winners is invalid.
+	InvalidWinner,
+	/// The mined solution would not pass the pre-dispatch checks, e.g. it is not better than the
+	/// queued one.
+	PreDispatchChecksFailed,
+}
+
+impl From<sp_phragmen::Error> for MinerError {
+	fn from(e: sp_phragmen::Error) -> Self {
+		MinerError::PhragmenError(e)
+	}
+}
+
+/// Checks if an execution of the offchain worker is permitted at the given block number, or not.
+///
+/// This essentially makes sure that we don't run on previous blocks in case of a re-org, and we
+/// don't run twice within a window of length [`OFFCHAIN_REPEAT`].
+///
+/// Returns `Ok(())` if offchain worker should happen, `Err(reason)` otherwise.
+pub(crate) fn set_check_offchain_execution_status<T: Trait>(
+	now: T::BlockNumber,
+) -> Result<(), &'static str> {
+	let storage = StorageValueRef::persistent(&OFFCHAIN_HEAD_DB);
+	let threshold = T::BlockNumber::from(OFFCHAIN_REPEAT);
+
+	let mutate_stat =
+		storage.mutate::<_, &'static str, _>(|maybe_head: Option<Option<T::BlockNumber>>| {
+			match maybe_head {
+				Some(Some(head)) if now < head => Err("fork."),
+				Some(Some(head)) if now >= head && now <= head + threshold => {
+					Err("recently executed.")
+				}
+				Some(Some(head)) if now > head + threshold => {
+					// we can run again now. Write the new head.
+					Ok(now)
+				}
+				_ => {
+					// value doesn't exists. Probably this node just booted up. Write, and run
+					Ok(now)
+				}
+			}
+		});
+
+	match mutate_stat {
+		// all good
+		Ok(Ok(_)) => Ok(()),
+		// failed to write.
+		Ok(Err(_)) => Err("failed to write to offchain db."),
+		// fork etc.
+		Err(why) => Err(why),
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Mine a new solution, and submit it back to the chain as an unsigned transaction.
+	pub(crate) fn mine_and_submit() -> Result<(), MinerError> {
+		let (solution, witness) = Self::mine_solution()?;
+
+		// there is no point in submitting a solution that will be rejected.
+		Self::unsigned_pre_dispatch_checks(&solution)
+			.map_err(|_| MinerError::PreDispatchChecksFailed)?;
+
+		let call = Call::submit_unsigned(solution, witness).into();
+		SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call)
+			.map_err(|_| MinerError::PoolSubmissionFailed)
+	}
+
+	/// Mine a new solution over the snapshot of the current round, with [`Trait::Solver`].
+	///
+	/// Returns the solution along with the witness data of the snapshot.
+	pub fn mine_solution() -> Result<(RawSolution, SolutionSize), MinerError> {
+		let RoundSnapshot { voters, targets } =
+			Self::snapshot().ok_or(MinerError::SnapshotUnavailable)?;
+		let desired_targets = Self::desired_targets().ok_or(MinerError::SnapshotUnavailable)?;
+		let witness = Self::snapshot_metadata().ok_or(MinerError::SnapshotUnavailable)?;
+
+		let PhragmenResult { winners, assignments } = T::Solver::solve::<_, SolutionAccuracy>(
+			desired_targets as usize,
+			0,
+			targets.clone(),
+			voters.clone(),
+		).ok_or(MinerError::ElectionFailed)?;
+
+		Self::prepare_election_result(winners, assignments, RoundSnapshot { voters, targets })
+			.map(|solution| (solution, witness))
+	}
+
+	/// Takes the result of a solver and converts it into a [`RawSolution`] that can be submitted
+	/// for the given `snapshot`.
+	///
+	/// The assignments are reduced, and the score is computed in the same way as the
+	/// [`Module::feasibility_check`] does.
+	pub fn prepare_election_result(
+		winners: Vec<(T::AccountId, ExtendedBalance)>,
+		assignments: Vec<Assignment<T::AccountId, SolutionAccuracy>>,
+		snapshot: RoundSnapshot<T::AccountId>,
+	) -> Result<RawSolution, MinerError> {
+		let RoundSnapshot { voters, targets } = snapshot;
+
+		// all helper closures
+		let voters_cache = voters
+			.iter()
+			.enumerate()
+			.map(|(i, (who, stake, _))| (who.clone(), (i, *stake)))
+			.collect::<BTreeMap<T::AccountId, (usize, VoteWeight)>>();
+		let voter_index = |who: &T::AccountId| -> Option<VoterIndex> {
+			voters_cache.get(who).and_then(|(i, _)| (*i).try_into().ok())
+		};
+		let target_index = |who: &T::AccountId| -> Option<TargetIndex> {
+			targets.iter().position(|t| t == who).and_then(|i| i.try_into().ok())
+		};
+		let stake_of = |who: &T::AccountId| -> VoteWeight {
+			voters_cache.get(who).map(|(_, stake)| *stake).unwrap_or_default()
+		};
+
+		// Clean winners.
+		let winners = to_without_backing(winners);
+
+		// convert into absolute value and to obtain the reduced version.
+		let mut staked = assignment_ratio_to_staked(assignments, &stake_of);
+		reduce(&mut staked);
+
+		// Convert back to ratio assignment. This takes less space.
+		let low_accuracy_assignment = assignment_staked_to_ratio(staked);
+
+		// convert back to staked to compute the score in the receiver's accuracy. After reduce,
+		// converting to ratio is not trivially reversible, thus the score must be computed from
+		// the exact same assignments as the ones submitted.
+		let score = {
+			let staked = assignment_ratio_to_staked(low_accuracy_assignment.clone(), &stake_of);
+			let (support_map, _) = build_support_map::<T::AccountId>(&winners, &staked);
+			evaluate_support::<T::AccountId>(&support_map)
+		};
+
+		// compact encode the assignment.
+		let compact = CompactAssignments::from_assignment(
+			low_accuracy_assignment,
+			voter_index,
+			target_index,
+		)?;
+
+		// winners to index.
+		let winners = winners
+			.iter()
+			.map(|w| target_index(w).ok_or(MinerError::InvalidWinner))
+			.collect::<Result<Vec<TargetIndex>, MinerError>>()?;
+
+		Ok(RawSolution { winners, compact, score, round: Self::round() })
+	}
+}
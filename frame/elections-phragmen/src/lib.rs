@@ -100,7 +100,7 @@ use frame_support::{
 		ContainsLengthBound,
 	}
 };
-use sp_phragmen::{build_support_map, ExtendedBalance, VoteWeight, PhragmenResult};
+use sp_phragmen::{build_support_map, ExtendedBalance, VoteWeight, PhragmenResult, NposSolver};
 use frame_system::{self as system, ensure_signed, ensure_root};
 
 mod benchmarking;
@@ -184,6 +184,9 @@ pub trait Trait: frame_system::Trait {
 	/// round will happen. If set to zero, no elections are ever triggered and the module will
 	/// be in passive mode.
 	type TermDuration: Get<Self::BlockNumber>;
+
+	/// The election algorithm used to select the members and runners-up.
+	type Solver: NposSolver;
 }
 
 decl_storage! {
@@ -876,7 +879,7 @@ impl<T: Trait> Module<T> {
 		let voters_and_votes = Voting::<T>::iter()
 			.map(|(voter, (stake, targets))| { (voter, to_votes(stake), targets) })
 			.collect::<Vec<_>>();
-		let maybe_phragmen_result = sp_phragmen::elect::<T::AccountId, Perbill>(
+		let maybe_phragmen_result = T::Solver::solve::<T::AccountId, Perbill>(
 			num_to_elect,
 			0,
 			candidates,
@@ -1217,6 +1220,7 @@ mod tests {
 		type LoserCandidate = ();
 		type KickedMember = ();
 		type BadReport = ();
+		type Solver = sp_phragmen::SequentialPhragmen;
 	}
 
 	pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition required by EVM RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding Ethereum-compatible access methods.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_evm::{Account, Bloom, CallInfo, CreateInfo, Receipt, TransactionStatus};
+use sp_core::{H160, H256, U256};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the EVM state and to execute EVM calls without using executive.
+	pub trait EvmApi<BlockNumber> where
+		BlockNumber: Codec,
+	{
+		/// Returns the chain id used to sign Ethereum transactions for this chain.
+		fn chain_id() -> u64;
+
+		/// Returns the minimal gas price of EVM executions.
+		fn gas_price() -> U256;
+
+		/// Returns the nonce and balance of the given account.
+		fn account_basic(address: H160) -> Account;
+
+		/// Returns the code stored at the given address.
+		fn account_code_at(address: H160) -> Vec<u8>;
+
+		/// Returns the value stored under `index` in the storage of the given account.
+		fn storage_at(address: H160, index: H256) -> H256;
+
+		/// Executes a call from `from` to `to`.
+		///
+		/// Uses the minimal gas price if `gas_price` is `None`. See the pallet's `call`
+		/// dispatchable for more details.
+		fn call(
+			from: H160,
+			to: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: u32,
+			gas_price: Option<U256>,
+		) -> Result<CallInfo, DispatchError>;
+
+		/// Executes the creation of a contract from `from` with the given init code.
+		///
+		/// Uses the minimal gas price if `gas_price` is `None`. See the pallet's `create`
+		/// dispatchable for more details.
+		fn create(
+			from: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: u32,
+			gas_price: Option<U256>,
+		) -> Result<CreateInfo, DispatchError>;
+
+		/// Returns the block and the outcome of the Ethereum transaction with the given hash.
+		///
+		/// Returns `None` if no such transaction was executed.
+		fn transaction_status(hash: H256) -> Option<TransactionStatus<BlockNumber>>;
+
+		/// Returns the receipts of the EVM executions in the given block.
+		///
+		/// Returns no receipts for blocks older than the retention period of the pallet.
+		fn block_receipts(number: BlockNumber) -> Vec<Receipt>;
+
+		/// Returns the bloom filter of all logs emitted in the given block.
+		fn block_logs_bloom(number: BlockNumber) -> Bloom;
+
+		/// Returns the number of blocks for which receipts and transaction statuses are kept.
+		fn receipts_retention() -> BlockNumber;
+	}
+}
@@ -0,0 +1,1240 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ethereum-compatible RPC methods for interaction with the EVM pallet.
+//!
+//! Implements the subset of the `eth_*` namespace that wallets and web3 tooling need to query
+//! accounts, execute calls and submit transactions. All state is read through the
+//! [`EvmRuntimeApi`], which the runtime of the node has to implement.
+//!
+//! Signed Ethereum transactions submitted through `eth_sendRawTransaction` are wrapped into an
+//! extrinsic by a [`ConvertTransaction`] implementation that the node supplies, and then
+//! submitted to the transaction pool.
+//!
+//! Receipts and logs are only available for the blocks within the retention period of the pallet.
+//! `eth_getLogs` fails for ranges that reach further back.
+
+use std::{convert::TryFrom, marker::PhantomData, sync::Arc};
+
+use futures::TryFutureExt;
+use jsonrpc_core::{
+	futures::{future, Future},
+	Error, ErrorCode, Result, Value,
+};
+use ethbloom::{Bloom, Input as BloomInput};
+use jsonrpc_derive::rpc;
+use pallet_evm::{ExitStatus, Transaction};
+use sc_rpc_api::DenyUnsafe;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H160, H256, U256};
+use sp_rpc::gas::search_gas_limit;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor, UniqueSaturatedInto},
+	DispatchError,
+};
+use sp_transaction_pool::{TransactionPool, TransactionSource};
+
+pub use self::gen_client::Client as EthClient;
+pub use pallet_evm_rpc_runtime_api::{self as runtime_api, EvmApi as EvmRuntimeApi};
+
+/// Ethereum RPC future Result type.
+pub type FutureResult<T> = Box<dyn Future<Item = T, Error = Error> + Send>;
+
+const RUNTIME_ERROR: i64 = 1;
+const UNKNOWN_BLOCK: i64 = 2;
+const EXECUTION_REVERTED: i64 = 3;
+const EXECUTION_FAILED: i64 = 4;
+const TRANSACTION_REJECTED: i64 = 5;
+const RECEIPTS_PRUNED: i64 = 6;
+
+/// The gas limit of calls that don't specify one, and the maximum gas limit of any call.
+///
+/// This prevents blocking the RPC for too long. It matches the block gas limit of the
+/// Ethereum mainnet at the time of writing.
+const MAX_GAS_LIMIT: u32 = 10_000_000;
+
+/// The maximum number of blocks `eth_getLogs` searches in a single request.
+const MAX_LOG_BLOCK_RANGE: u64 = 2048;
+
+/// Converts a signed Ethereum transaction into an extrinsic of the runtime.
+///
+/// The node has to supply an implementation to accept transactions through
+/// `eth_sendRawTransaction`. The returned extrinsic is submitted to the transaction pool as is.
+/// Usually this wraps `pallet_evm::Call::transact` into an unsigned extrinsic, since the pallet
+/// validates the signature of the transaction itself.
+pub trait ConvertTransaction<Extrinsic> {
+	/// Wraps `transaction` into an extrinsic that executes it.
+	fn convert_transaction(&self, transaction: Transaction) -> Extrinsic;
+}
+
+/// A block given either by number or by one of the tags Ethereum tooling uses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockNumber {
+	/// The genesis block.
+	Earliest,
+	/// The best block.
+	Latest,
+	/// The state of the pending block, which is approximated by the best block.
+	Pending,
+	/// The block with the given number.
+	Num(u64),
+}
+
+impl Serialize for BlockNumber {
+	fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		match self {
+			BlockNumber::Earliest => serializer.serialize_str("earliest"),
+			BlockNumber::Latest => serializer.serialize_str("latest"),
+			BlockNumber::Pending => serializer.serialize_str("pending"),
+			BlockNumber::Num(number) => serializer.serialize_str(&format!("0x{:x}", number)),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for BlockNumber {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+		let value = String::deserialize(deserializer)?;
+		match value.as_str() {
+			"earliest" => Ok(BlockNumber::Earliest),
+			"latest" => Ok(BlockNumber::Latest),
+			"pending" => Ok(BlockNumber::Pending),
+			number if number.starts_with("0x") => u64::from_str_radix(&number[2..], 16)
+				.map(BlockNumber::Num)
+				.map_err(|e| de::Error::custom(format!("Invalid block number: {}", e))),
+			_ => Err(de::Error::custom(
+				"Expected a hex encoded block number or one of `earliest`, `latest` and `pending`",
+			)),
+		}
+	}
+}
+
+/// The parameters of a call that is executed without submitting a transaction.
+///
+/// All fields are optional, a missing `to` means that a contract is created.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallRequest {
+	/// The sender, defaults to the zero address.
+	pub from: Option<H160>,
+	/// The account to call.
+	pub to: Option<H160>,
+	/// The gas price, defaults to the minimal gas price of the chain.
+	pub gas_price: Option<U256>,
+	/// The gas limit, defaults to the maximum allowed by this RPC.
+	pub gas: Option<U256>,
+	/// The value transferred along with the call.
+	pub value: Option<U256>,
+	/// The call data or the init code.
+	pub data: Option<Bytes>,
+}
+
+/// A log emitted by a contract.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+	/// The contract that emitted the log.
+	pub address: H160,
+	/// The topics of the log.
+	pub topics: Vec<H256>,
+	/// The data of the log.
+	pub data: Bytes,
+	/// Hash of the block the log was emitted in.
+	pub block_hash: H256,
+	/// Number of the block the log was emitted in.
+	pub block_number: U256,
+	/// Hash of the transaction, `None` if it wasn't a signed Ethereum transaction.
+	pub transaction_hash: Option<H256>,
+	/// Index of the transaction within its block.
+	pub transaction_index: U256,
+	/// Index of the log within its block.
+	pub log_index: U256,
+	/// Whether the log was removed by a reorganization, which is never the case for this RPC.
+	pub removed: bool,
+}
+
+/// Either a single value or a list of values, of which any matches.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ValueOrArray<T> {
+	/// A single value.
+	Single(T),
+	/// A list of values.
+	Multiple(Vec<T>),
+}
+
+impl<T> ValueOrArray<T> {
+	fn values(&self) -> &[T] {
+		match self {
+			ValueOrArray::Single(single) => std::slice::from_ref(single),
+			ValueOrArray::Multiple(values) => &values[..],
+		}
+	}
+}
+
+impl<T: PartialEq> ValueOrArray<T> {
+	fn contains(&self, value: &T) -> bool {
+		self.values().contains(value)
+	}
+}
+
+impl<T: AsRef<[u8]>> ValueOrArray<T> {
+	fn any_in_bloom(&self, bloom: &Bloom) -> bool {
+		self.values().iter().any(|value| bloom.contains_input(BloomInput::Raw(value.as_ref())))
+	}
+}
+
+/// The criteria logs are selected by.
+///
+/// Either a block range or a block hash can be given. The range defaults to the best block.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Filter {
+	/// The first block to search.
+	pub from_block: Option<BlockNumber>,
+	/// The last block to search.
+	pub to_block: Option<BlockNumber>,
+	/// The only block to search.
+	pub block_hash: Option<H256>,
+	/// The contracts that emitted the logs.
+	pub address: Option<ValueOrArray<H160>>,
+	/// The topics of the logs by position, `None` matches any topic.
+	pub topics: Option<Vec<Option<ValueOrArray<H256>>>>,
+}
+
+impl Filter {
+	/// Returns whether the bloom filter may contain logs matching this filter.
+	fn matches_bloom(&self, bloom: &Bloom) -> bool {
+		if bloom.is_zero() {
+			return false;
+		}
+		if let Some(address) = &self.address {
+			if !address.any_in_bloom(bloom) {
+				return false;
+			}
+		}
+		self.topics.iter().flatten().flatten().all(|topic| topic.any_in_bloom(bloom))
+	}
+
+	/// Returns whether the log matches this filter.
+	fn matches(&self, log: &Log) -> bool {
+		if let Some(address) = &self.address {
+			if !address.contains(&log.address) {
+				return false;
+			}
+		}
+		self.topics.iter().flatten().enumerate().all(|(index, topic)| match topic {
+			Some(topic) => log.topics.get(index).map_or(false, |actual| topic.contains(actual)),
+			None => true,
+		})
+	}
+}
+
+/// The receipt of an executed Ethereum transaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+	/// Hash of the transaction.
+	pub transaction_hash: H256,
+	/// Index of the transaction within its block.
+	pub transaction_index: U256,
+	/// Hash of the block the transaction was included in.
+	pub block_hash: H256,
+	/// Number of the block the transaction was included in.
+	pub block_number: U256,
+	/// The sender of the transaction.
+	pub from: H160,
+	/// The account called by the transaction, `None` for contract creations.
+	pub to: Option<H160>,
+	/// The gas used by the transaction.
+	pub gas_used: U256,
+	/// The gas used by this and all previous transactions of the block.
+	pub cumulative_gas_used: U256,
+	/// The contract created by the transaction, if any.
+	pub contract_address: Option<H160>,
+	/// The logs emitted by the transaction.
+	pub logs: Vec<Log>,
+	/// The bloom filter of the logs of the transaction.
+	pub logs_bloom: Bloom,
+	/// `1` if the execution succeeded, `0` otherwise.
+	pub status: U256,
+}
+
+/// Ethereum-compatible RPC methods.
+#[rpc]
+pub trait EthApi {
+	/// Returns the chain id used to sign transactions for this chain.
+	#[rpc(name = "eth_chainId")]
+	fn chain_id(&self) -> Result<U256>;
+
+	/// Returns the chain id as a decimal string, which Ethereum tooling uses as network id.
+	#[rpc(name = "net_version")]
+	fn net_version(&self) -> Result<String>;
+
+	/// Returns the number of the best block.
+	#[rpc(name = "eth_blockNumber")]
+	fn best_number(&self) -> Result<U256>;
+
+	/// Returns the minimal gas price transactions have to pay.
+	#[rpc(name = "eth_gasPrice")]
+	fn gas_price(&self) -> Result<U256>;
+
+	/// Returns the balance of the given account.
+	#[rpc(name = "eth_getBalance")]
+	fn balance(&self, address: H160, number: Option<BlockNumber>) -> Result<U256>;
+
+	/// Returns the code stored at the given address.
+	#[rpc(name = "eth_getCode")]
+	fn code_at(&self, address: H160, number: Option<BlockNumber>) -> Result<Bytes>;
+
+	/// Returns the value stored under `index` in the storage of the given account.
+	#[rpc(name = "eth_getStorageAt")]
+	fn storage_at(&self, address: H160, index: U256, number: Option<BlockNumber>) -> Result<H256>;
+
+	/// Returns the nonce of the given account, which is the number of transactions it sent.
+	#[rpc(name = "eth_getTransactionCount")]
+	fn transaction_count(&self, address: H160, number: Option<BlockNumber>) -> Result<U256>;
+
+	/// Executes a call without submitting a transaction.
+	///
+	/// Returns the data returned by the call. If no `to` is given, a contract is created and its
+	/// code is returned. Thus executing this won't change any state.
+	#[rpc(name = "eth_call")]
+	fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<Bytes>;
+
+	/// Returns the smallest gas limit with which the given call succeeds.
+	///
+	/// The gas limit is searched for by repeating the call with smaller limits, which makes this
+	/// method considerably more expensive than `eth_call`. It is therefore considered unsafe.
+	/// The number of repetitions is bounded, so the returned limit can be slightly above the
+	/// smallest one.
+	#[rpc(name = "eth_estimateGas")]
+	fn estimate_gas(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<U256>;
+
+	/// Submits an RLP encoded, signed Ethereum transaction to the transaction pool.
+	///
+	/// Returns the hash of the transaction.
+	#[rpc(name = "eth_sendRawTransaction")]
+	fn send_raw_transaction(&self, bytes: Bytes) -> FutureResult<H256>;
+
+	/// Returns the receipt of the transaction with the given hash.
+	///
+	/// Returns `None` if the transaction isn't part of the best chain.
+	#[rpc(name = "eth_getTransactionReceipt")]
+	fn transaction_receipt(&self, hash: H256) -> Result<Option<Receipt>>;
+
+	/// Returns the logs matching the given filter.
+	///
+	/// Fails if the requested blocks include blocks whose receipts were pruned already.
+	#[rpc(name = "eth_getLogs")]
+	fn logs(&self, filter: Filter) -> Result<Vec<Log>>;
+}
+
+/// An implementation of the Ethereum-compatible RPC methods.
+pub struct Eth<B, C, P, CT> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	convert_transaction: CT,
+	deny_unsafe: DenyUnsafe,
+	_marker: PhantomData<B>,
+}
+
+impl<B, C, P, CT> Eth<B, C, P, CT> {
+	/// Create new `Eth` with the given reference to the client and the transaction pool.
+	pub fn new(
+		client: Arc<C>,
+		pool: Arc<P>,
+		convert_transaction: CT,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Eth {
+			client,
+			pool,
+			convert_transaction,
+			deny_unsafe,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<B, C, P, CT> Eth<B, C, P, CT> where
+	B: BlockT<Hash = H256>,
+	C: HeaderBackend<B>,
+{
+	/// Resolves the requested block, which defaults to the best block.
+	fn block_id(&self, number: Option<BlockNumber>) -> Result<BlockId<B>> {
+		let number = match number.unwrap_or(BlockNumber::Latest) {
+			BlockNumber::Latest | BlockNumber::Pending =>
+				return Ok(BlockId::hash(self.client.info().best_hash)),
+			BlockNumber::Earliest => 0,
+			BlockNumber::Num(number) => number,
+		};
+
+		NumberFor::<B>::try_from(number)
+			.ok()
+			.and_then(|number| self.client.hash(number).ok().flatten())
+			.map(BlockId::hash)
+			.ok_or_else(|| Error {
+				code: ErrorCode::ServerError(UNKNOWN_BLOCK),
+				message: format!("Unknown block number: {}", number),
+				data: None,
+			})
+	}
+
+	/// Resolves the requested block number, which defaults to the best block.
+	fn block_number(&self, number: Option<BlockNumber>) -> u64 {
+		match number.unwrap_or(BlockNumber::Latest) {
+			BlockNumber::Latest | BlockNumber::Pending =>
+				self.client.info().best_number.unique_saturated_into(),
+			BlockNumber::Earliest => 0,
+			BlockNumber::Num(number) => number,
+		}
+	}
+}
+
+impl<B, C, P, CT> EthApi for Eth<B, C, P, CT> where
+	B: BlockT<Hash = H256>,
+	C: Send + Sync + 'static + ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EvmRuntimeApi<B, NumberFor<B>>,
+	P: TransactionPool<Block = B> + 'static,
+	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
+{
+	fn chain_id(&self) -> Result<U256> {
+		let at = BlockId::hash(self.client.info().best_hash);
+
+		let chain_id = self.client.runtime_api()
+			.chain_id(&at)
+			.map_err(|e| runtime_error_into_rpc_err(e))?;
+
+		Ok(chain_id.into())
+	}
+
+	fn net_version(&self) -> Result<String> {
+		self.chain_id().map(|chain_id| chain_id.to_string())
+	}
+
+	fn best_number(&self) -> Result<U256> {
+		Ok(self.block_number(None).into())
+	}
+
+	fn gas_price(&self) -> Result<U256> {
+		let at = BlockId::hash(self.client.info().best_hash);
+
+		self.client.runtime_api()
+			.gas_price(&at)
+			.map_err(|e| runtime_error_into_rpc_err(e))
+	}
+
+	fn balance(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
+		let at = self.block_id(number)?;
+
+		let account = self.client.runtime_api()
+			.account_basic(&at, address)
+			.map_err(|e| runtime_error_into_rpc_err(e))?;
+
+		Ok(account.balance)
+	}
+
+	fn code_at(&self, address: H160, number: Option<BlockNumber>) -> Result<Bytes> {
+		let at = self.block_id(number)?;
+
+		let code = self.client.runtime_api()
+			.account_code_at(&at, address)
+			.map_err(|e| runtime_error_into_rpc_err(e))?;
+
+		Ok(code.into())
+	}
+
+	fn storage_at(&self, address: H160, index: U256, number: Option<BlockNumber>) -> Result<H256> {
+		let at = self.block_id(number)?;
+		let mut key = [0u8; 32];
+		index.to_big_endian(&mut key);
+
+		self.client.runtime_api()
+			.storage_at(&at, address, key.into())
+			.map_err(|e| runtime_error_into_rpc_err(e))
+	}
+
+	fn transaction_count(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
+		let at = self.block_id(number)?;
+
+		let account = self.client.runtime_api()
+			.account_basic(&at, address)
+			.map_err(|e| runtime_error_into_rpc_err(e))?;
+
+		Ok(account.nonce)
+	}
+
+	fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<Bytes> {
+		let at = self.block_id(number)?;
+		let gas_limit = checked_gas_limit(request.gas)?;
+		let CallRequest { from, to, gas_price, value, data, .. } = request;
+		let from = from.unwrap_or_default();
+		let value = value.unwrap_or_default();
+		let data = data.map(|data| data.0).unwrap_or_default();
+
+		let api = self.client.runtime_api();
+		match to {
+			Some(to) => {
+				let info = api
+					.call(&at, from, to, data, value, gas_limit, gas_price)
+					.map_err(|e| runtime_error_into_rpc_err(e))?
+					.map_err(dispatch_error_into_rpc_err)?;
+
+				exit_status_into_result(info.exit_status, info.value).map(Bytes)
+			}
+			None => {
+				let info = api
+					.create(&at, from, data, value, gas_limit, gas_price)
+					.map_err(|e| runtime_error_into_rpc_err(e))?
+					.map_err(dispatch_error_into_rpc_err)?;
+				exit_status_into_result(info.exit_status, Vec::new())?;
+
+				// The runtime api instance keeps the changes of the creation, so the code of the
+				// new contract is visible to this query.
+				api.account_code_at(&at, info.value)
+					.map(Bytes)
+					.map_err(|e| runtime_error_into_rpc_err(e))
+			}
+		}
+	}
+
+	fn estimate_gas(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<U256> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let at = self.block_id(number)?;
+		let gas_limit = checked_gas_limit(request.gas)?;
+		let CallRequest { from, to, gas_price, value, data, .. } = request;
+		let from = from.unwrap_or_default();
+		let value = value.unwrap_or_default();
+		let data = data.map(|data| data.0).unwrap_or_default();
+
+		let execute = |gas_limit| -> Result<(ExitStatus, U256, Vec<u8>)> {
+			// A fresh runtime api instance is used for every attempt so that none of them
+			// observes the changes of a previous one.
+			let api = self.client.runtime_api();
+			let result = match to {
+				Some(to) => api
+					.call(&at, from, to, data.clone(), value, gas_limit, gas_price)
+					.map_err(|e| runtime_error_into_rpc_err(e))?
+					.map(|info| (info.exit_status, info.used_gas, info.value)),
+				None => api
+					.create(&at, from, data.clone(), value, gas_limit, gas_price)
+					.map_err(|e| runtime_error_into_rpc_err(e))?
+					.map(|info| (info.exit_status, info.used_gas, Vec::new())),
+			};
+			result.map_err(dispatch_error_into_rpc_err)
+		};
+
+		let (exit_status, used_gas, output) = execute(gas_limit)?;
+		exit_status_into_result(exit_status, output)?;
+
+		// The gas used is a lower bound of the gas limit the call requires.
+		let used_gas = used_gas.min(U256::from(gas_limit)).as_u64();
+		let required = search_gas_limit(used_gas, gas_limit.into(), |limit| {
+			execute(limit as u32).map(|(exit_status, _, _)| exit_status == ExitStatus::Succeeded)
+		})?;
+
+		Ok(required.into())
+	}
+
+	fn send_raw_transaction(&self, bytes: Bytes) -> FutureResult<H256> {
+		let transaction = match decode_transaction(&bytes) {
+			Ok(transaction) => transaction,
+			Err(e) => return Box::new(future::result(Err(e))),
+		};
+		let hash = transaction.hash();
+		let extrinsic = self.convert_transaction.convert_transaction(transaction);
+		let best_block_hash = self.client.info().best_hash;
+
+		Box::new(self.pool
+			.submit_one(&BlockId::hash(best_block_hash), TransactionSource::External, extrinsic)
+			.compat()
+			.map(move |_| hash)
+			.map_err(|e| Error {
+				code: ErrorCode::ServerError(TRANSACTION_REJECTED),
+				message: "The transaction was rejected by the transaction pool.".into(),
+				data: Some(e.to_string().into()),
+			})
+		)
+	}
+
+	fn transaction_receipt(&self, hash: H256) -> Result<Option<Receipt>> {
+		let at = BlockId::hash(self.client.info().best_hash);
+
+		let status = match self.client.runtime_api()
+			.transaction_status(&at, hash)
+			.map_err(|e| runtime_error_into_rpc_err(e))?
+		{
+			Some(status) => status,
+			None => return Ok(None),
+		};
+		let block_hash = match self.client.hash(status.block_number)
+			.map_err(|e| runtime_error_into_rpc_err(e))?
+		{
+			Some(block_hash) => block_hash,
+			None => return Ok(None),
+		};
+		let block_number: u128 = status.block_number.unique_saturated_into();
+
+		let receipts = self.client.runtime_api()
+			.block_receipts(&at, status.block_number)
+			.map_err(|e| runtime_error_into_rpc_err(e))?;
+		let (cumulative_gas_used, logs_bloom) = receipts.iter()
+			.find(|receipt| receipt.transaction_hash == Some(hash))
+			.map(|receipt| (receipt.cumulative_gas_used, receipt.logs_bloom))
+			.unwrap_or((status.gas_used, Bloom::zero()));
+		let logs = block_logs(receipts, block_hash, block_number.into())
+			.into_iter()
+			.filter(|log| log.transaction_hash == Some(hash))
+			.collect();
+
+		Ok(Some(Receipt {
+			transaction_hash: hash,
+			transaction_index: status.transaction_index.into(),
+			block_hash,
+			block_number: block_number.into(),
+			from: status.from,
+			to: status.to,
+			gas_used: status.gas_used,
+			cumulative_gas_used,
+			contract_address: status.contract_address,
+			logs,
+			logs_bloom,
+			status: if status.success { U256::one() } else { U256::zero() },
+		}))
+	}
+
+	fn logs(&self, filter: Filter) -> Result<Vec<Log>> {
+		let at = BlockId::hash(self.client.info().best_hash);
+		let best_number = self.block_number(None);
+
+		let (from, to) = match filter.block_hash {
+			Some(hash) => {
+				let number = self.client.number(hash)
+					.map_err(|e| runtime_error_into_rpc_err(e))?
+					.ok_or_else(|| Error {
+						code: ErrorCode::ServerError(UNKNOWN_BLOCK),
+						message: format!("Unknown block hash: {:?}", hash),
+						data: None,
+					})?
+					.unique_saturated_into();
+				(number, number)
+			},
+			None => (
+				self.block_number(filter.from_block),
+				self.block_number(filter.to_block).min(best_number),
+			),
+		};
+		if from > to {
+			return Ok(Vec::new());
+		}
+		if to - from >= MAX_LOG_BLOCK_RANGE {
+			return Err(Error {
+				code: ErrorCode::InvalidParams,
+				message: format!(
+					"Requested block range is greater than maximum allowed: {} > {}",
+					to - from + 1, MAX_LOG_BLOCK_RANGE
+				),
+				data: None,
+			});
+		}
+
+		let api = self.client.runtime_api();
+		let retention: u64 = api.receipts_retention(&at)
+			.map_err(|e| runtime_error_into_rpc_err(e))?
+			.unique_saturated_into();
+		let oldest = (best_number + 1).saturating_sub(retention);
+		if from < oldest {
+			return Err(Error {
+				code: ErrorCode::ServerError(RECEIPTS_PRUNED),
+				message: format!("Receipts of blocks before #{} have been pruned", oldest),
+				data: None,
+			});
+		}
+
+		let mut logs = Vec::new();
+		for number in from..=to {
+			let block_number: NumberFor<B> = number.unique_saturated_into();
+			let bloom = api.block_logs_bloom(&at, block_number)
+				.map_err(|e| runtime_error_into_rpc_err(e))?;
+			if !filter.matches_bloom(&bloom) {
+				continue;
+			}
+
+			let block_hash = match self.client.hash(block_number)
+				.map_err(|e| runtime_error_into_rpc_err(e))?
+			{
+				Some(block_hash) => block_hash,
+				None => continue,
+			};
+			let receipts = api.block_receipts(&at, block_number)
+				.map_err(|e| runtime_error_into_rpc_err(e))?;
+			logs.extend(
+				block_logs(receipts, block_hash, number.into())
+					.into_iter()
+					.filter(|log| filter.matches(log))
+			);
+		}
+
+		Ok(logs)
+	}
+}
+
+/// Converts the logs of the receipts of a block into RPC logs, numbering them within the block.
+fn block_logs(
+	receipts: Vec<pallet_evm::Receipt>,
+	block_hash: H256,
+	block_number: U256,
+) -> Vec<Log> {
+	let mut logs = Vec::new();
+	for receipt in receipts {
+		for log in receipt.logs {
+			logs.push(Log {
+				address: log.address,
+				topics: log.topics,
+				data: log.data.into(),
+				block_hash,
+				block_number,
+				transaction_hash: receipt.transaction_hash,
+				transaction_index: receipt.transaction_index.into(),
+				log_index: logs.len().into(),
+				removed: false,
+			});
+		}
+	}
+	logs
+}
+
+/// Decodes an RLP encoded, signed Ethereum transaction.
+fn decode_transaction(bytes: &[u8]) -> Result<Transaction> {
+	rlp::decode(bytes).map_err(|e| Error {
+		code: ErrorCode::InvalidParams,
+		message: format!("Invalid transaction: {}", e),
+		data: None,
+	})
+}
+
+/// Converts the requested gas limit into a number and checks that it doesn't exceed the maximum.
+fn checked_gas_limit(gas_limit: Option<U256>) -> Result<u32> {
+	let gas_limit = match gas_limit {
+		Some(gas_limit) => gas_limit,
+		None => return Ok(MAX_GAS_LIMIT),
+	};
+
+	if gas_limit > U256::from(MAX_GAS_LIMIT) {
+		return Err(Error {
+			code: ErrorCode::InvalidParams,
+			message: format!(
+				"Requested gas limit is greater than maximum allowed: {} > {}",
+				gas_limit, MAX_GAS_LIMIT
+			),
+			data: None,
+		});
+	}
+
+	Ok(gas_limit.as_u32())
+}
+
+/// Returns the output of an execution, or an RPC error if it didn't succeed.
+///
+/// The output of a reverted execution is passed along as the data of the error, so that the
+/// caller can decode the revert reason.
+fn exit_status_into_result(exit_status: ExitStatus, output: Vec<u8>) -> Result<Vec<u8>> {
+	match exit_status {
+		ExitStatus::Succeeded => Ok(output),
+		ExitStatus::Reverted => Err(Error {
+			code: ErrorCode::ServerError(EXECUTION_REVERTED),
+			message: "Execution reverted".into(),
+			data: Some(Value::String(sp_core::bytes::to_hex(&output, false))),
+		}),
+		ExitStatus::Failed | ExitStatus::Fatal => Err(Error {
+			code: ErrorCode::ServerError(EXECUTION_FAILED),
+			message: "Execution failed".into(),
+			data: Some(format!("{:?}", exit_status).into()),
+		}),
+	}
+}
+
+/// Converts an error returned by the pallet before the execution started into an RPC error.
+fn dispatch_error_into_rpc_err(err: DispatchError) -> Error {
+	Error {
+		code: ErrorCode::ServerError(EXECUTION_FAILED),
+		message: "Execution could not be started".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Runtime trapped".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_evm::{Account, CallInfo, CreateInfo, TransactionStatus};
+	use runtime_api::EvmApi;
+	use sc_transaction_pool::BasicPool;
+	use sp_api::ApiRef;
+	use sp_blockchain::{BlockStatus, Info};
+	use substrate_test_runtime_client::{AccountKeyring, runtime::{Block, Extrinsic}};
+	use substrate_test_runtime_transaction_pool::{uxt, TestApi as TestPoolApi};
+
+	const BEST_NUMBER: u64 = 3;
+	const FUNDED: H160 = H160([1; 20]);
+	const CONTRACT: H160 = H160([2; 20]);
+	const REVERTING: H160 = H160([3; 20]);
+	const TRANSACTION: H256 = H256([7; 32]);
+	const TOPIC: H256 = H256([9; 32]);
+
+	fn block_hash(number: u64) -> H256 {
+		H256::from_low_u64_be(number + 1)
+	}
+
+	/// A chain of `BEST_NUMBER` blocks whose EVM state is served by `RuntimeApi`.
+	struct TestClient;
+
+	impl ProvideRuntimeApi<Block> for TestClient {
+		type Api = RuntimeApi;
+
+		fn runtime_api<'a>(&'a self) -> ApiRef<'a, Self::Api> {
+			RuntimeApi.into()
+		}
+	}
+
+	impl HeaderBackend<Block> for TestClient {
+		fn header(
+			&self,
+			_id: BlockId<Block>,
+		) -> sp_blockchain::Result<Option<<Block as BlockT>::Header>> {
+			Ok(None)
+		}
+
+		fn info(&self) -> Info<Block> {
+			Info {
+				best_hash: block_hash(BEST_NUMBER),
+				best_number: BEST_NUMBER,
+				finalized_hash: block_hash(0),
+				finalized_number: 0,
+				genesis_hash: block_hash(0),
+				number_leaves: 1,
+			}
+		}
+
+		fn status(&self, _id: BlockId<Block>) -> sp_blockchain::Result<BlockStatus> {
+			Ok(BlockStatus::Unknown)
+		}
+
+		fn number(&self, hash: H256) -> sp_blockchain::Result<Option<u64>> {
+			Ok((0..=BEST_NUMBER).find(|number| block_hash(*number) == hash))
+		}
+
+		fn hash(&self, number: u64) -> sp_blockchain::Result<Option<H256>> {
+			Ok(Some(block_hash(number)).filter(|_| number <= BEST_NUMBER))
+		}
+	}
+
+	fn receipts(number: u64) -> Vec<pallet_evm::Receipt> {
+		if number != 2 {
+			return Vec::new();
+		}
+
+		let log = pallet_evm::Log { address: CONTRACT, topics: vec![TOPIC], data: vec![1] };
+		let mut logs_bloom = Bloom::zero();
+		logs_bloom.accrue(BloomInput::Raw(CONTRACT.as_bytes()));
+		logs_bloom.accrue(BloomInput::Raw(TOPIC.as_bytes()));
+		vec![
+			pallet_evm::Receipt {
+				transaction_hash: None,
+				transaction_index: 0,
+				from: FUNDED,
+				to: Some(FUNDED),
+				contract_address: None,
+				success: true,
+				gas_used: 5_000.into(),
+				cumulative_gas_used: 5_000.into(),
+				logs: Vec::new(),
+				logs_bloom: Bloom::zero(),
+			},
+			pallet_evm::Receipt {
+				transaction_hash: Some(TRANSACTION),
+				transaction_index: 1,
+				from: FUNDED,
+				to: Some(CONTRACT),
+				contract_address: None,
+				success: true,
+				gas_used: 21_000.into(),
+				cumulative_gas_used: 26_000.into(),
+				logs: vec![log],
+				logs_bloom,
+			},
+		]
+	}
+
+	struct RuntimeApi;
+
+	sp_api::mock_impl_runtime_apis! {
+		impl EvmApi<Block, u64> for RuntimeApi {
+			fn chain_id() -> u64 {
+				42
+			}
+
+			fn gas_price() -> U256 {
+				10.into()
+			}
+
+			fn account_basic(address: H160) -> Account {
+				let (nonce, balance) = if address == FUNDED { (5, 1_000) } else { (0, 0) };
+				Account { nonce: nonce.into(), balance: balance.into() }
+			}
+
+			fn account_code_at(_address: H160) -> Vec<u8> {
+				vec![0x60, 0x00]
+			}
+
+			fn storage_at(_address: H160, index: H256) -> H256 {
+				index
+			}
+
+			/// Succeeds with at least 30_000 gas, unless `REVERTING` is called.
+			fn call(
+				_from: H160,
+				to: H160,
+				_data: Vec<u8>,
+				_value: U256,
+				gas_limit: u32,
+				_gas_price: Option<U256>,
+			) -> std::result::Result<CallInfo, DispatchError> {
+				let (exit_status, value) = if to == REVERTING {
+					(ExitStatus::Reverted, vec![1, 2])
+				} else if gas_limit >= 30_000 {
+					(ExitStatus::Succeeded, vec![1])
+				} else {
+					(ExitStatus::Failed, Vec::new())
+				};
+				Ok(CallInfo {
+					exit_status,
+					value,
+					used_gas: U256::from(gas_limit.min(21_000)),
+					logs: Vec::new(),
+				})
+			}
+
+			fn create(
+				_from: H160,
+				_data: Vec<u8>,
+				_value: U256,
+				_gas_limit: u32,
+				_gas_price: Option<U256>,
+			) -> std::result::Result<CreateInfo, DispatchError> {
+				Err(DispatchError::Other("BalanceLow"))
+			}
+
+			fn transaction_status(hash: H256) -> Option<TransactionStatus<u64>> {
+				Some(TransactionStatus {
+					block_number: 2,
+					transaction_index: 1,
+					from: FUNDED,
+					to: Some(CONTRACT),
+					contract_address: None,
+					gas_used: 21_000.into(),
+					success: true,
+				}).filter(|_| hash == TRANSACTION)
+			}
+
+			fn block_receipts(number: u64) -> Vec<pallet_evm::Receipt> {
+				receipts(number)
+			}
+
+			fn block_logs_bloom(number: u64) -> Bloom {
+				receipts(number).iter().fold(Bloom::zero(), |mut bloom, receipt| {
+					bloom.accrue_bloom(&receipt.logs_bloom);
+					bloom
+				})
+			}
+
+			/// The receipts of block 0 were pruned already.
+			fn receipts_retention() -> u64 {
+				BEST_NUMBER
+			}
+		}
+	}
+
+	/// Submits every Ethereum transaction as a transfer of Alice with the same nonce.
+	struct TestConverter;
+
+	impl ConvertTransaction<Extrinsic> for TestConverter {
+		fn convert_transaction(&self, transaction: Transaction) -> Extrinsic {
+			uxt(AccountKeyring::Alice, transaction.nonce.as_u64())
+		}
+	}
+
+	type TestPool = BasicPool<TestPoolApi, Block>;
+
+	fn eth() -> (Eth<Block, TestClient, TestPool, TestConverter>, Arc<TestPool>) {
+		let pool = Arc::new(
+			BasicPool::new(Default::default(), Arc::new(TestPoolApi::empty()), None).0
+		);
+		(Eth::new(Arc::new(TestClient), pool.clone(), TestConverter, DenyUnsafe::No), pool)
+	}
+
+	/// The example transaction of EIP-155.
+	const SIGNED_TRANSACTION: &str = "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+
+	#[test]
+	fn should_serialize_deserialize_block_numbers() {
+		fn test(expected: &str, number: BlockNumber) {
+			let res: BlockNumber = serde_json::from_str(expected).unwrap();
+			assert_eq!(res, number);
+			assert_eq!(serde_json::to_string(&res).unwrap(), expected);
+		}
+
+		test(r#""earliest""#, BlockNumber::Earliest);
+		test(r#""latest""#, BlockNumber::Latest);
+		test(r#""pending""#, BlockNumber::Pending);
+		test(r#""0x2a""#, BlockNumber::Num(42));
+		assert!(serde_json::from_str::<BlockNumber>(r#""42""#).is_err());
+		assert!(serde_json::from_str::<BlockNumber>(r#""0xzz""#).is_err());
+	}
+
+	#[test]
+	fn should_deserialize_partial_call_request() {
+		let request: CallRequest = serde_json::from_str(
+			r#"{"to":"0x3535353535353535353535353535353535353535","data":"0x70a08231","gasPrice":"0x1"}"#
+		).unwrap();
+
+		assert_eq!(request.from, None);
+		assert_eq!(request.to, Some(H160::repeat_byte(0x35)));
+		assert_eq!(request.gas_price, Some(U256::one()));
+		assert_eq!(request.gas, None);
+		assert_eq!(request.data, Some(Bytes(vec![0x70, 0xa0, 0x82, 0x31])));
+	}
+
+	#[test]
+	fn should_serialize_receipt_properly() {
+		let expected = format!(
+			r#"{{"transactionHash":"0x0101010101010101010101010101010101010101010101010101010101010101","transactionIndex":"0x2","blockHash":"0x0202020202020202020202020202020202020202020202020202020202020202","blockNumber":"0x7","from":"0x0303030303030303030303030303030303030303","to":null,"gasUsed":"0x5208","cumulativeGasUsed":"0xa410","contractAddress":"0x0404040404040404040404040404040404040404","logs":[],"logsBloom":"0x{}","status":"0x1"}}"#,
+			"0".repeat(512),
+		);
+		let res: Receipt = serde_json::from_str(&expected).unwrap();
+		let actual = serde_json::to_string(&res).unwrap();
+		assert_eq!(actual, expected);
+	}
+
+	#[test]
+	fn should_serialize_log_properly() {
+		let expected = r#"{"address":"0x0101010101010101010101010101010101010101","topics":["0x0202020202020202020202020202020202020202020202020202020202020202"],"data":"0x0303","blockHash":"0x0404040404040404040404040404040404040404040404040404040404040404","blockNumber":"0x7","transactionHash":null,"transactionIndex":"0x1","logIndex":"0x3","removed":false}"#;
+		let res: Log = serde_json::from_str(expected).unwrap();
+		let actual = serde_json::to_string(&res).unwrap();
+		assert_eq!(actual, expected);
+	}
+
+	#[test]
+	fn filter_matches_addresses_and_topics() {
+		let filter: Filter = serde_json::from_str(r#"{
+			"fromBlock": "0x1",
+			"address": "0x0101010101010101010101010101010101010101",
+			"topics": [
+				null,
+				["0x0202020202020202020202020202020202020202020202020202020202020202", "0x0303030303030303030303030303030303030303030303030303030303030303"]
+			]
+		}"#).unwrap();
+		assert_eq!(filter.from_block, Some(BlockNumber::Num(1)));
+		assert_eq!(filter.to_block, None);
+
+		let log = |address: u8, topics: &[u8]| Log {
+			address: H160::repeat_byte(address),
+			topics: topics.iter().map(|topic| H256::repeat_byte(*topic)).collect(),
+			data: Bytes(Vec::new()),
+			block_hash: H256::zero(),
+			block_number: U256::zero(),
+			transaction_hash: None,
+			transaction_index: U256::zero(),
+			log_index: U256::zero(),
+			removed: false,
+		};
+		assert!(filter.matches(&log(1, &[9, 2])));
+		assert!(filter.matches(&log(1, &[9, 3, 4])));
+		assert!(!filter.matches(&log(1, &[9, 4])));
+		assert!(!filter.matches(&log(1, &[9])));
+		assert!(!filter.matches(&log(5, &[9, 2])));
+		assert!(Filter::default().matches(&log(5, &[])));
+
+		let bloom_of = |address: u8, topics: &[u8]| {
+			let mut bloom = Bloom::zero();
+			bloom.accrue(BloomInput::Raw(H160::repeat_byte(address).as_bytes()));
+			for topic in topics {
+				bloom.accrue(BloomInput::Raw(H256::repeat_byte(*topic).as_bytes()));
+			}
+			bloom
+		};
+		assert!(filter.matches_bloom(&bloom_of(1, &[3])));
+		assert!(!filter.matches_bloom(&bloom_of(1, &[4])));
+		assert!(!filter.matches_bloom(&bloom_of(5, &[2])));
+		assert!(!filter.matches_bloom(&Bloom::zero()));
+		assert!(Filter::default().matches_bloom(&bloom_of(5, &[])));
+	}
+
+	#[test]
+	fn should_decode_signed_transaction() {
+		let bytes = sp_core::bytes::from_hex(SIGNED_TRANSACTION).unwrap();
+
+		let transaction = decode_transaction(&bytes).unwrap();
+		assert_eq!(transaction.nonce, 9.into());
+		assert_eq!(transaction.gas_limit, 21_000.into());
+		assert_eq!(transaction.action, pallet_evm::TransactionAction::Call(H160::repeat_byte(0x35)));
+		assert_eq!(transaction.signature.v, 37);
+		assert_eq!(
+			transaction.hash(),
+			"33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788".parse().unwrap(),
+		);
+		assert_eq!(transaction.chain_id(), Some(1));
+		assert_eq!(
+			transaction.signing_hash(),
+			"daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53".parse().unwrap(),
+		);
+		assert_eq!(
+			transaction.recover_signer(),
+			Some("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f".parse().unwrap()),
+		);
+		assert!(decode_transaction(&bytes[1..]).is_err());
+	}
+
+	#[test]
+	fn should_read_state_through_runtime_api() {
+		let (eth, _) = eth();
+
+		assert_eq!(eth.chain_id().unwrap(), 42.into());
+		assert_eq!(eth.net_version().unwrap(), "42");
+		assert_eq!(eth.best_number().unwrap(), BEST_NUMBER.into());
+		assert_eq!(eth.gas_price().unwrap(), 10.into());
+		assert_eq!(eth.balance(FUNDED, None).unwrap(), 1_000.into());
+		assert_eq!(eth.transaction_count(FUNDED, None).unwrap(), 5.into());
+		assert_eq!(
+			eth.transaction_count(CONTRACT, Some(BlockNumber::Earliest)).unwrap(),
+			0.into(),
+		);
+		assert_eq!(eth.balance(CONTRACT, Some(BlockNumber::Earliest)).unwrap(), 0.into());
+		assert_eq!(eth.code_at(CONTRACT, Some(BlockNumber::Num(1))).unwrap(), Bytes(vec![0x60, 0x00]));
+		assert_eq!(
+			eth.storage_at(CONTRACT, 5.into(), Some(BlockNumber::Pending)).unwrap(),
+			H256::from_low_u64_be(5),
+		);
+		assert_eq!(
+			eth.balance(FUNDED, Some(BlockNumber::Num(BEST_NUMBER + 1))).unwrap_err().code,
+			ErrorCode::ServerError(UNKNOWN_BLOCK),
+		);
+	}
+
+	#[test]
+	fn should_execute_calls_and_estimate_gas() {
+		let (eth, _) = eth();
+		let request = |to, gas: Option<u32>| CallRequest {
+			from: Some(FUNDED),
+			to: Some(to),
+			gas: gas.map(Into::into),
+			..Default::default()
+		};
+
+		assert_eq!(eth.call(request(CONTRACT, None), None).unwrap(), Bytes(vec![1]));
+		assert_eq!(eth.estimate_gas(request(CONTRACT, None), None).unwrap(), 30_000.into());
+		assert_eq!(
+			eth.call(request(CONTRACT, Some(29_999)), None).unwrap_err().code,
+			ErrorCode::ServerError(EXECUTION_FAILED),
+		);
+		assert_eq!(
+			eth.call(request(CONTRACT, Some(MAX_GAS_LIMIT + 1)), None).unwrap_err().code,
+			ErrorCode::InvalidParams,
+		);
+
+		let reverted = eth.call(request(REVERTING, None), None).unwrap_err();
+		assert_eq!(reverted.code, ErrorCode::ServerError(EXECUTION_REVERTED));
+		assert_eq!(reverted.data, Some(Value::String("0x0102".into())));
+
+		let create = CallRequest { from: Some(FUNDED), ..Default::default() };
+		assert_eq!(
+			eth.estimate_gas(create, None).unwrap_err().code,
+			ErrorCode::ServerError(EXECUTION_FAILED),
+		);
+
+		let eth = Eth { deny_unsafe: DenyUnsafe::Yes, ..eth };
+		assert_eq!(
+			eth.estimate_gas(request(CONTRACT, None), None).unwrap_err().code,
+			ErrorCode::MethodNotFound,
+		);
+	}
+
+	#[test]
+	fn should_return_receipts_and_logs() {
+		let (eth, _) = eth();
+
+		let receipt = eth.transaction_receipt(TRANSACTION).unwrap().unwrap();
+		assert_eq!(receipt.block_hash, block_hash(2));
+		assert_eq!(receipt.block_number, 2.into());
+		assert_eq!(receipt.transaction_index, 1.into());
+		assert_eq!(receipt.gas_used, 21_000.into());
+		assert_eq!(receipt.cumulative_gas_used, 26_000.into());
+		assert_eq!(receipt.status, U256::one());
+		assert_eq!(receipt.logs.len(), 1);
+		assert_eq!(receipt.logs[0].transaction_hash, Some(TRANSACTION));
+		assert_eq!(receipt.logs[0].log_index, 0.into());
+		assert!(receipt.logs_bloom.contains_input(BloomInput::Raw(TOPIC.as_bytes())));
+		assert_eq!(eth.transaction_receipt(H256::repeat_byte(8)).unwrap(), None);
+
+		let filter = Filter {
+			from_block: Some(BlockNumber::Num(1)),
+			address: Some(ValueOrArray::Single(CONTRACT)),
+			..Default::default()
+		};
+		let logs = eth.logs(filter.clone()).unwrap();
+		assert_eq!(logs, receipt.logs);
+		let by_hash = Filter { block_hash: Some(block_hash(1)), ..filter.clone() };
+		assert_eq!(eth.logs(by_hash).unwrap(), Vec::new());
+
+		let pruned = Filter { from_block: Some(BlockNumber::Earliest), ..filter.clone() };
+		assert_eq!(eth.logs(pruned).unwrap_err().code, ErrorCode::ServerError(RECEIPTS_PRUNED));
+		let pruned = Filter { block_hash: Some(block_hash(0)), ..filter };
+		assert_eq!(eth.logs(pruned).unwrap_err().code, ErrorCode::ServerError(RECEIPTS_PRUNED));
+	}
+
+	#[test]
+	fn should_submit_raw_transactions_to_the_pool() {
+		let (eth, pool) = eth();
+		let bytes = sp_core::bytes::from_hex(SIGNED_TRANSACTION).unwrap();
+
+		assert_eq!(
+			eth.send_raw_transaction(bytes[1..].to_vec().into()).wait().unwrap_err().code,
+			ErrorCode::InvalidParams,
+		);
+		assert_eq!(pool.status().ready, 0);
+
+		let hash = eth.send_raw_transaction(bytes.into()).wait().unwrap();
+		assert_eq!(
+			hash,
+			"33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788".parse().unwrap(),
+		);
+		// The test pool expects Alice's nonce 0, so the converted transfer waits in the future queue.
+		assert_eq!(pool.status().future, 1);
+	}
+}
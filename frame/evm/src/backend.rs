@@ -5,7 +5,7 @@ use serde::{Serialize, Deserialize};
 use codec::{Encode, Decode};
 use sp_core::{U256, H256, H160};
 use sp_runtime::traits::UniqueSaturatedInto;
-use frame_support::storage::{StorageMap, StorageDoubleMap};
+use frame_support::{storage::{StorageMap, StorageDoubleMap}, traits::Get};
 use sha3::{Keccak256, Digest};
 use evm::backend::{Backend as BackendT, ApplyBackend, Apply};
 use crate::{Trait, Accounts, AccountStorages, AccountCodes, Module, Event};
@@ -87,11 +87,11 @@ impl<'vicinity, T: Trait> BackendT for Backend<'vicinity, T> {
 	}
 
 	fn block_gas_limit(&self) -> U256 {
-		U256::zero()
+		U256::from(T::BlockGasLimit::get())
 	}
 
 	fn chain_id(&self) -> U256 {
-		U256::from(sp_io::misc::chain_id())
+		U256::from(Module::<T>::chain_id())
 	}
 
 	fn exists(&self, _address: H160) -> bool {
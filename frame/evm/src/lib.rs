@@ -21,25 +21,37 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod backend;
+mod transaction;
+pub mod precompiles;
+#[cfg(test)]
+mod tests;
 
 pub use crate::backend::{Account, Log, Vicinity, Backend};
+pub use ethbloom::{Bloom, Input as BloomInput};
+pub use crate::precompiles::{Precompile, Precompiles, PrecompileResult, StandardPrecompiles};
+pub use crate::transaction::{Transaction, TransactionAction, TransactionSignature};
 
 use sp_std::{vec::Vec, marker::PhantomData};
-#[cfg(feature = "std")]
 use codec::{Encode, Decode};
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
 use frame_support::{ensure, decl_module, decl_storage, decl_event, decl_error};
 use frame_support::weights::{Weight, DispatchClass, FunctionOf, Pays};
 use frame_support::traits::{Currency, WithdrawReason, ExistenceRequirement, Get};
-use frame_system::{self as system, ensure_signed};
+use frame_support::storage::{with_transaction, IterableStorageDoubleMap, TransactionOutcome};
+use frame_system::{self as system, ensure_signed, ensure_none};
 use sp_runtime::ModuleId;
 use sp_core::{U256, H256, H160, Hasher};
 use sp_runtime::{
-	DispatchResult, traits::{UniqueSaturatedInto, AccountIdConversion, SaturatedConversion},
+	DispatchResult, RuntimeDebug,
+	traits::{UniqueSaturatedInto, AccountIdConversion, SaturatedConversion, One},
+	transaction_validity::{
+		TransactionValidity, TransactionValidityError, TransactionSource, TransactionPriority,
+		InvalidTransaction, ValidTransaction,
+	},
 };
 use sha3::{Digest, Keccak256};
-use evm::{ExitReason, ExitSucceed, ExitError, Config};
+use evm::{ExitReason, Config};
 use evm::executor::StackExecutor;
 use evm::backend::ApplyBackend;
 
@@ -94,32 +106,123 @@ impl<H: Hasher, A: AsRef<[u8]>> ConvertAccountId<A> for HashTruncateConvertAccou
 	}
 }
 
-/// Custom precompiles to be used by EVM engine.
-pub trait Precompiles {
-	/// Try to execute the code address as precompile. If the code address is not
-	/// a precompile or the precompile is not yet available, return `None`.
-	/// Otherwise, calculate the amount of gas needed with given `input` and
-	/// `target_gas`. Return `Some(Ok(status, output, gas_used))` if the execution
-	/// is successful. Otherwise return `Some(Err(_))`.
-	fn execute(
-		address: H160,
-		input: &[u8],
-		target_gas: Option<usize>
-	) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>>;
+/// Trait for mapping `H160` addresses of the EVM module to account ids.
+///
+/// This is the opposite direction of [`ConvertAccountId`]. It doesn't need to be its inverse,
+/// the EVM address controls the account it is mapped to instead.
+pub trait AddressMapping<A> {
+	/// Given an Ethereum address, return the corresponding Substrate account id.
+	fn into_account_id(address: H160) -> A;
 }
 
-impl Precompiles for () {
-	fn execute(
-		_address: H160,
-		_input: &[u8],
-		_target_gas: Option<usize>
-	) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>> {
-		None
+/// Hash the address prefixed with `evm:`, taking the hash as the account id.
+pub struct HashedAddressMapping<H>(PhantomData<H>);
+
+impl<H: Hasher, A: From<H::Out>> AddressMapping<A> for HashedAddressMapping<H> {
+	fn into_account_id(address: H160) -> A {
+		let mut data = [0u8; 24];
+		data[0..4].copy_from_slice(b"evm:");
+		data[4..24].copy_from_slice(address.as_bytes());
+		H::hash(&data).into()
 	}
 }
 
 static ISTANBUL_CONFIG: Config = Config::istanbul();
 
+/// The amount of weight one unit of gas pays for.
+///
+/// A block of the Ethereum mainnet executes roughly a million gas per second, while a second of
+/// execution is worth `10^12` weight. EVM executions weigh their gas limit at this rate, the
+/// [`Dispatch`](precompiles::Dispatch) precompile charges the weight of its calls as gas.
+pub const WEIGHT_PER_GAS: Weight = 1_000_000;
+
+/// How an EVM execution stopped.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum ExitStatus {
+	/// The execution succeeded.
+	Succeeded,
+	/// The execution was reverted by the contract.
+	Reverted,
+	/// The execution stopped with an EVM error, e.g. because it ran out of gas.
+	Failed,
+	/// The execution stopped with an error the EVM can't recover from.
+	Fatal,
+}
+
+impl From<ExitReason> for ExitStatus {
+	fn from(reason: ExitReason) -> Self {
+		match reason {
+			ExitReason::Succeed(_) => ExitStatus::Succeeded,
+			ExitReason::Revert(_) => ExitStatus::Reverted,
+			ExitReason::Error(_) => ExitStatus::Failed,
+			ExitReason::Fatal(_) => ExitStatus::Fatal,
+		}
+	}
+}
+
+/// The outcome of an EVM execution that got past the checks of the transaction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ExecutionInfo<T> {
+	/// How the execution stopped.
+	pub exit_status: ExitStatus,
+	/// The value produced by the execution.
+	pub value: T,
+	/// The gas used by the execution, including the intrinsic gas of the transaction.
+	pub used_gas: U256,
+	/// The logs emitted by the execution.
+	pub logs: Vec<Log>,
+}
+
+/// The outcome of a call. The value is the data returned or reverted with.
+pub type CallInfo = ExecutionInfo<Vec<u8>>;
+
+/// The outcome of a contract creation. The value is the address of the contract.
+pub type CreateInfo = ExecutionInfo<H160>;
+
+/// Where and with which result an Ethereum transaction was executed.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct TransactionStatus<BlockNumber> {
+	/// The block the transaction was included in.
+	pub block_number: BlockNumber,
+	/// The index of the extrinsic within the block.
+	pub transaction_index: u32,
+	/// The sender of the transaction.
+	pub from: H160,
+	/// The account called by the transaction, `None` for contract creations.
+	pub to: Option<H160>,
+	/// The contract created by the transaction, if any.
+	pub contract_address: Option<H160>,
+	/// The gas used by the transaction.
+	pub gas_used: U256,
+	/// Whether the execution succeeded.
+	pub success: bool,
+}
+
+/// The receipt of an EVM execution, like the receipt of an Ethereum transaction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Receipt {
+	/// The hash of the transaction if it was a signed Ethereum transaction, see `transact`.
+	pub transaction_hash: Option<H256>,
+	/// The index of the extrinsic within the block.
+	pub transaction_index: u32,
+	/// The account that executed the call or created the contract.
+	pub from: H160,
+	/// The account called, `None` for contract creations.
+	pub to: Option<H160>,
+	/// The contract created, if any.
+	pub contract_address: Option<H160>,
+	/// Whether the execution succeeded.
+	pub success: bool,
+	/// The gas used by the execution.
+	pub gas_used: U256,
+	/// The gas used by this and all previous executions of the block.
+	pub cumulative_gas_used: U256,
+	/// The logs emitted by the execution.
+	pub logs: Vec<Log>,
+	/// The bloom filter of the addresses and topics of the logs.
+	pub logs_bloom: Bloom,
+}
+
 /// EVM module trait
 pub trait Trait: frame_system::Trait + pallet_timestamp::Trait {
 	/// The EVM's module id
@@ -134,6 +237,15 @@ pub trait Trait: frame_system::Trait + pallet_timestamp::Trait {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 	/// Precompiles associated with this EVM engine.
 	type Precompiles: Precompiles;
+	/// The number of blocks for which receipts and transaction statuses are kept.
+	type ReceiptsRetention: Get<Self::BlockNumber>;
+	/// The maximum amount of gas the EVM executions of a block may use.
+	type BlockGasLimit: Get<u32>;
+	/// The chain id Ethereum transactions must be signed for, as defined by EIP-155.
+	///
+	/// It should be unique among the chains accepting Ethereum transactions, otherwise
+	/// transactions signed for one of them can be replayed on the others.
+	type ChainId: Get<u64>;
 
 	/// EVM config used in the module.
 	fn config() -> &'static Config {
@@ -158,8 +270,29 @@ pub struct GenesisAccount {
 decl_storage! {
 	trait Store for Module<T: Trait> as EVM {
 		Accounts get(fn accounts): map hasher(blake2_128_concat) H160 => Account;
-		AccountCodes: map hasher(blake2_128_concat) H160 => Vec<u8>;
-		AccountStorages: double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) H256 => H256;
+		AccountCodes get(fn account_codes): map hasher(blake2_128_concat) H160 => Vec<u8>;
+		AccountStorages get(fn account_storages):
+			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) H256 => H256;
+		/// The status of every executed Ethereum transaction, by transaction hash.
+		TransactionStatuses get(fn transaction_status):
+			map hasher(blake2_128_concat) H256 => Option<TransactionStatus<T::BlockNumber>>;
+		/// The receipts of the EVM executions of a block, by block and index of execution.
+		BlockReceipts get(fn block_receipt):
+			double_map hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) u32
+			=> Option<Receipt>;
+		/// The bloom filter of all logs emitted in a block.
+		BlockLogsBlooms get(fn block_logs_bloom):
+			map hasher(twox_64_concat) T::BlockNumber => Bloom;
+		/// The gas used by the EVM executions of the current block.
+		BlockGasUsed get(fn block_gas_used): U256;
+		/// The number of receipts stored for the current block.
+		BlockReceiptCount: u32;
+		/// Whether an EVM execution is in progress. Set only while runtime calls dispatched by
+		/// precompiles run, it is never committed.
+		Executing: bool;
+		/// The callee of the EVM call in progress and the number of frames that have been entered
+		/// in its context so far. Like `Executing`, it is never committed.
+		TopLevelFrame: Option<(H160, u32)>;
 	}
 
 	add_extra_genesis {
@@ -216,6 +349,16 @@ decl_error! {
 		ExitReasonFatal,
 		/// Nonce is invalid
 		InvalidNonce,
+		/// The signature of an Ethereum transaction is invalid
+		InvalidSignature,
+		/// An Ethereum transaction was signed for another chain
+		InvalidChainId,
+		/// The gas limit is higher than the gas limit of a block
+		GasLimitTooHigh,
+		/// The gas limit is higher than the gas left in the current block
+		BlockGasLimitReached,
+		/// The module was entered again from within an EVM execution
+		Reentrancy,
 	}
 }
 
@@ -227,10 +370,30 @@ decl_module! {
 
 		const ModuleId: ModuleId = T::ModuleId::get();
 
+		/// The number of blocks for which receipts and transaction statuses are kept.
+		const ReceiptsRetention: T::BlockNumber = T::ReceiptsRetention::get();
+
+		/// The maximum amount of gas the EVM executions of a block may use.
+		const BlockGasLimit: u32 = T::BlockGasLimit::get();
+
+		/// The chain id Ethereum transactions must be signed for.
+		const ChainId: u64 = T::ChainId::get();
+
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			// Account for clearing the gas used and the receipt count in `on_finalize`.
+			Self::prune_receipts(n).saturating_add(T::DbWeight::get().writes(2))
+		}
+
+		fn on_finalize(_n: T::BlockNumber) {
+			BlockGasUsed::kill();
+			BlockReceiptCount::kill();
+		}
+
 		/// Deposit balance from currency/balances module into EVM.
 		#[weight = 0]
 		fn deposit_balance(origin, value: BalanceOf<T>) {
 			let sender = ensure_signed(origin)?;
+			ensure!(!Executing::get(), Error::<T>::Reentrancy);
 
 			let imbalance = T::Currency::withdraw(
 				&sender,
@@ -252,6 +415,7 @@ decl_module! {
 		#[weight = 0]
 		fn withdraw_balance(origin, value: BalanceOf<T>) {
 			let sender = ensure_signed(origin)?;
+			ensure!(!Executing::get(), Error::<T>::Reentrancy);
 			let address = T::ConvertAccountId::convert_account_id(&sender);
 			let bvalue = U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(value));
 
@@ -274,8 +438,9 @@ decl_module! {
 
 		/// Issue an EVM call operation. This is similar to a message call transaction in Ethereum.
 		#[weight = FunctionOf(
-			|(_, _, _, gas_limit, gas_price, _): (&H160, &Vec<u8>, &U256, &u32, &U256, &Option<U256>)|
-				(*gas_price).saturated_into::<Weight>().saturating_mul(*gas_limit as Weight),
+			|(_, _, _, gas_limit, _, _): (&H160, &Vec<u8>, &U256, &u32, &U256, &Option<U256>)|
+				(*gas_limit as Weight).saturating_mul(WEIGHT_PER_GAS)
+					.saturating_add(Module::<T>::receipt_weight()),
 			DispatchClass::Normal,
 			Pays::Yes,
 		)]
@@ -289,9 +454,10 @@ decl_module! {
 			nonce: Option<U256>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			Self::ensure_block_gas(gas_limit.into())?;
 			let source = T::ConvertAccountId::convert_account_id(&sender);
 
-			Self::execute_call(
+			let info = Self::execute_call(
 				source,
 				target,
 				input,
@@ -299,14 +465,19 @@ decl_module! {
 				gas_limit,
 				gas_price,
 				nonce,
-			).map_err(Into::into)
+			)?;
+
+			Self::store_receipt(None, source, Some(target), None, &info);
+			Self::ensure_succeeded(info.exit_status)?;
+			Ok(())
 		}
 
 		/// Issue an EVM create operation. This is similar to a contract creation transaction in
 		/// Ethereum.
 		#[weight = FunctionOf(
-			|(_, _, gas_limit, gas_price, _): (&Vec<u8>, &U256, &u32, &U256, &Option<U256>)|
-				(*gas_price).saturated_into::<Weight>().saturating_mul(*gas_limit as Weight),
+			|(_, _, gas_limit, _, _): (&Vec<u8>, &U256, &u32, &U256, &Option<U256>)|
+				(*gas_limit as Weight).saturating_mul(WEIGHT_PER_GAS)
+					.saturating_add(Module::<T>::receipt_weight()),
 			DispatchClass::Normal,
 			Pays::Yes,
 		)]
@@ -319,9 +490,10 @@ decl_module! {
 			nonce: Option<U256>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			Self::ensure_block_gas(gas_limit.into())?;
 			let source = T::ConvertAccountId::convert_account_id(&sender);
 
-			let create_address = Self::execute_create(
+			let info = Self::execute_create(
 				source,
 				init,
 				value,
@@ -330,14 +502,17 @@ decl_module! {
 				nonce
 			)?;
 
-			Module::<T>::deposit_event(Event::<T>::Created(create_address));
+			Self::store_receipt(None, source, None, Some(info.value), &info);
+			Self::ensure_succeeded(info.exit_status)?;
+			Module::<T>::deposit_event(Event::<T>::Created(info.value));
 			Ok(())
 		}
 
 		/// Issue an EVM create2 operation.
 		#[weight = FunctionOf(
-			|(_, _, _, gas_limit, gas_price, _): (&Vec<u8>, &H256, &U256, &u32, &U256, &Option<U256>)|
-				(*gas_price).saturated_into::<Weight>().saturating_mul(*gas_limit as Weight),
+			|(_, _, _, gas_limit, _, _): (&Vec<u8>, &H256, &U256, &u32, &U256, &Option<U256>)|
+				(*gas_limit as Weight).saturating_mul(WEIGHT_PER_GAS)
+					.saturating_add(Module::<T>::receipt_weight()),
 			DispatchClass::Normal,
 			Pays::Yes,
 		)]
@@ -351,9 +526,10 @@ decl_module! {
 			nonce: Option<U256>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			Self::ensure_block_gas(gas_limit.into())?;
 			let source = T::ConvertAccountId::convert_account_id(&sender);
 
-			let create_address = Self::execute_create2(
+			let info = Self::execute_create2(
 				source,
 				init,
 				salt,
@@ -363,7 +539,76 @@ decl_module! {
 				nonce
 			)?;
 
-			Module::<T>::deposit_event(Event::<T>::Created(create_address));
+			Self::store_receipt(None, source, None, Some(info.value), &info);
+			Self::ensure_succeeded(info.exit_status)?;
+			Module::<T>::deposit_event(Event::<T>::Created(info.value));
+			Ok(())
+		}
+
+		/// Execute a signed Ethereum transaction.
+		///
+		/// The sender is recovered from the EIP-155 signature of the transaction. Thus this is an
+		/// unsigned extrinsic, which allows users to transact with nothing but an Ethereum wallet.
+		/// The fees are paid from the EVM balance of the sender like for `call` and `create`.
+		///
+		/// Unlike those, this doesn't fail if the execution fails. The outcome of the execution is
+		/// recorded as the status and the receipt of the transaction.
+		///
+		/// Like all EVM executions, the transaction weighs its gas limit and counts towards the
+		/// gas limit of the block. Its weight includes storing its receipt and status.
+		#[weight = FunctionOf(
+			|(transaction,): (&Transaction,)|
+				transaction.gas_limit.saturated_into::<Weight>().saturating_mul(WEIGHT_PER_GAS)
+					.saturating_add(Module::<T>::receipt_weight())
+					.saturating_add(T::DbWeight::get().writes(1)),
+			DispatchClass::Normal,
+			Pays::No,
+		)]
+		fn transact(origin, transaction: Transaction) -> DispatchResult {
+			ensure_none(origin)?;
+
+			let source = Self::check_transaction(&transaction)?;
+			let hash = transaction.hash();
+			let gas_limit = transaction.gas_limit.as_u32();
+
+			let receipt = match transaction.action {
+				TransactionAction::Call(target) => {
+					let info = Self::execute_call(
+						source,
+						target,
+						transaction.input,
+						transaction.value,
+						gas_limit,
+						transaction.gas_price,
+						Some(transaction.nonce),
+					)?;
+					Self::store_receipt(Some(hash), source, Some(target), None, &info)
+				},
+				TransactionAction::Create => {
+					let info = Self::execute_create(
+						source,
+						transaction.input,
+						transaction.value,
+						gas_limit,
+						transaction.gas_price,
+						Some(transaction.nonce),
+					)?;
+					if info.exit_status == ExitStatus::Succeeded {
+						Module::<T>::deposit_event(Event::<T>::Created(info.value));
+					}
+					Self::store_receipt(Some(hash), source, None, Some(info.value), &info)
+				},
+			};
+
+			<TransactionStatuses<T>>::insert(hash, TransactionStatus {
+				block_number: frame_system::Module::<T>::block_number(),
+				transaction_index: receipt.transaction_index,
+				from: receipt.from,
+				to: receipt.to,
+				contract_address: receipt.contract_address,
+				gas_used: receipt.gas_used,
+				success: receipt.success,
+			});
 			Ok(())
 		}
 	}
@@ -378,6 +623,125 @@ impl<T: Trait> Module<T> {
 		T::ModuleId::get().into_account()
 	}
 
+	/// The chain id Ethereum transactions are signed for.
+	pub fn chain_id() -> u64 {
+		T::ChainId::get()
+	}
+
+	/// Check the signature, the gas price and the gas limit of an Ethereum transaction.
+	///
+	/// Returns the sender of the transaction.
+	pub fn check_transaction(transaction: &Transaction) -> Result<H160, Error<T>> {
+		ensure!(transaction.chain_id() == Some(Self::chain_id()), Error::<T>::InvalidChainId);
+		let source = transaction.recover_signer().ok_or(Error::<T>::InvalidSignature)?;
+		ensure!(
+			transaction.gas_price >= T::FeeCalculator::min_gas_price(),
+			Error::<T>::GasPriceTooLow,
+		);
+		Self::ensure_block_gas(transaction.gas_limit)?;
+
+		Ok(source)
+	}
+
+	/// Check that an execution with the given gas limit fits into the current block.
+	///
+	/// Like on Ethereum, the gas limit is compared to the gas the block has left, while only the
+	/// gas actually used counts towards the gas used by the block.
+	pub fn ensure_block_gas(gas_limit: U256) -> Result<(), Error<T>> {
+		let block_gas_limit = U256::from(T::BlockGasLimit::get());
+		ensure!(gas_limit <= block_gas_limit, Error::<T>::GasLimitTooHigh);
+		ensure!(
+			Self::block_gas_used().saturating_add(gas_limit) <= block_gas_limit,
+			Error::<T>::BlockGasLimitReached,
+		);
+		Ok(())
+	}
+
+	/// Returns the receipts of the EVM executions of a block, in order of execution.
+	///
+	/// Returns no receipts for blocks older than the retention period.
+	pub fn block_receipts(number: T::BlockNumber) -> Vec<Receipt> {
+		let mut receipts: Vec<_> = <BlockReceipts<T>>::iter_prefix(number).collect();
+		receipts.sort_by_key(|(index, _)| *index);
+		receipts.into_iter().map(|(_, receipt)| receipt).collect()
+	}
+
+	/// The number of blocks for which receipts and transaction statuses are kept.
+	pub fn receipts_retention() -> T::BlockNumber {
+		T::ReceiptsRetention::get().max(One::one())
+	}
+
+	/// The weight of storing the receipt of an execution with `store_receipt`.
+	fn receipt_weight() -> Weight {
+		T::DbWeight::get().reads_writes(3, 4)
+	}
+
+	/// Appends the receipt of an execution to the receipts of the current block.
+	///
+	/// The `contract_address` is only recorded if the execution succeeded.
+	fn store_receipt<V>(
+		transaction_hash: Option<H256>,
+		from: H160,
+		to: Option<H160>,
+		contract_address: Option<H160>,
+		info: &ExecutionInfo<V>,
+	) -> Receipt {
+		let mut logs_bloom = Bloom::default();
+		for log in &info.logs {
+			logs_bloom.accrue(BloomInput::Raw(log.address.as_bytes()));
+			for topic in &log.topics {
+				logs_bloom.accrue(BloomInput::Raw(topic.as_bytes()));
+			}
+		}
+
+		let block_number = frame_system::Module::<T>::block_number();
+		let success = info.exit_status == ExitStatus::Succeeded;
+		let cumulative_gas_used = BlockGasUsed::mutate(|gas_used| {
+			*gas_used = gas_used.saturating_add(info.used_gas);
+			*gas_used
+		});
+		let receipt = Receipt {
+			transaction_hash,
+			transaction_index: frame_system::Module::<T>::extrinsic_index().unwrap_or_default(),
+			from,
+			to,
+			contract_address: contract_address.filter(|_| success),
+			success,
+			gas_used: info.used_gas,
+			cumulative_gas_used,
+			logs: info.logs.clone(),
+			logs_bloom,
+		};
+		let index = BlockReceiptCount::get();
+		BlockReceiptCount::put(index + 1);
+		<BlockReceipts<T>>::insert(block_number, index, &receipt);
+		<BlockLogsBlooms<T>>::mutate(block_number, |bloom| bloom.accrue_bloom(&logs_bloom));
+
+		receipt
+	}
+
+	/// Removes the receipts and transaction statuses that fell out of the retention period at
+	/// block `now`.
+	fn prune_receipts(now: T::BlockNumber) -> Weight {
+		let retention = Self::receipts_retention();
+		if now <= retention {
+			return 0;
+		}
+
+		let expired = now - retention;
+		<BlockLogsBlooms<T>>::remove(expired);
+		let (mut receipts, mut statuses) = (0, 0);
+		for (_, receipt) in <BlockReceipts<T>>::drain_prefix(expired) {
+			receipts += 1;
+			if let Some(hash) = receipt.transaction_hash {
+				<TransactionStatuses<T>>::remove(hash);
+				statuses += 1;
+			}
+		}
+
+		T::DbWeight::get().reads_writes(receipts, 1 + receipts + statuses)
+	}
+
 	/// Check whether an account is empty.
 	pub fn is_account_empty(address: &H160) -> bool {
 		let account = Accounts::get(address);
@@ -410,9 +774,10 @@ impl<T: Trait> Module<T> {
 		gas_limit: u32,
 		gas_price: U256,
 		nonce: Option<U256>
-	) -> Result<H160, Error<T>> {
+	) -> Result<CreateInfo, Error<T>> {
 		Self::execute_evm(
 			source,
+			None,
 			value,
 			gas_limit,
 			gas_price,
@@ -439,10 +804,11 @@ impl<T: Trait> Module<T> {
 		gas_limit: u32,
 		gas_price: U256,
 		nonce: Option<U256>
-	) -> Result<H160, Error<T>> {
+	) -> Result<CreateInfo, Error<T>> {
 		let code_hash = H256::from_slice(Keccak256::digest(&init).as_slice());
 		Self::execute_evm(
 			source,
+			None,
 			value,
 			gas_limit,
 			gas_price,
@@ -470,36 +836,104 @@ impl<T: Trait> Module<T> {
 		gas_limit: u32,
 		gas_price: U256,
 		nonce: Option<U256>,
-	) -> Result<(), Error<T>> {
+	) -> Result<CallInfo, Error<T>> {
 		Self::execute_evm(
 			source,
+			Some(target),
 			value,
 			gas_limit,
 			gas_price,
 			nonce,
-			|executor| ((), executor.transact_call(
-				source,
-				target,
-				value,
-				input,
-				gas_limit as usize,
-			)),
+			|executor| {
+				let (reason, output) = executor.transact_call(
+					source,
+					target,
+					value,
+					input,
+					gas_limit as usize,
+				);
+				(output, reason)
+			},
 		)
 	}
 
+	/// Turn the status of a stopped execution into an error unless it succeeded.
+	pub fn ensure_succeeded(status: ExitStatus) -> Result<(), Error<T>> {
+		match status {
+			ExitStatus::Succeeded => Ok(()),
+			ExitStatus::Failed => Err(Error::<T>::ExitReasonFailed),
+			ExitStatus::Reverted => Err(Error::<T>::ExitReasonRevert),
+			ExitStatus::Fatal => Err(Error::<T>::ExitReasonFatal),
+		}
+	}
+
+	/// Whether the frame calling a precompile from `address` is the top-level frame of the EVM
+	/// call in progress.
+	///
+	/// This is the case if `address` is the callee of the call and no other frame has been
+	/// entered in its context, e.g. by calling itself or with `DELEGATECALL`. The top-level frame
+	/// isn't static and if it reverts, the whole execution does.
+	pub fn is_top_level_frame(address: H160) -> bool {
+		TopLevelFrame::get() == Some((address, 1))
+	}
+
+	/// Counts the frames entered in the context of the callee before looking up a precompile.
+	///
+	/// The executor looks up a precompile whenever it enters the frame of a call, including the
+	/// top-level one.
+	fn enter_frame(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &evm::Context,
+	) -> Option<PrecompileResult> {
+		TopLevelFrame::mutate(|frame| if let Some((callee, entered)) = frame {
+			if *callee == context.address {
+				*entered += 1;
+			}
+		});
+		T::Precompiles::execute(address, input, target_gas, context)
+	}
+
 	/// Execute an EVM operation.
+	///
+	/// Returns an error only if the checks of the transaction fail. Once the execution started
+	/// its fees are applied regardless of whether it succeeds.
+	///
+	/// Runtime calls dispatched by precompiles write to storage during the execution. They are
+	/// executed in a storage transaction that is rolled back unless the execution succeeds. As
+	/// the account state of the executor is only applied afterwards, these calls must not enter
+	/// this module again.
+	///
+	/// `target` is the callee of a call, whose top-level frame is tracked in `TopLevelFrame`.
 	fn execute_evm<F, R>(
 		source: H160,
+		target: Option<H160>,
 		value: U256,
 		gas_limit: u32,
 		gas_price: U256,
 		nonce: Option<U256>,
 		f: F,
-	) -> Result<R, Error<T>> where
+	) -> Result<ExecutionInfo<R>, Error<T>> where
 		F: FnOnce(&mut StackExecutor<Backend<T>>) -> (R, ExitReason),
 	{
+		ensure!(!Executing::get(), Error::<T>::Reentrancy);
 		ensure!(gas_price >= T::FeeCalculator::min_gas_price(), Error::<T>::GasPriceTooLow);
 
+		let total_fee = gas_price.checked_mul(U256::from(gas_limit))
+			.ok_or(Error::<T>::FeeOverflow)?;
+		let total_payment = value.checked_add(total_fee).ok_or(Error::<T>::PaymentOverflow)?;
+		let mut source_account = Accounts::get(&source);
+		if let Some(nonce) = nonce {
+			ensure!(source_account.nonce == nonce, Error::<T>::InvalidNonce);
+		}
+		ensure!(source_account.balance >= total_payment, Error::<T>::BalanceLow);
+
+		// The fee for the whole gas limit is charged before the execution starts, so the
+		// execution can't spend it. The fee of the unused gas is refunded afterwards.
+		source_account.balance -= total_fee;
+		Accounts::insert(&source, source_account);
+
 		let vicinity = Vicinity {
 			gas_price,
 			origin: source,
@@ -510,35 +944,113 @@ impl<T: Trait> Module<T> {
 			&backend,
 			gas_limit as usize,
 			T::config(),
-			T::Precompiles::execute,
+			Self::enter_frame,
 		);
 
-		let total_fee = gas_price.checked_mul(U256::from(gas_limit))
-			.ok_or(Error::<T>::FeeOverflow)?;
-		let total_payment = value.checked_add(total_fee).ok_or(Error::<T>::PaymentOverflow)?;
-		let source_account = Accounts::get(&source);
-		ensure!(source_account.balance >= total_payment, Error::<T>::BalanceLow);
-		executor.withdraw(source, total_fee).map_err(|_| Error::<T>::WithdrawFailed)?;
+		let (value, exit_reason) = with_transaction(|| {
+			Executing::put(true);
+			if let Some(target) = target {
+				TopLevelFrame::put((target, 0));
+			}
+			let (value, exit_reason) = f(&mut executor);
+			Executing::kill();
+			TopLevelFrame::kill();
+			match exit_reason {
+				ExitReason::Succeed(_) => TransactionOutcome::Commit((value, exit_reason)),
+				_ => TransactionOutcome::Rollback((value, exit_reason)),
+			}
+		});
+		let used_gas = U256::from(executor.used_gas());
 
-		if let Some(nonce) = nonce {
-			ensure!(source_account.nonce == nonce, Error::<T>::InvalidNonce);
-		}
+		let actual_fee = executor.fee(gas_price);
+		let (values, logs) = executor.deconstruct();
+		let logs: Vec<_> = logs.into_iter().collect();
+		let info_logs = logs.iter()
+			.map(|log| Log {
+				address: log.address,
+				topics: log.topics.clone(),
+				data: log.data.clone(),
+			})
+			.collect();
+		backend.apply(values, logs, true);
+		Accounts::mutate(&source, |account| {
+			account.balance = account.balance.saturating_add(total_fee.saturating_sub(actual_fee));
+		});
+
+		Ok(ExecutionInfo {
+			exit_status: exit_reason.into(),
+			value,
+			used_gas,
+			logs: info_logs,
+		})
+	}
 
-		let (retv, reason) = f(&mut executor);
+	/// Checks a `transact` call for the transaction pool and block production.
+	///
+	/// Returns the transaction, its sender and the sender's account. Transactions with a stale
+	/// nonce are rejected, future ones are left to the caller.
+	fn check_transact(
+		call: &Call<T>,
+	) -> Result<(&Transaction, H160, Account), TransactionValidityError> {
+		let transaction = match call {
+			Call::transact(transaction) => transaction,
+			_ => return Err(InvalidTransaction::Call.into()),
+		};
 
-		let ret = match reason {
-			ExitReason::Succeed(_) => Ok(retv),
-			ExitReason::Error(_) => Err(Error::<T>::ExitReasonFailed),
-			ExitReason::Revert(_) => Err(Error::<T>::ExitReasonRevert),
-			ExitReason::Fatal(_) => Err(Error::<T>::ExitReasonFatal),
+		let source = match Self::check_transaction(transaction) {
+			Ok(source) => source,
+			Err(Error::<T>::GasPriceTooLow) => return Err(InvalidTransaction::Payment.into()),
+			Err(Error::<T>::GasLimitTooHigh) | Err(Error::<T>::BlockGasLimitReached) =>
+				return Err(InvalidTransaction::ExhaustsResources.into()),
+			Err(_) => return Err(InvalidTransaction::BadProof.into()),
 		};
 
-		let actual_fee = executor.fee(gas_price);
-		executor.deposit(source, total_fee.saturating_sub(actual_fee));
+		let account = Accounts::get(&source);
+		if transaction.nonce < account.nonce {
+			return Err(InvalidTransaction::Stale.into());
+		}
 
-		let (values, logs) = executor.deconstruct();
-		backend.apply(values, logs, true);
+		let total_payment = transaction.gas_price
+			.checked_mul(transaction.gas_limit)
+			.and_then(|fee| fee.checked_add(transaction.value));
+		match total_payment {
+			Some(total_payment) if total_payment <= account.balance => (),
+			_ => return Err(InvalidTransaction::Payment.into()),
+		}
+
+		Ok((transaction, source, account))
+	}
+}
+
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(
+		_source: TransactionSource,
+		call: &Self::Call,
+	) -> TransactionValidity {
+		let (transaction, source, account) = Self::check_transact(call)?;
+
+		// Transactions with a higher gas price are preferred, like on Ethereum.
+		let mut builder = ValidTransaction::with_tag_prefix("EVM")
+			.priority(transaction.gas_price.saturated_into::<TransactionPriority>())
+			.and_provides((source, transaction.nonce))
+			.propagate(true);
+		if transaction.nonce > account.nonce {
+			builder = builder.and_requires((source, transaction.nonce - 1));
+		}
+
+		builder.build()
+	}
+
+	/// `transact` pays no fee and is weighed by its gas limit, so it is only included if its
+	/// execution starts. Unlike in the pool, future nonces are rejected.
+	fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+		let (transaction, _, account) = Self::check_transact(call)?;
+		if transaction.nonce > account.nonce {
+			return Err(InvalidTransaction::Future.into());
+		}
 
-		ret
+		Ok(())
 	}
 }
@@ -0,0 +1,493 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Precompiled contracts of the EVM.
+//!
+//! [`StandardPrecompiles`] implements the precompiles that Ethereum provides at the addresses
+//! `0x01` to `0x05`. [`Dispatch`] allows contracts to dispatch runtime calls. Sets of precompiles
+//! are combined as tuples, e.g. `(StandardPrecompiles, Dispatch<Runtime, AddressMapping>)`.
+
+use sp_std::{cmp, marker::PhantomData, vec::Vec};
+use codec::Decode;
+use sp_core::{H160, H256};
+use sp_runtime::traits::Dispatchable;
+use frame_support::{
+	dispatch::IsSubType,
+	storage::{with_transaction, TransactionOutcome},
+	traits::Filter,
+	weights::GetDispatchInfo,
+};
+use num_bigint::BigUint;
+use sha3::{Digest, Keccak256};
+use evm::{Context, ExitError, ExitSucceed};
+use crate::{AddressMapping, Module, Trait, WEIGHT_PER_GAS};
+
+/// The result of a precompile: the exit status, the output and the gas used.
+pub type PrecompileResult = core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>;
+
+/// Custom precompiles to be used by EVM engine.
+pub trait Precompiles {
+	/// Try to execute the code address as precompile. If the code address is not
+	/// a precompile or the precompile is not yet available, return `None`.
+	/// Otherwise, calculate the amount of gas needed with given `input` and
+	/// `target_gas`. Return `Some(Ok(status, output, gas_used))` if the execution
+	/// is successful. Otherwise return `Some(Err(_))`.
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &Context,
+	) -> Option<PrecompileResult>;
+}
+
+/// The first precompile set of the tuple that knows the address executes it.
+#[impl_trait_for_tuples::impl_for_tuples(16)]
+#[tuple_types_no_default_trait_bound]
+impl Precompiles for Tuple {
+	for_tuples!( where #( Tuple: Precompiles )* );
+
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &Context,
+	) -> Option<PrecompileResult> {
+		for_tuples!( #(
+			if let Some(result) = Tuple::execute(address, input, target_gas, context) {
+				return Some(result);
+			}
+		)* );
+		None
+	}
+}
+
+/// A single precompiled contract.
+pub trait Precompile {
+	/// Execute the precompile with the given `input`, using at most `target_gas`.
+	fn execute(input: &[u8], target_gas: Option<usize>, context: &Context) -> PrecompileResult;
+}
+
+/// Returns `cost` if it doesn't exceed `target_gas`.
+fn ensure_gas(cost: usize, target_gas: Option<usize>) -> Result<usize, ExitError> {
+	match target_gas {
+		Some(target_gas) if cost > target_gas => Err(ExitError::OutOfGas),
+		_ => Ok(cost),
+	}
+}
+
+/// The cost of a precompile that charges `base` plus `word` per started 32 byte word of input.
+fn linear_cost(len: usize, base: usize, word: usize) -> usize {
+	let words = len / 32 + if len % 32 == 0 { 0 } else { 1 };
+	base.saturating_add(word.saturating_mul(words))
+}
+
+/// Copies `data[offset..offset + len]` into a new vector, padding missing bytes with zeros.
+fn padded(data: &[u8], offset: usize, len: usize) -> Vec<u8> {
+	let mut out = sp_std::vec![0u8; len];
+	if offset < data.len() {
+		let available = cmp::min(len, data.len() - offset);
+		out[..available].copy_from_slice(&data[offset..offset + available]);
+	}
+	out
+}
+
+/// The precompiles Ethereum provides at the addresses `0x01` to `0x05`.
+pub struct StandardPrecompiles;
+
+impl Precompiles for StandardPrecompiles {
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &Context,
+	) -> Option<PrecompileResult> {
+		if address.as_bytes()[..19].iter().any(|byte| *byte != 0) {
+			return None;
+		}
+
+		match address.as_bytes()[19] {
+			1 => Some(ECRecover::execute(input, target_gas, context)),
+			2 => Some(Sha256::execute(input, target_gas, context)),
+			3 => Some(Ripemd160::execute(input, target_gas, context)),
+			4 => Some(Identity::execute(input, target_gas, context)),
+			5 => Some(Modexp::execute(input, target_gas, context)),
+			_ => None,
+		}
+	}
+}
+
+/// Recovers the address that signed a message hash, at address `0x01`.
+///
+/// The input is the hash, `v`, `r` and `s`, each as a 32 byte word. The output is the address
+/// as a 32 byte word, or empty if the signature is invalid.
+pub struct ECRecover;
+
+impl Precompile for ECRecover {
+	fn execute(input: &[u8], target_gas: Option<usize>, _: &Context) -> PrecompileResult {
+		let cost = ensure_gas(3000, target_gas)?;
+		let input = padded(input, 0, 128);
+
+		let mut message = [0u8; 32];
+		message.copy_from_slice(&input[0..32]);
+		let v = H256::from_slice(&input[32..64]);
+		let mut signature = [0u8; 65];
+		signature[0..64].copy_from_slice(&input[64..128]);
+
+		// `v` is 27 or 28, every other value yields no address.
+		let recovery_id = v.as_bytes()[31];
+		if v.as_bytes()[..31].iter().any(|byte| *byte != 0) || !(27..=28).contains(&recovery_id) {
+			return Ok((ExitSucceed::Returned, Vec::new(), cost));
+		}
+		signature[64] = recovery_id - 27;
+
+		let output = match sp_io::crypto::secp256k1_ecdsa_recover(&signature, &message) {
+			Ok(public) => {
+				let mut address = [0u8; 32];
+				address[12..].copy_from_slice(&Keccak256::digest(&public)[12..]);
+				address.to_vec()
+			},
+			Err(_) => Vec::new(),
+		};
+		Ok((ExitSucceed::Returned, output, cost))
+	}
+}
+
+/// Computes the SHA2-256 hash of the input, at address `0x02`.
+pub struct Sha256;
+
+impl Precompile for Sha256 {
+	fn execute(input: &[u8], target_gas: Option<usize>, _: &Context) -> PrecompileResult {
+		let cost = ensure_gas(linear_cost(input.len(), 60, 12), target_gas)?;
+		Ok((ExitSucceed::Returned, sp_io::hashing::sha2_256(input).to_vec(), cost))
+	}
+}
+
+/// Computes the RIPEMD-160 hash of the input, as a 32 byte word, at address `0x03`.
+pub struct Ripemd160;
+
+impl Precompile for Ripemd160 {
+	fn execute(input: &[u8], target_gas: Option<usize>, _: &Context) -> PrecompileResult {
+		let cost = ensure_gas(linear_cost(input.len(), 600, 120), target_gas)?;
+		let mut output = [0u8; 32];
+		output[12..].copy_from_slice(&ripemd160::Ripemd160::digest(input));
+		Ok((ExitSucceed::Returned, output.to_vec(), cost))
+	}
+}
+
+/// Returns the input, at address `0x04`.
+pub struct Identity;
+
+impl Precompile for Identity {
+	fn execute(input: &[u8], target_gas: Option<usize>, _: &Context) -> PrecompileResult {
+		let cost = ensure_gas(linear_cost(input.len(), 15, 3), target_gas)?;
+		Ok((ExitSucceed::Returned, input.to_vec(), cost))
+	}
+}
+
+/// Computes `base ^ exponent % modulus` on arbitrarily large integers, at address `0x05`.
+///
+/// The input and the gas costs are specified by EIP-198.
+pub struct Modexp;
+
+impl Modexp {
+	/// Reads the length at `offset`. Lengths that don't fit into `usize` saturate, so that they
+	/// are rejected by the gas calculation.
+	fn read_length(input: &[u8], offset: usize) -> usize {
+		let word = padded(input, offset, 32);
+		if word[..24].iter().any(|byte| *byte != 0) {
+			return usize::max_value();
+		}
+		let mut bytes = [0u8; 8];
+		bytes.copy_from_slice(&word[24..]);
+		u64::from_be_bytes(bytes).min(usize::max_value() as u64) as usize
+	}
+
+	fn mult_complexity(x: u128) -> u128 {
+		if x <= 64 {
+			x * x
+		} else if x <= 1024 {
+			x * x / 4 + 96 * x - 3072
+		} else {
+			x * x / 16 + 480 * x - 199_680
+		}
+	}
+
+	/// The length of the exponent in bits minus one, where only the first 32 bytes count fully.
+	fn adjusted_exponent_length(exponent_length: usize, exponent_head: &BigUint) -> u128 {
+		let head_bits = exponent_head.bits().saturating_sub(1) as u128;
+		if exponent_length <= 32 {
+			head_bits
+		} else {
+			8 * (exponent_length as u128 - 32) + head_bits
+		}
+	}
+
+	fn cost(
+		base_length: usize,
+		exponent_length: usize,
+		modulus_length: usize,
+		exponent_head: &BigUint,
+	) -> usize {
+		let complexity = Self::mult_complexity(cmp::max(base_length, modulus_length) as u128);
+		let exponent = cmp::max(Self::adjusted_exponent_length(exponent_length, exponent_head), 1);
+		let cost = complexity.saturating_mul(exponent) / 20;
+		cost.min(usize::max_value() as u128) as usize
+	}
+}
+
+impl Precompile for Modexp {
+	fn execute(input: &[u8], target_gas: Option<usize>, _: &Context) -> PrecompileResult {
+		let base_length = Self::read_length(input, 0);
+		let exponent_length = Self::read_length(input, 32);
+		let modulus_length = Self::read_length(input, 64);
+
+		// Lengths beyond 2^32 can never be paid for, reject them before reading the input.
+		let max_length = u32::max_value() as usize;
+		if base_length > max_length || exponent_length > max_length || modulus_length > max_length {
+			return Err(ExitError::OutOfGas);
+		}
+
+		let exponent_offset = 96usize.saturating_add(base_length);
+		let head_length = cmp::min(exponent_length, 32);
+		let head = BigUint::from_bytes_be(&padded(input, exponent_offset, head_length));
+		let cost = ensure_gas(
+			Self::cost(base_length, exponent_length, modulus_length, &head),
+			target_gas,
+		)?;
+
+		let modulus_offset = exponent_offset.saturating_add(exponent_length);
+		let modulus = BigUint::from_bytes_be(&padded(input, modulus_offset, modulus_length));
+		let result = if modulus == BigUint::from(0u32) {
+			BigUint::from(0u32)
+		} else {
+			let base = BigUint::from_bytes_be(&padded(input, 96, base_length));
+			let exponent = BigUint::from_bytes_be(&padded(input, exponent_offset, exponent_length));
+			base.modpow(&exponent, &modulus)
+		};
+
+		// The result is left-padded to the length of the modulus.
+		let bytes = result.to_bytes_be();
+		let mut output = sp_std::vec![0u8; modulus_length];
+		if !bytes.iter().all(|byte| *byte == 0) {
+			output[modulus_length - bytes.len()..].copy_from_slice(&bytes);
+		}
+		Ok((ExitSucceed::Returned, output, cost))
+	}
+}
+
+/// The address of the [`Dispatch`] precompile, `0x0000000000000000000000000000000000000401`.
+///
+/// It is outside of the range Ethereum reserves for its own precompiles.
+pub const DISPATCH_ADDRESS: u64 = 0x401;
+
+/// Dispatches a SCALE encoded runtime call, at [`DISPATCH_ADDRESS`].
+///
+/// The call is dispatched with a signed origin of the account that `M` maps the caller to. The
+/// precompile charges the weight of the call as gas and fails if the call fails. It doesn't
+/// accept any value and can't be called with `DELEGATECALL` or `CALLCODE`.
+///
+/// Calls of this module are always rejected, as are calls `F` doesn't let through. The EVM
+/// account state is written back to storage once the execution is done, which would undo the
+/// changes a dispatched `withdraw_balance` made to it while keeping its currency. Calls which
+/// only reach this module through other modules, e.g. in a batch, fail as the module refuses to
+/// be entered again while an execution is in progress.
+///
+/// The storage changes of the call are not part of the EVM state and are only rolled back if the
+/// EVM execution as a whole fails or reverts. The precompile therefore fails unless it is called
+/// from the top-level frame of a call, see [`Module::is_top_level_frame`]. Contracts called by
+/// other contracts, including with `STATICCALL`, can't dispatch.
+pub struct Dispatch<T, M, F = ()>(PhantomData<(T, M, F)>);
+
+impl<T, M, F> Precompiles for Dispatch<T, M, F> where
+	T: Trait,
+	T::Call: Decode + GetDispatchInfo + Dispatchable<Origin = T::Origin> + IsSubType<Module<T>, T>,
+	M: AddressMapping<T::AccountId>,
+	F: Filter<T::Call>,
+{
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &Context,
+	) -> Option<PrecompileResult> {
+		if address != H160::from_low_u64_be(DISPATCH_ADDRESS) {
+			return None;
+		}
+		Some(<Self as Precompile>::execute(input, target_gas, context))
+	}
+}
+
+impl<T, M, F> Precompile for Dispatch<T, M, F> where
+	T: Trait,
+	T::Call: Decode + GetDispatchInfo + Dispatchable<Origin = T::Origin> + IsSubType<Module<T>, T>,
+	M: AddressMapping<T::AccountId>,
+	F: Filter<T::Call>,
+{
+	fn execute(input: &[u8], target_gas: Option<usize>, context: &Context) -> PrecompileResult {
+		// With `DELEGATECALL` the precompile runs in the context of the calling contract, which
+		// could dispatch on behalf of its own caller then.
+		if context.address != H160::from_low_u64_be(DISPATCH_ADDRESS) {
+			return Err(ExitError::Other("dispatch must be called directly".into()));
+		}
+		if !context.apparent_value.is_zero() {
+			return Err(ExitError::Other("dispatch doesn't accept value".into()));
+		}
+
+		let call = T::Call::decode(&mut &input[..])
+			.map_err(|_| ExitError::Other("decode failed".into()))?;
+		if call.is_sub_type().is_some() || !F::filter(&call) {
+			return Err(ExitError::Other("call filtered".into()));
+		}
+		if !Module::<T>::is_top_level_frame(context.caller) {
+			return Err(ExitError::Other("dispatch must be called from the top-level frame".into()));
+		}
+		let weight = call.get_dispatch_info().weight;
+		let gas = weight / WEIGHT_PER_GAS + if weight % WEIGHT_PER_GAS == 0 { 0 } else { 1 };
+		let cost = ensure_gas(gas.min(usize::max_value() as u64) as usize, target_gas)?;
+
+		let origin = frame_system::RawOrigin::Signed(M::into_account_id(context.caller));
+		with_transaction(|| match call.dispatch(origin.into()) {
+			Ok(_) => TransactionOutcome::Commit(Ok((ExitSucceed::Stopped, Vec::new(), cost))),
+			Err(_) => TransactionOutcome::Rollback(
+				Err(ExitError::Other("dispatch execution failed".into()))
+			),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::bytes::from_hex;
+
+	fn context() -> Context {
+		Context {
+			address: H160::zero(),
+			caller: H160::zero(),
+			apparent_value: 0.into(),
+		}
+	}
+
+	fn execute(address: u64, input: &str, target_gas: Option<usize>) -> PrecompileResult {
+		StandardPrecompiles::execute(
+			H160::from_low_u64_be(address),
+			&from_hex(input).unwrap(),
+			target_gas,
+			&context(),
+		).expect("standard precompile exists; qed")
+	}
+
+	#[test]
+	fn unknown_addresses_are_not_precompiles() {
+		let unknown = [0, 6, 0x401, 0x101];
+		for address in unknown.iter() {
+			assert!(StandardPrecompiles::execute(
+				H160::from_low_u64_be(*address), &[], None, &context(),
+			).is_none());
+		}
+		assert!(<() as Precompiles>::execute(
+			H160::from_low_u64_be(1), &[], None, &context(),
+		).is_none());
+	}
+
+	#[test]
+	fn ecrecover_works() {
+		let input = "0x\
+			456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3\
+			000000000000000000000000000000000000000000000000000000000000001c\
+			9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608\
+			4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada";
+		let (_, output, cost) = execute(1, input, None).unwrap();
+		assert_eq!(cost, 3000);
+		assert_eq!(
+			output,
+			from_hex("0x0000000000000000000000007156526fbd7a3c72969b54f64e42c10fbb768c8a").unwrap(),
+		);
+
+		// An invalid `v` recovers nothing.
+		let invalid = input.replace("001c9242", "001d9242");
+		assert_eq!(execute(1, &invalid, None).unwrap().1, Vec::<u8>::new());
+		assert_eq!(execute(1, input, Some(2999)), Err(ExitError::OutOfGas));
+	}
+
+	#[test]
+	fn hashes_work() {
+		assert_eq!(
+			execute(2, "0x", None).unwrap(),
+			(
+				ExitSucceed::Returned,
+				from_hex("0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855").unwrap(),
+				60,
+			),
+		);
+		assert_eq!(
+			execute(3, "0x", None).unwrap(),
+			(
+				ExitSucceed::Returned,
+				from_hex("0x0000000000000000000000009c1185a5c5e9fc54612808977ee8f548b2258d31").unwrap(),
+				600,
+			),
+		);
+		assert_eq!(execute(2, "0x00", None).unwrap().2, 72);
+		assert_eq!(execute(3, "0x00", Some(719)), Err(ExitError::OutOfGas));
+	}
+
+	#[test]
+	fn identity_works() {
+		let input = "0x00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff01";
+		assert_eq!(
+			execute(4, input, None).unwrap(),
+			(ExitSucceed::Returned, from_hex(input).unwrap(), 21),
+		);
+		assert_eq!(execute(4, input, Some(20)), Err(ExitError::OutOfGas));
+	}
+
+	#[test]
+	fn modexp_works() {
+		// 3 ^ (2^256 - 2^32 - 978) % (2^256 - 2^32 - 977), the example of EIP-198.
+		let input = "0x\
+			0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			03\
+			fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e\
+			fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+		assert_eq!(
+			execute(5, input, None).unwrap(),
+			(
+				ExitSucceed::Returned,
+				from_hex("0x0000000000000000000000000000000000000000000000000000000000000001").unwrap(),
+				13056,
+			),
+		);
+
+		// A zero modulus yields zero, missing input is padded with zeros.
+		let input = "0x\
+			0000000000000000000000000000000000000000000000000000000000000000\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e";
+		assert_eq!(execute(5, input, None).unwrap().1, sp_std::vec![0u8; 32]);
+
+		// Huge lengths can't be paid for.
+		let input = "0x\
+			0000000000000000000000000000000000000000000000000000000000000000\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+		assert_eq!(execute(5, input, None), Err(ExitError::OutOfGas));
+	}
+}
@@ -0,0 +1,683 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use std::collections::BTreeMap;
+use codec::Encode;
+use evm::{Context, ExitError, ExitSucceed};
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+	traits::{Filter, OnFinalize, OnInitialize},
+	unsigned::ValidateUnsigned,
+	weights::{GetDispatchInfo, RuntimeDbWeight},
+};
+use sp_runtime::{
+	Perbill,
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	transaction_validity::TransactionValidityError,
+};
+use crate::{
+	Call as EvmCall,
+	precompiles::{Dispatch, DISPATCH_ADDRESS},
+};
+
+/// Dispatches calls on behalf of its origin, like a batch of the utility module does.
+pub mod relay {
+	use frame_support::{decl_module, dispatch::DispatchResult, weights::PostDispatchInfo, Parameter};
+	use sp_runtime::traits::Dispatchable;
+
+	pub trait Trait: frame_system::Trait {
+		type Call: Parameter + Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo>;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+			#[weight = 0]
+			pub fn relay(origin, call: Box<<T as Trait>::Call>) -> DispatchResult {
+				call.dispatch(origin).map(|_| ()).map_err(|e| e.error)
+			}
+		}
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		pallet_balances::Balances,
+		self::EVM,
+		relay::Relay,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+	pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 10, write: 100 };
+}
+impl frame_system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Call = Call;
+	type Hashing = BlakeTwo256;
+	type AccountId = H256;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = DbWeight;
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+}
+
+pub struct FixedGasPrice;
+impl FeeCalculator for FixedGasPrice {
+	fn min_gas_price() -> U256 {
+		1.into()
+	}
+}
+
+parameter_types! {
+	pub const EvmModuleId: ModuleId = ModuleId(*b"py/evmpa");
+	pub const ReceiptsRetention: u64 = 10;
+	pub const BlockGasLimit: u32 = 50_000;
+	pub const ChainId: u64 = CHAIN_ID;
+}
+impl Trait for Test {
+	type ModuleId = EvmModuleId;
+	type FeeCalculator = FixedGasPrice;
+	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
+	type Currency = Balances;
+	type Event = ();
+	type Precompiles = (StandardPrecompiles, Dispatch<Test, TestAddressMapping>);
+	type ReceiptsRetention = ReceiptsRetention;
+	type BlockGasLimit = BlockGasLimit;
+	type ChainId = ChainId;
+}
+impl relay::Trait for Test {
+	type Call = Call;
+}
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type EVM = Module<Test>;
+type Relay = relay::Module<Test>;
+type TestAddressMapping = HashedAddressMapping<BlakeTwo256>;
+
+/// The chain id of the test runtime, which differs from the one of the test externalities.
+const CHAIN_ID: u64 = 1337;
+const BALANCE: u64 = 100_000;
+const TARGET: H160 = H160([2; 20]);
+const BOB: H256 = H256([9; 32]);
+
+/// Forwards its call data to the dispatch precompile with `CALL` and returns whether the call
+/// succeeded.
+const FORWARDER: H160 = H160([3; 20]);
+const FORWARDER_CODE: &str = "0x366000600037 6000600036600060006104015af1 60005260206000f3";
+/// Like `FORWARDER`, but reverts after the call.
+const REVERTING_FORWARDER: H160 = H160([4; 20]);
+const REVERTING_FORWARDER_CODE: &str = "0x366000600037 6000600036600060006104015af1 5060006000fd";
+/// Like `FORWARDER`, but uses `DELEGATECALL`.
+const DELEGATING_FORWARDER: H160 = H160([5; 20]);
+const DELEGATING_FORWARDER_CODE: &str = "0x366000600037 600060003660006104015af4 60005260206000f3";
+/// Forwards its call data to `REVERTING_FORWARDER` with `CALL` and returns whether the call
+/// succeeded.
+const NESTED_FORWARDER: H160 = H160([6; 20]);
+const NESTED_FORWARDER_CODE: &str =
+	"0x366000600037 600060003660006000730404040404040404040404040404040404040404 5af1 \
+	60005260206000f3";
+/// Forwards its call data to `FORWARDER` with `STATICCALL` and returns whether the call succeeded.
+const STATIC_FORWARDER: H160 = H160([7; 20]);
+const STATIC_FORWARDER_CODE: &str =
+	"0x366000600037 60006000366000730303030303030303030303030303030303030303 5afa \
+	60005260206000f3";
+
+/// The order of the secp256k1 curve.
+const SECP256K1N: [u8; 32] = [
+	0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+	0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+fn secret_key() -> secp256k1::SecretKey {
+	secp256k1::SecretKey::parse(&[1; 32]).unwrap()
+}
+
+fn sender() -> H160 {
+	let public = secp256k1::PublicKey::from_secret_key(&secret_key());
+	H160::from_slice(&Keccak256::digest(&public.serialize()[1..])[12..])
+}
+
+/// The account the dispatch precompile maps `address` to.
+fn account_of(address: H160) -> H256 {
+	<TestAddressMapping as AddressMapping<H256>>::into_account_id(address)
+}
+
+/// The EVM address the balances of `account` are deposited to.
+fn evm_address_of(account: &H256) -> H160 {
+	<Test as Trait>::ConvertAccountId::convert_account_id(account)
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let account = |code: &str| GenesisAccount {
+		nonce: 0.into(),
+		balance: BALANCE.into(),
+		storage: BTreeMap::new(),
+		code: sp_core::bytes::from_hex(&code.replace(' ', "")).unwrap(),
+	};
+	let mut accounts = BTreeMap::new();
+	accounts.insert(sender(), account("0x"));
+	accounts.insert(FORWARDER, account(FORWARDER_CODE));
+	accounts.insert(REVERTING_FORWARDER, account(REVERTING_FORWARDER_CODE));
+	accounts.insert(DELEGATING_FORWARDER, account(DELEGATING_FORWARDER_CODE));
+	accounts.insert(NESTED_FORWARDER, account(NESTED_FORWARDER_CODE));
+	accounts.insert(STATIC_FORWARDER, account(STATIC_FORWARDER_CODE));
+	accounts.insert(
+		evm_address_of(&account_of(FORWARDER)),
+		GenesisAccount { balance: (10 * BALANCE).into(), ..account("0x") },
+	);
+	GenesisConfig { accounts }.assimilate_storage(&mut t).unwrap();
+	// The accounts the forwarders dispatch from.
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![
+			(account_of(FORWARDER), 1_000),
+			(account_of(REVERTING_FORWARDER), 1_000),
+			(account_of(DELEGATING_FORWARDER), 1_000),
+			(account_of(sender()), 1_000),
+			// The currency backing the EVM balances.
+			(EVM::account_id(), 100 * BALANCE),
+		],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
+}
+
+/// A transfer of one unit to `TARGET` with a gas price of one, signed for `chain_id`.
+fn transaction(nonce: u64, chain_id: u64) -> Transaction {
+	sign(Transaction {
+		nonce: nonce.into(),
+		gas_price: 1.into(),
+		gas_limit: 21_000.into(),
+		action: TransactionAction::Call(TARGET),
+		value: 1.into(),
+		input: Vec::new(),
+		signature: TransactionSignature { v: chain_id * 2 + 35, r: H256::zero(), s: H256::zero() },
+	})
+}
+
+/// Signs the transaction for the chain id its `v` encodes.
+fn sign(mut transaction: Transaction) -> Transaction {
+	let chain_id = transaction.chain_id().expect("test transactions use EIP-155; qed");
+	let message = secp256k1::Message::parse(&transaction.signing_hash().0);
+	let (signature, recovery_id) = secp256k1::sign(&message, &secret_key());
+	let signature = signature.serialize();
+	transaction.signature = TransactionSignature {
+		v: chain_id * 2 + 35 + recovery_id.serialize() as u64,
+		r: H256::from_slice(&signature[0..32]),
+		s: H256::from_slice(&signature[32..64]),
+	};
+	transaction
+}
+
+fn validate(transaction: Transaction) -> TransactionValidity {
+	EVM::validate_unsigned(TransactionSource::External, &EvmCall::transact(transaction))
+}
+
+fn invalid(error: InvalidTransaction) -> TransactionValidity {
+	Err(TransactionValidityError::Invalid(error))
+}
+
+#[test]
+fn transact_works() {
+	new_test_ext().execute_with(|| {
+		let transaction = transaction(0, CHAIN_ID);
+		let hash = transaction.hash();
+		assert!(validate(transaction.clone()).is_ok());
+
+		assert_ok!(EVM::transact(Origin::NONE, transaction));
+		assert_eq!(EVM::accounts(sender()).nonce, 1.into());
+		assert_eq!(EVM::accounts(sender()).balance, (BALANCE - 21_000 - 1).into());
+		assert_eq!(EVM::accounts(TARGET).balance, 1.into());
+
+		let status = EVM::transaction_status(hash).unwrap();
+		assert_eq!((status.from, status.to, status.success), (sender(), Some(TARGET), true));
+		assert_eq!(status.gas_used, 21_000.into());
+		let receipts = EVM::block_receipts(0);
+		assert_eq!(receipts.len(), 1);
+		assert_eq!(receipts[0].transaction_hash, Some(hash));
+		assert_eq!(receipts[0].cumulative_gas_used, 21_000.into());
+	});
+}
+
+#[test]
+fn transact_weighs_gas_limit_and_storage() {
+	// Storing the receipt reads and writes the gas used, the receipt count and the logs bloom,
+	// and writes the receipt itself.
+	let receipt_weight = 3 * 10 + 4 * 100;
+	let info = EvmCall::<Test>::transact(transaction(0, CHAIN_ID)).get_dispatch_info();
+	assert_eq!(info.weight, 21_000 * WEIGHT_PER_GAS + receipt_weight + 100);
+	assert_eq!(info.pays_fee, Pays::No);
+
+	let info = EvmCall::<Test>::call(TARGET, Vec::new(), 0.into(), 30_000, 1.into(), None)
+		.get_dispatch_info();
+	assert_eq!(info.weight, 30_000 * WEIGHT_PER_GAS + receipt_weight);
+}
+
+#[test]
+fn transact_rejects_wrong_chain_id() {
+	new_test_ext().execute_with(|| {
+		let transaction = transaction(0, CHAIN_ID + 1);
+		assert_eq!(validate(transaction.clone()), invalid(InvalidTransaction::BadProof));
+		assert_noop!(EVM::transact(Origin::NONE, transaction), Error::<Test>::InvalidChainId);
+	});
+}
+
+#[test]
+fn transact_rejects_high_s_signature() {
+	new_test_ext().execute_with(|| {
+		// Negating `s` and flipping the recovery id yields another valid signature of the same
+		// message, which would change the hash of the transaction.
+		let mut transaction = transaction(0, CHAIN_ID);
+		let order = U256::from_big_endian(&SECP256K1N);
+		let s = order - U256::from_big_endian(transaction.signature.s.as_bytes());
+		s.to_big_endian(transaction.signature.s.as_mut());
+		let recovery_id = transaction.signature.v - (CHAIN_ID * 2 + 35);
+		transaction.signature.v = CHAIN_ID * 2 + 35 + (1 - recovery_id);
+
+		assert_eq!(validate(transaction.clone()), invalid(InvalidTransaction::BadProof));
+		assert_noop!(EVM::transact(Origin::NONE, transaction), Error::<Test>::InvalidSignature);
+	});
+}
+
+#[test]
+fn transact_rejects_stale_nonce() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(EVM::transact(Origin::NONE, transaction(0, CHAIN_ID)));
+
+		let stale = sign(Transaction { gas_price: 2.into(), ..transaction(0, CHAIN_ID) });
+		assert_eq!(validate(stale.clone()), invalid(InvalidTransaction::Stale));
+		assert_noop!(EVM::transact(Origin::NONE, stale), Error::<Test>::InvalidNonce);
+	});
+}
+
+#[test]
+fn transact_rejects_future_nonce() {
+	new_test_ext().execute_with(|| {
+		// The pool keeps future transactions until the previous nonce was used.
+		let valid = validate(transaction(0, CHAIN_ID)).unwrap();
+		assert!(valid.requires.is_empty());
+		let future = validate(transaction(1, CHAIN_ID)).unwrap();
+		assert_eq!(future.requires, valid.provides);
+
+		assert_noop!(
+			EVM::transact(Origin::NONE, transaction(1, CHAIN_ID)),
+			Error::<Test>::InvalidNonce,
+		);
+
+		// A block can't include them, as they would take up their weight without paying a fee
+		// and could be included again.
+		let pre_dispatch = |nonce| {
+			EVM::pre_dispatch(&EvmCall::transact(transaction(nonce, CHAIN_ID)))
+		};
+		assert_eq!(pre_dispatch(1), Err(InvalidTransaction::Future.into()));
+		assert_eq!(pre_dispatch(0), Ok(()));
+		assert_ok!(EVM::transact(Origin::NONE, transaction(0, CHAIN_ID)));
+		assert_eq!(pre_dispatch(0), Err(InvalidTransaction::Stale.into()));
+		assert_eq!(pre_dispatch(1), Ok(()));
+	});
+}
+
+#[test]
+fn transact_rejects_low_gas_price() {
+	new_test_ext().execute_with(|| {
+		let transaction = sign(Transaction { gas_price: 0.into(), ..transaction(0, CHAIN_ID) });
+		assert_eq!(validate(transaction.clone()), invalid(InvalidTransaction::Payment));
+		assert_noop!(EVM::transact(Origin::NONE, transaction), Error::<Test>::GasPriceTooLow);
+	});
+}
+
+#[test]
+fn executions_reject_low_gas_price() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			EVM::call(Origin::signed(BOB), TARGET, Vec::new(), 0.into(), 21_000, 0.into(), None),
+			Error::<Test>::GasPriceTooLow,
+		);
+		assert!(matches!(
+			EVM::execute_call(sender(), TARGET, Vec::new(), 0.into(), 21_000, 0.into(), None),
+			Err(Error::<Test>::GasPriceTooLow),
+		));
+	});
+}
+
+#[test]
+fn executions_pay_the_fee_up_front() {
+	new_test_ext().execute_with(|| {
+		// Returns the balance of the origin of the execution.
+		let code = sp_core::bytes::from_hex("0x323160005260206000f3").unwrap();
+		AccountCodes::insert(TARGET, code);
+
+		let info = EVM::execute_call(
+			sender(), TARGET, Vec::new(), 0.into(), 30_000, 2.into(), None,
+		).unwrap();
+		assert_eq!(info.exit_status, ExitStatus::Succeeded);
+		assert_eq!(U256::from_big_endian(&info.value), U256::from(BALANCE - 2 * 30_000));
+		// The unused gas is refunded.
+		assert_eq!(EVM::accounts(sender()).balance, U256::from(BALANCE) - info.used_gas * 2);
+	});
+}
+
+#[test]
+fn transact_rejects_insufficient_balance() {
+	new_test_ext().execute_with(|| {
+		// The balance covers the fee, but not the fee and the value.
+		let value = (BALANCE - 21_000 + 1).into();
+		let transaction = sign(Transaction { value, ..transaction(0, CHAIN_ID) });
+		assert_eq!(validate(transaction.clone()), invalid(InvalidTransaction::Payment));
+		assert_noop!(EVM::transact(Origin::NONE, transaction), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn transact_respects_block_gas_limit() {
+	new_test_ext().execute_with(|| {
+		let too_high = sign(Transaction { gas_limit: 50_001.into(), ..transaction(0, CHAIN_ID) });
+		assert_eq!(validate(too_high.clone()), invalid(InvalidTransaction::ExhaustsResources));
+		assert_noop!(EVM::transact(Origin::NONE, too_high), Error::<Test>::GasLimitTooHigh);
+
+		assert_ok!(EVM::transact(Origin::NONE, transaction(0, CHAIN_ID)));
+		assert_ok!(EVM::transact(Origin::NONE, transaction(1, CHAIN_ID)));
+		assert_eq!(EVM::block_gas_used(), 42_000.into());
+
+		// Only 8_000 gas are left in this block.
+		assert_eq!(
+			validate(transaction(2, CHAIN_ID)),
+			invalid(InvalidTransaction::ExhaustsResources),
+		);
+		assert_noop!(
+			EVM::transact(Origin::NONE, transaction(2, CHAIN_ID)),
+			Error::<Test>::BlockGasLimitReached,
+		);
+
+		EVM::on_finalize(0);
+		assert_eq!(EVM::block_gas_used(), U256::zero());
+		assert_ok!(EVM::transact(Origin::NONE, transaction(2, CHAIN_ID)));
+	});
+}
+
+fn transfer_to_bob(value: u64) -> Vec<u8> {
+	Call::Balances(pallet_balances::Call::transfer(BOB, value)).encode()
+}
+
+/// Calls `contract` from the sender with the given input.
+fn forward(contract: H160, input: Vec<u8>) -> CallInfo {
+	EVM::execute_call(sender(), contract, input, 0.into(), 30_000, 1.into(), None).unwrap()
+}
+
+fn dispatch(input: &[u8], target_gas: Option<usize>, context: Context) -> PrecompileResult {
+	<Dispatch<Test, TestAddressMapping> as Precompile>::execute(input, target_gas, &context)
+}
+
+fn dispatch_context(caller: H160) -> Context {
+	Context {
+		address: H160::from_low_u64_be(DISPATCH_ADDRESS),
+		caller,
+		apparent_value: 0.into(),
+	}
+}
+
+#[test]
+fn dispatch_works() {
+	new_test_ext().execute_with(|| {
+		let info = forward(FORWARDER, transfer_to_bob(100));
+		assert_eq!(info.exit_status, ExitStatus::Succeeded);
+		assert_eq!(info.value, H256::from_low_u64_be(1).as_bytes());
+		assert_eq!(Balances::free_balance(BOB), 100);
+		assert_eq!(Balances::free_balance(account_of(FORWARDER)), 900);
+
+		// A failing call fails the precompile.
+		let info = forward(FORWARDER, transfer_to_bob(1_000));
+		assert_eq!(info.value, H256::zero().as_bytes());
+		assert_eq!(Balances::free_balance(BOB), 100);
+	});
+}
+
+#[test]
+fn dispatch_rejects_delegatecall() {
+	new_test_ext().execute_with(|| {
+		// The delegating contract would dispatch on behalf of its caller, the sender.
+		let info = forward(DELEGATING_FORWARDER, transfer_to_bob(100));
+		assert_eq!(info.exit_status, ExitStatus::Succeeded);
+		assert_eq!(info.value, H256::zero().as_bytes());
+		assert_eq!(Balances::free_balance(BOB), 0);
+
+		let context = Context { address: FORWARDER, ..dispatch_context(sender()) };
+		assert_eq!(
+			dispatch(&transfer_to_bob(100), None, context),
+			Err(ExitError::Other("dispatch must be called directly".into())),
+		);
+	});
+}
+
+#[test]
+fn dispatch_rejects_value() {
+	new_test_ext().execute_with(|| {
+		let context = Context { apparent_value: 1.into(), ..dispatch_context(FORWARDER) };
+		assert_eq!(
+			dispatch(&transfer_to_bob(100), None, context),
+			Err(ExitError::Other("dispatch doesn't accept value".into())),
+		);
+		assert_eq!(Balances::free_balance(BOB), 0);
+	});
+}
+
+#[test]
+fn dispatch_rejects_undecodable_call() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			dispatch(&[0xff, 0xff], None, dispatch_context(FORWARDER)),
+			Err(ExitError::Other("decode failed".into())),
+		);
+	});
+}
+
+#[test]
+fn dispatch_charges_weight_as_gas() {
+	new_test_ext().execute_with(|| {
+		let input = transfer_to_bob(100);
+		let weight = Call::decode(&mut &input[..]).unwrap().get_dispatch_info().weight;
+		let gas = ((weight + WEIGHT_PER_GAS - 1) / WEIGHT_PER_GAS) as usize;
+
+		assert_eq!(
+			dispatch(&input, Some(gas - 1), dispatch_context(FORWARDER)),
+			Err(ExitError::OutOfGas),
+		);
+		assert_eq!(Balances::free_balance(BOB), 0);
+		TopLevelFrame::put((FORWARDER, 1));
+		assert_eq!(
+			dispatch(&input, Some(gas), dispatch_context(FORWARDER)),
+			Ok((ExitSucceed::Stopped, Vec::new(), gas)),
+		);
+		assert_eq!(Balances::free_balance(BOB), 100);
+		TopLevelFrame::kill();
+
+		// Like any failing call, a failed dispatch consumes all the gas passed to it, while a
+		// successful one only costs its weight.
+		let failed = forward(FORWARDER, transfer_to_bob(BALANCE)).used_gas;
+		assert!(failed > 29_000.into(), "{}", failed);
+		let dispatched = forward(FORWARDER, input).used_gas;
+		assert!(dispatched < 23_000.into(), "{}", dispatched);
+	});
+}
+
+#[test]
+fn dispatch_is_rolled_back_with_execution() {
+	new_test_ext().execute_with(|| {
+		let info = forward(REVERTING_FORWARDER, transfer_to_bob(100));
+		assert_eq!(info.exit_status, ExitStatus::Reverted);
+		assert_eq!(Balances::free_balance(BOB), 0);
+		assert_eq!(Balances::free_balance(account_of(REVERTING_FORWARDER)), 1_000);
+	});
+}
+
+#[test]
+fn dispatch_is_rejected_outside_of_top_level_frame() {
+	new_test_ext().execute_with(|| {
+		// The reverting forwarder is called by another contract, so its revert would undo the EVM
+		// side of the dispatch but keep the transfer.
+		let info = forward(NESTED_FORWARDER, transfer_to_bob(100));
+		assert_eq!(info.exit_status, ExitStatus::Succeeded);
+		assert_eq!(info.value, H256::zero().as_bytes());
+		assert_eq!(Balances::free_balance(BOB), 0);
+		assert_eq!(Balances::free_balance(account_of(REVERTING_FORWARDER)), 1_000);
+
+		// Neither can a contract dispatch within a static call.
+		let info = forward(STATIC_FORWARDER, transfer_to_bob(100));
+		assert_eq!(info.exit_status, ExitStatus::Succeeded);
+		assert_eq!(info.value, H256::from_low_u64_be(1).as_bytes());
+		assert_eq!(Balances::free_balance(BOB), 0);
+		assert_eq!(Balances::free_balance(account_of(FORWARDER)), 1_000);
+
+		// Nor outside of an execution.
+		assert_eq!(
+			dispatch(&transfer_to_bob(100), None, dispatch_context(FORWARDER)),
+			Err(ExitError::Other("dispatch must be called from the top-level frame".into())),
+		);
+	});
+}
+
+#[test]
+fn dispatch_cannot_inflate_evm_balance() {
+	new_test_ext().execute_with(|| {
+		let account = account_of(FORWARDER);
+		let address = evm_address_of(&account);
+		let withdraw = || Call::EVM(EvmCall::withdraw_balance(500));
+		let relayed = |call: Call| Call::Relay(relay::Call::relay(Box::new(call)));
+		let calls = vec![
+			withdraw(),
+			relayed(withdraw()),
+			relayed(Call::EVM(EvmCall::deposit_balance(500))),
+			relayed(Call::EVM(EvmCall::call(TARGET, Vec::new(), 0.into(), 21_000, 1.into(), None))),
+		];
+
+		// The account paying for the execution is cached by the executor and written back once
+		// it is done, undoing any change made to it by a dispatched call.
+		let mut balance = U256::from(10 * BALANCE);
+		for call in calls {
+			let info = EVM::execute_call(
+				address, FORWARDER, call.encode(), 0.into(), 50_000, 1.into(), None,
+			).unwrap();
+			assert_eq!(info.exit_status, ExitStatus::Succeeded);
+			assert_eq!(info.value, H256::zero().as_bytes());
+			balance -= info.used_gas;
+			assert_eq!(EVM::accounts(address).balance, balance);
+			assert_eq!(Balances::free_balance(account), 1_000);
+		}
+
+		// Outside of an execution the calls work as usual.
+		assert_ok!(Relay::relay(Origin::signed(account), Box::new(withdraw())));
+		assert_eq!(EVM::accounts(address).balance, balance - 500);
+		assert_eq!(Balances::free_balance(account), 1_500);
+	});
+}
+
+#[test]
+fn dispatch_rejects_filtered_calls() {
+	struct NoTransfers;
+	impl Filter<Call> for NoTransfers {
+		fn filter(call: &Call) -> bool {
+			!matches!(call, Call::Balances(pallet_balances::Call::transfer(..)))
+		}
+	}
+
+	new_test_ext().execute_with(|| {
+		let context = dispatch_context(FORWARDER);
+		assert_eq!(
+			<Dispatch<Test, TestAddressMapping, NoTransfers> as Precompile>::execute(
+				&transfer_to_bob(100), None, &context,
+			),
+			Err(ExitError::Other("call filtered".into())),
+		);
+		assert_eq!(
+			dispatch(&Call::EVM(EvmCall::withdraw_balance(500)).encode(), None, context),
+			Err(ExitError::Other("call filtered".into())),
+		);
+		assert_eq!(Balances::free_balance(BOB), 0);
+	});
+}
+
+#[test]
+fn receipts_are_pruned_after_retention() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let first = transaction(0, CHAIN_ID);
+		let second = transaction(1, CHAIN_ID);
+		assert_ok!(EVM::transact(Origin::NONE, first.clone()));
+		assert_ok!(EVM::transact(Origin::NONE, second.clone()));
+		EVM::on_finalize(1);
+
+		let receipts = EVM::block_receipts(1);
+		let hashes: Vec<_> = receipts.iter().map(|receipt| receipt.transaction_hash).collect();
+		assert_eq!(hashes, vec![Some(first.hash()), Some(second.hash())]);
+		assert_eq!(receipts[1].cumulative_gas_used, 42_000.into());
+		assert!(EVM::block_receipt(1, 1).is_some());
+
+		// Receipts are kept for `ReceiptsRetention` blocks.
+		assert_eq!(EVM::on_initialize(10), 2 * 100);
+		assert_eq!(EVM::block_receipts(1).len(), 2);
+		assert_eq!(EVM::on_initialize(11), 2 * 10 + (1 + 2 + 2) * 100 + 2 * 100);
+		assert!(EVM::block_receipts(1).is_empty());
+		assert_eq!(EVM::transaction_status(first.hash()), None);
+		assert_eq!(EVM::transaction_status(second.hash()), None);
+	});
+}
@@ -0,0 +1,203 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ethereum transactions as they are signed by Ethereum wallets.
+
+use sp_std::vec::Vec;
+use codec::{Encode, Decode};
+use sp_core::{U256, H256, H160};
+use sp_runtime::RuntimeDebug;
+use rlp::{Rlp, RlpStream, DecoderError};
+use sha3::{Digest, Keccak256};
+
+/// Half the order of the secp256k1 curve.
+///
+/// Signatures with a higher `s` are rejected like on Ethereum since Homestead. Otherwise anyone
+/// could change the hash of a transaction by negating `s`.
+const SECP256K1N_HALF: [u8; 32] = [
+	0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+	0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// The action of an Ethereum transaction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum TransactionAction {
+	/// Call the account at the given address.
+	Call(H160),
+	/// Create a new contract from the transaction input.
+	Create,
+}
+
+impl rlp::Encodable for TransactionAction {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		// Encode the value directly, appending would count the action as two list items.
+		match self {
+			TransactionAction::Call(address) => s.encoder().encode_value(address.as_bytes()),
+			TransactionAction::Create => s.encoder().encode_value(&[]),
+		}
+	}
+}
+
+impl rlp::Decodable for TransactionAction {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.is_empty() {
+			Ok(TransactionAction::Create)
+		} else {
+			Ok(TransactionAction::Call(rlp.as_val()?))
+		}
+	}
+}
+
+/// The signature of an Ethereum transaction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct TransactionSignature {
+	/// The recovery id, including the chain id for EIP-155 transactions.
+	pub v: u64,
+	/// The `r` value of the signature.
+	pub r: H256,
+	/// The `s` value of the signature.
+	pub s: H256,
+}
+
+/// A signed Ethereum transaction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Transaction {
+	/// Nonce of the sender.
+	pub nonce: U256,
+	/// Gas price the sender pays.
+	pub gas_price: U256,
+	/// Maximum amount of gas the transaction may use.
+	pub gas_limit: U256,
+	/// Whether the transaction calls an account or creates a contract.
+	pub action: TransactionAction,
+	/// Value transferred along with the transaction.
+	pub value: U256,
+	/// Call data or init code of the transaction.
+	pub input: Vec<u8>,
+	/// Signature of the sender.
+	pub signature: TransactionSignature,
+}
+
+impl Transaction {
+	/// The hash by which Ethereum tooling refers to this transaction.
+	///
+	/// This is the Keccak-256 hash of the RLP encoded, signed transaction.
+	pub fn hash(&self) -> H256 {
+		H256::from_slice(Keccak256::digest(&rlp::encode(self)).as_slice())
+	}
+
+	/// The chain id the transaction was signed for.
+	///
+	/// Returns `None` for transactions that were signed without replay protection, i.e. not
+	/// according to EIP-155.
+	pub fn chain_id(&self) -> Option<u64> {
+		match self.signature.v {
+			v if v >= 35 => Some((v - 35) / 2),
+			_ => None,
+		}
+	}
+
+	/// The hash that was signed by the sender.
+	pub fn signing_hash(&self) -> H256 {
+		let mut s = RlpStream::new();
+		match self.chain_id() {
+			Some(chain_id) => {
+				s.begin_list(9);
+				self.append_unsigned_fields(&mut s);
+				s.append(&chain_id);
+				s.append(&0u8);
+				s.append(&0u8);
+			}
+			None => {
+				s.begin_list(6);
+				self.append_unsigned_fields(&mut s);
+			}
+		}
+		H256::from_slice(Keccak256::digest(&s.out()).as_slice())
+	}
+
+	/// Recovers the address of the sender from the signature.
+	///
+	/// Returns `None` if the signature is invalid.
+	pub fn recover_signer(&self) -> Option<H160> {
+		let recovery_id = match self.chain_id() {
+			Some(chain_id) => self.signature.v - 35 - chain_id * 2,
+			None => self.signature.v.checked_sub(27)?,
+		};
+		if recovery_id > 1 || self.signature.s > H256::from(SECP256K1N_HALF) {
+			return None;
+		}
+
+		let mut signature = [0u8; 65];
+		signature[0..32].copy_from_slice(self.signature.r.as_bytes());
+		signature[32..64].copy_from_slice(self.signature.s.as_bytes());
+		signature[64] = recovery_id as u8;
+
+		let public = sp_io::crypto::secp256k1_ecdsa_recover(&signature, &self.signing_hash().0)
+			.ok()?;
+		Some(H160::from_slice(&Keccak256::digest(&public)[12..]))
+	}
+
+	fn append_unsigned_fields(&self, s: &mut RlpStream) {
+		s.append(&self.nonce);
+		s.append(&self.gas_price);
+		s.append(&self.gas_limit);
+		s.append(&self.action);
+		s.append(&self.value);
+		s.append(&self.input);
+	}
+}
+
+impl rlp::Encodable for Transaction {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(9);
+		self.append_unsigned_fields(s);
+		s.append(&self.signature.v);
+		s.append(&U256::from_big_endian(self.signature.r.as_bytes()));
+		s.append(&U256::from_big_endian(self.signature.s.as_bytes()));
+	}
+}
+
+impl rlp::Decodable for Transaction {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 9 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+
+		// `r` and `s` are encoded as integers, so leading zeros are stripped.
+		let signature_value = |index| -> Result<H256, DecoderError> {
+			let value: U256 = rlp.val_at(index)?;
+			let mut bytes = [0u8; 32];
+			value.to_big_endian(&mut bytes);
+			Ok(H256::from(bytes))
+		};
+
+		Ok(Transaction {
+			nonce: rlp.val_at(0)?,
+			gas_price: rlp.val_at(1)?,
+			gas_limit: rlp.val_at(2)?,
+			action: rlp.val_at(3)?,
+			value: rlp.val_at(4)?,
+			input: rlp.val_at(5)?,
+			signature: TransactionSignature {
+				v: rlp.val_at(6)?,
+				r: signature_value(7)?,
+				s: signature_value(8)?,
+			},
+		})
+	}
+}
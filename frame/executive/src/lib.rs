@@ -205,6 +205,37 @@ where
 	OriginOf<Block::Extrinsic, Context>: From<Option<System::AccountId>>,
 	UnsignedValidator: ValidateUnsigned<Call=CallOf<Block::Extrinsic, Context>>,
 {
+	/// Execute all `OnRuntimeUpgrade` of this runtime, and return the aggregate weight.
+	pub fn execute_on_runtime_upgrade() -> frame_support::weights::Weight {
+		// System is not part of `AllModules`, so we need to call this manually.
+		let mut weight = <frame_system::Module::<System> as OnRuntimeUpgrade>::on_runtime_upgrade();
+		weight = weight.saturating_add(COnRuntimeUpgrade::on_runtime_upgrade());
+		weight.saturating_add(<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade())
+	}
+
+	/// Execute all `OnRuntimeUpgrade` of this runtime, surrounded by the `pre_upgrade` and
+	/// `post_upgrade` checks of all modules, and return the aggregate weight.
+	///
+	/// This is only meant to be used by testing tools and must never be executed on-chain.
+	#[cfg(feature = "try-runtime")]
+	pub fn try_runtime_upgrade() -> Result<frame_support::weights::Weight, &'static str> {
+		<
+			(frame_system::Module<System>, COnRuntimeUpgrade, AllModules)
+			as
+			OnRuntimeUpgrade
+		>::pre_upgrade()?;
+
+		let weight = Self::execute_on_runtime_upgrade();
+
+		<
+			(frame_system::Module<System>, COnRuntimeUpgrade, AllModules)
+			as
+			OnRuntimeUpgrade
+		>::post_upgrade()?;
+
+		Ok(weight)
+	}
+
 	/// Start the execution of a particular block.
 	pub fn initialize_block(header: &System::Header) {
 		let digests = Self::extract_pre_digest(&header);
@@ -233,10 +264,7 @@ where
 		digest: &Digest<System::Hash>,
 	) {
 		if Self::runtime_upgraded() {
-			// System is not part of `AllModules`, so we need to call this manually.
-			let mut weight = <frame_system::Module::<System> as OnRuntimeUpgrade>::on_runtime_upgrade();
-			weight = weight.saturating_add(COnRuntimeUpgrade::on_runtime_upgrade());
-			weight = weight.saturating_add(<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade());
+			let weight = Self::execute_on_runtime_upgrade();
 			<frame_system::Module<System>>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
 		}
 		<frame_system::Module<System>>::initialize(
@@ -503,6 +531,14 @@ mod tests {
 					sp_io::storage::set(super::TEST_KEY, "module".as_bytes());
 					0
 				}
+
+				fn post_upgrade() -> Result<(), &'static str> {
+					frame_support::ensure!(
+						sp_io::storage::get(super::TEST_KEY).is_some(),
+						"module was not upgraded",
+					);
+					Ok(())
+				}
 			}
 		}
 	}
@@ -1028,4 +1064,22 @@ mod tests {
 			assert_eq!(sp_io::storage::get(CUSTOM_ON_RUNTIME_KEY).unwrap(), true.encode());
 		});
 	}
+
+	#[test]
+	fn execute_on_runtime_upgrade_runs_all_upgrades() {
+		new_test_ext(1).execute_with(|| {
+			assert_eq!(Executive::execute_on_runtime_upgrade(), 0);
+
+			assert_eq!(&sp_io::storage::get(TEST_KEY).unwrap()[..], *b"module");
+			assert_eq!(sp_io::storage::get(CUSTOM_ON_RUNTIME_KEY).unwrap(), true.encode());
+		});
+	}
+
+	#[test]
+	#[cfg(feature = "try-runtime")]
+	fn try_runtime_upgrade_runs_checks() {
+		new_test_ext(1).execute_with(|| {
+			assert_eq!(Executive::try_runtime_upgrade(), Ok(0));
+		});
+	}
 }
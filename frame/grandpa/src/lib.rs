@@ -347,6 +347,14 @@ impl<T: Trait> Module<T> {
 		storage::unhashed::get_or_default::<VersionedAuthorityList>(GRANDPA_AUTHORITIES_KEY).into()
 	}
 
+	/// Get the authority set change that was signaled but isn't enacted yet, if any.
+	pub fn grandpa_pending_change() -> Option<ScheduledChange<T::BlockNumber>> {
+		<PendingChange<T>>::get().map(|change| ScheduledChange {
+			next_authorities: change.next_authorities,
+			delay: change.delay,
+		})
+	}
+
 	/// Set the current set of authorities, along with their respective weights.
 	fn set_grandpa_authorities(authorities: &AuthorityList) {
 		storage::unhashed::put(
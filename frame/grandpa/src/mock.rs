@@ -229,6 +229,15 @@ impl staking::Trait for Test {
 	type Call = Call;
 	type UnsignedPriority = StakingUnsignedPriority;
 	type MaxIterations = ();
+	type Solver = sp_phragmen::SequentialPhragmen;
+	type ElectionProvider = sp_election_providers::onchain::OnChainSequentialPhragmen<Self>;
+}
+
+impl sp_election_providers::onchain::Config for Test {
+	type AccountId = u64;
+	type BlockNumber = u64;
+	type Accuracy = sp_runtime::Perbill;
+	type DataProvider = Staking;
 }
 
 parameter_types! {
@@ -300,6 +300,11 @@ pub enum StorageEntryType {
 		value: DecodeDifferentStr,
 		key2_hasher: StorageHasher,
 	},
+	NMap {
+		keys: DecodeDifferentArray<&'static str, StringBuf>,
+		hashers: DecodeDifferentArray<StorageHasher>,
+		value: DecodeDifferentStr,
+	},
 }
 
 /// A storage entry modifier.
@@ -364,6 +369,12 @@ pub enum RuntimeMetadata {
 	V10(RuntimeMetadataDeprecated),
 	/// Version 11 for runtime metadata.
 	V11(RuntimeMetadataV11),
+	/// Version 12 for runtime metadata.
+	///
+	/// Adds `StorageEntryType::NMap`. Decoders of version 11 don't know that storage entry type,
+	/// so the version is bumped to make them fail on the version instead of in the middle of the
+	/// storage metadata.
+	V12(RuntimeMetadataV12),
 }
 
 /// Enum that should fail.
@@ -387,15 +398,21 @@ impl Decode for RuntimeMetadataDeprecated {
 /// The metadata of a runtime.
 #[derive(Eq, Encode, PartialEq, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Decode, Serialize))]
-pub struct RuntimeMetadataV11 {
+pub struct RuntimeMetadataV12 {
 	/// Metadata of all the modules.
 	pub modules: DecodeDifferentArray<ModuleMetadata>,
 	/// Metadata of the extrinsic.
 	pub extrinsic: ExtrinsicMetadata,
 }
 
+/// The metadata of a runtime, in version 11.
+///
+/// Version 12 only adds a storage entry type, so version 11 metadata decodes with the types of
+/// version 12.
+pub type RuntimeMetadataV11 = RuntimeMetadataV12;
+
 /// The latest version of the metadata.
-pub type RuntimeMetadataLastVersion = RuntimeMetadataV11;
+pub type RuntimeMetadataLastVersion = RuntimeMetadataV12;
 
 /// All metadata about an runtime module.
 #[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
@@ -420,6 +437,6 @@ impl Into<sp_core::OpaqueMetadata> for RuntimeMetadataPrefixed {
 
 impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataLastVersion {
 	fn into(self) -> RuntimeMetadataPrefixed {
-		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V11(self))
+		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V12(self))
 	}
 }
@@ -0,0 +1,877 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Multisig Module
+//! A module for doing multisig dispatch.
+//!
+//! - [`multisig::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! This module contains functionality for multi-signature dispatch, a (potentially) stateful
+//! operation, allowing multiple signed origins (accounts) to coordinate and dispatch a call from
+//! a well-known origin, derivable deterministically from the set of account IDs and the threshold
+//! number of accounts from the set that must approve it. In the case that the threshold is just
+//! one then this is a stateless operation. This is useful for multisig wallets where
+//! cryptographic threshold signatures are not available or desired.
+//!
+//! The encoded call may optionally be stored on-chain, keyed by its hash, in return for a deposit.
+//! Later approvers then need only provide the hash, and the final approval dispatches the stored
+//! call.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `as_multi` - Approve and if possible dispatch a call from a composite origin formed from a
+//!   number of signed origins.
+//! * `approve_as_multi` - Approve a call from a composite origin.
+//! * `cancel_as_multi` - Cancel a call from a composite origin.
+//! * `as_multi_threshold_1` - Dispatch a call from a composite origin with a threshold of one.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use sp_io::hashing::blake2_256;
+use frame_support::{decl_module, decl_event, decl_error, decl_storage, Parameter, ensure, RuntimeDebug};
+use frame_support::{traits::{Get, ReservableCurrency, Currency, Filter},
+	weights::{Weight, GetDispatchInfo, DispatchClass},
+	dispatch::{DispatchResultWithPostInfo, DispatchErrorWithPostInfo, PostDispatchInfo},
+	storage::migration::StorageIterator,
+};
+use frame_system::{self as system, ensure_signed};
+use sp_runtime::{DispatchError, DispatchResult, traits::{Dispatchable, Zero}};
+
+mod tests;
+mod benchmarking;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+/// Just a bunch of bytes, but they should decode to a valid `Call`.
+pub type OpaqueCall = Vec<u8>;
+
+/// Configuration trait.
+pub trait Trait: frame_system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The overarching call type.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin, PostInfo=PostDispatchInfo>
+		+ GetDispatchInfo + From<frame_system::Call<Self>>;
+
+	/// The currency mechanism.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The base amount of currency needed to reserve for creating a multisig execution or to store
+	/// a dispatch call for later.
+	///
+	/// This is held for an additional storage item whose value size is
+	/// `4 + sizeof((BlockNumber, Balance, AccountId))` bytes and whose key size is
+	/// `32 + sizeof(AccountId)` bytes.
+	type DepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount of currency needed per unit threshold when creating a multisig execution.
+	///
+	/// This is held for adding 32 bytes more into a pre-existing storage value. It is also held
+	/// for each 32 bytes of a call that is stored on-chain.
+	type DepositFactor: Get<BalanceOf<Self>>;
+
+	/// The maximum amount of signatories allowed in the multisig.
+	type MaxSignatories: Get<u16>;
+
+	/// Is a given call compatible with the multisig subsystem?
+	type IsCallable: Filter<<Self as Trait>::Call>;
+}
+
+/// A global extrinsic index, formed as the extrinsic index within a block, together with that
+/// block's height. This allows a transaction in which a multisig operation of a particular
+/// composite was created to be uniquely identified.
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+pub struct Timepoint<BlockNumber> {
+	/// The height of the chain at the point in time.
+	pub height: BlockNumber,
+	/// The index of the extrinsic at the point in time.
+	pub index: u32,
+}
+
+/// An open multisig operation.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+pub struct Multisig<BlockNumber, Balance, AccountId> {
+	/// The extrinsic when the multisig operation was opened.
+	when: Timepoint<BlockNumber>,
+	/// The amount held in reserve of the `depositor`, to be returned once the operation ends.
+	deposit: Balance,
+	/// The account who opened it (i.e. the first to approve it).
+	depositor: AccountId,
+	/// The approvals achieved so far, including the depositor. Always sorted.
+	approvals: Vec<AccountId>,
+}
+
+// A value placed in storage that represents the current version of the Multisig storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// Open multisig operations live in this module rather than in `Utility`.
+	V1,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Multisig {
+		/// The set of open multisig operations.
+		pub Multisigs: double_map
+			hasher(twox_64_concat) T::AccountId, hasher(blake2_128_concat) [u8; 32]
+			=> Option<Multisig<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
+
+		/// The encoded calls stored for open multisig operations, along with the account that
+		/// stored each and the deposit held for it. Keyed by the multisig account, so that an
+		/// account cannot hold up or remove the calls of another multisig with the same hash.
+		pub Calls: double_map
+			hasher(twox_64_concat) T::AccountId, hasher(identity) [u8; 32]
+			=> Option<(OpaqueCall, T::AccountId, BalanceOf<T>)>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with last version.
+		StorageVersion build(|_| Some(Releases::V1)): Option<Releases>;
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Threshold must be 2 or greater.
+		MinimumThreshold,
+		/// Call is already approved by this signatory.
+		AlreadyApproved,
+		/// Call doesn't need any (more) approvals.
+		NoApprovalsNeeded,
+		/// There are too few signatories in the list.
+		TooFewSignatories,
+		/// There are too many signatories in the list.
+		TooManySignatories,
+		/// The signatories were provided out of order; they should be ordered.
+		SignatoriesOutOfOrder,
+		/// The sender was contained in the other signatories; it shouldn't be.
+		SenderInSignatories,
+		/// Multisig operation not found when attempting to cancel.
+		NotFound,
+		/// Only the account that originally created the multisig is able to cancel it.
+		NotOwner,
+		/// No timepoint was given, yet the multisig operation is already underway.
+		NoTimepoint,
+		/// A different timepoint was given to the multisig operation that is underway.
+		WrongTimepoint,
+		/// A timepoint was given, yet no multisig operation is underway.
+		UnexpectedTimepoint,
+		/// A call with a `false` IsCallable filter was attempted.
+		Uncallable,
+		/// The maximum weight information provided was too low.
+		WeightTooLow,
+		/// The data to be stored is already stored.
+		AlreadyStored,
+	}
+}
+
+decl_event! {
+	/// Events type.
+	pub enum Event<T> where
+		AccountId = <T as system::Trait>::AccountId,
+		BlockNumber = <T as system::Trait>::BlockNumber,
+		CallHash = [u8; 32]
+	{
+		/// A new multisig operation has begun. First param is the account that is approving,
+		/// second is the multisig account, third is hash of the call.
+		NewMultisig(AccountId, AccountId, CallHash),
+		/// A multisig operation has been approved by someone. First param is the account that is
+		/// approving, third is the multisig account, fourth is hash of the call.
+		MultisigApproval(AccountId, Timepoint<BlockNumber>, AccountId, CallHash),
+		/// A multisig operation has been executed. First param is the account that is
+		/// approving, third is the multisig account, fourth is hash of the call to be executed.
+		MultisigExecuted(AccountId, Timepoint<BlockNumber>, AccountId, CallHash, DispatchResult),
+		/// A multisig operation has been cancelled. First param is the account that is
+		/// cancelling, third is the multisig account, fourth is hash of the call.
+		MultisigCancelled(AccountId, Timepoint<BlockNumber>, AccountId, CallHash),
+		/// The call of a multisig operation has been stored on-chain. First param is the account
+		/// that stored it, second is the hash of the call.
+		CallStored(AccountId, CallHash),
+	}
+}
+
+enum CallOrHash {
+	Call(OpaqueCall, bool),
+	Hash([u8; 32]),
+}
+
+mod weight_of {
+	use super::*;
+
+	/// - Base Weight: 33.72 + 0.002 * S µs
+	/// - DB Weight: None
+	/// - Plus Call Weight
+	pub fn as_multi_threshold_1<T: Trait>(other_sig_len: usize, call_weight: Weight) -> Weight {
+		call_weight
+			.saturating_add(34_000_000)
+			.saturating_add((other_sig_len as Weight).saturating_mul(5_000))
+	}
+
+	/// - Base Weight: 71.85 + 0.129 * S + 0.002 * Z µs
+	/// - Plus, if storing the call: 17.4 + 0.001 * Z µs
+	/// - DB Weight:
+	///     - Reads: Multisig Storage, [Caller Account], [Calls]
+	///     - Writes: Multisig Storage, [Caller Account], [Calls]
+	pub fn as_multi_create<T: Trait>(other_sig_len: usize, call_len: usize, store: bool) -> Weight {
+		let weight = T::DbWeight::get().reads_writes(1, 1)
+			.saturating_add(72_000_000)
+			.saturating_add((other_sig_len as Weight).saturating_mul(130_000))
+			.saturating_add((call_len as Weight).saturating_mul(2_000));
+		if store {
+			weight.saturating_add(store_call::<T>(call_len))
+		} else {
+			weight
+		}
+	}
+
+	/// - Base Weight: 41.25 + 0.148 * S + 0.002 * Z µs
+	/// - Plus, if storing the call: 17.4 + 0.001 * Z µs
+	/// - DB Weight:
+	///     - Reads: Multisig Storage, [Calls]
+	///     - Writes: Multisig Storage, [Caller Account], [Calls]
+	pub fn as_multi_approve<T: Trait>(other_sig_len: usize, call_len: usize, store: bool) -> Weight {
+		let weight = T::DbWeight::get().reads_writes(1, 1)
+			.saturating_add(42_000_000)
+			.saturating_add((other_sig_len as Weight).saturating_mul(150_000))
+			.saturating_add((call_len as Weight).saturating_mul(2_000));
+		if store {
+			weight.saturating_add(store_call::<T>(call_len))
+		} else {
+			weight
+		}
+	}
+
+	/// - Base Weight: 89.47 + 0.254 * S + 0.005 * Z µs
+	/// - DB Weight:
+	///     - Reads: Multisig Storage, Calls, Depositor Account
+	///     - Writes: Multisig Storage, Calls, Depositor Account
+	/// - Plus Call Weight
+	pub fn as_multi_complete<T: Trait>(other_sig_len: usize, call_len: usize, call_weight: Weight) -> Weight {
+		call_weight
+			.saturating_add(T::DbWeight::get().reads_writes(3, 3))
+			.saturating_add(90_000_000)
+			.saturating_add((other_sig_len as Weight).saturating_mul(260_000))
+			.saturating_add((call_len as Weight).saturating_mul(5_000))
+	}
+
+	/// The worst case of `as_multi`, which is always the completing approval.
+	pub fn as_multi<T: Trait>(other_sig_len: usize, call_len: usize, call_weight: Weight) -> Weight {
+		as_multi_complete::<T>(other_sig_len, call_len, call_weight)
+			.max(as_multi_create::<T>(other_sig_len, call_len, true))
+			.max(as_multi_approve::<T>(other_sig_len, call_len, true))
+	}
+
+	/// - Base Weight:
+	///     - Create: 61.94 + 0.126 * S µs
+	///     - Approve: 36.72 + 0.152 * S µs
+	/// - DB Weight:
+	///     - Read: Multisig Storage, [Caller Account]
+	///     - Write: Multisig Storage, [Caller Account]
+	pub fn approve_as_multi<T: Trait>(other_sig_len: usize) -> Weight {
+		T::DbWeight::get().reads_writes(1, 1)
+			.saturating_add(62_000_000)
+			.saturating_add((other_sig_len as Weight).saturating_mul(155_000))
+	}
+
+	/// - Base Weight: 113.8 + 0.268 * S µs, plus decoding the stored call at 0.005 * Z µs.
+	/// - DB Weight:
+	///     - Reads: Multisig Storage, Calls, Depositor Account, Call Depositor Account
+	///     - Writes: Multisig Storage, Calls, Depositor Account, Call Depositor Account
+	/// - Plus Call Weight
+	pub fn approve_as_multi_complete<T: Trait>(other_sig_len: usize, call_len: usize, call_weight: Weight) -> Weight {
+		call_weight
+			.saturating_add(T::DbWeight::get().reads_writes(4, 4))
+			.saturating_add(114_000_000)
+			.saturating_add((other_sig_len as Weight).saturating_mul(270_000))
+			.saturating_add((call_len as Weight).saturating_mul(5_000))
+	}
+
+	/// - Base Weight: 58.72 + 0.131 * S µs
+	/// - DB Weight:
+	///     - Read: Multisig Storage, Calls, Depositor Account, [Call Depositor Account]
+	///     - Write: Multisig Storage, Calls, Depositor Account, [Call Depositor Account]
+	pub fn cancel_as_multi<T: Trait>(other_sig_len: usize) -> Weight {
+		T::DbWeight::get().reads_writes(3, 3)
+			.saturating_add(59_000_000)
+			.saturating_add((other_sig_len as Weight).saturating_mul(135_000))
+	}
+
+	/// - Base Weight: 17.4 + 0.001 * Z µs
+	/// - DB Weight:
+	///     - Read: Calls, [Caller Account]
+	///     - Write: Calls, [Caller Account]
+	fn store_call<T: Trait>(call_len: usize) -> Weight {
+		T::DbWeight::get().reads_writes(1, 1)
+			.saturating_add(18_000_000)
+			.saturating_add((call_len as Weight).saturating_mul(1_000))
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// Deposit one of this module's events by using the default implementation.
+		fn deposit_event() = default;
+
+		fn on_runtime_upgrade() -> Weight {
+			if let None = StorageVersion::get() {
+				StorageVersion::put(Releases::V1);
+				Self::migrate_from_utility().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		/// Ensure no multisig operations were left behind in the `Utility` module.
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				StorageIterator::<
+					Multisig<T::BlockNumber, BalanceOf<T>, T::AccountId>
+				>::new(b"Utility", b"Multisigs").next().is_none(),
+				"Multisig operations left in the Utility module",
+			);
+			Ok(())
+		}
+
+		/// Immediately dispatch a multi-signature call using a single approval from the caller.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `other_signatories`: The accounts (other than the sender) who are part of the
+		/// multi-signature, but do not participate in the approval process.
+		/// - `call`: The call to be executed.
+		///
+		/// Result is equivalent to the dispatched result.
+		///
+		/// # <weight>
+		/// - `O(S + Call)` where `S` is the number of signatories, capped by `MaxSignatories`.
+		/// - One encode & hash, both of complexity `O(S)`.
+		/// - The weight of the `call`.
+		/// -------------------------------
+		/// - Base Weight: 33.72 + 0.002 * S µs
+		/// - DB Weight: None
+		/// - Plus Call Weight
+		/// # </weight>
+		#[weight = {
+			let di = call.get_dispatch_info();
+			(weight_of::as_multi_threshold_1::<T>(other_signatories.len(), di.weight), di.class)
+		}]
+		fn as_multi_threshold_1(origin,
+			other_signatories: Vec<T::AccountId>,
+			call: Box<<T as Trait>::Call>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(T::IsCallable::filter(call.as_ref()), Error::<T>::Uncallable);
+			let max_sigs = T::MaxSignatories::get() as usize;
+			ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
+			let other_signatories_len = other_signatories.len();
+			ensure!(other_signatories_len < max_sigs, Error::<T>::TooManySignatories);
+			let signatories = Self::ensure_sorted_and_insert(other_signatories, who)?;
+
+			let id = Self::multi_account_id(&signatories, 1);
+
+			let result = call.dispatch(frame_system::RawOrigin::Signed(id).into());
+			result.map(|post_dispatch_info| post_dispatch_info.actual_weight
+				.map(|actual_weight| weight_of::as_multi_threshold_1::<T>(
+					other_signatories_len,
+					actual_weight,
+				))
+				.into()
+			).map_err(|err| match err.post_info.actual_weight {
+				Some(actual_weight) => {
+					let weight_used = weight_of::as_multi_threshold_1::<T>(
+						other_signatories_len,
+						actual_weight,
+					);
+					DispatchErrorWithPostInfo { post_info: Some(weight_used).into(), error: err.error }
+				},
+				None => err,
+			})
+		}
+
+		/// Register approval for a dispatch to be made from a deterministic composite account if
+		/// approved by a total of `threshold - 1` of `other_signatories`.
+		///
+		/// If there are enough, then dispatch the call. Calls must each fulfil the `IsCallable`
+		/// filter.
+		///
+		/// Payment: `DepositBase` will be reserved if this is the first approval, plus
+		/// `threshold` times `DepositFactor`. It is returned once this dispatch happens or
+		/// is cancelled. If the call is stored, a further `DepositBase` plus `DepositFactor` for
+		/// each 32 bytes of the call is reserved until then.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. May not be empty.
+		/// - `maybe_timepoint`: If this is the first approval, then this must be `None`. If it is
+		/// not the first approval, then it must be `Some`, with the timepoint (block number and
+		/// transaction index) of the first approval transaction.
+		/// - `call`: The encoded call to be executed.
+		/// - `store_call`: Whether to store the call on-chain, so that later approvals (including
+		/// the final one) need only provide its hash through `approve_as_multi`.
+		/// - `max_weight`: The maximum weight that the call may have when it is dispatched.
+		///
+		/// NOTE: Unless this is the final approval, you will generally want to use
+		/// `approve_as_multi` instead, since it only requires a hash of the call.
+		///
+		/// Result is equivalent to the dispatched result if `threshold` is exactly `1`. Otherwise
+		/// on success, result is `Ok` and the result from the interior call, if it was executed,
+		/// may be found in the deposited `MultisigExecuted` event.
+		///
+		/// # <weight>
+		/// - `O(S + Z + Call)`.
+		/// - Up to one balance-reserve or unreserve operation.
+		/// - One passthrough operation, one insert, both `O(S)` where `S` is the number of
+		///   signatories. `S` is capped by `MaxSignatories`, with weight being proportional.
+		/// - One call encode & hash, both of complexity `O(Z)` where `Z` is tx-len.
+		/// - One encode & hash, both of complexity `O(S)`.
+		/// - Up to one binary search and insert (`O(logS + S)`).
+		/// - I/O: 1 read `O(S)`, up to 1 mutate `O(S)`. Up to one remove.
+		/// - One event.
+		/// - The weight of the `call`.
+		/// - Storage: inserts one item, value size bounded by `MaxSignatories`, with a
+		///   deposit taken for its lifetime of
+		///   `DepositBase + threshold * DepositFactor`.
+		/// -------------------------------
+		/// - Base Weight:
+		///     - Create: 71.85 + 0.129 * S + 0.002 * Z µs
+		///     - Create w/ Store: 89.25 + 0.129 * S + 0.003 * Z µs
+		///     - Approve: 41.25 + 0.148 * S + 0.002 * Z µs
+		///     - Complete: 89.47 + 0.254 * S + 0.005 * Z µs
+		/// - DB Weight:
+		///     - Reads: Multisig Storage, [Caller Account], Calls (if `store_call`)
+		///     - Writes: Multisig Storage, [Caller Account], Calls (if `store_call`)
+		/// - Plus Call Weight
+		/// # </weight>
+		#[weight = (
+			weight_of::as_multi::<T>(other_signatories.len(), call.len(), *max_weight),
+			DispatchClass::Normal,
+		)]
+		fn as_multi(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+			call: OpaqueCall,
+			store_call: bool,
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::operate(
+				who,
+				threshold,
+				other_signatories,
+				maybe_timepoint,
+				CallOrHash::Call(call, store_call),
+				max_weight,
+			)
+		}
+
+		/// Register approval for a dispatch to be made from a deterministic composite account if
+		/// approved by a total of `threshold - 1` of `other_signatories`.
+		///
+		/// If this is the final approval and the call was stored on-chain by an earlier
+		/// approval, then the stored call is dispatched.
+		///
+		/// Payment: `DepositBase` will be reserved if this is the first approval, plus
+		/// `threshold` times `DepositFactor`. It is returned once this dispatch happens or
+		/// is cancelled.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. May not be empty.
+		/// - `maybe_timepoint`: If this is the first approval, then this must be `None`. If it is
+		/// not the first approval, then it must be `Some`, with the timepoint (block number and
+		/// transaction index) of the first approval transaction.
+		/// - `call_hash`: The hash of the call to be executed.
+		/// - `max_weight`: The maximum weight that the stored call may have when it is
+		/// dispatched. May be zero unless this is the final approval of a stored call.
+		///
+		/// NOTE: If this is the final approval and the call is not stored, you will want to use
+		/// `as_multi` instead.
+		///
+		/// # <weight>
+		/// - `O(S)`.
+		/// - Up to one balance-reserve or unreserve operation.
+		/// - One passthrough operation, one insert, both `O(S)` where `S` is the number of
+		///   signatories. `S` is capped by `MaxSignatories`, with weight being proportional.
+		/// - One encode & hash, both of complexity `O(S)`.
+		/// - Up to one binary search and insert (`O(logS + S)`).
+		/// - I/O: 1 read `O(S)`, up to 1 mutate `O(S)`. Up to one remove.
+		/// - One event.
+		/// - Storage: inserts one item, value size bounded by `MaxSignatories`, with a
+		///   deposit taken for its lifetime of
+		///   `DepositBase + threshold * DepositFactor`.
+		/// ----------------------------------
+		/// - Base Weight:
+		///     - Create: 61.94 + 0.126 * S µs
+		///     - Approve: 36.72 + 0.152 * S µs
+		///     - Complete: 113.8 + 0.268 * S µs
+		/// - DB Weight:
+		///     - Read: Multisig Storage, [Caller Account], [Calls], [Depositor Accounts]
+		///     - Write: Multisig Storage, [Caller Account], [Calls], [Depositor Accounts]
+		/// - Plus the weight of the stored call, if it is dispatched.
+		/// # </weight>
+		#[weight = (
+			weight_of::approve_as_multi::<T>(other_signatories.len())
+				.max(weight_of::approve_as_multi_complete::<T>(other_signatories.len(), 0, *max_weight)),
+			DispatchClass::Normal,
+		)]
+		fn approve_as_multi(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+			call_hash: [u8; 32],
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::operate(
+				who,
+				threshold,
+				other_signatories,
+				maybe_timepoint,
+				CallOrHash::Hash(call_hash),
+				max_weight,
+			)
+		}
+
+		/// Cancel a pre-existing, on-going multisig transaction. Any deposit reserved previously
+		/// for this operation will be unreserved on success, including that of a stored call.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. May not be empty.
+		/// - `timepoint`: The timepoint (block number and transaction index) of the first approval
+		/// transaction for this dispatch.
+		/// - `call_hash`: The hash of the call to be executed.
+		///
+		/// # <weight>
+		/// - `O(S)`.
+		/// - Up to two balance-unreserve operations.
+		/// - One passthrough operation, one insert, both `O(S)` where `S` is the number of
+		///   signatories. `S` is capped by `MaxSignatories`, with weight being proportional.
+		/// - One encode & hash, both of complexity `O(S)`.
+		/// - One event.
+		/// - I/O: 2 reads `O(S)`, two removes.
+		/// - Storage: removes one item, and a stored call if any.
+		/// ----------------------------------
+		/// - Base Weight: 58.72 + 0.131 * S µs
+		/// - DB Weight:
+		///     - Read: Multisig Storage, Calls, Depositor Account, [Call Depositor Account]
+		///     - Write: Multisig Storage, Calls, Depositor Account, [Call Depositor Account]
+		/// # </weight>
+		#[weight = weight_of::cancel_as_multi::<T>(other_signatories.len())]
+		fn cancel_as_multi(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			timepoint: Timepoint<T::BlockNumber>,
+			call_hash: [u8; 32],
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
+			let max_sigs = T::MaxSignatories::get() as usize;
+			ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
+			ensure!(other_signatories.len() < max_sigs, Error::<T>::TooManySignatories);
+			let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+
+			let id = Self::multi_account_id(&signatories, threshold);
+
+			let m = <Multisigs<T>>::get(&id, call_hash)
+				.ok_or(Error::<T>::NotFound)?;
+			ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
+			ensure!(m.depositor == who, Error::<T>::NotOwner);
+
+			let _ = T::Currency::unreserve(&m.depositor, m.deposit);
+			<Multisigs<T>>::remove(&id, call_hash);
+			Self::clear_call(&id, &call_hash);
+
+			Self::deposit_event(RawEvent::MultisigCancelled(who, timepoint, id, call_hash));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Derive a multi-account ID from the sorted list of accounts and the threshold that are
+	/// required.
+	///
+	/// NOTE: `who` must be sorted. If it is not, then you'll get the wrong answer.
+	pub fn multi_account_id(who: &[T::AccountId], threshold: u16) -> T::AccountId {
+		// The same prefix as when multisig dispatch lived in the utility module, so that existing
+		// multisig accounts are preserved.
+		let entropy = (b"modlpy/utilisuba", who, threshold).using_encoded(blake2_256);
+		T::AccountId::decode(&mut &entropy[..]).unwrap_or_default()
+	}
+
+	/// The current `Timepoint`.
+	pub fn timepoint() -> Timepoint<T::BlockNumber> {
+		Timepoint {
+			height: <system::Module<T>>::block_number(),
+			index: <system::Module<T>>::extrinsic_index().unwrap_or_default(),
+		}
+	}
+
+	fn operate(
+		who: T::AccountId,
+		threshold: u16,
+		other_signatories: Vec<T::AccountId>,
+		maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+		call_or_hash: CallOrHash,
+		max_weight: Weight,
+	) -> DispatchResultWithPostInfo {
+		ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
+		let max_sigs = T::MaxSignatories::get() as usize;
+		ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
+		let other_signatories_len = other_signatories.len();
+		ensure!(other_signatories_len < max_sigs, Error::<T>::TooManySignatories);
+		let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+
+		let id = Self::multi_account_id(&signatories, threshold);
+
+		// Threshold > 1; this means it's a multi-step operation. We extract the `call_hash`.
+		let (call_hash, call_len, maybe_call, store) = match call_or_hash {
+			CallOrHash::Call(call, should_store) => {
+				let call_hash = blake2_256(&call);
+				let call_len = call.len();
+				(call_hash, call_len, Some(call), should_store)
+			}
+			CallOrHash::Hash(h) => (h, 0, None, false),
+		};
+		let has_call = maybe_call.is_some();
+
+		if let Some(mut m) = <Multisigs<T>>::get(&id, call_hash) {
+			// The operation is underway; ensure that the timepoint exists and agrees.
+			let timepoint = maybe_timepoint.ok_or(Error::<T>::NoTimepoint)?;
+			ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
+
+			// We only bother with the approval if we're below threshold.
+			let already_approved = m.approvals.binary_search(&who);
+			let mut approvals = m.approvals.len() as u16;
+			let maybe_pos = already_approved.err().filter(|_| approvals < threshold);
+			if maybe_pos.is_some() {
+				approvals += 1;
+			}
+
+			// We only bother fetching and decoding the call if we're ready to execute.
+			let maybe_approved_call = if approvals >= threshold {
+				Self::get_call(&id, &call_hash, maybe_call.as_ref().map(|c| &c[..]))
+			} else {
+				None
+			};
+
+			if let Some((call, stored_call_len)) = maybe_approved_call {
+				ensure!(T::IsCallable::filter(&call), Error::<T>::Uncallable);
+				let call_weight = call.get_dispatch_info().weight;
+				ensure!(call_weight <= max_weight, Error::<T>::WeightTooLow);
+
+				// Clean up storage before executing the call, so it cannot be re-entered.
+				let _ = T::Currency::unreserve(&m.depositor, m.deposit);
+				<Multisigs<T>>::remove(&id, call_hash);
+				Self::clear_call(&id, &call_hash);
+
+				let result = call.dispatch(frame_system::RawOrigin::Signed(id.clone()).into());
+				let actual_call_weight = match &result {
+					Ok(post_info) => post_info.actual_weight,
+					Err(err) => err.post_info.actual_weight,
+				}.unwrap_or(call_weight);
+				Self::deposit_event(RawEvent::MultisigExecuted(
+					who, timepoint, id, call_hash, result.map(|_| ()).map_err(|e| e.error)
+				));
+
+				let weight = if has_call {
+					weight_of::as_multi_complete::<T>(other_signatories_len, call_len, actual_call_weight)
+				} else {
+					weight_of::approve_as_multi_complete::<T>(
+						other_signatories_len,
+						stored_call_len,
+						actual_call_weight,
+					)
+				};
+				Ok(Some(weight).into())
+			} else {
+				// We cannot dispatch the call now; either it isn't available, or it is, but we
+				// don't have threshold approvals even with our signature.
+				let stored = if let Some(data) = maybe_call.filter(|_| store) {
+					Self::store_call_and_reserve(who.clone(), &id, &call_hash, data, Zero::zero())?;
+					true
+				} else {
+					false
+				};
+
+				if let Some(pos) = maybe_pos {
+					m.approvals.insert(pos, who.clone());
+					<Multisigs<T>>::insert(&id, call_hash, m);
+					Self::deposit_event(RawEvent::MultisigApproval(who, timepoint, id, call_hash));
+				} else if !stored {
+					// Nothing has been done, so this approval was useless.
+					if already_approved.is_ok() {
+						Err(Error::<T>::AlreadyApproved)?
+					} else {
+						Err(Error::<T>::NoApprovalsNeeded)?
+					}
+				}
+
+				let weight = if has_call {
+					weight_of::as_multi_approve::<T>(other_signatories_len, call_len, stored)
+				} else {
+					weight_of::approve_as_multi::<T>(other_signatories_len)
+				};
+				Ok(Some(weight).into())
+			}
+		} else {
+			// Not yet started; there should be no timepoint given.
+			ensure!(maybe_timepoint.is_none(), Error::<T>::UnexpectedTimepoint);
+
+			let deposit = T::DepositBase::get()
+				+ T::DepositFactor::get() * threshold.into();
+
+			let stored = if let Some(data) = maybe_call.filter(|_| store) {
+				Self::store_call_and_reserve(who.clone(), &id, &call_hash, data, deposit)?;
+				true
+			} else {
+				T::Currency::reserve(&who, deposit)?;
+				false
+			};
+
+			<Multisigs<T>>::insert(&id, call_hash, Multisig {
+				when: Self::timepoint(),
+				deposit,
+				depositor: who.clone(),
+				approvals: vec![who.clone()],
+			});
+			Self::deposit_event(RawEvent::NewMultisig(who, id, call_hash));
+
+			let weight = if has_call {
+				weight_of::as_multi_create::<T>(other_signatories_len, call_len, stored)
+			} else {
+				weight_of::approve_as_multi::<T>(other_signatories_len)
+			};
+			Ok(Some(weight).into())
+		}
+	}
+
+	/// Move any open multisig operations out of the `Utility` module, where they were kept
+	/// before multisig dispatch became its own module.
+	fn migrate_from_utility() -> Weight {
+		let mut migrated: Weight = 0;
+		for (key, m) in StorageIterator::<
+			Multisig<T::BlockNumber, BalanceOf<T>, T::AccountId>
+		>::new(b"Utility", b"Multisigs").drain() {
+			// Key is `twox_64(who) ++ who ++ blake2_128(call_hash) ++ call_hash`.
+			if let Some(mut k) = key.get(8..) {
+				if let Ok(who) = T::AccountId::decode(&mut k) {
+					if let Some(mut k) = k.get(16..) {
+						if let Ok(call_hash) = <[u8; 32]>::decode(&mut k) {
+							<Multisigs<T>>::insert(&who, call_hash, m);
+						}
+					}
+				}
+			}
+			migrated += 1;
+		}
+		T::DbWeight::get().reads_writes(migrated + 1, migrated * 2)
+	}
+
+	/// Place a call's encoded data in storage for the multisig account `id`, reserving funds as
+	/// appropriate.
+	///
+	/// `other_deposit` is reserved together with the deposit for the call, so that either both
+	/// are reserved or neither is. Only the latter is recorded against the stored call.
+	///
+	/// We store `data` here because storing `call` would result in needing another `.encode`.
+	fn store_call_and_reserve(
+		who: T::AccountId,
+		id: &T::AccountId,
+		hash: &[u8; 32],
+		data: OpaqueCall,
+		other_deposit: BalanceOf<T>,
+	) -> DispatchResult {
+		ensure!(!Calls::<T>::contains_key(id, hash), Error::<T>::AlreadyStored);
+		let deposit = T::DepositBase::get()
+			+ T::DepositFactor::get() * BalanceOf::<T>::from(((data.len() + 31) / 32) as u32);
+		T::Currency::reserve(&who, other_deposit + deposit)?;
+		Calls::<T>::insert(id, hash, (data, who.clone(), deposit));
+		Self::deposit_event(RawEvent::CallStored(who, *hash));
+		Ok(())
+	}
+
+	/// Attempt to decode and return the call, provided by the user or from the storage of the
+	/// multisig account `id`.
+	fn get_call(
+		id: &T::AccountId,
+		hash: &[u8; 32],
+		maybe_known: Option<&[u8]>,
+	) -> Option<(<T as Trait>::Call, usize)> {
+		maybe_known.map_or_else(|| {
+			Calls::<T>::get(id, hash).and_then(|(data, ..)| {
+				Decode::decode(&mut &data[..]).ok().map(|d| (d, data.len()))
+			})
+		}, |data| {
+			Decode::decode(&mut &data[..]).ok().map(|d| (d, data.len()))
+		})
+	}
+
+	/// Attempt to remove a call of the multisig account `id` from storage, returning any deposit
+	/// on it to the owner.
+	fn clear_call(id: &T::AccountId, hash: &[u8; 32]) {
+		if let Some((_, who, deposit)) = Calls::<T>::take(id, hash) {
+			if !deposit.is_zero() {
+				let _ = T::Currency::unreserve(&who, deposit);
+			}
+		}
+	}
+
+	/// Check that signatories is sorted and doesn't contain sender, then insert sender.
+	fn ensure_sorted_and_insert(other_signatories: Vec<T::AccountId>, who: T::AccountId)
+		-> Result<Vec<T::AccountId>, DispatchError>
+	{
+		let mut signatories = other_signatories;
+		let mut maybe_last = None;
+		let mut index = 0;
+		for item in signatories.iter() {
+			if let Some(last) = maybe_last {
+				ensure!(last < item, Error::<T>::SignatoriesOutOfOrder);
+			}
+			if item <= &who {
+				ensure!(item != &who, Error::<T>::SenderInSignatories);
+				index += 1;
+			}
+			maybe_last = Some(item);
+		}
+		signatories.insert(index, who);
+		Ok(signatories)
+	}
+}
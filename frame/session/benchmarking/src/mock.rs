@@ -183,6 +183,15 @@ impl pallet_staking::Trait for Test {
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = UnsignedPriority;
 	type MaxIterations = ();
+	type Solver = sp_phragmen::SequentialPhragmen;
+	type ElectionProvider = sp_election_providers::onchain::OnChainSequentialPhragmen<Self>;
+}
+
+impl sp_election_providers::onchain::Config for Test {
+	type AccountId = AccountId;
+	type BlockNumber = BlockNumber;
+	type Accuracy = sp_runtime::Perbill;
+	type DataProvider = Staking;
 }
 
 impl crate::Trait for Test {}
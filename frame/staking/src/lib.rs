@@ -305,7 +305,7 @@ use sp_runtime::{
 	curve::PiecewiseLinear,
 	traits::{
 		Convert, Zero, StaticLookup, CheckedSub, Saturating, SaturatedConversion, AtLeast32Bit,
-		Dispatchable,
+		Dispatchable, Bounded,
 	},
 	transaction_validity::{
 		TransactionValidityError, TransactionValidity, ValidTransaction, InvalidTransaction,
@@ -324,8 +324,11 @@ use frame_system::{
 };
 use sp_phragmen::{
 	ExtendedBalance, Assignment, PhragmenScore, PhragmenResult, build_support_map, evaluate_support,
-	elect, generate_compact_solution_type, is_score_better, VotingLimit, SupportMap, VoteWeight,
+	generate_compact_solution_type, is_score_better, VotingLimit, SupportMap, VoteWeight,
+	NposSolver,
 };
+use sp_election_providers::{ElectionDataProvider, ElectionProvider};
+pub use sp_election_providers::ElectionCompute;
 
 const DEFAULT_MINIMUM_VALIDATOR_COUNT: u32 = 4;
 const STAKING_ID: LockIdentifier = *b"staking ";
@@ -648,18 +651,6 @@ pub struct UnappliedSlash<AccountId, Balance: HasCompact> {
 	payout: Balance,
 }
 
-/// Indicate how an election round was computed.
-#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
-pub enum ElectionCompute {
-	/// Result was forcefully computed on chain at the end of the session.
-	OnChain,
-	/// Result was submitted and accepted to the chain via a signed transaction.
-	Signed,
-	/// Result was submitted and accepted to the chain via an unsigned transaction (by an
-	/// authority).
-	Unsigned,
-}
-
 /// The result of an election round.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
 pub struct ElectionResult<AccountId, Balance: HasCompact> {
@@ -880,10 +871,21 @@ pub trait Trait: frame_system::Trait + SendTransactionTypes<Call<Self>> {
 	type NextNewSession: EstimateNextNewSession<Self::BlockNumber>;
 
 	/// How many blocks ahead of the era, within the last do we try to run the phragmen offchain?
-	/// Setting this to zero will disable the offchain compute and only on-chain seq-phragmen will
-	/// be used.
+	/// Setting this to zero will disable the offchain compute and only [`Trait::ElectionProvider`]
+	/// will be used.
+	///
+	/// This should be zero if [`Trait::ElectionProvider`] runs its own offchain phases, since the
+	/// election provider is not asked for a result if an offchain solution is queued here.
 	type ElectionLookahead: Get<Self::BlockNumber>;
 
+	/// Something that provides the election functionality at the end of an era, if no offchain
+	/// solution is queued. This module is its data provider.
+	type ElectionProvider: ElectionProvider<
+		Self::AccountId,
+		Self::BlockNumber,
+		DataProvider = Module<Self>,
+	>;
+
 	/// The overarching call type.
 	type Call: Dispatchable + From<Call<Self>> + IsSubType<Module<Self>, Self> + Clone;
 
@@ -891,6 +893,12 @@ pub trait Trait: frame_system::Trait + SendTransactionTypes<Call<Self>> {
 	/// equalize will not be executed at all.
 	type MaxIterations: Get<u32>;
 
+	/// The election algorithm used to compute the offchain submission.
+	///
+	/// The on-chain fallback election always uses sequential phragmen, since it must be cheap
+	/// enough to run within a single block.
+	type Solver: NposSolver;
+
 	/// The maximum number of nominator rewarded for each validator.
 	///
 	/// For each validator only the `$MaxNominatorRewardedPerValidator` biggest stakers can claim
@@ -2992,15 +3000,14 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// Select a new validator set from the assembled stakers and their role preferences. It tries
-	/// first to peek into [`QueuedElected`]. Otherwise, it runs a new phragmen.
+	/// first to peek into [`QueuedElected`]. Otherwise, it asks [`Trait::ElectionProvider`] for a
+	/// new election.
 	///
 	/// If [`QueuedElected`] and [`QueuedScore`] exists, they are both removed. No further storage
 	/// is updated.
 	fn try_do_phragmen() -> Option<ElectionResult<T::AccountId, BalanceOf<T>>> {
-		// a phragmen result from either a stored submission or locally executed one.
-		let next_result = <QueuedElected<T>>::take().or_else(||
-			Self::do_phragmen_with_post_processing::<ChainAccuracy>(ElectionCompute::OnChain)
-		);
+		// an election result from either a stored submission or the election provider.
+		let next_result = <QueuedElected<T>>::take().or_else(Self::elect_with_provider);
 
 		// either way, kill this. We remove it here to make sure it always has the exact same
 		// lifetime as `QueuedElected`.
@@ -3009,70 +3016,72 @@ impl<T: Trait> Module<T> {
 		next_result
 	}
 
-	/// Execute phragmen and return the new results. The edge weights are processed into support
-	/// values.
+	/// Request a new election from [`Trait::ElectionProvider`], and translate its supports into an
+	/// `ElectionResult`, keeping the type of computation reported by the provider.
 	///
-	/// This is basically a wrapper around [`do_phragmen`] which translates `PhragmenResult` into
-	/// `ElectionResult`.
+	/// Returns `None` if the election failed, or if less than [`MinimumValidatorCount`] (at least
+	/// one) validators were elected.
 	///
 	/// No storage item is updated.
-	fn do_phragmen_with_post_processing<Accuracy: PerThing>(compute: ElectionCompute)
-		-> Option<ElectionResult<T::AccountId, BalanceOf<T>>>
-	where
-		Accuracy: sp_std::ops::Mul<ExtendedBalance, Output=ExtendedBalance>,
-		ExtendedBalance: From<<Accuracy as PerThing>::Inner>,
-	{
-		if let Some(phragmen_result) = Self::do_phragmen::<Accuracy>() {
-			let elected_stashes = phragmen_result.winners.iter()
-				.map(|(s, _)| s.clone())
-				.collect::<Vec<T::AccountId>>();
-			let assignments = phragmen_result.assignments;
-
-			let staked_assignments = sp_phragmen::assignment_ratio_to_staked(
-				assignments,
-				Self::slashable_balance_of_vote_weight,
-			);
+	fn elect_with_provider() -> Option<ElectionResult<T::AccountId, BalanceOf<T>>> {
+		let (supports, compute) = T::ElectionProvider::elect()
+			.map_err(|e| log!(warn, "💸 election provider failed due to {:?}", e))
+			.ok()?;
 
-			let (supports, _) = build_support_map::<T::AccountId>(
-				&elected_stashes,
-				&staked_assignments,
+		if (supports.len() as u32) < Self::minimum_validator_count().max(1) {
+			log!(
+				warn,
+				"💸 election provider returned {} winners, less than the minimum.",
+				supports.len(),
 			);
+			return None;
+		}
 
-			// collect exposures
-			let exposures = Self::collect_exposure(supports);
+		let elected_stashes = supports.iter()
+			.map(|(s, _)| s.clone())
+			.collect::<Vec<T::AccountId>>();
+		let exposures = Self::collect_exposure(supports.into_iter().collect());
 
-			// In order to keep the property required by `on_session_ending` that we must return the
-			// new validator set even if it's the same as the old, as long as any underlying
-			// economic conditions have changed, we don't attempt to do any optimization where we
-			// compare against the prior set.
-			Some(ElectionResult::<T::AccountId, BalanceOf<T>> {
-				elected_stashes,
-				exposures,
-				compute,
-			})
-		} else {
-			// There were not enough candidates for even our minimal level of functionality. This is
-			// bad. We should probably disable all functionality except for block production and let
-			// the chain keep producing blocks until we can decide on a sufficiently substantial
-			// set. TODO: #2494
-			None
-		}
+		// In order to keep the property required by `on_session_ending` that we must return the
+		// new validator set even if it's the same as the old, as long as any underlying
+		// economic conditions have changed, we don't attempt to do any optimization where we
+		// compare against the prior set.
+		Some(ElectionResult::<T::AccountId, BalanceOf<T>> {
+			elected_stashes,
+			exposures,
+			compute,
+		})
 	}
 
-	/// Execute phragmen and return the new results. No post-processing is applied and the raw edge
-	/// weights are returned.
+	/// Execute the election with the given `Solver` and return the new results. No post-processing
+	/// is applied and the raw edge weights are returned.
 	///
-	/// Self votes are added and nominations before the most recent slashing span are reaped.
+	/// See [`get_npos_voters`] and [`get_npos_targets`] for the input of the election.
 	///
 	/// No storage item is updated.
-	fn do_phragmen<Accuracy: PerThing>() -> Option<PhragmenResult<T::AccountId, Accuracy>> {
+	fn do_phragmen<Solver: NposSolver, Accuracy: PerThing>()
+		-> Option<PhragmenResult<T::AccountId, Accuracy>>
+	where
+		Accuracy: sp_std::ops::Mul<ExtendedBalance, Output=ExtendedBalance>,
+		ExtendedBalance: From<<Accuracy as PerThing>::Inner>,
+	{
+		Solver::solve::<_, Accuracy>(
+			Self::validator_count() as usize,
+			Self::minimum_validator_count().max(1) as usize,
+			Self::get_npos_targets(),
+			Self::get_npos_voters(),
+		)
+	}
+
+	/// Get all of the voters of the next election, with their stake and their votes.
+	///
+	/// Self votes are added and nominations before the most recent slashing span are reaped.
+	pub fn get_npos_voters() -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
 		let mut all_nominators: Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> = Vec::new();
-		let mut all_validators = Vec::new();
 		for (validator, _) in <Validators<T>>::iter() {
 			// append self vote
 			let self_vote = (validator.clone(), Self::slashable_balance_of_vote_weight(&validator), vec![validator.clone()]);
 			all_nominators.push(self_vote);
-			all_validators.push(validator);
 		}
 
 		let nominator_votes = <Nominators<T>>::iter().map(|(nominator, nominations)| {
@@ -3094,12 +3103,12 @@ impl<T: Trait> Module<T> {
 			(n, s, ns)
 		}));
 
-		elect::<_, Accuracy>(
-			Self::validator_count() as usize,
-			Self::minimum_validator_count().max(1) as usize,
-			all_validators,
-			all_nominators,
-		)
+		all_nominators
+	}
+
+	/// Get all of the targets of the next election, i.e. the validator candidates.
+	pub fn get_npos_targets() -> Vec<T::AccountId> {
+		<Validators<T>>::iter().map(|(v, _)| v).collect::<Vec<_>>()
 	}
 
 	/// Consume a set of [`Supports`] from [`sp_phragmen`] and collect them into a [`Exposure`]
@@ -3242,6 +3251,29 @@ impl<T: Trait> Module<T> {
 
 }
 
+impl<T: Trait> ElectionDataProvider<T::AccountId, T::BlockNumber> for Module<T> {
+	fn targets() -> Vec<T::AccountId> {
+		Self::get_npos_targets()
+	}
+
+	fn voters() -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
+		Self::get_npos_voters()
+	}
+
+	fn desired_targets() -> u32 {
+		Self::validator_count()
+	}
+
+	fn next_election_prediction(now: T::BlockNumber) -> T::BlockNumber {
+		// the election only happens at the end of the final session of an era, or if forced.
+		if Self::is_current_session_final() || Self::will_era_be_forced() {
+			T::NextNewSession::estimate_next_new_session(now).unwrap_or_else(Bounded::max_value)
+		} else {
+			Bounded::max_value()
+		}
+	}
+}
+
 /// In this implementation `new_session(session)` must be called before `end_session(session-1)`
 /// i.e. the new session must be planned before the ending of the previous session.
 ///
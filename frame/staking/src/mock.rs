@@ -66,6 +66,7 @@ thread_local! {
 	static ELECTION_LOOKAHEAD: RefCell<BlockNumber> = RefCell::new(0);
 	static PERIOD: RefCell<BlockNumber> = RefCell::new(1);
 	static MAX_ITERATIONS: RefCell<u32> = RefCell::new(0);
+	pub static ELECTION_COMPUTE: RefCell<ElectionCompute> = RefCell::new(ElectionCompute::OnChain);
 }
 
 /// Another session handler struct to test on_disabled.
@@ -321,10 +322,31 @@ impl Trait for Test {
 	type ElectionLookahead = ElectionLookahead;
 	type Call = Call;
 	type MaxIterations = MaxIterations;
+	type Solver = sp_phragmen::SequentialPhragmen;
+	type ElectionProvider = MockElectionProvider;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = UnsignedPriority;
 }
 
+/// An on-chain election which reports the type of computation set in `ELECTION_COMPUTE`.
+pub struct MockElectionProvider;
+impl ElectionProvider<AccountId, BlockNumber> for MockElectionProvider {
+	type Error = sp_election_providers::onchain::Error;
+	type DataProvider = Staking;
+
+	fn elect() -> Result<(sp_phragmen::Supports<AccountId>, ElectionCompute), Self::Error> {
+		<sp_election_providers::onchain::OnChainSequentialPhragmen<Test> as ElectionProvider<_, _>>::elect()
+			.map(|(supports, _)| (supports, ELECTION_COMPUTE.with(|c| *c.borrow())))
+	}
+}
+
+impl sp_election_providers::onchain::Config for Test {
+	type AccountId = AccountId;
+	type BlockNumber = BlockNumber;
+	type Accuracy = sp_runtime::Perbill;
+	type DataProvider = Staking;
+}
+
 impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test where
 	Call: From<LocalCall>,
 {
@@ -896,7 +918,7 @@ pub(crate) fn prepare_submission_with(
 	let sp_phragmen::PhragmenResult {
 		winners,
 		assignments,
-	} = Staking::do_phragmen::<OffchainAccuracy>().unwrap();
+	} = Staking::do_phragmen::<<Test as Trait>::Solver, OffchainAccuracy>().unwrap();
 	let winners = sp_phragmen::to_without_backing(winners);
 
 	let stake_of = |who: &AccountId| -> VoteWeight {
@@ -110,7 +110,7 @@ pub(crate) fn compute_offchain_election<T: Trait>() -> Result<(), OffchainElecti
 	let PhragmenResult {
 		winners,
 		assignments,
-	} = <Module<T>>::do_phragmen::<OffchainAccuracy>()
+	} = <Module<T>>::do_phragmen::<T::Solver, OffchainAccuracy>()
 		.ok_or(OffchainElectionError::ElectionFailed)?;
 
 	// process and prepare it for submission.
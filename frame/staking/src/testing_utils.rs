@@ -256,7 +256,7 @@ pub fn get_seq_phragmen_solution<T: Trait>(
 	let sp_phragmen::PhragmenResult {
 		winners,
 		assignments,
-	} = <Module<T>>::do_phragmen::<OffchainAccuracy>().unwrap();
+	} = <Module<T>>::do_phragmen::<T::Solver, OffchainAccuracy>().unwrap();
 
 	offchain_election::prepare_submission::<T>(assignments, winners, do_reduce).unwrap()
 }
@@ -3040,6 +3040,27 @@ mod offchain_phragmen {
 		})
 	}
 
+	#[test]
+	fn election_compute_is_reported_by_provider() {
+		ExtBuilder::default().build_and_execute(|| {
+			ELECTION_COMPUTE.with(|c| *c.borrow_mut() = ElectionCompute::Signed);
+			start_session(1);
+			start_session(2);
+			assert_eq!(
+				System::events()
+					.into_iter()
+					.filter_map(|r| if let MetaEvent::staking(inner) = r.event {
+						Some(inner)
+					} else {
+						None
+					})
+					.last()
+					.unwrap(),
+				RawEvent::StakingElection(ElectionCompute::Signed),
+			);
+		})
+	}
+
 	#[test]
 	#[ignore] // This takes a few mins
 	fn offchain_wont_work_if_snapshot_fails() {
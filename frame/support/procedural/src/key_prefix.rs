@@ -0,0 +1,92 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of `HasKeyPrefix` and `HasReversibleKeyPrefix` for tuples of storage keys.
+
+use proc_macro2::{TokenStream, Span, Ident};
+use quote::quote;
+
+/// The maximum number of keys of a tuple of storage keys.
+const MAX_KEYS: usize = 18;
+
+/// Wrap the given items into a tuple, unless there is exactly one of them.
+fn tuple_or_single(items: &[TokenStream]) -> TokenStream {
+	if items.len() == 1 {
+		items[0].clone()
+	} else {
+		quote!( ( #( #items, )* ) )
+	}
+}
+
+pub fn impl_key_prefix_for_tuples(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	if !input.is_empty() {
+		return syn::Error::new(Span::call_site(), "No arguments expected")
+			.to_compile_error()
+			.into()
+	}
+
+	let mut all_trait_impls = TokenStream::new();
+
+	for keys_count in 2..=MAX_KEYS {
+		let hashers = (0..keys_count)
+			.map(|i| Ident::new(&format!("H{}", i), Span::call_site()))
+			.collect::<Vec<_>>();
+		let key_types = (0..keys_count)
+			.map(|i| Ident::new(&format!("K{}", i), Span::call_site()))
+			.collect::<Vec<_>>();
+		let keys = hashers.iter().zip(key_types.iter())
+			.map(|(hasher, key_type)| quote!( Key<#hasher, #key_type> ))
+			.collect::<Vec<_>>();
+
+		for prefix_count in 1..keys_count {
+			let (prefix_keys, suffix_keys) = keys.split_at(prefix_count);
+			let (prefix_types, suffix_types) = key_types.split_at(prefix_count);
+
+			let prefix_key_generator = tuple_or_single(prefix_keys);
+			let suffix_key_generator = tuple_or_single(suffix_keys);
+			let suffix = tuple_or_single(
+				&suffix_types.iter().map(|t| quote!( #t )).collect::<Vec<_>>()
+			);
+
+			all_trait_impls.extend(quote! {
+				impl<#( #hashers: StorageHasher, #key_types: FullCodec, )*>
+					HasKeyPrefix<( #( #prefix_types, )* )> for ( #( #keys, )* )
+				{
+					type Suffix = #suffix;
+
+					fn partial_key(prefix: ( #( #prefix_types, )* )) -> Vec<u8> {
+						<#prefix_key_generator as KeyGenerator>::final_key(prefix)
+					}
+				}
+
+				impl<#( #hashers: ReversibleStorageHasher, #key_types: FullCodec, )*>
+					HasReversibleKeyPrefix<( #( #prefix_types, )* )> for ( #( #keys, )* )
+				{
+					fn decode_partial_key(
+						key_material: &[u8],
+					) -> Result<Self::Suffix, codec::Error> {
+						<#suffix_key_generator as ReversibleKeyGenerator>::decode_final_key(
+							key_material,
+						).map(|k| k.0)
+					}
+				}
+			});
+		}
+	}
+
+	all_trait_impls.into()
+}
@@ -23,6 +23,7 @@
 
 mod storage;
 mod construct_runtime;
+mod key_prefix;
 
 use proc_macro::TokenStream;
 
@@ -124,6 +125,26 @@ use proc_macro::TokenStream;
 ///   Twox128(module_prefix) ++ Twox128(storage_prefix) ++ Hasher1(encode(key1)) ++ Hasher2(encode(key2))
 ///   ```
 ///
+/// * N map: `Foo: nmap hasher($hash1) u32, hasher($hash2) u32, hasher($hash3) u32 => u32`:
+///   Implements the [`StorageNMap`](../frame_support/storage/trait.StorageNMap.html) trait using the
+///   [`StorageNMap generator`](../frame_support/storage/generator/trait.StorageNMap.html).
+///   And [`StoragePrefixedMap`](../frame_support/storage/trait.StoragePrefixedMap.html).
+///
+///   Any number of keys can be given, each with its own hasher. Keys are given as a tuple, e.g.
+///   `Foo::get((key1, key2, key3))`, and any prefix of the keys can be used to iterate over or
+///   remove the entries sharing that prefix.
+///
+///   The generator is implemented with:
+///   * `module_prefix`: $module_prefix
+///   * `storage_prefix`: storage_name
+///   * the key generator `(Key<$hash1, u32>, Key<$hash2, u32>, Key<$hash3, u32>)`
+///
+///   Thus keys are stored at:
+///   ```nocompile
+///   Twox128(module_prefix) ++ Twox128(storage_prefix)
+///       ++ Hasher1(encode(key1)) ++ Hasher2(encode(key2)) ++ Hasher3(encode(key3))
+///   ```
+///
 /// Supported hashers (ordered from least to best security):
 ///
 /// * `identity` - Just the unrefined key material. Use only when it is known to be a secure hash
@@ -291,3 +312,13 @@ pub fn decl_storage(input: TokenStream) -> TokenStream {
 pub fn construct_runtime(input: TokenStream) -> TokenStream {
 	construct_runtime::construct_runtime(input)
 }
+
+/// Implement `HasKeyPrefix` and `HasReversibleKeyPrefix` for every tuple of storage keys up to
+/// 18 keys, and every prefix of those tuples.
+///
+/// This is an internal macro of `frame_support::storage::key` and should not be used elsewhere.
+#[proc_macro]
+#[doc(hidden)]
+pub fn impl_key_prefix_for_tuples(input: TokenStream) -> TokenStream {
+	key_prefix::impl_key_prefix_for_tuples(input)
+}
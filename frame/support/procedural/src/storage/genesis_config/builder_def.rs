@@ -120,6 +120,18 @@ impl BuilderDef {
 							});
 						}}
 					},
+					StorageLineTypeDef::NMap(map) => {
+						let keys = map.to_key_tuple();
+						quote!{{
+							#data
+							let data: &#scrate::sp_std::vec::Vec<(#keys, #value_type)> = data;
+							data.iter().for_each(|(k, v)| {
+								<#storage_struct as #scrate::#storage_trait>::insert::<
+									&#keys, &#value_type
+								>(k, v);
+							});
+						}}
+					},
 				});
 			}
 		}
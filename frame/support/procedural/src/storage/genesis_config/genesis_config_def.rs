@@ -104,6 +104,11 @@ impl GenesisConfigDef {
 
 					parse_quote!( Vec<(#key1, #key2, #value_type)> )
 				},
+				StorageLineTypeDef::NMap(map) => {
+					let keys = map.to_key_tuple();
+
+					parse_quote!( Vec<(#keys, #value_type)> )
+				},
 			};
 
 			let default = line.default_value.as_ref()
@@ -105,9 +105,13 @@ pub struct DeclStorageDefExt {
 
 impl From<DeclStorageDef> for DeclStorageDefExt {
 	fn from(mut def: DeclStorageDef) -> Self {
+		let hidden_crate_name = def.hidden_crate.as_ref().map(|i| i.to_string())
+			.unwrap_or_else(|| "decl_storage".to_string());
+		let scrate = generate_crate_access(&hidden_crate_name, "frame-support");
+
 		let storage_lines = def.storage_lines.drain(..).collect::<Vec<_>>();
 		let storage_lines = storage_lines.into_iter()
-			.map(|line| StorageLineDefExt::from_def(line, &def))
+			.map(|line| StorageLineDefExt::from_def(line, &def, &scrate))
 			.collect();
 
 		let (
@@ -227,7 +231,11 @@ pub struct StorageLineDefExt {
 }
 
 impl StorageLineDefExt {
-	fn from_def(storage_def: StorageLineDef, def: &DeclStorageDef) -> Self {
+	fn from_def(
+		storage_def: StorageLineDef,
+		def: &DeclStorageDef,
+		scrate: &proc_macro2::TokenStream,
+	) -> Self {
 		let is_generic = match &storage_def.storage_type {
 			StorageLineTypeDef::Simple(value) => {
 				ext::type_contains_ident(&value, &def.module_runtime_generic)
@@ -241,12 +249,18 @@ impl StorageLineDefExt {
 					|| ext::type_contains_ident(&map.key2, &def.module_runtime_generic)
 					|| ext::type_contains_ident(&map.value, &def.module_runtime_generic)
 			}
+			StorageLineTypeDef::NMap(map) => {
+				map.keys.iter()
+					.any(|key| ext::type_contains_ident(key, &def.module_runtime_generic))
+					|| ext::type_contains_ident(&map.value, &def.module_runtime_generic)
+			}
 		};
 
 		let query_type = match &storage_def.storage_type {
 			StorageLineTypeDef::Simple(value) => value.clone(),
 			StorageLineTypeDef::Map(map) => map.value.clone(),
 			StorageLineTypeDef::DoubleMap(map) => map.value.clone(),
+			StorageLineTypeDef::NMap(map) => map.value.clone(),
 		};
 		let is_option = ext::extract_type_option(&query_type).is_some();
 		let value_type = ext::extract_type_option(&query_type).unwrap_or(query_type.clone());
@@ -292,6 +306,10 @@ impl StorageLineDefExt {
 				let key2 = &map.key2;
 				quote!( StorageDoubleMap<#key1, #key2, #value_type> )
 			},
+			StorageLineTypeDef::NMap(map) => {
+				let key_generator = map.to_key_generator(scrate);
+				quote!( StorageNMap<#key_generator, #value_type> )
+			},
 		};
 
 		let storage_trait = quote!( storage::#storage_trait_truncated );
@@ -329,6 +347,7 @@ impl StorageLineDefExt {
 pub enum StorageLineTypeDef {
 	Map(MapDef),
 	DoubleMap(DoubleMapDef),
+	NMap(NMapDef),
 	Simple(syn::Type),
 }
 
@@ -348,6 +367,39 @@ pub struct DoubleMapDef {
 	pub value: syn::Type,
 }
 
+pub struct NMapDef {
+	pub hashers: Vec<HasherKind>,
+	pub keys: Vec<syn::Type>,
+	/// This is the query value not the inner value used in storage trait implementation.
+	pub value: syn::Type,
+}
+
+impl NMapDef {
+	/// The key generator of the map, for example:
+	/// `(storage::key::Key<Blake2_128Concat, u32>, storage::key::Key<Twox64Concat, u64>)`.
+	///
+	/// The `keys` and `hashers` are expected to have the same length.
+	fn to_key_generator(&self, scrate: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+		let mut keys = self.hashers.iter().zip(&self.keys).map(|(hasher, key)| {
+			let hasher = hasher.to_storage_hasher_struct();
+			quote!( #scrate::storage::key::Key<#scrate::#hasher, #key> )
+		}).collect::<Vec<_>>();
+
+		// A single key is a key generator on its own, tuples of one element are not.
+		if keys.len() == 1 {
+			keys.remove(0)
+		} else {
+			quote!( ( #( #keys, )* ) )
+		}
+	}
+
+	/// The tuple of all key types, for example: `(u32, u64)`.
+	fn to_key_tuple(&self) -> proc_macro2::TokenStream {
+		let keys = &self.keys;
+		quote!( ( #( #keys, )* ) )
+	}
+}
+
 pub struct ExtraGenesisLineDef {
 	attrs: Vec<syn::Attribute>,
 	name: syn::Ident,
@@ -415,6 +467,7 @@ pub fn decl_storage_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStr
 			StorageValue as _,
 			StorageMap as _,
 			StorageDoubleMap as _,
+			StorageNMap as _,
 			StoragePrefixedMap as _,
 		};
 
@@ -257,6 +257,12 @@ impl<T> Parameter for T where T: Codec + EncodeLike + Clone + Eq + fmt::Debug {}
 /// [`OnRuntimeUpgrade`](../sp_runtime/traits/trait.OnRuntimeUpgrade.html) trait.
 /// Function signature must be `fn on_runtime_upgrade() -> frame_support::weights::Weight`.
 ///
+/// * `pre_upgrade` and `post_upgrade`: Checks executed right before and right after
+/// `on_runtime_upgrade` by off-chain testing tools such as `try-runtime`. They are never executed
+/// on-chain and are only compiled when the module enables the `try-runtime` feature.
+/// Function signatures must be `fn pre_upgrade() -> Result<(), &'static str>` and
+/// `fn post_upgrade() -> Result<(), &'static str>`.
+///
 /// * `on_initialize`: Executes at the beginning of a block. Using this function will
 /// implement the [`OnInitialize`](./trait.OnInitialize.html) trait.
 /// Function signature can be either:
@@ -462,7 +468,7 @@ macro_rules! decl_module {
 		{ $( $other_where_bounds:tt )* }
 		{ $( $deposit_event:tt )* }
 		{ $( $on_initialize:tt )* }
-		{}
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
@@ -486,7 +492,7 @@ macro_rules! decl_module {
 		{ $( $other_where_bounds:tt )* }
 		{ $( $deposit_event:tt )* }
 		{ $( $on_initialize:tt )* }
-		{}
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
@@ -512,7 +518,7 @@ macro_rules! decl_module {
 		{ $( $other_where_bounds:tt )* }
 		{ $( $deposit_event:tt )* }
 		{ $( $on_initialize:tt )* }
-		{}
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
@@ -530,6 +536,7 @@ macro_rules! decl_module {
 			{ $( $deposit_event )* }
 			{ $( $on_initialize )* }
 			{
+				$( $on_runtime_upgrade )*
 				fn on_runtime_upgrade( $( $param_name : $param ),* ) -> $return { $( $impl )* }
 			}
 			{ $( $on_finalize )* }
@@ -540,6 +547,84 @@ macro_rules! decl_module {
 			$($rest)*
 		);
 	};
+	// Add pre_upgrade
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<
+			$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?
+		>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $other_where_bounds:tt )* }
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialize:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $on_finalize:tt )* }
+		{ $( $offchain:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
+		[ $( $dispatchables:tt )* ]
+		$(#[doc = $doc_attr:tt])*
+		fn pre_upgrade() -> $return:ty { $( $impl:tt )* }
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<$trait_instance: $trait_name$(<I>, I: $instantiable $(= $module_default_instance)?)?>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $other_where_bounds )* }
+			{ $( $deposit_event )* }
+			{ $( $on_initialize )* }
+			{
+				$( $on_runtime_upgrade )*
+				fn pre_upgrade() -> $return { $( $impl )* }
+			}
+			{ $( $on_finalize )* }
+			{ $( $offchain )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
+			[ $( $dispatchables )* ]
+			$($rest)*
+		);
+	};
+	// Add post_upgrade
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<
+			$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?
+		>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $other_where_bounds:tt )* }
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialize:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $on_finalize:tt )* }
+		{ $( $offchain:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
+		[ $( $dispatchables:tt )* ]
+		$(#[doc = $doc_attr:tt])*
+		fn post_upgrade() -> $return:ty { $( $impl:tt )* }
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<$trait_instance: $trait_name$(<I>, I: $instantiable $(= $module_default_instance)?)?>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $other_where_bounds )* }
+			{ $( $deposit_event )* }
+			{ $( $on_initialize )* }
+			{
+				$( $on_runtime_upgrade )*
+				fn post_upgrade() -> $return { $( $impl )* }
+			}
+			{ $( $on_finalize )* }
+			{ $( $offchain )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
+			[ $( $dispatchables )* ]
+			$($rest)*
+		);
+	};
 	// compile_error on_initialize, without a given weight removed syntax.
 	(@normalize
 		$(#[$attr:meta])*
@@ -1058,27 +1143,42 @@ macro_rules! decl_module {
 	(@impl_on_runtime_upgrade
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
 		{ $( $other_where_bounds:tt )* }
-		fn on_runtime_upgrade() -> $return:ty { $( $impl:tt )* }
+		$( fn $fn_name:ident() -> $return:ty { $( $impl:tt )* } )*
 	) => {
 		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
 			$crate::traits::OnRuntimeUpgrade
 			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
 		{
-			fn on_runtime_upgrade() -> $return {
-				$crate::sp_tracing::enter_span!("on_runtime_upgrade");
-				{ $( $impl )* }
-			}
+			$(
+				$crate::decl_module! {
+					@impl_on_runtime_upgrade_fn
+					fn $fn_name() -> $return { $( $impl )* }
+				}
+			)*
 		}
 	};
 
-	(@impl_on_runtime_upgrade
-		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
-		{ $( $other_where_bounds:tt )* }
+	(@impl_on_runtime_upgrade_fn
+		fn on_runtime_upgrade() -> $return:ty { $( $impl:tt )* }
 	) => {
-		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
-			$crate::traits::OnRuntimeUpgrade
-			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
-		{}
+		fn on_runtime_upgrade() -> $return {
+			$crate::sp_tracing::enter_span!("on_runtime_upgrade");
+			{ $( $impl )* }
+		}
+	};
+
+	(@impl_on_runtime_upgrade_fn
+		fn pre_upgrade() -> $return:ty { $( $impl:tt )* }
+	) => {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> $return { $( $impl )* }
+	};
+
+	(@impl_on_runtime_upgrade_fn
+		fn post_upgrade() -> $return:ty { $( $impl:tt )* }
+	) => {
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> $return { $( $impl )* }
 	};
 
 
@@ -2094,6 +2194,8 @@ mod tests {
 			fn on_initialize(n: T::BlockNumber,) -> Weight { if n.into() == 42 { panic!("on_initialize") } 7 }
 			fn on_finalize(n: T::BlockNumber,) { if n.into() == 42 { panic!("on_finalize") } }
 			fn on_runtime_upgrade() -> Weight { 10 }
+			fn pre_upgrade() -> Result<(), &'static str> { Ok(()) }
+			fn post_upgrade() -> Result<(), &'static str> { Err("post_upgrade") }
 			fn offchain_worker() {}
 		}
 	}
@@ -2242,6 +2344,13 @@ mod tests {
 		assert_eq!(<Module<TraitImpl> as OnRuntimeUpgrade>::on_runtime_upgrade(), 10);
 	}
 
+	#[test]
+	#[cfg(feature = "try-runtime")]
+	fn upgrade_hooks_should_work() {
+		assert_eq!(<Module<TraitImpl> as OnRuntimeUpgrade>::pre_upgrade(), Ok(()));
+		assert_eq!(<Module<TraitImpl> as OnRuntimeUpgrade>::post_upgrade(), Err("post_upgrade"));
+	}
+
 	#[test]
 	fn weight_should_attach_to_call_enum() {
 		// operational.
@@ -74,8 +74,8 @@ pub use self::hash::{
 	StorageHasher, ReversibleStorageHasher
 };
 pub use self::storage::{
-	StorageValue, StorageMap, StorageDoubleMap, StoragePrefixedMap, IterableStorageMap,
-	IterableStorageDoubleMap, migration
+	StorageValue, StorageMap, StorageDoubleMap, StorageNMap, StoragePrefixedMap, IterableStorageMap,
+	IterableStorageDoubleMap, IterableStorageNMap, migration
 };
 pub use self::dispatch::{Parameter, Callable, IsSubType};
 pub use sp_runtime::{self, ConsensusEngineId, print, traits::Printable};
@@ -352,6 +352,11 @@ mod tests {
 				=> Option<T::BlockNumber>;
 			pub AppendableDM:
 				double_map hasher(blake2_128_concat) u32, hasher(blake2_128_concat) T::BlockNumber => Vec<u32>;
+			pub DataNM config(test_n_map_config) build(|_| vec![((15u32, 16u32, 17u8), 42u64)]):
+				nmap hasher(twox_64_concat) u32, hasher(blake2_128_concat) u32, hasher(identity) u8 => u64;
+			pub GenericDataNM:
+				nmap hasher(blake2_128_concat) T::BlockNumber, hasher(identity) T::BlockNumber
+				=> Option<T::BlockNumber>;
 		}
 	}
 
@@ -553,6 +558,62 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn n_map_basic_insert_remove_remove_prefix_should_work() {
+		new_test_ext().execute_with(|| {
+			type NMap = DataNM;
+			// initialized during genesis
+			assert_eq!(NMap::get((15u32, 16u32, 17u8)), 42u64);
+
+			// get / insert / take
+			let key = (17u32, 18u32, 19u8);
+			assert_eq!(NMap::get(&key), 0u64);
+			NMap::insert(&key, &4u64);
+			assert_eq!(NMap::get(&key), 4u64);
+			assert_eq!(NMap::take(&key), 4u64);
+			assert_eq!(NMap::get(&key), 0u64);
+
+			// mutate
+			NMap::mutate(&key, |val| {
+				*val = 15;
+			});
+			assert_eq!(NMap::get(&key), 15u64);
+
+			// remove
+			NMap::remove(&key);
+			assert_eq!(NMap::get(&key), 0u64);
+
+			// remove prefix
+			NMap::insert((17u32, 18u32, 19u8), &4u64);
+			NMap::insert((17u32, 18u32, 20u8), &4u64);
+			NMap::insert((17u32, 19u32, 19u8), &4u64);
+			NMap::insert((18u32, 18u32, 19u8), &4u64);
+			NMap::remove_prefix((17u32, 18u32));
+			assert_eq!(NMap::get((17u32, 18u32, 19u8)), 0u64);
+			assert_eq!(NMap::get((17u32, 18u32, 20u8)), 0u64);
+			assert_eq!(NMap::get((17u32, 19u32, 19u8)), 4u64);
+			assert_eq!(NMap::get((18u32, 18u32, 19u8)), 4u64);
+			NMap::remove_prefix((17u32,));
+			assert_eq!(NMap::get((17u32, 19u32, 19u8)), 0u64);
+			assert_eq!(NMap::get((18u32, 18u32, 19u8)), 4u64);
+		});
+	}
+
+	#[test]
+	fn n_map_generic_keys_work() {
+		new_test_ext().execute_with(|| {
+			type NMap = GenericDataNM<Test>;
+
+			assert_eq!(NMap::get((1u32, 2u32)), None);
+			NMap::insert((1u32, 2u32), 3u32);
+			assert_eq!(NMap::get((1u32, 2u32)), Some(3));
+			NMap::swap((1u32, 2u32), (2u32, 1u32));
+			assert_eq!(NMap::get((1u32, 2u32)), None);
+			assert_eq!(NMap::get((2u32, 1u32)), Some(3));
+			assert_eq!(NMap::iter().collect::<Vec<_>>(), vec![((2, 1), 3)]);
+		});
+	}
+
 	#[test]
 	fn double_map_append_should_work() {
 		new_test_ext().execute_with(|| {
@@ -687,6 +748,39 @@ mod tests {
 					),
 					documentation: DecodeDifferent::Encode(&[]),
 				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Encode("DataNM"),
+					modifier: StorageEntryModifier::Default,
+					ty: StorageEntryType::NMap {
+						keys: DecodeDifferent::Encode(&["u32", "u32", "u8"]),
+						hashers: DecodeDifferent::Encode(&[
+							StorageHasher::Twox64Concat,
+							StorageHasher::Blake2_128Concat,
+							StorageHasher::Identity,
+						]),
+						value: DecodeDifferent::Encode("u64"),
+					},
+					default: DecodeDifferent::Encode(
+						DefaultByteGetter(&__GetByteStructDataNM(PhantomData::<Test>))
+					),
+					documentation: DecodeDifferent::Encode(&[]),
+				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Encode("GenericDataNM"),
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::NMap {
+						keys: DecodeDifferent::Encode(&["T::BlockNumber", "T::BlockNumber"]),
+						hashers: DecodeDifferent::Encode(&[
+							StorageHasher::Blake2_128Concat,
+							StorageHasher::Identity,
+						]),
+						value: DecodeDifferent::Encode("T::BlockNumber"),
+					},
+					default: DecodeDifferent::Encode(
+						DefaultByteGetter(&__GetByteStructGenericDataNM(PhantomData::<Test>))
+					),
+					documentation: DecodeDifferent::Encode(&[]),
+				},
 			]
 		),
 	};
@@ -606,4 +606,17 @@ mod tests {
 
 		pretty_assertions::assert_eq!(expected_metadata, metadata_decoded.unwrap());
 	}
+
+	#[test]
+	fn runtime_metadata_v11_is_decodable() {
+		let mut metadata_encoded = TestRuntime::metadata().encode();
+		// The version follows the 4 bytes of `META_RESERVED`.
+		assert_eq!(metadata_encoded[4], 12);
+		metadata_encoded[4] = 11;
+
+		match RuntimeMetadataPrefixed::decode(&mut &metadata_encoded[..]).unwrap().1 {
+			RuntimeMetadata::V11(_) => {},
+			metadata => panic!("Unexpected metadata version: {:?}", metadata),
+		}
+	}
 }
@@ -296,13 +296,13 @@ impl<K1, K2, V, G> storage::StorageDoubleMap<K1, K2, V> for G where
 
 /// Iterate over a prefix and decode raw_key and raw_value into `T`.
 pub struct MapIterator<T> {
-	prefix: Vec<u8>,
-	previous_key: Vec<u8>,
+	pub(super) prefix: Vec<u8>,
+	pub(super) previous_key: Vec<u8>,
 	/// If true then value are removed while iterating
-	drain: bool,
+	pub(super) drain: bool,
 	/// Function that take `(raw_key_without_prefix, raw_value)` and decode `T`.
 	/// `raw_key_without_prefix` is the raw storage key without the prefix iterated on.
-	closure: fn(&[u8], &[u8]) -> Result<T, codec::Error>,
+	pub(super) closure: fn(&[u8], &[u8]) -> Result<T, codec::Error>,
 }
 
 impl<T> Iterator for MapIterator<T> {
@@ -26,10 +26,12 @@
 
 mod map;
 mod double_map;
+mod nmap;
 mod value;
 
 pub use map::StorageMap;
-pub use double_map::StorageDoubleMap;
+pub use double_map::{StorageDoubleMap, MapIterator};
+pub use nmap::StorageNMap;
 pub use value::StorageValue;
 
 #[cfg(test)]
@@ -0,0 +1,244 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Key types used by storage maps with an arbitrary number of keys.
+//!
+//! A `StorageNMap` is keyed by a tuple of [`Key`]s, each of them describing the hasher used for
+//! one key and the type of that key, e.g. `(Key<Blake2_128Concat, u32>, Key<Twox64Concat, u64>)`.
+
+use sp_std::{prelude::*, marker::PhantomData};
+use codec::{Encode, EncodeLike, FullCodec};
+use crate::hash::{StorageHasher, ReversibleStorageHasher};
+
+/// A key used by storage maps with an arbitrary number of keys, combining the hasher used for the
+/// key with the type of the key.
+///
+/// The final key generated by a tuple of `Key`s has the following form:
+/// ```nocompile
+/// Hasher1(encode(key1)) ++ Hasher2(encode(key2)) ++ ... ++ HasherN(encode(keyN))
+/// ```
+pub struct Key<Hasher, KeyType>(PhantomData<(Hasher, KeyType)>);
+
+/// A key, or a tuple of keys, that can generate the hashed part of a final storage key.
+pub trait KeyGenerator {
+	/// The full key, i.e. the key type for a single `Key` or a tuple of key types.
+	type Key: EncodeLike<Self::Key>;
+	/// The tuple of all key types, used to encode the key arguments element by element.
+	type KArg: Encode;
+
+	/// Given a `key` tuple, calculate the final key by encoding each element individually and
+	/// hashing them using the corresponding hasher.
+	fn final_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(key: KArg) -> Vec<u8>;
+}
+
+/// A `KeyGenerator` whose hashers are all reversible, thus the keys can be decoded back from a
+/// final key.
+pub trait ReversibleKeyGenerator: KeyGenerator {
+	/// Decode the full key from the given key material.
+	///
+	/// Returns the key alongside the remaining key material.
+	fn decode_final_key(key_material: &[u8]) -> Result<(Self::Key, &[u8]), codec::Error>;
+}
+
+/// A `KeyGenerator` whose first keys are the key types of `P`.
+///
+/// This is used to compute and iterate over the partial keys of a map.
+pub trait HasKeyPrefix<P>: KeyGenerator {
+	/// The remaining key types after the prefix, or the last key type itself.
+	type Suffix;
+
+	/// Calculate the hashed part of the final key for the given `prefix`.
+	fn partial_key(prefix: P) -> Vec<u8>;
+}
+
+/// A `ReversibleKeyGenerator` whose first keys are the key types of `P`.
+pub trait HasReversibleKeyPrefix<P>: ReversibleKeyGenerator + HasKeyPrefix<P> {
+	/// Decode the keys following the prefix from the given key material.
+	fn decode_partial_key(key_material: &[u8]) -> Result<Self::Suffix, codec::Error>;
+}
+
+/// A tuple whose elements can be encoded one by one.
+pub trait TupleToEncodedIter {
+	/// Encode every element of the tuple.
+	fn to_encoded_iter(&self) -> sp_std::vec::IntoIter<Vec<u8>>;
+}
+
+/// Marker trait indicating that each element of a tuple encodes like the corresponding element of
+/// the tuple `T`.
+pub trait EncodeLikeTuple<T> {}
+
+impl<T: TupleToEncodedIter> TupleToEncodedIter for &T {
+	fn to_encoded_iter(&self) -> sp_std::vec::IntoIter<Vec<u8>> {
+		(*self).to_encoded_iter()
+	}
+}
+
+impl<T: EncodeLikeTuple<U>, U> EncodeLikeTuple<U> for &T {}
+
+macro_rules! impl_encode_like_tuples {
+	( $( ($elem:ident, $like:ident), )+ ) => {
+		impl_encode_like_tuples!(@impl $( ($elem, $like), )+);
+		impl_encode_like_tuples!(@recurse $( ($elem, $like), )+);
+	};
+	(@recurse ($first:ident, $first_like:ident), ) => {};
+	(@recurse ($first:ident, $first_like:ident), $( ($elem:ident, $like:ident), )+) => {
+		impl_encode_like_tuples!( $( ($elem, $like), )+ );
+	};
+	(@impl $( ($elem:ident, $like:ident), )+) => {
+		#[allow(non_snake_case)]
+		impl<$( $elem: Encode, )+> TupleToEncodedIter for ($( $elem, )+) {
+			fn to_encoded_iter(&self) -> sp_std::vec::IntoIter<Vec<u8>> {
+				let ($( ref $elem, )+) = *self;
+				vec![$( $elem.encode(), )+].into_iter()
+			}
+		}
+
+		impl<$( $elem: EncodeLike<$like>, $like: Encode, )+> EncodeLikeTuple<($( $like, )+)>
+			for ($( $elem, )+) {}
+	};
+}
+
+impl_encode_like_tuples!(
+	(A0, A1), (B0, B1), (C0, C1), (D0, D1), (E0, E1), (F0, F1), (G0, G1), (H0, H1), (I0, I1),
+	(J0, J1), (K0, K1), (L0, L1), (M0, M1), (N0, N1), (O0, O1), (P0, P1), (Q0, Q1), (R0, R1),
+);
+
+impl<H: StorageHasher, K: FullCodec> KeyGenerator for Key<H, K> {
+	type Key = K;
+	type KArg = (K,);
+
+	fn final_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(key: KArg) -> Vec<u8> {
+		let encoded = key.to_encoded_iter().next()
+			.expect("`KArg` is a tuple of one element; qed");
+		H::hash(&encoded).as_ref().to_vec()
+	}
+}
+
+impl<H: ReversibleStorageHasher, K: FullCodec> ReversibleKeyGenerator for Key<H, K> {
+	fn decode_final_key(key_material: &[u8]) -> Result<(Self::Key, &[u8]), codec::Error> {
+		let mut current_key_material = H::reverse(key_material);
+		let key = K::decode(&mut current_key_material)?;
+		Ok((key, current_key_material))
+	}
+}
+
+macro_rules! impl_key_generator_for_tuples {
+	( ($hasher:ident, $key:ident), ) => {};
+	( $( ($hasher:ident, $key:ident), )+ ) => {
+		impl<$( $hasher: StorageHasher, $key: FullCodec, )+> KeyGenerator
+			for ($( Key<$hasher, $key>, )+)
+		{
+			type Key = ($( $key, )+);
+			type KArg = ($( $key, )+);
+
+			fn final_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(
+				key: KArg,
+			) -> Vec<u8> {
+				let mut final_key = Vec::new();
+				let mut iter = key.to_encoded_iter();
+				$(
+					let encoded = iter.next()
+						.expect("`KArg` has as many elements as there are keys; qed");
+					final_key.extend_from_slice($hasher::hash(&encoded).as_ref());
+				)+
+				final_key
+			}
+		}
+
+		#[allow(non_snake_case)]
+		impl<$( $hasher: ReversibleStorageHasher, $key: FullCodec, )+> ReversibleKeyGenerator
+			for ($( Key<$hasher, $key>, )+)
+		{
+			fn decode_final_key(key_material: &[u8]) -> Result<(Self::Key, &[u8]), codec::Error> {
+				let current_key_material = key_material;
+				$(
+					let ($key, current_key_material) =
+						<Key<$hasher, $key> as ReversibleKeyGenerator>::decode_final_key(
+							current_key_material,
+						)?;
+				)+
+				Ok((($( $key, )+), current_key_material))
+			}
+		}
+
+		impl_key_generator_for_tuples!(@recurse $( ($hasher, $key), )+);
+	};
+	(@recurse ($first_hasher:ident, $first_key:ident), $( ($hasher:ident, $key:ident), )+) => {
+		impl_key_generator_for_tuples!( $( ($hasher, $key), )+ );
+	};
+}
+
+impl_key_generator_for_tuples!(
+	(HA, KA), (HB, KB), (HC, KC), (HD, KD), (HE, KE), (HF, KF), (HG, KG), (HH, KH), (HI, KI),
+	(HJ, KJ), (HK, KK), (HL, KL), (HM, KM), (HN, KN), (HO, KO), (HP, KP), (HQ, KQ), (HR, KR),
+);
+
+frame_support_procedural::impl_key_prefix_for_tuples!();
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::hash::{Blake2_128Concat, Identity, Twox64Concat};
+
+	type ThreeKeys = (Key<Blake2_128Concat, u16>, Key<Twox64Concat, u32>, Key<Identity, u64>);
+
+	#[test]
+	fn final_key_is_concatenation_of_hashed_keys() {
+		let expected = [
+			Blake2_128Concat::hash(&1u16.encode()),
+			Twox64Concat::hash(&2u32.encode()),
+			Identity::hash(&3u64.encode()),
+		].concat();
+
+		assert_eq!(ThreeKeys::final_key((1u16, 2u32, 3u64)), expected);
+		assert_eq!(ThreeKeys::final_key(&(1u16, 2u32, 3u64)), expected);
+		assert_eq!(ThreeKeys::final_key((&1u16, 2u32, &3u64)), expected);
+	}
+
+	#[test]
+	fn partial_keys_are_prefixes_of_final_key() {
+		let final_key = ThreeKeys::final_key((1u16, 2u32, 3u64));
+		let prefix1 = <ThreeKeys as HasKeyPrefix<(u16,)>>::partial_key((1,));
+		let prefix2 = <ThreeKeys as HasKeyPrefix<(u16, u32)>>::partial_key((1, 2));
+
+		assert!(final_key.starts_with(&prefix1));
+		assert!(final_key.starts_with(&prefix2));
+		assert!(prefix2.starts_with(&prefix1));
+	}
+
+	#[test]
+	fn keys_can_be_decoded() {
+		let final_key = ThreeKeys::final_key((1u16, 2u32, 3u64));
+		assert_eq!(ThreeKeys::decode_final_key(&final_key).unwrap(), ((1, 2, 3), &[][..]));
+
+		let prefix1 = <ThreeKeys as HasKeyPrefix<(u16,)>>::partial_key((1,));
+		assert_eq!(
+			<ThreeKeys as HasReversibleKeyPrefix<(u16,)>>::decode_partial_key(
+				&final_key[prefix1.len()..],
+			).unwrap(),
+			(2, 3),
+		);
+
+		let prefix2 = <ThreeKeys as HasKeyPrefix<(u16, u32)>>::partial_key((1, 2));
+		assert_eq!(
+			<ThreeKeys as HasReversibleKeyPrefix<(u16, u32)>>::decode_partial_key(
+				&final_key[prefix2.len()..],
+			).unwrap(),
+			3,
+		);
+	}
+}
@@ -20,6 +20,10 @@
 use sp_std::{prelude::*, marker::PhantomData};
 use codec::{FullCodec, FullEncode, Encode, EncodeLike, Decode};
 use crate::hash::{Twox128, StorageHasher};
+use key::{
+	KeyGenerator, ReversibleKeyGenerator, HasKeyPrefix, HasReversibleKeyPrefix, EncodeLikeTuple,
+	TupleToEncodedIter,
+};
 use sp_runtime::generic::{Digest, DigestItem};
 
 pub mod unhashed;
@@ -27,8 +31,36 @@ pub mod hashed;
 pub mod child;
 #[doc(hidden)]
 pub mod generator;
+pub mod key;
 pub mod migration;
 
+/// Describes whether a storage transaction should be committed or rolled back.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TransactionOutcome<R> {
+	/// Commit the transaction.
+	Commit(R),
+	/// Rollback the transaction.
+	Rollback(R),
+}
+
+/// Execute the supplied function in a new storage transaction.
+///
+/// All changes to storage performed by the supplied function are discarded if the returned
+/// outcome is `TransactionOutcome::Rollback`.
+///
+/// Transactions can be nested to any depth. Commits happen to the parent transaction.
+pub fn with_transaction<R>(f: impl FnOnce() -> TransactionOutcome<R>) -> R {
+	use sp_io::storage::{start_transaction, commit_transaction, rollback_transaction};
+	use TransactionOutcome::*;
+
+	start_transaction();
+
+	match f() {
+		Commit(res) => { commit_transaction(); res },
+		Rollback(res) => { rollback_transaction(); res },
+	}
+}
+
 /// A trait for working with macro-generated storage values under the substrate storage API.
 ///
 /// Details on implementation can be found at
@@ -399,6 +431,155 @@ pub trait StorageDoubleMap<K1: FullEncode, K2: FullEncode, V: FullCodec> {
 	>(key1: KeyArg1, key2: KeyArg2) -> Option<V>;
 }
 
+/// An implementation of a map with an arbitrary number of keys.
+///
+/// It provides an important ability to efficiently remove all entries
+/// that have a common key prefix.
+///
+/// Details on implementation can be found at
+/// [`generator::StorageNMap`]
+pub trait StorageNMap<K: KeyGenerator, V: FullCodec> {
+	/// The type that get/take returns.
+	type Query;
+
+	/// Get the storage key used to fetch a value corresponding to a specific key.
+	fn hashed_key_for<KArg>(key: KArg) -> Vec<u8>
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter;
+
+	/// Does the value (explicitly) exist in storage?
+	fn contains_key<KArg>(key: KArg) -> bool
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter;
+
+	/// Load the value associated with the given key from the map.
+	fn get<KArg>(key: KArg) -> Self::Query
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter;
+
+	/// Take a value from storage, removing it afterwards.
+	fn take<KArg>(key: KArg) -> Self::Query
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter;
+
+	/// Swap the values of two keys.
+	fn swap<KArg1, KArg2>(key1: KArg1, key2: KArg2)
+	where
+		KArg1: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		KArg2: EncodeLikeTuple<K::KArg> + TupleToEncodedIter;
+
+	/// Store a value to be associated with the given keys from the map.
+	fn insert<KArg, VArg>(key: KArg, val: VArg)
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		VArg: EncodeLike<V>;
+
+	/// Remove the value under the given keys.
+	fn remove<KArg>(key: KArg)
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter;
+
+	/// Remove all values under the partial prefix key.
+	fn remove_prefix<KP>(partial_key: KP) where K: HasKeyPrefix<KP>;
+
+	/// Iterate over values that share the partial prefix key.
+	fn iter_prefix_values<KP>(partial_key: KP) -> PrefixIterator<V> where K: HasKeyPrefix<KP>;
+
+	/// Mutate the value under the given keys.
+	fn mutate<KArg, R, F>(key: KArg, f: F) -> R
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		F: FnOnce(&mut Self::Query) -> R;
+
+	/// Mutate the value under the given keys when the closure returns `Ok`.
+	fn try_mutate<KArg, R, E, F>(key: KArg, f: F) -> Result<R, E>
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		F: FnOnce(&mut Self::Query) -> Result<R, E>;
+
+	/// Append the given item to the value in the storage.
+	///
+	/// `V` is required to implement [`StorageAppend`].
+	///
+	/// # Warning
+	///
+	/// If the storage item is not encoded properly, the storage will be overwritten
+	/// and set to `[item]`. Any default value set for the storage item will be ignored
+	/// on overwrite.
+	fn append<Item, EncodeLikeItem, KArg>(key: KArg, item: EncodeLikeItem)
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		Item: Encode,
+		EncodeLikeItem: EncodeLike<Item>,
+		V: StorageAppend<Item>;
+
+	/// Read the length of the storage value without decoding the entire value under the
+	/// given `key`.
+	///
+	/// `V` is required to implement [`StorageDecodeLength`].
+	///
+	/// If the value does not exists or it fails to decode the length, `None` is returned.
+	/// Otherwise `Some(len)` is returned.
+	///
+	/// # Warning
+	///
+	/// `None` does not mean that `get()` does not return a value. The default value is completly
+	/// ignored by this function.
+	fn decode_len<KArg>(key: KArg) -> Option<usize>
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		V: StorageDecodeLength,
+	{
+		V::decode_len(&Self::hashed_key_for(key))
+	}
+
+	/// Migrate an item with the given `key` from defunct hashers, described by the key generator
+	/// `OldKG`, to the current hashers.
+	///
+	/// If the key doesn't exist, then it's a no-op. If it does, then it returns its value.
+	fn migrate_keys<OldKG, KArg>(key: KArg) -> Option<V>
+	where
+		OldKG: KeyGenerator<KArg = K::KArg>,
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter;
+}
+
+/// A strongly-typed map with an arbitrary number of keys in storage whose keys and values can be
+/// iterated over.
+pub trait IterableStorageNMap<K: ReversibleKeyGenerator, V: FullCodec>: StorageNMap<K, V> {
+	/// The type that iterates over all `(key, value)`.
+	type Iterator: Iterator<Item = (K::Key, V)>;
+
+	/// Enumerate all elements in the map with prefix key `partial_key` in no particular order.
+	/// If you add or remove values whose prefix key is `partial_key` to the map while doing this,
+	/// you'll get undefined results.
+	fn iter_prefix<KP>(
+		partial_key: KP,
+	) -> generator::MapIterator<(<K as HasKeyPrefix<KP>>::Suffix, V)>
+	where
+		K: HasReversibleKeyPrefix<KP>;
+
+	/// Remove all elements from the map with prefix key `partial_key` and iterate through them in
+	/// no particular order. If you add elements with prefix key `partial_key` to the map while
+	/// doing this, you'll get undefined results.
+	fn drain_prefix<KP>(
+		partial_key: KP,
+	) -> generator::MapIterator<(<K as HasKeyPrefix<KP>>::Suffix, V)>
+	where
+		K: HasReversibleKeyPrefix<KP>;
+
+	/// Enumerate all elements in the map in no particular order. If you add or remove values to
+	/// the map while doing this, you'll get undefined results.
+	fn iter() -> Self::Iterator;
+
+	/// Remove all elements from the map and iterate through them in no particular order. If you
+	/// add elements to the map while doing this, you'll get undefined results.
+	fn drain() -> Self::Iterator;
+
+	/// Translate the values of all elements by a function `f`, in the map in no particular order.
+	/// By returning `None` from `f` for an element, you'll remove it from the map.
+	fn translate<O: Decode, F: Fn(K::Key, O) -> Option<V>>(f: F);
+}
+
 /// Iterator for prefixed map.
 pub struct PrefixIterator<Value> {
 	prefix: Vec<u8>,
@@ -640,6 +821,44 @@ mod test {
 		});
 	}
 
+	#[test]
+	fn with_transaction_works() {
+		TestExternalities::default().execute_with(|| {
+			unhashed::put(b"value", &1u32);
+
+			let res = with_transaction(|| {
+				unhashed::put(b"value", &2u32);
+
+				let inner = with_transaction(|| {
+					unhashed::put(b"value", &3u32);
+					unhashed::put(b"other", &4u32);
+					TransactionOutcome::Rollback(5u32)
+				});
+				assert_eq!(inner, 5);
+				assert_eq!(unhashed::get::<u32>(b"value"), Some(2));
+				assert_eq!(unhashed::get::<u32>(b"other"), None);
+
+				with_transaction(|| {
+					unhashed::put(b"other", &6u32);
+					TransactionOutcome::Commit(())
+				});
+				assert_eq!(unhashed::get::<u32>(b"other"), Some(6));
+
+				TransactionOutcome::Rollback(7u32)
+			});
+
+			assert_eq!(res, 7);
+			assert_eq!(unhashed::get::<u32>(b"value"), Some(1));
+			assert_eq!(unhashed::get::<u32>(b"other"), None);
+
+			with_transaction(|| {
+				unhashed::put(b"value", &8u32);
+				TransactionOutcome::Commit(())
+			});
+			assert_eq!(unhashed::get::<u32>(b"value"), Some(8));
+		});
+	}
+
 	// This test ensures that the Digest encoding does not change without being noticied.
 	#[test]
 	fn digest_storage_append_works_as_expected() {
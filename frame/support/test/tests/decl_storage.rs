@@ -506,6 +506,10 @@ mod test_append_and_len {
 			DoubleMapVec: double_map hasher(blake2_128_concat) u32, hasher(blake2_128_concat) u32 => Vec<u32>;
 			DoubleMapVecWithDefault: double_map hasher(blake2_128_concat) u32, hasher(blake2_128_concat) u32 => Vec<u32> = vec![6, 9];
 			OptionDoubleMapVec: double_map hasher(blake2_128_concat) u32, hasher(blake2_128_concat) u32 => Option<Vec<u32>>;
+
+			NMapVec get(fn n_map_vec): nmap hasher(blake2_128_concat) u32, hasher(twox_64_concat) u64 => Vec<u32>;
+			NMapVecWithDefault: nmap hasher(blake2_128_concat) u32, hasher(twox_64_concat) u64 => Vec<u32> = vec![6, 9];
+			OptionNMapVec: nmap hasher(blake2_128_concat) u32, hasher(twox_64_concat) u64 => Option<Vec<u32>>;
 		}
 	}
 
@@ -544,6 +548,12 @@ mod test_append_and_len {
 			JustVec::kill();
 			JustVec::append(1);
 			assert_eq!(JustVec::get(), vec![1]);
+
+			for val in &[1, 2, 3] {
+				NMapVec::append((1, 2), val);
+			}
+			assert_eq!(NMapVec::get((1, 2)), vec![1, 2, 3]);
+			assert_eq!(Module::<Test>::n_map_vec((1, 2)), vec![1, 2, 3]);
 		});
 	}
 
@@ -576,6 +586,10 @@ mod test_append_and_len {
 			assert_eq!(OptionVec::get(), None);
 			OptionVec::append(1);
 			assert_eq!(OptionVec::get(), Some(vec![1]));
+
+			assert_eq!(NMapVecWithDefault::get((0, 0)), vec![6, 9]);
+			NMapVecWithDefault::append((0, 0), 1);
+			assert_eq!(NMapVecWithDefault::get((0, 0)), vec![1]);
 		});
 	}
 
@@ -586,11 +600,13 @@ mod test_append_and_len {
 			OptionVec::put(&vec![1, 2, 3, 4, 5]);
 			MapVec::insert(1, &vec![1, 2, 3, 4, 5, 6]);
 			DoubleMapVec::insert(0, 1, &vec![1, 2]);
+			NMapVec::insert((0, 1), &vec![1, 2, 3]);
 
 			assert_eq!(JustVec::decode_len().unwrap(), 4);
 			assert_eq!(OptionVec::decode_len().unwrap(), 5);
 			assert_eq!(MapVec::decode_len(1).unwrap(), 6);
 			assert_eq!(DoubleMapVec::decode_len(0, 1).unwrap(), 2);
+			assert_eq!(NMapVec::decode_len((0, 1)).unwrap(), 3);
 		});
 	}
 
@@ -628,6 +644,16 @@ mod test_append_and_len {
 
 			assert_eq!(OptionDoubleMapVec::get(0, 0), None);
 			assert_eq!(OptionDoubleMapVec::decode_len(0, 1), None);
+
+			// N map
+			assert_eq!(NMapVec::get((0, 0)), vec![]);
+			assert_eq!(NMapVec::decode_len((0, 1)), None);
+
+			assert_eq!(NMapVecWithDefault::get((0, 0)), vec![6, 9]);
+			assert_eq!(NMapVecWithDefault::decode_len((0, 1)), None);
+
+			assert_eq!(OptionNMapVec::get((0, 0)), None);
+			assert_eq!(OptionNMapVec::decode_len((0, 1)), None);
 		});
 	}
 }
@@ -0,0 +1,35 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition used by the `try-runtime` command.
+//!
+//! This API should only be implemented by runtimes built with the `try-runtime` feature, and
+//! must never be called on-chain.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::weights::Weight;
+use sp_runtime::RuntimeString;
+
+sp_api::decl_runtime_apis! {
+	/// Runtime api for testing the execution of a runtime upgrade.
+	pub trait TryRuntime {
+		/// Execute the runtime upgrade of all modules, including their `pre_upgrade` and
+		/// `post_upgrade` checks, and return the weight it consumed.
+		fn on_runtime_upgrade() -> Result<Weight, RuntimeString>;
+	}
+}
@@ -23,12 +23,37 @@ use super::*;
 
 use frame_support::{
 	assert_ok, assert_noop, impl_outer_origin, parameter_types, impl_outer_dispatch,
-	weights::Weight, impl_outer_event
+	weights::Weight, impl_outer_event, decl_storage
 };
 use sp_core::H256;
 use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
 use crate as utility;
 
+// Example module to check that failing calls of a batch don't leave partial changes behind.
+pub mod example {
+	use super::*;
+
+	pub trait Trait: system::Trait {}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as Example {
+			pub Value get(fn value): u32;
+		}
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+			#[weight = 0]
+			fn set_value(origin, value: u32, fail: bool) {
+				ensure_signed(origin)?;
+				Value::put(value);
+				// The value is written before the call fails.
+				ensure!(!fail, "set_value failed");
+			}
+		}
+	}
+}
+
 impl_outer_origin! {
 	pub enum Origin for Test where system = frame_system {}
 }
@@ -37,7 +62,7 @@ impl_outer_event! {
 	pub enum TestEvent for Test {
 		system<T>,
 		pallet_balances<T>,
-		utility<T>,
+		utility,
 	}
 }
 impl_outer_dispatch! {
@@ -45,6 +70,7 @@ impl_outer_dispatch! {
 		frame_system::System,
 		pallet_balances::Balances,
 		utility::Utility,
+		example::Example,
 	}
 }
 
@@ -94,17 +120,12 @@ impl pallet_balances::Trait for Test {
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 }
-parameter_types! {
-	pub const MultisigDepositBase: u64 = 1;
-	pub const MultisigDepositFactor: u64 = 1;
-	pub const MaxSignatories: u16 = 3;
-}
-
 pub struct TestIsCallable;
 impl Filter<Call> for TestIsCallable {
 	fn filter(c: &Call) -> bool {
 		match *c {
 			Call::Balances(pallet_balances::Call::transfer(..)) => true,
+			Call::Example(_) => true,
 			_ => false,
 		}
 	}
@@ -112,15 +133,13 @@ impl Filter<Call> for TestIsCallable {
 impl Trait for Test {
 	type Event = TestEvent;
 	type Call = Call;
-	type Currency = Balances;
-	type MultisigDepositBase = MultisigDepositBase;
-	type MultisigDepositFactor = MultisigDepositFactor;
-	type MaxSignatories = MaxSignatories;
 	type IsCallable = TestIsCallable;
 }
+impl example::Trait for Test {}
 type System = frame_system::Module<Test>;
 type Balances = pallet_balances::Module<Test>;
 type Utility = Module<Test>;
+type Example = example::Module<Test>;
 
 use pallet_balances::Call as BalancesCall;
 use pallet_balances::Error as BalancesError;
@@ -136,271 +155,13 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 }
 
 fn last_event() -> TestEvent {
-	system::Module::<Test>::events().pop().map(|e| e.event).expect("Event expected")
+	System::events().pop().map(|e| e.event).expect("Event expected")
 }
 
 fn expect_event<E: Into<TestEvent>>(e: E) {
 	assert_eq!(last_event(), e.into());
 }
 
-fn now() -> Timepoint<u64> {
-	Utility::timepoint()
-}
-
-#[test]
-fn multisig_deposit_is_taken_and_returned() {
-	new_test_ext().execute_with(|| {
-		let multi = Utility::multi_account_id(&[1, 2, 3][..], 2);
-		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
-
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		assert_ok!(Utility::as_multi(Origin::signed(1), 2, vec![2, 3], None, call.clone()));
-		assert_eq!(Balances::free_balance(1), 2);
-		assert_eq!(Balances::reserved_balance(1), 3);
-
-		assert_ok!(Utility::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), call));
-		assert_eq!(Balances::free_balance(1), 5);
-		assert_eq!(Balances::reserved_balance(1), 0);
-	});
-}
-
-#[test]
-fn cancel_multisig_returns_deposit() {
-	new_test_ext().execute_with(|| {
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		let hash = call.using_encoded(blake2_256);
-		assert_ok!(Utility::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash.clone()));
-		assert_ok!(Utility::approve_as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), hash.clone()));
-		assert_eq!(Balances::free_balance(1), 6);
-		assert_eq!(Balances::reserved_balance(1), 4);
-		assert_ok!(
-			Utility::cancel_as_multi(Origin::signed(1), 3, vec![2, 3], now(), hash.clone()),
-		);
-		assert_eq!(Balances::free_balance(1), 10);
-		assert_eq!(Balances::reserved_balance(1), 0);
-	});
-}
-
-#[test]
-fn timepoint_checking_works() {
-	new_test_ext().execute_with(|| {
-		let multi = Utility::multi_account_id(&[1, 2, 3][..], 2);
-		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
-
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		let hash = call.using_encoded(blake2_256);
-
-		assert_noop!(
-			Utility::approve_as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), hash.clone()),
-			Error::<Test>::UnexpectedTimepoint,
-		);
-
-		assert_ok!(Utility::approve_as_multi(Origin::signed(1), 2, vec![2, 3], None, hash));
-
-		assert_noop!(
-			Utility::as_multi(Origin::signed(2), 2, vec![1, 3], None, call.clone()),
-			Error::<Test>::NoTimepoint,
-		);
-		let later = Timepoint { index: 1, .. now() };
-		assert_noop!(
-			Utility::as_multi(Origin::signed(2), 2, vec![1, 3], Some(later), call.clone()),
-			Error::<Test>::WrongTimepoint,
-		);
-	});
-}
-
-#[test]
-fn multisig_2_of_3_works() {
-	new_test_ext().execute_with(|| {
-		let multi = Utility::multi_account_id(&[1, 2, 3][..], 2);
-		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
-
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		let hash = call.using_encoded(blake2_256);
-		assert_ok!(Utility::approve_as_multi(Origin::signed(1), 2, vec![2, 3], None, hash));
-		assert_eq!(Balances::free_balance(6), 0);
-
-		assert_ok!(Utility::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), call));
-		assert_eq!(Balances::free_balance(6), 15);
-	});
-}
-
-#[test]
-fn multisig_3_of_3_works() {
-	new_test_ext().execute_with(|| {
-		let multi = Utility::multi_account_id(&[1, 2, 3][..], 3);
-		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
-
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		let hash = call.using_encoded(blake2_256);
-		assert_ok!(Utility::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash.clone()));
-		assert_ok!(Utility::approve_as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), hash.clone()));
-		assert_eq!(Balances::free_balance(6), 0);
-
-		assert_ok!(Utility::as_multi(Origin::signed(3), 3, vec![1, 2], Some(now()), call));
-		assert_eq!(Balances::free_balance(6), 15);
-	});
-}
-
-#[test]
-fn cancel_multisig_works() {
-	new_test_ext().execute_with(|| {
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		let hash = call.using_encoded(blake2_256);
-		assert_ok!(Utility::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash.clone()));
-		assert_ok!(Utility::approve_as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), hash.clone()));
-		assert_noop!(
-			Utility::cancel_as_multi(Origin::signed(2), 3, vec![1, 3], now(), hash.clone()),
-			Error::<Test>::NotOwner,
-		);
-		assert_ok!(
-			Utility::cancel_as_multi(Origin::signed(1), 3, vec![2, 3], now(), hash.clone()),
-		);
-	});
-}
-
-#[test]
-fn multisig_2_of_3_as_multi_works() {
-	new_test_ext().execute_with(|| {
-		let multi = Utility::multi_account_id(&[1, 2, 3][..], 2);
-		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
-
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		assert_ok!(Utility::as_multi(Origin::signed(1), 2, vec![2, 3], None, call.clone()));
-		assert_eq!(Balances::free_balance(6), 0);
-
-		assert_ok!(Utility::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), call));
-		assert_eq!(Balances::free_balance(6), 15);
-	});
-}
-
-#[test]
-fn multisig_2_of_3_as_multi_with_many_calls_works() {
-	new_test_ext().execute_with(|| {
-		let multi = Utility::multi_account_id(&[1, 2, 3][..], 2);
-		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
-
-		let call1 = Box::new(Call::Balances(BalancesCall::transfer(6, 10)));
-		let call2 = Box::new(Call::Balances(BalancesCall::transfer(7, 5)));
-
-		assert_ok!(Utility::as_multi(Origin::signed(1), 2, vec![2, 3], None, call1.clone()));
-		assert_ok!(Utility::as_multi(Origin::signed(2), 2, vec![1, 3], None, call2.clone()));
-		assert_ok!(Utility::as_multi(Origin::signed(3), 2, vec![1, 2], Some(now()), call2));
-		assert_ok!(Utility::as_multi(Origin::signed(3), 2, vec![1, 2], Some(now()), call1));
-
-		assert_eq!(Balances::free_balance(6), 10);
-		assert_eq!(Balances::free_balance(7), 5);
-	});
-}
-
-#[test]
-fn multisig_2_of_3_cannot_reissue_same_call() {
-	new_test_ext().execute_with(|| {
-		let multi = Utility::multi_account_id(&[1, 2, 3][..], 2);
-		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
-
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 10)));
-		assert_ok!(Utility::as_multi(Origin::signed(1), 2, vec![2, 3], None, call.clone()));
-		assert_ok!(Utility::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), call.clone()));
-		assert_eq!(Balances::free_balance(multi), 5);
-
-		assert_ok!(Utility::as_multi(Origin::signed(1), 2, vec![2, 3], None, call.clone()));
-		assert_ok!(Utility::as_multi(Origin::signed(3), 2, vec![1, 2], Some(now()), call.clone()));
-
-		let err = DispatchError::from(BalancesError::<Test, _>::InsufficientBalance).stripped();
-		expect_event(RawEvent::MultisigExecuted(3, now(), multi, call.using_encoded(blake2_256), Err(err)));
-	});
-}
-
-#[test]
-fn zero_threshold_fails() {
-	new_test_ext().execute_with(|| {
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		assert_noop!(
-			Utility::as_multi(Origin::signed(1), 0, vec![2], None, call),
-			Error::<Test>::ZeroThreshold,
-		);
-	});
-}
-
-#[test]
-fn too_many_signatories_fails() {
-	new_test_ext().execute_with(|| {
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		assert_noop!(
-			Utility::as_multi(Origin::signed(1), 2, vec![2, 3, 4], None, call.clone()),
-			Error::<Test>::TooManySignatories,
-		);
-	});
-}
-
-#[test]
-fn duplicate_approvals_are_ignored() {
-	new_test_ext().execute_with(|| {
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		let hash = call.using_encoded(blake2_256);
-		assert_ok!(Utility::approve_as_multi(Origin::signed(1), 2, vec![2, 3], None, hash.clone()));
-		assert_noop!(
-			Utility::approve_as_multi(Origin::signed(1), 2, vec![2, 3], Some(now()), hash.clone()),
-			Error::<Test>::AlreadyApproved,
-		);
-		assert_ok!(Utility::approve_as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), hash.clone()));
-		assert_noop!(
-			Utility::approve_as_multi(Origin::signed(3), 2, vec![1, 2], Some(now()), hash.clone()),
-			Error::<Test>::NoApprovalsNeeded,
-		);
-	});
-}
-
-#[test]
-fn multisig_1_of_3_works() {
-	new_test_ext().execute_with(|| {
-		let multi = Utility::multi_account_id(&[1, 2, 3][..], 1);
-		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
-		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
-
-		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
-		let hash = call.using_encoded(blake2_256);
-		assert_noop!(
-			Utility::approve_as_multi(Origin::signed(1), 1, vec![2, 3], None, hash.clone()),
-			Error::<Test>::NoApprovalsNeeded,
-		);
-		assert_noop!(
-			Utility::as_multi(Origin::signed(4), 1, vec![2, 3], None, call.clone()),
-			BalancesError::<Test, _>::InsufficientBalance,
-		);
-		assert_ok!(Utility::as_multi(Origin::signed(1), 1, vec![2, 3], None, call));
-
-		assert_eq!(Balances::free_balance(6), 15);
-	});
-}
-
-#[test]
-fn multisig_filters() {
-	new_test_ext().execute_with(|| {
-		let call = Box::new(Call::System(frame_system::Call::remark(vec![])));
-		assert_noop!(
-			Utility::as_multi(Origin::signed(1), 1, vec![], None, call.clone()),
-			Error::<Test>::Uncallable,
-		);
-	});
-}
-
 #[test]
 fn as_sub_works() {
 	new_test_ext().execute_with(|| {
@@ -470,7 +231,7 @@ fn batch_with_signed_filters() {
 				Call::System(frame_system::Call::remark(vec![]))
 			]),
 		);
-		expect_event(RawEvent::Uncallable(0));
+		expect_event(Event::Uncallable(0));
 	});
 }
 
@@ -490,3 +251,24 @@ fn batch_early_exit_works() {
 		assert_eq!(Balances::free_balance(2), 15);
 	});
 }
+
+#[test]
+fn batch_rolls_back_failed_call() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(
+			Utility::batch(Origin::signed(1), vec![
+				Call::Balances(BalancesCall::transfer(2, 5)),
+				Call::Example(example::Call::set_value(1, false)),
+				Call::Example(example::Call::set_value(2, true)),
+			]),
+		);
+		match last_event() {
+			TestEvent::utility(Event::BatchInterrupted(2, _)) => {},
+			event => panic!("Unexpected event: {:?}", event),
+		}
+		// The calls before the failed one persist, the write of the failed one doesn't.
+		assert_eq!(Balances::free_balance(1), 5);
+		assert_eq!(Balances::free_balance(2), 15);
+		assert_eq!(Example::value(), 1);
+	});
+}
@@ -164,6 +164,10 @@ pub struct BlockImportParams<Block: BlockT, Transaction> {
 	pub allow_missing_state: bool,
 	/// Re-validate existing block.
 	pub import_existing: bool,
+	/// The state of the block was downloaded and imported beforehand, rather than built by
+	/// executing it. The block becomes the new finalized base of the chain, its ancestors
+	/// are not imported.
+	pub state_imported: bool,
 	/// Cached full header hash (with post-digests applied).
 	pub post_hash: Option<Block::Hash>,
 }
@@ -186,6 +190,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			fork_choice: None,
 			allow_missing_state: false,
 			import_existing: false,
+			state_imported: false,
 			post_hash: None,
 		}
 	}
@@ -226,6 +231,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			allow_missing_state: self.allow_missing_state,
 			fork_choice: self.fork_choice,
 			import_existing: self.import_existing,
+			state_imported: self.state_imported,
 			post_hash: self.post_hash,
 		}
 	}
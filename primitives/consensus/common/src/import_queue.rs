@@ -34,7 +34,7 @@ use crate::{
 	error::Error as ConsensusError,
 	block_import::{
 		BlockImport, BlockOrigin, BlockImportParams, ImportedAux, JustificationImport, ImportResult,
-		BlockCheckParams, FinalityProofImport,
+		BlockCheckParams, FinalityProofImport, ForkChoiceStrategy,
 	},
 	metrics::Metrics,
 };
@@ -76,6 +76,10 @@ pub struct IncomingBlock<B: BlockT> {
 	pub allow_missing_state: bool,
 	/// Re-validate existing block.
 	pub import_existing: bool,
+	/// The state of the block has already been imported, and the block is the target of the
+	/// state sync. The block is imported without verification, see
+	/// [`BlockImportParams::state_imported`].
+	pub state_imported: bool,
 }
 
 /// Type of keys in the blockchain cache that consensus module could use for its needs.
@@ -248,6 +252,19 @@ pub(crate) fn import_single_block_metered<B: BlockT, V: Verifier<B>, Transaction
 		}
 	};
 
+	if block.state_imported {
+		// The state sync has already proven the header and its state against a finalized
+		// justification, and the parent is unknown: there is nothing to check or verify.
+		let mut import_block = BlockImportParams::new(block_origin, header);
+		import_block.justification = justification;
+		import_block.body = block.body;
+		import_block.finalized = true;
+		import_block.fork_choice = Some(ForkChoiceStrategy::Custom(true));
+		import_block.allow_missing_state = true;
+		import_block.state_imported = true;
+		return import_handler(import_handle.import_block(import_block, HashMap::new()))
+	}
+
 	match import_handler(import_handle.check_block(BlockCheckParams {
 		hash,
 		number,
@@ -0,0 +1,116 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Primitive traits for providing election functionality.
+//!
+//! This crate provides two traits that could interact to enable extensible election functionality
+//! within FRAME pallets.
+//!
+//! Something that will provide the functionality of election will implement [`ElectionProvider`],
+//! whilst needing an associated [`ElectionProvider::DataProvider`], which needs to be fulfilled by
+//! an entity implementing [`ElectionDataProvider`]. Most often, *the data provider is* the receiver
+//! of the election, resulting in a diagram as below:
+//!
+//! ```ignore
+//!                                         ElectionDataProvider
+//!                          <------------------------------------------+
+//!                          |                                          |
+//!                          v                                          |
+//!                    +-----+----+                              +------+---+
+//!                    |          |                              |          |
+//! pallet-do-election |          |                              |          | pallet-needs-election
+//!                    |          |                              |          |
+//!                    |          |                              |          |
+//!                    +-----+----+                              +------+---+
+//!                          |                                          ^
+//!                          |                                          |
+//!                          +------------------------------------------+
+//!                                         ElectionProvider
+//! ```
+//!
+//! > It could also be possible that a third party pallet (C), provides the data of election to an
+//! > election provider (B), which then passes the election result to another pallet (A).
+//!
+//! The simplest [`ElectionProvider`] is [`onchain::OnChainSequentialPhragmen`], which computes the
+//! election right away when asked for it.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod onchain;
+
+use sp_std::{prelude::*, fmt::Debug};
+use codec::{Encode, Decode};
+
+/// Re-export some type as they are used in the interface.
+pub use sp_arithmetic::PerThing;
+pub use sp_phragmen::{Supports, Support, VoteWeight, ExtendedBalance, PhragmenScore};
+
+/// The type of computation that yielded the result of an election.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Encode, Decode)]
+pub enum ElectionCompute {
+	/// Election was computed on-chain when it was requested.
+	OnChain,
+	/// Election was computed with a solution submitted by a signed transaction.
+	Signed,
+	/// Election was computed with a solution submitted by an unsigned transaction.
+	Unsigned,
+}
+
+impl Default for ElectionCompute {
+	fn default() -> Self {
+		ElectionCompute::OnChain
+	}
+}
+
+/// Something that can provide the data to an [`ElectionProvider`].
+pub trait ElectionDataProvider<AccountId, BlockNumber> {
+	/// All possible targets for the election, i.e. the candidates.
+	fn targets() -> Vec<AccountId>;
+
+	/// All possible voters for the election, with their stake and the targets they voted for.
+	///
+	/// Note that if a notion of self-vote exists, it should be represented here.
+	fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)>;
+
+	/// The number of targets to elect.
+	fn desired_targets() -> u32;
+
+	/// Provide a best effort prediction about when the next election is about to happen.
+	///
+	/// In essence, the implementor should predict with this function when it will trigger the
+	/// [`ElectionProvider::elect`].
+	fn next_election_prediction(now: BlockNumber) -> BlockNumber;
+}
+
+/// Something that can compute the result of an election and pass it back to the caller.
+///
+/// This trait only provides an interface to _request_ an election, i.e.
+/// [`ElectionProvider::elect`]. That data required for the election need to be passed to the
+/// implemented of this trait through [`ElectionProvider::DataProvider`].
+pub trait ElectionProvider<AccountId, BlockNumber> {
+	/// The error type that is returned by the provider.
+	type Error: Debug;
+
+	/// The data provider of the election.
+	type DataProvider: ElectionDataProvider<AccountId, BlockNumber>;
+
+	/// Elect a new set of winners.
+	///
+	/// The result is returned in a target major format, namely as vector of supports, along with
+	/// the type of computation that yielded it.
+	fn elect() -> Result<(Supports<AccountId>, ElectionCompute), Self::Error>;
+}
@@ -0,0 +1,159 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An implementation of [`ElectionProvider`] that does an on-chain sequential phragmen.
+
+use crate::{ElectionCompute, ElectionDataProvider, ElectionProvider};
+use sp_arithmetic::PerThing;
+use sp_phragmen::{
+	elect, assignment_ratio_to_staked, build_support_map, to_without_backing, ExtendedBalance,
+	IdentifierT, PhragmenResult, Supports, VoteWeight,
+};
+use sp_std::{prelude::*, collections::btree_map::BTreeMap, marker::PhantomData, ops::Mul};
+
+/// Errors of the on-chain election.
+#[derive(Eq, PartialEq, Debug)]
+pub enum Error {
+	/// The election failed, most likely because less targets than desired were provided.
+	ElectionFailed,
+}
+
+/// A simple on-chain implementation of the election provider trait.
+///
+/// This will accept voting data on the fly and produce the results immediately.
+///
+/// ### Warning
+///
+/// This can be very expensive to run frequently on-chain. Use with care.
+pub struct OnChainSequentialPhragmen<T: Config>(PhantomData<T>);
+
+/// Configuration trait of [`OnChainSequentialPhragmen`].
+pub trait Config {
+	/// The account identifier type.
+	type AccountId: IdentifierT;
+	/// The block number type.
+	type BlockNumber;
+	/// The accuracy used to compute the election.
+	type Accuracy: PerThing + Mul<ExtendedBalance, Output = ExtendedBalance>;
+	/// Something that provides the data for election.
+	type DataProvider: ElectionDataProvider<Self::AccountId, Self::BlockNumber>;
+}
+
+impl<T: Config> ElectionProvider<T::AccountId, T::BlockNumber> for OnChainSequentialPhragmen<T>
+where
+	ExtendedBalance: From<<T::Accuracy as PerThing>::Inner>,
+{
+	type Error = Error;
+	type DataProvider = T::DataProvider;
+
+	fn elect() -> Result<(Supports<T::AccountId>, ElectionCompute), Self::Error> {
+		let voters = Self::DataProvider::voters();
+		let targets = Self::DataProvider::targets();
+		let desired_targets = Self::DataProvider::desired_targets() as usize;
+
+		let stake_map = voters
+			.iter()
+			.map(|(who, stake, _)| (who.clone(), *stake))
+			.collect::<BTreeMap<T::AccountId, VoteWeight>>();
+		let stake_of = |who: &T::AccountId| -> VoteWeight {
+			stake_map.get(who).cloned().unwrap_or_default()
+		};
+
+		let PhragmenResult { winners, assignments } =
+			elect::<_, T::Accuracy>(desired_targets, 0, targets, voters)
+				.ok_or(Error::ElectionFailed)?;
+
+		let staked = assignment_ratio_to_staked(assignments, &stake_of);
+		let winners = to_without_backing(winners);
+		let (mut supports, _) = build_support_map(&winners, &staked);
+
+		// keep the order in which the winners were elected.
+		let supports = winners
+			.into_iter()
+			.filter_map(|w| supports.remove(&w).map(|s| (w, s)))
+			.collect();
+		Ok((supports, ElectionCompute::OnChain))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_arithmetic::Perbill;
+	use sp_phragmen::Support;
+
+	type AccountId = u64;
+	type BlockNumber = u32;
+
+	struct Runtime;
+	impl Config for Runtime {
+		type AccountId = AccountId;
+		type BlockNumber = BlockNumber;
+		type Accuracy = Perbill;
+		type DataProvider = mock_data_provider::DataProvider;
+	}
+
+	type OnChainPhragmen = OnChainSequentialPhragmen<Runtime>;
+
+	mod mock_data_provider {
+		use super::*;
+
+		pub struct DataProvider;
+
+		impl ElectionDataProvider<AccountId, BlockNumber> for DataProvider {
+			fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
+				vec![
+					(1, 10, vec![10, 20]),
+					(2, 20, vec![30, 20]),
+					(3, 30, vec![10, 30]),
+				]
+			}
+
+			fn targets() -> Vec<AccountId> {
+				vec![10, 20, 30]
+			}
+
+			fn desired_targets() -> u32 {
+				2
+			}
+
+			fn next_election_prediction(_: BlockNumber) -> BlockNumber {
+				0
+			}
+		}
+	}
+
+	#[test]
+	fn onchain_seq_phragmen_works() {
+		assert_eq!(
+			OnChainPhragmen::elect().unwrap(),
+			(
+				vec![
+					(
+						30,
+						Support { total: 35, voters: vec![(2, 20), (3, 15)] },
+					),
+					(
+						10,
+						Support { total: 25, voters: vec![(1, 10), (3, 15)] },
+					),
+				],
+				ElectionCompute::OnChain,
+			)
+		);
+	}
+}
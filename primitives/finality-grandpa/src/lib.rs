@@ -478,7 +478,7 @@ sp_api::decl_runtime_apis! {
 	/// applied in the runtime after those N blocks have passed.
 	///
 	/// The consensus protocol will coordinate the handoff externally.
-	#[api_version(2)]
+	#[api_version(3)]
 	pub trait GrandpaApi {
 		/// Get the current GRANDPA authorities and weights. This should not change except
 		/// for when changes are scheduled and the corresponding delay has passed.
@@ -488,6 +488,13 @@ sp_api::decl_runtime_apis! {
 		/// is finalized by the authorities from block B-1.
 		fn grandpa_authorities() -> AuthorityList;
 
+		/// Get the authority set change that was signaled but isn't enacted yet, if any.
+		///
+		/// When called at block B, it returns the change signaled at B or one of its ancestors
+		/// that is enacted in a descendant of B. A node starting from the state of B without
+		/// importing its ancestors never sees the signal of that change.
+		fn grandpa_pending_change() -> Option<ScheduledChange<NumberFor<Block>>>;
+
 		/// Submits an extrinsic to report an equivocation. The caller must
 		/// provide the equivocation proof and a key ownership proof (should be
 		/// obtained using `generate_key_ownership_proof`). This method will
@@ -155,6 +155,46 @@ pub trait Storage {
 	fn next_key(&mut self, key: &[u8]) -> Option<Vec<u8>> {
 		self.next_storage_key(&key)
 	}
+
+	/// Start a new nested transaction.
+	///
+	/// This allows to either commit or roll back all changes that are made after this call.
+	/// For every transaction there must be a matching call to either `rollback_transaction`
+	/// or `commit_transaction`. This is also effective for all values manipulated using the
+	/// `DefaultChildStorage` API.
+	///
+	/// # Warning
+	///
+	/// This is a low level API that is potentially dangerous as it can easily result
+	/// in unbalanced transactions. For example, FRAME users should use high level storage
+	/// abstractions.
+	fn start_transaction(&mut self) {
+		self.storage_start_transaction();
+	}
+
+	/// Rollback the last transaction started by `start_transaction`.
+	///
+	/// Any changes made during that transaction are discarded.
+	///
+	/// # Panics
+	///
+	/// Will panic if there is no open transaction.
+	fn rollback_transaction(&mut self) {
+		self.storage_rollback_transaction()
+			.expect("No open transaction that can be rolled back.");
+	}
+
+	/// Commit the last transaction started by `start_transaction`.
+	///
+	/// Any changes made during that transaction are committed.
+	///
+	/// # Panics
+	///
+	/// Will panic if there is no open transaction.
+	fn commit_transaction(&mut self) {
+		self.storage_commit_transaction()
+			.expect("No open transaction that can be committed.");
+	}
 }
 
 /// Interface for accessing the child storage for default child trie,
@@ -478,6 +518,32 @@ pub trait Crypto {
 		}
 	}
 
+	/// Verify `ed25519` signature without taking part in batch verification.
+	///
+	/// Returns the result of the verification immediately, even if a batching verification
+	/// extension is registered. Use this when the outcome of the verification is acted upon
+	/// right away, e.g. by a smart contract.
+	fn ed25519_verify_unbatched(
+		sig: &ed25519::Signature,
+		msg: &[u8],
+		pub_key: &ed25519::Public,
+	) -> bool {
+		ed25519::Pair::verify(sig, msg, pub_key)
+	}
+
+	/// Verify `sr25519` signature without taking part in batch verification.
+	///
+	/// Returns the result of the verification immediately, even if a batching verification
+	/// extension is registered. Use this when the outcome of the verification is acted upon
+	/// right away, e.g. by a smart contract.
+	fn sr25519_verify_unbatched(
+		sig: &sr25519::Signature,
+		msg: &[u8],
+		pub_key: &sr25519::Public,
+	) -> bool {
+		sr25519::Pair::verify(sig, msg, pub_key)
+	}
+
 	/// Start verification extension.
 	fn start_batch_verify(&mut self) {
 		let scheduler = self.extension::<TaskExecutorExt>()
@@ -1236,6 +1302,36 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn unbatched_verification_is_not_deferred() {
+		let mut ext = BasicExternalities::with_tasks_executor();
+		ext.execute_with(|| {
+			crypto::start_batch_verify();
+
+			assert!(!crypto::ed25519_verify_unbatched(
+				&Default::default(),
+				&Vec::new(),
+				&Default::default(),
+			));
+			assert!(!crypto::sr25519_verify_unbatched(
+				&Default::default(),
+				&Vec::new(),
+				&Default::default(),
+			));
+
+			let pair = ed25519::Pair::generate_with_phrase(None).0;
+			let msg = b"Important message";
+			assert!(crypto::ed25519_verify_unbatched(&pair.sign(msg), msg, &pair.public()));
+
+			let pair = sr25519::Pair::generate_with_phrase(None).0;
+			let msg = b"Schnorrkel rules";
+			assert!(crypto::sr25519_verify_unbatched(&pair.sign(msg), msg, &pair.public()));
+
+			// Nothing was pushed to the batch.
+			assert!(crypto::finish_batch_verify());
+		});
+	}
+
 	#[test]
 	fn batching_works() {
 		let mut ext = BasicExternalities::with_tasks_executor();
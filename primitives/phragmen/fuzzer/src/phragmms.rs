@@ -0,0 +1,147 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Fuzzing for the PhragMMS algorithm.
+//!
+//! It compares the score of the solution of sequential phragmen with the one of PhragMMS, both
+//! followed by balancing, and ensures that both are valid solutions of the same size.
+//!
+//! Run with `cargo hfuzz run phragmms`. `honggfuzz`.
+
+mod common;
+use common::to_range;
+use honggfuzz::fuzz;
+use sp_phragmen::{
+	assignment_ratio_to_staked, build_support_map, to_without_backing, elect, phragmms, balance,
+	PhragmenResult, PhragmenScore, VoteWeight, evaluate_support, is_score_better,
+};
+use sp_std::collections::btree_map::BTreeMap;
+use sp_runtime::Perbill;
+use rand::{self, Rng, SeedableRng, RngCore};
+
+type AccountId = u64;
+
+type Voters = Vec<(AccountId, VoteWeight, Vec<AccountId>)>;
+
+fn generate_random_election(
+	voter_count: u64,
+	target_count: u64,
+	edge_per_voter: u64,
+	mut rng: impl RngCore,
+) -> (Vec<AccountId>, Voters, BTreeMap<AccountId, VoteWeight>) {
+	let prefix = 100_000;
+	let base_stake: u64 = 1_000_000_000;
+	let ed: u64 = base_stake;
+
+	let mut candidates = Vec::with_capacity(target_count as usize);
+	let mut stake_of_tree: BTreeMap<AccountId, VoteWeight> = BTreeMap::new();
+
+	(1..=target_count).for_each(|acc| {
+		candidates.push(acc);
+		let stake_var = rng.gen_range(ed, 100 * ed);
+		stake_of_tree.insert(acc, base_stake + stake_var);
+	});
+
+	let mut voters = Vec::with_capacity(voter_count as usize);
+	(prefix ..= (prefix + voter_count)).for_each(|acc| {
+		// all possible targets
+		let mut all_targets = candidates.clone();
+		// we remove and pop into `targets` `edge_per_voter` times.
+		let targets = (0..edge_per_voter).map(|_| {
+			let upper = all_targets.len() - 1;
+			let idx = rng.gen_range(0, upper);
+			all_targets.remove(idx)
+		})
+		.collect::<Vec<AccountId>>();
+
+		let stake_var = rng.gen_range(ed, 100 * ed) ;
+		let stake = base_stake + stake_var;
+		stake_of_tree.insert(acc, stake);
+		voters.push((acc, stake, targets));
+	});
+
+	(candidates, voters, stake_of_tree)
+}
+
+/// Evaluate the given result, ensuring that it has no edge to a non-winner. Returns the score and
+/// the number of winners.
+fn evaluate(
+	result: PhragmenResult<AccountId, Perbill>,
+	stake_of: impl Fn(&AccountId) -> VoteWeight,
+	iterations: usize,
+) -> (PhragmenScore, usize) {
+	let PhragmenResult { winners, assignments } = result;
+	let mut staked = assignment_ratio_to_staked(assignments, &stake_of);
+	let winners = to_without_backing(winners);
+	balance(&winners, &mut staked, iterations, 0);
+
+	let (support, errors) = build_support_map(winners.as_ref(), staked.as_ref());
+	assert_eq!(errors, 0);
+	(evaluate_support(&support), winners.len())
+}
+
+fn main() {
+	loop {
+		fuzz!(|data: (usize, usize, usize, usize, usize, u64)| {
+			let (mut target_count, mut voter_count, mut iterations, mut edge_per_voter, mut to_elect, seed) = data;
+			let rng = rand::rngs::SmallRng::seed_from_u64(seed);
+			target_count = to_range(target_count, 50, 2000);
+			voter_count = to_range(voter_count, 50, 1000);
+			iterations = to_range(iterations, 1, 20);
+			to_elect = to_range(to_elect, 25, target_count);
+			edge_per_voter = to_range(edge_per_voter, 1, target_count);
+
+			println!("++ [{} / {} / {} / {}]", voter_count, target_count, to_elect, iterations);
+			let (candidates, voters, stake_of_tree) = generate_random_election(
+				voter_count as u64,
+				target_count as u64,
+				edge_per_voter as u64,
+				rng,
+			);
+
+			let stake_of = |who: &AccountId| -> VoteWeight {
+				*stake_of_tree.get(who).unwrap()
+			};
+
+			let (phragmen_score, phragmen_winners) = evaluate(
+				elect::<AccountId, Perbill>(to_elect, 0, candidates.clone(), voters.clone()).unwrap(),
+				&stake_of,
+				iterations,
+			);
+			let (phragmms_score, phragmms_winners) = evaluate(
+				phragmms::<AccountId, Perbill>(
+					to_elect,
+					0,
+					candidates,
+					voters,
+					Some((iterations, 0)),
+				).unwrap(),
+				&stake_of,
+				iterations,
+			);
+
+			let enhance = is_score_better(phragmen_score, phragmms_score);
+
+			println!(
+				"phragmen = {:?} // phragmms = {:?} [{}]",
+				phragmen_score,
+				phragmms_score,
+				enhance,
+			);
+			assert_eq!(phragmen_winners, phragmms_winners);
+		});
+	}
+}
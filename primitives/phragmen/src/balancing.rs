@@ -0,0 +1,249 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Iterative balancing of the stake distribution of an election result.
+//!
+//! Balancing repeatedly re-distributes the budget of each voter among the elected candidates it
+//! voted for, such that the backed stake of these candidates becomes as even as possible. Each
+//! round can only increase the minimum backed stake and decrease the sum of the squared backed
+//! stakes of the winners.
+
+use crate::{ExtendedBalance, IdentifierT, StakedAssignment};
+use sp_arithmetic::traits::Zero;
+use sp_std::{prelude::*, collections::btree_map::BTreeMap};
+
+/// A candidate in the staked representation used by balancing and PhragMMS.
+#[derive(Clone, Default, Debug)]
+pub(crate) struct StakedCandidate<AccountId> {
+	/// Identifier.
+	pub(crate) who: AccountId,
+	/// Sum of the budgets of all voters voting for this candidate.
+	pub(crate) approval_stake: ExtendedBalance,
+	/// Sum of the weights of all edges pointing to this candidate.
+	pub(crate) backed_stake: ExtendedBalance,
+	/// Flag for being elected.
+	pub(crate) elected: bool,
+}
+
+/// A voter in the staked representation used by balancing and PhragMMS.
+#[derive(Clone, Default, Debug)]
+pub(crate) struct StakedVoter<AccountId> {
+	/// Identifier.
+	pub(crate) who: AccountId,
+	/// The stake of this voter.
+	pub(crate) budget: ExtendedBalance,
+	/// The candidates this voter voted for, and how much of the budget each of them receives.
+	pub(crate) edges: Vec<StakedEdge>,
+}
+
+impl<AccountId: Clone> StakedVoter<AccountId> {
+	/// Convert this voter into a [`StakedAssignment`] over the elected candidates that receive
+	/// some of its budget, if any.
+	pub(crate) fn into_staked_assignment(
+		self,
+		candidates: &[StakedCandidate<AccountId>],
+	) -> Option<StakedAssignment<AccountId>> {
+		let distribution = self.edges
+			.into_iter()
+			.filter(|e| candidates[e.candidate_index].elected && !e.weight.is_zero())
+			.map(|e| (candidates[e.candidate_index].who.clone(), e.weight))
+			.collect::<Vec<_>>();
+
+		if distribution.is_empty() {
+			None
+		} else {
+			Some(StakedAssignment { who: self.who, distribution })
+		}
+	}
+}
+
+/// A vote of a [`StakedVoter`].
+#[derive(Clone, Default, Debug)]
+pub(crate) struct StakedEdge {
+	/// Index of the candidate in the candidates vector.
+	pub(crate) candidate_index: usize,
+	/// The part of the budget of the voter given to the candidate.
+	pub(crate) weight: ExtendedBalance,
+}
+
+/// Balance the stake distribution of the given assignments among `winners`, in place.
+///
+/// Runs at most `iterations` rounds, and stops early once no voter had to move more than
+/// `tolerance` in a round. Returns the number of rounds that were executed.
+///
+/// Edges of the assignments that point to a target which is not among the `winners` are left
+/// untouched.
+///
+/// - `winners`: the elected candidates.
+/// - `assignments`: the staked assignments of the election result. This parameter is updated.
+/// - `iterations`: maximum number of rounds.
+/// - `tolerance`: maximum difference that can occur before an early exit happens.
+pub fn balance<AccountId: IdentifierT>(
+	winners: &[AccountId],
+	assignments: &mut Vec<StakedAssignment<AccountId>>,
+	iterations: usize,
+	tolerance: ExtendedBalance,
+) -> usize {
+	let mut c_idx_cache = BTreeMap::<AccountId, usize>::new();
+	let mut candidates = winners
+		.iter()
+		.enumerate()
+		.map(|(idx, who)| {
+			c_idx_cache.insert(who.clone(), idx);
+			StakedCandidate { who: who.clone(), elected: true, ..Default::default() }
+		})
+		.collect::<Vec<_>>();
+
+	let mut voters = assignments
+		.iter()
+		.map(|a| {
+			let edges = a.distribution
+				.iter()
+				.filter_map(|(target, weight)| c_idx_cache.get(target).map(|idx| {
+					candidates[*idx].backed_stake =
+						candidates[*idx].backed_stake.saturating_add(*weight);
+					StakedEdge { candidate_index: *idx, weight: *weight }
+				}))
+				.collect::<Vec<_>>();
+			// only the stake behind the winners can be re-distributed.
+			let budget = edges.iter().fold(0 as ExtendedBalance, |s, e| s.saturating_add(e.weight));
+			StakedVoter { who: a.who.clone(), budget, edges }
+		})
+		.collect::<Vec<_>>();
+
+	let rounds = balance_voters(&mut voters, &mut candidates, iterations, tolerance);
+
+	// write the new weights back, in the same order as they were read.
+	for (assignment, voter) in assignments.iter_mut().zip(voters.into_iter()) {
+		let mut edges = voter.edges.into_iter();
+		for (target, weight) in assignment.distribution.iter_mut() {
+			if c_idx_cache.contains_key(target) {
+				if let Some(edge) = edges.next() {
+					*weight = edge.weight;
+				}
+			}
+		}
+		assignment.distribution.retain(|(_, weight)| !weight.is_zero());
+	}
+	assignments.retain(|a| !a.distribution.is_empty());
+
+	rounds
+}
+
+/// Balance the budgets of `voters` among the elected `candidates`. Same interface as [`balance`].
+pub(crate) fn balance_voters<AccountId>(
+	voters: &mut [StakedVoter<AccountId>],
+	candidates: &mut [StakedCandidate<AccountId>],
+	iterations: usize,
+	tolerance: ExtendedBalance,
+) -> usize {
+	if iterations == 0 { return 0; }
+
+	let mut i = 0;
+	loop {
+		let mut max_diff = 0;
+		for voter in voters.iter_mut() {
+			let diff = balance_voter(voter, candidates, tolerance);
+			if diff > max_diff { max_diff = diff; }
+		}
+
+		i += 1;
+		if max_diff <= tolerance || i >= iterations {
+			break i;
+		}
+	}
+}
+
+/// Balance the budget of a single voter. Returns the difference between the most and the least
+/// backed candidates of the voter, prior to balancing.
+fn balance_voter<AccountId>(
+	voter: &mut StakedVoter<AccountId>,
+	candidates: &mut [StakedCandidate<AccountId>],
+	tolerance: ExtendedBalance,
+) -> ExtendedBalance {
+	let budget = voter.budget;
+	let mut elected_edges = voter.edges
+		.iter_mut()
+		.filter(|e| candidates[e.candidate_index].elected)
+		.collect::<Vec<&mut StakedEdge>>();
+
+	// Either nothing to do, or a single vote which can not be re-distributed.
+	if elected_edges.len() <= 1 { return Zero::zero(); }
+
+	let stake_used = elected_edges
+		.iter()
+		.fold(0 as ExtendedBalance, |s, e| s.saturating_add(e.weight));
+
+	let min_stake = elected_edges
+		.iter()
+		.map(|e| candidates[e.candidate_index].backed_stake)
+		.min()
+		.expect("vector with at least two elements has a min; qed");
+	let max_backing_stake = elected_edges
+		.iter()
+		.filter(|e| !e.weight.is_zero())
+		.map(|e| candidates[e.candidate_index].backed_stake)
+		.max();
+
+	let difference = if let Some(max_stake) = max_backing_stake {
+		let difference = max_stake
+			.saturating_sub(min_stake)
+			.saturating_add(budget.saturating_sub(stake_used));
+		if difference < tolerance {
+			return difference;
+		}
+		difference
+	} else {
+		budget
+	};
+
+	// remove all of the backing of this voter.
+	for edge in elected_edges.iter_mut() {
+		let candidate = &mut candidates[edge.candidate_index];
+		candidate.backed_stake = candidate.backed_stake.saturating_sub(edge.weight);
+		edge.weight = Zero::zero();
+	}
+
+	elected_edges.sort_unstable_by_key(|e| candidates[e.candidate_index].backed_stake);
+
+	// find the number of candidates among which the budget is split, from the least backed one.
+	let mut cumulative_stake: ExtendedBalance = 0;
+	let mut last_index = elected_edges.len() - 1;
+	for (idx, edge) in elected_edges.iter().enumerate() {
+		let stake = candidates[edge.candidate_index].backed_stake;
+		if stake.saturating_mul(idx as ExtendedBalance).saturating_sub(cumulative_stake) > budget {
+			last_index = idx.saturating_sub(1);
+			break;
+		}
+		cumulative_stake = cumulative_stake.saturating_add(stake);
+	}
+
+	let last_stake = candidates[elected_edges[last_index].candidate_index].backed_stake;
+	let split_ways = last_index + 1;
+	let excess = budget
+		.saturating_add(cumulative_stake)
+		.saturating_sub(last_stake.saturating_mul(split_ways as ExtendedBalance));
+	for edge in elected_edges.into_iter().take(split_ways) {
+		let candidate = &mut candidates[edge.candidate_index];
+		edge.weight = (excess / split_ways as ExtendedBalance)
+			.saturating_add(last_stake)
+			.saturating_sub(candidate.backed_stake);
+		candidate.backed_stake = candidate.backed_stake.saturating_add(edge.weight);
+	}
+
+	difference
+}
@@ -25,6 +25,11 @@
 //!   - Equalize post-processing: tries to further distribute the weight fairly among candidates.
 //!     Incurs more execution time.
 //!
+//! Alternatively, [`phragmms`] implements the PhragMMS (maximin support) method, which can be
+//! combined with the iterative [`balance`] post-processing. Both methods are exposed through the
+//! [`NposSolver`] trait, such that the consumers of this crate can choose one of them through
+//! configuration: see [`SequentialPhragmen`] and [`PhragMMS`].
+//!
 //! The main objective of the assignments done by phragmen is to maximize the minimum backed
 //! candidate in the elected set.
 //!
@@ -34,7 +39,10 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use sp_std::{prelude::*, collections::btree_map::BTreeMap, fmt::Debug, cmp::Ordering, convert::TryFrom};
+use sp_std::{
+	prelude::*, collections::btree_map::BTreeMap, fmt::Debug, cmp::Ordering, convert::TryFrom,
+	marker::PhantomData, ops::Mul,
+};
 use sp_arithmetic::{
 	PerThing, Rational128,
 	helpers_128bit::multiply_by_rational,
@@ -53,10 +61,16 @@ use codec::{Encode, Decode};
 mod node;
 mod reduce;
 mod helpers;
+mod balancing;
+mod phragmms;
 
 // re-export reduce stuff.
 pub use reduce::reduce;
 
+// re-export the balancing and phragmms stuff.
+pub use balancing::balance;
+pub use phragmms::phragmms;
+
 // re-export the helpers.
 pub use helpers::*;
 
@@ -305,8 +319,8 @@ impl<AccountId> StakedAssignment<AccountId> {
 ///
 /// This, at the current version, resembles the `Exposure` defined in the Staking pallet, yet
 /// they do not necessarily have to be the same.
-#[derive(Default, Debug)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Eq, PartialEq))]
+#[derive(Default, Debug, Clone, Eq, PartialEq, codec::Encode, codec::Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Support<AccountId> {
 	/// Total support.
 	pub total: ExtendedBalance,
@@ -317,6 +331,9 @@ pub struct Support<AccountId> {
 /// A linkage from a candidate and its [`Support`].
 pub type SupportMap<A> = BTreeMap<A, Support<A>>;
 
+/// A flat list of candidates and their [`Support`], e.g. as returned by an election.
+pub type Supports<A> = Vec<(A, Support<A>)>;
+
 /// Perform election based on Phragmén algorithm.
 ///
 /// Returns an `Option` the set of winners and their detailed support ratio from each voter if
@@ -530,6 +547,118 @@ pub fn elect<AccountId, R>(
 	})
 }
 
+/// The configuration of the [`balance`] post-processing used by a [`NposSolver`].
+pub trait BalancingConfig {
+	/// Maximum number of balancing rounds. Zero disables balancing.
+	const ITERATIONS: usize;
+	/// Maximum difference that can occur before balancing exits early.
+	const TOLERANCE: ExtendedBalance;
+}
+
+/// No balancing at all.
+impl BalancingConfig for () {
+	const ITERATIONS: usize = 0;
+	const TOLERANCE: ExtendedBalance = 0;
+}
+
+/// Something that can compute the result of an NPoS election.
+///
+/// The arguments and the return value are the same as the ones of [`elect`].
+pub trait NposSolver {
+	/// Solve the election.
+	fn solve<AccountId, R>(
+		candidate_count: usize,
+		minimum_candidate_count: usize,
+		initial_candidates: Vec<AccountId>,
+		initial_voters: Vec<(AccountId, VoteWeight, Vec<AccountId>)>,
+	) -> Option<PhragmenResult<AccountId, R>> where
+		AccountId: IdentifierT,
+		R: PerThing + Mul<ExtendedBalance, Output = ExtendedBalance>,
+		ExtendedBalance: From<<R as PerThing>::Inner>;
+}
+
+/// A [`NposSolver`] that runs sequential phragmen, i.e. [`elect`], followed by [`balance`] as
+/// configured by `B`.
+pub struct SequentialPhragmen<B = ()>(PhantomData<B>);
+
+impl<B: BalancingConfig> NposSolver for SequentialPhragmen<B> {
+	fn solve<AccountId, R>(
+		candidate_count: usize,
+		minimum_candidate_count: usize,
+		initial_candidates: Vec<AccountId>,
+		initial_voters: Vec<(AccountId, VoteWeight, Vec<AccountId>)>,
+	) -> Option<PhragmenResult<AccountId, R>> where
+		AccountId: IdentifierT,
+		R: PerThing + Mul<ExtendedBalance, Output = ExtendedBalance>,
+		ExtendedBalance: From<<R as PerThing>::Inner>,
+	{
+		if B::ITERATIONS == 0 {
+			return elect(
+				candidate_count,
+				minimum_candidate_count,
+				initial_candidates,
+				initial_voters,
+			);
+		}
+
+		let stake_of = initial_voters
+			.iter()
+			.map(|(who, stake, _)| (who.clone(), *stake))
+			.collect::<BTreeMap<AccountId, VoteWeight>>();
+		let PhragmenResult { winners, assignments } = elect::<AccountId, R>(
+			candidate_count,
+			minimum_candidate_count,
+			initial_candidates,
+			initial_voters,
+		)?;
+
+		let mut staked = assignments
+			.into_iter()
+			.map(|a| {
+				let stake = stake_of.get(&a.who).cloned().unwrap_or_default();
+				a.into_staked(stake.into(), true)
+			})
+			.collect::<Vec<StakedAssignment<AccountId>>>();
+		let winner_ids = winners.iter().map(|(who, _)| who.clone()).collect::<Vec<AccountId>>();
+		balance(&winner_ids, &mut staked, B::ITERATIONS, B::TOLERANCE);
+
+		Some(PhragmenResult {
+			winners,
+			assignments: staked.into_iter().map(|a| a.into_assignment(true)).collect(),
+		})
+	}
+}
+
+/// A [`NposSolver`] that runs [`phragmms`], with [`balance`] applied after each round as
+/// configured by `B`.
+pub struct PhragMMS<B = ()>(PhantomData<B>);
+
+impl<B: BalancingConfig> NposSolver for PhragMMS<B> {
+	fn solve<AccountId, R>(
+		candidate_count: usize,
+		minimum_candidate_count: usize,
+		initial_candidates: Vec<AccountId>,
+		initial_voters: Vec<(AccountId, VoteWeight, Vec<AccountId>)>,
+	) -> Option<PhragmenResult<AccountId, R>> where
+		AccountId: IdentifierT,
+		R: PerThing + Mul<ExtendedBalance, Output = ExtendedBalance>,
+		ExtendedBalance: From<<R as PerThing>::Inner>,
+	{
+		let balancing = if B::ITERATIONS == 0 {
+			None
+		} else {
+			Some((B::ITERATIONS, B::TOLERANCE))
+		};
+		phragmms(
+			candidate_count,
+			minimum_candidate_count,
+			initial_candidates,
+			initial_voters,
+			balancing,
+		)
+	}
+}
+
 /// Build the support map from the given phragmen result. It maps a flat structure like
 ///
 /// ```nocompile
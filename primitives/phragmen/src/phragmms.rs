@@ -0,0 +1,218 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of the PhragMMS method.
+//!
+//! PhragMMS is a greedy election algorithm. In each round, it elects the candidate that would
+//! receive the highest support if it was inserted into the current solution, i.e. the maximin
+//! support candidate, and re-distributes the stake of its voters accordingly. Combined with
+//! balancing after each round, it yields a constant-factor approximation of the maximin support
+//! objective.
+//!
+//! Reference: https://arxiv.org/abs/2004.12990
+
+use crate::{
+	ExtendedBalance, IdentifierT, PhragmenResult, VoteWeight,
+	balancing::{StakedCandidate, StakedEdge, StakedVoter, balance_voters},
+};
+use sp_arithmetic::{
+	PerThing,
+	helpers_128bit::multiply_by_rational,
+	traits::{Bounded, Zero},
+};
+use sp_std::{prelude::*, collections::btree_map::BTreeMap};
+
+/// The fixed-point scale used to compute the score denominators of the candidates.
+const SCALE: ExtendedBalance = 1_000_000_000_000_000_000;
+
+/// Perform election based on the PhragMMS algorithm.
+///
+/// Returns an `Option` the set of winners and their detailed support ratio from each voter if
+/// enough candidates are provided. Returns `None` otherwise.
+///
+/// * `candidate_count`: number of candidates to elect.
+/// * `minimum_candidate_count`: minimum number of candidates to elect. If less candidates exist,
+///   `None` is returned.
+/// * `initial_candidates`: candidates list to be elected from.
+/// * `initial_voters`: voters list.
+/// * `balancing`: if given, the maximum number of iterations and the tolerance of the balancing
+///   that is applied after each round.
+///
+/// Just like [`crate::elect`], this function does not strip out candidates who do not have any
+/// backing stake. They are only elected once no candidate with some backing is left.
+pub fn phragmms<AccountId, R>(
+	candidate_count: usize,
+	minimum_candidate_count: usize,
+	initial_candidates: Vec<AccountId>,
+	initial_voters: Vec<(AccountId, VoteWeight, Vec<AccountId>)>,
+	balancing: Option<(usize, ExtendedBalance)>,
+) -> Option<PhragmenResult<AccountId, R>> where
+	AccountId: IdentifierT,
+	R: PerThing,
+	ExtendedBalance: From<<R as PerThing>::Inner>,
+{
+	// used to cache and access candidates index.
+	let mut c_idx_cache = BTreeMap::<AccountId, usize>::new();
+
+	let mut candidates = initial_candidates
+		.into_iter()
+		.enumerate()
+		.map(|(idx, who)| {
+			c_idx_cache.insert(who.clone(), idx);
+			StakedCandidate { who, ..Default::default() }
+		})
+		.collect::<Vec<StakedCandidate<AccountId>>>();
+
+	// early return if we don't have enough candidates
+	if candidates.len() < minimum_candidate_count { return None; }
+
+	let mut voters = initial_voters
+		.into_iter()
+		.map(|(who, voter_stake, votes)| {
+			let budget: ExtendedBalance = voter_stake.into();
+			let mut edges: Vec<StakedEdge> = Vec::with_capacity(votes.len());
+			for v in votes {
+				if let Some(idx) = c_idx_cache.get(&v) {
+					candidates[*idx].approval_stake =
+						candidates[*idx].approval_stake.saturating_add(budget);
+					edges.push(StakedEdge { candidate_index: *idx, weight: Zero::zero() });
+				}
+			}
+			StakedVoter { who, budget, edges }
+		})
+		.collect::<Vec<StakedVoter<AccountId>>>();
+
+	let to_elect = candidate_count.min(candidates.len());
+	let mut winners = Vec::with_capacity(to_elect);
+	for _round in 0..to_elect {
+		if let Some((winner, score)) = max_score_candidate(&candidates, &voters) {
+			apply_elected(&mut voters, &mut candidates, winner, score);
+			winners.push(winner);
+
+			if let Some((iterations, tolerance)) = balancing {
+				balance_voters(&mut voters, &mut candidates, iterations, tolerance);
+			}
+		} else {
+			break
+		}
+	}
+
+	let winners = winners
+		.into_iter()
+		.map(|idx| (candidates[idx].who.clone(), candidates[idx].approval_stake))
+		.collect();
+	let assignments = voters
+		.into_iter()
+		.filter_map(|v| v.into_staked_assignment(&candidates))
+		.map(|a| a.into_assignment(true))
+		.collect();
+
+	Some(PhragmenResult { winners, assignments })
+}
+
+/// Find the unelected candidate with the highest score, along with its score.
+///
+/// The score of a candidate `c` is `approval(c) / (1 + Σ_v Σ_e w_e / backed(e))`, where `v`
+/// iterates over the voters of `c` and `e` over their edges to elected candidates. It is the
+/// support that `c` would get if elected, see [`apply_elected`].
+fn max_score_candidate<AccountId>(
+	candidates: &[StakedCandidate<AccountId>],
+	voters: &[StakedVoter<AccountId>],
+) -> Option<(usize, ExtendedBalance)> {
+	let mut denominators = vec![SCALE; candidates.len()];
+	for voter in voters {
+		let contribution = voter.edges
+			.iter()
+			.filter(|e| candidates[e.candidate_index].elected)
+			.fold(0 as ExtendedBalance, |acc, e| {
+				let backed_stake = candidates[e.candidate_index].backed_stake;
+				if backed_stake.is_zero() {
+					acc
+				} else {
+					// `weight <= backed_stake`, thus this can not exceed `SCALE`.
+					acc.saturating_add(
+						multiply_by_rational(SCALE, e.weight, backed_stake).unwrap_or(SCALE)
+					)
+				}
+			});
+
+		for edge in voter.edges.iter().filter(|e| !candidates[e.candidate_index].elected) {
+			denominators[edge.candidate_index] =
+				denominators[edge.candidate_index].saturating_add(contribution);
+		}
+	}
+
+	candidates
+		.iter()
+		.zip(denominators.into_iter())
+		.enumerate()
+		.filter(|(_, (c, _))| !c.elected)
+		.map(|(idx, (c, denominator))| {
+			let score = multiply_by_rational(c.approval_stake, SCALE, denominator)
+				.unwrap_or(Bounded::max_value());
+			(idx, score)
+		})
+		.fold(None, |best, (idx, score)| match best {
+			Some((_, best_score)) if best_score >= score => best,
+			_ => Some((idx, score)),
+		})
+}
+
+/// Elect the candidate at index `winner`, with a support of at least `cutoff`.
+///
+/// Every voter of the winner gives it its unused budget, and moves to it the part of its stake
+/// behind other candidates which are backed by more than `cutoff`, proportionally to how much
+/// these are over the cutoff.
+fn apply_elected<AccountId>(
+	voters: &mut [StakedVoter<AccountId>],
+	candidates: &mut [StakedCandidate<AccountId>],
+	winner: usize,
+	cutoff: ExtendedBalance,
+) {
+	candidates[winner].elected = true;
+
+	for voter in voters.iter_mut() {
+		if let Some(new_edge_index) = voter.edges.iter().position(|e| e.candidate_index == winner) {
+			let used_budget = voter.edges
+				.iter()
+				.fold(0 as ExtendedBalance, |s, e| s.saturating_add(e.weight));
+			let mut new_edge_weight = voter.budget.saturating_sub(used_budget);
+
+			for (_, edge) in voter.edges
+				.iter_mut()
+				.enumerate()
+				.filter(|(idx, e)| *idx != new_edge_index && !e.weight.is_zero())
+			{
+				let candidate = &mut candidates[edge.candidate_index];
+				if candidate.backed_stake > cutoff {
+					// keep `weight * cutoff / backed_stake` and move the rest.
+					let kept = multiply_by_rational(edge.weight, cutoff, candidate.backed_stake)
+						.unwrap_or(edge.weight);
+					let moved = edge.weight.saturating_sub(kept);
+
+					edge.weight = kept;
+					candidate.backed_stake = candidate.backed_stake.saturating_sub(moved);
+					new_edge_weight = new_edge_weight.saturating_add(moved);
+				}
+			}
+
+			voter.edges[new_edge_index].weight = new_edge_weight;
+			candidates[winner].backed_stake =
+				candidates[winner].backed_stake.saturating_add(new_edge_weight);
+		}
+	}
+}
@@ -21,8 +21,9 @@
 
 use crate::mock::*;
 use crate::{
-	elect, equalize, build_support_map, is_score_better, helpers::*,
-	Support, StakedAssignment, Assignment, PhragmenResult, ExtendedBalance,
+	elect, equalize, build_support_map, is_score_better, helpers::*, balance, phragmms,
+	evaluate_support, Support, StakedAssignment, Assignment, PhragmenResult, ExtendedBalance,
+	BalancingConfig, NposSolver, SequentialPhragmen, PhragMMS,
 };
 use substrate_test_utils::assert_eq_uvec;
 use sp_arithmetic::{Perbill, Permill, Percent, PerU16};
@@ -642,6 +643,179 @@ fn score_comparison_is_lexicographical() {
 	);
 }
 
+struct TestBalancing;
+impl BalancingConfig for TestBalancing {
+	const ITERATIONS: usize = 10;
+	const TOLERANCE: ExtendedBalance = 0;
+}
+
+#[test]
+fn balance_works() {
+	let winners = vec![2, 3];
+	let mut staked = vec![
+		StakedAssignment { who: 10u64, distribution: vec![(2, 10)] },
+		StakedAssignment { who: 20, distribution: vec![(3, 20)] },
+		StakedAssignment { who: 30, distribution: vec![(2, 15), (3, 15)] },
+	];
+
+	assert_eq!(balance(&winners, &mut staked, 10, 0), 2);
+
+	assert_eq_uvec!(
+		staked,
+		vec![
+			StakedAssignment { who: 10u64, distribution: vec![(2, 10)] },
+			StakedAssignment { who: 20, distribution: vec![(3, 20)] },
+			StakedAssignment { who: 30, distribution: vec![(2, 20), (3, 10)] },
+		]
+	);
+
+	let (support_map, _) = build_support_map::<AccountId>(&winners, &staked);
+	assert_eq!(evaluate_support(&support_map), [30, 60, 1800]);
+}
+
+#[test]
+fn balance_keeps_edges_to_non_winners() {
+	let winners = vec![2, 3];
+	let mut staked = vec![
+		StakedAssignment { who: 10u64, distribution: vec![(1, 5), (2, 10), (3, 0)] },
+	];
+
+	assert_eq!(balance(&winners, &mut staked, 10, 0), 2);
+
+	assert_eq!(
+		staked,
+		vec![StakedAssignment { who: 10u64, distribution: vec![(1, 5), (2, 5), (3, 5)] }],
+	);
+}
+
+#[test]
+fn phragmms_poc_works() {
+	let candidates = vec![1, 2, 3];
+	let voters = vec![
+		(10, vec![1, 2]),
+		(20, vec![1, 3]),
+		(30, vec![2, 3]),
+	];
+	let stake_of = create_stake_of(&[(10, 10), (20, 20), (30, 30)]);
+	let voters = voters
+		.iter()
+		.map(|(ref v, ref vs)| (v.clone(), stake_of(v), vs.clone()))
+		.collect::<Vec<_>>();
+
+	let PhragmenResult { winners, assignments } = phragmms::<_, Perbill>(
+		2,
+		2,
+		candidates.clone(),
+		voters.clone(),
+		None,
+	).unwrap();
+
+	assert_eq_uvec!(winners, vec![(3, 50), (2, 40)]);
+	assert_eq_uvec!(
+		assignments,
+		vec![
+			Assignment {
+				who: 10u64,
+				distribution: vec![(2, Perbill::from_percent(100))],
+			},
+			Assignment {
+				who: 20,
+				distribution: vec![(3, Perbill::from_percent(100))],
+			},
+			Assignment {
+				who: 30,
+				distribution: vec![
+					(2, Perbill::from_percent(50)),
+					(3, Perbill::from_percent(50)),
+				],
+			},
+		]
+	);
+
+	// with balancing, the stake of 30 is moved towards 2.
+	let PhragmenResult { winners, assignments } = phragmms::<_, Perbill>(
+		2,
+		2,
+		candidates,
+		voters,
+		Some((10, 0)),
+	).unwrap();
+
+	let staked = assignment_ratio_to_staked(assignments, &stake_of);
+	let winners = to_without_backing(winners);
+	let (support_map, errors) = build_support_map::<AccountId>(&winners, &staked);
+
+	assert_eq!(errors, 0);
+	assert_eq!(evaluate_support(&support_map), [30, 60, 1800]);
+}
+
+#[test]
+fn phragmms_minimum_to_elect_is_respected() {
+	let candidates = vec![10, 20, 30];
+	let voters = vec![
+		(1, 10, vec![10]),
+		(2, 10, vec![20]),
+	];
+
+	assert!(phragmms::<_, Perbill>(10, 10, candidates, voters, None).is_none());
+}
+
+#[test]
+fn phragmms_has_no_entry_barrier() {
+	let candidates = vec![10, 20, 30];
+	let voters = vec![
+		(1, 10, vec![10]),
+		(2, 10, vec![10, 20]),
+	];
+
+	let PhragmenResult { winners, .. } = phragmms::<_, Perbill>(3, 3, candidates, voters, None)
+		.unwrap();
+
+	// 30 has no backing, yet it is elected to fill the seats.
+	assert_eq_uvec!(winners, vec![(10, 20), (20, 10), (30, 0)]);
+}
+
+#[test]
+fn npos_solvers_work() {
+	let candidates = vec![1, 2, 3];
+	let voters = vec![
+		(10, 10, vec![1, 2]),
+		(20, 20, vec![1, 3]),
+		(30, 30, vec![2, 3]),
+	];
+	let stake_of = create_stake_of(&[(10, 10), (20, 20), (30, 30)]);
+	let score_of = |result: PhragmenResult<AccountId, Perbill>| {
+		let staked = assignment_ratio_to_staked(result.assignments, &stake_of);
+		let winners = to_without_backing(result.winners);
+		let (support_map, errors) = build_support_map::<AccountId>(&winners, &staked);
+		assert_eq!(errors, 0);
+		evaluate_support(&support_map)
+	};
+
+	// without balancing, sequential phragmen is exactly `elect`.
+	let result = SequentialPhragmen::<()>::solve::<_, Perbill>(
+		2,
+		2,
+		candidates.clone(),
+		voters.clone(),
+	).unwrap();
+	let expected = elect::<_, Perbill>(2, 2, candidates.clone(), voters.clone()).unwrap();
+	assert_eq!(result.winners, expected.winners);
+	assert_eq!(result.assignments, expected.assignments);
+	assert_eq!(score_of(result), [25, 60, 1850]);
+
+	let result = SequentialPhragmen::<TestBalancing>::solve::<_, Perbill>(
+		2,
+		2,
+		candidates.clone(),
+		voters.clone(),
+	).unwrap();
+	assert_eq!(score_of(result), [30, 60, 1800]);
+
+	let result = PhragMMS::<TestBalancing>::solve::<_, Perbill>(2, 2, candidates, voters).unwrap();
+	assert_eq!(score_of(result), [30, 60, 1800]);
+}
+
 mod compact {
 	use codec::{Decode, Encode};
 	use crate::{generate_compact_solution_type, VoteWeight};
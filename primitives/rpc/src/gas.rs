@@ -0,0 +1,86 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Estimation of the gas limit a call requires.
+
+/// The maximum number of attempts made by [`search_gas_limit`].
+///
+/// This bounds the time an RPC estimating the gas of a call takes. If the search is cut short
+/// the returned gas limit still suffices but can be slightly above the smallest one.
+pub const MAX_GAS_SEARCH_STEPS: u32 = 20;
+
+/// Finds the smallest gas limit within `low ..= high` for which `succeeds` returns `true`.
+///
+/// `succeeds` must return `true` for `high` and for every limit above the smallest one. It is
+/// called at most [`MAX_GAS_SEARCH_STEPS`] times, after which the smallest limit found so far is
+/// returned.
+pub fn search_gas_limit<E>(
+	mut low: u64,
+	mut high: u64,
+	mut succeeds: impl FnMut(u64) -> Result<bool, E>,
+) -> Result<u64, E> {
+	if low >= high || succeeds(low)? {
+		return Ok(low.min(high));
+	}
+
+	// From here on the call is known to fail with `low` and to succeed with `high`.
+	let mut steps = 1;
+	while high - low > 1 && steps < MAX_GAS_SEARCH_STEPS {
+		steps += 1;
+		let mid = low + (high - low) / 2;
+		if succeeds(mid)? {
+			high = mid;
+		} else {
+			low = mid;
+		}
+	}
+
+	Ok(high)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn finds_smallest_limit() {
+		let required = |limit| Ok::<_, ()>(limit >= 1_234);
+		assert_eq!(search_gas_limit(1_000, 5_000, required), Ok(1_234));
+		assert_eq!(search_gas_limit(1_234, 5_000, required), Ok(1_234));
+		assert_eq!(search_gas_limit(1_233, 1_234, required), Ok(1_234));
+		assert_eq!(search_gas_limit(5_000, 5_000, required), Ok(5_000));
+	}
+
+	#[test]
+	fn is_bounded() {
+		let high = 5_000_000_000_000;
+		let mut calls = 0;
+		let limit = search_gas_limit(0, high, |limit| {
+			calls += 1;
+			Ok::<_, ()>(limit >= 1_234)
+		}).unwrap();
+
+		assert_eq!(calls, MAX_GAS_SEARCH_STEPS);
+		assert!(limit >= 1_234);
+		assert!(limit - 1_234 <= high >> (MAX_GAS_SEARCH_STEPS - 1));
+	}
+
+	#[test]
+	fn passes_errors_on() {
+		assert_eq!(search_gas_limit(0, 5_000, |_| Err("trapped")), Err("trapped"));
+	}
+}
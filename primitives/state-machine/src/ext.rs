@@ -547,6 +547,22 @@ where
 		root.map(|r| r.map(|o| o.encode()))
 	}
 
+	fn storage_start_transaction(&mut self) {
+		trace!(target: "state", "{:04x}: StartTransaction", self.id);
+		self.overlay.start_transaction();
+	}
+
+	fn storage_rollback_transaction(&mut self) -> Result<(), ()> {
+		trace!(target: "state", "{:04x}: RollbackTransaction", self.id);
+		self.mark_dirty();
+		self.overlay.rollback_transaction().map_err(|_| ())
+	}
+
+	fn storage_commit_transaction(&mut self) -> Result<(), ()> {
+		trace!(target: "state", "{:04x}: CommitTransaction", self.id);
+		self.overlay.commit_transaction().map_err(|_| ())
+	}
+
 	fn wipe(&mut self) {
 		self.overlay.discard_prospective();
 		self.overlay.drain_storage_changes(
@@ -67,7 +67,7 @@ pub use changes_trie::{
 };
 pub use overlayed_changes::{
 	OverlayedChanges, StorageChanges, StorageTransactionCache, StorageKey, StorageValue,
-	StorageCollection, ChildStorageCollection,
+	StorageCollection, ChildStorageCollection, NoOpenTransaction,
 };
 pub use proving_backend::{
 	create_proof_check_backend, ProofRecorder, ProvingBackend, ProvingBackendRecorder,
@@ -747,6 +747,164 @@ where
 		.map_err(|e| Box::new(e) as Box<dyn Error>)
 }
 
+/// Generate a proof of the key-value pairs of the top trie, or of the given child trie, that
+/// follow `start_at` in lexicographic order. The whole trie is proven if `start_at` is `None`.
+///
+/// Key-value pairs are proven until the end of the trie, or until the proof is larger than
+/// `size_limit` bytes, with at least one pair proven. Returns the proof, and the number of
+/// key-value pairs proven.
+pub fn prove_range_read_with_size<B, H>(
+	mut backend: B,
+	child_info: Option<&ChildInfo>,
+	start_at: Option<&[u8]>,
+	size_limit: usize,
+) -> Result<(StorageProof, u32), Box<dyn Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let trie_backend = backend.as_trie_backend()
+		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+	prove_range_read_with_size_on_trie_backend(trie_backend, child_info, start_at, size_limit)
+}
+
+/// Generate a range proof on pre-created trie backend. See [`prove_range_read_with_size`].
+pub fn prove_range_read_with_size_on_trie_backend<S, H>(
+	trie_backend: &TrieBackend<S, H>,
+	child_info: Option<&ChildInfo>,
+	start_at: Option<&[u8]>,
+	size_limit: usize,
+) -> Result<(StorageProof, u32), Box<dyn Error>>
+where
+	S: trie_backend_essence::TrieBackendStorage<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	// Computing the size of the proof is linear in the number of recorded nodes, so it is only
+	// checked every few pairs.
+	const SIZE_CHECK_INTERVAL: u32 = 16;
+
+	let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+	let mut count = 0;
+	let mut next = next_range_key(&proving_backend, child_info, start_at)
+		.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+	while let Some(key) = next {
+		range_value(&proving_backend, child_info, &key)
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+		count += 1;
+
+		if count % SIZE_CHECK_INTERVAL == 0 && proving_backend.estimate_encoded_size() >= size_limit {
+			break;
+		}
+
+		next = next_range_key(&proving_backend, child_info, Some(&key))
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+	}
+	Ok((proving_backend.extract_proof(), count))
+}
+
+/// Result of checking a range proof, see [`read_range_proof_check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckedRange {
+	/// Key-value pairs following the start key, in lexicographic order.
+	pub values: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Trie nodes the key-value pairs were read from, keyed by their prefixed database key.
+	/// Once every range of a trie has been checked, this covers all the nodes of the trie.
+	pub nodes: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Whether the end of the trie was reached.
+	pub complete: bool,
+}
+
+/// Check a proof generated by a `prove_range_read_with_size` call.
+///
+/// Returns all the key-value pairs following `start_at` that can be read from the proof, the
+/// trie nodes they were read from, and whether the end of the trie was reached. Fails if the
+/// proof doesn't prove a single pair, while the end of the trie is not proven either.
+pub fn read_range_proof_check<H>(
+	root: H::Out,
+	proof: StorageProof,
+	child_info: Option<&ChildInfo>,
+	start_at: Option<&[u8]>,
+) -> Result<CheckedRange, Box<dyn Error>>
+where
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let db = proof.into_memory_db::<H>();
+	if !hash_db::HashDB::contains(&db, &root, hash_db::EMPTY_PREFIX) {
+		return Err(Box::new(ExecutionError::InvalidProof) as Box<dyn Error>);
+	}
+	let proving_backend = TrieBackend::new(proving_backend::ReadRecorder::new(db), root);
+
+	let mut values = Vec::new();
+	// The proof ends at the first node missing from it.
+	let mut next = next_range_key(&proving_backend, child_info, start_at);
+	let complete = loop {
+		let key = match next {
+			Ok(Some(key)) => key,
+			Ok(None) => break true,
+			Err(_) => break false,
+		};
+		let value = match range_value(&proving_backend, child_info, &key) {
+			Ok(Some(value)) => value,
+			_ => break false,
+		};
+		next = next_range_key(&proving_backend, child_info, Some(&key));
+		values.push((key, value));
+	};
+
+	if values.is_empty() && !complete {
+		return Err(Box::new(ExecutionError::InvalidProof) as Box<dyn Error>);
+	}
+	let nodes = proving_backend.into_storage().into_read();
+	Ok(CheckedRange { values, nodes, complete })
+}
+
+/// Returns the first key following `start_at` in the top trie or in the given child trie, or the
+/// first key of the trie if `start_at` is `None`.
+fn next_range_key<B, H>(
+	backend: &B,
+	child_info: Option<&ChildInfo>,
+	start_at: Option<&[u8]>,
+) -> Result<Option<Vec<u8>>, B::Error>
+where
+	B: Backend<H>,
+	H: Hasher,
+{
+	match (start_at, child_info) {
+		(Some(key), Some(child_info)) => backend.next_child_storage_key(child_info, key),
+		(Some(key), None) => backend.next_storage_key(key),
+		// the empty key is a valid key, that `next_storage_key` would skip.
+		(None, Some(child_info)) => if backend.exists_child_storage(child_info, &[])? {
+			Ok(Some(Vec::new()))
+		} else {
+			backend.next_child_storage_key(child_info, &[])
+		},
+		(None, None) => if backend.exists_storage(&[])? {
+			Ok(Some(Vec::new()))
+		} else {
+			backend.next_storage_key(&[])
+		},
+	}
+}
+
+/// Returns the value of `key` in the top trie or in the given child trie.
+fn range_value<B, H>(
+	backend: &B,
+	child_info: Option<&ChildInfo>,
+	key: &[u8],
+) -> Result<Option<Vec<u8>>, B::Error>
+where
+	B: Backend<H>,
+	H: Hasher,
+{
+	match child_info {
+		Some(child_info) => backend.child_storage(child_info, key),
+		None => backend.storage(key),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::collections::BTreeMap;
@@ -1270,6 +1428,83 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn prove_range_read_and_proof_check_works() {
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let expected = remote_backend.pairs();
+
+		// download the whole state in small chunks
+		let mut values = Vec::new();
+		let mut nodes = HashMap::new();
+		let mut start_at: Option<Vec<u8>> = None;
+		let mut chunks = 0;
+		loop {
+			let (proof, count) = prove_range_read_with_size(
+				trie_backend::tests::test_trie(),
+				None,
+				start_at.as_ref().map(|k| k.as_slice()),
+				0,
+			).unwrap();
+			let checked = read_range_proof_check::<BlakeTwo256>(
+				remote_root,
+				proof,
+				None,
+				start_at.as_ref().map(|k| k.as_slice()),
+			).unwrap();
+			assert!(checked.values.len() as u32 >= count);
+			start_at = checked.values.last().map(|(k, _)| k.clone());
+			values.extend(checked.values);
+			nodes.extend(checked.nodes);
+			chunks += 1;
+			if checked.complete {
+				break;
+			}
+		}
+		assert!(chunks > 1);
+		assert_eq!(values, expected);
+
+		// a proof for another root is rejected
+		let (proof, _) = prove_range_read_with_size(
+			trie_backend::tests::test_trie(),
+			None,
+			None,
+			0,
+		).unwrap();
+		assert!(read_range_proof_check::<BlakeTwo256>(Default::default(), proof, None, None).is_err());
+
+		// on child trie, in one go
+		let child_info = ChildInfo::new_default(b"sub1");
+		let (proof, count) = prove_range_read_with_size(
+			trie_backend::tests::test_trie(),
+			Some(&child_info),
+			None,
+			usize::max_value(),
+		).unwrap();
+		assert_eq!(count, 2);
+		let checked = read_range_proof_check::<BlakeTwo256>(
+			remote_root,
+			proof,
+			Some(&child_info),
+			None,
+		).unwrap();
+		assert!(checked.complete);
+		assert_eq!(checked.values, vec![
+			(b"value3".to_vec(), vec![142]),
+			(b"value4".to_vec(), vec![124]),
+		]);
+		nodes.extend(checked.nodes);
+
+		// the nodes read from the proofs are all the nodes of the remote state.
+		let remote_nodes: HashMap<_, _> = remote_backend.into_storage()
+			.drain()
+			.into_iter()
+			.filter(|(_, (_, rc))| *rc > 0)
+			.map(|(key, (value, _))| (key, value))
+			.collect();
+		assert_eq!(nodes, remote_nodes);
+	}
+
 	#[test]
 	fn child_storage_uuid() {
 
@@ -49,12 +49,19 @@ pub type ChildStorageCollection = Vec<(StorageKey, StorageCollection)>;
 ///
 /// A transaction shares all prospective changes within an inner overlay
 /// that can be cleared.
+///
+/// On top of the prospective changes, nested storage transactions can be started with
+/// [`start_transaction`](OverlayedChanges::start_transaction). Every open transaction keeps
+/// the original value of each prospective key it touches, so it can be rolled back or merged
+/// into its parent without cloning the whole change set.
 #[derive(Debug, Default, Clone)]
 pub struct OverlayedChanges {
 	/// Changes that are not yet committed.
 	prospective: OverlayedChangeSet,
 	/// Committed changes.
 	committed: OverlayedChangeSet,
+	/// Stack of currently open storage transactions, the innermost being the last.
+	transactions: Vec<TransactionLayer>,
 	/// True if extrinsics stats must be collected.
 	collect_extrinsics: bool,
 	/// Collect statistic on this execution.
@@ -82,6 +89,97 @@ pub struct OverlayedChangeSet {
 	children_default: HashMap<StorageKey, (BTreeMap<StorageKey, OverlayedValue>, ChildInfo)>,
 }
 
+/// Error returned when a storage transaction is committed or rolled back while none is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoOpenTransaction;
+
+/// The original values of the prospective keys modified within a single storage transaction.
+///
+/// `None` means that the key was not part of the prospective change set when the
+/// transaction first modified it.
+#[derive(Debug, Default, Clone)]
+struct TransactionLayer {
+	/// Original values of top level storage keys.
+	top: BTreeMap<StorageKey, Option<OverlayedValue>>,
+	/// Original values of child storage keys, alongside the child info the prospective
+	/// child change set had before the transaction touched it (`None` if it did not exist).
+	children_default: HashMap<StorageKey, (Option<ChildInfo>, BTreeMap<StorageKey, Option<OverlayedValue>>)>,
+}
+
+impl TransactionLayer {
+	/// Remember the prospective value of `key` if it was not already modified in this layer.
+	fn note_top(&mut self, prospective: &OverlayedChangeSet, key: &[u8]) {
+		if !self.top.contains_key(key) {
+			self.top.insert(key.to_vec(), prospective.top.get(key).cloned());
+		}
+	}
+
+	/// Remember the prospective values of the given child `keys` that were not already
+	/// modified in this layer.
+	fn note_child<'a>(
+		&mut self,
+		prospective: &OverlayedChangeSet,
+		storage_key: &[u8],
+		keys: impl IntoIterator<Item=&'a [u8]>,
+	) {
+		let prospective_child = prospective.children_default.get(storage_key);
+		let layer_child = self.children_default.entry(storage_key.to_vec())
+			.or_insert_with(|| (prospective_child.map(|c| c.1.clone()), Default::default()));
+		for key in keys {
+			if !layer_child.1.contains_key(key) {
+				layer_child.1.insert(
+					key.to_vec(),
+					prospective_child.and_then(|c| c.0.get(key).cloned()),
+				);
+			}
+		}
+	}
+
+	/// Merge the original values of a committed inner layer into this one.
+	///
+	/// Values already known by this layer are older and thus kept.
+	fn absorb(&mut self, inner: TransactionLayer) {
+		for (key, value) in inner.top {
+			self.top.entry(key).or_insert(value);
+		}
+		for (storage_key, (child_info, values)) in inner.children_default {
+			let child = self.children_default.entry(storage_key)
+				.or_insert_with(|| (child_info, Default::default()));
+			for (key, value) in values {
+				child.1.entry(key).or_insert(value);
+			}
+		}
+	}
+
+	/// Restore the original values noted in this layer into `prospective`.
+	fn revert(self, prospective: &mut OverlayedChangeSet) {
+		for (key, value) in self.top {
+			match value {
+				Some(value) => { prospective.top.insert(key, value); },
+				None => { prospective.top.remove(&key); },
+			}
+		}
+		for (storage_key, (child_info, values)) in self.children_default {
+			let child_info = match child_info {
+				Some(child_info) => child_info,
+				None => {
+					prospective.children_default.remove(&storage_key);
+					continue;
+				},
+			};
+			let child = prospective.children_default.entry(storage_key)
+				.or_insert_with(|| (Default::default(), child_info.clone()));
+			child.1 = child_info;
+			for (key, value) in values {
+				match value {
+					Some(value) => { child.0.insert(key, value); },
+					None => { child.0.remove(&key); },
+				}
+			}
+		}
+	}
+}
+
 /// A storage changes structure that can be generated by the data collected in [`OverlayedChanges`].
 ///
 /// This contains all the changes to the storage and transactions to apply theses changes to the
@@ -244,6 +342,9 @@ impl OverlayedChanges {
 		init: impl Fn() -> StorageValue,
 	) -> &mut StorageValue {
 		let extrinsic_index = self.extrinsic_index();
+		if let Some(layer) = self.transactions.last_mut() {
+			layer.note_top(&self.prospective, key);
+		}
 		let committed = &self.committed.top;
 
 		let mut entry = self.prospective.top.entry(key.to_vec())
@@ -296,6 +397,9 @@ impl OverlayedChanges {
 		let size_write = val.as_ref().map(|x| x.len() as u64).unwrap_or(0);
 		self.stats.tally_write_overlay(size_write);
 		let extrinsic_index = self.extrinsic_index();
+		if let Some(layer) = self.transactions.last_mut() {
+			layer.note_top(&self.prospective, &key);
+		}
 		let entry = self.prospective.top.entry(key).or_default();
 		entry.value = val;
 
@@ -317,6 +421,9 @@ impl OverlayedChanges {
 		let size_write = val.as_ref().map(|x| x.len() as u64).unwrap_or(0);
 		self.stats.tally_write_overlay(size_write);
 		let extrinsic_index = self.extrinsic_index();
+		if let Some(layer) = self.transactions.last_mut() {
+			layer.note_child(&self.prospective, child_info.storage_key(), Some(&key[..]));
+		}
 		let storage_key = child_info.storage_key().to_vec();
 		let map_entry = self.prospective.children_default.entry(storage_key)
 			.or_insert_with(|| (Default::default(), child_info.to_owned()));
@@ -344,6 +451,19 @@ impl OverlayedChanges {
 	) {
 		let extrinsic_index = self.extrinsic_index();
 		let storage_key = child_info.storage_key();
+		if let Some(layer) = self.transactions.last_mut() {
+			let prospective_keys = self.prospective.children_default.get(storage_key)
+				.into_iter()
+				.flat_map(|(map, _)| map.keys());
+			let committed_keys = self.committed.children_default.get(storage_key)
+				.into_iter()
+				.flat_map(|(map, _)| map.keys());
+			layer.note_child(
+				&self.prospective,
+				storage_key,
+				prospective_keys.chain(committed_keys).map(|k| &k[..]),
+			);
+		}
 		let map_entry = self.prospective.children_default.entry(storage_key.to_vec())
 			.or_insert_with(|| (Default::default(), child_info.to_owned()));
 		let updatable = map_entry.1.try_update(child_info);
@@ -383,6 +503,14 @@ impl OverlayedChanges {
 	/// [`discard_prospective`]: #method.discard_prospective
 	pub(crate) fn clear_prefix(&mut self, prefix: &[u8]) {
 		let extrinsic_index = self.extrinsic_index();
+		if let Some(layer) = self.transactions.last_mut() {
+			let keys = self.prospective.top.keys()
+				.chain(self.committed.top.keys())
+				.filter(|key| key.starts_with(prefix));
+			for key in keys {
+				layer.note_top(&self.prospective, key);
+			}
+		}
 
 		// Iterate over all prospective and mark all keys that share
 		// the given prefix as removed (None).
@@ -419,6 +547,21 @@ impl OverlayedChanges {
 	) {
 		let extrinsic_index = self.extrinsic_index();
 		let storage_key = child_info.storage_key();
+		if let Some(layer) = self.transactions.last_mut() {
+			let prospective_keys = self.prospective.children_default.get(storage_key)
+				.into_iter()
+				.flat_map(|(map, _)| map.keys());
+			let committed_keys = self.committed.children_default.get(storage_key)
+				.into_iter()
+				.flat_map(|(map, _)| map.keys());
+			layer.note_child(
+				&self.prospective,
+				storage_key,
+				prospective_keys.chain(committed_keys)
+					.filter(|key| key.starts_with(prefix))
+					.map(|k| &k[..]),
+			);
+		}
 		let map_entry = self.prospective.children_default.entry(storage_key.to_vec())
 			.or_insert_with(|| (Default::default(), child_info.to_owned()));
 		let updatable = map_entry.1.try_update(child_info);
@@ -453,12 +596,18 @@ impl OverlayedChanges {
 	}
 
 	/// Discard prospective changes to state.
+	///
+	/// This also drops all open storage transactions.
 	pub fn discard_prospective(&mut self) {
 		self.prospective.clear();
+		self.transactions.clear();
 	}
 
 	/// Commit prospective changes to state.
+	///
+	/// All open storage transactions are implicitly committed.
 	pub fn commit_prospective(&mut self) {
+		self.transactions.clear();
 		if self.committed.is_empty() {
 			mem::swap(&mut self.prospective, &mut self.committed);
 		} else {
@@ -489,6 +638,40 @@ impl OverlayedChanges {
 		}
 	}
 
+	/// Start a new nested storage transaction.
+	///
+	/// All changes to the prospective state made until the matching
+	/// [`commit_transaction`](OverlayedChanges::commit_transaction) or
+	/// [`rollback_transaction`](OverlayedChanges::rollback_transaction) belong to it.
+	pub fn start_transaction(&mut self) {
+		self.transactions.push(Default::default());
+	}
+
+	/// Rollback the innermost storage transaction.
+	///
+	/// All prospective changes made since the transaction was started are discarded.
+	pub fn rollback_transaction(&mut self) -> Result<(), NoOpenTransaction> {
+		let layer = self.transactions.pop().ok_or(NoOpenTransaction)?;
+		layer.revert(&mut self.prospective);
+		Ok(())
+	}
+
+	/// Commit the innermost storage transaction.
+	///
+	/// Its changes are kept and become part of the enclosing transaction, if any.
+	pub fn commit_transaction(&mut self) -> Result<(), NoOpenTransaction> {
+		let layer = self.transactions.pop().ok_or(NoOpenTransaction)?;
+		if let Some(parent) = self.transactions.last_mut() {
+			parent.absorb(layer);
+		}
+		Ok(())
+	}
+
+	/// Returns the number of currently open storage transactions.
+	pub fn transaction_depth(&self) -> usize {
+		self.transactions.len()
+	}
+
 	/// Consume `OverlayedChanges` and take committed set.
 	///
 	/// Panics:
@@ -542,8 +725,11 @@ impl OverlayedChanges {
 	}
 
 	/// Replace the currently pending changes.
+	///
+	/// Any open storage transaction is dropped.
 	pub fn replace_pending(&mut self, pending: OverlayedChangeSet) {
 		self.prospective = pending;
+		self.transactions.clear();
 	}
 
 	/// Convert this instance with all changes into a [`StorageChanges`] instance.
@@ -925,6 +1111,63 @@ mod tests {
 			Default::default());
 	}
 
+	#[test]
+	fn storage_transactions_are_rolled_back() {
+		let mut overlay = OverlayedChanges::default();
+		overlay.set_storage(vec![1], Some(vec![1]));
+		overlay.set_storage(vec![2], Some(vec![2]));
+
+		overlay.start_transaction();
+		overlay.set_storage(vec![1], Some(vec![11]));
+		overlay.set_storage(vec![3], Some(vec![3]));
+		overlay.clear_prefix(&[2]);
+
+		overlay.start_transaction();
+		overlay.set_storage(vec![1], Some(vec![111]));
+		overlay.set_storage(vec![4], Some(vec![4]));
+		assert_eq!(overlay.transaction_depth(), 2);
+
+		overlay.rollback_transaction().unwrap();
+		assert_eq!(overlay.storage(&[1]).unwrap(), Some(&[11][..]));
+		assert!(overlay.storage(&[4]).is_none());
+
+		overlay.rollback_transaction().unwrap();
+		assert_eq!(overlay.storage(&[1]).unwrap(), Some(&[1][..]));
+		assert_eq!(overlay.storage(&[2]).unwrap(), Some(&[2][..]));
+		assert!(overlay.storage(&[3]).is_none());
+		assert_eq!(overlay.rollback_transaction(), Err(NoOpenTransaction));
+	}
+
+	#[test]
+	fn committed_storage_transactions_can_be_rolled_back_by_parent() {
+		let child_info = ChildInfo::new_default(b"Child1");
+		let child_info = &child_info;
+		let mut overlay = OverlayedChanges::default();
+		overlay.set_storage(vec![1], Some(vec![1]));
+
+		overlay.start_transaction();
+		overlay.set_storage(vec![1], Some(vec![11]));
+
+		overlay.start_transaction();
+		overlay.set_storage(vec![1], Some(vec![111]));
+		overlay.set_child_storage(child_info, vec![5], Some(vec![5]));
+		overlay.commit_transaction().unwrap();
+
+		assert_eq!(overlay.storage(&[1]).unwrap(), Some(&[111][..]));
+		assert_eq!(overlay.child_storage(child_info, &[5]).unwrap(), Some(&[5][..]));
+
+		overlay.rollback_transaction().unwrap();
+		assert_eq!(overlay.storage(&[1]).unwrap(), Some(&[1][..]));
+		assert!(overlay.child_storage(child_info, &[5]).is_none());
+		assert!(overlay.default_child_info(child_info.storage_key()).is_none());
+
+		overlay.start_transaction();
+		overlay.set_storage(vec![1], Some(vec![11]));
+		overlay.commit_transaction().unwrap();
+		assert_eq!(overlay.storage(&[1]).unwrap(), Some(&[11][..]));
+		assert_eq!(overlay.commit_transaction(), Err(NoOpenTransaction));
+	}
+
 	#[test]
 	fn next_storage_key_change_works() {
 		let mut overlay = OverlayedChanges::default();
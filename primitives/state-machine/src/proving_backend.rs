@@ -24,7 +24,7 @@ use log::debug;
 use hash_db::{Hasher, HashDB, EMPTY_PREFIX, Prefix};
 use sp_trie::{
 	MemoryDB, empty_child_trie_root, read_trie_value_with, read_child_trie_value_with,
-	record_all_keys, StorageProof,
+	record_all_keys, StorageProof, prefixed_key,
 };
 pub use sp_trie::{Recorder, trie_types::{Layout, TrieError}};
 use crate::trie_backend::TrieBackend;
@@ -157,6 +157,17 @@ impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> ProvingBackend<'a, S, H>
 			.collect();
 		StorageProof::new(trie_nodes)
 	}
+
+	/// Returns the estimated encoded size of the proof gathered so far.
+	///
+	/// This goes over all the recorded trie nodes, so it is linear in the size of the proof.
+	pub fn estimate_encoded_size(&self) -> usize {
+		self.0.essence().backend_storage().proof_recorder
+			.read()
+			.values()
+			.filter_map(|v| v.as_ref().map(|v| v.len()))
+			.sum()
+	}
 }
 
 impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> TrieBackendStorage<H>
@@ -174,6 +185,37 @@ impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> TrieBackendStorage<H>
 	}
 }
 
+/// Proof check storage keeping track of the trie nodes read from it, keyed by their prefixed
+/// database key.
+pub(crate) struct ReadRecorder<H: Hasher> {
+	db: MemoryDB<H>,
+	read: RwLock<HashMap<Vec<u8>, DBValue>>,
+}
+
+impl<H: Hasher> ReadRecorder<H> {
+	/// Create a new recorder over the nodes of a proof.
+	pub(crate) fn new(db: MemoryDB<H>) -> Self {
+		ReadRecorder { db, read: Default::default() }
+	}
+
+	/// Consume the recorder, returning the nodes read so far.
+	pub(crate) fn into_read(self) -> Vec<(Vec<u8>, DBValue)> {
+		self.read.into_inner().into_iter().collect()
+	}
+}
+
+impl<H: Hasher> TrieBackendStorage<H> for ReadRecorder<H> {
+	type Overlay = MemoryDB<H>;
+
+	fn get(&self, key: &H::Out, prefix: Prefix) -> Result<Option<DBValue>, String> {
+		let value = HashDB::get(&self.db, key, prefix);
+		if let Some(value) = &value {
+			self.read.write().insert(prefixed_key::<H>(key, prefix), value.clone());
+		}
+		Ok(value)
+	}
+}
+
 impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> std::fmt::Debug
 	for ProvingBackend<'a, S, H>
 {
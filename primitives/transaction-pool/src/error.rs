@@ -45,10 +45,11 @@ pub enum Error {
 	/// The transaction is already in the pool.
 	#[display(fmt="[{:?}] Already imported", _0)]
 	AlreadyImported(Box<dyn std::any::Any + Send>),
-	/// The transaction cannot be imported cause it's a replacement and has too low priority.
-	#[display(fmt="Too low priority ({} > {})", old, new)]
+	/// The transaction cannot be imported cause it's a replacement and has too low priority
+	/// per byte.
+	#[display(fmt="Too low priority per byte (total {} vs {})", old, new)]
 	TooLowPriority {
-		/// Transaction already in the pool.
+		/// Transactions already in the pool, in total.
 		old: Priority,
 		/// Transaction entering the pool.
 		new: Priority
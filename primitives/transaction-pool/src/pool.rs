@@ -127,12 +127,39 @@ pub enum TransactionStatus<Hash, BlockHash> {
 	Invalid,
 }
 
+/// Pool-wide transaction event.
+///
+/// Unlike the `TransactionStatus`, which is reported for a single watched transaction,
+/// these events are reported for every transaction entering or leaving the pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PoolEvent<Hash> {
+	/// Transaction was imported to the future queue.
+	Future(Hash),
+	/// Transaction was imported to the ready queue or promoted from the future queue.
+	Ready(Hash),
+	/// Transaction (first) has been replaced in the pool by another transaction (second)
+	/// that provides the same tags.
+	Usurped(Hash, Hash),
+	/// Transaction has been dropped from the pool because of the limit.
+	Dropped(Hash),
+	/// Transaction has been removed from the pool as invalid.
+	Invalid(Hash),
+	/// Transaction has been temporarily banned from the pool.
+	Banned(Hash),
+}
+
 /// The stream of transaction events.
 pub type TransactionStatusStream<Hash, BlockHash> = dyn Stream<Item=TransactionStatus<Hash, BlockHash>> + Send + Unpin;
 
 /// The import notification event stream.
 pub type ImportNotificationStream<H> = mpsc::TracingUnboundedReceiver<H>;
 
+/// The pool-wide event stream.
+///
+/// The stream is bounded: it is closed by the pool if its consumer falls too far behind.
+pub type PoolEventStream<H> = futures::channel::mpsc::Receiver<PoolEvent<H>>;
+
 /// Transaction hash type for a pool.
 pub type TxHash<P> = <P as TransactionPool>::Hash;
 /// Block hash type for a pool.
@@ -169,6 +196,8 @@ pub trait InPoolTransaction {
 	fn provides(&self) -> &[TransactionTag];
 	/// Return a flag indicating if the transaction should be propagated to other peers.
 	fn is_propagable(&self) -> bool;
+	/// Get the number of the block the transaction was validated at.
+	fn validated_at(&self) -> &u64;
 }
 
 /// Transaction pool interface.
@@ -222,6 +251,10 @@ pub trait TransactionPool: Send + Sync {
 	/// Get an iterator for ready transactions ordered by priority.
 	fn ready(&self) -> Box<dyn Iterator<Item=Arc<Self::InPoolTransaction>> + Send>;
 
+	// *** RPC
+	/// Get all transactions in the future queue.
+	fn futures(&self) -> Vec<Arc<Self::InPoolTransaction>>;
+
 	// *** Block production
 	/// Remove transactions identified by given hashes (and dependent transactions) from the pool.
 	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>>;
@@ -234,6 +267,13 @@ pub trait TransactionPool: Send + Sync {
 	/// Return an event stream of transactions imported to the pool.
 	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>>;
 
+	// *** logging / RPC
+	/// Return an event stream of all transactions entering and leaving the pool.
+	fn pool_event_stream(&self) -> PoolEventStream<TxHash<Self>>;
+
+	/// Returns the sender of the transaction, if the pool is able to determine it.
+	fn transaction_sender(&self, tx: &Self::InPoolTransaction) -> Option<Vec<u8>>;
+
 	// *** networking
 	/// Notify the pool about transactions broadcast.
 	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>);
@@ -76,6 +76,9 @@ fn version() -> RuntimeVersion {
 	VERSION
 }
 
+/// The storage key of the GRANDPA authority set change reported as pending by the runtime.
+pub const GRANDPA_PENDING_CHANGE_KEY: &[u8] = b":grandpa_pending_change";
+
 /// Native version.
 #[cfg(any(feature = "std", test))]
 pub fn native_version() -> NativeVersion {
@@ -445,6 +448,22 @@ impl pallet_babe::Trait for Runtime {
 	type EpochChangeTrigger = pallet_babe::ExternalTrigger;
 }
 
+/// The BABE epoch with the given index. The test runtime never changes the authorities nor the
+/// configuration of epochs.
+fn babe_epoch(epoch_index: u64) -> sp_consensus_babe::Epoch {
+	sp_consensus_babe::Epoch {
+		epoch_index,
+		start_slot: <pallet_babe::Module<Runtime>>::genesis_slot() + epoch_index * EpochDuration::get(),
+		duration: EpochDuration::get(),
+		authorities: system::authorities().into_iter().map(|x| (x, 1)).collect(),
+		randomness: <pallet_babe::Module<Runtime>>::randomness(),
+		config: sp_consensus_babe::BabeEpochConfiguration {
+			c: (3, 10),
+			allowed_slots: AllowedSlots::PrimaryAndSecondaryPlainSlots,
+		},
+	}
+}
+
 /// Adds one to the given input and returns the final result.
 #[inline(never)]
 fn benchmark_add_one(i: u64) -> u64 {
@@ -666,6 +685,14 @@ cfg_if! {
 				fn current_epoch_start() -> SlotNumber {
 					<pallet_babe::Module<Runtime>>::current_epoch_start()
 				}
+
+				fn current_epoch() -> sp_consensus_babe::Epoch {
+					babe_epoch(<pallet_babe::Module<Runtime>>::epoch_index())
+				}
+
+				fn next_epoch() -> sp_consensus_babe::Epoch {
+					babe_epoch(<pallet_babe::Module<Runtime>>::epoch_index() + 1)
+				}
 			}
 
 			impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
@@ -692,6 +719,12 @@ cfg_if! {
 					Vec::new()
 				}
 
+				fn grandpa_pending_change(
+				) -> Option<sp_finality_grandpa::ScheduledChange<NumberFor<Block>>> {
+					sp_io::storage::get(GRANDPA_PENDING_CHANGE_KEY)
+						.and_then(|change| Decode::decode(&mut &change[..]).ok())
+				}
+
 				fn submit_report_equivocation_extrinsic(
 					_equivocation_proof: sp_finality_grandpa::EquivocationProof<
 						<Block as BlockT>::Hash,
@@ -887,6 +920,14 @@ cfg_if! {
 				fn current_epoch_start() -> SlotNumber {
 					<pallet_babe::Module<Runtime>>::current_epoch_start()
 				}
+
+				fn current_epoch() -> sp_consensus_babe::Epoch {
+					babe_epoch(<pallet_babe::Module<Runtime>>::epoch_index())
+				}
+
+				fn next_epoch() -> sp_consensus_babe::Epoch {
+					babe_epoch(<pallet_babe::Module<Runtime>>::epoch_index() + 1)
+				}
 			}
 
 			impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
@@ -908,6 +949,35 @@ cfg_if! {
 				}
 			}
 
+			impl sp_finality_grandpa::GrandpaApi<Block> for Runtime {
+				fn grandpa_authorities() -> sp_finality_grandpa::AuthorityList {
+					Vec::new()
+				}
+
+				fn grandpa_pending_change(
+				) -> Option<sp_finality_grandpa::ScheduledChange<NumberFor<Block>>> {
+					sp_io::storage::get(GRANDPA_PENDING_CHANGE_KEY)
+						.and_then(|change| Decode::decode(&mut &change[..]).ok())
+				}
+
+				fn submit_report_equivocation_extrinsic(
+					_equivocation_proof: sp_finality_grandpa::EquivocationProof<
+						<Block as BlockT>::Hash,
+						NumberFor<Block>,
+					>,
+					_key_owner_proof: sp_finality_grandpa::OpaqueKeyOwnershipProof,
+				) -> Option<()> {
+					None
+				}
+
+				fn generate_key_ownership_proof(
+					_set_id: sp_finality_grandpa::SetId,
+					_authority_id: sp_finality_grandpa::AuthorityId,
+				) -> Option<sp_finality_grandpa::OpaqueKeyOwnershipProof> {
+					None
+				}
+			}
+
 			impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Index> for Runtime {
 				fn account_nonce(_account: AccountId) -> Index {
 					0
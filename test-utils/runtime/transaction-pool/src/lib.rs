@@ -17,7 +17,9 @@
 
 //! Test utils for the transaction pool together with the test runtime.
 //!
-//! See [`TestApi`] for more information.
+//! See [`TestApi`] for more information, and [`simulation`] for randomized scenarios.
+
+pub mod simulation;
 
 use codec::Encode;
 use parking_lot::RwLock;
@@ -0,0 +1,778 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic simulation of the transaction pool.
+//!
+//! A [`Scenario`] is a list of [`Action`]s generated from a seed: transfers submitted by a few
+//! accounts, and blocks imported either on top of the best block or on top of an older block,
+//! which builds a fork tree and leads to reorganisations. Blocks on top of the best block are
+//! authored from the ready transactions of the pool, like block production does. [`run`] plays a
+//! scenario against a pool backed by a [`SimulatedChain`] and checks after every action that:
+//!
+//! - no transaction is offered twice for inclusion: the ready queue yields every transaction at
+//!   most once, never one which is also in the future queue and never one which is already
+//!   included in the best chain,
+//! - no valid transaction known to the pool is lost, i.e. every transaction which was accepted by
+//!   the pool or retracted with a block, and which is not stale at the best block, is either ready
+//!   or in the future queue, unless the pool rotator banned it once its longevity ran out or it
+//!   depended on such a transaction,
+//! - banned transactions are rejected when submitted again,
+//! - the ready transactions of every account follow the account nonce at the best block, without
+//!   gaps.
+//!
+//! The same seed always produces the same scenario, so a failure can be replayed from the seed it
+//! reports.
+
+use codec::Encode;
+use futures::executor::block_on;
+use parking_lot::RwLock;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{BlakeTwo256, Hash as HashT},
+	transaction_validity::{
+		TransactionValidity, ValidTransaction, InvalidTransaction, TransactionSource,
+		TransactionLongevity,
+	},
+};
+use sp_transaction_pool::{
+	ChainEvent, InPoolTransaction, MaintainedTransactionPool, PoolEvent,
+	error::{Error as PoolError, IntoPoolError},
+};
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, fmt};
+use substrate_test_runtime_client::{
+	runtime::{Index, AccountId, Block, BlockNumber, Extrinsic, Hash, Header, Transfer},
+	AccountKeyring::{self, *},
+};
+
+use crate::Error;
+
+/// Accounts which submit transactions in the simulation.
+const ACCOUNTS: [AccountKeyring; 6] = [Alice, Bob, Charlie, Dave, Eve, Ferdie];
+
+/// Small deterministic random number generator (SplitMix64).
+///
+/// It is implemented here rather than taken from a crate, so that a seed keeps producing the
+/// same scenario regardless of dependency upgrades.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+
+	/// Random number in `0..n`.
+	fn below(&mut self, n: u64) -> u64 {
+		self.next() % n
+	}
+
+	/// Returns true with a probability of `percent` / 100.
+	fn chance(&mut self, percent: u64) -> bool {
+		self.below(100) < percent
+	}
+}
+
+/// Parameters of the generated scenarios.
+#[derive(Debug, Clone)]
+pub struct Config {
+	/// Number of actions in a scenario.
+	pub actions: usize,
+	/// Number of accounts submitting transactions, at most 6.
+	pub accounts: usize,
+	/// Percentage of actions which import a block, the others submit a transaction.
+	pub import_percent: u64,
+	/// Percentage of imported blocks which are not built on top of the best block.
+	pub fork_percent: u64,
+	/// How many blocks below the best block a fork may start.
+	pub max_fork_depth: BlockNumber,
+	/// Maximal number of transactions in an imported block.
+	pub max_block_transactions: usize,
+	/// Percentage of submissions which skip some nonces and end up in the future queue.
+	pub gap_percent: u64,
+	/// Percentage of submissions which repeat an already submitted nonce.
+	pub resubmit_percent: u64,
+	/// Longevity of the transfers, in blocks.
+	///
+	/// Transfers which stay in the pool for longer without being revalidated are culled and
+	/// banned by the pool rotator.
+	pub longevity: TransactionLongevity,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Config {
+			actions: 200,
+			accounts: 3,
+			import_percent: 30,
+			fork_percent: 30,
+			max_fork_depth: 3,
+			max_block_transactions: 4,
+			gap_percent: 10,
+			resubmit_percent: 10,
+			longevity: TransactionLongevity::max_value(),
+		}
+	}
+}
+
+/// Single step of a scenario.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+	/// Submit the transfer of the given account with the given nonce at the best block.
+	///
+	/// The account is an index into the simulated accounts.
+	Submit { account: usize, nonce: Index },
+	/// Import a block on top of the `parent`-th imported block, `0` being the genesis block.
+	///
+	/// The block includes up to `transactions` transfers which are valid on top of the parent:
+	/// ready transactions of the pool if the parent is the best block, submitted transfers
+	/// otherwise. It becomes the new best block if it is higher than the current best block.
+	Import { parent: usize, transactions: usize },
+}
+
+/// Actions generated from a seed.
+#[derive(Debug, Clone)]
+pub struct Scenario {
+	/// Seed the scenario was generated from.
+	pub seed: u64,
+	/// Number of accounts submitting transactions.
+	pub accounts: usize,
+	/// Actions to perform in order.
+	pub actions: Vec<Action>,
+}
+
+impl Scenario {
+	/// Generate a scenario from the given seed.
+	pub fn generate(seed: u64, config: &Config) -> Self {
+		assert!(
+			config.accounts > 0 && config.accounts <= ACCOUNTS.len(),
+			"The simulation supports between 1 and {} accounts", ACCOUNTS.len(),
+		);
+
+		let mut rng = Rng(seed);
+		let mut next_nonces = vec![0; config.accounts];
+		// Numbers of the imported blocks, by import index.
+		let mut numbers: Vec<BlockNumber> = vec![0];
+		let mut best = 0;
+		let mut actions = Vec::with_capacity(config.actions);
+
+		for _ in 0..config.actions {
+			if rng.chance(config.import_percent) {
+				let parent = if rng.chance(config.fork_percent) {
+					let lowest = numbers[best].saturating_sub(config.max_fork_depth);
+					let candidates = (0..numbers.len())
+						.filter(|index| numbers[*index] >= lowest)
+						.collect::<Vec<_>>();
+					candidates[rng.below(candidates.len() as u64) as usize]
+				} else {
+					best
+				};
+				let transactions = rng.below(config.max_block_transactions as u64 + 1) as usize;
+
+				numbers.push(numbers[parent] + 1);
+				if numbers[parent] + 1 > numbers[best] {
+					best = numbers.len() - 1;
+				}
+				actions.push(Action::Import { parent, transactions });
+			} else {
+				let account = rng.below(config.accounts as u64) as usize;
+				let next_nonce = next_nonces[account];
+				let nonce = if next_nonce > 0 && rng.chance(config.resubmit_percent) {
+					rng.below(next_nonce)
+				} else if rng.chance(config.gap_percent) {
+					next_nonce + 1 + rng.below(2)
+				} else {
+					next_nonce
+				};
+
+				next_nonces[account] = next_nonces[account].max(nonce + 1);
+				actions.push(Action::Submit { account, nonce });
+			}
+		}
+
+		Scenario { seed, accounts: config.accounts, actions }
+	}
+}
+
+/// Invariant violation detected while running a scenario.
+#[derive(Debug)]
+pub struct Failure {
+	/// Seed of the scenario.
+	pub seed: u64,
+	/// Index of the action after which the violation was detected.
+	pub step: usize,
+	/// The action itself.
+	pub action: Action,
+	/// Description of the violation.
+	pub reason: String,
+}
+
+/// Summary of a successful run, to check what a scenario exercised.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+	/// Number of new best blocks which retracted blocks of the previous best chain.
+	pub reorgs: usize,
+	/// Number of submissions rejected because the transfer was temporarily banned.
+	pub banned_submissions: usize,
+	/// Number of transfers culled by the pool rotator, along with the transfers depending on them.
+	pub culled: usize,
+}
+
+impl fmt::Display for Failure {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"scenario with seed {} failed after step {} ({:?}): {}",
+			self.seed, self.step, self.action, self.reason,
+		)
+	}
+}
+
+/// Transfer of the `account`-th simulated account with the given nonce.
+///
+/// Unlike [`crate::uxt`], the transfer isn't signed, so the same transaction always has the same
+/// hash. [`SimulatedChain`] doesn't check signatures.
+pub fn transfer(account: usize, nonce: Index) -> Extrinsic {
+	let transfer = Transfer {
+		from: ACCOUNTS[account].into(),
+		to: AccountId::default(),
+		nonce,
+		amount: 1,
+	};
+	Extrinsic::Transfer {
+		transfer,
+		signature: Default::default(),
+		exhaust_resources_when_not_first: false,
+	}
+}
+
+struct SimulatedBlock {
+	header: Header,
+	extrinsics: Vec<Extrinsic>,
+	/// Account nonces after the block.
+	nonces: HashMap<AccountId, Index>,
+}
+
+struct SimulatedChainState {
+	blocks: HashMap<Hash, SimulatedBlock>,
+	best: Hash,
+	imported: u64,
+}
+
+impl SimulatedChainState {
+	fn block(&self, hash: &Hash) -> &SimulatedBlock {
+		self.blocks.get(hash).expect("Only imported blocks are queried; qed")
+	}
+
+	fn canonical_hash(&self, number: BlockNumber) -> Option<Hash> {
+		let mut hash = self.best;
+		loop {
+			let header = &self.block(&hash).header;
+			if header.number == number {
+				return Some(hash);
+			}
+			if header.number < number {
+				return None;
+			}
+			hash = header.parent_hash;
+		}
+	}
+
+	fn resolve(&self, at: &BlockId<Block>) -> Option<&SimulatedBlock> {
+		match at {
+			BlockId::Hash(hash) => self.blocks.get(hash),
+			BlockId::Number(number) => self.canonical_hash(*number).map(|hash| self.block(&hash)),
+		}
+	}
+}
+
+/// Chain api for the simulation, which keeps the state of every block of a fork tree.
+///
+/// Transfers are validated like the runtime does: a transfer with a nonce below the account
+/// nonce is stale, a transfer with a higher nonce requires the transfer with the previous nonce.
+pub struct SimulatedChain {
+	state: RwLock<SimulatedChainState>,
+	longevity: TransactionLongevity,
+}
+
+impl SimulatedChain {
+	/// New chain which only contains the genesis block and validates transfers with the given
+	/// longevity.
+	pub fn new(longevity: TransactionLongevity) -> Self {
+		let genesis = Header {
+			number: 0,
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+			parent_hash: Default::default(),
+			state_root: Default::default(),
+		};
+		let hash = genesis.hash();
+		let mut blocks = HashMap::new();
+		blocks.insert(hash, SimulatedBlock {
+			header: genesis,
+			extrinsics: Vec::new(),
+			nonces: HashMap::new(),
+		});
+
+		SimulatedChain {
+			state: RwLock::new(SimulatedChainState { blocks, best: hash, imported: 0 }),
+			longevity,
+		}
+	}
+
+	/// Longevity of the transfers.
+	pub fn longevity(&self) -> TransactionLongevity {
+		self.longevity
+	}
+
+	/// Hash of the best block.
+	pub fn best(&self) -> Hash {
+		self.state.read().best
+	}
+
+	/// Header of an imported block.
+	pub fn header(&self, hash: &Hash) -> Header {
+		self.state.read().block(hash).header.clone()
+	}
+
+	/// Extrinsics of an imported block.
+	pub fn extrinsics(&self, hash: &Hash) -> Vec<Extrinsic> {
+		self.state.read().block(hash).extrinsics.clone()
+	}
+
+	/// Nonce of the account after the given block.
+	pub fn nonce(&self, at: &Hash, who: &AccountId) -> Index {
+		self.state.read().block(at).nonces.get(who).cloned().unwrap_or(0)
+	}
+
+	/// Import a block with the given transfers on top of `parent`.
+	///
+	/// The transfers have to be valid on top of the parent. The best block is not changed.
+	pub fn import(&self, parent: Hash, extrinsics: Vec<Extrinsic>) -> Header {
+		let mut state = self.state.write();
+		state.imported += 1;
+
+		let parent_block = state.block(&parent);
+		let mut nonces = parent_block.nonces.clone();
+		for xt in &extrinsics {
+			let transfer = xt.transfer();
+			let nonce = nonces.entry(transfer.from.clone()).or_insert(0);
+			assert_eq!(*nonce, transfer.nonce, "Imported transfers have to be valid");
+			*nonce += 1;
+		}
+
+		// The import counter makes blocks with the same contents on different forks distinct.
+		let header = Header {
+			number: parent_block.header.number + 1,
+			digest: Default::default(),
+			extrinsics_root: BlakeTwo256::hash_of(&extrinsics),
+			parent_hash: parent,
+			state_root: BlakeTwo256::hash_of(&state.imported),
+		};
+		state.blocks.insert(header.hash(), SimulatedBlock {
+			header: header.clone(),
+			extrinsics,
+			nonces,
+		});
+		header
+	}
+
+	/// Make the given block the best block.
+	///
+	/// Returns the blocks of the previous best chain which are not part of the new one and the
+	/// blocks of the new best chain which were not part of the previous one, both ordered by
+	/// block number.
+	pub fn set_best(&self, hash: Hash) -> (Vec<Hash>, Vec<Hash>) {
+		let mut state = self.state.write();
+		let (mut retracted, mut enacted) = (Vec::new(), Vec::new());
+		let (mut from, mut to) = (state.best, hash);
+		while from != to {
+			let from_header = &state.block(&from).header;
+			let to_header = &state.block(&to).header;
+			let (from_number, to_number) = (from_header.number, to_header.number);
+			let (from_parent, to_parent) = (from_header.parent_hash, to_header.parent_hash);
+			if from_number >= to_number {
+				retracted.push(from);
+				from = from_parent;
+			}
+			if to_number >= from_number {
+				enacted.push(to);
+				to = to_parent;
+			}
+		}
+		retracted.reverse();
+		enacted.reverse();
+		state.best = hash;
+		(retracted, enacted)
+	}
+}
+
+impl sc_transaction_graph::ChainApi for SimulatedChain {
+	type Block = Block;
+	type Hash = Hash;
+	type Error = Error;
+	type ValidationFuture = futures::future::Ready<Result<TransactionValidity, Error>>;
+	type BodyFuture = futures::future::Ready<Result<Option<Vec<Extrinsic>>, Error>>;
+
+	fn validate_transaction(
+		&self,
+		at: &BlockId<Self::Block>,
+		_source: TransactionSource,
+		uxt: sc_transaction_graph::ExtrinsicFor<Self>,
+	) -> Self::ValidationFuture {
+		let state = self.state.read();
+		let block = match state.resolve(at) {
+			Some(block) => block,
+			None => return futures::future::ready(Err(
+				sp_transaction_pool::error::Error::InvalidBlockId(format!("{:?}", at)).into()
+			)),
+		};
+
+		let transfer = uxt.transfer();
+		let nonce = block.nonces.get(&transfer.from).cloned().unwrap_or(0);
+		if transfer.nonce < nonce {
+			return futures::future::ready(Ok(Err(InvalidTransaction::Stale.into())));
+		}
+
+		let requires = if transfer.nonce > nonce {
+			vec![(&transfer.from, transfer.nonce - 1).encode()]
+		} else {
+			vec![]
+		};
+
+		futures::future::ready(Ok(Ok(ValidTransaction {
+			priority: 1,
+			requires,
+			provides: vec![(&transfer.from, transfer.nonce).encode()],
+			longevity: self.longevity,
+			propagate: true,
+		})))
+	}
+
+	fn block_id_to_number(
+		&self,
+		at: &BlockId<Self::Block>,
+	) -> Result<Option<sc_transaction_graph::NumberFor<Self>>, Error> {
+		Ok(self.state.read().resolve(at).map(|block| block.header.number))
+	}
+
+	fn block_id_to_hash(
+		&self,
+		at: &BlockId<Self::Block>,
+	) -> Result<Option<sc_transaction_graph::BlockHash<Self>>, Error> {
+		Ok(self.state.read().resolve(at).map(|block| block.header.hash()))
+	}
+
+	fn hash_and_length(
+		&self,
+		ex: &sc_transaction_graph::ExtrinsicFor<Self>,
+	) -> (Self::Hash, usize) {
+		let encoded = ex.encode();
+		(BlakeTwo256::hash(&encoded), encoded.len())
+	}
+
+	fn block_body(&self, id: &BlockId<Self::Block>) -> Self::BodyFuture {
+		futures::future::ready(Ok(
+			self.state.read().resolve(id).map(|block| block.extrinsics.clone())
+		))
+	}
+}
+
+/// Play the scenario against the pool and check the invariants after every action.
+///
+/// The pool has to be backed by `chain`, freshly created, and must revalidate transactions
+/// during maintenance rather than in the background.
+///
+/// Every imported block is announced to the pool like a node does: a block which doesn't become
+/// the best block is announced with `is_new_best: false`, a new best block along with the blocks
+/// it retracts. The other blocks of a fork which becomes the best chain are not announced again.
+pub fn run<P>(pool: &P, chain: &SimulatedChain, scenario: &Scenario) -> Result<Outcome, Failure>
+where
+	P: MaintainedTransactionPool<Block = Block, Hash = Hash>,
+{
+	let accounts = ACCOUNTS[..scenario.accounts]
+		.iter()
+		.map(|keyring| AccountId::from(*keyring))
+		.collect::<Vec<_>>();
+	let mut events = pool.pool_event_stream();
+
+	// Hashes of the imported blocks, by import index.
+	let mut blocks = vec![chain.best()];
+	// Transfers submitted so far, available for inclusion in blocks.
+	let mut submitted = BTreeSet::new();
+	// Transfers the pool has been told about, either by submission or through retracted blocks,
+	// along with the number of the best block at that time.
+	let mut known = BTreeMap::new();
+	// Transfers by hash, to follow the events of the pool.
+	let mut transfers = HashMap::new();
+	// Transfers banned by the pool.
+	let mut banned = BTreeSet::new();
+	let mut outcome = Outcome::default();
+
+	for (step, action) in scenario.actions.iter().enumerate() {
+		let failure = |reason| Failure {
+			seed: scenario.seed,
+			step,
+			action: action.clone(),
+			reason,
+		};
+
+		match *action {
+			Action::Submit { account, nonce } => {
+				let xt = transfer(account, nonce);
+				transfers.insert(pool.hash_of(&xt), (account, nonce));
+				submitted.insert((account, nonce));
+
+				let best = chain.best();
+				let result = block_on(pool.submit_one(
+					&BlockId::Hash(best),
+					TransactionSource::External,
+					xt,
+				));
+				let rejected_as_banned = match result {
+					Ok(_) => {
+						known.insert((account, nonce), chain.header(&best).number);
+						false
+					},
+					Err(e) => matches!(e.into_pool_error(), Ok(PoolError::TemporarilyBanned)),
+				};
+
+				if rejected_as_banned {
+					outcome.banned_submissions += 1;
+				} else if banned.contains(&(account, nonce)) {
+					return Err(failure(format!(
+						"banned transfer {:?} was not rejected as banned",
+						(account, nonce),
+					)));
+				}
+			},
+			Action::Import { parent, transactions } => {
+				let parent = blocks[parent];
+				let best = chain.best();
+				let extrinsics = if parent == best {
+					author_block(pool, chain, &parent, &accounts, transactions).map_err(failure)?
+				} else {
+					build_block(chain, &parent, &accounts, &submitted, blocks.len(), transactions)
+				};
+				let header = chain.import(parent, extrinsics);
+				let hash = header.hash();
+				blocks.push(hash);
+
+				let is_new_best = header.number > chain.header(&best).number;
+				let retracted = if is_new_best {
+					chain.set_best(hash).0
+				} else {
+					Vec::new()
+				};
+				for retracted_hash in &retracted {
+					for xt in chain.extrinsics(retracted_hash) {
+						known.insert(describe(&accounts, &xt), header.number);
+					}
+				}
+				if !retracted.is_empty() {
+					outcome.reorgs += 1;
+				}
+
+				block_on(pool.maintain(ChainEvent::NewBlock {
+					is_new_best,
+					id: BlockId::Hash(hash),
+					header,
+					retracted,
+				}));
+			},
+		}
+
+		loop {
+			match events.try_next() {
+				Ok(Some(PoolEvent::Banned(hash))) => {
+					if let Some(transfer) = transfers.get(&hash) {
+						banned.insert(*transfer);
+					}
+				},
+				Ok(Some(_)) => {},
+				Ok(None) => return Err(failure("the pool closed its event stream".into())),
+				Err(_) => break,
+			}
+		}
+
+		check_invariants(pool, chain, &accounts, &mut known, &banned, &mut outcome)
+			.map_err(failure)?;
+	}
+
+	Ok(outcome)
+}
+
+/// Account index and nonce of a simulated transfer.
+fn describe(accounts: &[AccountId], xt: &Extrinsic) -> (usize, Index) {
+	let transfer = xt.transfer();
+	let account = accounts
+		.iter()
+		.position(|account| *account == transfer.from)
+		.expect("Only simulated accounts submit transfers; qed");
+	(account, transfer.nonce)
+}
+
+/// Author a block on top of the best block from the ready transactions of the pool.
+///
+/// Like block production, transactions are taken in the order of the ready queue and skipped if
+/// they are not valid on top of the transactions taken so far.
+fn author_block<P>(
+	pool: &P,
+	chain: &SimulatedChain,
+	parent: &Hash,
+	accounts: &[AccountId],
+	limit: usize,
+) -> Result<Vec<Extrinsic>, String>
+where
+	P: MaintainedTransactionPool<Block = Block, Hash = Hash>,
+{
+	let mut nonces = accounts.iter().map(|who| chain.nonce(parent, who)).collect::<Vec<_>>();
+	let mut offered = BTreeSet::new();
+	let mut extrinsics = Vec::new();
+	for tx in pool.ready() {
+		if extrinsics.len() == limit {
+			break;
+		}
+
+		let (account, nonce) = describe(accounts, tx.data());
+		if !offered.insert((account, nonce)) {
+			return Err(format!(
+				"transfer {:?} is offered twice for inclusion",
+				(account, nonce),
+			));
+		}
+		if nonce == nonces[account] {
+			extrinsics.push(tx.data().clone());
+			nonces[account] += 1;
+		}
+	}
+	Ok(extrinsics)
+}
+
+/// Pick the transfers of a block on top of `parent`.
+///
+/// Accounts take turns being considered first, and every account contributes the submitted
+/// transfers which directly follow its nonce at the parent.
+fn build_block(
+	chain: &SimulatedChain,
+	parent: &Hash,
+	accounts: &[AccountId],
+	submitted: &BTreeSet<(usize, Index)>,
+	index: usize,
+	limit: usize,
+) -> Vec<Extrinsic> {
+	let mut extrinsics = Vec::new();
+	for offset in 0..accounts.len() {
+		let account = (index + offset) % accounts.len();
+		let mut nonce = chain.nonce(parent, &accounts[account]);
+		while extrinsics.len() < limit && submitted.contains(&(account, nonce)) {
+			extrinsics.push(transfer(account, nonce));
+			nonce += 1;
+		}
+	}
+	extrinsics
+}
+
+/// Check the invariants after an action.
+///
+/// Known transfers which were rightfully culled by the pool rotator are forgotten.
+fn check_invariants<P>(
+	pool: &P,
+	chain: &SimulatedChain,
+	accounts: &[AccountId],
+	known: &mut BTreeMap<(usize, Index), BlockNumber>,
+	banned: &BTreeSet<(usize, Index)>,
+	outcome: &mut Outcome,
+) -> Result<(), String>
+where
+	P: MaintainedTransactionPool<Block = Block, Hash = Hash>,
+{
+	let best = chain.best();
+	let best_number = chain.header(&best).number;
+	let nonces = accounts.iter().map(|who| chain.nonce(&best, who)).collect::<Vec<_>>();
+
+	let ready = pool.ready().map(|tx| describe(accounts, tx.data())).collect::<Vec<_>>();
+	let future = pool.futures()
+		.into_iter()
+		.map(|tx| describe(accounts, tx.data()))
+		.collect::<BTreeSet<_>>();
+
+	let mut offered = BTreeSet::new();
+	let mut ready_by_account = vec![Vec::new(); accounts.len()];
+	for (account, nonce) in ready {
+		if !offered.insert((account, nonce)) {
+			return Err(format!("transfer {:?} is ready twice", (account, nonce)));
+		}
+		if future.contains(&(account, nonce)) {
+			return Err(format!(
+				"transfer {:?} is both ready and in the future queue",
+				(account, nonce),
+			));
+		}
+		if nonce < nonces[account] {
+			return Err(format!(
+				"transfer {:?} is ready, but already included in the best chain",
+				(account, nonce),
+			));
+		}
+		ready_by_account[account].push(nonce);
+	}
+
+	for (account, mut ready_nonces) in ready_by_account.into_iter().enumerate() {
+		ready_nonces.sort();
+		let expected = (nonces[account]..).take(ready_nonces.len()).collect::<Vec<_>>();
+		if ready_nonces != expected {
+			return Err(format!(
+				"ready nonces {:?} of account {} don't follow its nonce {} at the best block",
+				ready_nonces, account, nonces[account],
+			));
+		}
+	}
+
+	// The pool rotator culls and bans a transfer once it was validated `longevity` blocks ago,
+	// and the transfers depending on it are removed along with it. Known transfers are ordered
+	// by account and nonce, so the transfer a transfer depends on is checked first.
+	let mut culled = BTreeSet::new();
+	for (&(account, nonce), &known_at) in known.iter() {
+		if nonce < nonces[account]
+			|| offered.contains(&(account, nonce))
+			|| future.contains(&(account, nonce))
+		{
+			continue;
+		}
+
+		let expired = known_at.saturating_add(chain.longevity()) <= best_number
+			&& banned.contains(&(account, nonce));
+		let depends_on_culled = nonce > 0 && culled.contains(&(account, nonce - 1));
+		if !expired && !depends_on_culled {
+			return Err(format!(
+				"valid transfer {:?} is neither ready nor in the future queue",
+				(account, nonce),
+			));
+		}
+		culled.insert((account, nonce));
+	}
+
+	outcome.culled += culled.len();
+	for transfer in culled {
+		known.remove(&transfer);
+	}
+
+	Ok(())
+}